@@ -0,0 +1,7 @@
+-- ============================================================================
+-- Per-campaign sender identity
+-- Lets a campaign override the From display name and route replies elsewhere
+-- ============================================================================
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS from_name VARCHAR(255);
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS reply_to VARCHAR(255);
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- Global (cross-workspace) suppression
+-- Addresses listed here are never mailed by any workspace. A global entry wins
+-- over workspace-level state: removing an address from a workspace's
+-- suppression_list does not make it sendable while it is listed here.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS global_suppression (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) NOT NULL UNIQUE,      -- stored lowercased
+    reason VARCHAR(50) NOT NULL,             -- role_account, complained, bounced, manual
+    note TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_global_suppression_email ON global_suppression(email);
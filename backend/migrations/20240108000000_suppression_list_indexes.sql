@@ -0,0 +1,9 @@
+-- ============================================================================
+-- Suppression list paging support
+-- ============================================================================
+
+-- Lookups and search by address (already present on most installs)
+CREATE INDEX IF NOT EXISTS idx_suppression_email ON suppression_list(workspace_id, email);
+
+-- Newest-first paging within a workspace
+CREATE INDEX IF NOT EXISTS idx_suppression_workspace_created ON suppression_list(workspace_id, created_at DESC);
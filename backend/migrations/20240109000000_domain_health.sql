@@ -0,0 +1,36 @@
+-- ============================================================================
+-- DOMAIN-LEVEL HEALTH
+-- Inboxes on the same sending domain share reputation, so roll their metrics
+-- up per (workspace, domain).
+-- ============================================================================
+
+-- Domain part of an address, lowercased ('' if there is no '@')
+CREATE OR REPLACE FUNCTION email_domain(p_email TEXT)
+RETURNS TEXT AS $$
+    SELECT LOWER(SPLIT_PART(COALESCE(p_email, ''), '@', 2));
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE INDEX IF NOT EXISTS idx_email_accounts_domain ON email_accounts(workspace_id, email_domain(email));
+
+-- Same thresholds as the per-inbox status, applied to the domain averages.
+-- A domain is also in danger when at least half of its inboxes are.
+CREATE OR REPLACE VIEW email_domain_health AS
+SELECT
+    ea.workspace_id,
+    email_domain(ea.email) as domain,
+    COUNT(*) as inbox_count,
+    COALESCE(AVG(ea.spam_rate), 0)::FLOAT as spam_rate,
+    COALESCE(AVG(ea.bounce_rate), 0)::FLOAT as bounce_rate,
+    COALESCE(AVG(ea.reply_rate), 0)::FLOAT as reply_rate,
+    COALESCE(SUM(ea.sent_today), 0) as sent_today,
+    COUNT(*) FILTER (WHERE ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08) as danger_inboxes,
+    CASE
+        WHEN AVG(ea.spam_rate) > 0.03 OR AVG(ea.bounce_rate) > 0.08
+          OR COUNT(*) FILTER (WHERE ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08) * 2 >= COUNT(*)
+            THEN 'danger'
+        WHEN AVG(ea.spam_rate) > 0.02 OR AVG(ea.bounce_rate) > 0.05 OR AVG(ea.reply_rate) < 0.02
+            THEN 'warning'
+        ELSE 'healthy'
+    END as health_status
+FROM email_accounts ea
+GROUP BY ea.workspace_id, email_domain(ea.email);
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Scheduled campaign starts
+-- A campaign in status 'scheduled' is activated by the worker once
+-- scheduled_start_at has passed.
+-- ============================================================================
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS scheduled_start_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_campaigns_scheduled_start
+    ON campaigns(scheduled_start_at)
+    WHERE status = 'scheduled';
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- Per-lead send-time optimization
+-- preferred_send_hour is in the lead's local time; timezone_offset_minutes is
+-- the guessed offset from UTC. Both stay NULL when no guess could be made.
+-- ============================================================================
+
+ALTER TABLE leads ADD COLUMN IF NOT EXISTS timezone_offset_minutes INTEGER;
+ALTER TABLE leads ADD COLUMN IF NOT EXISTS preferred_send_hour SMALLINT
+    CHECK (preferred_send_hour BETWEEN 0 AND 23);
+
+-- The scheduler orders and bounds deferral by when a lead joined the campaign
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW();
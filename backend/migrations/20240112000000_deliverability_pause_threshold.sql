@@ -0,0 +1,7 @@
+-- ============================================================================
+-- Deliverability score auto-pause trigger
+-- Campaigns are paused when the composite 0-100 deliverability score computed
+-- from spam/bounce/reply rates falls below this threshold.
+-- ============================================================================
+
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS deliverability_score_threshold FLOAT DEFAULT 75.0;
@@ -0,0 +1,9 @@
+-- ============================================================================
+-- Per-inbox daily counter reset
+-- sent_today is reset once per local day of the sending account.
+-- timezone_offset_minutes is the account's offset from UTC (NULL = UTC);
+-- last_counter_reset_date is the local date of the most recent reset.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS timezone_offset_minutes INTEGER;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS last_counter_reset_date DATE;
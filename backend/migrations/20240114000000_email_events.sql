@@ -0,0 +1,36 @@
+-- ============================================================================
+-- Delivery events from transactional providers (SendGrid, Mailgun)
+-- Events are matched to the most recent send to the recipient. Lifetime
+-- per-inbox totals back the bounce/spam rates used by health checks.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS email_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    campaign_id UUID REFERENCES campaigns(id) ON DELETE SET NULL,
+    campaign_lead_id UUID REFERENCES campaign_leads(id) ON DELETE SET NULL,
+    email_account_id UUID REFERENCES email_accounts(id) ON DELETE SET NULL,
+    provider VARCHAR(50) NOT NULL,
+    event_type VARCHAR(50) NOT NULL,           -- delivered, opened, clicked, bounced, spam_report
+    recipient VARCHAR(255) NOT NULL,
+    provider_event_id VARCHAR(255),            -- dedupes webhook redeliveries
+    provider_message_id VARCHAR(255),
+    reason TEXT,
+    occurred_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_events_provider_event
+    ON email_events(provider, provider_event_id) WHERE provider_event_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_email_events_campaign ON email_events(campaign_id, event_type);
+CREATE INDEX IF NOT EXISTS idx_email_events_workspace ON email_events(workspace_id, occurred_at DESC);
+
+-- Which inbox sent each campaign email
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS email_account_id UUID REFERENCES email_accounts(id) ON DELETE SET NULL;
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS total_sent INTEGER DEFAULT 0;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS total_bounced INTEGER DEFAULT 0;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS total_spam_reports INTEGER DEFAULT 0;
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS bounced INTEGER DEFAULT 0;
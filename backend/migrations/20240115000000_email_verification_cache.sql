@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Email verification cache
+-- One row per lowercased address; results older than the verifier's TTL are
+-- re-checked on the next lookup.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS email_verifications (
+    email VARCHAR(255) PRIMARY KEY,
+    status VARCHAR(20) NOT NULL,
+    confidence REAL NOT NULL DEFAULT 0,
+    checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_verifications_checked_at ON email_verifications(checked_at);
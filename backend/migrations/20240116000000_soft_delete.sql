@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Soft delete for campaigns and leads
+-- Deleting from the API only stamps deleted_at so the row (and its sends,
+-- replies and events) can be restored. Hard deletes go through the admin API.
+-- ============================================================================
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE leads ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_campaigns_workspace_live ON campaigns(workspace_id, created_at DESC) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_leads_workspace_live ON leads(workspace_id, created_at DESC) WHERE deleted_at IS NULL;
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Configurable reply intent categories
+-- reply_categories holds the workspace's custom categories ([{label, description}])
+-- on top of the five built-in ones. reply_category_priority orders the dashboard
+-- reply queue; intents not listed sort last.
+-- ============================================================================
+
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS reply_categories JSONB NOT NULL DEFAULT '[]';
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS reply_category_priority TEXT[] NOT NULL
+    DEFAULT ARRAY['interested', 'objection', 'maybe_later'];
+
@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Outbound email archive
+-- One row per message actually handed to SMTP, with the exact rendered copy
+-- the recipient received. message_id is the RFC 5322 Message-ID we set on the
+-- email, used for threading replies; smtp_response is the server's reply
+-- (often carrying the provider's queue ID) for disputes.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS sent_emails (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    campaign_lead_id UUID NOT NULL REFERENCES campaign_leads(id) ON DELETE CASCADE,
+    lead_id UUID NOT NULL REFERENCES leads(id) ON DELETE CASCADE,
+    email_account_id UUID REFERENCES email_accounts(id) ON DELETE SET NULL,
+    from_email VARCHAR(255) NOT NULL,
+    to_email VARCHAR(255) NOT NULL,
+    subject TEXT NOT NULL,
+    body_html TEXT NOT NULL,
+    body_text TEXT NOT NULL,
+    message_id VARCHAR(255) NOT NULL,
+    smtp_response TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sent_emails_campaign_lead ON sent_emails(campaign_id, lead_id, sent_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sent_emails_message_id ON sent_emails(message_id);
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- One scraper_state row per (source, company)
+-- The worker's stale-signal refresh upserts into this table after every scrape.
+-- ============================================================================
+
+DELETE FROM scraper_state a
+USING scraper_state b
+WHERE a.source = b.source
+  AND a.company_id = b.company_id
+  AND a.ctid < b.ctid;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scraper_state_source_company ON scraper_state(source, company_id);
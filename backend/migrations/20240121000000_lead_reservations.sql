@@ -0,0 +1,17 @@
+-- ============================================================================
+-- Lead quota reservations
+-- A lead search reserves its requested count against the monthly lead limit
+-- before generating, under a lock on the workspace row, so concurrent searches
+-- can't both pass the check. Reservations are released once the leads are
+-- stored; expires_at covers requests that die mid-way.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS lead_reservations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    reserved INTEGER NOT NULL CHECK (reserved > 0),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_lead_reservations_workspace ON lead_reservations(workspace_id, expires_at);
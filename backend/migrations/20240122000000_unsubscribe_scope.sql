@@ -0,0 +1,13 @@
+-- ============================================================================
+-- Campaign-scoped unsubscribes
+-- Recipients can opt out of a single campaign instead of everything from the
+-- workspace. unsubscribe_scope records which one they chose: 'workspace' rows
+-- also have a suppression_list entry, 'campaign' rows only stop that campaign.
+-- ============================================================================
+
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS unsubscribe_scope VARCHAR(20)
+    CHECK (unsubscribe_scope IN ('workspace', 'campaign'));
+
+-- Every unsubscribe before this migration was workspace-wide
+UPDATE campaign_leads SET unsubscribe_scope = 'workspace'
+WHERE unsubscribed_at IS NOT NULL AND unsubscribe_scope IS NULL;
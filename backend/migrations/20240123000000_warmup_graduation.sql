@@ -0,0 +1,25 @@
+-- ============================================================================
+-- Warmup graduation
+-- healthy_since marks the start of the inbox's current run of healthy spam and
+-- bounce rates; a warming inbox graduates to active once that run is long
+-- enough and it has reached the full warmup volume. warmup_started_at restarts
+-- the ramp part-way through when an active inbox is demoted back to warming.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS healthy_since TIMESTAMP WITH TIME ZONE;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS warmup_started_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS inbox_warmup_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email_account_id UUID NOT NULL REFERENCES email_accounts(id) ON DELETE CASCADE,
+    workspace_id UUID,
+    event VARCHAR(20) NOT NULL CHECK (event IN ('graduated', 'demoted')),
+    detail TEXT,                               -- Human readable: "Bounce rate rose to 6.1%"
+    spam_rate FLOAT,
+    bounce_rate FLOAT,
+    daily_limit INTEGER,                       -- Limit after the transition
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_warmup_events_account ON inbox_warmup_events(email_account_id);
+CREATE INDEX IF NOT EXISTS idx_warmup_events_workspace ON inbox_warmup_events(workspace_id, created_at DESC);
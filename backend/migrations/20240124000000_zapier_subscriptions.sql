@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Zapier subscriptions
+-- Simple outbound hooks for no-code tools: each row posts a flat JSON payload
+-- to target_url when the event fires, optionally only for one campaign.
+-- Deliveries go through the jobs table so failures are retried with backoff.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS zapier_subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    event VARCHAR(50) NOT NULL CHECK (event IN ('reply.interested', 'meeting.created')),
+    target_url TEXT NOT NULL,
+    campaign_id UUID REFERENCES campaigns(id) ON DELETE CASCADE,  -- NULL = every campaign
+    created_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_zapier_subscriptions_workspace ON zapier_subscriptions(workspace_id, event);
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Inbox health score breakdown
+-- The periodic health job recomputes health_score from spam, bounce and reply
+-- rates, DNS authentication and recent volume; health_breakdown keeps each
+-- component's contribution so the UI can explain the number.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS health_breakdown JSONB;
+
+-- Recent volume per inbox is counted from the send archive
+CREATE INDEX IF NOT EXISTS idx_sent_emails_account_sent ON sent_emails(email_account_id, sent_at DESC);
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- Reply sentiment and urgency
+-- Set alongside intent by the classifier. sentiment runs from -1 (hostile) to
+-- 1 (enthusiastic); is_urgent flags replies asking to talk this week or citing
+-- a tight timeline, which the dashboard surfaces first.
+-- ============================================================================
+
+ALTER TABLE email_replies ADD COLUMN IF NOT EXISTS sentiment FLOAT
+    CHECK (sentiment BETWEEN -1 AND 1);
+ALTER TABLE email_replies ADD COLUMN IF NOT EXISTS is_urgent BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_replies_urgent ON email_replies(workspace_id, is_urgent) WHERE is_actioned = FALSE;
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Meeting reminders
+-- The worker emails the lead 24 hours and 1 hour before a scheduled meeting.
+-- meeting_reminders records each one once it's queued, so a reminder is never
+-- sent twice. timezone_offset_minutes is the offset the meeting was booked in
+-- (NULL = fall back to the lead's, then UTC) and is used to show the local time.
+-- ============================================================================
+
+ALTER TABLE meetings ADD COLUMN IF NOT EXISTS timezone_offset_minutes INTEGER;
+
+CREATE TABLE IF NOT EXISTS meeting_reminders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    meeting_id UUID NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+    kind VARCHAR(10) NOT NULL,                  -- 24h, 1h
+    queued_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    sent_at TIMESTAMP WITH TIME ZONE,
+    UNIQUE (meeting_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_meetings_upcoming ON meetings(scheduled_at) WHERE status = 'scheduled';
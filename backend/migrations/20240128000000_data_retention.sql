@@ -0,0 +1,29 @@
+-- ============================================================================
+-- Per-workspace data retention
+-- A nightly worker job deletes provider email events older than
+-- event_retention_months and strips sender details and content from replies
+-- older than reply_retention_months. NULL keeps data forever. Records tied to
+-- running campaigns or upcoming meetings are left alone. Each run is logged
+-- in retention_purge_runs.
+-- ============================================================================
+
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS event_retention_months INTEGER
+    CHECK (event_retention_months > 0);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS reply_retention_months INTEGER
+    CHECK (reply_retention_months > 0);
+
+ALTER TABLE email_replies ADD COLUMN IF NOT EXISTS anonymized_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS retention_purge_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    event_retention_months INTEGER,
+    reply_retention_months INTEGER,
+    events_deleted BIGINT NOT NULL DEFAULT 0,
+    replies_anonymized BIGINT NOT NULL DEFAULT 0,
+    ran_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_retention_runs_workspace ON retention_purge_runs(workspace_id, ran_at DESC);
+CREATE INDEX IF NOT EXISTS idx_email_events_occurred ON email_events(workspace_id, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_replies_received ON email_replies(workspace_id, received_at) WHERE anonymized_at IS NULL;
@@ -0,0 +1,16 @@
+-- ============================================================================
+-- Lead tags
+-- Free-form labels ("warm", "event-list") for grouping leads and adding a
+-- whole group to a campaign. Tags are stored lowercased; workspace_id is
+-- copied from the lead so tag lookups never cross workspaces.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS lead_tags (
+    lead_id UUID NOT NULL REFERENCES leads(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (lead_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lead_tags_workspace_tag ON lead_tags(workspace_id, tag);
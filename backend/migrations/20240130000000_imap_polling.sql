@@ -0,0 +1,18 @@
+-- ============================================================================
+-- IMAP reply ingestion
+-- The worker polls each inbox over IMAP and stores replies to campaign sends
+-- in email_replies. imap_host/imap_port override the server derived from the
+-- provider or SMTP host. imap_last_uid is the highest UID already processed
+-- and is only valid for the mailbox's imap_uid_validity.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_host VARCHAR(255);
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_port INTEGER;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_last_uid BIGINT;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_uid_validity BIGINT;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_last_polled_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS imap_last_error TEXT;
+
+-- The same message can be seen again after a UIDVALIDITY reset
+CREATE UNIQUE INDEX IF NOT EXISTS idx_replies_account_message
+    ON email_replies(email_account_id, message_id) WHERE message_id IS NOT NULL;
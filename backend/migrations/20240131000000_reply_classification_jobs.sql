@@ -0,0 +1,10 @@
+-- ============================================================================
+-- Background reply classification
+-- A worker sweep queues a ClassifyReply job for every unclassified reply and
+-- stamps classification_queued_at so the same reply isn't queued twice.
+-- ============================================================================
+
+ALTER TABLE email_replies ADD COLUMN IF NOT EXISTS classification_queued_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_replies_unclassified ON email_replies(created_at)
+    WHERE classified_at IS NULL AND classification_queued_at IS NULL;
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- Campaign email templates
+-- Each campaign can store its own subject and bodies, rendered with Handlebars
+-- merge fields ({{firstName}}, {{company}}, ...). step_order orders the emails
+-- of a sequence; campaigns without a template use the built-in default. Bodies
+-- must contain {{unsubscribe_url}}, which the API checks before saving.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS campaign_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    step_order INTEGER NOT NULL DEFAULT 1 CHECK (step_order > 0),
+    subject TEXT NOT NULL,
+    body_html TEXT NOT NULL,
+    body_text TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(campaign_id, step_order)
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_templates_workspace ON campaign_templates(workspace_id);
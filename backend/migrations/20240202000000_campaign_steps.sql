@@ -0,0 +1,32 @@
+-- ============================================================================
+-- Multi-step follow-up sequences
+-- campaign_steps lists a campaign's emails in step_index order, each with its
+-- template, the days to wait after the previous send and a condition on the
+-- previous email (no_reply, not_opened, opened). Every step requires that the
+-- lead hasn't replied. Leads always start at step 0; campaign_leads.current_step
+-- is the last step sent or claimed. A sequence ends when the lead replies,
+-- unsubscribes, is suppressed, a reply is classified as interested, a condition
+-- isn't met or the steps run out.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS campaign_steps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    step_index INTEGER NOT NULL CHECK (step_index >= 0),
+    template_id UUID REFERENCES campaign_templates(id) ON DELETE SET NULL,
+    delay_days INTEGER NOT NULL DEFAULT 0 CHECK (delay_days >= 0),
+    condition VARCHAR(20) NOT NULL DEFAULT 'no_reply',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(campaign_id, step_index)
+);
+
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS current_step INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS sequence_stopped_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE campaign_leads ADD COLUMN IF NOT EXISTS sequence_stop_reason VARCHAR(30);
+
+ALTER TABLE sent_emails ADD COLUMN IF NOT EXISTS step_index INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_campaign_leads_sequence ON campaign_leads(campaign_id, sent_at)
+    WHERE status = 'sent' AND sequence_stopped_at IS NULL;
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- OAuth2 email accounts
+-- Google Workspace and Microsoft 365 inboxes can connect through OAuth instead
+-- of an SMTP password. For auth_method = 'oauth' the encrypted password columns
+-- hold the refresh token, so key rotation re-encrypts it like any password, and
+-- SMTP/IMAP log in with XOAUTH2 using short-lived access tokens minted from it.
+-- auth_error is set when the refresh token stops working; the inbox is paused
+-- until it is reconnected.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS auth_method VARCHAR(20) NOT NULL DEFAULT 'password';
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS auth_error TEXT;
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS auth_failed_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,37 @@
+-- ============================================================================
+-- Send windows
+-- Each inbox only sends between send_window_start and send_window_end in its
+-- own local time (timezone_offset_minutes, UTC when unset). A window whose end
+-- is before its start runs past midnight; equal start and end means all day.
+-- Campaigns can also hold sends until the recipient's business hours, reading
+-- the recipient's timezone from recipient_timezone_field.
+-- ============================================================================
+
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS send_window_start TIME NOT NULL DEFAULT '09:00';
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS send_window_end TIME NOT NULL DEFAULT '17:00';
+
+-- NULL ignores the recipient's timezone. 'timezone_offset_minutes' uses the
+-- lead's stored offset; any other value names a key in leads.signals holding an
+-- IANA zone such as 'America/New_York'.
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS recipient_timezone_field VARCHAR(100);
+
+-- Current local time for a lead, or NULL when its timezone is unknown or isn't
+-- a zone Postgres recognises
+CREATE OR REPLACE FUNCTION recipient_local_time(field TEXT, offset_minutes INTEGER, signals JSONB)
+RETURNS TIME AS $$
+BEGIN
+    IF field = 'timezone_offset_minutes' THEN
+        IF offset_minutes IS NULL THEN
+            RETURN NULL;
+        END IF;
+        RETURN ((NOW() AT TIME ZONE 'UTC') + make_interval(mins => offset_minutes))::time;
+    END IF;
+
+    IF signals IS NULL OR NULLIF(TRIM(signals->>field), '') IS NULL THEN
+        RETURN NULL;
+    END IF;
+    RETURN (NOW() AT TIME ZONE TRIM(signals->>field))::time;
+EXCEPTION WHEN OTHERS THEN
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql STABLE;
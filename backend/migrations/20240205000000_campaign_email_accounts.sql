@@ -0,0 +1,15 @@
+-- ============================================================================
+-- Campaign inboxes
+-- A campaign with rows here only sends from those inboxes; a campaign with
+-- none keeps sending from every inbox in its workspace.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS campaign_email_accounts (
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    email_account_id UUID NOT NULL REFERENCES email_accounts(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (campaign_id, email_account_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_email_accounts_account ON campaign_email_accounts(email_account_id);
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Job priority
+-- Workers claim higher-priority jobs first and fall back to age within a
+-- priority, so a large warmup batch can't hold up campaign sends queued after
+-- it. Defaults per job type live in JobType::default_priority.
+-- ============================================================================
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0;
+
+-- Jobs already waiting get the same priority they'd be enqueued with now
+UPDATE jobs SET priority = CASE job_type
+        WHEN '"SendEmail"' THEN 10
+        WHEN '"SendMeetingReminder"' THEN 10
+        WHEN '"WarmupEmail"' THEN -10
+        ELSE 0
+    END
+WHERE status IN ('pending', 'scheduled');
+
+CREATE INDEX IF NOT EXISTS idx_jobs_claim_order ON jobs(priority DESC, created_at)
+    WHERE status IN ('pending', 'scheduled');
@@ -0,0 +1,8 @@
+-- ============================================================================
+-- Company blog/news feeds
+-- RSS 2.0 or Atom feed polled for product-launch and expansion signals.
+-- ============================================================================
+
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS rss_feed_url TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_companies_rss_feed ON companies(rss_feed_url) WHERE rss_feed_url IS NOT NULL;
@@ -0,0 +1,9 @@
+-- ============================================================================
+-- Company Crunchbase permalinks
+-- Organization permalink (crunchbase.com/organization/<permalink>) used to
+-- pull funding rounds when CRUNCHBASE_API_KEY is set.
+-- ============================================================================
+
+ALTER TABLE companies ADD COLUMN IF NOT EXISTS crunchbase_permalink VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_companies_crunchbase ON companies(crunchbase_permalink) WHERE crunchbase_permalink IS NOT NULL;
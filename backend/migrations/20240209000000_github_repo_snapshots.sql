@@ -0,0 +1,14 @@
+-- ============================================================================
+-- GitHub repo snapshots
+-- Star counts captured on each org activity fetch, so star growth can be
+-- measured against the count from a week earlier.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS github_repo_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    repo_full_name VARCHAR(255) NOT NULL,
+    stars INTEGER NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_github_repo_snapshots_repo ON github_repo_snapshots(repo_full_name, captured_at DESC);
@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Warmup sends
+-- Each row is one warmup email between two inboxes of the same workspace, or
+-- the partner's reply to one. The receiving inbox's poller fills in when it
+-- saw the message, which is what the warmup stats are computed from.
+-- ============================================================================
+
+-- Daily volume the warmup ramp stops at; NULL means the default of 50
+ALTER TABLE email_accounts ADD COLUMN IF NOT EXISTS warmup_daily_target INTEGER;
+
+CREATE TABLE IF NOT EXISTS warmup_emails (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    sender_account_id UUID NOT NULL REFERENCES email_accounts(id) ON DELETE CASCADE,
+    recipient_account_id UUID NOT NULL REFERENCES email_accounts(id) ON DELETE CASCADE,
+    -- Set on replies: the warmup email being answered
+    in_reply_to_id UUID REFERENCES warmup_emails(id) ON DELETE CASCADE,
+    message_id VARCHAR(255) NOT NULL,
+    subject TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set when the recipient's inbox poll finds the message in INBOX
+    opened_at TIMESTAMPTZ,
+    replied_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_warmup_emails_sender ON warmup_emails(sender_account_id, sent_at DESC);
+CREATE INDEX IF NOT EXISTS idx_warmup_emails_recipient ON warmup_emails(recipient_account_id, message_id);
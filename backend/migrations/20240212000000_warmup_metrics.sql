@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Warmup metrics
+-- Daily per-inbox rollup of warmup_emails, refreshed by the warmup cycle. The
+-- warmup stats endpoint sums a rolling window of these rows.
+-- ============================================================================
+
+-- Set when the partner's server rejected the warmup email outright
+ALTER TABLE warmup_emails ADD COLUMN IF NOT EXISTS bounced_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS warmup_metrics (
+    email_account_id UUID NOT NULL REFERENCES email_accounts(id) ON DELETE CASCADE,
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    metric_date DATE NOT NULL,
+    -- Accepted by the partner's server
+    delivered INTEGER NOT NULL DEFAULT 0,
+    -- Found in the partner's INBOX by its poll
+    landed_inbox INTEGER NOT NULL DEFAULT 0,
+    -- Delivered but still not in INBOX once the partner has had a day to see it
+    spam_foldered INTEGER NOT NULL DEFAULT 0,
+    bounced INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (email_account_id, metric_date)
+);
@@ -0,0 +1,9 @@
+-- ============================================================================
+-- Per-campaign auto-pause thresholds
+-- Optional overrides of the workspace_settings thresholds. NULL means the
+-- campaign uses the workspace value.
+-- ============================================================================
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS spam_rate_threshold FLOAT;
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS reply_drop_threshold FLOAT;
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS bounce_rate_threshold FLOAT;
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- Auto-resume
+-- Opt-in: campaigns the health check paused go back to active once their
+-- metrics have stayed within thresholds for the cooldown.
+-- ============================================================================
+
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS auto_resume_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS auto_resume_cooldown_hours INTEGER NOT NULL DEFAULT 24;
+
+-- When an auto-paused campaign's metrics came back within thresholds; NULL
+-- while they're still over, or once the campaign is running again
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS metrics_recovered_at TIMESTAMPTZ;
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Campaign metric snapshots
+-- One row per campaign per day, so the health check can compare the live
+-- reply rate with where it stood a couple of days ago.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS campaign_metric_snapshots (
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    snapshot_date DATE NOT NULL,
+    sent INTEGER NOT NULL DEFAULT 0,
+    replied INTEGER NOT NULL DEFAULT 0,
+    reply_rate FLOAT NOT NULL DEFAULT 0,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (campaign_id, snapshot_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_metric_snapshots_workspace ON campaign_metric_snapshots(workspace_id, snapshot_date);
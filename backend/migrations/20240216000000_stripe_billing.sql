@@ -0,0 +1,10 @@
+-- ============================================================================
+-- Stripe billing
+-- The customer and subscription a workspace pays through, kept in sync by
+-- the Stripe webhook.
+-- ============================================================================
+
+ALTER TABLE workspaces ADD COLUMN IF NOT EXISTS stripe_customer_id VARCHAR(255);
+ALTER TABLE workspaces ADD COLUMN IF NOT EXISTS stripe_subscription_id VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_workspaces_stripe_subscription ON workspaces(stripe_subscription_id) WHERE stripe_subscription_id IS NOT NULL;
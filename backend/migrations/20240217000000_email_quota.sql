@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Monthly email quota
+-- A running count of campaign sends this billing period, so the send path
+-- can enforce monthly_email_limit without counting rows on every send.
+-- ============================================================================
+
+ALTER TABLE workspaces ADD COLUMN IF NOT EXISTS emails_sent_this_period INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE workspaces ADD COLUMN IF NOT EXISTS email_period_start DATE NOT NULL DEFAULT date_trunc('month', NOW())::date;
+
+-- Why a running campaign isn't sending, e.g. 'monthly_email_limit_reached';
+-- cleared by the next successful send
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS send_blocked_reason VARCHAR(100);
+
+CREATE INDEX IF NOT EXISTS idx_sent_emails_workspace_sent ON sent_emails(workspace_id, sent_at);
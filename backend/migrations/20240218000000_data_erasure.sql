@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Data subject erasure
+-- Who erased which address and when, kept after the data itself is gone.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS data_erasure_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    leads_deleted INTEGER NOT NULL DEFAULT 0,
+    sent_emails_deleted INTEGER NOT NULL DEFAULT 0,
+    events_deleted INTEGER NOT NULL DEFAULT 0,
+    replies_anonymized INTEGER NOT NULL DEFAULT 0,
+    erased_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_erasure_log_workspace ON data_erasure_log(workspace_id, erased_at DESC);
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- Physical mailing address for campaign email footers
+-- CAN-SPAM requires a valid postal address in every commercial email. Campaign
+-- sends are held until the workspace has line 1, city and country set.
+-- ============================================================================
+
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_name VARCHAR(255);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_address_line1 VARCHAR(255);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_address_line2 VARCHAR(255);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_city VARCHAR(255);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_region VARCHAR(255);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_postal_code VARCHAR(32);
+ALTER TABLE workspace_settings ADD COLUMN IF NOT EXISTS company_country VARCHAR(255);
@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Bounces
+-- One row per bounce we learn about: a rejection from our own SMTP send, a
+-- provider webhook, or a bounce-back found in an inbox. Hard bounces are
+-- permanent (unknown mailbox, dead domain); soft ones are worth retrying.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS bounces (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    campaign_id UUID REFERENCES campaigns(id) ON DELETE SET NULL,
+    campaign_lead_id UUID REFERENCES campaign_leads(id) ON DELETE SET NULL,
+    email_account_id UUID REFERENCES email_accounts(id) ON DELETE SET NULL,
+    recipient VARCHAR(255) NOT NULL,
+    bounce_type VARCHAR(10) NOT NULL CHECK (bounce_type IN ('hard', 'soft')),
+    source VARCHAR(20) NOT NULL,               -- smtp, webhook, imap
+    reason TEXT,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounces_workspace ON bounces(workspace_id, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_bounces_campaign_lead ON bounces(campaign_lead_id);
@@ -0,0 +1,7 @@
+-- ============================================================================
+-- Lead custom fields
+-- Arbitrary per-lead data (industry, recent funding, a note) from CSV columns
+-- we don't otherwise map. Each key is usable as a template merge tag.
+-- ============================================================================
+
+ALTER TABLE leads ADD COLUMN IF NOT EXISTS custom_fields JSONB NOT NULL DEFAULT '{}';
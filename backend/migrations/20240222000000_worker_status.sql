@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Worker status
+-- Lets the API report whether the background worker is keeping up: when each
+-- of its periodic cycles last succeeded, and when jobs last failed.
+-- ============================================================================
+
+-- Last failed attempt, whether or not the job will be retried
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS failed_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_completed_at ON jobs(completed_at) WHERE status = 'completed';
+CREATE INDEX IF NOT EXISTS idx_jobs_failed_at ON jobs(failed_at) WHERE failed_at IS NOT NULL;
+
+-- One row per worker cycle (job loop, scheduler, warmup, auto-pause)
+CREATE TABLE IF NOT EXISTS worker_heartbeats (
+    task VARCHAR(50) PRIMARY KEY,
+    last_success_at TIMESTAMP WITH TIME ZONE,
+    last_error_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
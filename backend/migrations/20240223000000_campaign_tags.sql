@@ -0,0 +1,16 @@
+-- ============================================================================
+-- Campaign tags
+-- Labels ("q3", "founders") for grouping campaigns, stored lowercased like lead
+-- tags. The campaign list can be filtered to campaigns carrying every tag asked
+-- for; workspace_id is copied from the campaign so lookups stay in-workspace.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS campaign_tags (
+    campaign_id UUID NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (campaign_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_campaign_tags_workspace_tag ON campaign_tags(workspace_id, tag);
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Unique lead addresses among live leads only
+-- A trashed lead no longer blocks the address: importing or generating it
+-- again adds a new lead instead of quietly reviving or merging into the
+-- trashed one. Restoring a trashed lead fails while a live copy exists.
+-- ============================================================================
+
+DROP INDEX IF EXISTS idx_leads_workspace_email;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_leads_workspace_email_live
+    ON leads(workspace_id, email) WHERE deleted_at IS NULL;
@@ -0,0 +1,214 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, get_user_id, require_role};
+use crate::services::encryption::EncryptionService;
+use crate::services::key_rotation;
+use crate::services::worker_status;
+
+// ============================================================================
+// Platform admin endpoints
+// Only users with the global `admin` role may call these. They operate across
+// workspaces, so nothing here is scoped by workspace_id.
+// ============================================================================
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct GlobalSuppressionEntry {
+    pub id: Uuid,
+    pub email: String,
+    pub reason: String,
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddGlobalSuppressionRequest {
+    pub email: String,
+    pub reason: String,
+    pub note: Option<String>,
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .route("/suppression", web::get().to(get_global_suppression))
+            .route("/suppression", web::post().to(add_global_suppression))
+            .route("/suppression/{email}", web::delete().to(remove_global_suppression))
+            .route("/encryption/reencrypt", web::post().to(reencrypt_secrets))
+            .route("/campaigns/{id}", web::delete().to(purge_campaign))
+            .route("/leads/{id}", web::delete().to(purge_lead))
+            .route("/worker-status", web::get().to(get_worker_status))
+    );
+}
+
+async fn get_global_suppression(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+
+    let entries = sqlx::query_as::<_, GlobalSuppressionEntry>(
+        r#"
+        SELECT id, email, reason, note, created_by, created_at
+        FROM global_suppression
+        ORDER BY created_at DESC
+        LIMIT 1000
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+async fn add_global_suppression(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<AddGlobalSuppressionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    let user_id = get_user_id(&claims)?;
+
+    let email = body.email.trim().to_lowercase();
+    if email.parse::<lettre::Address>().is_err() {
+        return Err(ApiError::Validation("Invalid email address".to_string()));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO global_suppression (id, email, reason, note, created_by, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (email) DO UPDATE SET
+            reason = $3,
+            note = $4,
+            created_by = $5
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(&email)
+    .bind(&body.reason)
+    .bind(&body.note)
+    .bind(user_id)
+    .bind(Utc::now())
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "success": true,
+        "email": email,
+        "reason": body.reason
+    })))
+}
+
+async fn remove_global_suppression(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    let email = path.into_inner().trim().to_lowercase();
+
+    let result = sqlx::query("DELETE FROM global_suppression WHERE email = $1")
+        .bind(&email)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() > 0 {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true, "email": email})))
+    } else {
+        Err(ApiError::NotFound("Email not found in global suppression list".to_string()))
+    }
+}
+
+/// Moves every stored SMTP password and TOTP secret onto the current `ENCRYPTION_KEY`.
+/// Run after rotating keys, with the old key listed in `ENCRYPTION_PREVIOUS_KEYS`.
+async fn reencrypt_secrets(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+
+    let encryption = EncryptionService::new().map_err(ApiError::internal)?;
+
+    let smtp_passwords = key_rotation::reencrypt_smtp_passwords(pool.get_ref(), &encryption)
+        .await
+        .map_err(ApiError::internal)?;
+    let totp_secrets = key_rotation::reencrypt_totp_secrets(pool.get_ref(), &encryption)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "smtp_passwords": smtp_passwords,
+        "totp_secrets": totp_secrets
+    })))
+}
+
+/// Permanently deletes a campaign and everything that cascades from it.
+/// Workspace users can only soft-delete; this is for erasure requests.
+async fn purge_campaign(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    let campaign_id = path.into_inner();
+
+    let result = sqlx::query("DELETE FROM campaigns WHERE id = $1")
+        .bind(campaign_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() > 0 {
+        tracing::info!("Campaign {} permanently deleted by admin {}", campaign_id, claims.user_id);
+        Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true, "id": campaign_id})))
+    } else {
+        Err(ApiError::NotFound("Campaign not found".to_string()))
+    }
+}
+
+/// Permanently deletes a lead (GDPR erasure), whether or not it was soft-deleted first.
+async fn purge_lead(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    let lead_id = path.into_inner();
+
+    let result = sqlx::query("DELETE FROM leads WHERE id = $1")
+        .bind(lead_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() > 0 {
+        tracing::info!("Lead {} permanently deleted by admin {}", lead_id, claims.user_id);
+        Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true, "id": lead_id})))
+    } else {
+        Err(ApiError::NotFound("Lead not found".to_string()))
+    }
+}
+
+/// Queue depth, last hour's throughput and when each worker cycle last ran,
+/// for alerting on a stalled worker
+async fn get_worker_status(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+
+    let status = worker_status::status(pool.get_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
@@ -1,7 +1,10 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate, Utc};
 use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
-use crate::services::deliverability::DeliverabilityService;
+use uuid::Uuid;
+use crate::services::analytics::{self, TimeseriesInterval, TimeseriesMetric, TimeseriesPoint, TimeseriesRange};
+use crate::services::deliverability::{DeliverabilityReport, DeliverabilityService};
 use crate::middleware::auth::{extract_claims, get_workspace_id};
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -11,6 +14,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/campaigns", web::get().to(get_campaign_analytics))
             .route("/leads", web::get().to(get_lead_analytics))
             .route("/deliverability", web::get().to(get_deliverability_report))
+            .route("/timeseries", web::get().to(get_timeseries))
+            .route("/inboxes", web::get().to(get_inbox_ranking))
+            .route("/inboxes/{id}", web::get().to(get_inbox_health))
     );
 }
 
@@ -44,7 +50,7 @@ async fn get_overview(pool: web::Data<PgPool>, req: HttpRequest) -> impl Respond
             COUNT(*) as total,
             COUNT(*) FILTER (WHERE verification_status = 'valid') as verified
         FROM leads
-        WHERE workspace_id = $1
+        WHERE workspace_id = $1 AND deleted_at IS NULL
         "#
     )
     .bind(workspace_id)
@@ -61,7 +67,7 @@ async fn get_overview(pool: web::Data<PgPool>, req: HttpRequest) -> impl Respond
             COALESCE(SUM(opened), 0) as opened,
             COALESCE(SUM(replied), 0) as replied
         FROM campaigns
-        WHERE workspace_id = $1
+        WHERE workspace_id = $1 AND deleted_at IS NULL
         "#
     )
     .bind(workspace_id)
@@ -125,7 +131,7 @@ async fn get_campaign_analytics(pool: web::Data<PgPool>, req: HttpRequest) -> im
     };
 
     let result = sqlx::query_as::<_, crate::models::campaign::Campaign>(
-        "SELECT * FROM campaigns WHERE workspace_id = $1 ORDER BY created_at DESC LIMIT 50"
+        "SELECT * FROM campaigns WHERE workspace_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT 50"
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
@@ -204,7 +210,7 @@ async fn get_lead_analytics(pool: web::Data<PgPool>, req: HttpRequest) -> impl R
             COUNT(*) FILTER (WHERE verification_status = 'risky') as risky,
             COALESCE(AVG(confidence_score), 0) as avg_confidence
         FROM leads
-        WHERE workspace_id = $1
+        WHERE workspace_id = $1 AND deleted_at IS NULL
         "#
     )
     .bind(workspace_id)
@@ -231,26 +237,179 @@ async fn get_lead_analytics(pool: web::Data<PgPool>, req: HttpRequest) -> impl R
     }
 }
 
-async fn get_deliverability_report(_pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
-    // Validate auth even though we're returning mock data
+#[derive(Debug, Deserialize)]
+struct DeliverabilityQuery {
+    /// First day included (UTC); defaults to 30 days before `to`
+    from: Option<NaiveDate>,
+    /// Last day included (UTC); defaults to today
+    to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeliverabilityResponse {
+    from: NaiveDate,
+    to: NaiveDate,
+    #[serde(flatten)]
+    report: DeliverabilityReport,
+}
+
+async fn get_deliverability_report(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    query: web::Query<DeliverabilityQuery>,
+) -> impl Responder {
     let claims = match extract_claims(&req) {
         Ok(c) => c,
         Err(e) => return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()})),
     };
-    let _workspace_id = match get_workspace_id(&claims) {
+    let workspace_id = match get_workspace_id(&claims) {
         Ok(id) => id,
         Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e.to_string()})),
     };
 
-    let service = DeliverabilityService::new();
-    
-    // In production, these would come from actual tracking data
-    let report = service.generate_report(
-        1000,  // total_sent
-        950,   // delivered
-        30,    // bounced
-        5,     // spam_complaints
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_TIMESERIES_DAYS - 1));
+    let range = match TimeseriesRange::new(from, to, TimeseriesInterval::Day) {
+        Ok(range) => range,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e})),
+    };
+
+    let totals = match analytics::deliverability_totals(pool.get_ref(), workspace_id, &range).await {
+        Ok(totals) => totals,
+        Err(e) => return HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    };
+
+    let count = |n: i64| i32::try_from(n).unwrap_or(i32::MAX);
+    let report = DeliverabilityService::new().generate_report(
+        count(totals.total_sent),
+        count(totals.delivered),
+        count(totals.bounced),
+        count(totals.spam_complaints),
     );
-    
-    HttpResponse::Ok().json(report)
+
+    HttpResponse::Ok().json(DeliverabilityResponse { from, to, report })
+}
+
+/// Days covered when the request doesn't give `from`
+const DEFAULT_TIMESERIES_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+struct TimeseriesQuery {
+    metric: TimeseriesMetric,
+    /// First day included (UTC); defaults to 30 days before `to`
+    from: Option<NaiveDate>,
+    /// Last day included (UTC); defaults to today
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    interval: TimeseriesInterval,
+    campaign_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+struct TimeseriesResponse {
+    metric: TimeseriesMetric,
+    interval: TimeseriesInterval,
+    from: NaiveDate,
+    to: NaiveDate,
+    campaign_id: Option<Uuid>,
+    /// One point per bucket, oldest first, with zero counts for quiet buckets
+    points: Vec<TimeseriesPoint>,
+}
+
+async fn get_timeseries(pool: web::Data<PgPool>, req: HttpRequest, query: web::Query<TimeseriesQuery>) -> impl Responder {
+    let claims = match extract_claims(&req) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()})),
+    };
+    let workspace_id = match get_workspace_id(&claims) {
+        Ok(id) => id,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_TIMESERIES_DAYS - 1));
+    let range = match TimeseriesRange::new(from, to, query.interval) {
+        Ok(range) => range,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e})),
+    };
+
+    match analytics::timeseries(pool.get_ref(), workspace_id, query.campaign_id, query.metric, &range).await {
+        Ok(points) => HttpResponse::Ok().json(TimeseriesResponse {
+            metric: query.metric,
+            interval: query.interval,
+            from,
+            to,
+            campaign_id: query.campaign_id,
+            points,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InboxHealthQuery {
+    /// First day included (UTC); defaults to 30 days before `to`
+    from: Option<NaiveDate>,
+    /// Last day included (UTC); defaults to today
+    to: Option<NaiveDate>,
+}
+
+async fn get_inbox_health(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<InboxHealthQuery>,
+) -> impl Responder {
+    let claims = match extract_claims(&req) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()})),
+    };
+    let workspace_id = match get_workspace_id(&claims) {
+        Ok(id) => id,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_TIMESERIES_DAYS - 1));
+    let range = match TimeseriesRange::new(from, to, TimeseriesInterval::Day) {
+        Ok(range) => range,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e})),
+    };
+
+    match analytics::inbox_health(pool.get_ref(), workspace_id, path.into_inner(), &range).await {
+        Ok(Some(series)) => HttpResponse::Ok().json(serde_json::json!({
+            "from": from,
+            "to": to,
+            "inbox": series,
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({"error": "Inbox not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    }
+}
+
+async fn get_inbox_ranking(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
+    let claims = match extract_claims(&req) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()})),
+    };
+    let workspace_id = match get_workspace_id(&claims) {
+        Ok(id) => id,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    match analytics::inbox_ranking(pool.get_ref(), workspace_id).await {
+        Ok(inboxes) => HttpResponse::Ok().json(serde_json::json!({
+            "trend_days": analytics::INBOX_RANKING_TREND_DAYS,
+            "inboxes": inboxes,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    }
 }
@@ -3,20 +3,31 @@ use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc, Duration};
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
 
-use std::env;
-
-fn get_jwt_secret() -> String {
-    env::var("JWT_SECRET").unwrap_or_else(|_| "your-super-secret-jwt-key-change-in-production".to_string())
-}
+use std::sync::LazyLock;
+use utoipa::{OpenApi, ToSchema};
+use crate::services::encryption::EncryptionService;
+use crate::services::jwt_keys::jwt_keys;
+use crate::services::password_reset::{self, ResetError};
+use crate::services::rate_limiter::LoginThrottle;
+use crate::services::two_factor::{self, EnrollmentOutcome};
+use crate::services::workspace_membership::{self, WorkspaceMembership};
 
 const JWT_EXPIRATION_HOURS: i64 = 24;
 
+/// Failed logins per account; `LOGIN_MAX_FAILURES` (default 6)
+static LOGIN_EMAIL_THROTTLE: LazyLock<LoginThrottle> =
+    LazyLock::new(|| LoginThrottle::from_env("LOGIN_MAX_FAILURES", 6));
+
+/// Failed logins per client address, looser since offices share one;
+/// `LOGIN_MAX_FAILURES_PER_IP` (default 30)
+static LOGIN_IP_THROTTLE: LazyLock<LoginThrottle> =
+    LazyLock::new(|| LoginThrottle::from_env("LOGIN_MAX_FAILURES_PER_IP", 30));
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
     pub id: Uuid,
@@ -27,6 +38,8 @@ pub struct User {
     pub role: String,
     pub created_at: DateTime<Utc>,
     pub last_login: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing)]
+    pub totp_enabled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,26 +52,65 @@ pub struct Claims {
     pub iat: usize,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterRequest {
     pub email: String,
     pub password: String,
     pub name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// Authenticator or recovery code; required once two-factor auth is enabled
+    #[serde(default)]
+    pub two_factor_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorVerifyRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorVerifyResponse {
+    pub enabled: bool,
+    /// Shown only once; each code can be used in place of an authenticator code
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DisableTwoFactorRequest {
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResetPasswordRequest {
+    /// Token from the reset link
+    pub token: String,
+    pub password: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     pub token: String,
     pub user: UserResponse,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub email: String,
@@ -66,6 +118,12 @@ pub struct UserResponse {
     pub role: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SwitchWorkspaceResponse {
+    pub token: String,
+    pub workspace: WorkspaceMembership,
+}
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
@@ -73,9 +131,42 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/login", web::post().to(login))
             .route("/me", web::get().to(get_current_user))
             .route("/refresh", web::post().to(refresh_token))
+            .route("/workspaces", web::get().to(list_workspaces))
+            .route("/switch-workspace/{id}", web::post().to(switch_workspace))
+            .route("/2fa/setup", web::post().to(setup_two_factor))
+            .route("/2fa/verify", web::post().to(verify_two_factor))
+            .route("/2fa/disable", web::post().to(disable_two_factor))
+            .route("/forgot-password", web::post().to(forgot_password))
+            .route("/reset-password", web::post().to(reset_password))
     );
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    register,
+    login,
+    get_current_user,
+    refresh_token,
+    list_workspaces,
+    switch_workspace,
+    setup_two_factor,
+    verify_two_factor,
+    disable_two_factor,
+    forgot_password,
+    reset_password,
+))]
+pub struct AuthApi;
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created", body = AuthResponse),
+        (status = 409, description = "Email already registered"),
+    )
+)]
 async fn register(
     pool: web::Data<PgPool>,
     payload: web::Json<RegisterRequest>,
@@ -188,10 +279,48 @@ async fn register(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in, or `{\"two_factor_required\": true}` when a code is needed", body = AuthResponse),
+        (status = 401, description = "Invalid credentials or two-factor code"),
+        (status = 429, description = "Too many failed attempts for this email or address; see Retry-After"),
+    )
+)]
 async fn login(
     pool: web::Data<PgPool>,
+    req: HttpRequest,
     payload: web::Json<LoginRequest>,
 ) -> impl Responder {
+    // Honours X-Forwarded-For so proxied clients aren't all one address; a spoofed
+    // header only dodges the per-IP limit, never the per-email one
+    let ip_key = format!("ip:{}", req.connection_info().realip_remote_addr().unwrap_or("unknown"));
+    let email_key = format!("email:{}", payload.email.trim().to_lowercase());
+
+    let locked_for = [
+        LOGIN_EMAIL_THROTTLE.check(&email_key),
+        LOGIN_IP_THROTTLE.check(&ip_key),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .max();
+    if let Some(retry_after) = locked_for {
+        let secs = retry_after.as_secs().max(1);
+        return HttpResponse::TooManyRequests()
+            .insert_header((actix_web::http::header::RETRY_AFTER, secs.to_string()))
+            .json(serde_json::json!({
+                "error": format!("Too many failed login attempts. Try again in {} seconds.", secs)
+            }));
+    }
+
+    let record_failure = || {
+        LOGIN_EMAIL_THROTTLE.record_failure(&email_key);
+        LOGIN_IP_THROTTLE.record_failure(&ip_key);
+    };
+
     let result = sqlx::query_as::<_, User>(
         "SELECT * FROM users WHERE email = $1"
     )
@@ -210,11 +339,41 @@ async fn login(
             };
 
             if Argon2::default().verify_password(payload.password.as_bytes(), &parsed_hash).is_err() {
+                record_failure();
                 return HttpResponse::Unauthorized().json(
                     serde_json::json!({"error": "Invalid credentials"})
                 );
             }
 
+            // No token until the second factor checks out
+            if user.totp_enabled {
+                let code = payload.two_factor_code.as_deref().map(str::trim).filter(|c| !c.is_empty());
+                let Some(code) = code else {
+                    return HttpResponse::Ok().json(serde_json::json!({"two_factor_required": true}));
+                };
+
+                let encryption = match EncryptionService::new() {
+                    Ok(e) => e,
+                    Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({"error": e})),
+                };
+                match two_factor::verify_login_code(pool.get_ref(), &encryption, user.id, &user.email, code).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        record_failure();
+                        return HttpResponse::Unauthorized().json(
+                            serde_json::json!({"error": "Invalid two-factor code"})
+                        );
+                    }
+                    Err(e) => return HttpResponse::InternalServerError().json(
+                        serde_json::json!({"error": e})
+                    ),
+                }
+            }
+
+            // Only the account's counter resets; clearing the IP's would let one valid
+            // login launder a stuffing run's failures
+            LOGIN_EMAIL_THROTTLE.record_success(&email_key);
+
             // Update last login
             let _ = sqlx::query("UPDATE users SET last_login = NOW() WHERE id = $1")
                 .bind(user.id)
@@ -249,15 +408,28 @@ async fn login(
                 },
             })
         }
-        Ok(None) => HttpResponse::Unauthorized().json(
-            serde_json::json!({"error": "Invalid credentials"})
-        ),
+        Ok(None) => {
+            record_failure();
+            HttpResponse::Unauthorized().json(
+                serde_json::json!({"error": "Invalid credentials"})
+            )
+        }
         Err(e) => HttpResponse::InternalServerError().json(
             serde_json::json!({"error": e.to_string()})
         ),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The authenticated user", body = UserResponse),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_current_user(
     pool: web::Data<PgPool>,
     req: HttpRequest,
@@ -299,6 +471,16 @@ async fn get_current_user(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "A fresh token with the same claims"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn refresh_token(req: HttpRequest) -> impl Responder {
     let claims = match extract_claims(&req) {
         Some(c) => c,
@@ -314,10 +496,292 @@ async fn refresh_token(req: HttpRequest) -> impl Responder {
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/workspaces",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Workspaces the user belongs to, oldest membership first", body = [WorkspaceMembership]),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_workspaces(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.user_id) else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": "Invalid user ID"}));
+    };
+
+    match workspace_membership::list_for_user(pool.get_ref(), user_id).await {
+        Ok(workspaces) => HttpResponse::Ok().json(workspaces),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/switch-workspace/{id}",
+    tag = "auth",
+    params(("id" = Uuid, Path, description = "Workspace to switch to")),
+    responses(
+        (status = 200, description = "A new token scoped to the workspace", body = SwitchWorkspaceResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Not a member of the workspace"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn switch_workspace(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.user_id) else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": "Invalid user ID"}));
+    };
+    let workspace_id = path.into_inner();
+
+    match workspace_membership::find(pool.get_ref(), user_id, workspace_id).await {
+        // Everything downstream reads the workspace from the token, so a new token is the switch
+        Ok(Some(workspace)) => HttpResponse::Ok().json(SwitchWorkspaceResponse {
+            token: generate_token(&claims.user_id, &claims.sub, &claims.role, Some(&workspace_id.to_string())),
+            workspace,
+        }),
+        Ok(None) => HttpResponse::Forbidden().json(
+            serde_json::json!({"error": "You are not a member of this workspace"})
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/setup",
+    tag = "auth",
+    responses(
+        (status = 200, description = "New secret; 2FA is enabled once a code is verified", body = TwoFactorSetupResponse),
+        (status = 409, description = "Two-factor authentication is already enabled"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn setup_two_factor(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.user_id) else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": "Invalid user ID"}));
+    };
+
+    let encryption = match EncryptionService::new() {
+        Ok(e) => e,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({"error": e})),
+    };
+    match two_factor::begin_enrollment(pool.get_ref(), &encryption, user_id, &claims.sub).await {
+        Ok(Some(enrollment)) => HttpResponse::Ok().json(TwoFactorSetupResponse {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+        }),
+        Ok(None) => HttpResponse::Conflict().json(
+            serde_json::json!({"error": "Two-factor authentication is already enabled"})
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e})
+        ),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/verify",
+    tag = "auth",
+    request_body = TwoFactorVerifyRequest,
+    responses(
+        (status = 200, description = "2FA enabled", body = TwoFactorVerifyResponse),
+        (status = 400, description = "Invalid code or setup not started"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn verify_two_factor(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    payload: web::Json<TwoFactorVerifyRequest>,
+) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.user_id) else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": "Invalid user ID"}));
+    };
+
+    let encryption = match EncryptionService::new() {
+        Ok(e) => e,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({"error": e})),
+    };
+    match two_factor::confirm_enrollment(pool.get_ref(), &encryption, user_id, &claims.sub, &payload.code).await {
+        Ok(EnrollmentOutcome::Enabled(recovery_codes)) => HttpResponse::Ok().json(TwoFactorVerifyResponse {
+            enabled: true,
+            recovery_codes,
+        }),
+        Ok(EnrollmentOutcome::InvalidCode) => HttpResponse::BadRequest().json(
+            serde_json::json!({"error": "Invalid two-factor code"})
+        ),
+        Ok(EnrollmentOutcome::NotStarted) => HttpResponse::BadRequest().json(
+            serde_json::json!({"error": "Start two-factor setup first"})
+        ),
+        Ok(EnrollmentOutcome::AlreadyEnabled) => HttpResponse::BadRequest().json(
+            serde_json::json!({"error": "Two-factor authentication is already enabled"})
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e})
+        ),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/disable",
+    tag = "auth",
+    request_body = DisableTwoFactorRequest,
+    responses(
+        (status = 200, description = "2FA disabled and recovery codes discarded"),
+        (status = 403, description = "Password is incorrect"),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn disable_two_factor(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    payload: web::Json<DisableTwoFactorRequest>,
+) -> impl Responder {
+    let claims = match require_auth(&req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let Ok(user_id) = Uuid::parse_str(&claims.user_id) else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": "Invalid user ID"}));
+    };
+
+    let password_hash = sqlx::query_scalar::<_, String>("SELECT password_hash FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    let password_hash = match password_hash {
+        Ok(Some(hash)) => hash,
+        Ok(None) => return HttpResponse::NotFound().json(
+            serde_json::json!({"error": "User not found"})
+        ),
+        Err(e) => return HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+    };
+
+    // 403 rather than 401: the session is valid, only the re-entered password is wrong
+    if !password_matches(&password_hash, &payload.password) {
+        return HttpResponse::Forbidden().json(
+            serde_json::json!({"error": "Incorrect password"})
+        );
+    }
+
+    match two_factor::disable(pool.get_ref(), user_id).await {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({"enabled": false})),
+        Err(e) => HttpResponse::InternalServerError().json(
+            serde_json::json!({"error": e})
+        ),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/forgot-password",
+    tag = "auth",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "Always returned, whether or not the email has an account"),
+    )
+)]
+async fn forgot_password(
+    pool: web::Data<PgPool>,
+    payload: web::Json<ForgotPasswordRequest>,
+) -> impl Responder {
+    let email = payload.into_inner().email.trim().to_string();
+
+    // Done in the background so the response time doesn't reveal whether the account exists
+    actix_web::rt::spawn(async move {
+        let user = sqlx::query_as::<_, (Uuid, String, String)>(
+            "SELECT id, email, name FROM users WHERE email = $1"
+        )
+        .bind(&email)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+        match user {
+            Ok(Some((user_id, email, name))) => match password_reset::create_reset_token(pool.get_ref(), user_id).await {
+                Ok(token) => password_reset::send_reset_email(&email, &name, &token).await,
+                Err(e) => tracing::error!("Failed to create password reset token: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to look up user for password reset: {}", e),
+        }
+    });
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "message": "If an account exists for that email, a password reset link has been sent"
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password changed"),
+        (status = 400, description = "Invalid, expired or used token, or a weak password"),
+    )
+)]
+async fn reset_password(
+    pool: web::Data<PgPool>,
+    payload: web::Json<ResetPasswordRequest>,
+) -> impl Responder {
+    match password_reset::reset_password(pool.get_ref(), payload.token.trim(), &payload.password).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"message": "Password has been reset"})),
+        Err(e @ (ResetError::InvalidToken | ResetError::WeakPassword(_))) => HttpResponse::BadRequest().json(
+            serde_json::json!({"error": e.to_string()})
+        ),
+        Err(e) => {
+            tracing::error!("Password reset failed: {}", e);
+            HttpResponse::InternalServerError().json(
+                serde_json::json!({"error": "Failed to reset password"})
+            )
+        }
+    }
+}
+
+fn password_matches(password_hash: &str, password: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
 fn generate_token(user_id: &str, email: &str, role: &str, workspace_id: Option<&str>) -> String {
     let now = Utc::now();
     let exp = now + Duration::hours(JWT_EXPIRATION_HOURS);
-    let secret = get_jwt_secret();
 
     let claims = Claims {
         sub: email.to_string(),
@@ -328,12 +792,7 @@ fn generate_token(user_id: &str, email: &str, role: &str, workspace_id: Option<&
         iat: now.timestamp() as usize,
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .unwrap_or_default()
+    jwt_keys().encode(&claims).unwrap_or_default()
 }
 
 pub fn extract_claims(req: &HttpRequest) -> Option<Claims> {
@@ -346,14 +805,7 @@ pub fn extract_claims(req: &HttpRequest) -> Option<Claims> {
 
     let token = &auth_str[7..];
     
-    let secret = get_jwt_secret();
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(),
-    )
-    .ok()
-    .map(|data| data.claims)
+    jwt_keys().decode::<Claims>(token)
 }
 
 pub fn require_auth(req: &HttpRequest) -> Result<Claims, HttpResponse> {
@@ -375,3 +827,54 @@ pub fn require_admin(req: &HttpRequest) -> Result<Claims, HttpResponse> {
     
     Ok(claims)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use actix_web::{http::{header, StatusCode}, test, App};
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[actix_web::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_seventh_login_after_six_failures_is_429_with_retry_after() {
+        let pool = test_db::pool().await;
+        let password_hash = Argon2::default()
+            .hash_password(b"correct horse", &SaltString::generate(&mut OsRng))
+            .unwrap()
+            .to_string();
+        let (user_id, email) = test_db::user(&pool, &password_hash).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .service(web::scope("/api").configure(configure)),
+        )
+        .await;
+        let login_with = |password: &str| {
+            test::TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(serde_json::json!({"email": email, "password": password}))
+                .to_request()
+        };
+
+        let mut failures = Vec::new();
+        for _ in 0..6 {
+            failures.push(test::call_service(&app, login_with("wrong")).await.status());
+        }
+        // Locked out even with the right password
+        let seventh = test::call_service(&app, login_with("correct horse")).await;
+        let status = seventh.status();
+        let retry_after: Option<u64> = seventh
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+
+        test_db::delete_user(&pool, user_id).await;
+
+        assert!(failures.iter().all(|s| *s == StatusCode::UNAUTHORIZED));
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(retry_after.is_some_and(|secs| (1..=60).contains(&secs)));
+    }
+}
@@ -1,56 +1,12 @@
 use actix_web::{web, HttpResponse, HttpRequest};
 use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 use chrono::Utc;
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
-
-#[derive(Debug, Clone, Serialize)]
-pub struct PricingTier {
-    pub id: String,
-    pub name: String,
-    pub price_monthly: i32,
-    pub price_yearly: i32,
-    pub leads_per_month: i32,
-    pub inboxes: i32,
-    pub emails_per_month: i32,
-    pub features: Vec<String>,
-}
-
-pub fn get_pricing_tiers() -> Vec<PricingTier> {
-    vec![
-        PricingTier {
-            id: "starter".to_string(),
-            name: "Starter".to_string(),
-            price_monthly: 9700,
-            price_yearly: 97000,
-            leads_per_month: 1000,
-            inboxes: 1,
-            emails_per_month: 500,
-            features: vec!["Basic warmup".to_string(), "Email support".to_string(), "Lead verification".to_string()],
-        },
-        PricingTier {
-            id: "professional".to_string(),
-            name: "Professional".to_string(),
-            price_monthly: 29700,
-            price_yearly: 297000,
-            leads_per_month: 10000,
-            inboxes: 5,
-            emails_per_month: 5000,
-            features: vec!["Advanced warmup".to_string(), "Domain health monitoring".to_string(), "Priority support".to_string(), "A/B testing".to_string(), "Analytics dashboard".to_string()],
-        },
-        PricingTier {
-            id: "business".to_string(),
-            name: "Business".to_string(),
-            price_monthly: 99700,
-            price_yearly: 997000,
-            leads_per_month: 50000,
-            inboxes: 20,
-            emails_per_month: 25000,
-            features: vec!["Dedicated deliverability manager".to_string(), "Custom integrations".to_string(), "Slack support".to_string(), "95%+ inbox guarantee".to_string(), "ROI reporting".to_string(), "White-glove onboarding".to_string()],
-        },
-    ]
-}
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::billing::{self, get_pricing_tiers, BillingCycle, StripeClient};
+use crate::services::email_quota;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateCheckoutRequest {
@@ -80,45 +36,116 @@ async fn get_pricing() -> HttpResponse {
     HttpResponse::Ok().json(get_pricing_tiers())
 }
 
+fn frontend_url(path: &str) -> String {
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    format!("{}{}", frontend_url.trim_end_matches('/'), path)
+}
+
+fn stripe_not_configured() -> HttpResponse {
+    HttpResponse::ServiceUnavailable().json(serde_json::json!({
+        "error": "Stripe not configured. Set STRIPE_SECRET_KEY environment variable."
+    }))
+}
+
+/// Stripe itself failed; the details are logged rather than passed on
+fn stripe_unavailable(message: &str) -> HttpResponse {
+    HttpResponse::BadGateway().json(serde_json::json!({ "error": message }))
+}
+
 async fn create_checkout(
-    _pool: web::Data<PgPool>,
+    pool: web::Data<PgPool>,
     req: HttpRequest,
     body: web::Json<CreateCheckoutRequest>,
-) -> Result<HttpResponse, actix_web::Error> {
-    let _claims = extract_claims(&req)?;
-    let _workspace_id = parse_workspace_id(&_claims)?;
-
-    let tiers = get_pricing_tiers();
-    let _tier = tiers.iter()
-        .find(|t| t.id == body.tier_id)
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("Invalid tier"))?;
-
-    // TODO: Implement Stripe checkout when STRIPE_SECRET_KEY is configured
-    // For now, return a proper error status so frontend handles it correctly
-    Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
-        "error": "Stripe not configured. Set STRIPE_SECRET_KEY environment variable.",
-        "tier_id": body.tier_id,
-        "billing_cycle": body.billing_cycle
-    })))
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Admin).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let tier = billing::find_tier(&body.tier_id)
+        .ok_or_else(|| ApiError::Validation("Invalid tier".to_string()))?;
+    let cycle = BillingCycle::parse(&body.billing_cycle)
+        .ok_or_else(|| ApiError::Validation("Billing cycle must be monthly or yearly".to_string()))?;
+
+    let Some(stripe) = StripeClient::from_env() else {
+        return Ok(stripe_not_configured());
+    };
+    let Some(price_id) = billing::price_id(&tier.id, cycle) else {
+        tracing::error!("No Stripe price configured for {} ({})", tier.id, cycle.as_str());
+        return Ok(stripe_not_configured());
+    };
+
+    // Returning customers keep their Stripe customer, and with it their payment methods
+    let customer_id: Option<String> = sqlx::query_scalar("SELECT stripe_customer_id FROM workspaces WHERE id = $1")
+        .bind(workspace_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(ApiError::internal)?
+        .flatten();
+
+    let session = match stripe
+        .create_checkout_session(
+            workspace_id,
+            &tier.id,
+            &price_id,
+            customer_id.as_deref(),
+            &frontend_url("/dashboard/settings?checkout=success"),
+            &frontend_url("/pricing?checkout=cancelled"),
+        )
+        .await
+    {
+        Ok(session) => session,
+        Err(e) => {
+            tracing::error!("Stripe checkout for workspace {} failed: {}", workspace_id, e);
+            return Ok(stripe_unavailable("Could not start checkout"));
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(CheckoutResponse {
+        checkout_url: session.url,
+        session_id: session.id,
+    }))
 }
 
 async fn create_portal_session(
-    _pool: web::Data<PgPool>,
+    pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let _claims = extract_claims(&req)?;
-    let _workspace_id = parse_workspace_id(&_claims)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Admin).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
 
-    // TODO: Implement Stripe portal when configured
-    Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
-        "error": "Stripe not configured. Set STRIPE_SECRET_KEY environment variable."
-    })))
+    let Some(stripe) = StripeClient::from_env() else {
+        return Ok(stripe_not_configured());
+    };
+
+    let customer_id: Option<String> = sqlx::query_scalar("SELECT stripe_customer_id FROM workspaces WHERE id = $1")
+        .bind(workspace_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(ApiError::internal)?
+        .flatten();
+    let Some(customer_id) = customer_id else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "This workspace has no subscription yet. Choose a plan first."
+        })));
+    };
+
+    let url = match stripe
+        .create_portal_session(&customer_id, &frontend_url("/dashboard/settings"))
+        .await
+    {
+        Ok(url) => url,
+        Err(e) => {
+            tracing::error!("Stripe portal for workspace {} failed: {}", workspace_id, e);
+            return Ok(stripe_unavailable("Could not open the billing portal"));
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "url": url })))
 }
 
 async fn get_subscription(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -139,7 +166,7 @@ async fn get_subscription(
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     match subscription {
         Some(sub) => Ok(HttpResponse::Ok().json(sub)),
@@ -150,7 +177,7 @@ async fn get_subscription(
 async fn get_usage(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -174,20 +201,12 @@ async fn get_usage(
     .bind(workspace_id)
     .fetch_one(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
-    let emails_sent: (i64,) = sqlx::query_as(
-        r#"
-        SELECT COUNT(*) FROM campaign_leads cl
-        JOIN campaigns c ON cl.campaign_id = c.id
-        WHERE c.workspace_id = $1 
-        AND cl.sent_at >= date_trunc('month', NOW())
-        "#
-    )
-    .bind(workspace_id)
-    .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    // The same counter the send path enforces the limit with
+    let emails_sent = email_quota::sent_this_period(pool.get_ref(), workspace_id)
+        .await
+        .map_err(ApiError::internal)?;
 
     let limits: Option<(i32, i32)> = sqlx::query_as(
         "SELECT monthly_lead_limit, monthly_email_limit FROM workspaces WHERE id = $1"
@@ -195,7 +214,7 @@ async fn get_usage(
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     let (leads_limit, emails_limit) = limits.unwrap_or((1000, 500));
 
@@ -206,7 +225,7 @@ async fn get_usage(
     Ok(HttpResponse::Ok().json(UsageSummary {
         leads_used: leads_used.0,
         leads_limit,
-        emails_sent: emails_sent.0,
+        emails_sent,
         emails_limit,
         period_start,
         period_end,
@@ -214,10 +233,50 @@ async fn get_usage(
 }
 
 async fn handle_webhook(
-    _pool: web::Data<PgPool>,
-    _req: HttpRequest,
-    _body: web::Bytes,
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Bytes,
 ) -> HttpResponse {
-    // TODO: Implement Stripe webhook handling when configured
-    HttpResponse::Ok().json(serde_json::json!({"received": true}))
+    let Some(secret) = std::env::var("STRIPE_WEBHOOK_SECRET").ok().filter(|s| !s.trim().is_empty()) else {
+        return stripe_not_configured();
+    };
+
+    let signature = req
+        .headers()
+        .get("Stripe-Signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if let Err(e) = billing::verify_signature(&body, signature, secret.trim(), Utc::now().timestamp()) {
+        tracing::warn!("Rejected Stripe webhook: {}", e);
+        return HttpResponse::BadRequest().json(serde_json::json!({"error": "Invalid signature"}));
+    }
+
+    let event: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(event) => event,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({"error": format!("Invalid payload: {}", e)})),
+    };
+
+    let update = match billing::plan_update(&event) {
+        Ok(Some(update)) => update,
+        Ok(None) => return HttpResponse::Ok().json(serde_json::json!({"received": true})),
+        // Acknowledged so Stripe doesn't keep retrying an event we can't act on
+        Err(e) => {
+            tracing::warn!("Ignoring Stripe event {}: {}", event["id"], e);
+            return HttpResponse::Ok().json(serde_json::json!({"received": true}));
+        }
+    };
+
+    match billing::apply_plan_update(pool.get_ref(), &update).await {
+        Ok(updated) => {
+            if !updated {
+                tracing::warn!("Stripe event {} matched no workspace (subscription {})", event["id"], update.subscription_id);
+            }
+            HttpResponse::Ok().json(serde_json::json!({"received": true}))
+        }
+        // A 5xx makes Stripe retry later
+        Err(e) => {
+            tracing::error!("Failed to apply Stripe event {}: {}", event["id"], e);
+            HttpResponse::InternalServerError().json(serde_json::json!({"error": "Failed to apply event"}))
+        }
+    }
 }
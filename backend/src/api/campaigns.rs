@@ -1,9 +1,22 @@
 use actix_web::{web, HttpResponse, HttpRequest};
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
-use chrono::Utc;
-use crate::models::campaign::{Campaign, CreateCampaignRequest, UpdateCampaignRequest, CampaignStatus};
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
+use chrono::{DateTime, Utc};
+use crate::models::campaign::{AssignInboxesRequest, Campaign, CampaignInbox, CampaignTagsRequest, CreateCampaignRequest, UpdateCampaignRequest, StartCampaignRequest, ScheduleCampaignRequest, CampaignStatus, SentEmail, PreviewCampaignRequest, SendTestEmailRequest, is_valid_reply_to};
+use crate::api::error::{ApiError, ErrorResponse};
+use crate::services::campaign_clone;
+use crate::services::campaign_tags::{self, CampaignFilter};
+use crate::services::campaign_scheduler::{activate_campaign, start_action, CampaignScheduler, ResendOutcome, StartAction};
+use crate::services::deliverability::spam_check;
+use crate::services::email_sender::{CampaignEmailSender, EmailTemplate, PreviewError};
+use crate::services::lead_tags;
+use crate::services::mailing_address;
+use crate::services::export::{self, CampaignResultRow, ExportQuery};
+use crate::models::lead::Lead;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use crate::middleware::auth::{extract_claims, get_user_id, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -14,68 +27,178 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/{id}", web::put().to(update_campaign))
             .route("/{id}", web::delete().to(delete_campaign))
             .route("/{id}/start", web::post().to(start_campaign))
+            .route("/{id}/schedule", web::post().to(schedule_campaign))
+            .route("/{id}/schedule", web::delete().to(cancel_campaign_schedule))
             .route("/{id}/pause", web::post().to(pause_campaign))
+            .route("/{id}/restore", web::post().to(restore_campaign))
+            .route("/{id}/clone", web::post().to(clone_campaign))
+            .route("/{id}/preview", web::post().to(preview_campaign))
+            .route("/{id}/send-test", web::post().to(send_test_email))
             .route("/{id}/leads", web::get().to(get_campaign_leads))
             .route("/{id}/leads", web::post().to(add_leads_to_campaign))
+            .route("/{id}/leads/{lead_id}/resend", web::post().to(resend_to_lead))
+            .route("/{id}/sent/{lead_id}", web::get().to(get_sent_emails))
+            .route("/{id}/export", web::get().to(export_campaign_results))
+            .route("/{id}/inboxes", web::get().to(get_campaign_inboxes))
+            .route("/{id}/inboxes", web::put().to(set_campaign_inboxes))
+            .route("/{id}/tags", web::get().to(get_campaign_tags))
+            .route("/{id}/tags", web::post().to(add_campaign_tags))
+            .route("/{id}/tags", web::delete().to(remove_campaign_tag))
     );
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_campaigns,
+    get_campaign_by_id,
+    create_campaign,
+    update_campaign,
+    delete_campaign,
+    start_campaign,
+    schedule_campaign,
+    cancel_campaign_schedule,
+    pause_campaign,
+    restore_campaign,
+    clone_campaign,
+    preview_campaign,
+    send_test_email,
+    get_campaign_leads,
+    add_leads_to_campaign,
+    resend_to_lead,
+    get_sent_emails,
+    export_campaign_results,
+    get_campaign_inboxes,
+    set_campaign_inboxes,
+    get_campaign_tags,
+    add_campaign_tags,
+    remove_campaign_tag,
+))]
+pub struct CampaignsApi;
+
+#[derive(serde::Deserialize, IntoParams)]
+pub struct CampaignListQuery {
+    /// Comma-separated tags; only campaigns with all of them are listed
+    pub tags: Option<String>,
+    /// Only campaigns in this status: `draft`, `scheduled`, `active`, `paused` or `completed`
+    pub status: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns",
+    tag = "campaigns",
+    params(CampaignListQuery),
+    responses(
+        (status = 200, description = "Campaigns in the workspace, newest first", body = [Campaign]),
+        (status = 400, description = "Unknown campaign status", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_campaigns(
     pool: web::Data<PgPool>,
+    query: web::Query<CampaignListQuery>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
-    let campaigns = sqlx::query_as::<_, Campaign>(
-        "SELECT * FROM campaigns WHERE workspace_id = $1 ORDER BY created_at DESC"
-    )
-    .bind(workspace_id)
-    .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let status = match query.status.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(status) => Some(
+            CampaignStatus::parse(status)
+                .ok_or_else(|| ApiError::Validation(format!("Unknown campaign status: {}", status)))?,
+        ),
+        None => None,
+    };
+    let filter = CampaignFilter {
+        // A tag that can't exist matches nothing rather than being ignored
+        tags: query
+            .tags
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| lead_tags::normalize_tag(t).unwrap_or_default())
+            .collect(),
+        status,
+    };
+
+    let campaigns = campaign_tags::list_campaigns(pool.get_ref(), workspace_id, &filter).await?;
 
     Ok(HttpResponse::Ok().json(campaigns))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, body = Campaign),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_campaign_by_id(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
     let campaign = sqlx::query_as::<_, Campaign>(
-        "SELECT * FROM campaigns WHERE id = $1 AND workspace_id = $2"
+        "SELECT * FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     match campaign {
         Some(c) => Ok(HttpResponse::Ok().json(c)),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Campaign not found"}))),
+        None => Err(ApiError::NotFound("Campaign not found".to_string())),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/campaigns",
+    tag = "campaigns",
+    request_body = CreateCampaignRequest,
+    responses(
+        (status = 201, description = "Campaign created as a draft"),
+        (status = 400, description = "Invalid reply_to address", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn create_campaign(
     pool: web::Data<PgPool>,
     body: web::Json<CreateCampaignRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
+
+    if !is_valid_reply_to(body.reply_to.as_deref()) {
+        return Err(ApiError::Validation("Invalid reply_to address".to_string()));
+    }
+
+    if body.recipient_timezone_field.as_deref().is_some_and(|f| f.trim().len() > 100) {
+        return Err(ApiError::Validation("recipient_timezone_field is too long".to_string()));
+    }
+
     let campaign_id = Uuid::new_v4();
     let now = Utc::now();
     
     sqlx::query(
         r#"
-        INSERT INTO campaigns (id, name, vertical, status, total_leads, sent, opened, clicked, replied, created_at, workspace_id)
-        VALUES ($1, $2, $3, $4, 0, 0, 0, 0, 0, $5, $6)
+        INSERT INTO campaigns (id, name, vertical, status, total_leads, sent, opened, clicked, replied, created_at, workspace_id, from_name, reply_to, recipient_timezone_field)
+        VALUES ($1, $2, $3, $4, 0, 0, 0, 0, 0, $5, $6, NULLIF(TRIM($7), ''), NULLIF(TRIM($8), ''), NULLIF(TRIM($9), ''))
         "#
     )
     .bind(campaign_id)
@@ -84,9 +207,11 @@ async fn create_campaign(
     .bind(CampaignStatus::Draft.as_str())
     .bind(now)
     .bind(workspace_id)
+    .bind(&body.from_name)
+    .bind(&body.reply_to)
+    .bind(&body.recipient_timezone_field)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // If lead_ids provided, add them to campaign
     if let Some(lead_ids) = &body.lead_ids {
@@ -119,19 +244,55 @@ async fn create_campaign(
         "name": body.name,
         "vertical": body.vertical,
         "status": "draft",
+        "from_name": body.from_name,
+        "reply_to": body.reply_to,
         "created_at": now
     })))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/campaigns/{id}",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = UpdateCampaignRequest,
+    responses(
+        (status = 200, description = "Campaign updated"),
+        (status = 400, description = "No fields to update, invalid reply_to, or a threshold outside 0-1", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn update_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     body: web::Json<UpdateCampaignRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
+
+    if !is_valid_reply_to(body.reply_to.as_deref()) {
+        return Err(ApiError::Validation("Invalid reply_to address".to_string()));
+    }
+
+    if body.recipient_timezone_field.as_deref().is_some_and(|f| f.trim().len() > 100) {
+        return Err(ApiError::Validation("recipient_timezone_field is too long".to_string()));
+    }
+
+    let thresholds = [
+        ("spam_rate_threshold", body.spam_rate_threshold),
+        ("reply_drop_threshold", body.reply_drop_threshold),
+        ("bounce_rate_threshold", body.bounce_rate_threshold),
+    ];
+    for (column, value) in thresholds {
+        if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+            return Err(ApiError::Validation(format!("{} must be between 0 and 1", column)));
+        }
+    }
     
     let mut updates = Vec::new();
     let mut params: Vec<String> = Vec::new();
@@ -145,13 +306,37 @@ async fn update_campaign(
         updates.push(format!("status = ${}", params.len() + 1));
         params.push(status.clone());
     }
+
+    // Empty strings clear the override so the sender falls back to defaults
+    if let Some(from_name) = &body.from_name {
+        updates.push(format!("from_name = NULLIF(TRIM(${}), '')", params.len() + 1));
+        params.push(from_name.clone());
+    }
+
+    if let Some(reply_to) = &body.reply_to {
+        updates.push(format!("reply_to = NULLIF(TRIM(${}), '')", params.len() + 1));
+        params.push(reply_to.clone());
+    }
+
+    if let Some(field) = &body.recipient_timezone_field {
+        updates.push(format!("recipient_timezone_field = NULLIF(TRIM(${}), '')", params.len() + 1));
+        params.push(field.clone());
+    }
+
+    // 0 clears the override, so the campaign goes back to the workspace threshold
+    for (column, value) in thresholds {
+        if let Some(value) = value {
+            updates.push(format!("{} = NULLIF(${}::float8, 0)", column, params.len() + 1));
+            params.push(value.to_string());
+        }
+    }
     
     if updates.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": "No fields to update"})));
+        return Err(ApiError::Validation("No fields to update".to_string()));
     }
     
     let query = format!(
-        "UPDATE campaigns SET {} WHERE id = ${} AND workspace_id = ${}",
+        "UPDATE campaigns SET {} WHERE id = ${} AND workspace_id = ${} AND deleted_at IS NULL",
         updates.join(", "),
         params.len() + 1,
         params.len() + 2
@@ -165,165 +350,776 @@ async fn update_campaign(
     q = q.bind(workspace_id);
     
     let result = q.execute(pool.get_ref())
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"updated": true})))
     } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Campaign not found"})))
+        Err(ApiError::NotFound("Campaign not found".to_string()))
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/campaigns/{id}",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign moved to trash; active sends are paused"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn delete_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
-    let result = sqlx::query("DELETE FROM campaigns WHERE id = $1 AND workspace_id = $2")
-        .bind(campaign_id)
-        .bind(workspace_id)
-        .execute(pool.get_ref())
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    // Soft delete: sending stops, history stays, and the campaign can be restored.
+    // Permanent deletion is only available through the admin API.
+    let result = sqlx::query(
+        r#"
+        UPDATE campaigns
+        SET deleted_at = NOW(),
+            status = CASE WHEN status IN ('active', 'scheduled') THEN 'paused' ELSE status END,
+            scheduled_start_at = NULL
+        WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL
+        "#
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .execute(pool.get_ref())
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true})))
     } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Campaign not found"})))
+        Err(ApiError::NotFound("Campaign not found".to_string()))
     }
 }
 
-async fn start_campaign(
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/restore",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign restored; previously running campaigns come back paused"),
+        (status = 404, description = "No deleted campaign with this ID", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn restore_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
-    let now = Utc::now();
-    
+
     let result = sqlx::query(
-        "UPDATE campaigns SET status = 'active', started_at = $1 WHERE id = $2 AND workspace_id = $3 AND status IN ('draft', 'paused')"
+        "UPDATE campaigns SET deleted_at = NULL WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NOT NULL"
     )
-    .bind(now)
     .bind(campaign_id)
     .bind(workspace_id)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     if result.rows_affected() > 0 {
-        Ok(HttpResponse::Ok().json(serde_json::json!({
-            "status": "active",
-            "started_at": now
-        })))
+        Ok(HttpResponse::Ok().json(serde_json::json!({"restored": true})))
     } else {
-        Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Campaign not found or cannot be started"
-        })))
+        Err(ApiError::NotFound("Deleted campaign not found".to_string()))
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/clone",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 201, description = "New draft with the campaign's settings, templates, steps and inboxes, but no leads or history", body = Campaign),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn clone_campaign(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    match campaign_clone::clone_campaign(pool.get_ref(), workspace_id, path.into_inner()).await? {
+        Some(campaign) => Ok(HttpResponse::Created().json(campaign)),
+        None => Err(ApiError::NotFound("Campaign not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/start",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = StartCampaignRequest,
+    responses(
+        (status = 200, description = "Campaign started, or scheduled when `scheduled_start_at` is set. Starting an active campaign is a no-op that returns its current state"),
+        (status = 400, description = "Campaign cannot be started, the schedule is in the past, or the workspace has no mailing address", body = ErrorResponse),
+        (status = 422, description = "A template scores above SPAM_SCORE_BLOCK_THRESHOLD", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn start_campaign(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<StartCampaignRequest>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+    let scheduled_at = body.and_then(|b| b.into_inner().scheduled_start_at);
+
+    start_or_schedule(pool.get_ref(), workspace_id, campaign_id, scheduled_at).await
+}
+
+/// Starts the campaign now, or with `scheduled_at` queues it for the worker to
+/// start then. Shared by `/start` and `/schedule`.
+async fn start_or_schedule(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    campaign_id: Uuid,
+    scheduled_at: Option<DateTime<Utc>>,
+) -> Result<HttpResponse, ApiError> {
+    let now = Utc::now();
+
+    if scheduled_at.is_some_and(|at| at <= now) {
+        return Err(ApiError::Validation("scheduled_start_at must be in the future".to_string()));
+    }
+
+    let (status, started_at) = fetch_start_state(pool, campaign_id, workspace_id).await?;
+    let action = start_action(&status, scheduled_at.is_some());
+
+    if matches!(action, StartAction::Schedule | StartAction::Activate) {
+        ensure_templates_pass_spam_check(pool, campaign_id).await?;
+        ensure_mailing_address(pool, workspace_id).await?;
+    }
+
+    match action {
+        StartAction::AlreadyActive => Ok(already_active(started_at)),
+        StartAction::NotStartable => Err(ApiError::Validation(format!("Campaign cannot be started while {}", status))),
+        StartAction::Schedule => {
+            let result = sqlx::query(
+                "UPDATE campaigns SET status = 'scheduled', scheduled_start_at = $1 WHERE id = $2 AND workspace_id = $3 AND deleted_at IS NULL AND status IN ('draft', 'paused', 'scheduled')"
+            )
+            .bind(scheduled_at)
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .execute(pool)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                Ok(HttpResponse::Ok().json(serde_json::json!({
+                    "status": "scheduled",
+                    "scheduled_start_at": scheduled_at
+                })))
+            } else {
+                Err(ApiError::Validation("Campaign cannot be scheduled".to_string()))
+            }
+        }
+        StartAction::Activate => {
+            if activate_campaign(pool, workspace_id, campaign_id, now).await? {
+                return Ok(HttpResponse::Ok().json(serde_json::json!({
+                    "status": "active",
+                    "started_at": now
+                })));
+            }
+
+            // A concurrent start got there first
+            match fetch_start_state(pool, campaign_id, workspace_id).await? {
+                (CampaignStatus::Active, started_at) => Ok(already_active(started_at)),
+                (status, _) => Err(ApiError::Validation(format!("Campaign cannot be started while {}", status))),
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/schedule",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = ScheduleCampaignRequest,
+    responses(
+        (status = 200, description = "Campaign scheduled; the worker starts it once the time passes. A scheduled campaign is moved to the new time"),
+        (status = 400, description = "Campaign cannot be scheduled, the time is in the past, or the workspace has no mailing address", body = ErrorResponse),
+        (status = 422, description = "A template scores above SPAM_SCORE_BLOCK_THRESHOLD", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn schedule_campaign(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<ScheduleCampaignRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    start_or_schedule(pool.get_ref(), workspace_id, path.into_inner(), Some(body.scheduled_start_at)).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/campaigns/{id}/schedule",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Schedule cancelled; the campaign is back in draft, or paused if it had run before"),
+        (status = 400, description = "Campaign isn't scheduled", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_campaign_schedule(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    // A campaign that already sent (scheduled again after a pause) can't go back to draft
+    let status: Option<String> = sqlx::query_scalar(
+        r#"
+        UPDATE campaigns
+        SET status = CASE WHEN started_at IS NULL THEN 'draft' ELSE 'paused' END,
+            scheduled_start_at = NULL
+        WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL AND status = 'scheduled'
+        RETURNING status
+        "#
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+
+    match status {
+        Some(status) => Ok(HttpResponse::Ok().json(serde_json::json!({"status": status}))),
+        None => {
+            let (status, _) = fetch_start_state(pool.get_ref(), campaign_id, workspace_id).await?;
+            Err(ApiError::Validation(format!("Campaign isn't scheduled; it is {}", status)))
+        }
+    }
+}
+
+/// Refuses to start a campaign before the workspace has the postal address its
+/// email footers need; the sender would only hold every send
+async fn ensure_mailing_address(pool: &PgPool, workspace_id: Uuid) -> Result<(), ApiError> {
+    if mailing_address::footer_address(pool, Some(workspace_id)).await?.is_none() {
+        return Err(ApiError::Validation(
+            "Set the workspace's mailing address (PUT /api/compliance/mailing-address) before starting a campaign".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Refuses to start a campaign whose templates score above `SPAM_SCORE_BLOCK_THRESHOLD`
+/// (0-100) on the spam check. Nothing is blocked when the variable isn't set.
+async fn ensure_templates_pass_spam_check(pool: &PgPool, campaign_id: Uuid) -> Result<(), ApiError> {
+    let Some(threshold) = std::env::var("SPAM_SCORE_BLOCK_THRESHOLD").ok().and_then(|v| v.trim().parse::<u32>().ok()) else {
+        return Ok(());
+    };
+
+    let templates = sqlx::query_as::<_, (i32, String, String, Option<String>)>(
+        "SELECT step_order, subject, body_html, body_text FROM campaign_templates WHERE campaign_id = $1 ORDER BY step_order"
+    )
+    .bind(campaign_id)
+    .fetch_all(pool)
+    .await?;
+
+    for (step_order, subject, body_html, body_text) in templates {
+        let result = spam_check(&subject, &body_html, body_text.as_deref());
+        if result.score > threshold {
+            let rules: Vec<&str> = result.findings.iter().map(|f| f.rule.as_str()).collect();
+            return Err(ApiError::Unprocessable(format!(
+                "Template {} scores {} for spam risk, above the limit of {} ({}); check it with /api/compliance/spam-check",
+                step_order,
+                result.score,
+                threshold,
+                rules.join(", ")
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_start_state(
+    pool: &PgPool,
+    campaign_id: Uuid,
+    workspace_id: Uuid,
+) -> Result<(CampaignStatus, Option<DateTime<Utc>>), ApiError> {
+    let (status, started_at) = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+        "SELECT status, started_at FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Campaign not found".to_string()))?;
+
+    let status = CampaignStatus::parse(&status)
+        .ok_or_else(|| ApiError::internal(format!("Unknown campaign status: {}", status)))?;
+    Ok((status, started_at))
+}
+
+fn already_active(started_at: Option<DateTime<Utc>>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "status": "active",
+        "started_at": started_at,
+        "already_active": true
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/pause",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign paused"),
+        (status = 400, description = "Campaign is not active or scheduled", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn pause_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
     let result = sqlx::query(
-        "UPDATE campaigns SET status = 'paused' WHERE id = $1 AND workspace_id = $2 AND status = 'active'"
+        "UPDATE campaigns SET status = 'paused', scheduled_start_at = NULL WHERE id = $1 AND workspace_id = $2 AND status IN ('active', 'scheduled')"
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"status": "paused"})))
     } else {
-        Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Campaign not found or not active"
-        })))
+        Err(ApiError::Validation("Campaign not found or not active or scheduled".to_string()))
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/leads",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, body = [Lead]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_campaign_leads(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
-    let leads = sqlx::query_as::<_, crate::models::lead::Lead>(
+    let leads = sqlx::query_as::<_, Lead>(
         r#"
         SELECT l.* FROM leads l
         INNER JOIN campaign_leads cl ON l.id = cl.lead_id
         INNER JOIN campaigns c ON c.id = cl.campaign_id
         WHERE cl.campaign_id = $1 AND c.workspace_id = $2
+          AND c.deleted_at IS NULL AND l.deleted_at IS NULL
         "#
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(leads))
 }
 
-#[derive(serde::Deserialize)]
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/export",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID"), ExportQuery),
+    responses(
+        (status = 200, description = "Per-lead status, send, open and reply times as CSV or XLSX"),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn export_campaign_results(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<ExportQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    if !exists {
+        return Err(ApiError::NotFound("Campaign not found".to_string()));
+    }
+
+    export::respond::<CampaignResultRow>(
+        pool.get_ref().clone(),
+        (workspace_id, campaign_id),
+        query.format,
+        &format!("campaign-{}-results", campaign_id),
+        "Campaign results",
+    )
+    .await
+    .map_err(ApiError::internal)
+}
+
+async fn campaign_exists(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await
+}
+
+async fn fetch_campaign_inboxes(pool: &PgPool, campaign_id: Uuid) -> Result<Vec<CampaignInbox>, sqlx::Error> {
+    sqlx::query_as::<_, CampaignInbox>(
+        r#"
+        SELECT
+            ea.id as email_account_id,
+            ea.email,
+            COALESCE(ea.warmup_status, 'pending') as warmup_status,
+            COALESCE(ea.daily_limit, 0) as daily_limit,
+            COALESCE(ea.sent_today, 0) as sent_today,
+            CASE
+                WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 'danger'
+                WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 OR ea.reply_rate < 0.02 THEN 'warning'
+                ELSE 'healthy'
+            END as health_status,
+            cea.created_at as assigned_at
+        FROM campaign_email_accounts cea
+        JOIN email_accounts ea ON ea.id = cea.email_account_id
+        WHERE cea.campaign_id = $1
+        ORDER BY ea.email
+        "#
+    )
+    .bind(campaign_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/inboxes",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Inboxes assigned to the campaign; empty means it sends from every inbox", body = [CampaignInbox]),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_campaign_inboxes(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    if !campaign_exists(pool.get_ref(), workspace_id, campaign_id).await? {
+        return Err(ApiError::NotFound("Campaign not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(fetch_campaign_inboxes(pool.get_ref(), campaign_id).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/campaigns/{id}/inboxes",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = AssignInboxesRequest,
+    responses(
+        (status = 200, description = "The campaign's inboxes after the change", body = [CampaignInbox]),
+        (status = 400, description = "An inbox isn't in this workspace", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn set_campaign_inboxes(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<AssignInboxesRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+    let mut account_ids = body.into_inner().email_account_ids;
+    account_ids.sort();
+    account_ids.dedup();
+
+    if !campaign_exists(pool.get_ref(), workspace_id, campaign_id).await? {
+        return Err(ApiError::NotFound("Campaign not found".to_string()));
+    }
+
+    let owned: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM email_accounts WHERE workspace_id = $1 AND id = ANY($2)"
+    )
+    .bind(workspace_id)
+    .bind(&account_ids)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    if owned != account_ids.len() as i64 {
+        return Err(ApiError::Validation("One or more email accounts were not found in this workspace".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM campaign_email_accounts WHERE campaign_id = $1")
+        .bind(campaign_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_email_accounts (campaign_id, email_account_id, workspace_id)
+        SELECT $1, UNNEST($2::uuid[]), $3
+        "#
+    )
+    .bind(campaign_id)
+    .bind(&account_ids)
+    .bind(workspace_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(fetch_campaign_inboxes(pool.get_ref(), campaign_id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/tags",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "The campaign's tags, alphabetically", body = [String]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_campaign_tags(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let tags = campaign_tags::list_tags(pool.get_ref(), workspace_id, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/tags",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = CampaignTagsRequest,
+    responses(
+        (status = 200, description = "Tags added; returns all of the campaign's tags", body = [String]),
+        (status = 400, description = "Invalid tag", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn add_campaign_tags(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<CampaignTagsRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let tags = lead_tags::normalize_request_tags(&body.tags).map_err(ApiError::Validation)?;
+
+    match campaign_tags::add_tags(pool.get_ref(), workspace_id, path.into_inner(), &tags).await? {
+        Some(tags) => Ok(HttpResponse::Ok().json(tags)),
+        None => Err(ApiError::NotFound("Campaign not found".to_string())),
+    }
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+pub struct RemoveCampaignTagQuery {
+    /// Tag to remove
+    pub tag: String,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/campaigns/{id}/tags",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID"), RemoveCampaignTagQuery),
+    responses(
+        (status = 200, description = "Tag removed"),
+        (status = 404, description = "Campaign doesn't have this tag", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn remove_campaign_tag(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<RemoveCampaignTagQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let removed = match lead_tags::normalize_tag(&query.tag) {
+        Some(tag) => campaign_tags::remove_tag(pool.get_ref(), workspace_id, path.into_inner(), &tag).await?,
+        None => false,
+    };
+
+    if removed {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"removed": true})))
+    } else {
+        Err(ApiError::NotFound("Tag not found on this campaign".to_string()))
+    }
+}
+
+#[derive(serde::Deserialize, ToSchema)]
 pub struct AddLeadsRequest {
     pub lead_ids: Vec<Uuid>,
 }
 
+#[derive(serde::Deserialize, IntoParams)]
+pub struct AddLeadsQuery {
+    /// Also add every lead in the workspace with this tag
+    pub by_tag: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/leads",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID"), AddLeadsQuery),
+    request_body(content = AddLeadsRequest, description = "Optional when `by_tag` is given"),
+    responses(
+        (status = 200, description = "Number of leads added"),
+        (status = 400, description = "Neither lead IDs nor a tag given, or the tag is invalid", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn add_leads_to_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
-    body: web::Json<AddLeadsRequest>,
+    query: web::Query<AddLeadsQuery>,
+    body: Option<web::Json<AddLeadsRequest>>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
+    let lead_ids = body.map(|b| b.into_inner().lead_ids).unwrap_or_default();
+
+    let tag = match query.by_tag.as_deref() {
+        Some(tag) => Some(
+            lead_tags::normalize_tag(tag).ok_or_else(|| ApiError::Validation(format!("Invalid tag '{}'", tag)))?
+        ),
+        None => None,
+    };
+
+    if lead_ids.is_empty() && tag.is_none() {
+        return Err(ApiError::Validation("Provide lead_ids or a by_tag filter".to_string()));
+    }
     
     // Verify campaign belongs to workspace
     let campaign_exists = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM campaigns WHERE id = $1 AND workspace_id = $2"
+        "SELECT COUNT(*) FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
     
     if campaign_exists == 0 {
-        return Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Campaign not found"
-        })));
+        return Err(ApiError::NotFound("Campaign not found".to_string()));
     }
     
     let mut added = 0;
     
     // Only add leads that belong to the same workspace
-    for lead_id in &body.lead_ids {
+    for lead_id in &lead_ids {
         let result = sqlx::query(
             r#"
             INSERT INTO campaign_leads (id, campaign_id, lead_id, status)
             SELECT $1, $2, l.id, 'pending'
             FROM leads l
-            WHERE l.id = $3 AND l.workspace_id = $4
+            WHERE l.id = $3 AND l.workspace_id = $4 AND l.deleted_at IS NULL
             ON CONFLICT (campaign_id, lead_id) DO NOTHING
             "#
         )
@@ -338,6 +1134,25 @@ async fn add_leads_to_campaign(
             added += res.rows_affected();
         }
     }
+
+    if let Some(tag) = &tag {
+        added += sqlx::query(
+            r#"
+            INSERT INTO campaign_leads (id, campaign_id, lead_id, status)
+            SELECT gen_random_uuid(), $1, l.id, 'pending'
+            FROM leads l
+            JOIN lead_tags t ON t.lead_id = l.id AND t.workspace_id = $2 AND t.tag = $3
+            WHERE l.workspace_id = $2 AND l.deleted_at IS NULL
+            ON CONFLICT (campaign_id, lead_id) DO NOTHING
+            "#
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .bind(tag)
+        .execute(pool.get_ref())
+        .await?
+        .rows_affected();
+    }
     
     // Update total_leads count
     let _ = sqlx::query(
@@ -352,3 +1167,347 @@ async fn add_leads_to_campaign(
         "campaign_id": campaign_id
     })))
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/leads/{lead_id}/resend",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("lead_id" = Uuid, Path, description = "Lead ID"),
+    ),
+    responses(
+        (status = 200, description = "Lead reset to pending; `queued` says whether a send job was enqueued immediately"),
+        (status = 400, description = "Lead is unsubscribed or suppressed", body = ErrorResponse),
+        (status = 404, description = "Lead not found in this campaign", body = ErrorResponse),
+        (status = 409, description = "Lead's last send didn't fail", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn resend_to_lead(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, lead_id) = path.into_inner();
+
+    let scheduler = CampaignScheduler::new(Arc::new(pool.get_ref().clone()));
+    let outcome = scheduler
+        .resend_to_lead(workspace_id, campaign_id, lead_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    match outcome {
+        ResendOutcome::Queued(job_id) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "status": "scheduled",
+            "queued": true,
+            "job_id": job_id
+        }))),
+        ResendOutcome::Pending => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "status": "pending",
+            "queued": false
+        }))),
+        ResendOutcome::NotFound => Err(ApiError::NotFound("Lead not found in this campaign".to_string())),
+        ResendOutcome::Suppressed => Err(ApiError::Validation(
+            "Lead is unsubscribed or on a suppression list".to_string(),
+        )),
+        ResendOutcome::NotResendable(status) => Err(ApiError::Conflict(format!(
+            "Only bounced or failed sends can be resent (lead is '{}')",
+            status
+        ))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/sent/{lead_id}",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("lead_id" = Uuid, Path, description = "Lead ID"),
+    ),
+    responses(
+        (status = 200, description = "Archived emails sent to the lead in this campaign, oldest first", body = [SentEmail]),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_sent_emails(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, lead_id) = path.into_inner();
+
+    // Archived copies stay readable for soft-deleted campaigns so disputes can be answered
+    let campaign_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM campaigns WHERE id = $1 AND workspace_id = $2"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    if campaign_exists == 0 {
+        return Err(ApiError::NotFound("Campaign not found".to_string()));
+    }
+
+    let sent = sqlx::query_as::<_, SentEmail>(
+        r#"
+        SELECT id, campaign_id, campaign_lead_id, lead_id, email_account_id, from_email, to_email,
+               subject, body_html, body_text, message_id, smtp_response, sent_at, step_index
+        FROM sent_emails
+        WHERE campaign_id = $1 AND lead_id = $2
+        ORDER BY sent_at ASC
+        "#
+    )
+    .bind(campaign_id)
+    .bind(lead_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(sent))
+}
+
+/// Lets the preview and test-send handlers use `?` on the sender
+impl From<PreviewError> for ApiError {
+    fn from(e: PreviewError) -> Self {
+        match e {
+            PreviewError::NotFound(what) => ApiError::NotFound(what.to_string()),
+            PreviewError::Failed(e) => ApiError::internal(e),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/preview",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = PreviewCampaignRequest,
+    responses(
+        (status = 200, description = "Subject and bodies as the lead would receive them", body = EmailTemplate),
+        (status = 404, description = "Campaign or lead not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn preview_campaign(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<PreviewCampaignRequest>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+
+    let sender = CampaignEmailSender::new(Arc::new(pool.get_ref().clone()));
+    let preview = sender.preview(workspace_id, path.into_inner(), body.lead_id).await?;
+
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/send-test",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = SendTestEmailRequest,
+    responses(
+        (status = 200, description = "Test email sent to the requesting user. Send limits and campaign stats are not affected"),
+        (status = 404, description = "Campaign, lead or inbox not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn send_test_email(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<SendTestEmailRequest>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let user_id = get_user_id(&claims)?;
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+
+    let to_email = sqlx::query_scalar::<_, String>("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(pool.get_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    let sender = CampaignEmailSender::new(Arc::new(pool.get_ref().clone()));
+    let from = sender
+        .send_test(workspace_id, path.into_inner(), body.lead_id, body.inbox_id, &to_email)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "sent": true,
+        "to": to_email,
+        "from": from
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use actix_web::{http::StatusCode, test, App};
+    use crate::middleware::auth::{AuthMiddleware, Claims};
+    use crate::services::jwt_keys::jwt_keys;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[actix_web::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_viewer_cannot_delete_a_campaign_but_owner_can() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Delete roles").await;
+        let campaign_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO campaigns (id, workspace_id, name, vertical, status) VALUES ($1, $2, 'Doomed', 'saas', 'draft')")
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut tokens = Vec::new();
+        let mut users = Vec::new();
+        for role in [WorkspaceRole::Viewer, WorkspaceRole::Owner] {
+            let (user_id, _) = test_db::user(&pool, "hash").await;
+            sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, $3)")
+                .bind(workspace_id)
+                .bind(user_id)
+                .bind(role.as_str())
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let now = Utc::now().timestamp() as usize;
+            let claims = Claims {
+                sub: user_id.to_string(),
+                user_id: user_id.to_string(),
+                workspace_id: Some(workspace_id.to_string()),
+                // The account-wide role is the same for both; only the workspace role differs
+                role: "user".to_string(),
+                exp: now + 3600,
+                iat: now,
+            };
+            tokens.push(jwt_keys().encode(&claims).unwrap());
+            users.push(user_id);
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(AuthMiddleware)
+                .service(web::scope("/api").configure(configure)),
+        )
+        .await;
+        let delete_as = |token: &str| {
+            test::TestRequest::delete()
+                .uri(&format!("/api/campaigns/{}", campaign_id))
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        let viewer = test::call_service(&app, delete_as(&tokens[0])).await.status();
+        let after_viewer: bool = sqlx::query_scalar("SELECT deleted_at IS NULL FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let owner = test::call_service(&app, delete_as(&tokens[1])).await.status();
+        let after_owner: bool = sqlx::query_scalar("SELECT deleted_at IS NULL FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        for user_id in users {
+            test_db::delete_user(&pool, user_id).await;
+        }
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(viewer, StatusCode::FORBIDDEN);
+        assert!(after_viewer);
+        assert_eq!(owner, StatusCode::OK);
+        assert!(!after_owner);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_two_starts_leave_one_active_campaign_and_one_send_per_lead() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Double start").await;
+        let address = mailing_address::CompanyAddress {
+            address_line1: Some("1 Main St".to_string()),
+            city: Some("Springfield".to_string()),
+            country: Some("US".to_string()),
+            ..Default::default()
+        };
+        mailing_address::save(&pool, workspace_id, &address).await.unwrap();
+
+        let campaign_id = test_db::campaign(&pool, workspace_id, "draft").await;
+        test_db::inbox(&pool, workspace_id, 50).await;
+
+        let mut campaign_leads = Vec::new();
+        for i in 0..3 {
+            let email = format!("lead{}-{}@double-start.test", i, Uuid::new_v4());
+            let (_, campaign_lead_id) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+            campaign_leads.push(campaign_lead_id);
+        }
+
+        // Two POST /start calls, each followed by the worker's scheduling pass
+        let scheduler = CampaignScheduler::new(Arc::new(pool.clone()));
+        let mut responses = Vec::new();
+        let mut scheduled = Vec::new();
+        for _ in 0..2 {
+            let response = start_or_schedule(&pool, workspace_id, campaign_id, None).await.unwrap();
+            let status = response.status();
+            let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+            responses.push((status, serde_json::from_slice::<serde_json::Value>(&body).unwrap()));
+            scheduled.push(scheduler.schedule_campaign_sends(campaign_id).await.unwrap());
+        }
+        // Even an activation that slips past the status check can't start it again
+        let raced = activate_campaign(&pool, workspace_id, campaign_id, Utc::now()).await.unwrap();
+
+        let active: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM campaigns WHERE workspace_id = $1 AND status = 'active'")
+            .bind(workspace_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let mut jobs_per_lead = Vec::new();
+        for campaign_lead_id in &campaign_leads {
+            let jobs: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM jobs WHERE job_type = '\"SendEmail\"' AND payload->>'campaign_lead_id' = $1::text"
+            )
+            .bind(campaign_lead_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            jobs_per_lead.push(jobs);
+        }
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert!(responses.iter().all(|(status, _)| *status == StatusCode::OK));
+        assert_eq!(responses[0].1["status"], "active");
+        assert!(responses[0].1.get("already_active").is_none());
+        assert_eq!(responses[1].1["already_active"], true);
+        assert!(!raced);
+        assert_eq!(scheduled, vec![3, 0]);
+        assert_eq!(active, 1);
+        assert_eq!(jobs_per_lead, vec![1, 1, 1]);
+    }
+}
@@ -1,14 +1,35 @@
-use actix_web::{web, HttpResponse, HttpRequest};
+use actix_web::{http::StatusCode, web, HttpResponse, HttpRequest};
 use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::Utc;
-use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, get_user_id, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::compliance::SuppressionReason;
+use crate::models::pagination::{PageQuery, Paginated};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::email_sender::html_escape;
+use crate::services::export::{self, ExportFormat, SuppressionExportRow};
+use crate::services::mailing_address::{self, CompanyAddress};
+use crate::services::suppression::{self, SkippedImportLine};
+use crate::services::unsubscribe_token::{self, UnsubscribeRecipient};
+use crate::services::{data_subject, deliverability};
 
 #[derive(Debug, Deserialize)]
 pub struct UnsubscribeRequest {
     pub token: String,
+    /// `campaign` opts out of just the campaign named in the token; anything
+    /// else (or nothing) unsubscribes from the whole workspace
+    pub scope: Option<String>,
+}
+
+/// One-click unsubscribes (RFC 8058) POST `List-Unsubscribe=One-Click` to the URL
+/// from the header, so the token arrives in the query string; forms and API
+/// clients may send it in the body instead.
+#[derive(Debug, Default, Deserialize)]
+pub struct UnsubscribeParams {
+    pub token: Option<String>,
+    pub scope: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,114 +44,426 @@ pub struct AddSuppressionRequest {
     pub reason: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SuppressionListQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub q: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportSuppressionQuery {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportSuppressionResponse {
+    pub added: i64,
+    pub updated: i64,
+    pub skipped: Vec<SkippedImportLine>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DataSubjectRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpamCheckRequest {
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: Option<String>,
+}
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/compliance")
             .route("/unsubscribe", web::get().to(handle_unsubscribe))
             .route("/unsubscribe", web::post().to(handle_unsubscribe_post))
+            .route("/resubscribe", web::post().to(resubscribe))
             .route("/suppression", web::get().to(get_suppression_list))
             .route("/suppression", web::post().to(add_to_suppression))
+            .service(
+                // Imports can be large exports from previous tools, so allow up to 10MB
+                web::resource("/suppression/import")
+                    .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
+                    .route(web::post().to(import_suppression))
+            )
+            .route("/suppression/export", web::get().to(export_suppression))
             .route("/suppression/{email}", web::delete().to(remove_from_suppression))
+            .route("/spam-check", web::post().to(spam_check))
+            .route("/data-export", web::get().to(export_subject_data))
+            .route("/erase", web::post().to(erase_subject_data))
+            .route("/mailing-address", web::get().to(get_mailing_address))
+            .route("/mailing-address", web::put().to(update_mailing_address))
     );
 }
 
+/// What an unsubscribe applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnsubscribeScope {
+    /// Everything from the workspace, via the suppression list
+    Workspace,
+    /// Only the remaining sends of one campaign
+    Campaign(Uuid),
+}
+
+impl UnsubscribeScope {
+    /// Workspace-wide unless the campaign scope was explicitly asked for and the
+    /// token names a campaign, so an ambiguous request never under-suppresses.
+    fn resolve(requested: Option<&str>, token: &UnsubscribeRecipient) -> Self {
+        match (requested, token.campaign_id) {
+            (Some("campaign"), Some(campaign_id)) => Self::Campaign(campaign_id),
+            _ => Self::Workspace,
+        }
+    }
+}
+
+/// The recipient an unsubscribe token names. Every unsubscribe and resubscribe
+/// path goes through here so token validation lives in one place.
+async fn resolve_unsubscribe_token(pool: &PgPool, token: &str) -> Result<UnsubscribeRecipient, (StatusCode, String)> {
+    let claims = unsubscribe_token::decode(token, Utc::now(), unsubscribe_token::legacy_accepted_until())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    unsubscribe_token::recipient(pool, claims)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to look up unsubscribe token: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to process unsubscribe request.".to_string())
+        })?
+        .ok_or((StatusCode::BAD_REQUEST, "This unsubscribe link is no longer valid".to_string()))
+}
+
+/// Browsers following the link in an email get the HTML page; API clients get JSON
+fn wants_html(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"))
+}
+
 // Public endpoint - no auth required (for email unsubscribe links)
 async fn handle_unsubscribe(
     pool: web::Data<PgPool>,
     query: web::Query<UnsubscribeRequest>,
+    req: HttpRequest,
 ) -> HttpResponse {
-    process_unsubscribe(&pool, &query.token).await
+    let result = process_unsubscribe(&pool, &query.token, query.scope.as_deref()).await;
+
+    if wants_html(&req) {
+        return match result {
+            Ok((email, scope)) => {
+                let message = match scope {
+                    UnsubscribeScope::Workspace => "won't receive any more emails from this sender",
+                    UnsubscribeScope::Campaign(_) => "won't receive any more emails from this campaign",
+                };
+                unsubscribe_page(
+                    StatusCode::OK,
+                    "You've been unsubscribed",
+                    &format!("<p><strong>{}</strong> {}.</p>", html_escape(&email), message),
+                    Some(&query),
+                )
+            }
+            Err((status, message)) => unsubscribe_page(status, "Unsubscribe failed", &format!("<p>{}</p>", html_escape(&message)), None),
+        };
+    }
+
+    unsubscribe_json(result, "You have been successfully unsubscribed.")
 }
 
-// Public endpoint - no auth required (for one-click unsubscribe)
-async fn handle_unsubscribe_post(
+// Public endpoint - no auth required (for one-click unsubscribe). Also routed
+// at POST /unsubscribe, the URL in the List-Unsubscribe header.
+pub async fn handle_unsubscribe_post(
     pool: web::Data<PgPool>,
-    query: web::Query<UnsubscribeRequest>,
+    query: web::Query<UnsubscribeParams>,
+    form: Option<web::Form<UnsubscribeParams>>,
 ) -> HttpResponse {
-    process_unsubscribe(&pool, &query.token).await
-}
-
-async fn process_unsubscribe(pool: &PgPool, token: &str) -> HttpResponse {
-    // Decode token: format is "lead_id:email:workspace_id"
-    let decoded = match URL_SAFE_NO_PAD.decode(token) {
-        Ok(bytes) => match String::from_utf8(bytes) {
-            Ok(s) => s,
-            Err(_) => return HttpResponse::BadRequest().json(UnsubscribeResponse {
-                success: false,
-                message: "Invalid token format".to_string(),
-            }),
-        },
-        Err(_) => return HttpResponse::BadRequest().json(UnsubscribeResponse {
-            success: false,
-            message: "Invalid token".to_string(),
-        }),
+    let form = form.map(web::Form::into_inner).unwrap_or_default();
+    let query = query.into_inner();
+
+    let Some(token) = form.token.or(query.token) else {
+        return unsubscribe_json::<()>(
+            Err((StatusCode::BAD_REQUEST, "Missing unsubscribe token".to_string())),
+            "",
+        );
     };
+    let scope = form.scope.or(query.scope);
 
-    let parts: Vec<&str> = decoded.split(':').collect();
-    if parts.len() < 2 {
-        return HttpResponse::BadRequest().json(UnsubscribeResponse {
+    unsubscribe_json(
+        process_unsubscribe(&pool, &token, scope.as_deref()).await,
+        "You have been successfully unsubscribed.",
+    )
+}
+
+/// GET /unsubscribe - the link in outgoing emails points here
+pub async fn unsubscribe_landing(
+    pool: web::Data<PgPool>,
+    query: web::Query<UnsubscribeRequest>,
+    req: HttpRequest,
+) -> HttpResponse {
+    handle_unsubscribe(pool, query, req).await
+}
+
+/// POST /unsubscribe/resubscribe - form on the confirmation page
+pub async fn resubscribe(
+    pool: web::Data<PgPool>,
+    form: web::Form<UnsubscribeRequest>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let result = process_resubscribe(&pool, &form.token, form.scope.as_deref()).await;
+
+    if wants_html(&req) {
+        return match result {
+            Ok((email, scope)) => {
+                let message = match scope {
+                    UnsubscribeScope::Workspace => "can receive emails from this sender again",
+                    UnsubscribeScope::Campaign(_) => "can receive emails from this campaign again",
+                };
+                unsubscribe_page(
+                    StatusCode::OK,
+                    "You're subscribed again",
+                    &format!("<p><strong>{}</strong> {}.</p>", html_escape(&email), message),
+                    None,
+                )
+            }
+            Err((status, message)) => unsubscribe_page(status, "Resubscribe failed", &format!("<p>{}</p>", html_escape(&message)), None),
+        };
+    }
+
+    unsubscribe_json(result, "You have been resubscribed.")
+}
+
+fn unsubscribe_json<T>(result: Result<T, (StatusCode, String)>, success_message: &str) -> HttpResponse {
+    match result {
+        Ok(_) => HttpResponse::Ok().json(UnsubscribeResponse {
+            success: true,
+            message: success_message.to_string(),
+        }),
+        Err((status, message)) => HttpResponse::build(status).json(UnsubscribeResponse {
             success: false,
-            message: "Invalid token structure".to_string(),
-        });
+            message,
+        }),
     }
+}
 
-    let email = parts[1];
-    let workspace_id: Option<Uuid> = parts.get(2)
-        .and_then(|s| Uuid::parse_str(s).ok());
+/// Opts the token's address out and returns it with the scope that was applied.
+/// Workspace scope adds a suppression entry; campaign scope only marks the lead
+/// unsubscribed in that campaign, which the scheduler and sender both respect.
+async fn process_unsubscribe(
+    pool: &PgPool,
+    token: &str,
+    requested_scope: Option<&str>,
+) -> Result<(String, UnsubscribeScope), (StatusCode, String)> {
+    let token = resolve_unsubscribe_token(pool, token).await?;
+    let scope = UnsubscribeScope::resolve(requested_scope, &token);
+    let email = token.email.as_str();
 
-    // Add to suppression list
-    let result = sqlx::query(
-        r#"
-        INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at)
-        VALUES ($1, $2, $3, 'unsubscribe', 'user_request', $4)
-        ON CONFLICT (workspace_id, email) DO UPDATE SET
-            reason = 'unsubscribe',
-            source = 'user_request',
-            created_at = $4
-        "#
-    )
-    .bind(Uuid::new_v4())
-    .bind(workspace_id)
-    .bind(email)
-    .bind(Utc::now())
-    .execute(pool)
-    .await;
+    let result = match scope {
+        UnsubscribeScope::Workspace => {
+            // Add to suppression list. An existing entry keeps its reason, so a
+            // resubscribe can't lift a bounce or complaint suppression.
+            let result = sqlx::query(
+                r#"
+                INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at)
+                VALUES ($1, $2, $3, 'unsubscribe', 'user_request', $4)
+                ON CONFLICT (workspace_id, email) DO NOTHING
+                "#
+            )
+            .bind(Uuid::new_v4())
+            .bind(token.workspace_id)
+            .bind(email)
+            .bind(Utc::now())
+            .execute(pool)
+            .await;
 
-    match result {
-        Ok(_) => {
-            // Also update any campaign_leads to mark as unsubscribed
-            let _ = sqlx::query(
+            if result.is_ok() {
+                // Also mark the workspace's campaign_leads, widening any earlier campaign-only opt-out
+                let _ = sqlx::query(
+                    r#"
+                    UPDATE campaign_leads cl
+                    SET unsubscribed_at = COALESCE(cl.unsubscribed_at, NOW()),
+                        status = 'unsubscribed',
+                        unsubscribe_scope = 'workspace'
+                    FROM leads l
+                    WHERE cl.lead_id = l.id AND l.email = $1
+                      AND l.workspace_id IS NOT DISTINCT FROM $2
+                    "#
+                )
+                .bind(email)
+                .bind(token.workspace_id)
+                .execute(pool)
+                .await;
+            }
+
+            result
+        }
+        UnsubscribeScope::Campaign(campaign_id) => {
+            sqlx::query(
                 r#"
                 UPDATE campaign_leads cl
-                SET unsubscribed_at = NOW(), status = 'unsubscribed'
+                SET unsubscribed_at = NOW(), status = 'unsubscribed', unsubscribe_scope = 'campaign'
                 FROM leads l
                 WHERE cl.lead_id = l.id AND l.email = $1
+                  AND cl.campaign_id = $2
+                  AND cl.unsubscribed_at IS NULL
                 "#
             )
             .bind(email)
+            .bind(campaign_id)
+            .execute(pool)
+            .await
+        }
+    };
+
+    match result {
+        Ok(_) => Ok((token.email, scope)),
+        Err(e) => {
+            tracing::error!("Failed to process unsubscribe: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process unsubscribe request.".to_string(),
+            ))
+        }
+    }
+}
+
+/// Undoes an unsubscribe made through the link. Workspace scope removes the
+/// suppression entry the recipient created and puts the lead back into the
+/// campaigns it took them out of; entries added for other reasons (bounces,
+/// complaints, manual) are left alone. Campaign scope puts the lead back into
+/// that campaign.
+async fn process_resubscribe(
+    pool: &PgPool,
+    token: &str,
+    requested_scope: Option<&str>,
+) -> Result<(String, UnsubscribeScope), (StatusCode, String)> {
+    let token = resolve_unsubscribe_token(pool, token).await?;
+    let scope = UnsubscribeScope::resolve(requested_scope, &token);
+
+    let result = match scope {
+        UnsubscribeScope::Workspace => {
+            let result = sqlx::query(
+                r#"
+                DELETE FROM suppression_list
+                WHERE workspace_id IS NOT DISTINCT FROM $1 AND email = $2
+                  AND reason = 'unsubscribe' AND source = 'user_request'
+                "#
+            )
+            .bind(token.workspace_id)
+            .bind(&token.email)
             .execute(pool)
             .await;
 
-            HttpResponse::Ok().json(UnsubscribeResponse {
-                success: true,
-                message: "You have been successfully unsubscribed.".to_string(),
-            })
+            if result.is_ok() {
+                // Put the lead back into the campaigns the workspace-wide opt-out took it out of
+                let _ = sqlx::query(
+                    r#"
+                    UPDATE campaign_leads cl
+                    SET unsubscribed_at = NULL,
+                        unsubscribe_scope = NULL,
+                        status = CASE WHEN cl.sent_at IS NULL THEN 'pending' ELSE 'sent' END
+                    FROM leads l
+                    WHERE cl.lead_id = l.id AND l.email = $1
+                      AND l.workspace_id IS NOT DISTINCT FROM $2
+                      AND cl.unsubscribe_scope = 'workspace'
+                    "#
+                )
+                .bind(&token.email)
+                .bind(token.workspace_id)
+                .execute(pool)
+                .await;
+            }
+
+            result
         }
+        UnsubscribeScope::Campaign(campaign_id) => {
+            sqlx::query(
+                r#"
+                UPDATE campaign_leads cl
+                SET unsubscribed_at = NULL,
+                    unsubscribe_scope = NULL,
+                    status = CASE WHEN cl.sent_at IS NULL THEN 'pending' ELSE 'sent' END
+                FROM leads l
+                WHERE cl.lead_id = l.id AND l.email = $1
+                  AND cl.campaign_id = $2
+                  AND cl.unsubscribe_scope = 'campaign'
+                "#
+            )
+            .bind(&token.email)
+            .bind(campaign_id)
+            .execute(pool)
+            .await
+        }
+    };
+
+    match result {
+        Ok(_) => Ok((token.email, scope)),
         Err(e) => {
-            eprintln!("Failed to process unsubscribe: {}", e);
-            HttpResponse::InternalServerError().json(UnsubscribeResponse {
-                success: false,
-                message: "Failed to process unsubscribe request.".to_string(),
-            })
+            tracing::error!("Failed to process resubscribe: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process resubscribe request.".to_string(),
+            ))
         }
     }
 }
 
+/// Minimal standalone page; `resubscribe` adds a button that undoes that unsubscribe.
+fn unsubscribe_page(status: StatusCode, title: &str, body_html: &str, resubscribe: Option<&UnsubscribeRequest>) -> HttpResponse {
+    let resubscribe_form = resubscribe
+        .map(|request| {
+            format!(
+                r#"<form method="post" action="/unsubscribe/resubscribe">
+            <input type="hidden" name="token" value="{}">
+            <input type="hidden" name="scope" value="{}">
+            <p>Unsubscribed by mistake?</p>
+            <button type="submit">Resubscribe</button>
+        </form>"#,
+                html_escape(&request.token),
+                html_escape(request.scope.as_deref().unwrap_or("workspace"))
+            )
+        })
+        .unwrap_or_default();
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{title}</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; background: #f6f7f9; }}
+        .container {{ max-width: 480px; margin: 80px auto; padding: 32px; background: #fff; border-radius: 8px; }}
+        button {{ padding: 8px 16px; border: 1px solid #ccc; border-radius: 4px; background: #fff; cursor: pointer; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>{title}</h1>
+        {body_html}
+        {resubscribe_form}
+    </div>
+</body>
+</html>
+"#,
+        title = html_escape(title),
+        body_html = body_html,
+        resubscribe_form = resubscribe_form,
+    );
+
+    HttpResponse::build(status)
+        .content_type("text/html; charset=utf-8")
+        .body(html)
+}
+
 // Protected endpoint - requires auth
 async fn get_suppression_list(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+    query: web::Query<SuppressionListQuery>,
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -143,21 +476,58 @@ async fn get_suppression_list(
         created_at: chrono::DateTime<Utc>,
     }
 
+    let page = PageQuery { limit: query.limit, offset: query.offset };
+    let search = query.q.as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| {
+            let escaped = q.to_lowercase().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            format!("%{}%", escaped)
+        });
+    let reason = query.reason.as_deref().filter(|r| !r.is_empty());
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM suppression_list
+        WHERE workspace_id = $1
+          AND ($2::text IS NULL OR LOWER(email) LIKE $2)
+          AND ($3::text IS NULL OR reason = $3)
+        "#
+    )
+    .bind(workspace_id)
+    .bind(&search)
+    .bind(reason)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(ApiError::internal)?;
+
     let entries = sqlx::query_as::<_, SuppressionEntry>(
         r#"
         SELECT id, email, reason, source, created_at
         FROM suppression_list
         WHERE workspace_id = $1
+          AND ($2::text IS NULL OR LOWER(email) LIKE $2)
+          AND ($3::text IS NULL OR reason = $3)
         ORDER BY created_at DESC
-        LIMIT 1000
+        LIMIT $4 OFFSET $5
         "#
     )
     .bind(workspace_id)
+    .bind(&search)
+    .bind(reason)
+    .bind(page.limit())
+    .bind(page.offset())
     .fetch_all(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
-    Ok(HttpResponse::Ok().json(entries))
+    Ok(HttpResponse::Ok().json(Paginated {
+        data: entries,
+        total,
+        limit: page.limit(),
+        offset: page.offset(),
+    }))
 }
 
 // Protected endpoint - requires auth
@@ -165,11 +535,18 @@ async fn add_to_suppression(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     body: web::Json<AddSuppressionRequest>,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
 
-    let result = sqlx::query(
+    // `@example.com` suppresses every address at the domain
+    let Some(email) = suppression::normalize_entry(&body.email) else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Expected an email address or @domain"
+        })));
+    };
+
+    sqlx::query(
         r#"
         INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at)
         VALUES ($1, $2, $3, $4, 'manual', $5)
@@ -181,16 +558,16 @@ async fn add_to_suppression(
     )
     .bind(Uuid::new_v4())
     .bind(workspace_id)
-    .bind(&body.email)
+    .bind(&email)
     .bind(&body.reason)
     .bind(Utc::now())
     .execute(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     Ok(HttpResponse::Created().json(serde_json::json!({
         "success": true,
-        "email": body.email,
+        "email": email,
         "reason": body.reason
     })))
 }
@@ -200,19 +577,19 @@ async fn remove_from_suppression(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     path: web::Path<String>,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let email = path.into_inner();
 
     let result = sqlx::query(
-        "DELETE FROM suppression_list WHERE workspace_id = $1 AND email = $2"
+        "DELETE FROM suppression_list WHERE workspace_id = $1 AND LOWER(email) = LOWER($2)"
     )
     .bind(workspace_id)
     .bind(&email)
     .execute(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true, "email": email})))
@@ -220,3 +597,249 @@ async fn remove_from_suppression(
         Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Email not found in suppression list"})))
     }
 }
+
+// Protected endpoint - requires auth
+//
+// Accepts a newline-separated list or CSV (address or `@domain` in the first
+// column, optional reason in the second). A header row starting with "email" is ignored.
+async fn import_suppression(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    query: web::Query<ImportSuppressionQuery>,
+    body: String,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let default_reason = match query.reason.as_deref() {
+        Some(r) => match SuppressionReason::parse(r) {
+            Some(reason) => reason,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Unknown reason '{}'", r)
+                })));
+            }
+        },
+        None => SuppressionReason::Manual,
+    };
+
+    let import = suppression::parse_import(&body, &default_reason);
+    let (added, updated) = suppression::upsert_entries(pool.get_ref(), workspace_id, &import.entries, "import")
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(ImportSuppressionResponse {
+        added,
+        updated,
+        skipped: import.skipped,
+    }))
+}
+
+// Protected endpoint - requires auth
+//
+// The whole list as CSV, in the format the import accepts
+async fn export_suppression(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    export::respond::<SuppressionExportRow>(
+        pool.get_ref().clone(),
+        workspace_id,
+        ExportFormat::Csv,
+        &format!("suppression-list-{}", Utc::now().format("%Y-%m-%d")),
+        "Suppression list",
+    )
+    .await
+    .map_err(ApiError::internal)
+}
+
+/// Lints email content for spam triggers before it goes into a campaign
+async fn spam_check(
+    req: HttpRequest,
+    body: web::Json<SpamCheckRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    extract_claims(&req)?;
+
+    Ok(HttpResponse::Ok().json(deliverability::spam_check(
+        &body.subject,
+        &body.body_html,
+        body.body_text.as_deref(),
+    )))
+}
+
+// Protected endpoint - requires admin
+//
+// Everything the workspace holds about one address, for a data access request
+async fn export_subject_data(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    query: web::Query<DataSubjectRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Admin).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let email = data_subject::normalize_email(&query.email)
+        .ok_or_else(|| ApiError::Validation("A valid email address is required".to_string()))?;
+
+    let bundle = data_subject::export(pool.get_ref(), workspace_id, &email)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"data-export-{}.json\"", Utc::now().format("%Y%m%d")),
+        ))
+        .json(bundle))
+}
+
+// Protected endpoint - requires admin
+//
+// Erases one address's data from the workspace for a deletion request. The
+// address stays on the suppression list so it isn't contacted or imported again.
+async fn erase_subject_data(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<DataSubjectRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Admin).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let user_id = get_user_id(&claims)?;
+    let email = data_subject::normalize_email(&body.email)
+        .ok_or_else(|| ApiError::Validation("A valid email address is required".to_string()))?;
+
+    let record = data_subject::erase(pool.get_ref(), workspace_id, &email, user_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    tracing::info!("Erased data for an address in workspace {} (erasure {})", workspace_id, record.id);
+    Ok(HttpResponse::Ok().json(record))
+}
+
+// Protected endpoint - requires auth
+//
+// The postal address printed in campaign email footers
+async fn get_mailing_address(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let address = mailing_address::get(pool.get_ref(), workspace_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "complete": address.is_complete(),
+        "address": address,
+    })))
+}
+
+// Protected endpoint - requires admin
+//
+// Campaigns don't send until line 1, city and country are set
+async fn update_mailing_address(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<CompanyAddress>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Admin).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let address = body.into_inner().normalized();
+    address.validate().map_err(ApiError::Validation)?;
+
+    mailing_address::save(pool.get_ref(), workspace_id, &address)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "complete": true,
+        "address": address,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_resubscribe_leaves_a_bounce_suppression_in_place() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Bounce resubscribe").await;
+        let (lead_id, campaign_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let email = format!("bounced-{}@example.com", lead_id);
+        sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, $3)")
+            .bind(lead_id)
+            .bind(workspace_id)
+            .bind(&email)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO suppression_list (workspace_id, email, reason, source) VALUES ($1, $2, 'bounced', 'bounce')")
+            .bind(workspace_id)
+            .bind(&email)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let token = unsubscribe_token::sign(lead_id, Some(workspace_id), campaign_id);
+        let unsubscribed = process_unsubscribe(&pool, &token, Some("workspace")).await;
+        let resubscribed = process_resubscribe(&pool, &token, Some("workspace")).await;
+        let remaining: Vec<(String, String)> = sqlx::query_as(
+            "SELECT reason, source FROM suppression_list WHERE workspace_id = $1 AND email = $2"
+        )
+        .bind(workspace_id)
+        .bind(&email)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert!(unsubscribed.is_ok());
+        assert!(resubscribed.is_ok());
+        assert_eq!(remaining, vec![("bounced".to_string(), "bounce".to_string())]);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_workspace_resubscribe_puts_the_lead_back_into_its_campaigns() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Workspace resubscribe").await;
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let email = format!("resubscribe-{}@example.com", Uuid::new_v4());
+        let (lead_id, _) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+
+        let token = unsubscribe_token::sign(lead_id, Some(workspace_id), campaign_id);
+        let status = || async {
+            sqlx::query_as::<_, (String, Option<String>)>("SELECT status, unsubscribe_scope FROM campaign_leads WHERE lead_id = $1")
+                .bind(lead_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+        process_unsubscribe(&pool, &token, Some("workspace")).await.unwrap();
+        let unsubscribed = status().await;
+        process_resubscribe(&pool, &token, Some("workspace")).await.unwrap();
+        let resubscribed = status().await;
+        let suppressed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM suppression_list WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(unsubscribed, ("unsubscribed".to_string(), Some("workspace".to_string())));
+        assert_eq!(resubscribed, ("pending".to_string(), None));
+        assert_eq!(suppressed, 0);
+    }
+}
@@ -1,10 +1,19 @@
 use actix_web::{web, HttpResponse, HttpRequest};
 use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
+use chrono::{DateTime, NaiveTime, Utc};
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::auto_pause::detect_email_provider;
+use crate::services::domain_auth::{DnsTxtResolver, DomainAuthCache, DOMAIN_AUTH_CACHE_TTL};
+use crate::services::email_oauth::{self, OAuthProvider};
+use crate::services::email_sender::test_smtp_credentials;
 use crate::services::encryption::EncryptionService;
+use crate::services::job_runner;
+use crate::services::warmup_service;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct EmailAccount {
@@ -19,6 +28,14 @@ pub struct EmailAccount {
     pub health_score: f32,
     pub created_at: DateTime<Utc>,
     pub workspace_id: Option<Uuid>,
+    pub timezone_offset_minutes: Option<i32>,
+    /// 'password' or 'oauth'
+    pub auth_method: String,
+    /// Set when an OAuth grant stopped working; the inbox needs reconnecting
+    pub auth_error: Option<String>,
+    /// Local hours this inbox sends in; an end before the start runs past midnight
+    pub send_window_start: NaiveTime,
+    pub send_window_end: NaiveTime,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,8 +46,64 @@ pub struct CreateEmailAccountRequest {
     pub smtp_port: i32,
     pub smtp_username: String,
     pub smtp_password: String,
+    /// Offset from UTC in minutes; the daily send counter resets at this local midnight
+    pub timezone_offset_minutes: Option<i32>,
+    /// IMAP server for reply polling; derived from the provider or SMTP host when unset
+    pub imap_host: Option<String>,
+    pub imap_port: Option<i32>,
+    /// Defaults to 09:00-17:00 in the inbox's timezone
+    pub send_window_start: Option<NaiveTime>,
+    pub send_window_end: Option<NaiveTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSendWindowRequest {
+    pub send_window_start: NaiveTime,
+    pub send_window_end: NaiveTime,
+    /// Offset from UTC in minutes the window is read in; UTC when unset
+    pub timezone_offset_minutes: Option<i32>,
+}
+
+/// One row of an inbox import CSV. Columns are matched by header name;
+/// a blank `provider` is detected from the email domain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportEmailAccountRow {
+    pub email: String,
+    #[serde(default)]
+    pub provider: Option<String>,
+    pub smtp_host: String,
+    pub smtp_port: i32,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    #[serde(default)]
+    pub timezone_offset_minutes: Option<i32>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ImportEmailAccountResult {
+    pub line: usize,
+    pub email: Option<String>,
+    pub success: bool,
+    pub id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportEmailAccountsResponse {
+    pub imported: usize,
+    pub failed: usize,
+    pub results: Vec<ImportEmailAccountResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OAuthCallbackRequest {
+    pub code: String,
+    pub state: String,
+}
+
+/// SMTP logins tested at once during an import
+const IMPORT_SMTP_CONCURRENCY: usize = 5;
+
 #[derive(Debug, Serialize)]
 pub struct WarmupStats {
     pub health_score: f32,
@@ -42,33 +115,45 @@ pub struct WarmupStats {
     pub warmup_progress: f32,
 }
 
+static DNS_RESOLVER: LazyLock<DnsTxtResolver> = LazyLock::new(DnsTxtResolver::new);
+static DOMAIN_AUTH_CACHE: LazyLock<DomainAuthCache> = LazyLock::new(|| DomainAuthCache::new(DOMAIN_AUTH_CACHE_TTL));
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/email-accounts")
             .route("", web::get().to(get_email_accounts))
             .route("", web::post().to(create_email_account))
+            .service(
+                web::resource("/import")
+                    .app_data(web::PayloadConfig::new(2 * 1024 * 1024))
+                    .route(web::post().to(import_email_accounts))
+            )
+            .route("/oauth/{provider}/start", web::get().to(start_oauth))
+            .route("/oauth/{provider}/callback", web::post().to(complete_oauth))
             .route("/{id}", web::get().to(get_email_account))
             .route("/{id}", web::delete().to(delete_email_account))
             .route("/{id}/warmup/start", web::post().to(start_warmup))
             .route("/{id}/warmup/pause", web::post().to(pause_warmup))
             .route("/{id}/warmup/stats", web::get().to(get_warmup_stats))
+            .route("/{id}/send-window", web::put().to(update_send_window))
+            .route("/{id}/dns-check", web::get().to(check_dns))
     );
 }
 
 async fn get_email_accounts(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
     let accounts = sqlx::query_as::<_, EmailAccount>(
-        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id FROM email_accounts WHERE workspace_id = $1 ORDER BY created_at DESC"
+        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end FROM email_accounts WHERE workspace_id = $1 ORDER BY created_at DESC"
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     Ok(HttpResponse::Ok().json(accounts))
 }
@@ -77,19 +162,19 @@ async fn get_email_account(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = path.into_inner();
 
     let account = sqlx::query_as::<_, EmailAccount>(
-        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id FROM email_accounts WHERE id = $1 AND workspace_id = $2"
+        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end FROM email_accounts WHERE id = $1 AND workspace_id = $2"
     )
     .bind(account_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     match account {
         Some(acc) => Ok(HttpResponse::Ok().json(acc)),
@@ -101,33 +186,36 @@ async fn create_email_account(
     pool: web::Data<PgPool>,
     payload: web::Json<CreateEmailAccountRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = Uuid::new_v4();
     let now = Utc::now();
 
-    // Encrypt SMTP password before storing
-    let (encrypted_password, key_id) = match EncryptionService::new() {
-        Ok(enc) => match enc.encrypt(&payload.smtp_password) {
-            Ok((encrypted, key_id)) => (Some(encrypted), Some(key_id)),
-            Err(e) => {
-                eprintln!("Warning: Failed to encrypt password: {}. Storing without encryption.", e);
-                (None, None)
-            }
-        },
-        Err(e) => {
-            eprintln!("Warning: Encryption service unavailable: {}. Storing without encryption.", e);
-            (None, None)
+    if let Some(offset) = payload.timezone_offset_minutes {
+        if !(-720..=840).contains(&offset) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "timezone_offset_minutes must be between -720 and 840"
+            })));
         }
-    };
+    }
+
+    if payload.imap_port.is_some_and(|port| !(1..=65535).contains(&port)) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "imap_port must be between 1 and 65535"
+        })));
+    }
+
+    // Never store the password in plaintext; without a working key the inbox isn't saved
+    let (encrypted_password, key_id) = encrypt_smtp_password(EncryptionService::new().as_ref(), &payload.smtp_password)
+        .map_err(ApiError::internal)?;
 
     let result = sqlx::query_as::<_, EmailAccount>(
         r#"
         INSERT INTO email_accounts 
-        (id, email, provider, smtp_host, smtp_port, smtp_username, smtp_password, smtp_password_encrypted, encryption_key_id, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'pending', 10, 0, 100.0, $10, $11)
-        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id
+        (id, email, provider, smtp_host, smtp_port, smtp_username, smtp_password_encrypted, encryption_key_id, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, imap_host, imap_port, send_window_start, send_window_end)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending', 10, 0, 100.0, $9, $10, $11, NULLIF(TRIM($12), ''), $13, COALESCE($14, '09:00'::time), COALESCE($15, '17:00'::time))
+        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end
         "#
     )
     .bind(account_id)
@@ -136,26 +224,283 @@ async fn create_email_account(
     .bind(&payload.smtp_host)
     .bind(payload.smtp_port)
     .bind(&payload.smtp_username)
-    .bind(if encrypted_password.is_some() { None::<&str> } else { Some(payload.smtp_password.as_str()) }) // Only store plaintext if encryption failed
     .bind(&encrypted_password)
     .bind(&key_id)
     .bind(now)
     .bind(workspace_id)
+    .bind(payload.timezone_offset_minutes)
+    .bind(&payload.imap_host)
+    .bind(payload.imap_port)
+    .bind(payload.send_window_start)
+    .bind(payload.send_window_end)
     .fetch_one(pool.get_ref())
     .await;
 
     let account = result
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        .map_err(ApiError::internal)?;
     
     Ok(HttpResponse::Created().json(account))
 }
 
+/// Returns `(ciphertext, key_id)`. Fails with a message safe to show the
+/// client when the key is missing or broken; the details only go to the log.
+fn encrypt_smtp_password(encryption: Result<&EncryptionService, &String>, password: &str) -> Result<(Vec<u8>, String), String> {
+    encryption
+        .map_err(String::clone)
+        .and_then(|enc| enc.encrypt(password))
+        .map_err(|e| {
+            tracing::error!("Failed to encrypt SMTP password: {}", e);
+            "SMTP password encryption is unavailable; the inbox was not saved".to_string()
+        })
+}
+
+fn oauth_provider(value: &str) -> Result<OAuthProvider, HttpResponse> {
+    OAuthProvider::parse(value).ok_or_else(|| {
+        HttpResponse::BadRequest().json(serde_json::json!({"error": "provider must be google or microsoft"}))
+    })
+}
+
+/// GET /email-accounts/oauth/{provider}/start - Consent URL for connecting a Gmail or Outlook inbox
+async fn start_oauth(
+    path: web::Path<String>,
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let provider = match oauth_provider(&path) {
+        Ok(provider) => provider,
+        Err(response) => return Ok(response),
+    };
+
+    let state = email_oauth::sign_state(workspace_id, provider, Utc::now().timestamp());
+    match email_oauth::authorization_url(provider, &state) {
+        Ok(url) => Ok(HttpResponse::Ok().json(serde_json::json!({"authorization_url": url}))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": e}))),
+    }
+}
+
+/// POST /email-accounts/oauth/{provider}/callback - Redeems the consent code and saves the inbox.
+/// Reconnecting an inbox this workspace already has replaces its grant and clears `auth_error`.
+async fn complete_oauth(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    payload: web::Json<OAuthCallbackRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let provider = match oauth_provider(&path) {
+        Ok(provider) => provider,
+        Err(response) => return Ok(response),
+    };
+
+    if let Err(e) = email_oauth::verify_state(&payload.state, workspace_id, provider, Utc::now().timestamp()) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": e})));
+    }
+
+    let tokens = match email_oauth::exchange_code(provider, &payload.code).await {
+        Ok(tokens) => tokens,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": e}))),
+    };
+    let Some(refresh_token) = tokens.refresh_token else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "The provider did not grant offline access; remove the app from your account and try again"
+        })));
+    };
+    let Some(email) = tokens.id_token.as_deref().and_then(email_oauth::email_from_id_token) else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Could not read the mailbox address from the provider's response"
+        })));
+    };
+
+    let (detected_provider, provider_limit) = detect_email_provider(&email);
+    let (encrypted_token, key_id) = encrypt_smtp_password(EncryptionService::new().as_ref(), &refresh_token)
+        .map_err(ApiError::internal)?;
+
+    // Email is unique across workspaces, so the upsert only touches this workspace's row
+    let account = sqlx::query_as::<_, EmailAccount>(
+        r#"
+        INSERT INTO email_accounts
+        (id, email, provider, smtp_host, smtp_port, smtp_username, smtp_password_encrypted, encryption_key_id,
+         auth_method, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id,
+         detected_provider, provider_daily_limit)
+        VALUES ($1, $2, $3, $4, 587, $2, $5, $6, 'oauth', 'pending', 10, 0, 100.0, NOW(), $7, $8, $9)
+        ON CONFLICT (email) DO UPDATE SET
+            provider = EXCLUDED.provider,
+            smtp_host = EXCLUDED.smtp_host,
+            smtp_port = EXCLUDED.smtp_port,
+            smtp_username = EXCLUDED.smtp_username,
+            smtp_password = NULL,
+            smtp_password_encrypted = EXCLUDED.smtp_password_encrypted,
+            encryption_key_id = EXCLUDED.encryption_key_id,
+            auth_method = 'oauth',
+            auth_error = NULL,
+            auth_failed_at = NULL
+        WHERE email_accounts.workspace_id = EXCLUDED.workspace_id
+        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(&email)
+    .bind(provider.account_provider())
+    .bind(provider.smtp_host())
+    .bind(&encrypted_token)
+    .bind(&key_id)
+    .bind(workspace_id)
+    .bind(detected_provider)
+    .bind(provider_limit)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(ApiError::internal)?;
+
+    match account {
+        Some(account) => Ok(HttpResponse::Ok().json(account)),
+        None => Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "error": format!("{} is already connected to another workspace", email)
+        }))),
+    }
+}
+
+async fn import_email_accounts(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: String,
+) -> Result<HttpResponse, actix_web::Error> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let mut results = Vec::new();
+    let mut rows = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    for (idx, record) in reader.deserialize::<ImportEmailAccountRow>().enumerate() {
+        // Line 1 is the header
+        let line = idx + 2;
+        match record {
+            Ok(row) if row.email.parse::<lettre::Address>().is_err() => results.push(ImportEmailAccountResult {
+                line,
+                email: Some(row.email),
+                success: false,
+                id: None,
+                error: Some("Invalid email address".to_string()),
+            }),
+            Ok(row) if !(1..=65535).contains(&row.smtp_port) => results.push(ImportEmailAccountResult {
+                line,
+                email: Some(row.email),
+                success: false,
+                id: None,
+                error: Some("Invalid SMTP port".to_string()),
+            }),
+            Ok(row) => rows.push((line, row)),
+            Err(e) => results.push(ImportEmailAccountResult {
+                line,
+                email: None,
+                success: false,
+                id: None,
+                error: Some(format!("Could not parse row: {}", e)),
+            }),
+        }
+    }
+
+    // SMTP logins are slow, so check them in parallel before touching the database
+    let tested = job_runner::run_bounded(rows, IMPORT_SMTP_CONCURRENCY, |(line, row): (usize, ImportEmailAccountRow)| async move {
+        let check = test_smtp_credentials(&row.smtp_host, row.smtp_port as u16, &row.smtp_username, &row.smtp_password).await;
+        (line, row, check)
+    })
+    .await;
+
+    let encryption = EncryptionService::new();
+    for (line, row, check) in tested {
+        if let Err(e) = check {
+            results.push(ImportEmailAccountResult {
+                line,
+                email: Some(row.email),
+                success: false,
+                id: None,
+                error: Some(e),
+            });
+            continue;
+        }
+
+        let result = match insert_imported_account(pool.get_ref(), encryption.as_ref(), workspace_id, &row).await {
+            Ok(id) => ImportEmailAccountResult { line, email: Some(row.email), success: true, id: Some(id), error: None },
+            Err(e) => ImportEmailAccountResult { line, email: Some(row.email), success: false, id: None, error: Some(e) },
+        };
+        results.push(result);
+    }
+
+    results.sort_by_key(|r| r.line);
+    let imported = results.iter().filter(|r| r.success).count();
+
+    Ok(HttpResponse::Ok().json(ImportEmailAccountsResponse {
+        imported,
+        failed: results.len() - imported,
+        results,
+    }))
+}
+
+/// Inserts one imported inbox in its own transaction so a bad row only fails itself.
+async fn insert_imported_account(
+    pool: &PgPool,
+    encryption: Result<&EncryptionService, &String>,
+    workspace_id: Uuid,
+    row: &ImportEmailAccountRow,
+) -> Result<Uuid, String> {
+    let (detected_provider, provider_limit) = detect_email_provider(&row.email);
+    let provider = row.provider.clone()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| detected_provider.to_string());
+    let (encrypted_password, key_id) = encrypt_smtp_password(encryption, &row.smtp_password)?;
+    let account_id = Uuid::new_v4();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO email_accounts
+        (id, email, provider, smtp_host, smtp_port, smtp_username, smtp_password_encrypted, encryption_key_id,
+         warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes,
+         detected_provider, provider_daily_limit)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending', 10, 0, 100.0, NOW(), $9, $10, $11, $12)
+        "#
+    )
+    .bind(account_id)
+    .bind(&row.email)
+    .bind(&provider)
+    .bind(&row.smtp_host)
+    .bind(row.smtp_port)
+    .bind(&row.smtp_username)
+    .bind(&encrypted_password)
+    .bind(&key_id)
+    .bind(workspace_id)
+    .bind(row.timezone_offset_minutes)
+    .bind(detected_provider)
+    .bind(provider_limit)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = inserted {
+        let _ = tx.rollback().await;
+        return Err(match e {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => "Inbox already exists".to_string(),
+            other => other.to_string(),
+        });
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(account_id)
+}
+
 async fn delete_email_account(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = path.into_inner();
 
@@ -164,7 +509,7 @@ async fn delete_email_account(
         .bind(workspace_id)
         .execute(pool.get_ref())
         .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        .map_err(ApiError::internal)?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::NoContent().finish())
@@ -177,8 +522,8 @@ async fn start_warmup(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = path.into_inner();
 
@@ -187,14 +532,14 @@ async fn start_warmup(
         UPDATE email_accounts 
         SET warmup_status = 'warming'
         WHERE id = $1 AND workspace_id = $2 AND warmup_status IN ('pending', 'paused')
-        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id
+        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end
         "#
     )
     .bind(account_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     match account {
         Some(acc) => Ok(HttpResponse::Ok().json(acc)),
@@ -206,8 +551,8 @@ async fn pause_warmup(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = path.into_inner();
 
@@ -216,14 +561,14 @@ async fn pause_warmup(
         UPDATE email_accounts 
         SET warmup_status = 'paused'
         WHERE id = $1 AND workspace_id = $2 AND warmup_status IN ('warming', 'active')
-        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id
+        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end
         "#
     )
     .bind(account_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     match account {
         Some(acc) => Ok(HttpResponse::Ok().json(acc)),
@@ -231,41 +576,167 @@ async fn pause_warmup(
     }
 }
 
+/// SPF, DKIM and DMARC report for the domain the inbox sends from
+async fn check_dns(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let account_id = path.into_inner();
+
+    let email: Option<String> = sqlx::query_scalar(
+        "SELECT email FROM email_accounts WHERE id = $1 AND workspace_id = $2"
+    )
+    .bind(account_id)
+    .bind(workspace_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(ApiError::internal)?;
+
+    let Some(email) = email else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Email account not found"})));
+    };
+    let Some((_, domain)) = email.rsplit_once('@').filter(|(_, d)| !d.is_empty()) else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": "Email account has no domain"})));
+    };
+
+    let report = DOMAIN_AUTH_CACHE.check(&*DNS_RESOLVER, domain).await;
+    Ok(HttpResponse::Ok().json(report))
+}
+
 async fn get_warmup_stats(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let account_id = path.into_inner();
 
     let account = sqlx::query_as::<_, EmailAccount>(
-        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id FROM email_accounts WHERE id = $1 AND workspace_id = $2"
+        "SELECT id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end FROM email_accounts WHERE id = $1 AND workspace_id = $2"
     )
     .bind(account_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
+
+    let Some(acc) = account else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Email account not found"})));
+    };
+
+    let summary = warmup_service::warmup_summary(pool.get_ref(), acc.id)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::NotFound("Email account not found".to_string()))?;
+
+    let stats = WarmupStats {
+        health_score: acc.health_score,
+        daily_volume: acc.sent_today,
+        daily_limit: acc.daily_limit,
+        inbox_rate: summary.inbox_rate() as f32,
+        spam_rate: summary.spam_rate() as f32,
+        bounce_rate: summary.bounce_rate() as f32,
+        warmup_progress: summary.progress(Utc::now()) as f32,
+    };
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+/// PUT /email-accounts/{id}/send-window - Sets the local hours the inbox sends in
+async fn update_send_window(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    payload: web::Json<UpdateSendWindowRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let account_id = path.into_inner();
+
+    if payload.timezone_offset_minutes.is_some_and(|offset| !(-720..=840).contains(&offset)) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "timezone_offset_minutes must be between -720 and 840"
+        })));
+    }
+
+    let account = sqlx::query_as::<_, EmailAccount>(
+        r#"
+        UPDATE email_accounts
+        SET send_window_start = $3, send_window_end = $4, timezone_offset_minutes = $5
+        WHERE id = $1 AND workspace_id = $2
+        RETURNING id, email, provider, smtp_host, smtp_port, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id, timezone_offset_minutes, auth_method, auth_error, send_window_start, send_window_end
+        "#
+    )
+    .bind(account_id)
+    .bind(workspace_id)
+    .bind(payload.send_window_start)
+    .bind(payload.send_window_end)
+    .bind(payload.timezone_offset_minutes)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(ApiError::internal)?;
 
     match account {
-        Some(acc) => {
-            let target_volume = 50;
-            let warmup_progress = (acc.daily_limit as f32 / target_volume as f32) * 100.0;
-            
-            let stats = WarmupStats {
-                health_score: acc.health_score,
-                daily_volume: acc.sent_today,
-                daily_limit: acc.daily_limit,
-                inbox_rate: 98.0,
-                spam_rate: 0.5,
-                bounce_rate: 1.5,
-                warmup_progress: warmup_progress.min(100.0),
-            };
-            
-            Ok(HttpResponse::Ok().json(stats))
-        }
+        Some(acc) => Ok(HttpResponse::Ok().json(acc)),
         None => Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Email account not found"}))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use crate::services::encryption::generate_encryption_key;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_imported_inbox_is_only_saved_with_an_encrypted_password() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Inbox import").await;
+        let row = |email: String| ImportEmailAccountRow {
+            email: email.clone(),
+            provider: None,
+            smtp_host: "smtp.example.com".to_string(),
+            smtp_port: 587,
+            smtp_username: email,
+            smtp_password: "hunter2".to_string(),
+            timezone_offset_minutes: None,
+        };
+
+        let encryption = EncryptionService::from_config(Some(&generate_encryption_key()), None, None);
+        let saved = row(format!("saved-{}@example.com", workspace_id));
+        let id = insert_imported_account(&pool, encryption.as_ref(), workspace_id, &saved).await.unwrap();
+
+        let bad_key = EncryptionService::from_config(Some("not-a-key"), None, None);
+        let refused = row(format!("refused-{}@example.com", workspace_id));
+        let error = insert_imported_account(&pool, bad_key.as_ref(), workspace_id, &refused).await.unwrap_err();
+
+        let stored: (Option<String>, Option<Vec<u8>>, Option<String>) = sqlx::query_as(
+            "SELECT smtp_password, smtp_password_encrypted, encryption_key_id FROM email_accounts WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let refused_rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_accounts WHERE email = $1")
+            .bind(&refused.email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let encryption = encryption.unwrap();
+        assert_eq!(stored.0, None);
+        assert_eq!(encryption.decrypt_with_key_id(&stored.1.unwrap(), stored.2.as_deref()).unwrap(), "hunter2");
+        // The client gets a generic message, not the key problem itself
+        assert!(error.contains("encryption is unavailable"));
+        assert!(!error.contains("ENCRYPTION_KEY"));
+        assert_eq!(refused_rows, 0);
+    }
+}
@@ -0,0 +1,128 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Error type for API handlers. Every variant renders as `{"error": ..., "code": ...}`;
+/// internal errors are logged and replaced with a generic message so SQL and
+/// upstream details never reach the client.
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    Unauthorized(String),
+    Forbidden(String),
+    Validation(String),
+    /// Well-formed request whose content can't be accepted, e.g. a template missing a required field
+    Unprocessable(String),
+    Conflict(String),
+    /// Caller exceeded a rate limit; carries seconds until they may retry
+    RateLimited(String, u64),
+    Internal(String),
+}
+
+/// JSON body returned for every `ApiError`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+impl ApiError {
+    /// Wraps any displayable error as an internal error, e.g. `.map_err(ApiError::internal)`
+    pub fn internal(e: impl fmt::Display) -> Self {
+        ApiError::Internal(e.to_string())
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Validation(_) => "validation_error",
+            ApiError::Unprocessable(_) => "unprocessable",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::RateLimited(..) => "rate_limited",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn public_message(&self) -> &str {
+        match self {
+            ApiError::NotFound(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::Forbidden(msg)
+            | ApiError::Validation(msg)
+            | ApiError::Unprocessable(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::RateLimited(msg, _) => msg,
+            ApiError::Internal(_) => "Internal server error",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Internal(detail) => write!(f, "internal error: {}", detail),
+            other => write!(f, "{}", other.public_message()),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::RateLimited(..) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let ApiError::Internal(detail) = self {
+            tracing::error!("Internal API error: {}", detail);
+        }
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let ApiError::RateLimited(_, retry_after) = self {
+            response.insert_header((actix_web::http::header::RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response.json(ErrorResponse {
+            error: self.public_message().to_string(),
+            code: self.code().to_string(),
+        })
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => ApiError::NotFound("Resource not found".to_string()),
+            sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => {
+                ApiError::Conflict("Resource already exists".to_string())
+            }
+            _ => ApiError::Internal(e.to_string()),
+        }
+    }
+}
+
+/// Lets handlers keep using the `actix_web::Error`-returning auth helpers with `?`
+impl From<actix_web::Error> for ApiError {
+    fn from(e: actix_web::Error) -> Self {
+        let message = e.to_string();
+        match e.as_response_error().status_code() {
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized(message),
+            StatusCode::FORBIDDEN => ApiError::Forbidden(message),
+            StatusCode::NOT_FOUND => ApiError::NotFound(message),
+            StatusCode::CONFLICT => ApiError::Conflict(message),
+            status if status.is_client_error() => ApiError::Validation(message),
+            _ => ApiError::Internal(message),
+        }
+    }
+}
@@ -3,13 +3,23 @@ use sqlx::PgPool;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
+use crate::api::error::{ApiError, ErrorResponse};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::reply_classifier::{self, ReplyCategory};
+use crate::services::health_score::HealthBreakdown;
+use crate::services::zapier;
+use crate::services::campaign_scheduler;
+use crate::services::auto_pause;
+use crate::services::pause_notifications;
+use crate::services::data_retention::{self, PurgeRun};
 
 // ============================================================================
 // DATA TYPES
 // ============================================================================
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct DashboardOverview {
     pub total_campaigns: i64,
     pub active_campaigns: i64,
@@ -21,7 +31,7 @@ pub struct DashboardOverview {
     pub cost_per_meeting_trend: f64,  // % change from previous period
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct InboxHealthCard {
     pub id: Uuid,
     pub email: String,
@@ -33,9 +43,26 @@ pub struct InboxHealthCard {
     pub bounce_rate: f32,
     pub daily_limit: i32,
     pub sent_today: i32,
+    pub domain: String,
+    pub domain_health_status: String,
+    /// How health_score was computed; null until the health job has run
+    #[sqlx(json)]
+    pub health_breakdown: Option<HealthBreakdown>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct DomainHealthCard {
+    pub domain: String,
+    pub inbox_count: i64,
+    pub danger_inboxes: i64,
+    pub health_status: String,      // healthy, warning, danger
+    pub spam_rate: f64,
+    pub bounce_rate: f64,
+    pub reply_rate: f64,
+    pub sent_today: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct CampaignCard {
     pub id: Uuid,
     pub name: String,
@@ -51,7 +78,7 @@ pub struct CampaignCard {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct ReplyCard {
     pub id: Uuid,
     pub from_email: String,
@@ -65,19 +92,23 @@ pub struct ReplyCard {
     pub received_at: DateTime<Utc>,
     pub is_read: bool,
     pub is_actioned: bool,
+    /// -1 (hostile) to 1 (enthusiastic); null until classified
+    pub sentiment: Option<f64>,
+    pub is_urgent: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct FounderDashboardData {
     pub overview: DashboardOverview,
     pub campaigns: Vec<CampaignCard>,
     pub inboxes: Vec<InboxHealthCard>,
+    pub domains: Vec<DomainHealthCard>,
     pub recent_replies: Vec<ReplyCard>,
     pub unread_count: i64,
     pub action_required_count: i64,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct AutoPauseEvent {
     pub id: Uuid,
     pub campaign_id: Option<Uuid>,
@@ -88,12 +119,12 @@ pub struct AutoPauseEvent {
     pub is_resolved: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ClassifyReplyRequest {
     pub reply_id: Uuid,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateCostsRequest {
     pub campaign_id: Uuid,
     pub domain_cost: Option<f64>,
@@ -103,30 +134,52 @@ pub struct UpdateCostsRequest {
     pub other_cost: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateSettingsRequest {
     pub auto_pause_enabled: Option<bool>,
     pub spam_rate_threshold: Option<f64>,
     pub reply_drop_threshold: Option<f64>,
     pub bounce_rate_threshold: Option<f64>,
+    pub deliverability_score_threshold: Option<f64>,
     pub notification_email: Option<String>,
     pub slack_webhook_url: Option<String>,
+    /// Replaces the workspace's custom reply categories (built-in ones are always active)
+    pub reply_categories: Option<Vec<ReplyCategory>>,
+    /// Dashboard ordering of reply intents, highest priority first
+    pub reply_category_priority: Option<Vec<String>>,
+    /// Delete email events older than this many months; 0 keeps them forever
+    pub event_retention_months: Option<i32>,
+    /// Anonymize replies older than this many months; 0 keeps them forever
+    pub reply_retention_months: Option<i32>,
+    /// Resume auto-paused campaigns once their metrics recover
+    pub auto_resume_enabled: Option<bool>,
+    /// How long metrics must stay within thresholds before a campaign resumes
+    pub auto_resume_cooldown_hours: Option<i32>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct WorkspaceSettings {
     pub auto_pause_enabled: bool,
     pub spam_rate_threshold: f64,
     pub reply_drop_threshold: f64,
     pub bounce_rate_threshold: f64,
+    pub deliverability_score_threshold: f64,
     pub google_daily_limit: i32,
     pub outlook_daily_limit: i32,
     pub zoho_daily_limit: i32,
     pub notification_email: Option<String>,
     pub slack_webhook_url: Option<String>,
+    /// Custom reply categories, on top of the built-in ones
+    #[sqlx(json)]
+    pub reply_categories: Vec<ReplyCategory>,
+    pub reply_category_priority: Vec<String>,
+    pub event_retention_months: Option<i32>,
+    pub reply_retention_months: Option<i32>,
+    pub auto_resume_enabled: bool,
+    pub auto_resume_cooldown_hours: i32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CostPerMeetingStats {
     pub current_period: f64,
     pub previous_period: f64,
@@ -136,7 +189,7 @@ pub struct CostPerMeetingStats {
     pub by_campaign: Vec<CampaignCostSummary>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct CampaignCostSummary {
     pub campaign_id: Uuid,
     pub campaign_name: String,
@@ -158,6 +211,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/campaigns/{id}/resume", web::post().to(resume_campaign))
             .route("/inboxes", web::get().to(get_inbox_health))
             .route("/inboxes/{id}/health", web::get().to(get_inbox_health_detail))
+            .route("/domains/health", web::get().to(get_domain_health))
             .route("/replies", web::get().to(get_replies))
             .route("/replies/{id}/action", web::post().to(action_reply))
             .route("/replies/classify", web::post().to(classify_reply))
@@ -169,17 +223,52 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/meetings", web::post().to(create_meeting))
             .route("/settings", web::get().to(get_settings))
             .route("/settings", web::put().to(update_settings))
+            .route("/retention-runs", web::get().to(get_retention_runs))
     );
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_dashboard,
+    get_campaigns_with_health,
+    pause_campaign,
+    resume_campaign,
+    get_inbox_health,
+    get_inbox_health_detail,
+    get_domain_health,
+    get_replies,
+    action_reply,
+    classify_reply,
+    get_auto_pause_events,
+    resolve_pause_event,
+    get_cost_stats,
+    update_costs,
+    get_meetings,
+    create_meeting,
+    get_settings,
+    update_settings,
+    get_retention_runs,
+))]
+pub struct FounderApi;
+
 // ============================================================================
 // MAIN DASHBOARD ENDPOINT
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/dashboard",
+    tag = "founder",
+    responses(
+        (status = 200, body = FounderDashboardData),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_dashboard(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -207,7 +296,7 @@ async fn get_dashboard(
             CASE WHEN c.sent > 0 THEN (c.replied::FLOAT / c.sent::FLOAT) ELSE 0 END as reply_rate,
             c.created_at
         FROM campaigns c
-        WHERE c.workspace_id = $1
+        WHERE c.workspace_id = $1 AND c.deleted_at IS NULL
         ORDER BY 
             CASE WHEN c.auto_paused THEN 0 ELSE 1 END,
             c.created_at DESC
@@ -216,8 +305,7 @@ async fn get_dashboard(
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // Get inbox health
     let inboxes = sqlx::query_as::<_, InboxHealthCard>(
@@ -229,6 +317,7 @@ async fn get_dashboard(
             CASE 
                 WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 'danger'
                 WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 OR ea.reply_rate < 0.02 THEN 'warning'
+                WHEN dh.health_status = 'danger' THEN 'warning'
                 ELSE 'healthy'
             END as health_status,
             ea.health_score,
@@ -236,13 +325,18 @@ async fn get_dashboard(
             COALESCE(ea.reply_rate, 0) as reply_rate,
             COALESCE(ea.bounce_rate, 0) as bounce_rate,
             ea.daily_limit,
-            ea.sent_today
+            ea.sent_today,
+            email_domain(ea.email) as domain,
+            COALESCE(dh.health_status, 'healthy') as domain_health_status,
+            COALESCE(ea.health_breakdown, 'null'::jsonb) as health_breakdown
         FROM email_accounts ea
+        LEFT JOIN email_domain_health dh
+            ON dh.workspace_id = ea.workspace_id AND dh.domain = email_domain(ea.email)
         WHERE ea.workspace_id = $1
         ORDER BY 
             CASE 
                 WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 0
-                WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 THEN 1
+                WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 OR dh.health_status = 'danger' THEN 1
                 ELSE 2
             END,
             ea.email
@@ -250,10 +344,13 @@ async fn get_dashboard(
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
+
+    let domains = fetch_domain_health(&pool, workspace_id).await?;
 
-    // Get recent replies needing action
+    let reply_config = reply_classifier::load_workspace_config(pool.get_ref(), workspace_id).await?;
+
+    // Get recent replies needing action, in the workspace's priority order
     let recent_replies = sqlx::query_as::<_, ReplyCard>(
         r#"
         SELECT 
@@ -268,25 +365,23 @@ async fn get_dashboard(
             c.name as campaign_name,
             er.received_at,
             er.is_read,
-            er.is_actioned
+            er.is_actioned,
+            er.sentiment,
+            er.is_urgent
         FROM email_replies er
         LEFT JOIN campaigns c ON er.campaign_id = c.id
         WHERE er.workspace_id = $1 AND er.is_actioned = FALSE
         ORDER BY 
-            CASE er.intent 
-                WHEN 'interested' THEN 1 
-                WHEN 'objection' THEN 2
-                WHEN 'maybe_later' THEN 3
-                ELSE 4 
-            END,
+            er.is_urgent DESC,
+            COALESCE(array_position($2::text[], er.intent::text), 2147483647),
             er.received_at DESC
         LIMIT 20
         "#
     )
     .bind(workspace_id)
+    .bind(&reply_config.priority)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // Get counts
     let unread_count: (i64,) = sqlx::query_as(
@@ -294,24 +389,18 @@ async fn get_dashboard(
     )
     .bind(workspace_id)
     .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
-    let action_required: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM email_replies WHERE workspace_id = $1 AND is_actioned = FALSE AND intent = 'interested'"
-    )
-    .bind(workspace_id)
-    .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let action_required = count_action_required(pool.get_ref(), workspace_id, &reply_config.priority).await?;
 
     let dashboard = FounderDashboardData {
         overview,
         campaigns,
         inboxes,
+        domains,
         recent_replies,
         unread_count: unread_count.0,
-        action_required_count: action_required.0,
+        action_required_count: action_required,
     };
 
     Ok(HttpResponse::Ok().json(dashboard))
@@ -320,7 +409,7 @@ async fn get_dashboard(
 async fn get_overview_stats(
     pool: &PgPool,
     workspace_id: Uuid,
-) -> Result<DashboardOverview, actix_web::Error> {
+) -> Result<DashboardOverview, ApiError> {
     // Get campaign stats
     let campaign_stats: (i64, i64, i64, i64, i64, i64) = sqlx::query_as(
         r#"
@@ -332,13 +421,12 @@ async fn get_overview_stats(
             COALESCE(SUM(replied), 0),
             COALESCE(SUM(meetings_booked), 0)
         FROM campaigns
-        WHERE workspace_id = $1
+        WHERE workspace_id = $1 AND deleted_at IS NULL
         "#
     )
     .bind(workspace_id)
     .fetch_one(pool)
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // Get cost per meeting (last 30 days)
     let cost_stats: (Option<f64>, Option<i32>) = sqlx::query_as(
@@ -353,8 +441,7 @@ async fn get_overview_stats(
     )
     .bind(workspace_id)
     .fetch_one(pool)
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     let total_cost = cost_stats.0.unwrap_or(0.0);
     let meetings = cost_stats.1.unwrap_or(0).max(1) as f64;
@@ -374,8 +461,7 @@ async fn get_overview_stats(
     )
     .bind(workspace_id)
     .fetch_one(pool)
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     let prev_cost = prev_cost_stats.0.unwrap_or(0.0);
     let prev_meetings = prev_cost_stats.1.unwrap_or(0).max(1) as f64;
@@ -403,10 +489,36 @@ async fn get_overview_stats(
 // CAMPAIGN ENDPOINTS
 // ============================================================================
 
+/// Unactioned replies in the top-priority category. A workspace that has cleared
+/// its priority order has no top category, so every unactioned reply counts.
+async fn count_action_required(pool: &PgPool, workspace_id: Uuid, priority: &[String]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM email_replies
+        WHERE workspace_id = $1 AND is_actioned = FALSE
+          AND (cardinality($2::text[]) = 0 OR intent = ($2::text[])[1])
+        "#
+    )
+    .bind(workspace_id)
+    .bind(priority)
+    .fetch_one(pool)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/founder/campaigns",
+    tag = "founder",
+    responses(
+        (status = 200, body = [CampaignCard]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_campaigns_with_health(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -430,24 +542,37 @@ async fn get_campaigns_with_health(
             CASE WHEN c.sent > 0 THEN (c.replied::FLOAT / c.sent::FLOAT) ELSE 0 END as reply_rate,
             c.created_at
         FROM campaigns c
-        WHERE c.workspace_id = $1
+        WHERE c.workspace_id = $1 AND c.deleted_at IS NULL
         ORDER BY c.created_at DESC
         "#
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(campaigns))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/campaigns/{id}/pause",
+    operation_id = "founder_pause_campaign",
+    tag = "founder",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign paused"),
+        (status = 400, description = "Campaign is not active", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn pause_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
@@ -455,28 +580,40 @@ async fn pause_campaign(
         r#"
         UPDATE campaigns 
         SET status = 'paused', paused_at = NOW()
-        WHERE id = $1 AND workspace_id = $2 AND status = 'active'
+        WHERE id = $1 AND workspace_id = $2 AND status = 'active' AND deleted_at IS NULL
         "#
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"status": "paused"})))
     } else {
-        Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": "Campaign not found or not active"})))
+        Err(ApiError::Validation("Campaign not found or not active".to_string()))
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/campaigns/{id}/resume",
+    tag = "founder",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "Campaign resumed and auto-pause events resolved"),
+        (status = 400, description = "Campaign is not paused", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn resume_campaign(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let campaign_id = path.into_inner();
 
@@ -484,15 +621,18 @@ async fn resume_campaign(
     let result = sqlx::query(
         r#"
         UPDATE campaigns 
-        SET status = 'active', auto_paused = FALSE, auto_pause_reason = NULL, paused_at = NULL
-        WHERE id = $1 AND workspace_id = $2 AND (status = 'paused' OR auto_paused = TRUE)
+        SET status = 'active', auto_paused = FALSE, auto_pause_reason = NULL, paused_at = NULL, metrics_recovered_at = NULL
+        WHERE id = $1 AND workspace_id = $2 AND (status = 'paused' OR auto_paused = TRUE) AND deleted_at IS NULL
         "#
     )
     .bind(campaign_id)
     .bind(workspace_id)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::Validation("Campaign not found or not paused".to_string()));
+    }
 
     // Resolve any pending auto-pause events
     let _ = sqlx::query(
@@ -506,21 +646,27 @@ async fn resume_campaign(
     .execute(pool.get_ref())
     .await;
 
-    if result.rows_affected() > 0 {
-        Ok(HttpResponse::Ok().json(serde_json::json!({"status": "active"})))
-    } else {
-        Ok(HttpResponse::BadRequest().json(serde_json::json!({"error": "Campaign not found or not paused"})))
-    }
+    Ok(HttpResponse::Ok().json(serde_json::json!({"status": "active"})))
 }
 
 // ============================================================================
 // INBOX HEALTH ENDPOINTS
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/inboxes",
+    tag = "founder",
+    responses(
+        (status = 200, body = [InboxHealthCard]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_inbox_health(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -533,6 +679,7 @@ async fn get_inbox_health(
             CASE 
                 WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 'danger'
                 WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 OR ea.reply_rate < 0.02 THEN 'warning'
+                WHEN dh.health_status = 'danger' THEN 'warning'
                 ELSE 'healthy'
             END as health_status,
             ea.health_score,
@@ -540,25 +687,41 @@ async fn get_inbox_health(
             COALESCE(ea.reply_rate, 0) as reply_rate,
             COALESCE(ea.bounce_rate, 0) as bounce_rate,
             ea.daily_limit,
-            ea.sent_today
+            ea.sent_today,
+            email_domain(ea.email) as domain,
+            COALESCE(dh.health_status, 'healthy') as domain_health_status,
+            COALESCE(ea.health_breakdown, 'null'::jsonb) as health_breakdown
         FROM email_accounts ea
+        LEFT JOIN email_domain_health dh
+            ON dh.workspace_id = ea.workspace_id AND dh.domain = email_domain(ea.email)
         WHERE ea.workspace_id = $1
         ORDER BY ea.email
         "#
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(inboxes))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/inboxes/{id}/health",
+    tag = "founder",
+    params(("id" = Uuid, Path, description = "Inbox ID")),
+    responses(
+        (status = 200, body = InboxHealthCard),
+        (status = 404, description = "Inbox not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_inbox_health_detail(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let inbox_id = path.into_inner();
@@ -573,6 +736,7 @@ async fn get_inbox_health_detail(
             CASE 
                 WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 'danger'
                 WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 OR ea.reply_rate < 0.02 THEN 'warning'
+                WHEN dh.health_status = 'danger' THEN 'warning'
                 ELSE 'healthy'
             END as health_status,
             ea.health_score,
@@ -580,32 +744,86 @@ async fn get_inbox_health_detail(
             COALESCE(ea.reply_rate, 0) as reply_rate,
             COALESCE(ea.bounce_rate, 0) as bounce_rate,
             ea.daily_limit,
-            ea.sent_today
+            ea.sent_today,
+            email_domain(ea.email) as domain,
+            COALESCE(dh.health_status, 'healthy') as domain_health_status,
+            COALESCE(ea.health_breakdown, 'null'::jsonb) as health_breakdown
         FROM email_accounts ea
+        LEFT JOIN email_domain_health dh
+            ON dh.workspace_id = ea.workspace_id AND dh.domain = email_domain(ea.email)
         WHERE ea.id = $1 AND ea.workspace_id = $2
         "#
     )
     .bind(inbox_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     match inbox {
         Some(i) => Ok(HttpResponse::Ok().json(i)),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Inbox not found"}))),
+        None => Err(ApiError::NotFound("Inbox not found".to_string())),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/domains/health",
+    tag = "founder",
+    responses(
+        (status = 200, body = [DomainHealthCard]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_domain_health(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let domains = fetch_domain_health(&pool, workspace_id).await?;
+    Ok(HttpResponse::Ok().json(domains))
+}
+
+async fn fetch_domain_health(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<DomainHealthCard>, ApiError> {
+    sqlx::query_as::<_, DomainHealthCard>(
+        r#"
+        SELECT domain, inbox_count, danger_inboxes, health_status,
+               spam_rate, bounce_rate, reply_rate, sent_today
+        FROM email_domain_health
+        WHERE workspace_id = $1
+        ORDER BY
+            CASE health_status WHEN 'danger' THEN 0 WHEN 'warning' THEN 1 ELSE 2 END,
+            domain
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
 // ============================================================================
 // REPLY ENDPOINTS
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/replies",
+    tag = "founder",
+    params(RepliesQuery),
+    responses(
+        (status = 200, body = [ReplyCard]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_replies(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     query: web::Query<RepliesQuery>,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -628,7 +846,9 @@ async fn get_replies(
                 c.name as campaign_name,
                 er.received_at,
                 er.is_read,
-                er.is_actioned
+                er.is_actioned,
+                er.sentiment,
+                er.is_urgent
             FROM email_replies er
             LEFT JOIN campaigns c ON er.campaign_id = c.id
             WHERE er.workspace_id = $1
@@ -656,7 +876,9 @@ async fn get_replies(
                 c.name as campaign_name,
                 er.received_at,
                 er.is_read,
-                er.is_actioned
+                er.is_actioned,
+                er.sentiment,
+                er.is_urgent
             FROM email_replies er
             LEFT JOIN campaigns c ON er.campaign_id = c.id
             WHERE er.workspace_id = $1 AND er.intent = $4
@@ -672,29 +894,43 @@ async fn get_replies(
         .await
     };
 
-    let replies = replies.map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let replies = replies?;
     Ok(HttpResponse::Ok().json(replies))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct RepliesQuery {
     pub intent: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ActionReplyRequest {
     pub action: String,  // replied, booked_meeting, snoozed, archived
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/replies/{id}/action",
+    tag = "founder",
+    params(("id" = Uuid, Path, description = "Reply ID")),
+    request_body = ActionReplyRequest,
+    responses(
+        (status = 200, description = "Reply marked as actioned"),
+        (status = 404, description = "Reply not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn action_reply(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     body: web::Json<ActionReplyRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let reply_id = path.into_inner();
 
@@ -709,63 +945,90 @@ async fn action_reply(
     .bind(workspace_id)
     .bind(&body.action)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"success": true, "action": body.action})))
     } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Reply not found"})))
+        Err(ApiError::NotFound("Reply not found".to_string()))
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/replies/classify",
+    tag = "founder",
+    request_body = ClassifyReplyRequest,
+    responses(
+        (status = 200, description = "Classified intent, confidence, sentiment and urgency"),
+        (status = 404, description = "Reply not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn classify_reply(
     pool: web::Data<PgPool>,
     body: web::Json<ClassifyReplyRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
 
     // Get the reply text
-    let reply: Option<(String,)> = sqlx::query_as(
-        "SELECT body_text FROM email_replies WHERE id = $1 AND workspace_id = $2"
+    let reply: Option<(Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT subject, body_text FROM email_replies WHERE id = $1 AND workspace_id = $2"
     )
     .bind(body.reply_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
-    let reply_text = match reply {
-        Some((text,)) => text,
-        None => return Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Reply not found"}))),
+    let (subject, reply_text) = match reply {
+        Some((subject, text)) => (subject, text.unwrap_or_default()),
+        None => return Err(ApiError::NotFound("Reply not found".to_string())),
     };
 
-    // Classify using Claude (this will be called from the service)
-    let (intent, confidence) = crate::services::reply_classifier::classify_reply(&reply_text).await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    // Out-of-office notices and bounces don't need a model call. Anything else is
+    // classified using Claude against the workspace's active categories, or the
+    // rules without it.
+    let classification = if reply_classifier::is_auto_reply(&[], subject.as_deref().unwrap_or(""), &reply_text) {
+        reply_classifier::auto_reply_classification()
+    } else {
+        let reply_config = reply_classifier::load_workspace_config(pool.get_ref(), workspace_id).await?;
+        reply_classifier::classify_reply_or_fallback(&reply_text, &reply_config.categories()).await
+    };
 
     // Update the reply with classification
     sqlx::query(
         r#"
         UPDATE email_replies 
-        SET intent = $3, intent_confidence = $4, classified_at = NOW()
+        SET intent = $3, intent_confidence = $4, sentiment = $5, is_urgent = $6, classified_at = NOW()
         WHERE id = $1 AND workspace_id = $2
         "#
     )
     .bind(body.reply_id)
     .bind(workspace_id)
-    .bind(&intent)
-    .bind(confidence)
+    .bind(&classification.intent)
+    .bind(classification.confidence)
+    .bind(classification.sentiment)
+    .bind(classification.urgent)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
+
+    if classification.intent == "interested" {
+        campaign_scheduler::stop_sequence_for_reply(pool.get_ref(), body.reply_id, "interested").await?;
+        if let Err(e) = zapier::emit_reply_interested(pool.get_ref(), workspace_id, body.reply_id).await {
+            tracing::warn!("Failed to queue Zapier hooks for reply {}: {}", body.reply_id, e);
+        }
+    }
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "reply_id": body.reply_id,
-        "intent": intent,
-        "confidence": confidence
+        "intent": classification.intent,
+        "confidence": classification.confidence,
+        "sentiment": classification.sentiment,
+        "urgent": classification.urgent
     })))
 }
 
@@ -773,10 +1036,20 @@ async fn classify_reply(
 // AUTO-PAUSE EVENTS
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/auto-pause-events",
+    tag = "founder",
+    responses(
+        (status = 200, body = [AutoPauseEvent]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_auto_pause_events(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -799,18 +1072,30 @@ async fn get_auto_pause_events(
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(events))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/auto-pause-events/{id}/resolve",
+    tag = "founder",
+    params(("id" = Uuid, Path, description = "Auto-pause event ID")),
+    responses(
+        (status = 200, description = "Event resolved"),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn resolve_pause_event(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let event_id = path.into_inner();
 
@@ -824,13 +1109,12 @@ async fn resolve_pause_event(
     .bind(event_id)
     .bind(workspace_id)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"resolved": true})))
     } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Event not found"})))
+        Err(ApiError::NotFound("Event not found".to_string()))
     }
 }
 
@@ -838,10 +1122,20 @@ async fn resolve_pause_event(
 // COST TRACKING
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/costs",
+    tag = "founder",
+    responses(
+        (status = 200, body = CostPerMeetingStats),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_cost_stats(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -855,8 +1149,7 @@ async fn get_cost_stats(
     )
     .bind(workspace_id)
     .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // Previous period
     let previous: (Option<f64>, Option<i32>) = sqlx::query_as(
@@ -870,8 +1163,7 @@ async fn get_cost_stats(
     )
     .bind(workspace_id)
     .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     let current_cost = current.0.unwrap_or(0.0);
     let current_meetings = current.1.unwrap_or(0).max(1);
@@ -909,8 +1201,7 @@ async fn get_cost_stats(
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     let stats = CostPerMeetingStats {
         current_period: current_cpm,
@@ -924,12 +1215,24 @@ async fn get_cost_stats(
     Ok(HttpResponse::Ok().json(stats))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/costs",
+    tag = "founder",
+    request_body = UpdateCostsRequest,
+    responses(
+        (status = 200, description = "Whether a campaign cost row was updated"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn update_costs(
     pool: web::Data<PgPool>,
     body: web::Json<UpdateCostsRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
 
     // Upsert costs for current month
@@ -955,8 +1258,7 @@ async fn update_costs(
     .bind(body.tool_cost)
     .bind(body.other_cost)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({"updated": result.rows_affected() > 0})))
 }
@@ -965,7 +1267,7 @@ async fn update_costs(
 // MEETINGS
 // ============================================================================
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct Meeting {
     pub id: Uuid,
     pub campaign_id: Option<Uuid>,
@@ -978,10 +1280,20 @@ pub struct Meeting {
     pub outcome: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/meetings",
+    tag = "founder",
+    responses(
+        (status = 200, description = "The 50 most recent meetings", body = [Meeting]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_meetings(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -1007,34 +1319,53 @@ async fn get_meetings(
     )
     .bind(workspace_id)
     .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(meetings))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateMeetingRequest {
     pub campaign_id: Option<Uuid>,
     pub lead_id: Option<Uuid>,
     pub reply_id: Option<Uuid>,
     pub title: Option<String>,
     pub scheduled_at: Option<DateTime<Utc>>,
+    /// Offset from UTC the meeting was booked in, used for the local time shown
+    /// in reminders. Defaults to the lead's timezone.
+    pub timezone_offset_minutes: Option<i32>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/founder/meetings",
+    tag = "founder",
+    request_body = CreateMeetingRequest,
+    responses(
+        (status = 201, description = "Meeting created"),
+        (status = 400, description = "Invalid timezone offset", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn create_meeting(
     pool: web::Data<PgPool>,
     body: web::Json<CreateMeetingRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let meeting_id = Uuid::new_v4();
 
+    if body.timezone_offset_minutes.is_some_and(|offset| !(-720..=840).contains(&offset)) {
+        return Err(ApiError::Validation("timezone_offset_minutes must be between -720 and 840".to_string()));
+    }
+
     sqlx::query(
         r#"
-        INSERT INTO meetings (id, workspace_id, campaign_id, lead_id, reply_id, title, scheduled_at, status)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, 'scheduled')
+        INSERT INTO meetings (id, workspace_id, campaign_id, lead_id, reply_id, title, scheduled_at, timezone_offset_minutes, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'scheduled')
         "#
     )
     .bind(meeting_id)
@@ -1044,9 +1375,9 @@ async fn create_meeting(
     .bind(body.reply_id)
     .bind(&body.title)
     .bind(body.scheduled_at)
+    .bind(body.timezone_offset_minutes)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     // Update campaign meetings count
     if let Some(campaign_id) = body.campaign_id {
@@ -1071,6 +1402,10 @@ async fn create_meeting(
         .await;
     }
 
+    if let Err(e) = zapier::emit_meeting_created(pool.get_ref(), workspace_id, meeting_id).await {
+        tracing::warn!("Failed to queue Zapier hooks for meeting {}: {}", meeting_id, e);
+    }
+
     Ok(HttpResponse::Created().json(serde_json::json!({"id": meeting_id})))
 }
 
@@ -1078,10 +1413,20 @@ async fn create_meeting(
 // SETTINGS
 // ============================================================================
 
+#[utoipa::path(
+    get,
+    path = "/api/founder/settings",
+    tag = "founder",
+    responses(
+        (status = 200, description = "Workspace settings, or defaults if none are saved", body = WorkspaceSettings),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_settings(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
 
@@ -1092,19 +1437,25 @@ async fn get_settings(
             spam_rate_threshold,
             reply_drop_threshold,
             bounce_rate_threshold,
+            COALESCE(deliverability_score_threshold, 75.0) as deliverability_score_threshold,
             google_daily_limit,
             outlook_daily_limit,
             zoho_daily_limit,
             notification_email,
-            slack_webhook_url
+            slack_webhook_url,
+            reply_categories,
+            reply_category_priority,
+            event_retention_months,
+            reply_retention_months,
+            auto_resume_enabled,
+            auto_resume_cooldown_hours
         FROM workspace_settings
         WHERE workspace_id = $1
         "#
     )
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     match settings {
         Some(s) => Ok(HttpResponse::Ok().json(s)),
@@ -1115,28 +1466,83 @@ async fn get_settings(
                 spam_rate_threshold: 0.03,
                 reply_drop_threshold: 0.40,
                 bounce_rate_threshold: 0.08,
+                deliverability_score_threshold: 75.0,
                 google_daily_limit: 500,
                 outlook_daily_limit: 300,
                 zoho_daily_limit: 200,
                 notification_email: None,
                 slack_webhook_url: None,
+                reply_categories: Vec::new(),
+                reply_category_priority: reply_classifier::default_priority(),
+                event_retention_months: None,
+                reply_retention_months: None,
+                auto_resume_enabled: false,
+                auto_resume_cooldown_hours: auto_pause::DEFAULT_AUTO_RESUME_COOLDOWN_HOURS,
             }))
         }
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/founder/settings",
+    tag = "founder",
+    request_body = UpdateSettingsRequest,
+    responses(
+        (status = 200, description = "Settings saved"),
+        (status = 400, description = "Invalid settings", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn update_settings(
     pool: web::Data<PgPool>,
     body: web::Json<UpdateSettingsRequest>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
 
+    for months in [body.event_retention_months, body.reply_retention_months].into_iter().flatten() {
+        if months != 0 && !data_retention::is_valid_retention_months(months) {
+            return Err(ApiError::Validation(format!(
+                "Retention must be between 1 and {} months, or 0 to keep data forever",
+                data_retention::MAX_RETENTION_MONTHS
+            )));
+        }
+    }
+
+    if let Some(url) = body.slack_webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        if !pause_notifications::is_valid_slack_webhook_url(url) {
+            return Err(ApiError::Validation(
+                "slack_webhook_url must be a Slack incoming webhook (https://hooks.slack.com/...)".to_string(),
+            ));
+        }
+    }
+
+    if let Some(hours) = body.auto_resume_cooldown_hours {
+        if !(1..=auto_pause::MAX_AUTO_RESUME_COOLDOWN_HOURS).contains(&hours) {
+            return Err(ApiError::Validation(format!(
+                "Auto-resume cooldown must be between 1 and {} hours",
+                auto_pause::MAX_AUTO_RESUME_COOLDOWN_HOURS
+            )));
+        }
+    }
+
+    // Only touch the category columns when the request changes them, so other
+    // settings updates never rewrite (or reset) them
+    let reply_categories = if body.reply_categories.is_some() || body.reply_category_priority.is_some() {
+        Some(resolve_reply_categories(pool.get_ref(), workspace_id, &body).await?)
+    } else {
+        None
+    };
+    let (custom_categories, priority) = reply_categories.unzip();
+
     sqlx::query(
         r#"
-        INSERT INTO workspace_settings (workspace_id, auto_pause_enabled, spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold, notification_email, slack_webhook_url)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO workspace_settings (workspace_id, auto_pause_enabled, spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold, notification_email, slack_webhook_url, deliverability_score_threshold, reply_categories, reply_category_priority, event_retention_months, reply_retention_months, auto_resume_enabled, auto_resume_cooldown_hours)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, '[]'::jsonb), COALESCE($10, ARRAY['interested', 'objection', 'maybe_later']), NULLIF($11, 0), NULLIF($12, 0), COALESCE($13, FALSE), COALESCE($14, 24))
         ON CONFLICT (workspace_id) 
         DO UPDATE SET 
             auto_pause_enabled = COALESCE($2, workspace_settings.auto_pause_enabled),
@@ -1145,6 +1551,13 @@ async fn update_settings(
             bounce_rate_threshold = COALESCE($5, workspace_settings.bounce_rate_threshold),
             notification_email = COALESCE($6, workspace_settings.notification_email),
             slack_webhook_url = COALESCE($7, workspace_settings.slack_webhook_url),
+            deliverability_score_threshold = COALESCE($8, workspace_settings.deliverability_score_threshold),
+            reply_categories = COALESCE($9, workspace_settings.reply_categories),
+            reply_category_priority = COALESCE($10, workspace_settings.reply_category_priority),
+            event_retention_months = CASE WHEN $11::int IS NULL THEN workspace_settings.event_retention_months ELSE NULLIF($11, 0) END,
+            reply_retention_months = CASE WHEN $12::int IS NULL THEN workspace_settings.reply_retention_months ELSE NULLIF($12, 0) END,
+            auto_resume_enabled = COALESCE($13, workspace_settings.auto_resume_enabled),
+            auto_resume_cooldown_hours = COALESCE($14, workspace_settings.auto_resume_cooldown_hours),
             updated_at = NOW()
         "#
     )
@@ -1155,9 +1568,171 @@ async fn update_settings(
     .bind(body.bounce_rate_threshold)
     .bind(&body.notification_email)
     .bind(&body.slack_webhook_url)
+    .bind(body.deliverability_score_threshold)
+    .bind(custom_categories.map(sqlx::types::Json))
+    .bind(priority)
+    .bind(body.event_retention_months)
+    .bind(body.reply_retention_months)
+    .bind(body.auto_resume_enabled)
+    .bind(body.auto_resume_cooldown_hours)
     .execute(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({"updated": true})))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/founder/retention-runs",
+    tag = "founder",
+    responses(
+        (status = 200, description = "The 50 most recent data retention purges, newest first", body = [PurgeRun]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_retention_runs(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let runs = data_retention::recent_runs(pool.get_ref(), workspace_id, 50).await?;
+
+    Ok(HttpResponse::Ok().json(runs))
+}
+
+/// Validates requested category changes against the stored ones and returns the
+/// custom categories and priority order to save. Labels dropped from the custom
+/// set are also dropped from a stored priority order.
+async fn resolve_reply_categories(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    body: &UpdateSettingsRequest,
+) -> Result<(Vec<ReplyCategory>, Vec<String>), ApiError> {
+    let current = reply_classifier::load_workspace_config(pool, workspace_id).await?;
+
+    let custom = match &body.reply_categories {
+        Some(custom) => {
+            reply_classifier::validate_custom_categories(custom).map_err(ApiError::Validation)?;
+            custom.clone()
+        }
+        None => current.custom,
+    };
+
+    let mut active = reply_classifier::default_categories();
+    active.extend(custom.iter().cloned());
+
+    let priority = match &body.reply_category_priority {
+        Some(priority) => {
+            reply_classifier::validate_priority(priority, &active).map_err(ApiError::Validation)?;
+            priority.clone()
+        }
+        None => current
+            .priority
+            .into_iter()
+            .filter(|label| active.iter().any(|c| &c.label == label))
+            .collect(),
+    };
+
+    Ok((custom, priority))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use actix_web::{http::StatusCode, test, App};
+    use crate::middleware::auth::{AuthMiddleware, Claims};
+    use crate::services::jwt_keys::jwt_keys;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_action_required_counts_the_top_category_or_everything_without_a_priority() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Action required").await;
+        for (intent, actioned) in [("interested", false), ("interested", true), ("objection", false), ("pricing", false)] {
+            sqlx::query(
+                "INSERT INTO email_replies (workspace_id, from_email, intent, is_actioned) VALUES ($1, 'prospect@example.com', $2, $3)"
+            )
+            .bind(workspace_id)
+            .bind(intent)
+            .bind(actioned)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let priority = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let defaults = count_action_required(&pool, workspace_id, &reply_classifier::default_priority()).await;
+        let custom = count_action_required(&pool, workspace_id, &priority(&["pricing", "interested"])).await;
+        let empty = count_action_required(&pool, workspace_id, &[]).await;
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(defaults.unwrap(), 1);
+        assert_eq!(custom.unwrap(), 1);
+        assert_eq!(empty.unwrap(), 3);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[actix_web::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_viewer_cannot_resume_a_campaign_from_the_dashboard() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Founder resume roles").await;
+        let campaign_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO campaigns (id, workspace_id, name, vertical, status) VALUES ($1, $2, 'Held', 'saas', 'paused')")
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (user_id, _) = test_db::user(&pool, "hash").await;
+        sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, $3)")
+            .bind(workspace_id)
+            .bind(user_id)
+            .bind(WorkspaceRole::Viewer.as_str())
+            .execute(&pool)
+            .await
+            .unwrap();
+        let now = Utc::now().timestamp() as usize;
+        let token = jwt_keys()
+            .encode(&Claims {
+                sub: user_id.to_string(),
+                user_id: user_id.to_string(),
+                workspace_id: Some(workspace_id.to_string()),
+                role: "user".to_string(),
+                exp: now + 3600,
+                iat: now,
+            })
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(AuthMiddleware)
+                .service(web::scope("/api").configure(configure)),
+        )
+        .await;
+        let request = test::TestRequest::post()
+            .uri(&format!("/api/founder/campaigns/{}/resume", campaign_id))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let status = test::call_service(&app, request).await.status();
+        let campaign_status: String = sqlx::query_scalar("SELECT status FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_user(&pool, user_id).await;
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(campaign_status, "paused");
+    }
+}
@@ -0,0 +1,61 @@
+use actix_web::{web, HttpResponse, HttpRequest};
+use sqlx::PgPool;
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, require_role};
+use crate::services::job_queue;
+
+// ============================================================================
+// Background job inspection (platform admins)
+// Jobs that used up their retries stay in the table as `failed`. These
+// endpoints list them and put them back in the queue once the cause is fixed.
+// ============================================================================
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/jobs")
+            .route("/failed", web::get().to(get_failed_jobs))
+            .route("/{id}/requeue", web::post().to(requeue_job))
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FailedJobsQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/jobs/failed - Failed jobs with their payload and last error
+async fn get_failed_jobs(
+    pool: web::Data<PgPool>,
+    query: web::Query<FailedJobsQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let jobs = job_queue::failed_jobs(pool.get_ref(), limit).await?;
+
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+/// POST /api/jobs/{id}/requeue - Puts a failed job back in the queue with fresh retries
+async fn requeue_job(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    let job_id = path.into_inner();
+
+    let requeued = job_queue::requeue_job(pool.get_ref(), job_id).await?;
+
+    if requeued {
+        tracing::info!("Job {} requeued by admin {}", job_id, claims.user_id);
+        Ok(HttpResponse::Ok().json(serde_json::json!({"requeued": true, "id": job_id})))
+    } else {
+        Err(ApiError::NotFound("Failed job not found".to_string()))
+    }
+}
@@ -1,148 +1,270 @@
 use actix_web::{web, HttpResponse, HttpRequest};
 use sqlx::PgPool;
 use uuid::Uuid;
-use crate::models::lead::{Lead, LeadSearchQuery};
-use crate::services::lead_generator::LeadGenerator;
-use crate::services::email_verifier::EmailVerifier;
-use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use actix_multipart::Multipart;
+use futures_util::StreamExt;
+use crate::models::lead::{Lead, LeadSearchQuery, LeadTagsRequest, VerificationStatus};
+use crate::services::lead_generator::{self, LeadGenerator};
+use crate::services::email_verifier::{EmailVerifier, PgVerificationCache};
+use crate::services::company_discovery;
+use crate::services::lead_quota::{self, Reservation};
+use crate::services::lead_tags;
+use crate::services::lead_dedup::{self, DuplicateGroup, MergeOutcome};
+use crate::services::lead_list::{self, LeadFilter, DEFAULT_LEAD_PAGE_SIZE};
+use crate::models::pagination::PageQuery;
+use crate::services::lead_import::{self, RejectedRow};
+use crate::services::export::{self, ExportQuery, LeadExportRow};
+use crate::api::error::{ApiError, ErrorResponse};
+use crate::models::signal::Signal;
+use crate::services::lead_generator::GeneratedLead;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/leads")
             .route("", web::get().to(get_leads))
+            .route("/import", web::post().to(import_leads))
+            .route("/export", web::get().to(export_leads))
+            .route("/duplicates", web::get().to(get_duplicate_leads))
+            .route("/merge", web::post().to(merge_leads))
             .route("/{id}", web::get().to(get_lead_by_id))
             .route("/search", web::post().to(search_leads))
             .route("/verify", web::post().to(verify_leads))
             .route("/signals/{domain}", web::get().to(get_signals))
             .route("/{id}", web::delete().to(delete_lead))
+            .route("/{id}/restore", web::post().to(restore_lead))
+            .route("/{id}/tags", web::get().to(get_lead_tags))
+            .route("/{id}/tags", web::post().to(add_lead_tags))
+            .route("/{id}/tags", web::delete().to(remove_lead_tag))
     );
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_leads,
+    get_lead_by_id,
+    search_leads,
+    verify_leads,
+    import_leads,
+    export_leads,
+    get_duplicate_leads,
+    merge_leads,
+    get_signals,
+    delete_lead,
+    restore_lead,
+    get_lead_tags,
+    add_lead_tags,
+    remove_lead_tag,
+))]
+pub struct LeadsApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LeadListQuery {
+    /// Page size, 100 by default and at most 500
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Only leads with this tag
+    pub tag: Option<String>,
+    /// Only leads with this verification status: `pending`, `valid`, `invalid` or `risky`
+    pub status: Option<String>,
+    /// Only leads at this company, ignoring case
+    pub company: Option<String>,
+    /// Text to find in the email, name or company
+    pub q: Option<String>,
+}
+
+/// A page of leads and how many match in total
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeadPage {
+    pub data: Vec<Lead>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leads",
+    tag = "leads",
+    params(LeadListQuery),
+    responses(
+        (status = 200, description = "A page of leads, newest first", body = LeadPage),
+        (status = 400, description = "Unknown verification status", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_leads(
     pool: web::Data<PgPool>,
+    query: web::Query<LeadListQuery>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
+    let query = query.into_inner();
 
-    let leads = sqlx::query_as::<_, Lead>(
-        "SELECT * FROM leads WHERE workspace_id = $1 ORDER BY created_at DESC LIMIT 100"
-    )
-    .bind(workspace_id)
-    .fetch_all(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let status = match query.status.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(status) => Some(
+            VerificationStatus::parse(status)
+                .ok_or_else(|| ApiError::Validation(format!("Unknown verification status: {}", status)))?,
+        ),
+        None => None,
+    };
+    let filter = LeadFilter {
+        // A tag that can't exist matches nothing rather than being ignored
+        tag: query.tag.as_deref().map(|t| lead_tags::normalize_tag(t).unwrap_or_default()),
+        status,
+        company: query.company,
+        search: query.q,
+    };
+    let page = PageQuery {
+        limit: Some(query.limit.unwrap_or(DEFAULT_LEAD_PAGE_SIZE)),
+        offset: query.offset,
+    };
 
-    Ok(HttpResponse::Ok().json(leads))
+    let leads = lead_list::list(pool.get_ref(), workspace_id, &filter, &page).await?;
+
+    Ok(HttpResponse::Ok().json(LeadPage {
+        data: leads.data,
+        total: leads.total,
+        limit: leads.limit,
+        offset: leads.offset,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/leads/{id}",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID")),
+    responses(
+        (status = 200, body = Lead),
+        (status = 404, description = "Lead not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_lead_by_id(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let claims = extract_claims(&req)?;
     let workspace_id = parse_workspace_id(&claims)?;
     let lead_id = path.into_inner();
 
     let lead = sqlx::query_as::<_, Lead>(
-        "SELECT * FROM leads WHERE id = $1 AND workspace_id = $2"
+        "SELECT * FROM leads WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
     )
     .bind(lead_id)
     .bind(workspace_id)
     .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .await?;
 
     match lead {
         Some(lead) => Ok(HttpResponse::Ok().json(lead)),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Lead not found"}))),
+        None => Err(ApiError::NotFound("Lead not found".to_string())),
     }
 }
 
+/// Largest number of leads a single search may generate
+const MAX_SEARCH_LEADS: i64 = 500;
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/search",
+    tag = "leads",
+    request_body = LeadSearchQuery,
+    responses(
+        (status = 200, description = "Generated and verified leads", body = [GeneratedLead]),
+        (status = 400, description = "limit is outside 1 to 500", body = ErrorResponse),
+        (status = 402, description = "The requested number of leads would exceed the monthly lead limit"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn search_leads(
     query: web::Json<LeadSearchQuery>,
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    // Generated leads are saved to the workspace and count against its monthly quota
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
 
-    // Check usage limits
-    let usage_count: (i64,) = sqlx::query_as(
-        r#"
-        SELECT COUNT(*) FROM leads
-        WHERE workspace_id = $1 
-        AND created_at >= date_trunc('month', NOW())
-        "#
-    )
-    .bind(workspace_id)
-    .fetch_one(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-
-    let workspace_limit: Option<(i32,)> = sqlx::query_as(
-        "SELECT monthly_lead_limit FROM workspaces WHERE id = $1"
-    )
-    .bind(workspace_id)
-    .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    let requested = query.limit.unwrap_or(50);
+    if !(1..=MAX_SEARCH_LEADS).contains(&requested) {
+        return Err(ApiError::Validation(format!("limit must be between 1 and {}", MAX_SEARCH_LEADS)));
+    }
 
-    if let Some((limit,)) = workspace_limit {
-        if usage_count.0 >= limit as i64 {
-            return Ok(HttpResponse::PaymentRequired().json(
-                serde_json::json!({"error": "Monthly lead limit exceeded", "limit": limit, "used": usage_count.0})
-            ));
+    // Reserve the whole request against the monthly limit up front
+    let reservation_id = match lead_quota::reserve(pool.get_ref(), workspace_id, requested).await? {
+        Reservation::Granted(id) => id,
+        Reservation::LimitExceeded { limit, used } => {
+            return Ok(HttpResponse::PaymentRequired().json(serde_json::json!({
+                "error": "Monthly lead limit exceeded",
+                "limit": limit,
+                "used": used,
+                "remaining": (limit - used).max(0)
+            })));
         }
+    };
+
+    let result = generate_and_store_leads(pool.get_ref(), workspace_id, &query, requested).await;
+
+    // Stored leads now count on their own, so the reservation can go either way
+    if let Err(e) = lead_quota::release(pool.get_ref(), workspace_id, reservation_id).await {
+        tracing::warn!("Failed to release lead reservation: {}", e);
     }
 
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+async fn generate_and_store_leads(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    query: &LeadSearchQuery,
+    requested: i64,
+) -> Result<Vec<GeneratedLead>, ApiError> {
     let generator = LeadGenerator::new();
     
     let mut leads = generator.generate_leads(
         &query.vertical,
         query.role.as_deref(),
-        query.limit.unwrap_or(50) as usize,
+        usize::try_from(requested).map_err(ApiError::internal)?,
     )
     .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    .map_err(ApiError::internal)?;
 
     // Verify emails
     if let Ok(verifier) = EmailVerifier::new().await {
-        for lead in &mut leads {
-            let (status, confidence) = verifier.verify_email(&lead.email).await;
-            lead.verification_status = status;
-            lead.confidence_score = confidence;
-        }
+        let verifier = verifier.with_cache(Arc::new(PgVerificationCache::new(Arc::new(pool.clone()))));
+        let verifier = &verifier;
+        lead_generator::verify_leads(&mut leads, |email| async move { verifier.verify_email(&email).await }).await;
     }
 
     // Store in database with workspace_id
+    if let Err(e) = lead_generator::store_leads(pool, workspace_id, &leads).await {
+        tracing::warn!("Failed to store generated leads for workspace {}: {}", workspace_id, e);
+    }
+
+    // Track each lead's company so the signal pipeline can enrich it later
     for lead in &leads {
-        let _ = sqlx::query(
-            r#"
-            INSERT INTO leads (id, workspace_id, email, first_name, last_name, company, title, 
-                              linkedin_url, verification_status, confidence_score, signals, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            ON CONFLICT (workspace_id, email) DO UPDATE SET
-                verification_status = EXCLUDED.verification_status,
-                confidence_score = EXCLUDED.confidence_score,
-                signals = EXCLUDED.signals
-            "#
+        if let Err(e) = company_discovery::upsert_from_lead(
+            pool,
+            &lead.email,
+            lead.company.as_deref(),
+            Some(&query.vertical),
         )
-        .bind(&lead.id)
-        .bind(workspace_id)
-        .bind(&lead.email)
-        .bind(&lead.first_name)
-        .bind(&lead.last_name)
-        .bind(&lead.company)
-        .bind(&lead.title)
-        .bind(&lead.linkedin_url)
-        .bind(lead.verification_status.as_str())
-        .bind(lead.confidence_score)
-        .bind(&lead.signals)
-        .bind(lead.created_at)
-        .execute(pool.get_ref())
-        .await;
+        .await
+        {
+            tracing::warn!("Company discovery failed for {}: {}", lead.email, e);
+        }
     }
 
     // Track usage
@@ -156,27 +278,177 @@ async fn search_leads(
     )
     .bind(workspace_id)
     .bind(leads.len() as i32)
-    .execute(pool.get_ref())
+    .execute(pool)
     .await;
 
-    Ok(HttpResponse::Ok().json(leads))
+    Ok(leads)
+}
+
+/// Largest lead list accepted in one upload
+const MAX_IMPORT_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeadImportResponse {
+    pub imported: u64,
+    /// Rows skipped because the address was already in the workspace or earlier in the file
+    pub duplicates: u64,
+    pub rejected: Vec<RejectedRow>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/import",
+    tag = "leads",
+    request_body(content = String, content_type = "multipart/form-data", description = "CSV file in a `file` field"),
+    responses(
+        (status = 200, description = "Rows imported, skipped as duplicates and rejected", body = LeadImportResponse),
+        (status = 400, description = "No CSV file, or no email column in it", body = ErrorResponse),
+        (status = 402, description = "The new leads would exceed the monthly lead limit"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn import_leads(
+    pool: web::Data<PgPool>,
+    payload: Multipart,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let data = read_csv_upload(payload).await?;
+    let parsed = lead_import::parse_csv(&data).map_err(ApiError::Validation)?;
+
+    // Only addresses the workspace doesn't have yet count against the limit, and
+    // the whole file is refused rather than imported partway
+    let existing = lead_import::count_existing(pool.get_ref(), workspace_id, &parsed.leads).await?;
+    let new_leads = parsed.leads.len() as i64 - existing;
+
+    let reservation_id = match lead_quota::reserve(pool.get_ref(), workspace_id, new_leads).await? {
+        Reservation::Granted(id) => id,
+        Reservation::LimitExceeded { limit, used } => {
+            return Ok(HttpResponse::PaymentRequired().json(serde_json::json!({
+                "error": "Importing these leads would exceed the monthly lead limit",
+                "limit": limit,
+                "used": used,
+                "remaining": (limit - used).max(0),
+                "requested": new_leads
+            })));
+        }
+    };
+
+    let inserted = lead_import::insert_leads(pool.get_ref(), workspace_id, &parsed.leads).await;
+
+    if let Err(e) = lead_quota::release(pool.get_ref(), workspace_id, reservation_id).await {
+        tracing::warn!("Failed to release lead reservation: {}", e);
+    }
+    let imported = inserted?;
+
+    // Track the new leads' companies so the signal pipeline can enrich them later
+    if imported > 0 {
+        if let Err(e) = company_discovery::discover_from_leads(pool.get_ref(), workspace_id).await {
+            tracing::warn!("Company discovery failed after importing leads for workspace {}: {}", workspace_id, e);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(LeadImportResponse {
+        imported,
+        duplicates: parsed.duplicates as u64 + (parsed.leads.len() as u64 - imported),
+        rejected: parsed.rejected,
+    }))
 }
 
+/// Reads the uploaded file: the `file` field, or else the first field carrying a filename
+async fn read_csv_upload(mut payload: Multipart) -> Result<Vec<u8>, ApiError> {
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| ApiError::Validation(format!("Invalid upload: {}", e)))?;
+        let is_file = field.name() == Some("file")
+            || field.content_disposition().and_then(|cd| cd.get_filename()).is_some();
+        if !is_file {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| ApiError::Validation(format!("Invalid upload: {}", e)))?;
+            if data.len() + chunk.len() > MAX_IMPORT_BYTES {
+                return Err(ApiError::Validation("CSV file is larger than 10 MB".to_string()));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        return Ok(data);
+    }
+
+    Err(ApiError::Validation("Upload a CSV file in the `file` field".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leads/export",
+    tag = "leads",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Every lead in the workspace as CSV or XLSX"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn export_leads(
+    pool: web::Data<PgPool>,
+    query: web::Query<ExportQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    export::respond::<LeadExportRow>(
+        pool.get_ref().clone(),
+        workspace_id,
+        query.format,
+        &format!("leads-{}", chrono::Utc::now().format("%Y-%m-%d")),
+        "Leads",
+    )
+    .await
+    .map_err(ApiError::internal)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VerifyLeadsQuery {
+    /// Re-check every address even if a fresh cached result exists
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/verify",
+    tag = "leads",
+    params(VerifyLeadsQuery),
+    request_body = Vec<String>,
+    responses(
+        (status = 200, description = "Status and confidence per address"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn verify_leads(
     emails: web::Json<Vec<String>>,
-    _pool: web::Data<PgPool>,
+    query: web::Query<VerifyLeadsQuery>,
+    pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let _claims = extract_claims(&req)?;
 
     let verifier = EmailVerifier::new()
         .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        .map_err(ApiError::internal)?
+        .with_cache(Arc::new(PgVerificationCache::new(Arc::new(pool.get_ref().clone()))));
     
     let mut results = Vec::new();
 
     for email in emails.iter() {
-        let (status, confidence) = verifier.verify_email(email).await;
+        let (status, confidence) = verifier.verify_email_with(email, query.force).await;
         results.push(serde_json::json!({
             "email": email,
             "status": status.as_str(),
@@ -187,55 +459,286 @@ async fn verify_leads(
     Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/leads/signals/{domain}",
+    tag = "leads",
+    params(("domain" = String, Path, description = "Company domain")),
+    responses(
+        (status = 200, body = [Signal]),
+        (status = 404, description = "Company not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn get_signals(
     pool: web::Data<PgPool>,
     path: web::Path<String>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
+) -> Result<HttpResponse, ApiError> {
     let _claims = extract_claims(&req)?;
 
     let domain = path.into_inner();
     
     // Find company by domain and get its signals
     let company = crate::models::company::Company::find_by_domain(pool.get_ref(), &domain)
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+        .await?;
 
     match company {
         Some(company) => {
-            let signals = crate::models::signal::Signal::find_by_company(pool.get_ref(), company.id)
-                .await
-                .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+            let signals = Signal::find_by_company(pool.get_ref(), company.id)
+                .await?;
             Ok(HttpResponse::Ok().json(signals))
         }
         None => {
-            Ok(HttpResponse::NotFound().json(serde_json::json!({
-                "error": "Company not found",
-                "domain": domain
-            })))
+            Err(ApiError::NotFound(format!("Company not found for domain {}", domain)))
         }
     }
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DuplicateLeadsResponse {
+    pub groups: Vec<DuplicateGroup>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leads/duplicates",
+    tag = "leads",
+    responses(
+        (status = 200, description = "Groups of leads that look like the same person", body = DuplicateLeadsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_duplicate_leads(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let groups = lead_dedup::find_duplicates(pool.get_ref(), workspace_id).await?;
+
+    Ok(HttpResponse::Ok().json(DuplicateLeadsResponse { groups }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeLeadsRequest {
+    /// The leads to merge; the one with the highest confidence is kept
+    pub lead_ids: Vec<Uuid>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/merge",
+    tag = "leads",
+    request_body = MergeLeadsRequest,
+    responses(
+        (status = 200, description = "Leads merged into one", body = MergeOutcome),
+        (status = 400, description = "Fewer than two distinct leads", body = ErrorResponse),
+        (status = 404, description = "A lead was not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn merge_leads(
+    pool: web::Data<PgPool>,
+    body: web::Json<MergeLeadsRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let mut lead_ids = body.into_inner().lead_ids;
+    lead_ids.sort();
+    lead_ids.dedup();
+    if lead_ids.len() < 2 {
+        return Err(ApiError::Validation("Merging needs at least two distinct leads".to_string()));
+    }
+
+    match lead_dedup::merge(pool.get_ref(), workspace_id, &lead_ids).await? {
+        Some(outcome) => Ok(HttpResponse::Ok().json(outcome)),
+        None => Err(ApiError::NotFound("Lead not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/leads/{id}",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID")),
+    responses(
+        (status = 200, description = "Lead moved to trash and excluded from future sends"),
+        (status = 404, description = "Lead not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn delete_lead(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
     req: HttpRequest,
-) -> Result<HttpResponse, actix_web::Error> {
-    let claims = extract_claims(&req)?;
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
     let workspace_id = parse_workspace_id(&claims)?;
     let lead_id = path.into_inner();
 
-    let result = sqlx::query("DELETE FROM leads WHERE id = $1 AND workspace_id = $2")
-        .bind(lead_id)
-        .bind(workspace_id)
-        .execute(pool.get_ref())
-        .await
-        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    // Soft delete keeps send and reply history; GDPR erasure uses the admin API
+    let result = sqlx::query(
+        "UPDATE leads SET deleted_at = NOW() WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(lead_id)
+    .bind(workspace_id)
+    .execute(pool.get_ref())
+    .await?;
 
     if result.rows_affected() > 0 {
         Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true})))
     } else {
-        Ok(HttpResponse::NotFound().json(serde_json::json!({"error": "Lead not found"})))
+        Err(ApiError::NotFound("Lead not found".to_string()))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/{id}/restore",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID")),
+    responses(
+        (status = 200, description = "Lead restored"),
+        (status = 404, description = "No deleted lead with this ID", body = ErrorResponse),
+        (status = 409, description = "A live lead already has this address", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn restore_lead(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let lead_id = path.into_inner();
+
+    // The address may have been imported or generated again while this lead was in the trash
+    let result = sqlx::query(
+        "UPDATE leads SET deleted_at = NULL WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NOT NULL"
+    )
+    .bind(lead_id)
+    .bind(workspace_id)
+    .execute(pool.get_ref())
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some("23505") => {
+            ApiError::Conflict("A live lead already has this address".to_string())
+        }
+        _ => ApiError::from(e),
+    })?;
+
+    if result.rows_affected() > 0 {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"restored": true})))
+    } else {
+        Err(ApiError::NotFound("Deleted lead not found".to_string()))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leads/{id}/tags",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID")),
+    responses(
+        (status = 200, description = "The lead's tags, alphabetically", body = [String]),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_lead_tags(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let tags = lead_tags::list_tags(pool.get_ref(), workspace_id, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/leads/{id}/tags",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID")),
+    request_body = LeadTagsRequest,
+    responses(
+        (status = 200, description = "Tags added; returns all of the lead's tags", body = [String]),
+        (status = 400, description = "Invalid tag", body = ErrorResponse),
+        (status = 404, description = "Lead not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn add_lead_tags(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<LeadTagsRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let tags = lead_tags::normalize_request_tags(&body.tags).map_err(ApiError::Validation)?;
+
+    match lead_tags::add_tags(pool.get_ref(), workspace_id, path.into_inner(), &tags).await? {
+        Some(tags) => Ok(HttpResponse::Ok().json(tags)),
+        None => Err(ApiError::NotFound("Lead not found".to_string())),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RemoveTagQuery {
+    /// Tag to remove
+    pub tag: String,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/leads/{id}/tags",
+    tag = "leads",
+    params(("id" = Uuid, Path, description = "Lead ID"), RemoveTagQuery),
+    responses(
+        (status = 200, description = "Tag removed"),
+        (status = 404, description = "Lead doesn't have this tag", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn remove_lead_tag(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<RemoveTagQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let removed = match lead_tags::normalize_tag(&query.tag) {
+        Some(tag) => lead_tags::remove_tag(pool.get_ref(), workspace_id, path.into_inner(), &tag).await?,
+        None => false,
+    };
+
+    if removed {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"removed": true})))
+    } else {
+        Err(ApiError::NotFound("Tag not found on this lead".to_string()))
     }
 }
@@ -1,5 +1,8 @@
+pub mod error;
 pub mod leads;
 pub mod campaigns;
+pub mod templates;
+pub mod steps;
 pub mod analytics;
 pub mod email_accounts;
 pub mod auth;
@@ -7,3 +10,9 @@ pub mod compliance;
 pub mod billing;
 pub mod signals;
 pub mod founder_dashboard;
+pub mod admin;
+pub mod jobs;
+pub mod webhooks;
+pub mod tracking;
+pub mod zapier;
+pub mod openapi;
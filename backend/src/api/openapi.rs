@@ -0,0 +1,49 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::{auth, campaigns, founder_dashboard, leads, steps, templates};
+
+/// Served at `/api-docs/openapi.json`; the Swagger UI lives under `/swagger-ui/`.
+pub const OPENAPI_JSON_PATH: &str = "/api-docs/openapi.json";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "OutreachIQ API", description = "Campaigns, leads and founder dashboard endpoints"),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Registration, login and tokens"),
+        (name = "campaigns", description = "Campaign lifecycle and campaign leads"),
+        (name = "leads", description = "Lead search, verification and signals"),
+        (name = "founder", description = "Founder dashboard: health, replies, costs and settings"),
+    )
+)]
+struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+/// Full API description, merged from each module's annotated handlers.
+pub fn spec() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(auth::AuthApi::openapi());
+    doc.merge(campaigns::CampaignsApi::openapi());
+    doc.merge(templates::TemplatesApi::openapi());
+    doc.merge(steps::StepsApi::openapi());
+    doc.merge(leads::LeadsApi::openapi());
+    doc.merge(founder_dashboard::FounderApi::openapi());
+    doc
+}
+
+pub fn swagger_ui(spec: utoipa::openapi::OpenApi) -> SwaggerUi {
+    SwaggerUi::new("/swagger-ui/{_:.*}").url(OPENAPI_JSON_PATH, spec)
+}
@@ -1,11 +1,22 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::sync::LazyLock;
+use std::time::Duration;
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
+use crate::middleware::auth::{
+    extract_claims, get_user_id, get_workspace_id as parse_workspace_id, require_role, Claims,
+};
 use crate::models::company::Company;
-use crate::models::signal::{PublicSignal, Signal};
-use crate::services::signal_tracker::SignalTracker;
+use crate::models::pagination::Cursor;
+use crate::models::signal::{FeedFilter, PublicSignal, Signal};
+use crate::services::company_discovery;
+use crate::services::funding_connector::{FundingConnector, ManualFundingEntry};
+use crate::services::rate_limiter::RateLimiter;
+use crate::services::signal_tracker::{CompanySignalSummary, SignalTracker};
 
 // ============================================================================
 // Request/Response Types
@@ -16,18 +27,54 @@ pub struct FeedQuery {
     pub limit: Option<i64>,
     pub signal_type: Option<String>,
     pub company_id: Option<Uuid>,
+    /// Lowest confidence score to include, 0.0-1.0
+    pub min_confidence: Option<f64>,
+    /// Only signals detected at or after this RFC 3339 time
+    pub since: Option<DateTime<Utc>>,
+    /// Comma-separated sources, e.g. `github,rss_feed`
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub sources: Option<Vec<String>>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+/// Query strings can't carry lists, so `a,b,c` is split instead; blank means no filter
+fn comma_separated<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = Option::<String>::deserialize(deserializer)?;
+    Ok(raw
+        .map(|r| {
+            r.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|sources| !sources.is_empty()))
 }
 
 #[derive(Debug, Serialize)]
 pub struct SignalFeedResponse {
     pub signals: Vec<PublicSignal>,
+    /// Number of signals on this page
     pub total: usize,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompanyListQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CompanyListResponse {
     pub companies: Vec<CompanyInfo>,
+    /// Number of companies on this page
     pub total: usize,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +87,12 @@ pub struct CompanyInfo {
     pub twitter_handle: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DiscoverCompaniesResponse {
+    pub domains_scanned: usize,
+    pub companies_created: usize,
+}
+
 #[derive(Debug, Serialize)]
 pub struct IngestResponse {
     pub success: bool,
@@ -51,61 +104,92 @@ pub struct IngestResponse {
 // Public Endpoints (no auth required)
 // ============================================================================
 
-/// GET /api/signals/feed - Public signal feed
+/// Clamps a page size to `1..=100`, defaulting to 50
+fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(50).clamp(1, 100)
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, ApiError> {
+    cursor
+        .filter(|c| !c.is_empty())
+        .map(|c| Cursor::decode(c).ok_or_else(|| ApiError::Validation("Invalid cursor".to_string())))
+        .transpose()
+}
+
+/// GET /api/signals/feed - Public signal feed, newest first, paged with `cursor`
 pub async fn get_signal_feed(
     pool: web::Data<PgPool>,
     query: web::Query<FeedQuery>,
-) -> impl Responder {
-    let limit = query.limit.unwrap_or(50).min(100);
+) -> Result<HttpResponse, ApiError> {
+    let query = query.into_inner();
+    let limit = page_limit(query.limit);
+    let cursor = parse_cursor(query.cursor.as_deref())?;
+    if cursor.as_ref().is_some_and(|c| c.timestamp().is_none()) {
+        return Err(ApiError::Validation("Invalid cursor".to_string()));
+    }
+    if query.min_confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+        return Err(ApiError::Validation("min_confidence must be between 0.0 and 1.0".to_string()));
+    }
 
-    let signals = if let Some(ref signal_type) = query.signal_type {
-        Signal::find_by_type(pool.get_ref(), signal_type, limit).await
-    } else {
-        Signal::find_recent(pool.get_ref(), limit).await
+    let filter = FeedFilter {
+        signal_type: query.signal_type.filter(|t| !t.is_empty()),
+        company_id: query.company_id,
+        min_confidence: query.min_confidence,
+        since: query.since,
+        sources: query.sources,
     };
 
-    match signals {
-        Ok(signals) => {
-            let total = signals.len();
-            HttpResponse::Ok().json(SignalFeedResponse { signals, total })
-        }
-        Err(e) => {
-            tracing::error!("Failed to fetch signal feed: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch signals"
-            }))
-        }
-    }
+    let (signals, next_cursor) = SignalTracker::new(None)
+        .get_public_feed(pool.get_ref(), &filter, limit, cursor.as_ref())
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(SignalFeedResponse {
+        total: signals.len(),
+        signals,
+        next_cursor: next_cursor.map(|c| c.encode()),
+    }))
 }
 
-/// GET /api/signals/companies - List tracked companies
-pub async fn get_companies(pool: web::Data<PgPool>) -> impl Responder {
-    match Company::find_active(pool.get_ref()).await {
-        Ok(companies) => {
-            let company_infos: Vec<CompanyInfo> = companies
-                .into_iter()
-                .map(|c| CompanyInfo {
-                    id: c.id,
-                    name: c.name,
-                    domain: c.domain,
-                    industry: c.industry,
-                    github_org: c.github_org,
-                    twitter_handle: c.twitter_handle,
-                })
-                .collect();
-            let total = company_infos.len();
-            HttpResponse::Ok().json(CompanyListResponse {
-                companies: company_infos,
-                total,
-            })
-        }
-        Err(e) => {
-            tracing::error!("Failed to fetch companies: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch companies"
-            }))
-        }
-    }
+/// GET /api/signals/companies - List tracked companies by name, paged with `cursor`
+pub async fn get_companies(
+    pool: web::Data<PgPool>,
+    query: web::Query<CompanyListQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let limit = page_limit(query.limit);
+    let cursor = parse_cursor(query.cursor.as_deref())?;
+
+    let mut companies = Company::find_active_page(
+        pool.get_ref(),
+        limit + 1,
+        cursor.as_ref().map(|c| (c.key.as_str(), c.id)),
+    )
+    .await?;
+
+    let next_cursor = if companies.len() as i64 > limit {
+        companies.truncate(limit as usize);
+        companies.last().map(|c| Cursor::new(c.name.clone(), c.id).encode())
+    } else {
+        None
+    };
+
+    let company_infos: Vec<CompanyInfo> = companies
+        .into_iter()
+        .map(|c| CompanyInfo {
+            id: c.id,
+            name: c.name,
+            domain: c.domain,
+            industry: c.industry,
+            github_org: c.github_org,
+            twitter_handle: c.twitter_handle,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(CompanyListResponse {
+        total: company_infos.len(),
+        companies: company_infos,
+        next_cursor,
+    }))
 }
 
 /// GET /api/signals/company/{id} - Get signals for a specific company
@@ -163,80 +247,210 @@ pub async fn get_signal_stats(pool: web::Data<PgPool>) -> impl Responder {
 }
 
 // ============================================================================
-// Admin Endpoints (auth required in production)
+// Ingest Endpoints (admin only, rate limited per caller)
 // ============================================================================
 
-/// POST /api/signals/ingest - Trigger signal ingestion for all companies
-pub async fn trigger_ingest(pool: web::Data<PgPool>) -> impl Responder {
+/// A full ingest scrapes every tracked company, so it's allowed rarely
+static FULL_INGEST_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(1, Duration::from_secs(15 * 60)));
+
+static COMPANY_INGEST_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(20, Duration::from_secs(60 * 60)));
+
+fn check_rate_limit(limiter: &RateLimiter, claims: &Claims) -> Result<(), ApiError> {
+    limiter.check(&claims.user_id).map_err(|retry_after| {
+        ApiError::RateLimited(
+            "Too many ingest requests, try again later".to_string(),
+            retry_after.as_secs().max(1),
+        )
+    })
+}
+
+/// POST /api/signals/ingest - Trigger signal ingestion for all companies (platform admins)
+pub async fn trigger_ingest(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    check_rate_limit(&FULL_INGEST_LIMITER, &claims)?;
+
     let github_token = std::env::var("GITHUB_TOKEN").ok();
     let tracker = SignalTracker::new(github_token);
 
     match tracker.ingest_all_signals(pool.get_ref()).await {
-        Ok(signals) => HttpResponse::Ok().json(IngestResponse {
+        Ok(signals) => Ok(HttpResponse::Ok().json(IngestResponse {
             success: true,
             signals_created: signals.len(),
             message: format!("Successfully ingested {} signals", signals.len()),
-        }),
+        })),
         Err(e) => {
             tracing::error!("Signal ingestion failed: {}", e);
-            HttpResponse::InternalServerError().json(IngestResponse {
+            Ok(HttpResponse::InternalServerError().json(IngestResponse {
                 success: false,
                 signals_created: 0,
                 message: format!("Ingestion failed: {}", e),
-            })
+            }))
         }
     }
 }
 
-/// POST /api/signals/ingest/{company_id} - Trigger ingestion for a specific company
+/// POST /api/signals/ingest/{company_id} - Trigger ingestion for a specific company.
+/// Platform admins can refresh any company; workspace owners and admins only
+/// companies whose domain matches one of their workspace's leads.
 pub async fn trigger_company_ingest(
     pool: web::Data<PgPool>,
     path: web::Path<Uuid>,
-) -> impl Responder {
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
     let company_id = path.into_inner();
-    let github_token = std::env::var("GITHUB_TOKEN").ok();
-    let tracker = SignalTracker::new(github_token);
 
     // Find the company
     let company = sqlx::query_as::<_, Company>("SELECT * FROM companies WHERE id = $1")
         .bind(company_id)
         .fetch_optional(pool.get_ref())
-        .await;
-
-    match company {
-        Ok(Some(company)) => {
-            match tracker.ingest_company_signals(pool.get_ref(), &company).await {
-                Ok(signals) => HttpResponse::Ok().json(IngestResponse {
-                    success: true,
-                    signals_created: signals.len(),
-                    message: format!(
-                        "Successfully ingested {} signals for {}",
-                        signals.len(),
-                        company.name
-                    ),
-                }),
-                Err(e) => {
-                    tracing::error!("Company ingestion failed: {}", e);
-                    HttpResponse::InternalServerError().json(IngestResponse {
-                        success: false,
-                        signals_created: 0,
-                        message: format!("Ingestion failed: {}", e),
-                    })
-                }
-            }
-        }
-        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Company not found"
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Company not found".to_string()))?;
+
+    if claims.role != "admin" && !can_ingest_company(pool.get_ref(), &claims, &company).await? {
+        return Err(ApiError::Forbidden(
+            "Only workspace owners and admins can refresh signals for their own leads' companies".to_string(),
+        ));
+    }
+
+    check_rate_limit(&COMPANY_INGEST_LIMITER, &claims)?;
+
+    let github_token = std::env::var("GITHUB_TOKEN").ok();
+    let tracker = SignalTracker::new(github_token);
+
+    match tracker.ingest_company_signals(pool.get_ref(), &company).await {
+        Ok(signals) => Ok(HttpResponse::Ok().json(IngestResponse {
+            success: true,
+            signals_created: signals.len(),
+            message: format!(
+                "Successfully ingested {} signals for {}",
+                signals.len(),
+                company.name
+            ),
         })),
         Err(e) => {
-            tracing::error!("Failed to find company: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Database error"
+            tracing::error!("Company ingestion failed: {}", e);
+            Ok(HttpResponse::InternalServerError().json(IngestResponse {
+                success: false,
+                signals_created: 0,
+                message: format!("Ingestion failed: {}", e),
             }))
         }
     }
 }
 
+/// POST /api/signals/funding/manual - Create funding signals from hand-entered rounds,
+/// for bootstrapping without a Crunchbase key. Admin only: the signals land in the
+/// shared feed every workspace reads.
+pub async fn create_manual_funding(
+    pool: web::Data<PgPool>,
+    body: web::Json<ManualFundingEntry>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    require_role(&claims, &["admin"])?;
+    check_rate_limit(&COMPANY_INGEST_LIMITER, &claims)?;
+    let entry = body.into_inner();
+
+    if entry.rounds.is_empty() {
+        return Err(ApiError::Validation("At least one funding round is required".to_string()));
+    }
+    if entry.rounds.iter().any(|r| r.amount_usd.is_some_and(|a| a < 0)) {
+        return Err(ApiError::Validation("amount_usd cannot be negative".to_string()));
+    }
+
+    let company = sqlx::query_as::<_, Company>("SELECT * FROM companies WHERE id = $1")
+        .bind(entry.company_id)
+        .fetch_optional(pool.get_ref())
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Company not found".to_string()))?;
+
+    let signals = FundingConnector::default()
+        .create_signals_from_manual(pool.get_ref(), &company.name, &entry)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(HttpResponse::Ok().json(IngestResponse {
+        success: true,
+        signals_created: signals.len(),
+        message: format!(
+            "Created {} funding signals for {} ({} duplicates skipped)",
+            signals.len(),
+            company.name,
+            entry.rounds.len() - signals.len()
+        ),
+    }))
+}
+
+/// Whether the caller is an owner/admin of a workspace that has a lead at the company
+async fn can_ingest_company(pool: &PgPool, claims: &Claims, company: &Company) -> Result<bool, ApiError> {
+    let workspace_id = parse_workspace_id(claims)?;
+    let user_id = get_user_id(claims)?;
+
+    let allowed: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM workspace_members wm
+            WHERE wm.workspace_id = $1 AND wm.user_id = $2 AND wm.role IN ('owner', 'admin')
+        ) AND EXISTS (
+            SELECT 1 FROM leads l
+            WHERE l.workspace_id = $1 AND l.deleted_at IS NULL AND email_domain(l.email) = $3
+        )
+        "#
+    )
+    .bind(workspace_id)
+    .bind(user_id)
+    .bind(&company.domain)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(allowed)
+}
+
+/// POST /api/signals/companies/discover - Create company rows for the workspace's lead domains
+pub async fn discover_companies(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let (domains_scanned, companies_created) =
+        company_discovery::discover_from_leads(pool.get_ref(), workspace_id).await?;
+
+    Ok(HttpResponse::Ok().json(DiscoverCompaniesResponse {
+        domains_scanned,
+        companies_created,
+    }))
+}
+
+/// GET /api/signals/companies/{id}/summary - Signal totals for one company
+pub async fn get_company_summary(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    // Companies are shared across workspaces, so any signed-in user can read a summary
+    extract_claims(&req)?;
+    let company_id = path.into_inner();
+
+    let summary: Option<CompanySignalSummary> = SignalTracker::new(None)
+        .get_company_summary(pool.get_ref(), company_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    match summary {
+        Some(summary) => Ok(HttpResponse::Ok().json(summary)),
+        None => Err(ApiError::NotFound("Company not found".to_string())),
+    }
+}
+
 // ============================================================================
 // Route Configuration
 // ============================================================================
@@ -249,8 +463,11 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/companies", web::get().to(get_companies))
             .route("/company/{id}", web::get().to(get_company_signals))
             .route("/stats", web::get().to(get_signal_stats))
-            // Admin endpoints (should add auth middleware in production)
+            // Admin endpoints
             .route("/ingest", web::post().to(trigger_ingest))
-            .route("/ingest/{id}", web::post().to(trigger_company_ingest)),
+            .route("/ingest/{id}", web::post().to(trigger_company_ingest))
+            .route("/funding/manual", web::post().to(create_manual_funding))
+            .route("/companies/discover", web::post().to(discover_companies))
+            .route("/companies/{id}/summary", web::get().to(get_company_summary)),
     );
 }
@@ -0,0 +1,251 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::api::error::{ApiError, ErrorResponse};
+use crate::api::templates::ensure_campaign;
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
+use crate::models::campaign::{CampaignStep, CampaignStepRequest};
+use crate::services::campaign_scheduler::{StepCondition, MAX_STEP_DELAY_DAYS};
+
+/// Registered ahead of the `/campaigns` scope, which would otherwise claim these paths
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/campaigns/{id}/steps")
+            .route(web::get().to(list_steps))
+            .route(web::post().to(create_step)),
+    )
+    .service(
+        web::resource("/campaigns/{id}/steps/{step_id}")
+            .route(web::put().to(update_step))
+            .route(web::delete().to(delete_step)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_steps, create_step, update_step, delete_step))]
+pub struct StepsApi;
+
+const STEP_COLUMNS: &str = "id, campaign_id, step_index, template_id, delay_days, condition, created_at, updated_at";
+
+/// Checks the request and returns its condition, defaulting to `no_reply`
+async fn validate(pool: &PgPool, campaign_id: Uuid, body: &CampaignStepRequest) -> Result<StepCondition, ApiError> {
+    if body.step_index.is_some_and(|index| index < 0) {
+        return Err(ApiError::Validation("step_index must be 0 or more".to_string()));
+    }
+    if !(0..=MAX_STEP_DELAY_DAYS).contains(&body.delay_days) {
+        return Err(ApiError::Validation(format!("delay_days must be between 0 and {}", MAX_STEP_DELAY_DAYS)));
+    }
+
+    let condition = match body.condition.as_deref() {
+        None => StepCondition::NoReply,
+        Some(value) => StepCondition::parse(value).ok_or_else(|| {
+            let allowed: Vec<&str> = StepCondition::ALL.iter().map(|c| c.as_str()).collect();
+            ApiError::Validation(format!("condition must be one of: {}", allowed.join(", ")))
+        })?,
+    };
+
+    if let Some(template_id) = body.template_id {
+        let owned: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM campaign_templates WHERE id = $1 AND campaign_id = $2)"
+        )
+        .bind(template_id)
+        .bind(campaign_id)
+        .fetch_one(pool)
+        .await?;
+
+        if !owned {
+            return Err(ApiError::Validation("template_id must be one of this campaign's templates".to_string()));
+        }
+    }
+
+    Ok(condition)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/steps",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "The campaign's sequence in step order", body = [CampaignStep]),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_steps(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+
+    let steps = sqlx::query_as::<_, CampaignStep>(&format!(
+        "SELECT {} FROM campaign_steps WHERE campaign_id = $1 ORDER BY step_index ASC",
+        STEP_COLUMNS
+    ))
+    .bind(campaign_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(steps))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/steps",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = CampaignStepRequest,
+    responses(
+        (status = 201, description = "Step added", body = CampaignStep),
+        (status = 400, description = "Invalid index, delay, condition or template", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 409, description = "The campaign already has a step at this index", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn create_step(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<CampaignStepRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+    let condition = validate(pool.get_ref(), campaign_id, &body).await?;
+
+    let step = sqlx::query_as::<_, CampaignStep>(&format!(
+        r#"
+        INSERT INTO campaign_steps (campaign_id, workspace_id, step_index, template_id, delay_days, condition)
+        VALUES (
+            $1, $2,
+            COALESCE($3, (SELECT COALESCE(MAX(step_index) + 1, 0) FROM campaign_steps WHERE campaign_id = $1)),
+            $4, $5, $6
+        )
+        RETURNING {}
+        "#,
+        STEP_COLUMNS
+    ))
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .bind(body.step_index)
+    .bind(body.template_id)
+    .bind(body.delay_days)
+    .bind(condition.as_str())
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(step))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/campaigns/{id}/steps/{step_id}",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("step_id" = Uuid, Path, description = "Step ID"),
+    ),
+    request_body = CampaignStepRequest,
+    responses(
+        (status = 200, description = "Step replaced; leads already past it are unaffected", body = CampaignStep),
+        (status = 400, description = "Invalid index, delay, condition or template", body = ErrorResponse),
+        (status = 404, description = "Campaign or step not found", body = ErrorResponse),
+        (status = 409, description = "The campaign already has a step at this index", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn update_step(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<CampaignStepRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, step_id) = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+    let condition = validate(pool.get_ref(), campaign_id, &body).await?;
+
+    // Omitting step_index keeps the step where it is
+    let step = sqlx::query_as::<_, CampaignStep>(&format!(
+        r#"
+        UPDATE campaign_steps
+        SET step_index = COALESCE($3, step_index),
+            template_id = $4,
+            delay_days = $5,
+            condition = $6,
+            updated_at = NOW()
+        WHERE id = $1 AND campaign_id = $2
+        RETURNING {}
+        "#,
+        STEP_COLUMNS
+    ))
+    .bind(step_id)
+    .bind(campaign_id)
+    .bind(body.step_index)
+    .bind(body.template_id)
+    .bind(body.delay_days)
+    .bind(condition.as_str())
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Step not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(step))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/campaigns/{id}/steps/{step_id}",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("step_id" = Uuid, Path, description = "Step ID"),
+    ),
+    responses(
+        (status = 200, description = "Step removed; leads waiting on it move on to the following step"),
+        (status = 404, description = "Campaign or step not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_step(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, step_id) = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+
+    let result = sqlx::query("DELETE FROM campaign_steps WHERE id = $1 AND campaign_id = $2")
+        .bind(step_id)
+        .bind(campaign_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() > 0 {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true})))
+    } else {
+        Err(ApiError::NotFound("Step not found".to_string()))
+    }
+}
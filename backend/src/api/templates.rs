@@ -0,0 +1,241 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::api::error::{ApiError, ErrorResponse};
+use crate::middleware::auth::{extract_claims, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::campaign::{CampaignTemplate, CampaignTemplateRequest};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::email_sender::validate_template;
+
+/// Registered ahead of the `/campaigns` scope, which would otherwise claim these paths
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/campaigns/{id}/templates")
+            .route(web::get().to(list_templates))
+            .route(web::post().to(create_template)),
+    )
+    .service(
+        web::resource("/campaigns/{id}/templates/{template_id}")
+            .route(web::put().to(update_template))
+            .route(web::delete().to(delete_template)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_templates, create_template, update_template, delete_template))]
+pub struct TemplatesApi;
+
+const TEMPLATE_COLUMNS: &str = "id, campaign_id, step_order, subject, body_html, body_text, created_at, updated_at";
+
+/// Not found unless the campaign exists in the workspace and isn't trashed
+pub(crate) async fn ensure_campaign(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid) -> Result<(), ApiError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound("Campaign not found".to_string()))
+    }
+}
+
+fn validate(body: &CampaignTemplateRequest) -> Result<(), ApiError> {
+    if body.step_order.is_some_and(|step| step < 1) {
+        return Err(ApiError::Validation("step_order must be 1 or more".to_string()));
+    }
+    validate_template(&body.subject, &body.body_html, body.body_text.as_deref()).map_err(ApiError::Unprocessable)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/templates",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    responses(
+        (status = 200, description = "The campaign's templates in step order", body = [CampaignTemplate]),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn list_templates(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+
+    let templates = sqlx::query_as::<_, CampaignTemplate>(&format!(
+        "SELECT {} FROM campaign_templates WHERE campaign_id = $1 ORDER BY step_order ASC",
+        TEMPLATE_COLUMNS
+    ))
+    .bind(campaign_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(templates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/campaigns/{id}/templates",
+    tag = "campaigns",
+    params(("id" = Uuid, Path, description = "Campaign ID")),
+    request_body = CampaignTemplateRequest,
+    responses(
+        (status = 201, description = "Template created", body = CampaignTemplate),
+        (status = 400, description = "Invalid step_order", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 409, description = "The campaign already has a template for this step", body = ErrorResponse),
+        (status = 422, description = "Template is invalid or missing {{unsubscribe_url}}", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn create_template(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    body: web::Json<CampaignTemplateRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let campaign_id = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+    validate(&body)?;
+
+    let template = sqlx::query_as::<_, CampaignTemplate>(&format!(
+        r#"
+        INSERT INTO campaign_templates (campaign_id, workspace_id, step_order, subject, body_html, body_text)
+        VALUES (
+            $1, $2,
+            COALESCE($3, (SELECT COALESCE(MAX(step_order), 0) + 1 FROM campaign_templates WHERE campaign_id = $1)),
+            $4, $5, NULLIF(TRIM($6), '')
+        )
+        RETURNING {}
+        "#,
+        TEMPLATE_COLUMNS
+    ))
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .bind(body.step_order)
+    .bind(body.subject.trim())
+    .bind(&body.body_html)
+    .bind(&body.body_text)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(template))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/campaigns/{id}/templates/{template_id}",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("template_id" = Uuid, Path, description = "Template ID"),
+    ),
+    request_body = CampaignTemplateRequest,
+    responses(
+        (status = 200, description = "Template replaced", body = CampaignTemplate),
+        (status = 400, description = "Invalid step_order", body = ErrorResponse),
+        (status = 404, description = "Campaign or template not found", body = ErrorResponse),
+        (status = 409, description = "The campaign already has a template for this step", body = ErrorResponse),
+        (status = 422, description = "Template is invalid or missing {{unsubscribe_url}}", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Viewers have read-only access", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn update_template(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<CampaignTemplateRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, template_id) = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+    validate(&body)?;
+
+    // Omitting step_order keeps the template where it is
+    let template = sqlx::query_as::<_, CampaignTemplate>(&format!(
+        r#"
+        UPDATE campaign_templates
+        SET step_order = COALESCE($3, step_order),
+            subject = $4,
+            body_html = $5,
+            body_text = NULLIF(TRIM($6), ''),
+            updated_at = NOW()
+        WHERE id = $1 AND campaign_id = $2
+        RETURNING {}
+        "#,
+        TEMPLATE_COLUMNS
+    ))
+    .bind(template_id)
+    .bind(campaign_id)
+    .bind(body.step_order)
+    .bind(body.subject.trim())
+    .bind(&body.body_html)
+    .bind(&body.body_text)
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Template not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(template))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/campaigns/{id}/templates/{template_id}",
+    tag = "campaigns",
+    params(
+        ("id" = Uuid, Path, description = "Campaign ID"),
+        ("template_id" = Uuid, Path, description = "Template ID"),
+    ),
+    responses(
+        (status = 200, description = "Template deleted; a campaign left without templates sends the default"),
+        (status = 404, description = "Campaign or template not found", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn delete_template(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let (campaign_id, template_id) = path.into_inner();
+
+    ensure_campaign(pool.get_ref(), workspace_id, campaign_id).await?;
+
+    let result = sqlx::query("DELETE FROM campaign_templates WHERE id = $1 AND campaign_id = $2")
+        .bind(template_id)
+        .bind(campaign_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() > 0 {
+        Ok(HttpResponse::Ok().json(serde_json::json!({"deleted": true})))
+    } else {
+        Err(ApiError::NotFound("Template not found".to_string()))
+    }
+}
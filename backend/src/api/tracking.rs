@@ -0,0 +1,60 @@
+use actix_web::{http::header, web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::services::tracking;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/track")
+            .route("/open/{campaign_lead_id}", web::get().to(track_open))
+            .route("/click/{campaign_lead_id}", web::get().to(track_click))
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct ClickQuery {
+    url: String,
+    sig: String,
+}
+
+/// GET /api/track/open/{campaign_lead_id} - Open pixel embedded in campaign emails
+async fn track_open(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    // The pixel is served whatever happens, so a failure never shows as a broken image
+    if let Err(e) = tracking::record_open(pool.get_ref(), path.into_inner()).await {
+        tracing::error!("Failed to record open: {}", e);
+    }
+
+    HttpResponse::Ok()
+        .content_type("image/gif")
+        .insert_header((header::CACHE_CONTROL, "no-store, no-cache, must-revalidate, max-age=0"))
+        .body(tracking::PIXEL_GIF)
+}
+
+/// GET /api/track/click/{campaign_lead_id}?url=...&sig=... - Records a click and redirects to the link
+async fn track_click(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<ClickQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let campaign_lead_id = path.into_inner();
+
+    if !tracking::verify_click(campaign_lead_id, &query.url, &query.sig) {
+        return Err(ApiError::Validation("Invalid tracking link".to_string()));
+    }
+
+    // The reader still gets where they were going if recording fails
+    if let Err(e) = tracking::record_click(pool.get_ref(), campaign_lead_id, &query.url).await {
+        tracing::error!("Failed to record click: {}", e);
+    }
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, query.url.as_str()))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .finish())
+}
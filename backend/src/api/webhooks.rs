@@ -0,0 +1,43 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+
+use crate::api::error::ApiError;
+use crate::services::email_webhooks;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/webhooks")
+            .route("/email/{provider}", web::post().to(handle_email_events))
+    );
+}
+
+/// POST /api/webhooks/email/{provider} - Delivery, open, click, bounce and spam events
+async fn handle_email_events(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    body: web::Bytes,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let provider = email_webhooks::provider_for(&path.into_inner())
+        .ok_or_else(|| ApiError::NotFound("Unknown email provider".to_string()))?;
+
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+
+    if let Err(e) = provider.verify(&header, &body) {
+        tracing::warn!("Rejected {} webhook: {}", provider.name(), e);
+        return Err(ApiError::Unauthorized("Invalid webhook signature".to_string()));
+    }
+
+    let events = provider.parse(&body).map_err(ApiError::Validation)?;
+    let recorded = email_webhooks::record_events(pool.get_ref(), provider.name(), &events).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "received": events.len(),
+        "recorded": recorded
+    })))
+}
@@ -0,0 +1,188 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::middleware::auth::{extract_claims, get_user_id, get_workspace_id as parse_workspace_id, require_workspace_role};
+use crate::models::workspace::WorkspaceRole;
+use crate::services::zapier::{self, DeliveryOutcome, ZapierEvent};
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ZapierSubscription {
+    pub id: Uuid,
+    pub event: String,
+    pub target_url: String,
+    pub campaign_id: Option<Uuid>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSubscriptionRequest {
+    pub event: String,
+    pub target_url: String,
+    /// Only fire for this campaign; omit for every campaign in the workspace
+    pub campaign_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestTriggerResponse {
+    pub delivered: bool,
+    pub error: Option<String>,
+    pub payload: zapier::ZapierPayload,
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/integrations/zapier")
+            .route("/subscriptions", web::get().to(list_subscriptions))
+            .route("/subscriptions", web::post().to(create_subscription))
+            .route("/subscriptions/{id}", web::delete().to(delete_subscription))
+            .route("/subscriptions/{id}/test", web::post().to(test_subscription))
+    );
+}
+
+/// GET /api/integrations/zapier/subscriptions
+async fn list_subscriptions(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = extract_claims(&req)?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let subscriptions = sqlx::query_as::<_, ZapierSubscription>(
+        r#"
+        SELECT id, event, target_url, campaign_id, created_at
+        FROM zapier_subscriptions
+        WHERE workspace_id = $1
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(subscriptions))
+}
+
+/// POST /api/integrations/zapier/subscriptions - called by Zapier when a Zap is turned on
+async fn create_subscription(
+    pool: web::Data<PgPool>,
+    body: web::Json<CreateSubscriptionRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+    let user_id = get_user_id(&claims)?;
+
+    let event = ZapierEvent::parse(&body.event).ok_or_else(|| {
+        let events: Vec<&str> = ZapierEvent::ALL.iter().map(|e| e.as_str()).collect();
+        ApiError::Validation(format!("event must be one of: {}", events.join(", ")))
+    })?;
+
+    let target_url = body.target_url.trim();
+    if !zapier::is_valid_target_url(target_url) {
+        return Err(ApiError::Validation("target_url must be an https URL".to_string()));
+    }
+    if zapier::resolve_target(target_url).await.is_err() {
+        return Err(ApiError::Validation("target_url must resolve to a public address".to_string()));
+    }
+
+    if let Some(campaign_id) = body.campaign_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM campaigns WHERE id = $1 AND workspace_id = $2)"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .fetch_one(pool.get_ref())
+        .await?;
+
+        if !exists {
+            return Err(ApiError::NotFound("Campaign not found".to_string()));
+        }
+    }
+
+    let subscription = sqlx::query_as::<_, ZapierSubscription>(
+        r#"
+        INSERT INTO zapier_subscriptions (workspace_id, event, target_url, campaign_id, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, event, target_url, campaign_id, created_at
+        "#
+    )
+    .bind(workspace_id)
+    .bind(event.as_str())
+    .bind(target_url)
+    .bind(body.campaign_id)
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(subscription))
+}
+
+/// DELETE /api/integrations/zapier/subscriptions/{id} - called by Zapier when a Zap is turned off
+async fn delete_subscription(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let result = sqlx::query("DELETE FROM zapier_subscriptions WHERE id = $1 AND workspace_id = $2")
+        .bind(path.into_inner())
+        .bind(workspace_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Subscription not found".to_string()));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// POST /api/integrations/zapier/subscriptions/{id}/test - sends a sample payload
+/// straight away so fields can be mapped in Zapier before a real event happens
+async fn test_subscription(
+    pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let claims = require_workspace_role(&req, pool.get_ref(), WorkspaceRole::Member).await?;
+    let workspace_id = parse_workspace_id(&claims)?;
+
+    let subscription = sqlx::query_as::<_, ZapierSubscription>(
+        r#"
+        SELECT id, event, target_url, campaign_id, created_at
+        FROM zapier_subscriptions
+        WHERE id = $1 AND workspace_id = $2
+        "#
+    )
+    .bind(path.into_inner())
+    .bind(workspace_id)
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Subscription not found".to_string()))?;
+
+    let event = ZapierEvent::parse(&subscription.event)
+        .ok_or_else(|| ApiError::internal(format!("Unknown Zapier event {}", subscription.event)))?;
+    let payload = zapier::sample_payload(event);
+    let body = serde_json::to_value(&payload).map_err(ApiError::internal)?;
+
+    // Test sends aren't retried: the user is watching and can just try again. The
+    // hook's own response stays in the logs rather than being echoed back.
+    let (delivered, error) = match zapier::post(&subscription.target_url, &body).await {
+        Ok(DeliveryOutcome::Delivered) => (true, None),
+        Ok(DeliveryOutcome::Gone | DeliveryOutcome::Unsubscribed) => {
+            (false, Some("The hook URL is no longer active".to_string()))
+        }
+        Err(e) => {
+            tracing::warn!("Zapier test delivery for subscription {} failed: {}", subscription.id, e);
+            (false, Some("The hook URL could not be reached".to_string()))
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(TestTriggerResponse { delivered, error, payload }))
+}
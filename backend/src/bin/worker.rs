@@ -5,24 +5,75 @@ use tokio::time::sleep;
 use dotenvy::dotenv;
 use std::env;
 use uuid::Uuid;
-use chrono::{Utc, Timelike};
+use chrono::{DateTime, Utc};
 
 // Import from main crate
-use outreachiq::services::email_sender::{CampaignEmailSender, SendEmailJobPayload};
+use outreachiq::config::dkim_keys;
+use outreachiq::services::email_sender::{CampaignEmailSender, CampaignSendError, SendEmailJobPayload};
 use outreachiq::services::campaign_scheduler::CampaignScheduler;
 use outreachiq::services::warmup_service::WarmupService;
+use outreachiq::services::encryption::EncryptionService;
+use outreachiq::services::key_rotation;
 use outreachiq::services::auto_pause;
+use outreachiq::services::job_runner::{self, KeyedLimiter};
+use outreachiq::services::signal_tracker::{SignalSource, SignalTracker};
+use outreachiq::services::zapier::{self, DeliveryOutcome, ZapierDeliveryPayload};
+use outreachiq::services::meeting_reminders::{self, MeetingReminderPayload};
+use outreachiq::services::data_retention;
+use outreachiq::services::email_quota;
+use outreachiq::services::jwt_keys::jwt_keys;
+use outreachiq::services::unsubscribe_token::unsubscribe_keys;
+use outreachiq::services::imap_poller::ImapPoller;
+use outreachiq::services::job_queue::{self, ClassifyReplyPayload, WarmupEmailPayload};
+use outreachiq::services::reply_classifier;
+use outreachiq::services::worker_status::{self, WorkerTask};
+
+/// Stale-signal refresh runs once per source every ~hour (720 iterations x 5s)
+const SIGNAL_REFRESH_EVERY: u64 = 720;
+
+/// Iteration within each refresh cycle a source runs at, so sources never fire together
+fn signal_refresh_offset(source: SignalSource) -> u64 {
+    match source {
+        SignalSource::Github => 120,     // ~10 minutes into the hour
+        SignalSource::Wellfound => 480,  // ~40 minutes into the hour
+        SignalSource::RssFeed => 300,    // ~25 minutes into the hour
+        SignalSource::Crunchbase => 600, // ~50 minutes into the hour
+    }
+}
+
+/// Why a job didn't complete
+enum JobError {
+    /// Counts against the job's retries
+    Failed(String),
+    /// Can't run yet (e.g. its inbox is at the daily limit); runs again at the given
+    /// time without using up a retry
+    Deferred(DateTime<Utc>),
+}
+
+impl From<String> for JobError {
+    fn from(message: String) -> Self {
+        JobError::Failed(message)
+    }
+}
+
+impl From<CampaignSendError> for JobError {
+    fn from(e: CampaignSendError) -> Self {
+        match e {
+            CampaignSendError::RateLimited { retry_at } => JobError::Deferred(retry_at),
+            CampaignSendError::QuotaExceeded { retry_at } => JobError::Deferred(retry_at),
+            CampaignSendError::AddressMissing { retry_at } => JobError::Deferred(retry_at),
+            CampaignSendError::SoftBounced { retry_at } => JobError::Deferred(retry_at),
+            CampaignSendError::Failed(message) => JobError::Failed(message),
+        }
+    }
+}
 
 #[derive(Debug, sqlx::FromRow)]
 struct Job {
     id: Uuid,
-    workspace_id: Option<Uuid>,
     job_type: String,
     payload: serde_json::Value,
-    status: String,
     retry_count: i32,
-    max_retries: i32,
-    created_at: chrono::DateTime<Utc>,
 }
 
 #[tokio::main]
@@ -34,92 +85,252 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set");
+
+    // Tracked links are signed with JWT_SECRET; refuse to start without a usable one
+    jwt_keys();
+
+    // Campaign emails carry unsubscribe links signed with UNSUBSCRIBE_SECRET
+    unsubscribe_keys();
+
+    // Campaign emails are signed with the DKIM keys, so a broken one stops startup
+    dkim_keys();
+
+    // Sending decrypts inbox credentials, so a missing or malformed key stops startup too
+    let encryption = EncryptionService::new()
+        .unwrap_or_else(|e| panic!("Invalid encryption configuration: {}", e));
     
+    let concurrency = job_runner::worker_concurrency();
+    // Every job slot can hold a connection while the main loop, the IMAP poll
+    // and each signal refresh hold their own
+    let background_connections = 2 + SignalSource::ALL.len() as u32;
+
     let pool = PgPoolOptions::new()
-        .max_connections(5)
+        .max_connections(concurrency as u32 + background_connections)
         .connect(&database_url)
         .await
         .expect("Failed to create pool");
 
     let pool = Arc::new(pool);
 
+    // Inboxes saved before encryption was mandatory still have plaintext passwords
+    if let Err(e) = key_rotation::encrypt_plaintext_passwords(&pool, &encryption).await {
+        eprintln!("Failed to encrypt plaintext SMTP passwords: {}", e);
+    }
+
     println!("🔄 OutreachIQ Worker started");
-    println!("   - Processing email jobs");
+    println!("   - Processing email jobs ({} concurrent)", concurrency);
     println!("   - Running campaign scheduler");
     println!("   - Managing inbox warmup");
+    println!("   - Polling inboxes for replies over IMAP (every 5 minutes)");
+    println!("   - Classifying new replies");
     println!("   - Auto-pause health checks (every 6 hours)");
+    println!("   - Meeting reminders (24h and 1h before)");
+    println!("   - Data retention purge (nightly)");
+    println!("   - Reclaiming jobs stuck in processing > {}m", job_queue::STUCK_JOB_MINUTES);
+    for source in SignalSource::ALL {
+        println!("   - Refreshing {} signals stale > {}h (checked hourly)", source.as_str(), source.stale_hours());
+    }
 
-    let email_sender = CampaignEmailSender::new(pool.clone());
+    let email_sender = Arc::new(CampaignEmailSender::new(pool.clone()));
+    // One SMTP session per inbox at a time, however many tasks are running
+    let inbox_limiter = Arc::new(KeyedLimiter::new(1));
     let campaign_scheduler = CampaignScheduler::new(pool.clone());
     let warmup_service = WarmupService::new(pool.clone());
+    let imap_poller = Arc::new(ImapPoller::new(pool.clone()));
+    // Polling logs into every inbox, so like signal refreshes it runs off the job loop
+    let mut imap_poll: Option<tokio::task::JoinHandle<()>> = None;
+    let signal_tracker = Arc::new(SignalTracker::new(env::var("GITHUB_TOKEN").ok()));
+    // Refreshes scrape external sites and can take minutes, so they run off the job loop
+    let mut signal_refreshes: Vec<(SignalSource, Option<tokio::task::JoinHandle<()>>)> =
+        SignalSource::ALL.iter().map(|source| (*source, None)).collect();
 
     let mut iteration = 0u64;
 
     loop {
         iteration += 1;
 
-        // Process pending jobs
-        match claim_pending_jobs(&pool, 10).await {
+        // Process pending jobs, claiming at least one per slot
+        match claim_pending_jobs(&pool, concurrency.max(10) as i32).await {
             Ok(jobs) => {
                 if !jobs.is_empty() {
                     println!("[{}] Processing {} jobs", iteration, jobs.len());
                 }
-                
-                for job in jobs {
-                    let result = process_job(&job, &email_sender).await;
-                    
-                    match result {
-                        Ok(_) => {
-                            if let Err(e) = mark_completed(&pool, job.id).await {
-                                eprintln!("Failed to mark job {} as completed: {}", job.id, e);
+
+                let job_pool = pool.clone();
+                let email_sender = email_sender.clone();
+                let inbox_limiter = inbox_limiter.clone();
+                job_runner::run_bounded(jobs, concurrency, move |job| {
+                    let pool = job_pool.clone();
+                    let email_sender = email_sender.clone();
+                    let inbox_limiter = inbox_limiter.clone();
+                    async move {
+                        let result = process_job(&pool, &job, &email_sender, &inbox_limiter).await;
+
+                        match result {
+                            Ok(_) => {
+                                if let Err(e) = mark_completed(&pool, job.id).await {
+                                    eprintln!("Failed to mark job {} as completed: {}", job.id, e);
+                                }
                             }
-                        }
-                        Err(e) => {
-                            eprintln!("Job {} failed: {}", job.id, e);
-                            if let Err(mark_err) = mark_failed(&pool, job.id, &e).await {
-                                eprintln!("Failed to mark job {} as failed: {}", job.id, mark_err);
+                            Err(JobError::Deferred(run_at)) => {
+                                println!("⏳ Deferred job {} until {}", job.id, run_at);
+                                if let Err(e) = mark_deferred(&pool, job.id, run_at).await {
+                                    eprintln!("Failed to defer job {}: {}", job.id, e);
+                                }
+                            }
+                            Err(JobError::Failed(e)) => {
+                                eprintln!("Job {} failed: {}", job.id, e);
+                                if let Err(mark_err) = job_queue::mark_failed(&pool, job.id, job.retry_count, &e).await {
+                                    eprintln!("Failed to mark job {} as failed: {}", job.id, mark_err);
+                                }
                             }
                         }
                     }
-                }
+                })
+                .await;
+                record_cycle(&pool, WorkerTask::JobLoop, Ok(())).await;
             }
             Err(e) => {
                 eprintln!("Error fetching jobs: {}", e);
+                record_cycle(&pool, WorkerTask::JobLoop, Err::<(), _>(e)).await;
             }
         }
 
         // Run campaign scheduler every 10 iterations (~50 seconds)
-        if iteration % 10 == 0 {
-            if let Err(e) = campaign_scheduler.process_active_campaigns().await {
+        if iteration.is_multiple_of(10) {
+            match campaign_scheduler.activate_scheduled_campaigns(Utc::now()).await {
+                Ok(activated) => {
+                    for campaign_id in activated {
+                        println!("▶️ Scheduled campaign {} is now active", campaign_id);
+                        if let Err(e) = email_sender.warm_campaign(campaign_id).await {
+                            eprintln!("SMTP warm-up error for campaign {}: {}", campaign_id, e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Scheduled campaign activation error: {}", e),
+            }
+
+            let outcome = campaign_scheduler.process_active_campaigns().await.map_err(|e| e.to_string());
+            if let Err(e) = &outcome {
                 eprintln!("Campaign scheduler error: {}", e);
             }
+            record_cycle(&pool, WorkerTask::Scheduler, outcome).await;
         }
 
         // Run warmup cycle every 60 iterations (~5 minutes)
-        if iteration % 60 == 0 {
-            if let Err(e) = warmup_service.execute_warmup_cycle().await {
+        if iteration.is_multiple_of(60) {
+            let cycle = warmup_service.execute_warmup_cycle().await.map_err(|e| e.to_string());
+            if let Err(e) = &cycle {
                 eprintln!("Warmup cycle error: {}", e);
             }
             
-            if let Err(e) = warmup_service.monitor_and_protect().await {
+            let monitor = warmup_service.monitor_and_protect().await.map_err(|e| e.to_string());
+            if let Err(e) = &monitor {
                 eprintln!("Warmup monitor error: {}", e);
             }
+            record_cycle(&pool, WorkerTask::Warmup, cycle.and(monitor)).await;
+
+            // Pull new replies, unless the previous poll is still going
+            if imap_poll.as_ref().is_none_or(|task| task.is_finished()) {
+                let poller = imap_poller.clone();
+                imap_poll = Some(tokio::spawn(async move {
+                    match poller.poll_all().await {
+                        Ok(summary) if summary.replies > 0 || summary.failed > 0 => println!(
+                            "📥 Polled {} inboxes: {} new replies, {} failed",
+                            summary.accounts, summary.replies, summary.failed
+                        ),
+                        Ok(_) => {}
+                        Err(e) => eprintln!("IMAP poll error: {}", e),
+                    }
+                }));
+            }
         }
 
         // Run auto-pause health check every 4320 iterations (~6 hours)
         // This checks spam rates, reply drops, and bounce rates
-        if iteration % 4320 == 0 {
+        if iteration.is_multiple_of(4320) {
             println!("🔍 Running auto-pause health check...");
-            if let Err(e) = auto_pause::run_health_check_job(&pool).await {
+            let outcome = auto_pause::run_health_check_job(&pool).await.map_err(|e| e.to_string());
+            if let Err(e) = &outcome {
                 eprintln!("Auto-pause health check error: {}", e);
             }
+            record_cycle(&pool, WorkerTask::AutoPause, outcome).await;
+        }
+
+        // Recount monthly email usage every ~hour, correcting drift in the
+        // counters the send path keeps
+        if iteration.is_multiple_of(720) {
+            if let Err(e) = email_quota::refresh_counters(&pool).await {
+                eprintln!("Email quota refresh error: {}", e);
+            }
+        }
+
+        // Data retention purge, checked every ~10 minutes during the nightly window.
+        // Each workspace is purged at most once a day.
+        if iteration.is_multiple_of(120) && data_retention::is_purge_window(Utc::now()) {
+            match data_retention::run_nightly_purge(&pool).await {
+                Ok(runs) => {
+                    for run in runs {
+                        println!(
+                            "🧹 Retention purge for workspace {}: {} events deleted, {} replies anonymized",
+                            run.workspace_id, run.events_deleted, run.replies_anonymized
+                        );
+                    }
+                }
+                Err(e) => eprintln!("Data retention purge error: {}", e),
+            }
+        }
+
+        // Refresh stale company signals, one source at a time. A run that's still
+        // going when its next slot comes round is left to finish.
+        for (source, running) in signal_refreshes.iter_mut() {
+            let due = iteration % SIGNAL_REFRESH_EVERY == signal_refresh_offset(*source);
+            if !due || running.as_ref().is_some_and(|task| !task.is_finished()) {
+                continue;
+            }
+
+            let pool = pool.clone();
+            let tracker = signal_tracker.clone();
+            let source = *source;
+            *running = Some(tokio::spawn(async move {
+                match tracker.ingest_stale_signals(&pool, source, source.stale_hours()).await {
+                    Ok(summary) => println!(
+                        "📡 Refreshed {} signals for {}/{} companies ({} failed, {} new signals)",
+                        source.as_str(),
+                        summary.refreshed,
+                        summary.companies_found,
+                        summary.failed,
+                        summary.signals_created
+                    ),
+                    Err(e) => eprintln!("{} signal refresh error: {}", source.as_str(), e),
+                }
+            }));
         }
 
-        // Reset daily counters at midnight (check every iteration)
-        if should_reset_daily_counters() {
+        // Every ~minute: reclaim jobs a crashed worker left behind, reset daily
+        // counters at each inbox's local midnight, and queue meeting reminders
+        if iteration.is_multiple_of(12) {
+            match job_queue::reclaim_stuck_jobs(&pool).await {
+                Ok(0) => {}
+                Ok(reclaimed) => println!("♻️  Reclaimed {} jobs stuck in processing", reclaimed),
+                Err(e) => eprintln!("Failed to reclaim stuck jobs: {}", e),
+            }
+
             if let Err(e) = warmup_service.reset_daily_counters().await {
                 eprintln!("Failed to reset daily counters: {}", e);
             }
+
+            match meeting_reminders::enqueue_due_reminders(&pool).await {
+                Ok(0) => {}
+                Ok(queued) => println!("⏰ Queued {} meeting reminders", queued),
+                Err(e) => eprintln!("Meeting reminder scheduling error: {}", e),
+            }
+
+            match reply_classifier::enqueue_unclassified(&pool).await {
+                Ok(0) => {}
+                Ok(queued) => println!("🏷️  Queued {} replies for classification", queued),
+                Err(e) => eprintln!("Reply classification sweep error: {}", e),
+            }
         }
 
         // Sleep before next iteration
@@ -127,26 +338,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+/// Records how a cycle went for `GET /api/admin/worker-status`
+async fn record_cycle<T>(pool: &sqlx::PgPool, task: WorkerTask, outcome: Result<T, String>) {
+    if let Err(e) = worker_status::record_cycle(pool, task, outcome.err().as_deref()).await {
+        eprintln!("Failed to record the {} heartbeat: {}", task.as_str(), e);
+    }
+}
+
 async fn claim_pending_jobs(pool: &sqlx::PgPool, limit: i32) -> Result<Vec<Job>, String> {
-    // Atomically claim pending jobs using FOR UPDATE SKIP LOCKED
+    // Atomically claim pending jobs using FOR UPDATE SKIP LOCKED, highest priority first
     sqlx::query_as::<_, Job>(
         r#"
         WITH claimed AS (
             SELECT id FROM jobs
             WHERE status = 'pending' 
                OR (status = 'scheduled' AND next_retry_at <= NOW())
-            ORDER BY created_at ASC
+            ORDER BY priority DESC, created_at ASC
             LIMIT $1
             FOR UPDATE SKIP LOCKED
+        ), updated AS (
+            UPDATE jobs 
+            SET status = 'processing', 
+                started_at = NOW(),
+                retry_count = retry_count + 1
+            FROM claimed
+            WHERE jobs.id = claimed.id
+            RETURNING jobs.id, jobs.job_type, jobs.payload, jobs.retry_count, jobs.created_at, jobs.priority
         )
-        UPDATE jobs 
-        SET status = 'processing', 
-            started_at = NOW(),
-            retry_count = retry_count + 1
-        FROM claimed
-        WHERE jobs.id = claimed.id
-        RETURNING jobs.id, jobs.workspace_id, jobs.job_type, jobs.payload, 
-                  jobs.status, jobs.retry_count, jobs.max_retries, jobs.created_at
+        SELECT id, job_type, payload, retry_count FROM updated ORDER BY priority DESC, created_at ASC
         "#
     )
     .bind(limit)
@@ -155,7 +374,12 @@ async fn claim_pending_jobs(pool: &sqlx::PgPool, limit: i32) -> Result<Vec<Job>,
     .map_err(|e| e.to_string())
 }
 
-async fn process_job(job: &Job, email_sender: &CampaignEmailSender) -> Result<(), String> {
+async fn process_job(
+    pool: &sqlx::PgPool,
+    job: &Job,
+    email_sender: &CampaignEmailSender,
+    inbox_limiter: &KeyedLimiter,
+) -> Result<(), JobError> {
     // Parse job type (it's stored as JSON string like "\"SendEmail\"")
     let job_type = job.job_type.trim_matches('"');
     
@@ -163,9 +387,43 @@ async fn process_job(job: &Job, email_sender: &CampaignEmailSender) -> Result<()
         "SendEmail" => {
             let payload: SendEmailJobPayload = serde_json::from_value(job.payload.clone())
                 .map_err(|e| format!("Invalid payload: {}", e))?;
-            
-            email_sender.send_campaign_email(&payload).await?;
-            println!("✉️  Sent email to {} for campaign {}", payload.email, payload.campaign_id);
+
+            let _permit = inbox_limiter.acquire(payload.inbox_id).await;
+            match email_sender.send_campaign_email(&payload).await? {
+                Some(_) => println!("✉️  Sent email to {} for campaign {}", payload.email, payload.campaign_id),
+                None => println!("🚫 Skipped {} for campaign {}: campaign paused or trashed, or recipient gone", payload.email, payload.campaign_id),
+            }
+            Ok(())
+        }
+        "DeliverZapierHook" => {
+            let payload: ZapierDeliveryPayload = serde_json::from_value(job.payload.clone())
+                .map_err(|e| format!("Invalid payload: {}", e))?;
+
+            match zapier::deliver(pool, &payload).await? {
+                DeliveryOutcome::Delivered => println!("🔗 Delivered Zapier hook for subscription {}", payload.subscription_id),
+                DeliveryOutcome::Gone => println!("🔗 Zapier subscription {} is gone, removed it", payload.subscription_id),
+                DeliveryOutcome::Unsubscribed => println!("🔗 Skipped Zapier hook: subscription {} was deleted", payload.subscription_id),
+            }
+            Ok(())
+        }
+        "SendMeetingReminder" => {
+            let payload: MeetingReminderPayload = serde_json::from_value(job.payload.clone())
+                .map_err(|e| format!("Invalid payload: {}", e))?;
+
+            if meeting_reminders::send_reminder(pool, email_sender, &payload).await? {
+                println!("⏰ Sent meeting reminder {}", payload.reminder_id);
+            } else {
+                println!("⏰ Skipped meeting reminder {}: no longer applies", payload.reminder_id);
+            }
+            Ok(())
+        }
+        "ClassifyReply" => {
+            let payload: ClassifyReplyPayload = serde_json::from_value(job.payload.clone())
+                .map_err(|e| format!("Invalid payload: {}", e))?;
+
+            for (reply_id, classification) in reply_classifier::classify_and_store_batch(pool, &payload.reply_ids()).await? {
+                println!("🏷️  Classified reply {} as {}", reply_id, classification.intent);
+            }
             Ok(())
         }
         "VerifyEmail" => {
@@ -174,8 +432,14 @@ async fn process_job(job: &Job, email_sender: &CampaignEmailSender) -> Result<()
             Ok(())
         }
         "WarmupEmail" => {
-            // TODO: Implement warmup email job
-            println!("🔥 Warmup email job (not implemented)");
+            let payload: WarmupEmailPayload = serde_json::from_value(job.payload.clone())
+                .map_err(|e| format!("Invalid payload: {}", e))?;
+
+            let _permit = inbox_limiter.acquire(payload.email_account_id).await;
+            match email_sender.send_warmup_email(&payload).await? {
+                Some(_) => println!("🔥 Sent warmup email from {} to {}", payload.email_account_id, payload.target_email),
+                None => println!("🔥 Warmup email from {} to {} not sent", payload.email_account_id, payload.target_email),
+            }
             Ok(())
         }
         "ProcessCampaign" => {
@@ -184,7 +448,7 @@ async fn process_job(job: &Job, email_sender: &CampaignEmailSender) -> Result<()
             Ok(())
         }
         _ => {
-            Err(format!("Unknown job type: {}", job_type))
+            Err(format!("Unknown job type: {}", job_type).into())
         }
     }
 }
@@ -201,35 +465,23 @@ async fn mark_completed(pool: &sqlx::PgPool, job_id: Uuid) -> Result<(), String>
     Ok(())
 }
 
-async fn mark_failed(pool: &sqlx::PgPool, job_id: Uuid, error: &str) -> Result<(), String> {
-    // Exponential backoff: 5min, 20min, 80min
+/// Puts a job back in the queue for `run_at`, handing back the retry its claim took
+async fn mark_deferred(pool: &sqlx::PgPool, job_id: Uuid, run_at: DateTime<Utc>) -> Result<(), String> {
     sqlx::query(
         r#"
         UPDATE jobs
-        SET status = CASE 
-                WHEN retry_count >= max_retries THEN 'failed'
-                ELSE 'scheduled'
-            END,
-            error = $2,
-            next_retry_at = CASE
-                WHEN retry_count < max_retries 
-                THEN NOW() + interval '5 minutes' * POWER(2, retry_count)
-                ELSE NULL
-            END
+        SET status = 'scheduled',
+            next_retry_at = $2,
+            retry_count = GREATEST(retry_count - 1, 0)
         WHERE id = $1
         "#
     )
     .bind(job_id)
-    .bind(error)
+    .bind(run_at)
     .execute(pool)
     .await
     .map_err(|e| e.to_string())?;
-    
+
     Ok(())
 }
 
-fn should_reset_daily_counters() -> bool {
-    // Check if it's around midnight (between 00:00 and 00:05)
-    let now = Utc::now();
-    now.time().hour() == 0 && now.time().minute() < 5
-}
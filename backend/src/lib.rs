@@ -4,3 +4,6 @@ pub mod services;
 pub mod db;
 pub mod config;
 pub mod middleware;
+
+#[cfg(test)]
+mod test_db;
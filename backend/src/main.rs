@@ -4,13 +4,12 @@ use sqlx::postgres::PgPoolOptions;
 use dotenvy::dotenv;
 use std::env;
 
-mod api;
-mod models;
-mod services;
-mod db;
-mod config;
-mod middleware;
-use middleware as app_middleware;
+use outreachiq::api;
+use outreachiq::config::dkim_keys;
+use outreachiq::middleware as app_middleware;
+use outreachiq::services::encryption::EncryptionService;
+use outreachiq::services::jwt_keys::jwt_keys;
+use outreachiq::services::unsubscribe_token::unsubscribe_keys;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -19,6 +18,20 @@ async fn main() -> std::io::Result<()> {
 
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set");
+
+    // Refuse to start with a missing or weak JWT_SECRET
+    jwt_keys();
+
+    // Unsubscribe links are verified here, so a missing or weak UNSUBSCRIBE_SECRET stops startup too
+    unsubscribe_keys();
+
+    // DKIM keys are read and parsed once; a configured key that's broken stops startup
+    dkim_keys();
+
+    // Inbox credentials are only ever stored encrypted, so a bad key has to stop startup
+    if let Err(e) = EncryptionService::new() {
+        panic!("Invalid encryption configuration: {}", e);
+    }
     
     let pool = PgPoolOptions::new()
         .max_connections(10)
@@ -32,6 +45,8 @@ async fn main() -> std::io::Result<()> {
         .await
         .expect("Failed to run migrations");
 
+    let openapi = api::openapi::spec();
+
     println!("🚀 OutreachIQ API starting on http://0.0.0.0:8080");
 
     HttpServer::new(move || {
@@ -59,6 +74,8 @@ async fn main() -> std::io::Result<()> {
                 web::scope("/api")
                     .configure(api::auth::configure)
                     .configure(api::leads::configure)
+                    .configure(api::templates::configure)
+                    .configure(api::steps::configure)
                     .configure(api::campaigns::configure)
                     .configure(api::analytics::configure)
                     .configure(api::email_accounts::configure)
@@ -66,7 +83,17 @@ async fn main() -> std::io::Result<()> {
                     .configure(api::billing::configure)
                     .configure(api::signals::configure)
                     .configure(api::founder_dashboard::configure)
+                    .configure(api::admin::configure)
+                    .configure(api::jobs::configure)
+                    .configure(api::webhooks::configure)
+                    .configure(api::tracking::configure)
+                    .configure(api::zapier::configure)
             )
+            .service(api::openapi::swagger_ui(openapi.clone()))
+            // Unsubscribe links in outgoing emails land here rather than under /api
+            .route("/unsubscribe", web::get().to(api::compliance::unsubscribe_landing))
+            .route("/unsubscribe", web::post().to(api::compliance::handle_unsubscribe_post))
+            .route("/unsubscribe/resubscribe", web::post().to(api::compliance::resubscribe))
             .route("/health", web::get().to(|| async { "OK" }))
     })
     .bind(("0.0.0.0", 8080))?
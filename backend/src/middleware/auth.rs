@@ -3,10 +3,14 @@ use actix_web::{
     Error, HttpMessage,
 };
 use futures_util::future::LocalBoxFuture;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use std::future::{ready, Ready};
-use std::env;
+
+use sqlx::PgPool;
+
+use crate::models::workspace::WorkspaceRole;
+use crate::services::jwt_keys::jwt_keys;
+use crate::services::workspace_membership::{self, RoleCheck};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -59,17 +63,24 @@ where
         // Skip auth for public routes
         if path.starts_with("/api/auth/") 
             || path.starts_with("/api/compliance/unsubscribe")
+            || path == "/api/compliance/resubscribe"
+            || path == "/unsubscribe"
+            || path == "/unsubscribe/resubscribe"
             || path == "/api/billing/webhook"
+            || path.starts_with("/api/webhooks/")
+            || path.starts_with("/api/track/")
             || path == "/api/billing/pricing"
             || path.starts_with("/api/signals/feed")
-            || path.starts_with("/api/signals/companies")
+            || path == "/api/signals/companies"
             || path.starts_with("/api/signals/company/")
             || path.starts_with("/api/signals/stats")
+            || path == "/api-docs/openapi.json"
+            || path.starts_with("/swagger-ui")
             || path == "/health" 
             || path == "/" 
         {
             let fut = self.service.call(req);
-            return Box::pin(async move { fut.await });
+            return Box::pin(fut);
         }
 
         // Extract JWT from Authorization header
@@ -78,8 +89,8 @@ where
         let token = match auth_header {
             Some(header) => {
                 let header_str = header.to_str().unwrap_or("");
-                if header_str.starts_with("Bearer ") {
-                    header_str[7..].to_string()
+                if let Some(token) = header_str.strip_prefix("Bearer ") {
+                    token.to_string()
                 } else {
                     return Box::pin(async {
                         Err(actix_web::error::ErrorUnauthorized("Invalid authorization header format"))
@@ -93,19 +104,10 @@ where
             }
         };
 
-        // Decode and validate JWT
-        let jwt_secret = env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "your-super-secret-jwt-key-change-in-production".to_string());
-        
-        let validation = Validation::default();
-        let token_data = match decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(jwt_secret.as_bytes()),
-            &validation,
-        ) {
-            Ok(data) => data,
-            Err(e) => {
-                eprintln!("JWT decode error: {:?}", e);
+        // Decode and validate JWT against the current or previous secret
+        let claims = match jwt_keys().decode::<Claims>(&token) {
+            Some(claims) => claims,
+            None => {
                 return Box::pin(async {
                     Err(actix_web::error::ErrorUnauthorized("Invalid or expired token"))
                 });
@@ -113,10 +115,10 @@ where
         };
 
         // Add claims to request extensions for downstream handlers
-        req.extensions_mut().insert(token_data.claims);
+        req.extensions_mut().insert(claims);
 
         let fut = self.service.call(req);
-        Box::pin(async move { fut.await })
+        Box::pin(fut)
     }
 }
 
@@ -142,6 +144,30 @@ pub fn require_role(claims: &Claims, required_roles: &[&str]) -> Result<(), acti
     }
 }
 
+/// Checks the caller's role in the token's workspace against `min_role`, using the
+/// hierarchy owner > admin > member > viewer. Unlike `require_role`, which looks at the
+/// account-wide role in the token, this reads `workspace_members`.
+pub async fn require_workspace_role(
+    req: &actix_web::HttpRequest,
+    pool: &PgPool,
+    min_role: WorkspaceRole,
+) -> Result<Claims, actix_web::Error> {
+    let claims = extract_claims(req)?;
+    let workspace_id = get_workspace_id(&claims)?;
+    let user_id = get_user_id(&claims)?;
+
+    match workspace_membership::check_role(pool, user_id, workspace_id, &min_role).await {
+        Ok(RoleCheck::Allowed(_)) => Ok(claims),
+        Ok(RoleCheck::NotMember) => Err(actix_web::error::ErrorForbidden("You are not a member of this workspace")),
+        Ok(RoleCheck::Insufficient(role)) => Err(actix_web::error::ErrorForbidden(format!(
+            "This action needs the {} role or higher; you are a {} in this workspace",
+            min_role.as_str(),
+            role.as_str()
+        ))),
+        Err(e) => Err(actix_web::error::ErrorInternalServerError(e)),
+    }
+}
+
 // Check if user can write (owner, admin, member)
 pub fn require_write_access(claims: &Claims) -> Result<(), actix_web::Error> {
     require_role(claims, &["owner", "admin", "member"])
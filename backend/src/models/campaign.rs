@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Campaign {
     pub id: Uuid,
     pub name: String,
@@ -14,14 +15,28 @@ pub struct Campaign {
     pub opened: i32,
     pub clicked: i32,
     pub replied: i32,
+    pub bounced: i32,
     pub created_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
     pub workspace_id: Option<Uuid>,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub scheduled_start_at: Option<DateTime<Utc>>,
+    /// Where to read each lead's timezone so sends wait for their business hours:
+    /// `timezone_offset_minutes`, or a key in the lead's signals holding an IANA zone
+    pub recipient_timezone_field: Option<String>,
+    /// Auto-pause thresholds for this campaign; unset ones use the workspace's
+    pub spam_rate_threshold: Option<f64>,
+    pub reply_drop_threshold: Option<f64>,
+    pub bounce_rate_threshold: Option<f64>,
+    /// Why an active campaign isn't sending, e.g. `monthly_email_limit_reached`
+    pub send_blocked_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CampaignStatus {
     Draft,
+    Scheduled,
     Active,
     Paused,
     Completed,
@@ -31,11 +46,23 @@ impl CampaignStatus {
     pub fn as_str(&self) -> &'static str {
         match self {
             CampaignStatus::Draft => "draft",
+            CampaignStatus::Scheduled => "scheduled",
             CampaignStatus::Active => "active",
             CampaignStatus::Paused => "paused",
             CampaignStatus::Completed => "completed",
         }
     }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "draft" => Some(CampaignStatus::Draft),
+            "scheduled" => Some(CampaignStatus::Scheduled),
+            "active" => Some(CampaignStatus::Active),
+            "paused" => Some(CampaignStatus::Paused),
+            "completed" => Some(CampaignStatus::Completed),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for CampaignStatus {
@@ -44,17 +71,138 @@ impl std::fmt::Display for CampaignStatus {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CampaignTagsRequest {
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateCampaignRequest {
     pub name: String,
     pub vertical: String,
     pub lead_ids: Option<Vec<Uuid>>,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub recipient_timezone_field: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateCampaignRequest {
     pub name: Option<String>,
     pub status: Option<String>,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    /// Empty string stops waiting for recipients' business hours
+    pub recipient_timezone_field: Option<String>,
+    /// Auto-pause overrides as fractions (0.05 = 5%); 0 goes back to the workspace default
+    pub spam_rate_threshold: Option<f64>,
+    pub reply_drop_threshold: Option<f64>,
+    pub bounce_rate_threshold: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StartCampaignRequest {
+    /// When set, the campaign is queued as `scheduled` and activated by the worker at this time.
+    pub scheduled_start_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ScheduleCampaignRequest {
+    /// When the worker should start the campaign; must be in the future
+    pub scheduled_start_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PreviewCampaignRequest {
+    /// Lead to render for; omit to preview with a sample lead
+    pub lead_id: Option<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SendTestEmailRequest {
+    /// Lead to render for; omit to use a sample lead
+    pub lead_id: Option<Uuid>,
+    /// Inbox to send from; defaults to the one the campaign last sent from
+    pub inbox_id: Option<Uuid>,
+}
+
+/// Email for one step of a campaign, with Handlebars merge fields
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CampaignTemplate {
+    pub id: Uuid,
+    pub campaign_id: Uuid,
+    pub step_order: i32,
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CampaignTemplateRequest {
+    /// Position in the sequence, starting at 1; defaults to the next free step
+    pub step_order: Option<i32>,
+    pub subject: String,
+    /// Must contain `{{unsubscribe_url}}`
+    pub body_html: String,
+    /// Plain-text part; generated from the HTML when omitted
+    pub body_text: Option<String>,
+}
+
+/// One email of a campaign's follow-up sequence
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CampaignStep {
+    pub id: Uuid,
+    pub campaign_id: Uuid,
+    /// 0 is the first email; later steps are follow-ups
+    pub step_index: i32,
+    pub template_id: Option<Uuid>,
+    /// Days to wait after the previous send
+    pub delay_days: i32,
+    /// `no_reply`, `not_opened` or `opened`
+    pub condition: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CampaignStepRequest {
+    /// Defaults to the next free index
+    pub step_index: Option<i32>,
+    /// One of the campaign's templates; omit to use the built-in default
+    pub template_id: Option<Uuid>,
+    #[serde(default)]
+    pub delay_days: i32,
+    /// Defaults to `no_reply`
+    pub condition: Option<String>,
+}
+
+/// Returns true if `reply_to` is absent, empty, or a parseable mailbox address.
+pub fn is_valid_reply_to(reply_to: Option<&str>) -> bool {
+    match reply_to.map(str::trim) {
+        None | Some("") => true,
+        Some(addr) => addr.parse::<lettre::Address>().is_ok(),
+    }
+}
+
+/// An inbox assigned to send a campaign
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CampaignInbox {
+    pub email_account_id: Uuid,
+    pub email: String,
+    pub warmup_status: String,
+    pub daily_limit: i32,
+    pub sent_today: i32,
+    /// `healthy`, `warning` or `danger`; danger inboxes are skipped when sending
+    pub health_status: String,
+    pub assigned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignInboxesRequest {
+    /// Replaces the campaign's inboxes. Empty sends from every inbox in the workspace.
+    pub email_account_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -72,6 +220,27 @@ pub struct CampaignLead {
     pub click_count: Option<i32>,
     pub bounce_type: Option<String>,
     pub unsubscribed_at: Option<DateTime<Utc>>,
+    /// `workspace` or `campaign` once the lead has unsubscribed
+    pub unsubscribe_scope: Option<String>,
+}
+
+/// Archived copy of an email exactly as it was sent to a campaign lead
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SentEmail {
+    pub id: Uuid,
+    pub campaign_id: Uuid,
+    pub campaign_lead_id: Uuid,
+    pub lead_id: Uuid,
+    pub email_account_id: Option<Uuid>,
+    pub from_email: String,
+    pub to_email: String,
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: String,
+    pub message_id: String,
+    pub smtp_response: Option<String>,
+    pub sent_at: DateTime<Utc>,
+    pub step_index: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
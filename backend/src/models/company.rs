@@ -19,6 +19,8 @@ pub struct Company {
     pub twitter_handle: Option<String>,
     pub linkedin_url: Option<String>,
     pub wellfound_slug: Option<String>,
+    pub rss_feed_url: Option<String>,
+    pub crunchbase_permalink: Option<String>,
     pub is_active: bool,
     pub last_scraped_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -35,6 +37,8 @@ pub struct CreateCompany {
     pub github_org: Option<String>,
     pub twitter_handle: Option<String>,
     pub wellfound_slug: Option<String>,
+    pub rss_feed_url: Option<String>,
+    pub crunchbase_permalink: Option<String>,
 }
 
 impl Company {
@@ -66,6 +70,29 @@ impl Company {
         .await
     }
 
+    /// Active companies by name, `limit` at a time. `after` is the (name, id) of the
+    /// last company on the previous page.
+    pub async fn find_active_page(
+        pool: &sqlx::PgPool,
+        limit: i64,
+        after: Option<(&str, Uuid)>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            SELECT * FROM companies
+            WHERE is_active = TRUE
+              AND ($2::text IS NULL OR (name, id) > ($2, $3))
+            ORDER BY name, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .bind(after.map(|(name, _)| name))
+        .bind(after.map(|(_, id)| id))
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_needing_scrape(
         pool: &sqlx::PgPool,
         source: &str,
@@ -76,8 +103,17 @@ impl Company {
             SELECT c.* FROM companies c
             LEFT JOIN scraper_state ss ON ss.company_id = c.id AND ss.source = $1
             WHERE c.is_active = TRUE
-              AND (ss.last_successful_at IS NULL 
-                   OR ss.last_successful_at < NOW() - INTERVAL '1 hour' * $2)
+              -- Only companies the source can be scraped for
+              AND CASE $1
+                      WHEN 'github' THEN c.github_org IS NOT NULL
+                      WHEN 'wellfound' THEN c.wellfound_slug IS NOT NULL
+                      WHEN 'rss_feed' THEN c.rss_feed_url IS NOT NULL
+                      WHEN 'crunchbase' THEN c.crunchbase_permalink IS NOT NULL
+                      ELSE TRUE
+                  END
+              -- Failed attempts also wait out the interval instead of retrying every run
+              AND (ss.last_scraped_at IS NULL 
+                   OR ss.last_scraped_at < NOW() - INTERVAL '1 hour' * $2)
             ORDER BY ss.last_successful_at NULLS FIRST
             LIMIT 50
             "#,
@@ -19,6 +19,8 @@ pub enum SuppressionReason {
     Bounced,
     Complained,
     Manual,
+    /// Left behind by a data erasure request; the address can't be added as a lead again
+    Erased,
 }
 
 impl SuppressionReason {
@@ -28,15 +30,17 @@ impl SuppressionReason {
             SuppressionReason::Bounced => "bounced",
             SuppressionReason::Complained => "complained",
             SuppressionReason::Manual => "manual",
+            SuppressionReason::Erased => "erased",
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "unsubscribed" => Some(SuppressionReason::Unsubscribed),
             "bounced" => Some(SuppressionReason::Bounced),
             "complained" => Some(SuppressionReason::Complained),
             "manual" => Some(SuppressionReason::Manual),
+            "erased" => Some(SuppressionReason::Erased),
             _ => None,
         }
     }
@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Lead {
     pub id: Uuid,
     pub email: String,
@@ -13,14 +14,18 @@ pub struct Lead {
     pub title: Option<String>,
     pub linkedin_url: Option<String>,
     pub verification_status: String,
-    pub confidence_score: f32,
+    pub confidence_score: f64,
     pub signals: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub verified_at: Option<DateTime<Utc>>,
     pub workspace_id: Option<Uuid>,
+    pub timezone_offset_minutes: Option<i32>,
+    pub preferred_send_hour: Option<i16>,
+    /// Extra data from the lead's source, usable as template merge tags
+    pub custom_fields: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum VerificationStatus {
     Pending,
     Valid,
@@ -37,6 +42,16 @@ impl VerificationStatus {
             VerificationStatus::Risky => "risky",
         }
     }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "pending" => Some(VerificationStatus::Pending),
+            "valid" => Some(VerificationStatus::Valid),
+            "invalid" => Some(VerificationStatus::Invalid),
+            "risky" => Some(VerificationStatus::Risky),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for VerificationStatus {
@@ -54,15 +69,21 @@ pub struct LeadSignals {
     pub growth_indicators: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LeadSearchQuery {
     pub vertical: String,
     pub role: Option<String>,
     pub company_size: Option<String>,
     pub signals: Option<Vec<String>>,
+    /// Leads to generate, 50 by default and at most 500
     pub limit: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LeadTagsRequest {
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LeadResponse {
     pub id: Uuid,
@@ -73,7 +94,7 @@ pub struct LeadResponse {
     pub title: Option<String>,
     pub linkedin_url: Option<String>,
     pub verification_status: String,
-    pub confidence_score: f32,
+    pub confidence_score: f64,
     pub signals: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub workspace_id: Option<Uuid>,
@@ -5,3 +5,4 @@ pub mod user;
 pub mod compliance;
 pub mod company;
 pub mod signal;
+pub mod pagination;
@@ -0,0 +1,66 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl PageQuery {
+    /// Clamps the requested limit to `1..=MAX_PAGE_SIZE`, defaulting to `DEFAULT_PAGE_SIZE`.
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+}
+
+/// Standard envelope for paginated list endpoints.
+#[derive(Debug, Serialize)]
+pub struct Paginated<T: Serialize> {
+    pub data: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Keyset position: the sort key and id of the last row on a page. Clients get it
+/// as an opaque URL-safe string and send it back to fetch the rows after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub key: String,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(key: impl Into<String>, id: Uuid) -> Self {
+        Self { key: key.into(), id }
+    }
+
+    pub fn from_timestamp(at: DateTime<Utc>, id: Uuid) -> Self {
+        Self::new(at.timestamp_micros().to_string(), id)
+    }
+
+    /// The key as a timestamp, for cursors made with `from_timestamp`
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_micros(self.key.parse().ok()?)
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.id, self.key))
+    }
+
+    pub fn decode(encoded: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(encoded).ok()?).ok()?;
+        let (id, key) = raw.split_once('|')?;
+        Some(Self::new(key, id.parse().ok()?))
+    }
+}
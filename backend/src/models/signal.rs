@@ -2,6 +2,7 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use utoipa::ToSchema;
 
 // ============================================================================
 // Signal Types (matching DB enum)
@@ -77,7 +78,7 @@ impl std::fmt::Display for SignalSource {
 // Main Signal Model
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Signal {
     pub id: Uuid,
     pub company_id: Uuid,
@@ -134,6 +135,19 @@ pub struct PublicSignal {
     pub industry: Option<String>,
 }
 
+/// Public feed filters; `None` fields don't filter
+#[derive(Debug, Clone, Default)]
+pub struct FeedFilter {
+    pub signal_type: Option<String>,
+    pub company_id: Option<Uuid>,
+    /// Lowest confidence score to include, 0.0-1.0
+    pub min_confidence: Option<f64>,
+    /// Only signals detected at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only signals from these sources, e.g. `github`, `rss_feed`
+    pub sources: Option<Vec<String>>,
+}
+
 // ============================================================================
 // Hiring Signal Detail
 // ============================================================================
@@ -228,6 +242,19 @@ pub struct FundingSignal {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFundingSignal {
+    pub signal_id: Uuid,
+    pub round_type: Option<String>,
+    /// USD cents, as stored in `funding_signals.amount_usd`
+    pub amount_usd: Option<i64>,
+    pub amount_display: Option<String>,
+    pub investors: Vec<String>,
+    pub lead_investor: Option<String>,
+    pub announced_date: Option<NaiveDate>,
+    pub source_article_url: Option<String>,
+}
+
 // ============================================================================
 // Confidence Scoring (Rule-Based, NOT AI)
 // ============================================================================
@@ -315,16 +342,35 @@ impl Signal {
             .await
     }
 
+    /// Newest published signals matching `filter` first. `after` is the (detected_at, id)
+    /// of the last signal on the previous page; ties on detected_at are broken by id
+    /// so pages never overlap or skip.
     pub async fn find_recent(
         pool: &sqlx::PgPool,
+        filter: &FeedFilter,
         limit: i64,
+        after: Option<(DateTime<Utc>, Uuid)>,
     ) -> Result<Vec<PublicSignal>, sqlx::Error> {
         sqlx::query_as::<_, PublicSignal>(
             r#"
             SELECT * FROM public_signal_feed
-            LIMIT $1
+            WHERE ($1::text IS NULL OR signal_type = $1)
+              AND ($2::uuid IS NULL OR company_id = $2)
+              AND ($3::float8 IS NULL OR confidence_score >= $3)
+              AND ($4::timestamptz IS NULL OR detected_at >= $4)
+              AND ($5::text[] IS NULL OR source = ANY($5))
+              AND ($6::timestamptz IS NULL OR (detected_at, id) < ($6, $7))
+            ORDER BY detected_at DESC, id DESC
+            LIMIT $8
             "#,
         )
+        .bind(&filter.signal_type)
+        .bind(filter.company_id)
+        .bind(filter.min_confidence)
+        .bind(filter.since)
+        .bind(&filter.sources)
+        .bind(after.map(|(at, _)| at))
+        .bind(after.map(|(_, id)| id))
         .bind(limit)
         .fetch_all(pool)
         .await
@@ -342,22 +388,18 @@ impl Signal {
         .await
     }
 
+    /// `find_recent` for one signal type
     pub async fn find_by_type(
         pool: &sqlx::PgPool,
         signal_type: &str,
         limit: i64,
+        after: Option<(DateTime<Utc>, Uuid)>,
     ) -> Result<Vec<PublicSignal>, sqlx::Error> {
-        sqlx::query_as::<_, PublicSignal>(
-            r#"
-            SELECT * FROM public_signal_feed
-            WHERE signal_type = $1
-            LIMIT $2
-            "#,
-        )
-        .bind(signal_type)
-        .bind(limit)
-        .fetch_all(pool)
-        .await
+        let filter = FeedFilter {
+            signal_type: Some(signal_type.to_string()),
+            ..Default::default()
+        };
+        Self::find_recent(pool, &filter, limit, after).await
     }
 
     pub async fn exists_duplicate(
@@ -386,6 +428,24 @@ impl Signal {
 
         Ok(result > 0)
     }
+
+    /// Whether a signal already links to `source_url`, regardless of age. Feed posts stay
+    /// in the lookback window longer than `exists_duplicate` remembers titles for.
+    pub async fn exists_for_source_url(
+        pool: &sqlx::PgPool,
+        company_id: Uuid,
+        source: &str,
+        source_url: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM signals WHERE company_id = $1 AND source = $2 AND source_url = $3)",
+        )
+        .bind(company_id)
+        .bind(source)
+        .bind(source_url)
+        .fetch_one(pool)
+        .await
+    }
 }
 
 impl HiringSignal {
@@ -451,3 +511,56 @@ impl GithubSignal {
         .await
     }
 }
+
+impl FundingSignal {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        signal: CreateFundingSignal,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO funding_signals (
+                signal_id, round_type, amount_usd, amount_display, investors,
+                lead_investor, announced_date, source_article_url
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(signal.signal_id)
+        .bind(&signal.round_type)
+        .bind(signal.amount_usd)
+        .bind(&signal.amount_display)
+        .bind(&signal.investors)
+        .bind(&signal.lead_investor)
+        .bind(signal.announced_date)
+        .bind(&signal.source_article_url)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Whether the company already has a signal for this round. Missing round types
+    /// and dates only match other rounds that are missing them too.
+    pub async fn exists_for_round(
+        pool: &sqlx::PgPool,
+        company_id: Uuid,
+        round_type: Option<&str>,
+        announced_date: Option<NaiveDate>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM funding_signals fs
+                JOIN signals s ON s.id = fs.signal_id
+                WHERE s.company_id = $1
+                  AND fs.round_type IS NOT DISTINCT FROM $2
+                  AND fs.announced_date IS NOT DISTINCT FROM $3
+            )
+            "#,
+        )
+        .bind(company_id)
+        .bind(round_type)
+        .bind(announced_date)
+        .fetch_one(pool)
+        .await
+    }
+}
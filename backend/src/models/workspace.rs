@@ -32,7 +32,7 @@ impl PlanTier {
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "starter" => Some(PlanTier::Starter),
             "professional" => Some(PlanTier::Professional),
@@ -85,7 +85,7 @@ impl WorkspaceRole {
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "owner" => Some(WorkspaceRole::Owner),
             "admin" => Some(WorkspaceRole::Admin),
@@ -95,6 +95,20 @@ impl WorkspaceRole {
         }
     }
 
+    /// Higher ranks include everything lower ones can do: owner > admin > member > viewer
+    pub fn rank(&self) -> u8 {
+        match self {
+            WorkspaceRole::Owner => 3,
+            WorkspaceRole::Admin => 2,
+            WorkspaceRole::Member => 1,
+            WorkspaceRole::Viewer => 0,
+        }
+    }
+
+    pub fn is_at_least(&self, min_role: &WorkspaceRole) -> bool {
+        self.rank() >= min_role.rank()
+    }
+
     pub fn can_write(&self) -> bool {
         matches!(self, WorkspaceRole::Owner | WorkspaceRole::Admin | WorkspaceRole::Member)
     }
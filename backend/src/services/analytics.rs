@@ -0,0 +1,738 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::services::auto_pause::inbox_health_status;
+
+/// Most buckets one time-series request may return (a year of days)
+pub const MAX_TIMESERIES_BUCKETS: usize = 366;
+/// Days of snapshots the inbox ranking compares to find each inbox's trend
+pub const INBOX_RANKING_TREND_DAYS: i32 = 7;
+/// Smallest change in health score that counts as a trend rather than noise
+const HEALTH_TREND_MIN_CHANGE: f64 = 5.0;
+
+/// Which `campaign_leads` timestamp a time series counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeseriesMetric {
+    Sent,
+    Opened,
+    Replied,
+}
+
+impl TimeseriesMetric {
+    fn column(self) -> &'static str {
+        match self {
+            TimeseriesMetric::Sent => "cl.sent_at",
+            TimeseriesMetric::Opened => "cl.opened_at",
+            TimeseriesMetric::Replied => "cl.replied_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeseriesInterval {
+    #[default]
+    Day,
+    /// Weeks start on Monday, as Postgres' `date_trunc('week', ...)` does
+    Week,
+}
+
+impl TimeseriesInterval {
+    fn as_sql(self) -> &'static str {
+        match self {
+            TimeseriesInterval::Day => "day",
+            TimeseriesInterval::Week => "week",
+        }
+    }
+
+    fn step(self) -> Duration {
+        match self {
+            TimeseriesInterval::Day => Duration::days(1),
+            TimeseriesInterval::Week => Duration::weeks(1),
+        }
+    }
+
+    /// First day of the bucket `date` falls in
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            TimeseriesInterval::Day => date,
+            TimeseriesInterval::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+}
+
+/// An inclusive range of UTC days split into buckets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeseriesRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub interval: TimeseriesInterval,
+}
+
+impl TimeseriesRange {
+    /// Refuses inverted ranges and ones that would need more than
+    /// `MAX_TIMESERIES_BUCKETS` buckets
+    pub fn new(from: NaiveDate, to: NaiveDate, interval: TimeseriesInterval) -> Result<Self, String> {
+        if to < from {
+            return Err("`to` must not be before `from`".to_string());
+        }
+
+        let range = Self { from, to, interval };
+        let buckets = (interval.bucket_start(to) - interval.bucket_start(from)).num_days() / interval.step().num_days() + 1;
+        if buckets as usize > MAX_TIMESERIES_BUCKETS {
+            return Err(format!(
+                "Range needs {} buckets; at most {} are allowed, so narrow it or use a weekly interval",
+                buckets, MAX_TIMESERIES_BUCKETS
+            ));
+        }
+
+        Ok(range)
+    }
+
+    /// Start of every bucket in the range, oldest first. The first weekly bucket
+    /// may start before `from`; only events inside the range are counted in it.
+    pub fn buckets(&self) -> Vec<NaiveDate> {
+        let mut buckets = Vec::new();
+        let mut bucket = self.interval.bucket_start(self.from);
+        while bucket <= self.to {
+            buckets.push(bucket);
+            bucket += self.interval.step();
+        }
+        buckets
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeseriesPoint {
+    pub bucket: NaiveDate,
+    pub count: i64,
+}
+
+/// Puts counts into every bucket of the range, zero where nothing happened
+pub fn fill_buckets(range: &TimeseriesRange, counts: &[(NaiveDate, i64)]) -> Vec<TimeseriesPoint> {
+    let counts: HashMap<NaiveDate, i64> = counts.iter().copied().collect();
+    range
+        .buckets()
+        .into_iter()
+        .map(|bucket| TimeseriesPoint { bucket, count: counts.get(&bucket).copied().unwrap_or(0) })
+        .collect()
+}
+
+/// How many of the workspace's leads hit `metric` in each bucket, optionally
+/// for one campaign. Days are UTC.
+pub async fn timeseries(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    campaign_id: Option<Uuid>,
+    metric: TimeseriesMetric,
+    range: &TimeseriesRange,
+) -> Result<Vec<TimeseriesPoint>, sqlx::Error> {
+    let counts: Vec<(NaiveDate, i64)> = sqlx::query_as(&format!(
+        r#"
+        SELECT date_trunc('{interval}', {column} AT TIME ZONE 'UTC')::date AS bucket, COUNT(*)
+        FROM campaign_leads cl
+        JOIN campaigns c ON c.id = cl.campaign_id
+        WHERE c.workspace_id = $1
+          AND ($2::uuid IS NULL OR cl.campaign_id = $2)
+          AND {column} >= ($3::date)::timestamp AT TIME ZONE 'UTC'
+          AND {column} < ($4::date + 1)::timestamp AT TIME ZONE 'UTC'
+        GROUP BY 1
+        "#,
+        interval = range.interval.as_sql(),
+        column = metric.column(),
+    ))
+    .bind(workspace_id)
+    .bind(campaign_id)
+    .bind(range.from)
+    .bind(range.to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(fill_buckets(range, &counts))
+}
+
+/// What the workspace's campaign mail did over a range of days, as input to
+/// `DeliverabilityService::generate_report`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct DeliverabilityTotals {
+    /// Leads sent to, plus sends the recipient's server refused outright
+    pub total_sent: i64,
+    /// Sends confirmed by a delivered, open or click event, or by a reply
+    pub delivered: i64,
+    /// Recipients that hard bounced. Soft bounces are retried and not counted.
+    pub bounced: i64,
+    pub spam_complaints: i64,
+}
+
+/// Deliverability figures for the workspace over `range` (days are UTC, the
+/// interval is ignored). Repeat events for the same send count once.
+pub async fn deliverability_totals(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    range: &TimeseriesRange,
+) -> Result<DeliverabilityTotals, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH sent AS (
+            SELECT cl.id, cl.opened_at, cl.replied_at
+            FROM campaign_leads cl
+            JOIN campaigns c ON c.id = cl.campaign_id
+            WHERE c.workspace_id = $1
+              AND cl.sent_at >= ($2::date)::timestamp AT TIME ZONE 'UTC'
+              AND cl.sent_at < ($3::date + 1)::timestamp AT TIME ZONE 'UTC'
+        ),
+        hard_bounces AS (
+            SELECT DISTINCT ON (COALESCE(b.campaign_lead_id::text, b.recipient)) b.campaign_lead_id, b.source
+            FROM bounces b
+            WHERE b.workspace_id = $1
+              AND b.bounce_type = 'hard'
+              AND b.occurred_at >= ($2::date)::timestamp AT TIME ZONE 'UTC'
+              AND b.occurred_at < ($3::date + 1)::timestamp AT TIME ZONE 'UTC'
+            ORDER BY COALESCE(b.campaign_lead_id::text, b.recipient), b.occurred_at
+        )
+        SELECT
+            (SELECT COUNT(*) FROM sent)
+                + (SELECT COUNT(*) FROM hard_bounces hb
+                   WHERE hb.source = 'smtp' AND NOT EXISTS (SELECT 1 FROM sent s WHERE s.id = hb.campaign_lead_id)) AS total_sent,
+            (SELECT COUNT(*) FROM sent s
+             WHERE s.opened_at IS NOT NULL
+                OR s.replied_at IS NOT NULL
+                OR EXISTS (
+                    SELECT 1 FROM email_events e
+                    WHERE e.campaign_lead_id = s.id AND e.event_type IN ('delivered', 'opened', 'clicked')
+                )) AS delivered,
+            (SELECT COUNT(*) FROM hard_bounces) AS bounced,
+            (SELECT COUNT(DISTINCT COALESCE(e.campaign_lead_id::text, LOWER(e.recipient)))
+             FROM email_events e
+             WHERE e.workspace_id = $1
+               AND e.event_type = 'spam_report'
+               AND e.occurred_at >= ($2::date)::timestamp AT TIME ZONE 'UTC'
+               AND e.occurred_at < ($3::date + 1)::timestamp AT TIME ZONE 'UTC') AS spam_complaints
+        "#
+    )
+    .bind(workspace_id)
+    .bind(range.from)
+    .bind(range.to)
+    .fetch_one(pool)
+    .await
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthTrend {
+    Improving,
+    Stable,
+    Declining,
+}
+
+impl HealthTrend {
+    /// Direction from the earliest to the latest health score of a period
+    pub fn between(earliest: f64, latest: f64) -> Self {
+        let change = latest - earliest;
+        if change >= HEALTH_TREND_MIN_CHANGE {
+            HealthTrend::Improving
+        } else if change <= -HEALTH_TREND_MIN_CHANGE {
+            HealthTrend::Declining
+        } else {
+            HealthTrend::Stable
+        }
+    }
+}
+
+/// One day of an inbox's health, from the last snapshot taken that day.
+/// Snapshots record `sent_today`, so the last one holds the day's total.
+/// Bounces and spam complaints are estimated from the inbox's rates.
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct InboxHealthPoint {
+    pub day: NaiveDate,
+    pub sent: i64,
+    pub delivered: i64,
+    pub bounced: i64,
+    pub replied: i64,
+    pub spam_complaints: i64,
+    pub spam_rate: f64,
+    pub bounce_rate: f64,
+    pub reply_rate: f64,
+    pub health_score: f64,
+    #[sqlx(skip)]
+    pub health_status: &'static str,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InboxHealthTotals {
+    pub sent: i64,
+    pub delivered: i64,
+    pub bounced: i64,
+    pub replied: i64,
+    pub spam_complaints: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InboxHealthSeries {
+    pub email_account_id: Uuid,
+    pub email: String,
+    /// Days with at least one snapshot, oldest first. Days the health job
+    /// didn't run are left out rather than reported as zero.
+    pub points: Vec<InboxHealthPoint>,
+    pub totals: InboxHealthTotals,
+    /// From the first to the last point; `None` with fewer than two
+    pub trend: Option<HealthTrend>,
+    /// Status of the last point
+    pub health_status: Option<&'static str>,
+}
+
+/// Daily health of one of the workspace's inboxes over `range` (days are UTC,
+/// the interval is ignored). `None` when the inbox isn't in the workspace.
+pub async fn inbox_health(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    email_account_id: Uuid,
+    range: &TimeseriesRange,
+) -> Result<Option<InboxHealthSeries>, sqlx::Error> {
+    let email: Option<String> = sqlx::query_scalar("SELECT email FROM email_accounts WHERE id = $1 AND workspace_id = $2")
+        .bind(email_account_id)
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some(email) = email else {
+        return Ok(None);
+    };
+
+    let mut points: Vec<InboxHealthPoint> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON ((measured_at AT TIME ZONE 'UTC')::date)
+            (measured_at AT TIME ZONE 'UTC')::date AS day,
+            COALESCE(emails_sent, 0)::bigint AS sent,
+            COALESCE(emails_delivered, 0)::bigint AS delivered,
+            ROUND(COALESCE(emails_sent, 0) * COALESCE(bounce_rate, 0))::bigint AS bounced,
+            COALESCE(emails_replied, 0)::bigint AS replied,
+            ROUND(COALESCE(emails_sent, 0) * COALESCE(spam_rate, 0))::bigint AS spam_complaints,
+            COALESCE(spam_rate, 0) AS spam_rate,
+            COALESCE(bounce_rate, 0) AS bounce_rate,
+            COALESCE(reply_rate, 0) AS reply_rate,
+            COALESCE(health_score, 100.0) AS health_score
+        FROM inbox_health_metrics
+        WHERE email_account_id = $1
+          AND workspace_id = $2
+          AND measured_at >= ($3::date)::timestamp AT TIME ZONE 'UTC'
+          AND measured_at < ($4::date + 1)::timestamp AT TIME ZONE 'UTC'
+        ORDER BY (measured_at AT TIME ZONE 'UTC')::date, measured_at DESC
+        "#
+    )
+    .bind(email_account_id)
+    .bind(workspace_id)
+    .bind(range.from)
+    .bind(range.to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut totals = InboxHealthTotals::default();
+    for point in &mut points {
+        point.health_status = inbox_health_status(point.spam_rate, point.bounce_rate);
+        totals.sent += point.sent;
+        totals.delivered += point.delivered;
+        totals.bounced += point.bounced;
+        totals.replied += point.replied;
+        totals.spam_complaints += point.spam_complaints;
+    }
+
+    let trend = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() > 1 => Some(HealthTrend::between(first.health_score, last.health_score)),
+        _ => None,
+    };
+
+    Ok(Some(InboxHealthSeries {
+        email_account_id,
+        email,
+        health_status: points.last().map(|p| p.health_status),
+        points,
+        totals,
+        trend,
+    }))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct InboxHealthRank {
+    #[sqlx(skip)]
+    pub rank: usize,
+    pub email_account_id: Uuid,
+    pub email: String,
+    pub health_score: f64,
+    pub spam_rate: f64,
+    pub bounce_rate: f64,
+    pub reply_rate: f64,
+    #[sqlx(skip)]
+    pub health_status: &'static str,
+    #[sqlx(skip)]
+    pub trend: Option<HealthTrend>,
+    /// When the latest snapshot was taken; `None` for inboxes the health job
+    /// hasn't measured yet, which are ranked on their live figures
+    pub measured_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    baseline_score: Option<f64>,
+    #[serde(skip)]
+    recent_snapshots: i64,
+}
+
+fn status_severity(status: &str) -> u8 {
+    match status {
+        "danger" => 2,
+        "warning" => 1,
+        _ => 0,
+    }
+}
+
+/// Orders inboxes healthiest first: by status, then score, then address, and
+/// numbers them from 1
+pub fn rank_inboxes(mut inboxes: Vec<InboxHealthRank>) -> Vec<InboxHealthRank> {
+    inboxes.sort_by(|a, b| {
+        status_severity(a.health_status)
+            .cmp(&status_severity(b.health_status))
+            .then(b.health_score.total_cmp(&a.health_score))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    for (idx, inbox) in inboxes.iter_mut().enumerate() {
+        inbox.rank = idx + 1;
+    }
+    inboxes
+}
+
+/// Every inbox in the workspace ranked by its latest snapshot, with its trend
+/// over the last `INBOX_RANKING_TREND_DAYS` days
+pub async fn inbox_ranking(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<InboxHealthRank>, sqlx::Error> {
+    let mut inboxes: Vec<InboxHealthRank> = sqlx::query_as(
+        r#"
+        SELECT
+            ea.id AS email_account_id,
+            ea.email,
+            COALESCE(latest.health_score, ea.health_score, 100.0) AS health_score,
+            COALESCE(latest.spam_rate, ea.spam_rate, 0) AS spam_rate,
+            COALESCE(latest.bounce_rate, ea.bounce_rate, 0) AS bounce_rate,
+            COALESCE(latest.reply_rate, ea.reply_rate, 0) AS reply_rate,
+            latest.measured_at,
+            recent.baseline_score,
+            recent.snapshots AS recent_snapshots
+        FROM email_accounts ea
+        LEFT JOIN LATERAL (
+            SELECT m.health_score, m.spam_rate, m.bounce_rate, m.reply_rate, m.measured_at
+            FROM inbox_health_metrics m
+            WHERE m.email_account_id = ea.id
+            ORDER BY m.measured_at DESC
+            LIMIT 1
+        ) latest ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT (array_agg(m.health_score ORDER BY m.measured_at))[1] AS baseline_score, COUNT(*) AS snapshots
+            FROM inbox_health_metrics m
+            WHERE m.email_account_id = ea.id
+              AND m.measured_at >= NOW() - make_interval(days => $2)
+        ) recent ON TRUE
+        WHERE ea.workspace_id = $1
+        "#
+    )
+    .bind(workspace_id)
+    .bind(INBOX_RANKING_TREND_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    for inbox in &mut inboxes {
+        inbox.health_status = inbox_health_status(inbox.spam_rate, inbox.bounce_rate);
+        inbox.trend = match inbox.baseline_score {
+            Some(baseline) if inbox.recent_snapshots > 1 => Some(HealthTrend::between(baseline, inbox.health_score)),
+            _ => None,
+        };
+    }
+
+    Ok(rank_inboxes(inboxes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use chrono::TimeZone;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_ranges_are_validated_and_weeks_start_on_monday() {
+        assert!(TimeseriesRange::new(date("2024-03-10"), date("2024-03-09"), TimeseriesInterval::Day).is_err());
+        assert!(TimeseriesRange::new(date("2024-01-01"), date("2024-12-31"), TimeseriesInterval::Day).is_ok());
+        assert!(TimeseriesRange::new(date("2024-01-01"), date("2025-01-01"), TimeseriesInterval::Day).is_err());
+        assert!(TimeseriesRange::new(date("2020-01-01"), date("2025-01-01"), TimeseriesInterval::Week).is_ok());
+
+        // Wednesday to the following Tuesday spans two weeks
+        let weekly = TimeseriesRange::new(date("2024-03-06"), date("2024-03-12"), TimeseriesInterval::Week).unwrap();
+        assert_eq!(weekly.buckets(), vec![date("2024-03-04"), date("2024-03-11")]);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_daily_buckets_across_a_week_fill_empty_days() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Timeseries").await;
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let other_campaign = test_db::campaign(&pool, workspace_id, "active").await;
+
+        // Two sends on Monday, one late on Wednesday, one on Sunday, one outside the range,
+        // and one on Monday in another campaign
+        let sends = [
+            (campaign_id, Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap()),
+            (campaign_id, Utc.with_ymd_and_hms(2024, 3, 4, 17, 30, 0).unwrap()),
+            (campaign_id, Utc.with_ymd_and_hms(2024, 3, 6, 23, 59, 59).unwrap()),
+            (campaign_id, Utc.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap()),
+            (campaign_id, Utc.with_ymd_and_hms(2024, 3, 11, 0, 0, 0).unwrap()),
+            (other_campaign, Utc.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap()),
+        ];
+        for (campaign, sent_at) in sends {
+            let lead_id = Uuid::new_v4();
+            sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, $3)")
+                .bind(lead_id)
+                .bind(workspace_id)
+                .bind(format!("{}@example.com", lead_id))
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO campaign_leads (id, campaign_id, lead_id, status, sent_at) VALUES ($1, $2, $3, 'sent', $4)")
+                .bind(Uuid::new_v4())
+                .bind(campaign)
+                .bind(lead_id)
+                .bind(sent_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let week = TimeseriesRange::new(date("2024-03-04"), date("2024-03-10"), TimeseriesInterval::Day).unwrap();
+        let one_campaign = timeseries(&pool, workspace_id, Some(campaign_id), TimeseriesMetric::Sent, &week).await.unwrap();
+        let workspace = timeseries(&pool, workspace_id, None, TimeseriesMetric::Sent, &week).await.unwrap();
+        let opened = timeseries(&pool, workspace_id, None, TimeseriesMetric::Opened, &week).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let counts = |points: &[TimeseriesPoint]| points.iter().map(|p| p.count).collect::<Vec<_>>();
+        assert_eq!(one_campaign.len(), 7);
+        assert_eq!(one_campaign[0].bucket, date("2024-03-04"));
+        assert_eq!(one_campaign[6].bucket, date("2024-03-10"));
+        assert_eq!(counts(&one_campaign), vec![2, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(counts(&workspace), vec![3, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(counts(&opened), vec![0; 7]);
+    }
+
+    #[test]
+    fn test_health_status_uses_the_auto_pause_thresholds() {
+        assert_eq!(inbox_health_status(0.02, 0.05), "healthy");
+        assert_eq!(inbox_health_status(0.021, 0.0), "warning");
+        assert_eq!(inbox_health_status(0.0, 0.06), "warning");
+        assert_eq!(inbox_health_status(0.031, 0.0), "danger");
+        assert_eq!(inbox_health_status(0.0, 0.081), "danger");
+
+        assert_eq!(HealthTrend::between(80.0, 85.0), HealthTrend::Improving);
+        assert_eq!(HealthTrend::between(80.0, 83.0), HealthTrend::Stable);
+        assert_eq!(HealthTrend::between(80.0, 75.0), HealthTrend::Declining);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_inbox_snapshots_aggregate_by_day_and_rank_by_health() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Inbox analytics").await;
+
+        // Live rates only matter for `unmeasured`, which has no snapshots
+        let mut inbox_ids = Vec::new();
+        for bounce_rate in [0.0, 0.0, 0.0, 0.09] {
+            let id = test_db::inbox(&pool, workspace_id, 50).await;
+            sqlx::query("UPDATE email_accounts SET bounce_rate = $2 WHERE id = $1")
+                .bind(id)
+                .bind(bounce_rate)
+                .execute(&pool)
+                .await
+                .unwrap();
+            inbox_ids.push(id);
+        }
+        let (steady, flagged, slipping, unmeasured) = (inbox_ids[0], inbox_ids[1], inbox_ids[2], inbox_ids[3]);
+
+        let today = Utc::now().date_naive();
+        let at = |days_ago: i64, hour: u32| (today - Duration::days(days_ago)).and_hms_opt(hour, 0, 0).unwrap().and_utc();
+        // (inbox, measured_at, sent, replied, spam_rate, bounce_rate, health_score)
+        let snapshots = [
+            (steady, at(10, 12), 100, 0, 0.0, 0.0, 60.0),
+            (steady, at(3, 8), 10, 0, 0.0, 0.1, 80.0),
+            (steady, at(3, 20), 40, 2, 0.0, 0.05, 82.0),
+            (steady, at(1, 20), 50, 3, 0.02, 0.02, 90.0),
+            (flagged, at(1, 20), 50, 0, 0.025, 0.0, 95.0),
+            (slipping, at(2, 20), 30, 1, 0.0, 0.0, 85.0),
+            (slipping, at(1, 20), 30, 0, 0.0, 0.0, 70.0),
+        ];
+        for (inbox, measured_at, sent, replied, spam_rate, bounce_rate, health_score) in snapshots {
+            sqlx::query(
+                r#"
+                INSERT INTO inbox_health_metrics (email_account_id, workspace_id, spam_rate, bounce_rate, health_score,
+                                                  emails_sent, emails_delivered, emails_replied, measured_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8)
+                "#
+            )
+            .bind(inbox)
+            .bind(workspace_id)
+            .bind(spam_rate)
+            .bind(bounce_rate)
+            .bind(health_score)
+            .bind(sent)
+            .bind(replied)
+            .bind(measured_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let range = TimeseriesRange::new(today - Duration::days(5), today, TimeseriesInterval::Day).unwrap();
+        let series = inbox_health(&pool, workspace_id, steady, &range).await.unwrap();
+        let elsewhere = inbox_health(&pool, Uuid::new_v4(), steady, &range).await.unwrap();
+        let ranking = inbox_ranking(&pool, workspace_id).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let series = series.unwrap();
+        let days: Vec<(NaiveDate, i64, i64, i64)> = series.points.iter().map(|p| (p.day, p.sent, p.bounced, p.spam_complaints)).collect();
+        assert_eq!(days, vec![(today - Duration::days(3), 40, 2, 0), (today - Duration::days(1), 50, 1, 1)]);
+        assert_eq!(
+            series.totals,
+            InboxHealthTotals { sent: 90, delivered: 90, bounced: 3, replied: 5, spam_complaints: 1 }
+        );
+        assert_eq!(series.trend, Some(HealthTrend::Improving));
+        assert_eq!(series.health_status, Some("healthy"));
+        assert!(elsewhere.is_none());
+
+        let order: Vec<(Uuid, usize, &str, Option<HealthTrend>)> =
+            ranking.iter().map(|r| (r.email_account_id, r.rank, r.health_status, r.trend)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (steady, 1, "healthy", Some(HealthTrend::Improving)),
+                (slipping, 2, "healthy", Some(HealthTrend::Declining)),
+                (flagged, 3, "warning", None),
+                (unmeasured, 4, "danger", None),
+            ]
+        );
+        assert!(ranking[3].measured_at.is_none());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_deliverability_report_is_built_from_tracked_events() {
+        use crate::services::bounces::{self, BounceSource, BounceType, NewBounce};
+        use crate::services::deliverability::DeliverabilityService;
+
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Deliverability").await;
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+
+        // Ten sends inside the week, one before it, and one refused by the recipient's server
+        let in_week = Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap();
+        let mut leads = Vec::new();
+        for n in 0..12 {
+            let (lead_id, campaign_lead_id) = (Uuid::new_v4(), Uuid::new_v4());
+            let sent_at = match n {
+                10 => Some(Utc.with_ymd_and_hms(2024, 2, 20, 10, 0, 0).unwrap()),
+                11 => None,
+                _ => Some(in_week),
+            };
+            sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, $3)")
+                .bind(lead_id)
+                .bind(workspace_id)
+                .bind(format!("lead{}-{}@example.com", n, lead_id))
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                r#"
+                INSERT INTO campaign_leads (id, campaign_id, lead_id, status, sent_at, opened_at, replied_at)
+                VALUES ($1, $2, $3, 'sent', $4, $5, $6)
+                "#
+            )
+            .bind(campaign_lead_id)
+            .bind(campaign_id)
+            .bind(lead_id)
+            .bind(sent_at)
+            .bind(matches!(n, 3 | 4).then_some(in_week))
+            .bind((n == 5).then_some(in_week))
+            .execute(&pool)
+            .await
+            .unwrap();
+            leads.push((campaign_lead_id, format!("lead{}-{}@example.com", n, lead_id)));
+        }
+
+        // Leads 0-3 delivered (3 also opened, counted once), 4 opened, 5 replied
+        for (campaign_lead_id, email) in &leads[0..4] {
+            sqlx::query(
+                "INSERT INTO email_events (workspace_id, campaign_id, campaign_lead_id, provider, event_type, recipient, occurred_at) VALUES ($1, $2, $3, 'sendgrid', 'delivered', $4, $5)",
+            )
+            .bind(workspace_id)
+            .bind(campaign_id)
+            .bind(campaign_lead_id)
+            .bind(email)
+            .bind(in_week)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO email_events (workspace_id, campaign_id, campaign_lead_id, provider, event_type, recipient, occurred_at) VALUES ($1, $2, $3, 'sendgrid', 'spam_report', $4, $5)",
+        )
+        .bind(workspace_id)
+        .bind(campaign_id)
+        .bind(leads[1].0)
+        .bind(&leads[1].1)
+        .bind(in_week)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Lead 6 hard bounced (reported twice), 7 soft bounced, and 11 was refused on both attempts
+        let bounce = |n: usize, bounce_type, source| NewBounce {
+            workspace_id: Some(workspace_id),
+            campaign_id: Some(campaign_id),
+            campaign_lead_id: Some(leads[n].0),
+            email_account_id: None,
+            recipient: &leads[n].1,
+            bounce_type,
+            source,
+            reason: None,
+            occurred_at: in_week,
+        };
+        for (n, bounce_type, source) in [
+            (6, BounceType::Hard, BounceSource::Webhook),
+            (6, BounceType::Hard, BounceSource::Webhook),
+            (7, BounceType::Soft, BounceSource::Smtp),
+            (11, BounceType::Hard, BounceSource::Smtp),
+            (11, BounceType::Hard, BounceSource::Smtp),
+        ] {
+            bounces::record(&pool, &bounce(n, bounce_type, source)).await.unwrap();
+        }
+
+        let week = TimeseriesRange::new(date("2024-03-04"), date("2024-03-10"), TimeseriesInterval::Day).unwrap();
+        let totals = deliverability_totals(&pool, workspace_id, &week).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(totals, DeliverabilityTotals { total_sent: 11, delivered: 6, bounced: 2, spam_complaints: 1 });
+
+        let report = DeliverabilityService::new().generate_report(
+            totals.total_sent as i32,
+            totals.delivered as i32,
+            totals.bounced as i32,
+            totals.spam_complaints as i32,
+        );
+        assert_eq!(report.delivery_rate, 6.0 / 11.0);
+        assert_eq!(report.bounce_rate, 2.0 / 11.0);
+        assert_eq!(report.spam_rate, 1.0 / 11.0);
+    }
+}
@@ -1,6 +1,41 @@
 use sqlx::PgPool;
 use uuid::Uuid;
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::services::deliverability::DeliverabilityService;
+use crate::services::health_score;
+use crate::services::pause_notifications::{self, PausedCampaign};
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
+
+pub const DEFAULT_AUTO_RESUME_COOLDOWN_HOURS: i32 = 24;
+pub const MAX_AUTO_RESUME_COOLDOWN_HOURS: i32 = 168;
+
+/// Below this many sends a reply rate is too noisy to compare
+const REPLY_DROP_MIN_SENDS: i32 = 50;
+/// The reply-drop baseline is the latest snapshot at least this many days old...
+const REPLY_BASELINE_DAYS: i32 = 2;
+/// ...and no older than this
+const REPLY_BASELINE_MAX_AGE_DAYS: i32 = 7;
+
+/// Rates above which an inbox snapshot is marked `danger`...
+pub const DANGER_SPAM_RATE: f64 = 0.03;
+pub const DANGER_BOUNCE_RATE: f64 = 0.08;
+/// ...and above which it's `warning`
+pub const WARNING_SPAM_RATE: f64 = 0.02;
+pub const WARNING_BOUNCE_RATE: f64 = 0.05;
+
+/// `healthy`, `warning` or `danger` for an inbox's rates, as stored in
+/// `inbox_health_metrics.health_status`
+pub fn inbox_health_status(spam_rate: f64, bounce_rate: f64) -> &'static str {
+    if spam_rate > DANGER_SPAM_RATE || bounce_rate > DANGER_BOUNCE_RATE {
+        "danger"
+    } else if spam_rate > WARNING_SPAM_RATE || bounce_rate > WARNING_BOUNCE_RATE {
+        "warning"
+    } else {
+        "healthy"
+    }
+}
 
 #[derive(Debug)]
 pub struct AutoPauseResult {
@@ -9,12 +44,15 @@ pub struct AutoPauseResult {
     pub detail: Option<String>,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 struct WorkspaceThresholds {
     auto_pause_enabled: bool,
     spam_rate_threshold: f64,
     reply_drop_threshold: f64,
     bounce_rate_threshold: f64,
+    deliverability_score_threshold: f64,
+    auto_resume_enabled: bool,
+    auto_resume_cooldown_hours: i32,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -24,14 +62,86 @@ struct CampaignMetrics {
     current_spam_rate: f64,
     current_reply_rate: f64,
     current_bounce_rate: f64,
+    current_sent: i32,
+    /// Reply rate from the campaign's snapshot about 48 hours ago; 0 without one
     previous_reply_rate: f64,
+    /// The campaign's own thresholds, where it sets them
+    spam_rate_threshold: Option<f64>,
+    reply_drop_threshold: Option<f64>,
+    bounce_rate_threshold: Option<f64>,
+    /// Auto-paused campaigns only: since when the metrics have been within thresholds
+    metrics_recovered_at: Option<DateTime<Utc>>,
 }
 
-pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<AutoPauseResult>, sqlx::Error> {
-    // Get workspace settings
+/// Which campaigns a health check measures
+#[derive(Debug, Clone, Copy)]
+enum CampaignScope {
+    /// Running campaigns, which may need pausing
+    Active,
+    /// Campaigns the health check paused and nobody has dealt with since, which
+    /// may be resumed. A campaign a person paused, or whose pause event they
+    /// resolved, is never in here.
+    AutoPaused,
+}
+
+impl CampaignScope {
+    fn filter(self) -> &'static str {
+        match self {
+            CampaignScope::Active => "c.status = 'active' AND COALESCE(c.auto_paused, FALSE) = FALSE",
+            CampaignScope::AutoPaused => {
+                "c.status = 'paused' AND c.auto_paused = TRUE AND c.deleted_at IS NULL \
+                 AND EXISTS (SELECT 1 FROM auto_pause_events e WHERE e.campaign_id = c.id AND e.is_resolved = FALSE)"
+            }
+        }
+    }
+}
+
+/// What the health check does with an auto-paused campaign
+#[derive(Debug, PartialEq)]
+enum ResumeStep {
+    /// Still over a threshold; the cooldown starts again once it recovers
+    StillUnhealthy,
+    /// Within thresholds since this time, but not for the whole cooldown yet
+    Recovering(DateTime<Utc>),
+    Resume,
+}
+
+fn resume_step(
+    result: &AutoPauseResult,
+    recovered_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    cooldown: Duration,
+) -> ResumeStep {
+    if result.should_pause {
+        return ResumeStep::StillUnhealthy;
+    }
+    let since = recovered_at.unwrap_or(now);
+    if now - since >= cooldown {
+        ResumeStep::Resume
+    } else {
+        ResumeStep::Recovering(since)
+    }
+}
+
+impl WorkspaceThresholds {
+    /// The workspace thresholds with the campaign's overrides applied. An unset
+    /// override keeps the workspace value rather than becoming zero.
+    fn for_campaign(&self, metrics: &CampaignMetrics) -> WorkspaceThresholds {
+        WorkspaceThresholds {
+            spam_rate_threshold: metrics.spam_rate_threshold.unwrap_or(self.spam_rate_threshold),
+            reply_drop_threshold: metrics.reply_drop_threshold.unwrap_or(self.reply_drop_threshold),
+            bounce_rate_threshold: metrics.bounce_rate_threshold.unwrap_or(self.bounce_rate_threshold),
+            ..self.clone()
+        }
+    }
+}
+
+async fn load_thresholds(pool: &PgPool, workspace_id: Uuid) -> Result<WorkspaceThresholds, sqlx::Error> {
     let settings: Option<WorkspaceThresholds> = sqlx::query_as(
         r#"
-        SELECT auto_pause_enabled, spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold
+        SELECT auto_pause_enabled, spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold,
+               COALESCE(deliverability_score_threshold, 75.0) as deliverability_score_threshold,
+               auto_resume_enabled, auto_resume_cooldown_hours
         FROM workspace_settings
         WHERE workspace_id = $1
         "#
@@ -40,21 +150,19 @@ pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<V
     .fetch_optional(pool)
     .await?;
 
-    let settings = settings.unwrap_or(WorkspaceThresholds {
+    Ok(settings.unwrap_or(WorkspaceThresholds {
         auto_pause_enabled: true,
         spam_rate_threshold: 0.03,
         reply_drop_threshold: 0.40,
         bounce_rate_threshold: 0.08,
-    });
-
-    if !settings.auto_pause_enabled {
-        return Ok(vec![]);
-    }
-
-    let mut results = Vec::new();
+        deliverability_score_threshold: 75.0,
+        auto_resume_enabled: false,
+        auto_resume_cooldown_hours: DEFAULT_AUTO_RESUME_COOLDOWN_HOURS,
+    }))
+}
 
-    // Check each active campaign
-    let campaigns: Vec<CampaignMetrics> = sqlx::query_as(
+async fn campaign_metrics(pool: &PgPool, workspace_id: Uuid, scope: CampaignScope) -> Result<Vec<CampaignMetrics>, sqlx::Error> {
+    sqlx::query_as::<_, CampaignMetrics>(&format!(
         r#"
         WITH current_metrics AS (
             SELECT 
@@ -69,6 +177,7 @@ pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<V
                     0
                 ) as current_spam_rate,
                 CASE WHEN c.sent > 0 THEN c.replied::FLOAT / c.sent::FLOAT ELSE 0 END as current_reply_rate,
+                COALESCE(c.sent, 0) as current_sent,
                 COALESCE(
                     (SELECT AVG(ihm.bounce_rate) 
                      FROM inbox_health_metrics ihm 
@@ -76,18 +185,24 @@ pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<V
                      WHERE ea.workspace_id = c.workspace_id 
                      AND ihm.measured_at > NOW() - INTERVAL '24 hours'), 
                     0
-                ) as current_bounce_rate
+                ) as current_bounce_rate,
+                c.spam_rate_threshold,
+                c.reply_drop_threshold,
+                c.bounce_rate_threshold,
+                c.metrics_recovered_at
             FROM campaigns c
-            WHERE c.workspace_id = $1 
-            AND c.status = 'active' 
-            AND COALESCE(c.auto_paused, FALSE) = FALSE
+            WHERE c.workspace_id = $1 AND {filter}
         ),
         previous_metrics AS (
-            SELECT 
-                c.id as campaign_id,
-                CASE WHEN c.sent > 0 THEN c.replied::FLOAT / c.sent::FLOAT ELSE 0 END as previous_reply_rate
-            FROM campaigns c
-            WHERE c.workspace_id = $1
+            SELECT DISTINCT ON (s.campaign_id)
+                s.campaign_id,
+                s.reply_rate as previous_reply_rate
+            FROM campaign_metric_snapshots s
+            WHERE s.workspace_id = $1
+            AND s.snapshot_date <= CURRENT_DATE - {baseline_days}
+            AND s.snapshot_date >= CURRENT_DATE - {max_age_days}
+            AND s.sent >= {min_sends}
+            ORDER BY s.campaign_id, s.snapshot_date DESC
         )
         SELECT 
             cm.campaign_id,
@@ -95,29 +210,141 @@ pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<V
             cm.current_spam_rate,
             cm.current_reply_rate,
             cm.current_bounce_rate,
-            COALESCE(pm.previous_reply_rate, 0) as previous_reply_rate
+            cm.current_sent,
+            COALESCE(pm.previous_reply_rate, 0) as previous_reply_rate,
+            cm.spam_rate_threshold,
+            cm.reply_drop_threshold,
+            cm.bounce_rate_threshold,
+            cm.metrics_recovered_at
         FROM current_metrics cm
         LEFT JOIN previous_metrics pm ON cm.campaign_id = pm.campaign_id
-        "#
-    )
+        "#,
+        filter = scope.filter(),
+        baseline_days = REPLY_BASELINE_DAYS,
+        max_age_days = REPLY_BASELINE_MAX_AGE_DAYS,
+        min_sends = REPLY_DROP_MIN_SENDS,
+    ))
     .bind(workspace_id)
     .fetch_all(pool)
-    .await?;
+    .await
+}
+
+pub async fn check_and_auto_pause(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<AutoPauseResult>, sqlx::Error> {
+    let settings = load_thresholds(pool, workspace_id).await?;
+
+    if !settings.auto_pause_enabled {
+        return Ok(vec![]);
+    }
+
+    let mut results = Vec::new();
+    let mut paused = Vec::new();
+
+    // Check each active campaign
+    let campaigns = campaign_metrics(pool, workspace_id, CampaignScope::Active).await?;
 
     for campaign in campaigns {
         let result = check_campaign_thresholds(&campaign, &settings);
         
         if result.should_pause {
             // Auto-pause the campaign
-            let _ = pause_campaign(pool, workspace_id, campaign.campaign_id, &result).await;
+            match pause_campaign(pool, workspace_id, campaign.campaign_id, &result).await {
+                Ok(()) => paused.push(PausedCampaign {
+                    campaign_id: campaign.campaign_id,
+                    campaign_name: campaign.campaign_name.clone(),
+                    detail: result.detail.clone().unwrap_or_default(),
+                }),
+                Err(e) => tracing::error!("Failed to auto-pause campaign {}: {}", campaign.campaign_id, e),
+            }
             results.push(result);
         }
     }
 
+    // One digest for everything this check paused, rather than a ping per campaign
+    pause_notifications::notify_auto_paused(pool, workspace_id, &paused).await;
+
     Ok(results)
 }
 
+/// Puts auto-paused campaigns back to active once their metrics have stayed
+/// within thresholds for the workspace's cooldown. Opt-in per workspace.
+/// Returns the campaigns resumed.
+pub async fn check_and_auto_resume(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+    let settings = load_thresholds(pool, workspace_id).await?;
+    if !settings.auto_resume_enabled {
+        return Ok(vec![]);
+    }
+
+    let cooldown = Duration::hours(settings.auto_resume_cooldown_hours.clamp(1, MAX_AUTO_RESUME_COOLDOWN_HOURS) as i64);
+    let now = Utc::now();
+    let mut resumed = Vec::new();
+
+    for campaign in campaign_metrics(pool, workspace_id, CampaignScope::AutoPaused).await? {
+        let result = check_campaign_thresholds(&campaign, &settings);
+        match resume_step(&result, campaign.metrics_recovered_at, now, cooldown) {
+            ResumeStep::StillUnhealthy => {
+                sqlx::query("UPDATE campaigns SET metrics_recovered_at = NULL WHERE id = $1")
+                    .bind(campaign.campaign_id)
+                    .execute(pool)
+                    .await?;
+            }
+            ResumeStep::Recovering(since) => {
+                sqlx::query("UPDATE campaigns SET metrics_recovered_at = $2 WHERE id = $1")
+                    .bind(campaign.campaign_id)
+                    .bind(since)
+                    .execute(pool)
+                    .await?;
+            }
+            ResumeStep::Resume => {
+                if resume_campaign(pool, workspace_id, campaign.campaign_id).await? {
+                    resumed.push(campaign.campaign_id);
+                }
+            }
+        }
+    }
+
+    Ok(resumed)
+}
+
+async fn resume_campaign(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Guarded on still being auto-paused, in case someone acted in the meantime
+    let result = sqlx::query(
+        r#"
+        UPDATE campaigns
+        SET status = 'active', auto_paused = FALSE, auto_pause_reason = NULL, paused_at = NULL, metrics_recovered_at = NULL
+        WHERE id = $1 AND workspace_id = $2 AND status = 'paused' AND auto_paused = TRUE
+        "#
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE auto_pause_events
+        SET is_resolved = TRUE, resolved_at = NOW(), resolution_action = 'auto_resumed'
+        WHERE campaign_id = $1 AND is_resolved = FALSE
+        "#
+    )
+    .bind(campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("Auto-resumed campaign {} for workspace {} after metrics recovered", campaign_id, workspace_id);
+    Ok(true)
+}
+
 fn check_campaign_thresholds(metrics: &CampaignMetrics, settings: &WorkspaceThresholds) -> AutoPauseResult {
+    let settings = &settings.for_campaign(metrics);
+
     // Check spam rate
     if metrics.current_spam_rate > settings.spam_rate_threshold {
         return AutoPauseResult {
@@ -131,8 +358,8 @@ fn check_campaign_thresholds(metrics: &CampaignMetrics, settings: &WorkspaceThre
         };
     }
 
-    // Check reply rate drop
-    if metrics.previous_reply_rate > 0.0 {
+    // Check reply rate drop, once there's enough volume for the rate to mean something
+    if metrics.current_sent >= REPLY_DROP_MIN_SENDS && metrics.previous_reply_rate > 0.0 {
         let reply_drop = (metrics.previous_reply_rate - metrics.current_reply_rate) / metrics.previous_reply_rate;
         if reply_drop > settings.reply_drop_threshold {
             return AutoPauseResult {
@@ -161,6 +388,25 @@ fn check_campaign_thresholds(metrics: &CampaignMetrics, settings: &WorkspaceThre
         };
     }
 
+    // Check the composite deliverability score, which catches several metrics
+    // degrading together even when none of them crosses its own threshold
+    let score = DeliverabilityService::new().calculate_health_score(
+        metrics.current_bounce_rate as f32,
+        metrics.current_spam_rate as f32,
+        metrics.current_reply_rate as f32,
+    ) as f64;
+    if score < settings.deliverability_score_threshold {
+        return AutoPauseResult {
+            should_pause: true,
+            reason: Some("deliverability_score".to_string()),
+            detail: Some(format!(
+                "Deliverability score fell to {:.0} (threshold: {:.0})",
+                score,
+                settings.deliverability_score_threshold
+            )),
+        };
+    }
+
     AutoPauseResult {
         should_pause: false,
         reason: None,
@@ -183,7 +429,8 @@ async fn pause_campaign(
         SET status = 'paused', 
             auto_paused = TRUE, 
             auto_pause_reason = $3,
-            paused_at = $4
+            paused_at = $4,
+            metrics_recovered_at = NULL
         WHERE id = $1 AND workspace_id = $2
         "#
     )
@@ -220,6 +467,35 @@ async fn pause_campaign(
     Ok(())
 }
 
+/// Records today's reply rate for each active campaign, the baseline a later
+/// check compares against. Re-running on the same day overwrites the row.
+pub async fn snapshot_campaign_metrics(pool: &PgPool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_metric_snapshots (campaign_id, workspace_id, snapshot_date, sent, replied, reply_rate)
+        SELECT
+            c.id,
+            c.workspace_id,
+            CURRENT_DATE,
+            COALESCE(c.sent, 0),
+            COALESCE(c.replied, 0),
+            CASE WHEN c.sent > 0 THEN c.replied::FLOAT / c.sent::FLOAT ELSE 0 END
+        FROM campaigns c
+        WHERE c.workspace_id = $1 AND c.status = 'active'
+        ON CONFLICT (campaign_id, snapshot_date) DO UPDATE SET
+            sent = EXCLUDED.sent,
+            replied = EXCLUDED.replied,
+            reply_rate = EXCLUDED.reply_rate,
+            captured_at = NOW()
+        "#
+    )
+    .bind(workspace_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn update_inbox_health_metrics(pool: &PgPool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
     // Calculate and store health metrics for all inboxes
     sqlx::query(
@@ -235,8 +511,8 @@ pub async fn update_inbox_health_metrics(pool: &PgPool, workspace_id: Uuid) -> R
             COALESCE(ea.reply_rate, 0),
             COALESCE(ea.bounce_rate, 0),
             CASE 
-                WHEN ea.spam_rate > 0.03 OR ea.bounce_rate > 0.08 THEN 'danger'
-                WHEN ea.spam_rate > 0.02 OR ea.bounce_rate > 0.05 THEN 'warning'
+                WHEN ea.spam_rate > $2 OR ea.bounce_rate > $3 THEN 'danger'
+                WHEN ea.spam_rate > $4 OR ea.bounce_rate > $5 THEN 'warning'
                 ELSE 'healthy'
             END,
             ea.health_score,
@@ -249,6 +525,10 @@ pub async fn update_inbox_health_metrics(pool: &PgPool, workspace_id: Uuid) -> R
         "#
     )
     .bind(workspace_id)
+    .bind(DANGER_SPAM_RATE)
+    .bind(DANGER_BOUNCE_RATE)
+    .bind(WARNING_SPAM_RATE)
+    .bind(WARNING_BOUNCE_RATE)
     .execute(pool)
     .await?;
 
@@ -264,18 +544,25 @@ pub async fn update_inbox_health_metrics(pool: &PgPool, workspace_id: Uuid) -> R
 }
 
 pub async fn run_health_check_job(pool: &PgPool) -> Result<(), sqlx::Error> {
-    // Get all workspaces with active campaigns
+    // Get all workspaces with active campaigns, or auto-paused ones that may resume
     let workspaces: Vec<(Uuid,)> = sqlx::query_as(
         r#"
         SELECT DISTINCT workspace_id 
         FROM campaigns 
-        WHERE status = 'active'
+        WHERE status = 'active' OR (status = 'paused' AND auto_paused = TRUE)
         "#
     )
     .fetch_all(pool)
     .await?;
 
+    let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
+
     for (workspace_id,) in workspaces {
+        // Recompute inbox health scores before they're snapshotted below
+        if let Err(e) = health_score::refresh_workspace_scores(pool, &resolver, workspace_id).await {
+            tracing::error!("Failed to compute health scores for workspace {}: {}", workspace_id, e);
+        }
+
         // Update health metrics
         if let Err(e) = update_inbox_health_metrics(pool, workspace_id).await {
             tracing::error!("Failed to update health metrics for workspace {}: {}", workspace_id, e);
@@ -285,13 +572,22 @@ pub async fn run_health_check_job(pool: &PgPool) -> Result<(), sqlx::Error> {
         if let Err(e) = check_and_auto_pause(pool, workspace_id).await {
             tracing::error!("Failed to check auto-pause for workspace {}: {}", workspace_id, e);
         }
+
+        if let Err(e) = check_and_auto_resume(pool, workspace_id).await {
+            tracing::error!("Failed to check auto-resume for workspace {}: {}", workspace_id, e);
+        }
+
+        // After the checks, so today's numbers never serve as their own baseline
+        if let Err(e) = snapshot_campaign_metrics(pool, workspace_id).await {
+            tracing::error!("Failed to snapshot campaign metrics for workspace {}: {}", workspace_id, e);
+        }
     }
 
     Ok(())
 }
 
 pub fn detect_email_provider(email: &str) -> (&'static str, i32) {
-    let domain = email.split('@').last().unwrap_or("");
+    let domain = email.split('@').next_back().unwrap_or("");
     
     if domain.contains("gmail") || domain.contains("google") {
         ("google", 500)
@@ -326,3 +622,174 @@ pub async fn set_provider_limits(pool: &PgPool, email_account_id: Uuid, email: &
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_deliverability_score_pauses_on_combined_degradation() {
+        let settings = WorkspaceThresholds {
+            auto_pause_enabled: true,
+            spam_rate_threshold: 0.03,
+            reply_drop_threshold: 0.40,
+            bounce_rate_threshold: 0.08,
+            deliverability_score_threshold: 75.0,
+            auto_resume_enabled: false,
+            auto_resume_cooldown_hours: DEFAULT_AUTO_RESUME_COOLDOWN_HOURS,
+        };
+        let metrics = CampaignMetrics {
+            campaign_id: Uuid::new_v4(),
+            campaign_name: "Test".to_string(),
+            current_spam_rate: 0.025,
+            current_reply_rate: 0.0,
+            current_bounce_rate: 0.07,
+            current_sent: 100,
+            previous_reply_rate: 0.0,
+            spam_rate_threshold: None,
+            reply_drop_threshold: None,
+            bounce_rate_threshold: None,
+            metrics_recovered_at: None,
+        };
+
+        let result = check_campaign_thresholds(&metrics, &settings);
+        assert!(result.should_pause);
+        assert_eq!(result.reason.as_deref(), Some("deliverability_score"));
+    }
+
+    #[test]
+    fn test_campaign_override_pauses_where_workspace_default_does_not() {
+        let settings = WorkspaceThresholds {
+            auto_pause_enabled: true,
+            spam_rate_threshold: 0.03,
+            reply_drop_threshold: 0.40,
+            bounce_rate_threshold: 0.08,
+            deliverability_score_threshold: 75.0,
+            auto_resume_enabled: false,
+            auto_resume_cooldown_hours: DEFAULT_AUTO_RESUME_COOLDOWN_HOURS,
+        };
+        let campaign = |spam_override: Option<f64>| CampaignMetrics {
+            campaign_id: Uuid::new_v4(),
+            campaign_name: "Test".to_string(),
+            current_spam_rate: 0.02,
+            current_reply_rate: 0.05,
+            current_bounce_rate: 0.01,
+            current_sent: 100,
+            previous_reply_rate: 0.05,
+            spam_rate_threshold: spam_override,
+            reply_drop_threshold: None,
+            bounce_rate_threshold: None,
+            metrics_recovered_at: None,
+        };
+
+        let nurture = check_campaign_thresholds(&campaign(None), &settings);
+        assert!(!nurture.should_pause, "{:?}", nurture);
+
+        let strict = check_campaign_thresholds(&campaign(Some(0.01)), &settings);
+        assert!(strict.should_pause);
+        assert_eq!(strict.reason.as_deref(), Some("spam_rate"));
+        assert!(strict.detail.unwrap().contains("threshold: 1.0%"));
+
+        // Unset overrides fall back to the workspace values, not zero
+        let effective = settings.for_campaign(&campaign(None));
+        assert_eq!(effective.spam_rate_threshold, 0.03);
+        assert_eq!(effective.bounce_rate_threshold, 0.08);
+    }
+
+    #[test]
+    fn test_pauses_on_spike_then_resumes_after_cooldown() {
+        let settings = WorkspaceThresholds {
+            auto_pause_enabled: true,
+            spam_rate_threshold: 0.03,
+            reply_drop_threshold: 0.40,
+            bounce_rate_threshold: 0.08,
+            deliverability_score_threshold: 75.0,
+            auto_resume_enabled: true,
+            auto_resume_cooldown_hours: DEFAULT_AUTO_RESUME_COOLDOWN_HOURS,
+        };
+        let campaign = |spam_rate: f64| CampaignMetrics {
+            campaign_id: Uuid::new_v4(),
+            campaign_name: "Test".to_string(),
+            current_spam_rate: spam_rate,
+            current_reply_rate: 0.05,
+            current_bounce_rate: 0.01,
+            current_sent: 100,
+            previous_reply_rate: 0.05,
+            spam_rate_threshold: None,
+            reply_drop_threshold: None,
+            bounce_rate_threshold: None,
+            metrics_recovered_at: None,
+        };
+        let cooldown = Duration::hours(settings.auto_resume_cooldown_hours as i64);
+        let t0 = Utc::now();
+
+        let spike = check_campaign_thresholds(&campaign(0.06), &settings);
+        assert!(spike.should_pause);
+        assert_eq!(resume_step(&spike, None, t0, cooldown), ResumeStep::StillUnhealthy);
+
+        // Metrics normalise: the cooldown starts, and only a full one resumes
+        let normal = check_campaign_thresholds(&campaign(0.01), &settings);
+        assert_eq!(resume_step(&normal, None, t0, cooldown), ResumeStep::Recovering(t0));
+        assert_eq!(
+            resume_step(&normal, Some(t0), t0 + Duration::hours(12), cooldown),
+            ResumeStep::Recovering(t0)
+        );
+        assert_eq!(resume_step(&normal, Some(t0), t0 + Duration::hours(24), cooldown), ResumeStep::Resume);
+
+        // Another spike during the cooldown throws the recovery away
+        assert_eq!(
+            resume_step(&spike, Some(t0), t0 + Duration::hours(30), cooldown),
+            ResumeStep::StillUnhealthy
+        );
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_reply_drop_against_snapshot_baseline_pauses() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Reply Drop").await;
+
+        let mut campaigns = Vec::new();
+        for sent in [200, 20] {
+            let campaign_id = Uuid::new_v4();
+            // 2% live against a 10% baseline from two days ago
+            sqlx::query(
+                "INSERT INTO campaigns (id, name, vertical, status, workspace_id, sent, replied) VALUES ($1, 'Drop', 'saas', 'active', $2, $3, $4)",
+            )
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .bind(sent)
+            .bind(sent / 50)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO campaign_metric_snapshots (campaign_id, workspace_id, snapshot_date, sent, replied, reply_rate) VALUES ($1, $2, CURRENT_DATE - 2, 100, 10, 0.10)",
+            )
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            campaigns.push(campaign_id);
+        }
+
+        let results = check_and_auto_pause(&pool, workspace_id).await.unwrap();
+        assert_eq!(results.len(), 1, "{:?}", results);
+        assert_eq!(results[0].reason.as_deref(), Some("reply_drop"));
+
+        let (busy_reason, quiet_status): (Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT (SELECT pause_reason FROM auto_pause_events WHERE campaign_id = $1), (SELECT status FROM campaigns WHERE id = $2)",
+        )
+        .bind(campaigns[0])
+        .bind(campaigns[1])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(busy_reason.as_deref(), Some("reply_drop"));
+        // Twenty sends is too few to judge
+        assert_eq!(quiet_status.as_deref(), Some("active"));
+    }
+}
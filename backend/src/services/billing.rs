@@ -0,0 +1,432 @@
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Signed webhooks older than this are rejected to limit replays (Stripe's own default)
+const SIGNATURE_TOLERANCE_SECS: i64 = 5 * 60;
+
+const STRIPE_API_BASE: &str = "https://api.stripe.com/v1";
+
+static HTTP: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(20))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+});
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingTier {
+    pub id: String,
+    pub name: String,
+    pub price_monthly: i32,
+    pub price_yearly: i32,
+    pub leads_per_month: i32,
+    pub inboxes: i32,
+    pub emails_per_month: i32,
+    pub features: Vec<String>,
+}
+
+pub fn get_pricing_tiers() -> Vec<PricingTier> {
+    vec![
+        PricingTier {
+            id: "starter".to_string(),
+            name: "Starter".to_string(),
+            price_monthly: 9700,
+            price_yearly: 97000,
+            leads_per_month: 1000,
+            inboxes: 1,
+            emails_per_month: 500,
+            features: vec!["Basic warmup".to_string(), "Email support".to_string(), "Lead verification".to_string()],
+        },
+        PricingTier {
+            id: "professional".to_string(),
+            name: "Professional".to_string(),
+            price_monthly: 29700,
+            price_yearly: 297000,
+            leads_per_month: 10000,
+            inboxes: 5,
+            emails_per_month: 5000,
+            features: vec!["Advanced warmup".to_string(), "Domain health monitoring".to_string(), "Priority support".to_string(), "A/B testing".to_string(), "Analytics dashboard".to_string()],
+        },
+        PricingTier {
+            id: "business".to_string(),
+            name: "Business".to_string(),
+            price_monthly: 99700,
+            price_yearly: 997000,
+            leads_per_month: 50000,
+            inboxes: 20,
+            emails_per_month: 25000,
+            features: vec!["Dedicated deliverability manager".to_string(), "Custom integrations".to_string(), "Slack support".to_string(), "95%+ inbox guarantee".to_string(), "ROI reporting".to_string(), "White-glove onboarding".to_string()],
+        },
+    ]
+}
+
+pub fn find_tier(tier_id: &str) -> Option<PricingTier> {
+    get_pricing_tiers().into_iter().find(|t| t.id == tier_id)
+}
+
+/// The plan a workspace falls back to when its subscription ends
+fn default_tier() -> PricingTier {
+    find_tier("starter").expect("starter tier exists")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BillingCycle {
+    Monthly,
+    Yearly,
+}
+
+impl BillingCycle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "monthly" | "month" => Some(BillingCycle::Monthly),
+            "yearly" | "year" | "annual" => Some(BillingCycle::Yearly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BillingCycle::Monthly => "monthly",
+            BillingCycle::Yearly => "yearly",
+        }
+    }
+}
+
+/// Stripe price for a tier and cycle, from `STRIPE_PRICE_<TIER>_<CYCLE>`
+/// (e.g. `STRIPE_PRICE_PROFESSIONAL_YEARLY`)
+pub fn price_id(tier_id: &str, cycle: BillingCycle) -> Option<String> {
+    let var = format!("STRIPE_PRICE_{}_{}", tier_id.to_uppercase(), cycle.as_str().to_uppercase());
+    std::env::var(var).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// The tier a configured Stripe price belongs to, in either cycle
+pub fn tier_for_price(price: &str) -> Option<PricingTier> {
+    get_pricing_tiers().into_iter().find(|tier| {
+        [BillingCycle::Monthly, BillingCycle::Yearly]
+            .iter()
+            .any(|cycle| price_id(&tier.id, *cycle).as_deref() == Some(price))
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutSession {
+    pub id: String,
+    pub url: String,
+}
+
+/// Thin client over the few Stripe endpoints billing needs
+pub struct StripeClient {
+    secret_key: String,
+}
+
+impl StripeClient {
+    /// `None` unless `STRIPE_SECRET_KEY` is set
+    pub fn from_env() -> Option<Self> {
+        std::env::var("STRIPE_SECRET_KEY")
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .map(|secret_key| Self { secret_key })
+    }
+
+    async fn post(&self, path: &str, form: &[(&str, String)]) -> Result<serde_json::Value, String> {
+        let response = HTTP
+            .post(format!("{}{}", STRIPE_API_BASE, path))
+            .bearer_auth(&self.secret_key)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| format!("Stripe request failed: {}", e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Stripe response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "Stripe returned {}: {}",
+                status,
+                body["error"]["message"].as_str().unwrap_or("unknown error")
+            ));
+        }
+        Ok(body)
+    }
+
+    /// Subscription checkout for one price. The workspace and tier ride along
+    /// as metadata on both the session and the subscription, which is how the
+    /// webhook ties Stripe's events back to the workspace.
+    pub async fn create_checkout_session(
+        &self,
+        workspace_id: Uuid,
+        tier_id: &str,
+        price_id: &str,
+        customer_id: Option<&str>,
+        success_url: &str,
+        cancel_url: &str,
+    ) -> Result<CheckoutSession, String> {
+        let mut form = vec![
+            ("mode", "subscription".to_string()),
+            ("line_items[0][price]", price_id.to_string()),
+            ("line_items[0][quantity]", "1".to_string()),
+            ("success_url", success_url.to_string()),
+            ("cancel_url", cancel_url.to_string()),
+            ("client_reference_id", workspace_id.to_string()),
+            ("metadata[workspace_id]", workspace_id.to_string()),
+            ("metadata[tier_id]", tier_id.to_string()),
+            ("subscription_data[metadata][workspace_id]", workspace_id.to_string()),
+            ("subscription_data[metadata][tier_id]", tier_id.to_string()),
+        ];
+        if let Some(customer_id) = customer_id {
+            form.push(("customer", customer_id.to_string()));
+        }
+
+        let session = self.post("/checkout/sessions", &form).await?;
+        Ok(CheckoutSession {
+            id: session["id"].as_str().ok_or("Checkout session has no id")?.to_string(),
+            url: session["url"].as_str().ok_or("Checkout session has no url")?.to_string(),
+        })
+    }
+
+    /// Billing portal link for an existing customer
+    pub async fn create_portal_session(&self, customer_id: &str, return_url: &str) -> Result<String, String> {
+        let session = self
+            .post(
+                "/billing_portal/sessions",
+                &[("customer", customer_id.to_string()), ("return_url", return_url.to_string())],
+            )
+            .await?;
+        Ok(session["url"].as_str().ok_or("Portal session has no url")?.to_string())
+    }
+}
+
+/// Checks a `Stripe-Signature` header (`t=<unix time>,v1=<hex hmac>,...`)
+/// against the raw request body
+pub fn verify_signature(body: &[u8], header: &str, secret: &str, now: i64) -> Result<(), String> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or("Missing signature timestamp")?;
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return Err("Signature timestamp outside tolerance".to_string());
+    }
+    if signatures.is_empty() {
+        return Err("Missing v1 signature".to_string());
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| format!("Invalid signing secret: {}", e))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    // Stripe sends one v1 per active secret while a secret is being rolled
+    let matches = signatures
+        .iter()
+        .filter_map(|sig| hex::decode(sig).ok())
+        .any(|sig| mac.clone().verify_slice(&sig).is_ok());
+    if matches {
+        Ok(())
+    } else {
+        Err("Signature mismatch".to_string())
+    }
+}
+
+/// What a subscription event means for a workspace's plan
+#[derive(Debug, Clone)]
+pub struct PlanUpdate {
+    /// From the metadata set at checkout; events without it are matched on the subscription
+    pub workspace_id: Option<Uuid>,
+    pub subscription_id: String,
+    pub customer_id: Option<String>,
+    pub tier: PricingTier,
+    /// False once the subscription has ended, which drops the workspace back to the default tier
+    pub active: bool,
+}
+
+/// Reads a Stripe event. `Ok(None)` for events that don't change a plan.
+pub fn plan_update(event: &serde_json::Value) -> Result<Option<PlanUpdate>, String> {
+    let object = &event["data"]["object"];
+    let metadata_workspace = object["metadata"]["workspace_id"]
+        .as_str()
+        .or_else(|| object["client_reference_id"].as_str())
+        .and_then(|id| Uuid::parse_str(id).ok());
+    let metadata_tier = object["metadata"]["tier_id"].as_str().and_then(find_tier);
+    let customer_id = object["customer"].as_str().map(str::to_string);
+
+    match event["type"].as_str().unwrap_or_default() {
+        "checkout.session.completed" => {
+            if object["mode"].as_str() != Some("subscription") {
+                return Ok(None);
+            }
+            let subscription_id = object["subscription"].as_str().ok_or("Checkout session has no subscription")?;
+            Ok(Some(PlanUpdate {
+                workspace_id: Some(metadata_workspace.ok_or("Checkout session has no workspace")?),
+                subscription_id: subscription_id.to_string(),
+                customer_id,
+                tier: metadata_tier.ok_or("Checkout session has no known tier")?,
+                active: true,
+            }))
+        }
+        "customer.subscription.created" | "customer.subscription.updated" | "customer.subscription.deleted" => {
+            let subscription_id = object["id"].as_str().ok_or("Subscription has no id")?;
+            let active = event["type"] != "customer.subscription.deleted"
+                && !matches!(
+                    object["status"].as_str(),
+                    Some("canceled") | Some("unpaid") | Some("incomplete_expired")
+                );
+            // Not paid for yet; checkout.session.completed or a later update will follow
+            if active && object["status"].as_str() == Some("incomplete") {
+                return Ok(None);
+            }
+
+            let tier = if active {
+                // Plan changes made in the portal swap the price, not the metadata
+                object["items"]["data"][0]["price"]["id"]
+                    .as_str()
+                    .and_then(tier_for_price)
+                    .or(metadata_tier)
+                    .ok_or("Subscription price matches no tier")?
+            } else {
+                default_tier()
+            };
+
+            Ok(Some(PlanUpdate {
+                workspace_id: metadata_workspace,
+                subscription_id: subscription_id.to_string(),
+                customer_id,
+                tier,
+                active,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Writes the plan and its limits to the workspace. An ended subscription only
+/// downgrades the workspace if it's still the one on file, so cancelling an old
+/// subscription after switching to a new one changes nothing. Returns whether a
+/// workspace was updated.
+pub async fn apply_plan_update(pool: &PgPool, update: &PlanUpdate) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE workspaces
+        SET plan_tier = $1,
+            monthly_lead_limit = $2,
+            monthly_email_limit = $3,
+            stripe_subscription_id = CASE WHEN $4 THEN $5 ELSE NULL END,
+            stripe_customer_id = COALESCE($6, stripe_customer_id),
+            updated_at = NOW()
+        WHERE (id = $7 OR stripe_subscription_id = $5)
+        AND ($4 OR stripe_subscription_id = $5)
+        "#
+    )
+    .bind(&update.tier.id)
+    .bind(update.tier.leads_per_month)
+    .bind(update.tier.emails_per_month)
+    .bind(update.active)
+    .bind(&update.subscription_id)
+    .bind(&update.customer_id)
+    .bind(update.workspace_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use serde_json::json;
+
+    fn sign(body: &[u8], secret: &str, timestamp: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(body);
+        format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_webhook_signature_verification() {
+        let body = br#"{"id":"evt_1","type":"customer.subscription.updated"}"#;
+        let secret = "whsec_test";
+        let now = 1_700_000_000;
+
+        assert!(verify_signature(body, &sign(body, secret, now), secret, now).is_ok());
+        // A rolled secret adds a second v1; either may match
+        let rolled = format!("{},v1={}", sign(body, "whsec_old", now), "ab".repeat(32));
+        assert!(verify_signature(body, &rolled, "whsec_old", now).is_ok());
+
+        assert_eq!(verify_signature(body, &sign(body, "whsec_other", now), secret, now).unwrap_err(), "Signature mismatch");
+        assert!(verify_signature(b"{\"tampered\":true}", &sign(body, secret, now), secret, now).is_err());
+        assert!(verify_signature(body, &sign(body, secret, now - 600), secret, now).is_err());
+        assert!(verify_signature(body, "v1=deadbeef", secret, now).is_err());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_subscription_updated_event_adjusts_limits() {
+        let pool = test_db::pool().await;
+        let subscription_id = format!("sub_{}", Uuid::new_v4().simple());
+        let workspace_id = test_db::workspace(&pool, "Billing").await;
+        sqlx::query("UPDATE workspaces SET stripe_subscription_id = $2 WHERE id = $1")
+            .bind(workspace_id)
+            .bind(&subscription_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // An update from the portal: no workspace in sight, just the subscription and its tier
+        let event = |event_type: &str, status: &str| {
+            json!({
+                "type": event_type,
+                "data": { "object": {
+                    "id": subscription_id,
+                    "customer": "cus_123",
+                    "status": status,
+                    "metadata": { "tier_id": "business" },
+                    "items": { "data": [{ "price": { "id": "price_not_configured" } }] },
+                }},
+            })
+        };
+
+        let upgrade = plan_update(&event("customer.subscription.updated", "active")).unwrap().unwrap();
+        assert!(apply_plan_update(&pool, &upgrade).await.unwrap());
+        let limits: (String, i32, i32, Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT plan_tier, monthly_lead_limit, monthly_email_limit, stripe_subscription_id, stripe_customer_id FROM workspaces WHERE id = $1",
+        )
+        .bind(workspace_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(limits, ("business".to_string(), 50000, 25000, Some(subscription_id.clone()), Some("cus_123".to_string())));
+
+        let cancel = plan_update(&event("customer.subscription.deleted", "canceled")).unwrap().unwrap();
+        assert!(apply_plan_update(&pool, &cancel).await.unwrap());
+        let limits: (String, i32, i32, Option<String>) = sqlx::query_as(
+            "SELECT plan_tier, monthly_lead_limit, monthly_email_limit, stripe_subscription_id FROM workspaces WHERE id = $1",
+        )
+        .bind(workspace_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(limits, ("starter".to_string(), 1000, 500, None));
+
+        // Cancelling it again no longer matches any workspace
+        assert!(!apply_plan_update(&pool, &cancel).await.unwrap());
+    }
+}
@@ -0,0 +1,432 @@
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::models::compliance::SuppressionReason;
+
+/// Soft bounces one send may have before we stop retrying and treat it as hard
+pub const MAX_SOFT_BOUNCES: i64 = 5;
+/// Wait before retrying after the first soft bounce; it doubles with each one after
+const SOFT_BOUNCE_BASE_DELAY_MINUTES: i64 = 30;
+
+/// RFC 3463 enhanced status code, e.g. `5.1.1`
+static ENHANCED_STATUS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(#:;])([245])\.(\d{1,3})\.(\d{1,3})(?:$|[\s):;,.])").unwrap());
+/// Basic reply code, as lettre formats it (`permanent error (550): ...`) or at the start of a line
+static REPLY_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)(?:^|\()([45]\d\d)(?:\)|[\s-]|$)").unwrap());
+/// The fields of a delivery status notification we read
+static DSN_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^(final-recipient|original-recipient|x-failed-recipients|action|status|diagnostic-code):[ \t]*(.+?)\s*$").unwrap()
+});
+
+/// Wording servers use when the mailbox itself doesn't exist
+const UNKNOWN_RECIPIENT_PHRASES: &[&str] = &[
+    "user unknown",
+    "unknown user",
+    "no such user",
+    "unknown recipient",
+    "does not exist",
+    "doesn't exist",
+    "mailbox unavailable",
+    "mailbox not found",
+    "no mailbox",
+    "invalid recipient",
+    "invalid mailbox",
+    "recipient rejected",
+    "address rejected",
+    "account disabled",
+    "account has been disabled",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BounceType {
+    /// The address will never accept mail (unknown mailbox, dead domain)
+    Hard,
+    /// Temporary trouble (full mailbox, greylisting); worth trying again
+    Soft,
+}
+
+impl BounceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BounceType::Hard => "hard",
+            BounceType::Soft => "soft",
+        }
+    }
+}
+
+/// Where we heard about a bounce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BounceSource {
+    /// The recipient's server rejected our own SMTP send
+    Smtp,
+    /// A transactional provider's delivery webhook
+    Webhook,
+    /// A bounce-back message found in the sending inbox
+    Imap,
+}
+
+impl BounceSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BounceSource::Smtp => "smtp",
+            BounceSource::Webhook => "webhook",
+            BounceSource::Imap => "imap",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewBounce<'a> {
+    pub workspace_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
+    pub campaign_lead_id: Option<Uuid>,
+    pub email_account_id: Option<Uuid>,
+    pub recipient: &'a str,
+    pub bounce_type: BounceType,
+    pub source: BounceSource,
+    pub reason: Option<&'a str>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+pub async fn record<'e>(executor: impl sqlx::PgExecutor<'e>, bounce: &NewBounce<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO bounces (
+            workspace_id, campaign_id, campaign_lead_id, email_account_id,
+            recipient, bounce_type, source, reason, occurred_at
+        )
+        VALUES ($1, $2, $3, $4, LOWER($5), $6, $7, $8, $9)
+        "#
+    )
+    .bind(bounce.workspace_id)
+    .bind(bounce.campaign_id)
+    .bind(bounce.campaign_lead_id)
+    .bind(bounce.email_account_id)
+    .bind(bounce.recipient)
+    .bind(bounce.bounce_type.as_str())
+    .bind(bounce.source.as_str())
+    .bind(bounce.reason)
+    .bind(bounce.occurred_at)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Sorts a rejection from the recipient's server into a hard or soft bounce.
+/// `None` when it isn't about the recipient at all, e.g. our own login failing.
+pub fn classify_smtp_rejection(message: &str) -> Option<BounceType> {
+    let message = message.to_lowercase();
+    let reply_code = REPLY_CODE.captures(&message).map(|caps| caps[1].to_string());
+    let enhanced = ENHANCED_STATUS.captures(&message);
+
+    // Authentication required or credentials refused
+    if matches!(reply_code.as_deref(), Some("530" | "534" | "535"))
+        || enhanced.as_ref().is_some_and(|caps| &caps[1] == "5" && &caps[2] == "7" && &caps[3] == "8")
+    {
+        return None;
+    }
+
+    let unknown_recipient = UNKNOWN_RECIPIENT_PHRASES.iter().any(|phrase| message.contains(phrase));
+
+    if let Some(caps) = enhanced {
+        return match (&caps[1], &caps[2], &caps[3]) {
+            ("2", _, _) => None,
+            ("4", _, _) => Some(BounceType::Soft),
+            // Bad address, disabled mailbox, or a domain that can't be routed to
+            ("5", "1", _) | ("5", "2", "1") | ("5", "4", "4") => Some(BounceType::Hard),
+            _ if unknown_recipient => Some(BounceType::Hard),
+            // Full mailbox, message too big, policy or content blocks
+            _ => Some(BounceType::Soft),
+        };
+    }
+
+    match reply_code.as_deref() {
+        Some(code) if code.starts_with('4') => Some(BounceType::Soft),
+        Some("551" | "553") => Some(BounceType::Hard),
+        Some(_) if unknown_recipient => Some(BounceType::Hard),
+        Some(_) => Some(BounceType::Soft),
+        None => unknown_recipient.then_some(BounceType::Hard),
+    }
+}
+
+/// When to retry a send after its `soft_bounces`-th soft bounce
+pub fn soft_bounce_retry_at(now: DateTime<Utc>, soft_bounces: i64) -> DateTime<Utc> {
+    let doublings = soft_bounces.clamp(1, MAX_SOFT_BOUNCES) - 1;
+    now + Duration::minutes(SOFT_BOUNCE_BASE_DELAY_MINUTES << doublings)
+}
+
+/// Soft bounces already recorded for a campaign send
+pub async fn soft_bounce_count(pool: &PgPool, campaign_lead_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM bounces WHERE campaign_lead_id = $1 AND bounce_type = 'soft'")
+        .bind(campaign_lead_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Records a bounce. A hard one also marks the send bounced, the lead invalid
+/// and the address suppressed, and counts against the sending inbox's bounce
+/// rate. The same send bouncing again (say, reported by both SMTP and a
+/// bounce-back) is only counted once.
+pub async fn apply(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, bounce: &NewBounce<'_>) -> Result<(), sqlx::Error> {
+    record(&mut **tx, bounce).await?;
+
+    if bounce.bounce_type == BounceType::Soft {
+        return Ok(());
+    }
+
+    let mut first_time = true;
+    if let Some(campaign_lead_id) = bounce.campaign_lead_id {
+        let campaign_id: Option<Uuid> = sqlx::query_scalar(
+            "UPDATE campaign_leads SET status = 'bounced', bounce_reason = $2 WHERE id = $1 AND status <> 'bounced' RETURNING campaign_id"
+        )
+        .bind(campaign_lead_id)
+        .bind(bounce.reason)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        first_time = campaign_id.is_some();
+        if let Some(campaign_id) = campaign_id {
+            sqlx::query("UPDATE campaigns SET bounced = bounced + 1 WHERE id = $1")
+                .bind(campaign_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE leads SET verification_status = 'invalid' WHERE workspace_id IS NOT DISTINCT FROM $1 AND LOWER(email) = LOWER($2)"
+    )
+    .bind(bounce.workspace_id)
+    .bind(bounce.recipient)
+    .execute(&mut **tx)
+    .await?;
+
+    // An existing entry (an unsubscribe, say) keeps its reason
+    if let Some(workspace_id) = bounce.workspace_id {
+        sqlx::query(
+            r#"
+            INSERT INTO suppression_list (workspace_id, email, reason, source)
+            VALUES ($1, LOWER($2), $3, 'bounce')
+            ON CONFLICT (workspace_id, email) DO NOTHING
+            "#
+        )
+        .bind(workspace_id)
+        .bind(bounce.recipient)
+        .bind(SuppressionReason::Bounced.as_str())
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    if let (true, Some(email_account_id)) = (first_time, bounce.email_account_id) {
+        sqlx::query(
+            r#"
+            UPDATE email_accounts
+            SET total_bounced = total_bounced + 1,
+                bounce_rate = (total_bounced + 1)::FLOAT / GREATEST(total_sent, 1)
+            WHERE id = $1
+            "#
+        )
+        .bind(email_account_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// What a bounce-back message (RFC 3464 delivery status notification, or the
+/// looser reports some servers send) says about a failed recipient
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryStatusNotification {
+    pub recipient: String,
+    pub bounce_type: BounceType,
+    pub reason: Option<String>,
+}
+
+/// Reads a bounce-back. `None` when it names no recipient or only reports a
+/// delay or a successful delivery.
+pub fn parse_dsn(raw: &str) -> Option<DeliveryStatusNotification> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for caps in DSN_FIELD.captures_iter(raw) {
+        let name = caps[1].to_lowercase();
+        if !fields.iter().any(|(n, _)| *n == name) {
+            fields.push((name, caps[2].to_string()));
+        }
+    }
+    let field = |name: &str| fields.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+
+    if field("action").is_some_and(|action| !action.trim().eq_ignore_ascii_case("failed")) {
+        return None;
+    }
+
+    // `Final-Recipient: rfc822; jane@acme.io`, or a bare list in X-Failed-Recipients
+    let recipient = ["final-recipient", "original-recipient", "x-failed-recipients"]
+        .into_iter()
+        .filter_map(field)
+        .map(|value| value.rsplit(';').next().unwrap_or(value))
+        .filter_map(|value| value.split(',').next())
+        .map(|value| value.trim().trim_matches(['<', '>']).to_lowercase())
+        .find(|value| value.contains('@'))?;
+
+    let reason = field("diagnostic-code")
+        .map(|code| code.split_once(';').map(|(_, text)| text).unwrap_or(code).trim().to_string())
+        .or_else(|| field("status").map(str::to_string));
+
+    let status = [field("status"), field("diagnostic-code")].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    let bounce_type = classify_smtp_rejection(&status).or_else(|| classify_smtp_rejection(raw))?;
+
+    Some(DeliveryStatusNotification { recipient, bounce_type, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_smtp_errors_are_sorted_into_hard_and_soft_bounces() {
+        let cases = [
+            ("permanent error (550): 5.1.1 The email account that you tried to reach does not exist.", Some(BounceType::Hard)),
+            ("permanent error (550): Requested action not taken: mailbox unavailable", Some(BounceType::Hard)),
+            ("permanent error (553): sorry, that domain isn't in my list of allowed rcpthosts", Some(BounceType::Hard)),
+            ("permanent error (550): 5.4.4 Unable to route: no mail hosts for domain", Some(BounceType::Hard)),
+            ("transient error (452): 4.2.2 The email account that you tried to reach is over quota.", Some(BounceType::Soft)),
+            ("transient error (421): Service not available, try again later", Some(BounceType::Soft)),
+            ("permanent error (552): 5.2.2 Mailbox full", Some(BounceType::Soft)),
+            ("permanent error (554): 5.7.1 Message rejected as spam by Content Filtering.", Some(BounceType::Soft)),
+            ("permanent error (535): 5.7.8 Username and Password not accepted.", None),
+            ("client error: missing recipient", None),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(classify_smtp_rejection(message), expected, "{}", message);
+        }
+
+        let now = Utc::now();
+        assert_eq!(soft_bounce_retry_at(now, 1), now + Duration::minutes(30));
+        assert_eq!(soft_bounce_retry_at(now, 2), now + Duration::minutes(60));
+        assert_eq!(soft_bounce_retry_at(now, 4), now + Duration::minutes(240));
+    }
+
+    #[test]
+    fn test_bounce_backs_name_the_failed_recipient() {
+        let postfix = "From: MAILER-DAEMON@mail.acme.io\r\n\
+                       Content-Type: multipart/report; report-type=delivery-status; boundary=\"b\"\r\n\r\n\
+                       --b\r\nContent-Type: message/delivery-status\r\n\r\n\
+                       Reporting-MTA: dns; mail.acme.io\r\n\r\n\
+                       Final-Recipient: rfc822; Jane@Example.com\r\n\
+                       Action: failed\r\n\
+                       Status: 5.1.1\r\n\
+                       Diagnostic-Code: smtp; 550 5.1.1 <jane@example.com>: Recipient address rejected: User unknown\r\n\
+                       --b--\r\n";
+        assert_eq!(
+            parse_dsn(postfix),
+            Some(DeliveryStatusNotification {
+                recipient: "jane@example.com".to_string(),
+                bounce_type: BounceType::Hard,
+                reason: Some("550 5.1.1 <jane@example.com>: Recipient address rejected: User unknown".to_string()),
+            })
+        );
+
+        let exim = "From: Mail Delivery System <Mailer-Daemon@mx.acme.io>\r\n\
+                    X-Failed-Recipients: bob@example.com\r\n\r\n\
+                    This message was created automatically by mail delivery software.\r\n\
+                    550 No such user here\r\n";
+        assert_eq!(parse_dsn(exim).map(|dsn| (dsn.recipient, dsn.bounce_type)), Some(("bob@example.com".to_string(), BounceType::Hard)));
+
+        // A delay warning isn't a bounce
+        let delayed = postfix.replace("Action: failed", "Action: delayed").replace("5.1.1", "4.4.1");
+        assert_eq!(parse_dsn(&delayed), None);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_hard_bounce_invalidates_and_suppresses_but_soft_does_not() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Bounces").await;
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let inbox_id = test_db::inbox(&pool, workspace_id, 50).await;
+        sqlx::query("UPDATE email_accounts SET total_sent = 10 WHERE id = $1")
+            .bind(inbox_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut sends = Vec::new();
+        for name in ["gone", "full"] {
+            let email = format!("{}-{}@example.com", name, Uuid::new_v4());
+            let (lead_id, campaign_lead_id) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+            sqlx::query("UPDATE leads SET verification_status = 'valid' WHERE id = $1")
+                .bind(lead_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sends.push((campaign_lead_id, email));
+        }
+
+        let bounce = |n: usize, bounce_type, source| NewBounce {
+            workspace_id: Some(workspace_id),
+            campaign_id: Some(campaign_id),
+            campaign_lead_id: Some(sends[n].0),
+            email_account_id: Some(inbox_id),
+            recipient: &sends[n].1,
+            bounce_type,
+            source,
+            reason: Some("550 5.1.1 User unknown"),
+            occurred_at: Utc::now(),
+        };
+        // The SMTP rejection and the bounce-back for the same send count once
+        for (n, bounce_type, source) in [
+            (0, BounceType::Hard, BounceSource::Smtp),
+            (0, BounceType::Hard, BounceSource::Imap),
+            (1, BounceType::Soft, BounceSource::Smtp),
+        ] {
+            let mut tx = pool.begin().await.unwrap();
+            apply(&mut tx, &bounce(n, bounce_type, source)).await.unwrap();
+            tx.commit().await.unwrap();
+        }
+
+        // (send status, lead verification status, suppressed)
+        async fn state(pool: &PgPool, email: &str) -> (String, String, bool) {
+            sqlx::query_as(
+                r#"
+                SELECT cl.status, l.verification_status,
+                       EXISTS (SELECT 1 FROM suppression_list s WHERE s.workspace_id = l.workspace_id AND s.email = LOWER(l.email))
+                FROM leads l JOIN campaign_leads cl ON cl.lead_id = l.id
+                WHERE l.email = $1
+                "#
+            )
+            .bind(email)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+        }
+        let gone = state(&pool, &sends[0].1).await;
+        let full = state(&pool, &sends[1].1).await;
+        let campaign_bounced: i32 = sqlx::query_scalar("SELECT bounced FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let inbox: (i32, f64) = sqlx::query_as("SELECT total_bounced, bounce_rate FROM email_accounts WHERE id = $1")
+            .bind(inbox_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let soft_bounces = soft_bounce_count(&pool, sends[1].0).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(gone, ("bounced".to_string(), "invalid".to_string(), true));
+        assert_eq!(full, ("pending".to_string(), "valid".to_string(), false));
+        assert_eq!(campaign_bounced, 1);
+        assert_eq!(inbox, (1, 0.1));
+        assert_eq!(soft_bounces, 1);
+    }
+}
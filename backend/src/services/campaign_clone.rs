@@ -0,0 +1,222 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::campaign::Campaign;
+
+/// Longest name a campaign can have (`campaigns.name` is VARCHAR(255))
+const MAX_CAMPAIGN_NAME_CHARS: usize = 255;
+const COPY_SUFFIX: &str = " (copy)";
+
+/// The source's name with " (copy)" on the end, shortening the original so the
+/// suffix always fits
+pub fn copy_name(name: &str) -> String {
+    let keep = MAX_CAMPAIGN_NAME_CHARS - COPY_SUFFIX.chars().count();
+    let base: String = name.chars().take(keep).collect();
+    format!("{}{}", base.trim_end(), COPY_SUFFIX)
+}
+
+/// Copies a campaign's settings, templates, steps, inboxes and tags into a new
+/// draft in the same workspace. Leads, counters and send history stay behind,
+/// so the copy starts from zero. `None` when the source isn't in the workspace.
+pub async fn clone_campaign(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid) -> Result<Option<Campaign>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let source: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(name) = source else {
+        return Ok(None);
+    };
+
+    let clone_id = Uuid::new_v4();
+    let campaign = sqlx::query_as::<_, Campaign>(
+        r#"
+        INSERT INTO campaigns (
+            id, name, vertical, status, total_leads, sent, opened, clicked, replied, bounced, meetings_booked,
+            workspace_id, from_name, reply_to, recipient_timezone_field,
+            spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold
+        )
+        SELECT $1, $2, vertical, 'draft', 0, 0, 0, 0, 0, 0, 0,
+               workspace_id, from_name, reply_to, recipient_timezone_field,
+               spam_rate_threshold, reply_drop_threshold, bounce_rate_threshold
+        FROM campaigns
+        WHERE id = $3
+        RETURNING *
+        "#
+    )
+    .bind(clone_id)
+    .bind(copy_name(&name))
+    .bind(campaign_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_templates (campaign_id, workspace_id, step_order, subject, body_html, body_text)
+        SELECT $1, workspace_id, step_order, subject, body_html, body_text
+        FROM campaign_templates
+        WHERE campaign_id = $2
+        "#
+    )
+    .bind(clone_id)
+    .bind(campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // step_order is unique per campaign, so it finds each step's template in the copy
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_steps (campaign_id, workspace_id, step_index, template_id, delay_days, condition)
+        SELECT $1, s.workspace_id, s.step_index, copied.id, s.delay_days, s.condition
+        FROM campaign_steps s
+        LEFT JOIN campaign_templates original ON original.id = s.template_id
+        LEFT JOIN campaign_templates copied ON copied.campaign_id = $1 AND copied.step_order = original.step_order
+        WHERE s.campaign_id = $2
+        "#
+    )
+    .bind(clone_id)
+    .bind(campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_email_accounts (campaign_id, email_account_id, workspace_id)
+        SELECT $1, email_account_id, workspace_id
+        FROM campaign_email_accounts
+        WHERE campaign_id = $2
+        "#
+    )
+    .bind(clone_id)
+    .bind(campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_tags (campaign_id, workspace_id, tag)
+        SELECT $1, workspace_id, tag
+        FROM campaign_tags
+        WHERE campaign_id = $2
+        "#
+    )
+    .bind(clone_id)
+    .bind(campaign_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(campaign))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_copy_name_always_fits_the_suffix() {
+        assert_eq!(copy_name("Q3 founders"), "Q3 founders (copy)");
+
+        let long = "é".repeat(300);
+        let copied = copy_name(&long);
+        assert_eq!(copied.chars().count(), MAX_CAMPAIGN_NAME_CHARS);
+        assert!(copied.ends_with(" (copy)"));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_clone_copies_templates_and_steps_but_not_leads_or_counts() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Campaign clone").await;
+        let (campaign_id, lead_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        sqlx::query(
+            "INSERT INTO campaigns (id, name, vertical, status, workspace_id, total_leads, sent, opened, replied, started_at, from_name)
+             VALUES ($1, 'Founders', 'saas', 'active', $2, 1, 1, 1, 1, NOW(), 'Ada')"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let template_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO campaign_templates (campaign_id, workspace_id, step_order, subject, body_html)
+             VALUES ($1, $2, 2, 'Following up', '<p>{{unsubscribe_url}}</p>') RETURNING id"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO campaign_steps (campaign_id, workspace_id, step_index, template_id, delay_days)
+             VALUES ($1, $2, 1, $3, 3)"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .bind(template_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, 'lead@campaign-clone.test')")
+            .bind(lead_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO campaign_leads (id, campaign_id, lead_id, status) VALUES ($1, $2, $3, 'sent')")
+            .bind(Uuid::new_v4())
+            .bind(campaign_id)
+            .bind(lead_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let clone = clone_campaign(&pool, workspace_id, campaign_id).await.unwrap().unwrap();
+        let missing = clone_campaign(&pool, Uuid::new_v4(), campaign_id).await.unwrap();
+
+        let step: (i32, i32, String) = sqlx::query_as(
+            "SELECT s.step_index, s.delay_days, t.subject
+             FROM campaign_steps s JOIN campaign_templates t ON t.id = s.template_id
+             WHERE s.campaign_id = $1"
+        )
+        .bind(clone.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let template_campaign: Uuid = sqlx::query_scalar(
+            "SELECT t.campaign_id FROM campaign_steps s JOIN campaign_templates t ON t.id = s.template_id WHERE s.campaign_id = $1"
+        )
+        .bind(clone.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let cloned_leads: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM campaign_leads WHERE campaign_id = $1")
+            .bind(clone.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert!(missing.is_none());
+        assert_eq!(clone.name, "Founders (copy)");
+        assert_eq!(clone.vertical, "saas");
+        assert_eq!(clone.from_name.as_deref(), Some("Ada"));
+        assert_eq!(clone.status, "draft");
+        assert_eq!((clone.total_leads, clone.sent, clone.opened, clone.replied), (0, 0, 0, 0));
+        assert!(clone.started_at.is_none());
+
+        assert_eq!(step, (1, 3, "Following up".to_string()));
+        // The step points at the copy's template, not the source's
+        assert_eq!(template_campaign, clone.id);
+        assert_eq!(cloned_leads, 0);
+    }
+}
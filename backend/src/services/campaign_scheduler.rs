@@ -1,7 +1,180 @@
 use sqlx::PgPool;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use std::sync::Arc;
+use crate::models::campaign::CampaignStatus;
+use crate::services::email_quota;
+use crate::services::mailing_address;
+use crate::services::job_queue::JobType;
+use crate::services::send_time::{in_send_window, MAX_DEFERRAL_HOURS, RECIPIENT_BUSINESS_HOURS, SEND_HOUR_TOLERANCE};
+
+/// What a start request should do to a campaign in a given status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartAction {
+    Activate,
+    Schedule,
+    /// Already running: report the current state instead of re-activating
+    AlreadyActive,
+    NotStartable,
+}
+
+/// Starting is idempotent: a repeated or retried start on an active campaign is a
+/// no-op, so `started_at` isn't reset and nothing is re-enqueued.
+pub fn start_action(current: &CampaignStatus, schedule_requested: bool) -> StartAction {
+    match current {
+        CampaignStatus::Draft | CampaignStatus::Paused | CampaignStatus::Scheduled => {
+            if schedule_requested {
+                StartAction::Schedule
+            } else {
+                StartAction::Activate
+            }
+        }
+        CampaignStatus::Active if !schedule_requested => StartAction::AlreadyActive,
+        CampaignStatus::Active | CampaignStatus::Completed => StartAction::NotStartable,
+    }
+}
+
+/// Moves a draft, paused or scheduled campaign to `active`. `false` when it was
+/// in any other state, e.g. a concurrent start already activated it.
+pub async fn activate_campaign(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE campaigns SET status = 'active', started_at = $1, scheduled_start_at = NULL WHERE id = $2 AND workspace_id = $3 AND deleted_at IS NULL AND status IN ('draft', 'paused', 'scheduled')"
+    )
+    .bind(now)
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Result of a manual resend to a single lead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResendOutcome {
+    /// A send job was enqueued right away
+    Queued(Uuid),
+    /// Reset to `pending`; the scheduler sends it once the campaign is active and an inbox has capacity
+    Pending,
+    NotFound,
+    /// Unsubscribed or on the workspace/global suppression list
+    Suppressed,
+    /// The lead's current status (e.g. already sent or still queued) doesn't allow a resend
+    NotResendable(String),
+}
+
+/// A lead can be resent after a bounce or a failed send, or when its last send job
+/// exhausted its retries (the lead then stays `scheduled`).
+pub fn is_resendable(lead_status: &str, last_job_status: Option<&str>) -> bool {
+    match lead_status {
+        "bounced" | "failed" => true,
+        "scheduled" => last_job_status == Some("failed"),
+        _ => false,
+    }
+}
+
+/// Longest wait a follow-up step can have after the previous send
+pub const MAX_STEP_DELAY_DAYS: i32 = 365;
+
+/// What a follow-up step requires of the previous email. Every step also
+/// requires that the lead hasn't replied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepCondition {
+    NoReply,
+    NotOpened,
+    Opened,
+}
+
+impl StepCondition {
+    pub const ALL: [StepCondition; 3] = [StepCondition::NoReply, StepCondition::NotOpened, StepCondition::Opened];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StepCondition::NoReply => "no_reply",
+            StepCondition::NotOpened => "not_opened",
+            StepCondition::Opened => "opened",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|condition| condition.as_str() == value)
+    }
+
+    fn is_met(&self, opened: bool) -> bool {
+        match self {
+            StepCondition::NoReply => true,
+            StepCondition::NotOpened => !opened,
+            StepCondition::Opened => opened,
+        }
+    }
+}
+
+/// One step of a campaign's sequence, as the scheduler needs it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepRule {
+    pub step_index: i32,
+    pub delay_days: i32,
+    pub condition: StepCondition,
+}
+
+/// Where a lead that has already been emailed stands in its sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUp {
+    /// The next step's delay hasn't passed yet
+    NotDue,
+    Send(i32),
+    /// No steps left, or the next step's condition isn't met
+    Finished,
+}
+
+/// The follow-up due for a lead whose last send was step `current_step`.
+/// `steps` must be sorted by `step_index`. A lead that was never emailed isn't
+/// in the sequence yet: it goes through the normal first send at step 0, even
+/// when it was added while other leads are further along.
+pub fn due_follow_up(
+    steps: &[StepRule],
+    current_step: i32,
+    last_sent_at: Option<DateTime<Utc>>,
+    opened: bool,
+    now: DateTime<Utc>,
+) -> FollowUp {
+    let Some(last_sent_at) = last_sent_at else {
+        return FollowUp::NotDue;
+    };
+    let Some(next) = steps.iter().find(|step| step.step_index > current_step) else {
+        return FollowUp::Finished;
+    };
+
+    if now < last_sent_at + Duration::days(next.delay_days as i64) {
+        FollowUp::NotDue
+    } else if next.condition.is_met(opened) {
+        FollowUp::Send(next.step_index)
+    } else {
+        FollowUp::Finished
+    }
+}
+
+/// Ends the lead's sequence in the campaign a reply belongs to, e.g. once the
+/// reply is classified as interested. Returns whether a sequence was stopped.
+pub async fn stop_sequence_for_reply(pool: &PgPool, reply_id: Uuid, reason: &str) -> Result<bool, sqlx::Error> {
+    let stopped = sqlx::query(
+        r#"
+        UPDATE campaign_leads cl
+        SET sequence_stopped_at = NOW(), sequence_stop_reason = $2
+        FROM email_replies er
+        WHERE er.id = $1
+          AND cl.campaign_id = er.campaign_id
+          AND cl.lead_id = er.lead_id
+          AND cl.sequence_stopped_at IS NULL
+        "#
+    )
+    .bind(reply_id)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+
+    Ok(stopped.rows_affected() > 0)
+}
 
 pub struct CampaignScheduler {
     pool: Arc<PgPool>,
@@ -13,15 +186,133 @@ struct PendingLead {
     lead_id: Uuid,
     campaign_id: Uuid,
     email: String,
+    /// Sequence step this send is for
+    step_index: i32,
 }
 
 #[derive(Debug, sqlx::FromRow)]
-struct AvailableInbox {
+struct SequenceLead {
     id: Uuid,
+    lead_id: Uuid,
+    campaign_id: Uuid,
     email: String,
+    email_account_id: Option<Uuid>,
+    current_step: i32,
+    sent_at: Option<DateTime<Utc>>,
+    opened: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct StepRow {
+    step_index: i32,
+    delay_days: i32,
+    condition: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ResendTarget {
+    id: Uuid,
+    lead_id: Uuid,
+    campaign_id: Uuid,
+    email: String,
+    step_index: i32,
+    status: String,
+    campaign_status: String,
+    suppressed: bool,
+    last_job_status: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct AvailableInbox {
+    id: Uuid,
     daily_limit: i32,
     sent_today: i32,
-    health_score: f32,
+    /// Send jobs already queued for this inbox that haven't gone out yet
+    queued: i64,
+    warmup_status: String,
+    send_window_start: NaiveTime,
+    send_window_end: NaiveTime,
+    timezone_offset_minutes: Option<i32>,
+}
+
+impl AvailableInbox {
+    /// Sends the inbox can still take today, counting jobs already queued for it
+    fn remaining_capacity(&self) -> i64 {
+        (self.daily_limit as i64 - self.sent_today as i64 - self.queued).max(0)
+    }
+}
+
+/// Hands out a campaign's inboxes in a smooth weighted rotation: each inbox's share
+/// of sends follows its remaining capacity for the day, and consecutive sends are
+/// spread across inboxes rather than batched on one. Warming inboxes are only used
+/// when no active inbox has room.
+struct InboxRotation<'a> {
+    inboxes: &'a [AvailableInbox],
+    weights: Vec<i64>,
+    remaining: Vec<i64>,
+    /// Running credit per inbox; the highest goes next
+    credit: Vec<i64>,
+}
+
+impl<'a> InboxRotation<'a> {
+    fn new(inboxes: &'a [AvailableInbox]) -> Self {
+        let mut remaining: Vec<i64> = inboxes.iter().map(AvailableInbox::remaining_capacity).collect();
+        let active_has_room = inboxes
+            .iter()
+            .zip(&remaining)
+            .any(|(inbox, &left)| inbox.warmup_status == "active" && left > 0);
+        if active_has_room {
+            for (inbox, left) in inboxes.iter().zip(remaining.iter_mut()) {
+                if inbox.warmup_status != "active" {
+                    *left = 0;
+                }
+            }
+        }
+
+        Self {
+            inboxes,
+            weights: remaining.clone(),
+            credit: vec![0; inboxes.len()],
+            remaining,
+        }
+    }
+
+    /// The preferred inbox if it still has room, otherwise the next one in the
+    /// rotation. `None` once every inbox is used up for the day.
+    fn take(&mut self, preferred: Option<Uuid>) -> Option<&'a AvailableInbox> {
+        let preferred = preferred
+            .and_then(|id| self.inboxes.iter().position(|inbox| inbox.id == id))
+            .filter(|&idx| self.remaining[idx] > 0);
+
+        let idx = match preferred {
+            Some(idx) => idx,
+            None => {
+                let mut total = 0;
+                let mut best: Option<usize> = None;
+                for idx in (0..self.inboxes.len()).filter(|&idx| self.remaining[idx] > 0) {
+                    self.credit[idx] += self.weights[idx];
+                    total += self.weights[idx];
+                    if best.is_none_or(|b| self.credit[idx] > self.credit[b]) {
+                        best = Some(idx);
+                    }
+                }
+                let idx = best?;
+                self.credit[idx] -= total;
+                idx
+            }
+        };
+
+        self.remaining[idx] -= 1;
+        Some(&self.inboxes[idx])
+    }
+}
+
+/// Inboxes whose send window is open at `now`, in their own local time
+fn sending_now(inboxes: Vec<AvailableInbox>, now: DateTime<Utc>) -> Vec<AvailableInbox> {
+    inboxes
+        .into_iter()
+        .filter(|inbox| in_send_window(inbox.send_window_start, inbox.send_window_end, inbox.timezone_offset_minutes, now))
+        .collect()
 }
 
 impl CampaignScheduler {
@@ -41,17 +332,83 @@ impl CampaignScheduler {
 
         let workspace_id = workspace_id.ok_or("Campaign not found")?;
 
-        // Get campaign leads that need sending (excluding suppressed emails)
+        // Nothing is queued once the month's sends are used up; the sender would only defer it
+        if email_quota::is_exhausted(self.pool.as_ref(), workspace_id).await.map_err(|e| e.to_string())? {
+            email_quota::mark_limit_reached(self.pool.as_ref(), campaign_id).await.map_err(|e| e.to_string())?;
+            return Ok(0);
+        }
+
+        // Or while the workspace has no postal address for the footer
+        if mailing_address::footer_address(self.pool.as_ref(), Some(workspace_id)).await.map_err(|e| e.to_string())?.is_none() {
+            mailing_address::mark_address_missing(self.pool.as_ref(), campaign_id).await.map_err(|e| e.to_string())?;
+            return Ok(0);
+        }
+
+        // Pending leads whose address has since been suppressed leave the queue for
+        // good, so they show up as suppressed instead of waiting forever. Global
+        // suppression wins even when a workspace has removed the address from its list.
+        sqlx::query(
+            r#"
+            UPDATE campaign_leads cl
+            SET status = 'suppressed'
+            FROM leads l
+            WHERE l.id = cl.lead_id
+              AND cl.campaign_id = $1
+              AND cl.status = 'pending'
+              AND (
+                  EXISTS (SELECT 1 FROM suppression_list s WHERE s.workspace_id = $2 AND LOWER(s.email) IN (LOWER(l.email), '@' || split_part(LOWER(l.email), '@', 2)))
+                  OR EXISTS (SELECT 1 FROM global_suppression g WHERE g.email = LOWER(l.email))
+              )
+            "#
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // Get campaign leads that need sending. Suppression is checked again here in
+        // case an address was added since the update above.
         let leads = sqlx::query_as::<_, PendingLead>(
             r#"
-            SELECT cl.id, cl.lead_id, cl.campaign_id, l.email
+            SELECT cl.id, cl.lead_id, cl.campaign_id, l.email,
+                   -- Never-sent leads start the sequence at the beginning; a resend repeats its step
+                   CASE WHEN cl.sent_at IS NULL THEN 0 ELSE cl.current_step END as step_index
             FROM campaign_leads cl
             JOIN leads l ON cl.lead_id = l.id
+            JOIN campaigns c ON cl.campaign_id = c.id
             WHERE cl.campaign_id = $1 
               AND cl.status = 'pending'
-              AND l.email NOT IN (
-                  SELECT email FROM suppression_list 
-                  WHERE workspace_id = $2
+              AND cl.unsubscribed_at IS NULL
+              AND l.deleted_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM suppression_list s
+                  WHERE s.workspace_id = $2 AND LOWER(s.email) IN (LOWER(l.email), '@' || split_part(LOWER(l.email), '@', 2))
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM global_suppression g WHERE g.email = LOWER(l.email)
+              )
+              AND (
+                  -- No per-lead preference: the campaign-level schedule decides
+                  l.preferred_send_hour IS NULL
+                  OR l.timezone_offset_minutes IS NULL
+                  -- Never defer a lead indefinitely waiting for its slot
+                  OR GREATEST(cl.created_at, c.started_at) <= NOW() - make_interval(hours => $4)
+                  -- Within the tolerance window around the lead's local preferred hour
+                  OR (
+                      SELECT LEAST(ABS(h - l.preferred_send_hour), 24 - ABS(h - l.preferred_send_hour))
+                      FROM (
+                          SELECT EXTRACT(HOUR FROM (NOW() AT TIME ZONE 'UTC')
+                                 + make_interval(mins => l.timezone_offset_minutes))::int AS h
+                      ) local_now
+                  ) <= $3
+              )
+              AND (
+                  -- Held until the recipient's business hours when their timezone is known
+                  c.recipient_timezone_field IS NULL
+                  OR recipient_local_time(c.recipient_timezone_field, l.timezone_offset_minutes, l.signals) IS NULL
+                  OR EXTRACT(HOUR FROM recipient_local_time(c.recipient_timezone_field, l.timezone_offset_minutes, l.signals))::int
+                     BETWEEN $5 AND $6 - 1
               )
             ORDER BY cl.created_at ASC
             LIMIT 100
@@ -59,6 +416,10 @@ impl CampaignScheduler {
         )
         .bind(campaign_id)
         .bind(workspace_id)
+        .bind(SEND_HOUR_TOLERANCE)
+        .bind(MAX_DEFERRAL_HOURS)
+        .bind(RECIPIENT_BUSINESS_HOURS.0)
+        .bind(RECIPIENT_BUSINESS_HOURS.1)
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| e.to_string())?;
@@ -68,76 +429,220 @@ impl CampaignScheduler {
         }
 
         // Get available inboxes with capacity
-        let inboxes = self.get_available_inboxes(workspace_id).await?;
+        let inboxes = self.get_available_inboxes(workspace_id, campaign_id).await?;
 
+        // With every inbox capped for the day, or outside its window, the leads
+        // simply wait for a later pass
+        let inboxes = sending_now(inboxes, Utc::now());
         if inboxes.is_empty() {
-            return Err("No available inboxes with sending capacity".to_string());
+            return Ok(0);
         }
 
         let mut scheduled = 0;
 
         // Distribute leads across inboxes respecting daily limits
-        for (idx, lead) in leads.iter().enumerate() {
-            let inbox = &inboxes[idx % inboxes.len()];
-            
-            // Check if inbox still has capacity
-            let remaining_capacity = inbox.daily_limit - inbox.sent_today;
-            if remaining_capacity <= 0 {
-                continue;
+        let mut rotation = InboxRotation::new(&inboxes);
+        for lead in &leads {
+            let Some(inbox) = rotation.take(None) else { break };
+            if self.claim_and_enqueue(lead, workspace_id, inbox.id).await?.is_some() {
+                scheduled += 1;
             }
+        }
 
-            // Create send job
-            let job_id = Uuid::new_v4();
-            let payload = serde_json::json!({
-                "campaign_lead_id": lead.id,
-                "campaign_id": campaign_id,
-                "lead_id": lead.lead_id,
-                "inbox_id": inbox.id,
-                "email": lead.email
-            });
-
-            let result = sqlx::query(
-                r#"
-                INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries)
-                VALUES ($1, $2, '"SendEmail"', $3, 'pending', $4, 0, 3)
-                "#
-            )
-            .bind(job_id)
-            .bind(workspace_id)
-            .bind(&payload)
-            .bind(Utc::now())
-            .execute(self.pool.as_ref())
-            .await;
+        Ok(scheduled)
+    }
 
-            if result.is_ok() {
-                // Mark lead as scheduled
-                let _ = sqlx::query(
-                    "UPDATE campaign_leads SET status = 'scheduled' WHERE id = $1"
-                )
-                .bind(lead.id)
-                .execute(self.pool.as_ref())
-                .await;
+    /// Claims a lead for its next send and enqueues the job together. The claim only
+    /// succeeds while the lead is still pending, or sent and not yet past this step,
+    /// so overlapping scheduler passes (or a re-activated campaign) can't enqueue the
+    /// same send twice. Returns the job ID, or `None` if the lead was already claimed
+    /// or the job couldn't be inserted.
+    async fn claim_and_enqueue(&self, lead: &PendingLead, workspace_id: Uuid, inbox_id: Uuid) -> Result<Option<Uuid>, String> {
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
 
-                scheduled += 1;
-            }
+        let claimed = sqlx::query(
+            r#"
+            UPDATE campaign_leads SET status = 'scheduled', current_step = $2
+            WHERE id = $1 AND (status = 'pending' OR (status = 'sent' AND current_step < $2))
+            "#
+        )
+        .bind(lead.id)
+        .bind(lead.step_index)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if claimed.rows_affected() == 0 {
+            return Ok(None);
         }
 
-        Ok(scheduled)
+        let job_id = Uuid::new_v4();
+        let payload = serde_json::json!({
+            "campaign_lead_id": lead.id,
+            "campaign_id": lead.campaign_id,
+            "lead_id": lead.lead_id,
+            "inbox_id": inbox_id,
+            "email": lead.email,
+            "step_index": lead.step_index
+        });
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries, priority)
+            VALUES ($1, $2, '"SendEmail"', $3, 'pending', $4, 0, 3, $5)
+            "#
+        )
+        .bind(job_id)
+        .bind(workspace_id)
+        .bind(&payload)
+        .bind(Utc::now())
+        .bind(JobType::SendEmail.default_priority())
+        .execute(&mut *tx)
+        .await;
+
+        // A failed insert drops the transaction, leaving the lead pending for the next pass
+        if result.is_ok() && tx.commit().await.is_ok() {
+            Ok(Some(job_id))
+        } else {
+            Ok(None)
+        }
     }
 
-    async fn get_available_inboxes(&self, workspace_id: Uuid) -> Result<Vec<AvailableInbox>, String> {
+    /// Manually retries the send to one lead after a bounce or a send job that ran
+    /// out of retries. The lead goes back to `pending`; if the campaign is active and
+    /// an inbox has capacity it is enqueued straight away, otherwise the scheduler
+    /// picks it up once the campaign is running and capacity frees up.
+    pub async fn resend_to_lead(&self, workspace_id: Uuid, campaign_id: Uuid, lead_id: Uuid) -> Result<ResendOutcome, String> {
+        let target = sqlx::query_as::<_, ResendTarget>(
+            r#"
+            SELECT
+                cl.id, cl.lead_id, cl.campaign_id, l.email,
+                CASE WHEN cl.sent_at IS NULL THEN 0 ELSE cl.current_step END as step_index,
+                COALESCE(cl.status, 'pending') as status, COALESCE(c.status, 'draft') as campaign_status,
+                cl.unsubscribed_at IS NOT NULL
+                    OR EXISTS (SELECT 1 FROM suppression_list s WHERE s.workspace_id = $3 AND LOWER(s.email) IN (LOWER(l.email), '@' || split_part(LOWER(l.email), '@', 2)))
+                    OR EXISTS (SELECT 1 FROM global_suppression g WHERE g.email = LOWER(l.email)) as suppressed,
+                (
+                    SELECT j.status FROM jobs j
+                    WHERE j.job_type = '"SendEmail"' AND j.payload->>'campaign_lead_id' = cl.id::text
+                    ORDER BY j.created_at DESC
+                    LIMIT 1
+                ) as last_job_status
+            FROM campaign_leads cl
+            JOIN leads l ON l.id = cl.lead_id
+            JOIN campaigns c ON c.id = cl.campaign_id
+            WHERE cl.campaign_id = $1 AND cl.lead_id = $2 AND c.workspace_id = $3
+              AND c.deleted_at IS NULL AND l.deleted_at IS NULL
+            "#
+        )
+        .bind(campaign_id)
+        .bind(lead_id)
+        .bind(workspace_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let Some(target) = target else {
+            return Ok(ResendOutcome::NotFound);
+        };
+
+        if target.suppressed {
+            return Ok(ResendOutcome::Suppressed);
+        }
+        if !is_resendable(&target.status, target.last_job_status.as_deref()) {
+            return Ok(ResendOutcome::NotResendable(target.status));
+        }
+
+        // Status check repeated in the update so a concurrent send or resend wins cleanly
+        let reset = sqlx::query(
+            "UPDATE campaign_leads SET status = 'pending', bounce_reason = NULL WHERE id = $1 AND status = $2"
+        )
+        .bind(target.id)
+        .bind(&target.status)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if reset.rows_affected() == 0 {
+            return Ok(ResendOutcome::NotResendable(target.status));
+        }
+
+        if CampaignStatus::parse(&target.campaign_status) != Some(CampaignStatus::Active) {
+            return Ok(ResendOutcome::Pending);
+        }
+
+        let inboxes = sending_now(self.get_available_inboxes(workspace_id, campaign_id).await?, Utc::now());
+        let Some(inbox) = InboxRotation::new(&inboxes).take(None) else {
+            return Ok(ResendOutcome::Pending);
+        };
+
+        let lead = PendingLead {
+            id: target.id,
+            lead_id: target.lead_id,
+            campaign_id: target.campaign_id,
+            email: target.email,
+            step_index: target.step_index,
+        };
+
+        Ok(match self.claim_and_enqueue(&lead, workspace_id, inbox.id).await? {
+            Some(job_id) => ResendOutcome::Queued(job_id),
+            None => ResendOutcome::Pending,
+        })
+    }
+
+    /// Healthy, under-limit inboxes the campaign may send from
+    async fn get_available_inboxes(&self, workspace_id: Uuid, campaign_id: Uuid) -> Result<Vec<AvailableInbox>, String> {
         sqlx::query_as::<_, AvailableInbox>(
             r#"
-            SELECT id, email, daily_limit, sent_today, health_score 
-            FROM email_accounts
-            WHERE workspace_id = $1
-              AND warmup_status IN ('active', 'warming')
-              AND sent_today < daily_limit
-              AND health_score >= 50.0
+            SELECT id, daily_limit, sent_today, queued, warmup_status,
+                   send_window_start, send_window_end, timezone_offset_minutes
+            FROM (
+                SELECT ea.*, (
+                    SELECT COUNT(*) FROM jobs j
+                    WHERE j.job_type = '"SendEmail"'
+                      AND j.status IN ('pending', 'scheduled', 'processing')
+                      AND j.payload->>'inbox_id' = ea.id::text
+                ) AS queued
+                FROM email_accounts ea
+                WHERE ea.workspace_id = $1
+                  AND ea.warmup_status IN ('active', 'warming')
+                  AND ea.health_score >= 50.0
+                  -- Same thresholds the inbox health cards show as danger
+                  AND NOT (COALESCE(ea.spam_rate, 0) > 0.03 OR COALESCE(ea.bounce_rate, 0) > 0.08)
+                  -- A campaign with assigned inboxes sends only from those
+                  AND (
+                      NOT EXISTS (SELECT 1 FROM campaign_email_accounts cea WHERE cea.campaign_id = $2)
+                      OR EXISTS (
+                          SELECT 1 FROM campaign_email_accounts cea
+                          WHERE cea.campaign_id = $2 AND cea.email_account_id = ea.id
+                      )
+                  )
+            ) inboxes
+            WHERE sent_today + queued < daily_limit
             ORDER BY health_score DESC, sent_today ASC
             "#
         )
         .bind(workspace_id)
+        .bind(campaign_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Moves `scheduled` campaigns whose start time is at or before `now` to `active`.
+    pub async fn activate_scheduled_campaigns(&self, now: DateTime<Utc>) -> Result<Vec<Uuid>, String> {
+        sqlx::query_scalar(
+            r#"
+            UPDATE campaigns
+            SET status = 'active', started_at = $1
+            WHERE status = 'scheduled'
+              AND deleted_at IS NULL
+              AND scheduled_start_at IS NOT NULL
+              AND scheduled_start_at <= $1
+            RETURNING id
+            "#
+        )
+        .bind(now)
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| e.to_string())
@@ -146,7 +651,7 @@ impl CampaignScheduler {
     pub async fn process_active_campaigns(&self) -> Result<(), String> {
         // Get all active campaigns
         let campaign_ids: Vec<Uuid> = sqlx::query_scalar(
-            "SELECT id FROM campaigns WHERE status = 'active'"
+            "SELECT id FROM campaigns WHERE status = 'active' AND deleted_at IS NULL"
         )
         .fetch_all(self.pool.as_ref())
         .await
@@ -163,8 +668,310 @@ impl CampaignScheduler {
                     eprintln!("Failed to schedule campaign {}: {}", campaign_id, e);
                 }
             }
+
+            match self.schedule_follow_ups(campaign_id).await {
+                Ok(0) => {}
+                Ok(count) => println!("Scheduled {} follow-ups for campaign {}", count, campaign_id),
+                Err(e) => eprintln!("Failed to schedule follow-ups for campaign {}: {}", campaign_id, e),
+            }
         }
 
         Ok(())
     }
+
+    /// Enqueues the next sequence step for leads whose delay has passed. Leads that
+    /// replied, opted out or were suppressed have their sequence stopped first.
+    pub async fn schedule_follow_ups(&self, campaign_id: Uuid) -> Result<i32, String> {
+        let steps: Vec<StepRule> = sqlx::query_as::<_, StepRow>(
+            "SELECT step_index, delay_days, condition FROM campaign_steps WHERE campaign_id = $1 ORDER BY step_index ASC"
+        )
+        .bind(campaign_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| StepRule {
+            step_index: row.step_index,
+            delay_days: row.delay_days,
+            condition: StepCondition::parse(&row.condition).unwrap_or(StepCondition::NoReply),
+        })
+        .collect();
+
+        // Only step 0, or no steps at all: nothing to follow up with
+        if !steps.iter().any(|step| step.step_index > 0) {
+            return Ok(0);
+        }
+
+        let workspace_id: Uuid = sqlx::query_scalar("SELECT workspace_id FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .ok_or("Campaign not found")?;
+
+        sqlx::query(
+            r#"
+            UPDATE campaign_leads cl
+            SET sequence_stopped_at = NOW(),
+                sequence_stop_reason = CASE
+                    WHEN cl.replied_at IS NOT NULL THEN 'replied'
+                    WHEN cl.unsubscribed_at IS NOT NULL THEN 'unsubscribed'
+                    ELSE 'suppressed'
+                END
+            FROM leads l
+            WHERE l.id = cl.lead_id
+              AND cl.campaign_id = $1
+              AND cl.sequence_stopped_at IS NULL
+              AND (
+                  cl.replied_at IS NOT NULL
+                  OR cl.unsubscribed_at IS NOT NULL
+                  OR EXISTS (SELECT 1 FROM suppression_list s WHERE s.workspace_id = $2 AND LOWER(s.email) IN (LOWER(l.email), '@' || split_part(LOWER(l.email), '@', 2)))
+                  OR EXISTS (SELECT 1 FROM global_suppression g WHERE g.email = LOWER(l.email))
+              )
+            "#
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let min_delay = steps.iter().filter(|step| step.step_index > 0).map(|step| step.delay_days).min().unwrap_or(0);
+        let leads = sqlx::query_as::<_, SequenceLead>(
+            r#"
+            SELECT cl.id, cl.lead_id, cl.campaign_id, l.email, cl.email_account_id, cl.current_step,
+                   cl.sent_at, cl.opened_at IS NOT NULL as opened
+            FROM campaign_leads cl
+            JOIN leads l ON l.id = cl.lead_id
+            JOIN campaigns c ON c.id = cl.campaign_id
+            WHERE cl.campaign_id = $1
+              AND cl.status = 'sent'
+              AND cl.sequence_stopped_at IS NULL
+              AND cl.sent_at <= NOW() - make_interval(days => $2)
+              AND l.deleted_at IS NULL
+              -- Same business-hours hold as first sends
+              AND (
+                  c.recipient_timezone_field IS NULL
+                  OR recipient_local_time(c.recipient_timezone_field, l.timezone_offset_minutes, l.signals) IS NULL
+                  OR EXTRACT(HOUR FROM recipient_local_time(c.recipient_timezone_field, l.timezone_offset_minutes, l.signals))::int
+                     BETWEEN $3 AND $4 - 1
+              )
+            ORDER BY cl.sent_at ASC
+            LIMIT 100
+            "#
+        )
+        .bind(campaign_id)
+        .bind(min_delay)
+        .bind(RECIPIENT_BUSINESS_HOURS.0)
+        .bind(RECIPIENT_BUSINESS_HOURS.1)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut due = Vec::new();
+        let mut finished = Vec::new();
+        let now = Utc::now();
+        for lead in leads {
+            match due_follow_up(&steps, lead.current_step, lead.sent_at, lead.opened, now) {
+                FollowUp::Send(step_index) => due.push((lead, step_index)),
+                FollowUp::Finished => finished.push(lead.id),
+                FollowUp::NotDue => {}
+            }
+        }
+
+        if !finished.is_empty() {
+            sqlx::query(
+                r#"
+                UPDATE campaign_leads SET sequence_stopped_at = NOW(), sequence_stop_reason = 'completed'
+                WHERE id = ANY($1) AND sequence_stopped_at IS NULL
+                "#
+            )
+            .bind(&finished)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let inboxes = sending_now(self.get_available_inboxes(workspace_id, campaign_id).await?, Utc::now());
+        if inboxes.is_empty() {
+            return Ok(0);
+        }
+
+        let mut scheduled = 0;
+        let mut rotation = InboxRotation::new(&inboxes);
+        for (lead, step_index) in due {
+            // Follow up from the address the lead already heard from when it has room
+            let Some(inbox) = rotation.take(lead.email_account_id) else { break };
+
+            let pending = PendingLead {
+                id: lead.id,
+                lead_id: lead.lead_id,
+                campaign_id: lead.campaign_id,
+                email: lead.email,
+                step_index,
+            };
+            if self.claim_and_enqueue(&pending, workspace_id, inbox.id).await?.is_some() {
+                scheduled += 1;
+            }
+        }
+
+        Ok(scheduled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_second_start_is_a_no_op() {
+        let mut status = CampaignStatus::Draft;
+
+        // First click activates
+        assert_eq!(start_action(&status, false), StartAction::Activate);
+        status = CampaignStatus::Active;
+
+        // Double click / retried request leaves the running campaign alone
+        assert_eq!(start_action(&status, false), StartAction::AlreadyActive);
+        assert_eq!(start_action(&status, true), StartAction::NotStartable);
+
+        assert_eq!(start_action(&CampaignStatus::Paused, false), StartAction::Activate);
+        assert_eq!(start_action(&CampaignStatus::Scheduled, true), StartAction::Schedule);
+        assert_eq!(start_action(&CampaignStatus::Completed, false), StartAction::NotStartable);
+    }
+
+    fn inbox(daily_limit: i32, sent_today: i32, queued: i64, warmup_status: &str) -> AvailableInbox {
+        AvailableInbox {
+            id: Uuid::new_v4(),
+            daily_limit,
+            sent_today,
+            queued,
+            warmup_status: warmup_status.to_string(),
+            send_window_start: NaiveTime::MIN,
+            send_window_end: NaiveTime::MIN,
+            timezone_offset_minutes: None,
+        }
+    }
+
+    #[test]
+    fn test_rotation_stops_at_daily_limit() {
+        // daily_limit=2: the third send is left for tomorrow
+        let inboxes = [inbox(2, 0, 0, "active")];
+        let mut rotation = InboxRotation::new(&inboxes);
+        assert!(rotation.take(None).is_some());
+        assert!(rotation.take(None).is_some());
+        assert!(rotation.take(None).is_none());
+
+        // Queued jobs count against the limit; a capped preferred inbox falls back to the rotation
+        let inboxes = [inbox(2, 1, 1, "active"), inbox(10, 8, 0, "active")];
+        let mut rotation = InboxRotation::new(&inboxes);
+        assert_eq!(rotation.take(Some(inboxes[0].id)).unwrap().id, inboxes[1].id);
+        assert_eq!(rotation.take(Some(inboxes[1].id)).unwrap().id, inboxes[1].id);
+        assert!(rotation.take(None).is_none());
+    }
+
+    #[test]
+    fn test_rotation_spreads_sends_by_capacity() {
+        let count = |inboxes: &[AvailableInbox], sends: usize| {
+            let mut rotation = InboxRotation::new(inboxes);
+            let mut counts = vec![0; inboxes.len()];
+            for _ in 0..sends {
+                let Some(picked) = rotation.take(None) else { break };
+                counts[inboxes.iter().position(|i| i.id == picked.id).unwrap()] += 1;
+            }
+            counts
+        };
+
+        // Three inboxes with the same room left share 30 sends evenly
+        let inboxes = [inbox(50, 0, 0, "active"), inbox(60, 10, 0, "active"), inbox(60, 5, 5, "active")];
+        assert_eq!(count(&inboxes, 30), vec![10, 10, 10]);
+
+        // Shares follow remaining capacity
+        let inboxes = [inbox(100, 90, 0, "active"), inbox(100, 80, 0, "active"), inbox(100, 70, 0, "active")];
+        assert_eq!(count(&inboxes, 30), vec![5, 10, 15]);
+
+        // Warming inboxes only step in once no active inbox has room
+        let inboxes = [inbox(20, 0, 0, "warming"), inbox(40, 40, 0, "active")];
+        assert_eq!(count(&inboxes, 30), vec![20, 0]);
+        let inboxes = [inbox(20, 0, 0, "warming"), inbox(40, 0, 0, "active")];
+        assert_eq!(count(&inboxes, 30), vec![0, 30]);
+    }
+
+    #[test]
+    fn test_only_failed_sends_are_resendable() {
+        assert!(is_resendable("bounced", None));
+        assert!(is_resendable("failed", Some("failed")));
+        // Job ran out of retries, lead was left scheduled
+        assert!(is_resendable("scheduled", Some("failed")));
+
+        // Still queued or retrying, already delivered, or opted out
+        assert!(!is_resendable("scheduled", Some("pending")));
+        assert!(!is_resendable("scheduled", Some("scheduled")));
+        assert!(!is_resendable("pending", None));
+        assert!(!is_resendable("sent", Some("completed")));
+        assert!(!is_resendable("unsubscribed", None));
+    }
+
+    #[test]
+    fn test_follow_up_waits_for_delay_and_condition() {
+        let steps = vec![
+            StepRule { step_index: 0, delay_days: 0, condition: StepCondition::NoReply },
+            StepRule { step_index: 1, delay_days: 3, condition: StepCondition::NoReply },
+            StepRule { step_index: 2, delay_days: 4, condition: StepCondition::NotOpened },
+        ];
+        let sent = Utc::now() - Duration::days(5);
+
+        // Never emailed, e.g. added mid-sequence: it starts at step 0 through the first send
+        assert_eq!(due_follow_up(&steps, 0, None, false, Utc::now()), FollowUp::NotDue);
+
+        assert_eq!(due_follow_up(&steps, 0, Some(sent), false, sent + Duration::days(2)), FollowUp::NotDue);
+        assert_eq!(due_follow_up(&steps, 0, Some(sent), false, sent + Duration::days(3)), FollowUp::Send(1));
+        assert_eq!(due_follow_up(&steps, 1, Some(sent), false, sent + Duration::days(4)), FollowUp::Send(2));
+        // Step 2 only goes to leads who never opened
+        assert_eq!(due_follow_up(&steps, 1, Some(sent), true, sent + Duration::days(4)), FollowUp::Finished);
+        assert_eq!(due_follow_up(&steps, 2, Some(sent), false, sent + Duration::days(30)), FollowUp::Finished);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_scheduled_campaign_starts_once_its_time_passes() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Scheduled start").await;
+        let now = Utc::now();
+        let start_at = now + Duration::minutes(1);
+        let campaign_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO campaigns (id, workspace_id, name, vertical, status, scheduled_start_at) VALUES ($1, $2, 'Later', 'saas', 'scheduled', $3)"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .bind(start_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let scheduler = CampaignScheduler::new(Arc::new(pool.clone()));
+        let early = scheduler.activate_scheduled_campaigns(now).await.unwrap();
+        let on_time = scheduler.activate_scheduled_campaigns(start_at + Duration::seconds(1)).await.unwrap();
+        let (status, started_at): (String, Option<DateTime<Utc>>) =
+            sqlx::query_as("SELECT status, started_at FROM campaigns WHERE id = $1")
+                .bind(campaign_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert!(!early.contains(&campaign_id));
+        assert!(on_time.contains(&campaign_id));
+        assert_eq!(status, "active");
+        assert_eq!(started_at.map(|t| t.timestamp()), Some((start_at + Duration::seconds(1)).timestamp()));
+    }
 }
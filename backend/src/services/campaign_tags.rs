@@ -0,0 +1,156 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::campaign::{Campaign, CampaignStatus};
+
+/// Narrows the campaign list. Every filter that is set must match.
+#[derive(Debug, Default)]
+pub struct CampaignFilter {
+    /// Already-normalized tags, all of which a campaign must carry
+    pub tags: Vec<String>,
+    pub status: Option<CampaignStatus>,
+}
+
+/// Tags on a campaign, alphabetically
+pub async fn list_tags(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT tag FROM campaign_tags WHERE campaign_id = $1 AND workspace_id = $2 ORDER BY tag"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Adds already-normalized tags to a campaign in the workspace. Returns `None`
+/// if the campaign doesn't exist there, otherwise its tags afterwards.
+pub async fn add_tags(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    campaign_id: Uuid,
+    tags: &[String],
+) -> Result<Option<Vec<String>>, sqlx::Error> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    if !exists {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO campaign_tags (campaign_id, workspace_id, tag)
+        SELECT $1, $2, UNNEST($3::text[])
+        ON CONFLICT (campaign_id, tag) DO NOTHING
+        "#
+    )
+    .bind(campaign_id)
+    .bind(workspace_id)
+    .bind(tags)
+    .execute(pool)
+    .await?;
+
+    list_tags(pool, workspace_id, campaign_id).await.map(Some)
+}
+
+/// Removes a tag from a campaign. Returns whether it was there.
+pub async fn remove_tag(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid, tag: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM campaign_tags WHERE campaign_id = $1 AND workspace_id = $2 AND tag = $3")
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .bind(tag)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// The workspace's campaigns matching the filter, newest first
+pub async fn list_campaigns(pool: &PgPool, workspace_id: Uuid, filter: &CampaignFilter) -> Result<Vec<Campaign>, sqlx::Error> {
+    let mut tags = filter.tags.clone();
+    tags.sort();
+    tags.dedup();
+
+    sqlx::query_as::<_, Campaign>(
+        r#"
+        SELECT c.* FROM campaigns c
+        WHERE c.workspace_id = $1 AND c.deleted_at IS NULL
+          AND ($2::text IS NULL OR c.status = $2)
+          AND (
+              SELECT COUNT(*) FROM campaign_tags t
+              WHERE t.campaign_id = c.id AND t.workspace_id = $1 AND t.tag = ANY($3)
+          ) = CARDINALITY($3::text[])
+        ORDER BY c.created_at DESC
+        "#
+    )
+    .bind(workspace_id)
+    .bind(filter.status.as_ref().map(CampaignStatus::as_str))
+    .bind(&tags)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_campaigns_filter_by_every_tag_given() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Campaign tags").await;
+
+        let mut ids = Vec::new();
+        for (name, status) in [("Both", "active"), ("Q3 only", "draft"), ("Untagged", "active")] {
+            let id = Uuid::new_v4();
+            sqlx::query("INSERT INTO campaigns (id, name, vertical, status, workspace_id) VALUES ($1, $2, 'saas', $3, $4)")
+                .bind(id)
+                .bind(name)
+                .bind(status)
+                .bind(workspace_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        let tags = |t: &[&str]| t.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let added = add_tags(&pool, workspace_id, ids[0], &tags(&["q3", "founders", "q3"])).await.unwrap();
+        add_tags(&pool, workspace_id, ids[1], &tags(&["q3"])).await.unwrap();
+        let elsewhere = add_tags(&pool, Uuid::new_v4(), ids[2], &tags(&["q3"])).await.unwrap();
+
+        let names = |campaigns: Vec<Campaign>| campaigns.into_iter().map(|c| c.name).collect::<Vec<_>>();
+        let list = |tags: Vec<String>, status: Option<CampaignStatus>| {
+            let pool = pool.clone();
+            async move { names(list_campaigns(&pool, workspace_id, &CampaignFilter { tags, status }).await.unwrap()) }
+        };
+        let mut single = list(tags(&["q3"]), None).await;
+        single.sort();
+        let both = list(tags(&["q3", "founders"]), None).await;
+        let draft_q3 = list(tags(&["q3"]), Some(CampaignStatus::Draft)).await;
+        let unknown = list(tags(&["q3", "nope"]), None).await;
+        let everything = list(Vec::new(), None).await;
+
+        let removed = remove_tag(&pool, workspace_id, ids[0], "founders").await.unwrap();
+        let after_remove = list(tags(&["q3", "founders"]), None).await;
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(added, Some(tags(&["founders", "q3"])));
+        assert_eq!(elsewhere, None);
+        assert_eq!(single, vec!["Both", "Q3 only"]);
+        assert_eq!(both, vec!["Both"]);
+        assert_eq!(draft_q3, vec!["Q3 only"]);
+        assert!(unknown.is_empty());
+        assert_eq!(everything.len(), 3);
+        assert!(removed);
+        assert!(after_remove.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Mailbox providers whose domains say nothing about the lead's employer.
+const PERSONAL_EMAIL_DOMAINS: &[&str] = &[
+    "gmail.com", "googlemail.com", "yahoo.com", "ymail.com", "hotmail.com", "outlook.com",
+    "live.com", "msn.com", "icloud.com", "me.com", "mac.com", "aol.com", "proton.me",
+    "protonmail.com", "gmx.com", "gmx.de", "web.de", "mail.com", "yandex.com", "zoho.com",
+    "fastmail.com", "hey.com",
+];
+
+/// Second-level labels that sit under a ccTLD and aren't part of the company name.
+const GENERIC_SECOND_LEVEL: &[&str] = &["co", "com", "org", "net", "ac", "gov", "edu"];
+
+#[derive(Debug, sqlx::FromRow)]
+struct DomainCandidate {
+    domain: String,
+    company: Option<String>,
+    vertical: Option<String>,
+}
+
+/// Company domain for a lead's address, or `None` for free-mail and malformed addresses.
+pub fn company_domain(email: &str) -> Option<String> {
+    let (_, domain) = email.trim().rsplit_once('@')?;
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+
+    if !domain.contains('.') || is_personal_domain(&domain) {
+        return None;
+    }
+    Some(domain)
+}
+
+fn is_personal_domain(domain: &str) -> bool {
+    PERSONAL_EMAIL_DOMAINS.contains(&domain)
+}
+
+/// Readable name from a domain: "acme-labs.co.uk" -> "Acme Labs".
+pub fn infer_company_name(domain: &str) -> String {
+    let mut labels: Vec<&str> = domain.split('.').filter(|l| !l.is_empty()).collect();
+    if labels.len() > 1 {
+        labels.pop();
+    }
+    if labels.len() > 1 && GENERIC_SECOND_LEVEL.contains(labels.last().unwrap_or(&"")) {
+        labels.pop();
+    }
+
+    labels
+        .last()
+        .unwrap_or(&domain)
+        .split(['-', '_'])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Creates a `companies` row for the lead's email domain if none exists yet.
+/// The lead's own company name wins over the one inferred from the domain.
+/// Returns whether a row was created.
+pub async fn upsert_from_lead(
+    pool: &PgPool,
+    email: &str,
+    company_name: Option<&str>,
+    industry: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    match company_domain(email) {
+        Some(domain) => upsert_company(pool, &domain, company_name, industry).await,
+        None => Ok(false),
+    }
+}
+
+async fn upsert_company(
+    pool: &PgPool,
+    domain: &str,
+    company_name: Option<&str>,
+    industry: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let name = company_name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| infer_company_name(domain));
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO companies (name, domain, industry, website_url)
+        VALUES ($1, $2, COALESCE($3, 'web3'), $4)
+        ON CONFLICT (domain) DO NOTHING
+        "#
+    )
+    .bind(&name)
+    .bind(domain)
+    .bind(industry)
+    .bind(format!("https://{}", domain))
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Scans a workspace's leads and creates company rows for domains that aren't
+/// tracked yet. Returns `(domains_scanned, companies_created)`.
+pub async fn discover_from_leads(pool: &PgPool, workspace_id: Uuid) -> Result<(usize, usize), sqlx::Error> {
+    let candidates = sqlx::query_as::<_, DomainCandidate>(
+        r#"
+        SELECT
+            email_domain(l.email) as domain,
+            MAX(l.company) as company,
+            MAX(c.vertical) as vertical
+        FROM leads l
+        LEFT JOIN campaign_leads cl ON cl.lead_id = l.id
+        LEFT JOIN campaigns c ON c.id = cl.campaign_id
+        WHERE l.workspace_id = $1
+          AND email_domain(l.email) <> ''
+          AND NOT EXISTS (SELECT 1 FROM companies co WHERE co.domain = email_domain(l.email))
+        GROUP BY email_domain(l.email)
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    let scanned = candidates.len();
+    let mut created = 0;
+
+    for candidate in candidates {
+        if !candidate.domain.contains('.') || is_personal_domain(&candidate.domain) {
+            continue;
+        }
+        if upsert_company(pool, &candidate.domain, candidate.company.as_deref(), candidate.vertical.as_deref()).await? {
+            created += 1;
+        }
+    }
+
+    Ok((scanned, created))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_company_domain_and_name() {
+        assert_eq!(company_domain("Jane@Acme-Labs.io"), Some("acme-labs.io".to_string()));
+        assert_eq!(company_domain("jane@gmail.com"), None);
+        assert_eq!(company_domain("not-an-email"), None);
+
+        assert_eq!(infer_company_name("acme-labs.io"), "Acme Labs");
+        assert_eq!(infer_company_name("widgets.co.uk"), "Widgets");
+        assert_eq!(infer_company_name("eu.stripe.com"), "Stripe");
+    }
+}
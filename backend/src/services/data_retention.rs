@@ -0,0 +1,200 @@
+use chrono::{DateTime, Months, Timelike, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest retention period a workspace can set
+pub const MAX_RETENTION_MONTHS: i32 = 120;
+/// UTC hour the nightly purge runs in
+const PURGE_HOUR_UTC: u32 = 3;
+
+/// Campaigns whose data is still in use and must not be purged
+const LIVE_CAMPAIGNS: &str =
+    "SELECT id FROM campaigns WHERE workspace_id = $1 AND deleted_at IS NULL AND status IN ('active', 'scheduled', 'paused')";
+
+/// What one purge run removed, as recorded in `retention_purge_runs`
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct PurgeRun {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub event_retention_months: Option<i32>,
+    pub reply_retention_months: Option<i32>,
+    pub events_deleted: i64,
+    pub replies_anonymized: i64,
+    pub ran_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RetentionPolicy {
+    workspace_id: Uuid,
+    event_retention_months: Option<i32>,
+    reply_retention_months: Option<i32>,
+}
+
+/// Whether `months` is an allowed retention period. Settings use 0 to clear it.
+pub fn is_valid_retention_months(months: i32) -> bool {
+    (1..=MAX_RETENTION_MONTHS).contains(&months)
+}
+
+/// Records older than this are purged. Calendar months, so a 1-month policy run
+/// on 31 March keeps everything since 29 February.
+pub fn retention_cutoff(now: DateTime<Utc>, months: i32) -> Option<DateTime<Utc>> {
+    now.checked_sub_months(Months::new(u32::try_from(months).ok()?))
+}
+
+pub fn is_purge_window(now: DateTime<Utc>) -> bool {
+    now.hour() == PURGE_HOUR_UTC
+}
+
+/// Purges every workspace with a retention policy that hasn't had a run in the
+/// last day. Returns the runs made.
+pub async fn run_nightly_purge(pool: &PgPool) -> Result<Vec<PurgeRun>, sqlx::Error> {
+    let policies = sqlx::query_as::<_, RetentionPolicy>(
+        r#"
+        SELECT ws.workspace_id, ws.event_retention_months, ws.reply_retention_months
+        FROM workspace_settings ws
+        WHERE (ws.event_retention_months IS NOT NULL OR ws.reply_retention_months IS NOT NULL)
+          AND NOT EXISTS (
+              SELECT 1 FROM retention_purge_runs r
+              WHERE r.workspace_id = ws.workspace_id AND r.ran_at > NOW() - INTERVAL '20 hours'
+          )
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut runs = Vec::with_capacity(policies.len());
+    for policy in policies {
+        runs.push(purge_workspace(pool, &policy, Utc::now()).await?);
+    }
+
+    Ok(runs)
+}
+
+/// Deletes old email events and bounces, anonymizes old replies for one workspace,
+/// and logs the run, all in one transaction.
+async fn purge_workspace(pool: &PgPool, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<PurgeRun, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut events_deleted = 0;
+    if let Some(cutoff) = policy.event_retention_months.and_then(|m| retention_cutoff(now, m)) {
+        events_deleted = sqlx::query(&format!(
+            r#"
+            DELETE FROM email_events
+            WHERE workspace_id = $1
+              AND occurred_at < $2
+              AND (campaign_id IS NULL OR campaign_id NOT IN ({}))
+            "#,
+            LIVE_CAMPAIGNS
+        ))
+        .bind(policy.workspace_id)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // Bounces are events too, and name the recipient
+        events_deleted += sqlx::query(&format!(
+            r#"
+            DELETE FROM bounces
+            WHERE workspace_id = $1
+              AND occurred_at < $2
+              AND (campaign_id IS NULL OR campaign_id NOT IN ({}))
+            "#,
+            LIVE_CAMPAIGNS
+        ))
+        .bind(policy.workspace_id)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    // Intent and sentiment stay for reporting; who sent it and what it said go
+    let mut replies_anonymized = 0;
+    if let Some(cutoff) = policy.reply_retention_months.and_then(|m| retention_cutoff(now, m)) {
+        replies_anonymized = sqlx::query(&format!(
+            r#"
+            UPDATE email_replies er
+            SET from_email = 'anonymized',
+                from_name = NULL,
+                subject = NULL,
+                body_text = NULL,
+                body_html = NULL,
+                message_id = NULL,
+                in_reply_to = NULL,
+                lead_id = NULL,
+                anonymized_at = NOW()
+            WHERE er.workspace_id = $1
+              AND er.anonymized_at IS NULL
+              AND COALESCE(er.received_at, er.created_at) < $2
+              AND (er.campaign_id IS NULL OR er.campaign_id NOT IN ({}))
+              AND NOT EXISTS (
+                  SELECT 1 FROM meetings m
+                  WHERE m.reply_id = er.id AND m.status = 'scheduled'
+              )
+            "#,
+            LIVE_CAMPAIGNS
+        ))
+        .bind(policy.workspace_id)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    let run = sqlx::query_as::<_, PurgeRun>(
+        r#"
+        INSERT INTO retention_purge_runs (workspace_id, event_retention_months, reply_retention_months, events_deleted, replies_anonymized)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, workspace_id, event_retention_months, reply_retention_months, events_deleted, replies_anonymized, ran_at
+        "#
+    )
+    .bind(policy.workspace_id)
+    .bind(policy.event_retention_months)
+    .bind(policy.reply_retention_months)
+    .bind(events_deleted as i64)
+    .bind(replies_anonymized as i64)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(run)
+}
+
+/// Most recent purge runs for the workspace, newest first
+pub async fn recent_runs(pool: &PgPool, workspace_id: Uuid, limit: i64) -> Result<Vec<PurgeRun>, sqlx::Error> {
+    sqlx::query_as::<_, PurgeRun>(
+        r#"
+        SELECT id, workspace_id, event_retention_months, reply_retention_months, events_deleted, replies_anonymized, ran_at
+        FROM retention_purge_runs
+        WHERE workspace_id = $1
+        ORDER BY ran_at DESC
+        LIMIT $2
+        "#
+    )
+    .bind(workspace_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_retention_cutoff_uses_calendar_months() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 31, 3, 0, 0).unwrap();
+        assert_eq!(retention_cutoff(now, 1), Some(Utc.with_ymd_and_hms(2024, 2, 29, 3, 0, 0).unwrap()));
+        assert_eq!(retention_cutoff(now, 12), Some(Utc.with_ymd_and_hms(2023, 3, 31, 3, 0, 0).unwrap()));
+        assert_eq!(retention_cutoff(now, -1), None);
+
+        assert!(is_valid_retention_months(1));
+        assert!(is_valid_retention_months(MAX_RETENTION_MONTHS));
+        assert!(!is_valid_retention_months(0));
+        assert!(!is_valid_retention_months(MAX_RETENTION_MONTHS + 1));
+    }
+}
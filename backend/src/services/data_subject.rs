@@ -0,0 +1,373 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::compliance::SuppressionReason;
+
+/// Everything a workspace holds about one email address, as stored
+#[derive(Debug, Serialize)]
+pub struct DataExport {
+    pub email: String,
+    pub generated_at: DateTime<Utc>,
+    pub leads: Vec<serde_json::Value>,
+    /// The address's enrolment in each campaign, with the campaign's name
+    pub campaign_history: Vec<serde_json::Value>,
+    pub sent_emails: Vec<serde_json::Value>,
+    pub email_events: Vec<serde_json::Value>,
+    pub bounces: Vec<serde_json::Value>,
+    pub replies: Vec<serde_json::Value>,
+    pub meetings: Vec<serde_json::Value>,
+    pub suppression: Vec<serde_json::Value>,
+}
+
+/// Audit record of one erasure
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ErasureRecord {
+    pub id: Uuid,
+    pub email: String,
+    pub requested_by: Option<Uuid>,
+    pub leads_deleted: i32,
+    pub sent_emails_deleted: i32,
+    pub events_deleted: i32,
+    pub replies_anonymized: i32,
+    pub erased_at: DateTime<Utc>,
+}
+
+/// Addresses are matched case-insensitively throughout
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    (email.contains('@') && !email.starts_with('@') && !email.ends_with('@')).then_some(email)
+}
+
+const LEAD_IDS: &str = "SELECT id FROM leads WHERE workspace_id = $1 AND LOWER(email) = $2";
+
+async fn rows(pool: &PgPool, sql: &str, workspace_id: Uuid, email: &str) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    sqlx::query_scalar(sql).bind(workspace_id).bind(email).fetch_all(pool).await
+}
+
+/// Gathers the address's data from one workspace. `email` must already be normalized.
+pub async fn export(pool: &PgPool, workspace_id: Uuid, email: &str) -> Result<DataExport, sqlx::Error> {
+    Ok(DataExport {
+        email: email.to_string(),
+        generated_at: Utc::now(),
+        leads: rows(
+            pool,
+            "SELECT to_jsonb(l) FROM leads l WHERE l.workspace_id = $1 AND LOWER(l.email) = $2 ORDER BY l.created_at",
+            workspace_id,
+            email,
+        )
+        .await?,
+        campaign_history: rows(
+            pool,
+            &format!(
+                r#"
+                SELECT to_jsonb(cl) || jsonb_build_object('campaign_name', c.name)
+                FROM campaign_leads cl
+                JOIN campaigns c ON c.id = cl.campaign_id
+                WHERE cl.lead_id IN ({})
+                ORDER BY cl.created_at
+                "#,
+                LEAD_IDS
+            ),
+            workspace_id,
+            email,
+        )
+        .await?,
+        sent_emails: rows(
+            pool,
+            "SELECT to_jsonb(s) FROM sent_emails s WHERE s.workspace_id = $1 AND LOWER(s.to_email) = $2 ORDER BY s.sent_at",
+            workspace_id,
+            email,
+        )
+        .await?,
+        email_events: rows(
+            pool,
+            "SELECT to_jsonb(e) FROM email_events e WHERE e.workspace_id = $1 AND LOWER(e.recipient) = $2 ORDER BY e.occurred_at",
+            workspace_id,
+            email,
+        )
+        .await?,
+        bounces: rows(
+            pool,
+            "SELECT to_jsonb(b) FROM bounces b WHERE b.workspace_id = $1 AND LOWER(b.recipient) = $2 ORDER BY b.occurred_at",
+            workspace_id,
+            email,
+        )
+        .await?,
+        replies: rows(
+            pool,
+            &format!(
+                r#"
+                SELECT to_jsonb(r) FROM email_replies r
+                WHERE r.workspace_id = $1 AND (LOWER(r.from_email) = $2 OR r.lead_id IN ({}))
+                ORDER BY r.received_at
+                "#,
+                LEAD_IDS
+            ),
+            workspace_id,
+            email,
+        )
+        .await?,
+        meetings: rows(
+            pool,
+            &format!(
+                "SELECT to_jsonb(m) FROM meetings m WHERE m.workspace_id = $1 AND m.lead_id IN ({}) ORDER BY m.created_at",
+                LEAD_IDS
+            ),
+            workspace_id,
+            email,
+        )
+        .await?,
+        suppression: rows(
+            pool,
+            "SELECT to_jsonb(s) FROM suppression_list s WHERE s.workspace_id = $1 AND LOWER(s.email) = $2",
+            workspace_id,
+            email,
+        )
+        .await?,
+    })
+}
+
+/// Deletes the address's leads, campaign history, sent copies, events and bounces, and
+/// anonymizes its replies and meetings, all within one workspace. The address is
+/// then suppressed as `erased`, which also keeps it from being imported again,
+/// and the erasure is logged. `email` must already be normalized.
+pub async fn erase(pool: &PgPool, workspace_id: Uuid, email: &str, requested_by: Uuid) -> Result<ErasureRecord, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let lead_ids: Vec<Uuid> = sqlx::query_scalar(LEAD_IDS)
+        .bind(workspace_id)
+        .bind(email)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    // Intent and sentiment stay for reporting, as with retention purges
+    let replies_anonymized = sqlx::query(
+        r#"
+        UPDATE email_replies
+        SET from_email = 'anonymized',
+            from_name = NULL,
+            subject = NULL,
+            body_text = NULL,
+            body_html = NULL,
+            message_id = NULL,
+            in_reply_to = NULL,
+            lead_id = NULL,
+            anonymized_at = NOW()
+        WHERE workspace_id = $1 AND (LOWER(from_email) = $2 OR lead_id = ANY($3))
+        "#
+    )
+    .bind(workspace_id)
+    .bind(email)
+    .bind(&lead_ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query("UPDATE meetings SET lead_id = NULL, title = NULL, notes = NULL WHERE workspace_id = $1 AND lead_id = ANY($2)")
+        .bind(workspace_id)
+        .bind(&lead_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    let sent_emails_deleted = sqlx::query(
+        "DELETE FROM sent_emails WHERE workspace_id = $1 AND (LOWER(to_email) = $2 OR lead_id = ANY($3))"
+    )
+    .bind(workspace_id)
+    .bind(email)
+    .bind(&lead_ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let mut events_deleted = sqlx::query("DELETE FROM email_events WHERE workspace_id = $1 AND LOWER(recipient) = $2")
+        .bind(workspace_id)
+        .bind(email)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    events_deleted += sqlx::query("DELETE FROM bounces WHERE workspace_id = $1 AND LOWER(recipient) = $2")
+        .bind(workspace_id)
+        .bind(email)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // Campaign history and tags go with the lead
+    let leads_deleted = sqlx::query("DELETE FROM leads WHERE workspace_id = $1 AND id = ANY($2)")
+        .bind(workspace_id)
+        .bind(&lead_ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    sqlx::query(
+        r#"
+        INSERT INTO suppression_list (workspace_id, email, reason, source)
+        VALUES ($1, $2, $3, 'data_erasure')
+        ON CONFLICT (workspace_id, email) DO UPDATE SET
+            reason = EXCLUDED.reason,
+            source = EXCLUDED.source,
+            created_at = NOW()
+        "#
+    )
+    .bind(workspace_id)
+    .bind(email)
+    .bind(SuppressionReason::Erased.as_str())
+    .execute(&mut *tx)
+    .await?;
+
+    let record = sqlx::query_as::<_, ErasureRecord>(
+        r#"
+        INSERT INTO data_erasure_log (workspace_id, email, requested_by, leads_deleted, sent_emails_deleted, events_deleted, replies_anonymized)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, email, requested_by, leads_deleted, sent_emails_deleted, events_deleted, replies_anonymized, erased_at
+        "#
+    )
+    .bind(workspace_id)
+    .bind(email)
+    .bind(requested_by)
+    .bind(leads_deleted as i32)
+    .bind(sent_emails_deleted as i32)
+    .bind(events_deleted as i32)
+    .bind(replies_anonymized as i32)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use crate::services::lead_import::{self, ImportedLead};
+
+    #[test]
+    fn test_addresses_are_normalized_before_matching() {
+        assert_eq!(normalize_email("  Jane.Doe@Acme.io "), Some("jane.doe@acme.io".to_string()));
+        assert_eq!(normalize_email("jane"), None);
+        assert_eq!(normalize_email("@acme.io"), None);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_export_is_complete_and_erasure_leaves_a_tombstone() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Erasure").await;
+        let bystander = test_db::workspace(&pool, "Bystander").await;
+        let (user_id, _) = test_db::user(&pool, "hash").await;
+
+        let email = format!("subject-{}@example.com", Uuid::new_v4().simple());
+        let mut lead_ids = Vec::new();
+        for ws in [workspace_id, bystander] {
+            let (lead_id, campaign_id, campaign_lead_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+            sqlx::query("INSERT INTO leads (id, workspace_id, email, first_name) VALUES ($1, $2, $3, 'Jane')")
+                .bind(lead_id)
+                .bind(ws)
+                .bind(email.to_uppercase())
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO campaigns (id, name, vertical, status, workspace_id) VALUES ($1, 'Outreach', 'saas', 'active', $2)")
+                .bind(campaign_id)
+                .bind(ws)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO campaign_leads (id, campaign_id, lead_id, status) VALUES ($1, $2, $3, 'sent')")
+                .bind(campaign_lead_id)
+                .bind(campaign_id)
+                .bind(lead_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                r#"
+                INSERT INTO sent_emails (workspace_id, campaign_id, campaign_lead_id, lead_id, from_email, to_email, subject, body_html, body_text, message_id)
+                VALUES ($1, $2, $3, $4, 'sender@acme.io', $5, 'Hi', '<p>Hi</p>', 'Hi', $6)
+                "#
+            )
+            .bind(ws)
+            .bind(campaign_id)
+            .bind(campaign_lead_id)
+            .bind(lead_id)
+            .bind(&email)
+            .bind(format!("<{}@acme.io>", Uuid::new_v4()))
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO email_events (workspace_id, campaign_lead_id, provider, event_type, recipient, occurred_at) VALUES ($1, $2, 'sendgrid', 'opened', $3, NOW())",
+            )
+            .bind(ws)
+            .bind(campaign_lead_id)
+            .bind(&email)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO email_replies (workspace_id, campaign_id, lead_id, from_email, body_text) VALUES ($1, $2, $3, $4, 'Tell me more')")
+                .bind(ws)
+                .bind(campaign_id)
+                .bind(lead_id)
+                .bind(&email)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO suppression_list (workspace_id, email, reason, source) VALUES ($1, $2, 'unsubscribed', 'link')")
+                .bind(ws)
+                .bind(&email)
+                .execute(&pool)
+                .await
+                .unwrap();
+            lead_ids.push(lead_id);
+        }
+
+        let bundle = export(&pool, workspace_id, &email).await.unwrap();
+        assert_eq!(bundle.leads.len(), 1);
+        assert_eq!(bundle.leads[0]["id"], lead_ids[0].to_string());
+        assert_eq!(bundle.leads[0]["first_name"], "Jane");
+        assert_eq!(bundle.campaign_history.len(), 1);
+        assert_eq!(bundle.campaign_history[0]["campaign_name"], "Outreach");
+        assert_eq!(bundle.sent_emails.len(), 1);
+        assert_eq!(bundle.email_events.len(), 1);
+        assert_eq!(bundle.replies.len(), 1);
+        assert_eq!(bundle.replies[0]["body_text"], "Tell me more");
+        assert_eq!(bundle.suppression.len(), 1);
+
+        let record = erase(&pool, workspace_id, &email, user_id).await.unwrap();
+        assert_eq!(
+            (record.leads_deleted, record.sent_emails_deleted, record.events_deleted, record.replies_anonymized),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(record.requested_by, Some(user_id));
+
+        let after = export(&pool, workspace_id, &email).await.unwrap();
+        assert!(after.leads.is_empty() && after.campaign_history.is_empty() && after.sent_emails.is_empty());
+        assert!(after.email_events.is_empty() && after.replies.is_empty());
+        assert_eq!(after.suppression.len(), 1);
+        assert_eq!(after.suppression[0]["reason"], "erased");
+
+        // The tombstone keeps the address from being imported again
+        let reimport = ImportedLead {
+            line: 1,
+            email: email.clone(),
+            first_name: Some("Jane".to_string()),
+            last_name: None,
+            company: None,
+            title: None,
+            linkedin_url: None,
+            custom_fields: Default::default(),
+        };
+        assert_eq!(lead_import::insert_leads(&pool, workspace_id, &[reimport]).await.unwrap(), 0);
+
+        // Another workspace's data is untouched
+        let other = export(&pool, bystander, &email).await.unwrap();
+        assert_eq!((other.leads.len(), other.sent_emails.len(), other.replies.len()), (1, 1, 1));
+        assert_eq!(other.suppression[0]["reason"], "unsubscribed");
+    }
+}
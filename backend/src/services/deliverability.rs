@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::sync::LazyLock;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EmailAccountHealth {
@@ -94,7 +96,7 @@ impl DeliverabilityService {
         // Reward for replies
         score += reply_rate * 50.0; // 10% reply = +5 points
         
-        score.max(0.0).min(100.0)
+        score.clamp(0.0, 100.0)
     }
 
     /// Generate deliverability report
@@ -173,31 +175,171 @@ impl DeliverabilityService {
         
         recommendations
     }
+}
 
-    /// Check SPF, DKIM, DMARC records (mock implementation)
-    pub async fn check_domain_authentication(&self, domain: &str) -> DomainAuthStatus {
-        // In production: Actually check DNS records
-        DomainAuthStatus {
-            domain: domain.to_string(),
-            spf_valid: true,
-            dkim_valid: true,
-            dmarc_valid: true,
-            recommendations: vec![],
+impl Default for DeliverabilityService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Phrases spam filters weigh heavily in cold email
+const SPAM_PHRASES: &[&str] = &[
+    "100% free", "act now", "apply now", "as seen on", "buy now", "call now", "cash bonus",
+    "click here", "click now", "congratulations", "dear friend", "double your", "earn money",
+    "exclusive deal", "free gift", "free trial", "guaranteed", "incredible deal", "limited time",
+    "lowest price", "make money", "miracle", "no credit check", "no obligation", "once in a lifetime",
+    "order now", "risk-free", "risk free", "special promotion", "this is not spam", "urgent",
+    "winner", "you have been selected", "free", "$$$",
+];
+
+const URL_SHORTENERS: &[&str] = &["bit.ly/", "tinyurl.com/", "goo.gl/", "t.co/", "ow.ly/", "is.gd/", "buff.ly/"];
+
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static LINK_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)<a\s[^>]*href\s*=\s*["']?([^"'\s>]+)"#).unwrap());
+static IMAGE_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<img\b").unwrap());
+
+/// One heuristic that added to the spam score
+#[derive(Debug, Clone, Serialize)]
+pub struct SpamFinding {
+    pub rule: String,
+    pub points: u32,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpamCheckResult {
+    /// 0 (clean) to 100 (almost certainly filtered)
+    pub score: u32,
+    /// `low` below 30, `medium` below 60, otherwise `high`
+    pub risk: String,
+    pub findings: Vec<SpamFinding>,
+}
+
+/// Scores an email's content against common spam-filter heuristics: trigger
+/// phrases, shouting, link and image density, and missing plain-text or
+/// unsubscribe parts. It's a lint for authors, not a prediction of any one filter.
+pub fn spam_check(subject: &str, body_html: &str, body_text: Option<&str>) -> SpamCheckResult {
+    let mut findings = Vec::new();
+    let mut add = |rule: &str, points: u32, detail: String| {
+        findings.push(SpamFinding { rule: rule.to_string(), points, detail });
+    };
+
+    let visible_text = HTML_TAG.replace_all(body_html, " ").replace("&nbsp;", " ");
+    let body_lower = visible_text.to_lowercase();
+    let subject_lower = subject.to_lowercase();
+
+    // Trigger phrases count more in the subject, which filters weigh most
+    let mut phrase_points = 0;
+    let mut phrases = Vec::new();
+    for phrase in SPAM_PHRASES {
+        let in_subject = contains_phrase(&subject_lower, phrase);
+        let in_body = contains_phrase(&body_lower, phrase);
+        if in_subject || in_body {
+            phrase_points += if in_subject { 12 } else { 6 };
+            phrases.push(format!("\"{}\"", phrase));
         }
     }
+    if !phrases.is_empty() {
+        add("spam_phrases", phrase_points.min(40), format!("Contains trigger phrases: {}", phrases.join(", ")));
+    }
+
+    let shouted = |text: &str| {
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|w| w.chars().count() >= 3)
+            .collect();
+        let caps = words.iter().filter(|w| w.chars().all(|c| c.is_uppercase())).count();
+        (caps, words.len())
+    };
+    let (subject_caps, subject_words) = shouted(subject);
+    if subject_caps >= 2 || (subject_words > 0 && subject_caps == subject_words) {
+        add("all_caps_subject", 15, format!("{} word(s) in the subject are in capitals", subject_caps));
+    }
+    let (body_caps, body_words) = shouted(&visible_text);
+    if body_words >= 5 && body_caps * 5 > body_words {
+        add("all_caps_body", 10, format!("{} of {} words in the body are in capitals", body_caps, body_words));
+    }
+
+    let exclamations = subject.matches('!').count() + visible_text.matches('!').count();
+    if subject.contains("!!") || visible_text.contains("!!") || exclamations > 3 {
+        add("exclamation_marks", 10, format!("{} exclamation marks; repeated ones read as shouting", exclamations));
+    }
+
+    let links: Vec<&str> = LINK_TAG.captures_iter(body_html).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect();
+    let content_links = links.iter().filter(|l| !l.to_lowercase().contains("unsubscribe")).count();
+    if content_links > 3 {
+        add("too_many_links", if content_links > 6 { 20 } else { 10 }, format!("{} links; cold emails do best with one or two", content_links));
+    }
+    if links.iter().any(|l| URL_SHORTENERS.iter().any(|s| l.to_lowercase().contains(s))) {
+        add("url_shortener", 10, "Link shorteners hide the destination and are widely blocked".to_string());
+    }
+
+    let images = IMAGE_TAG.find_iter(body_html).count();
+    let words = visible_text.split_whitespace().count();
+    if images > 0 && words < 20 {
+        add("image_only", 25, format!("{} image(s) with almost no text", images));
+    } else if images > 0 && words < images * 50 {
+        add("image_to_text_ratio", 10, format!("{} image(s) for {} words of text", images, words));
+    }
+
+    if body_text.is_none_or(|t| t.trim().is_empty()) {
+        add("missing_text_part", 5, "No plain-text version; one is generated from the HTML, which often reads poorly".to_string());
+    }
+
+    if !body_html.to_lowercase().contains("unsubscribe") && !body_text.is_some_and(|t| t.to_lowercase().contains("unsubscribe")) {
+        add("missing_unsubscribe", 10, "No unsubscribe link; add {{unsubscribe_url}}".to_string());
+    }
+
+    let score = findings.iter().map(|f| f.points).sum::<u32>().min(100);
+    let risk = match score {
+        0..=29 => "low",
+        30..=59 => "medium",
+        _ => "high",
+    };
+
+    SpamCheckResult { score, risk: risk.to_string(), findings }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DomainAuthStatus {
-    pub domain: String,
-    pub spf_valid: bool,
-    pub dkim_valid: bool,
-    pub dmarc_valid: bool,
-    pub recommendations: Vec<String>,
+/// Whole-word match, so "free" doesn't hit "freelance"
+fn contains_phrase(haystack: &str, phrase: &str) -> bool {
+    haystack.match_indices(phrase).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
 }
 
-impl Default for DeliverabilityService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_email_scores_low() {
+        let result = spam_check(
+            "Quick question about Acme's hiring",
+            "<p>Hi Sam,</p><p>Saw you're hiring three backend engineers after the Series A. \
+             We help teams like yours shortlist candidates in a week. Worth a short call next Tuesday?</p>\
+             <p>Best,<br>Alex</p><p><a href=\"{{unsubscribe_url}}\">Unsubscribe</a></p>",
+            Some("Hi Sam, saw you're hiring... Unsubscribe: {{unsubscribe_url}}"),
+        );
+        assert!(result.score < 30, "{:?}", result);
+        assert_eq!(result.risk, "low");
+    }
+
+    #[test]
+    fn test_shouting_promotional_email_scores_high() {
+        let result = spam_check(
+            "FREE!!! CLICK NOW",
+            "<h1>FREE GIFT for YOU!!!</h1><p>Act now, limited time. CLICK HERE to claim your cash bonus.</p>\
+             <a href=\"https://bit.ly/x\"><img src=\"https://acme.io/banner.png\"></a>",
+            None,
+        );
+        assert!(result.score >= 60, "{:?}", result);
+        assert_eq!(result.risk, "high");
+        let rules: Vec<&str> = result.findings.iter().map(|f| f.rule.as_str()).collect();
+        for rule in ["spam_phrases", "all_caps_subject", "exclamation_marks", "missing_unsubscribe", "url_shortener"] {
+            assert!(rules.contains(&rule), "missing {}", rule);
+        }
     }
 }
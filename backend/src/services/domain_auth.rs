@@ -0,0 +1,424 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::TokioAsyncResolver;
+
+use crate::config::DkimKey;
+
+/// Reports are reused for this long before a domain's records are looked up again
+pub const DOMAIN_AUTH_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Selectors used by the common mail providers, tried when none is configured
+const COMMON_DKIM_SELECTORS: &[&str] = &[
+    "google", "selector1", "selector2", "default", "dkim", "mail", "k1", "s1", "s2", "smtp",
+];
+
+/// SPF evaluation fails (permerror) past this many DNS-querying mechanisms
+const SPF_MAX_LOOKUPS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub enum TxtLookup {
+    /// Each record's strings joined, as published
+    Records(Vec<String>),
+    NotFound,
+    /// DNS couldn't answer (timeout, SERVFAIL); says nothing about the record
+    Failed(String),
+}
+
+/// TXT lookups. CNAMEs are followed, which is how hosted DKIM keys are usually published.
+pub trait TxtResolver: Send + Sync {
+    fn lookup_txt<'a>(&'a self, name: &'a str) -> BoxFuture<'a, TxtLookup>;
+}
+
+pub struct DnsTxtResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl DnsTxtResolver {
+    pub fn new() -> Self {
+        Self {
+            resolver: TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()),
+        }
+    }
+}
+
+impl Default for DnsTxtResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxtResolver for DnsTxtResolver {
+    fn lookup_txt<'a>(&'a self, name: &'a str) -> BoxFuture<'a, TxtLookup> {
+        Box::pin(async move {
+            match self.resolver.txt_lookup(name).await {
+                Ok(records) => TxtLookup::Records(
+                    records
+                        .iter()
+                        .map(|record| record.txt_data().iter().map(|part| String::from_utf8_lossy(part)).collect())
+                        .collect(),
+                ),
+                Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => TxtLookup::NotFound,
+                Err(e) => TxtLookup::Failed(e.to_string()),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// The lookup itself failed; try again later
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MechanismCheck {
+    pub status: CheckStatus,
+    /// The record the verdict is based on, if one was found
+    pub record: Option<String>,
+    pub detail: String,
+    /// What to publish or change, when the check didn't pass
+    pub remediation: Option<String>,
+}
+
+impl MechanismCheck {
+    fn new(status: CheckStatus, record: Option<String>, detail: impl Into<String>, remediation: Option<String>) -> Self {
+        Self { status, record, detail: detail.into(), remediation }
+    }
+
+    fn unknown(name: &str, error: String) -> Self {
+        Self::new(CheckStatus::Unknown, None, format!("Couldn't look up {}: {}", name, error), None)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainAuthReport {
+    pub domain: String,
+    pub spf: MechanismCheck,
+    pub dkim: MechanismCheck,
+    pub dmarc: MechanismCheck,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl DomainAuthReport {
+    /// The worst of the three verdicts
+    pub fn overall(&self) -> CheckStatus {
+        let statuses = [self.spf.status, self.dkim.status, self.dmarc.status];
+        [CheckStatus::Fail, CheckStatus::Unknown, CheckStatus::Warn]
+            .into_iter()
+            .find(|status| statuses.contains(status))
+            .unwrap_or(CheckStatus::Pass)
+    }
+}
+
+/// Looks up and grades the domain's SPF, DKIM and DMARC records
+pub async fn check_domain_auth(resolver: &dyn TxtResolver, domain: &str) -> DomainAuthReport {
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+
+    DomainAuthReport {
+        spf: check_spf(resolver, &domain).await,
+        dkim: check_dkim(resolver, &domain).await,
+        dmarc: check_dmarc(resolver, &domain).await,
+        checked_at: Utc::now(),
+        domain,
+    }
+}
+
+fn records_with_prefix(records: Vec<String>, prefix: &str) -> Vec<String> {
+    records
+        .into_iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| r.to_lowercase().starts_with(prefix))
+        .collect()
+}
+
+async fn check_spf(resolver: &dyn TxtResolver, domain: &str) -> MechanismCheck {
+    let records = match resolver.lookup_txt(domain).await {
+        TxtLookup::Records(records) => records_with_prefix(records, "v=spf1"),
+        TxtLookup::NotFound => Vec::new(),
+        TxtLookup::Failed(e) => return MechanismCheck::unknown("SPF", e),
+    };
+
+    let publish_hint = Some(format!(
+        "Publish a TXT record on {} such as \"v=spf1 include:<your provider's SPF domain> ~all\"",
+        domain
+    ));
+
+    let record = match records.as_slice() {
+        [] => return MechanismCheck::new(CheckStatus::Fail, None, "No SPF record found", publish_hint),
+        [record] => record.clone(),
+        _ => {
+            return MechanismCheck::new(
+                CheckStatus::Fail,
+                Some(records.join(" | ")),
+                "Multiple SPF records found; receivers treat this as an error",
+                Some("Merge them into a single v=spf1 record".to_string()),
+            )
+        }
+    };
+
+    let terms: Vec<String> = record.split_whitespace().skip(1).map(str::to_lowercase).collect();
+    let lookups = terms
+        .iter()
+        .filter(|term| {
+            let mechanism = term.trim_start_matches(['+', '-', '~', '?']);
+            ["include:", "a", "a:", "a/", "mx", "mx:", "mx/", "ptr", "exists:", "redirect="]
+                .iter()
+                .any(|m| if m.ends_with([':', '/', '=']) { mechanism.starts_with(m) } else { mechanism == *m })
+        })
+        .count();
+    let all = terms.iter().find(|term| term.trim_start_matches(['+', '-', '~', '?']) == "all");
+
+    let (status, detail, remediation) = match all.map(String::as_str) {
+        Some("all" | "+all") => (
+            CheckStatus::Fail,
+            "SPF ends in +all, which authorises every server on the internet".to_string(),
+            Some("Replace +all with ~all or -all".to_string()),
+        ),
+        Some("?all") => (
+            CheckStatus::Warn,
+            "SPF ends in ?all, which asks receivers to ignore failures".to_string(),
+            Some("Replace ?all with ~all or -all".to_string()),
+        ),
+        None if !terms.iter().any(|t| t.starts_with("redirect=")) => (
+            CheckStatus::Warn,
+            "SPF has no all mechanism, so unlisted servers aren't flagged".to_string(),
+            Some("End the record with ~all or -all".to_string()),
+        ),
+        _ if lookups > SPF_MAX_LOOKUPS => (
+            CheckStatus::Warn,
+            format!("SPF uses {} DNS lookups; more than {} makes it fail at receivers", lookups, SPF_MAX_LOOKUPS),
+            Some("Remove unused includes or flatten them into ip4/ip6 ranges".to_string()),
+        ),
+        _ => (CheckStatus::Pass, "SPF record found with a restrictive policy".to_string(), None),
+    };
+
+    MechanismCheck::new(status, Some(record), detail, remediation)
+}
+
+async fn check_dkim(resolver: &dyn TxtResolver, domain: &str) -> MechanismCheck {
+    let configured = DkimKey::for_domain(domain).map(|key| key.selector);
+    let selectors: Vec<&str> = match &configured {
+        Some(selector) => vec![selector.as_str()],
+        None => COMMON_DKIM_SELECTORS.to_vec(),
+    };
+
+    let mut lookup_error = None;
+    for selector in &selectors {
+        let name = format!("{}._domainkey.{}", selector, domain);
+        let records = match resolver.lookup_txt(&name).await {
+            TxtLookup::Records(records) => records,
+            TxtLookup::NotFound => continue,
+            TxtLookup::Failed(e) => {
+                lookup_error = Some(e);
+                continue;
+            }
+        };
+
+        let Some(record) = records.into_iter().map(|r| r.trim().to_string()).find(|r| {
+            let lower = r.to_lowercase();
+            lower.starts_with("v=dkim1") || lower.contains("p=")
+        }) else {
+            continue;
+        };
+
+        let revoked = record
+            .split(';')
+            .map(str::trim)
+            .any(|tag| tag.eq_ignore_ascii_case("p=") || tag.eq_ignore_ascii_case("p"));
+        return if revoked {
+            MechanismCheck::new(
+                CheckStatus::Fail,
+                Some(record),
+                format!("DKIM key at selector \"{}\" has an empty p=, meaning it's revoked", selector),
+                Some("Publish the current public key from your mail provider".to_string()),
+            )
+        } else {
+            MechanismCheck::new(CheckStatus::Pass, Some(record), format!("DKIM key found at selector \"{}\"", selector), None)
+        };
+    }
+
+    if let Some(e) = lookup_error {
+        return MechanismCheck::unknown("DKIM", e);
+    }
+
+    match configured {
+        Some(selector) => MechanismCheck::new(
+            CheckStatus::Fail,
+            None,
+            format!("No DKIM key published at the configured selector \"{}\"", selector),
+            Some(format!("Publish the public key as a TXT record on {}._domainkey.{}", selector, domain)),
+        ),
+        // The selector could be anything, so not finding one is only a warning
+        None => MechanismCheck::new(
+            CheckStatus::Warn,
+            None,
+            "No DKIM key found at the usual selectors",
+            Some("Enable DKIM signing with your mail provider and publish the key it gives you".to_string()),
+        ),
+    }
+}
+
+async fn check_dmarc(resolver: &dyn TxtResolver, domain: &str) -> MechanismCheck {
+    let name = format!("_dmarc.{}", domain);
+    let records = match resolver.lookup_txt(&name).await {
+        TxtLookup::Records(records) => records_with_prefix(records, "v=dmarc1"),
+        TxtLookup::NotFound => Vec::new(),
+        TxtLookup::Failed(e) => return MechanismCheck::unknown("DMARC", e),
+    };
+
+    let Some(record) = records.into_iter().next() else {
+        return MechanismCheck::new(
+            CheckStatus::Fail,
+            None,
+            "No DMARC record found",
+            Some(format!(
+                "Publish a TXT record on {} such as \"v=DMARC1; p=none; rua=mailto:dmarc@{}\", then tighten to p=quarantine",
+                name, domain
+            )),
+        );
+    };
+
+    let tags: HashMap<String, String> = record
+        .split(';')
+        .filter_map(|tag| tag.split_once('='))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_lowercase()))
+        .collect();
+    let pct = tags.get("pct").and_then(|p| p.parse::<u8>().ok()).unwrap_or(100);
+
+    let (status, detail, remediation) = match tags.get("p").map(String::as_str) {
+        Some("reject") | Some("quarantine") if pct < 100 => (
+            CheckStatus::Warn,
+            format!("DMARC policy only applies to {}% of mail", pct),
+            Some("Raise pct to 100 once reports look clean".to_string()),
+        ),
+        Some("reject") => (CheckStatus::Pass, "DMARC policy rejects unauthenticated mail".to_string(), None),
+        Some("quarantine") => (CheckStatus::Pass, "DMARC policy quarantines unauthenticated mail".to_string(), None),
+        Some("none") => (
+            CheckStatus::Warn,
+            "DMARC is in monitoring mode (p=none) and doesn't protect the domain".to_string(),
+            Some("Move to p=quarantine once reports show your mail passing".to_string()),
+        ),
+        _ => (
+            CheckStatus::Fail,
+            "DMARC record has no valid p= policy".to_string(),
+            Some("Add p=none, p=quarantine or p=reject".to_string()),
+        ),
+    };
+
+    MechanismCheck::new(status, Some(record), detail, remediation)
+}
+
+/// Per-process cache of reports by domain
+pub struct DomainAuthCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, DomainAuthReport)>>,
+}
+
+impl DomainAuthCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// The cached report for the domain, or a fresh check when there is none or it's stale
+    pub async fn check(&self, resolver: &dyn TxtResolver, domain: &str) -> DomainAuthReport {
+        let key = domain.trim().trim_end_matches('.').to_lowercase();
+        if let Some(report) = self.get(&key) {
+            return report;
+        }
+
+        let report = check_domain_auth(resolver, &key).await;
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (at, _)| at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), report.clone()));
+        report
+    }
+
+    fn get(&self, key: &str) -> Option<DomainAuthReport> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, report)| report.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FakeResolver {
+        records: HashMap<&'static str, Vec<&'static str>>,
+        lookups: AtomicUsize,
+    }
+
+    impl FakeResolver {
+        fn new(records: &[(&'static str, &'static str)]) -> Self {
+            let mut map: HashMap<&'static str, Vec<&'static str>> = HashMap::new();
+            for (name, record) in records {
+                map.entry(name).or_default().push(record);
+            }
+            Self { records: map, lookups: AtomicUsize::new(0) }
+        }
+    }
+
+    impl TxtResolver for FakeResolver {
+        fn lookup_txt<'a>(&'a self, name: &'a str) -> BoxFuture<'a, TxtLookup> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            let result = match self.records.get(name) {
+                Some(records) => TxtLookup::Records(records.iter().map(|r| r.to_string()).collect()),
+                None => TxtLookup::NotFound,
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fully_configured_domain_passes() {
+        let resolver = FakeResolver::new(&[
+            ("acme.io", "google-site-verification=abc"),
+            ("acme.io", "v=spf1 include:_spf.google.com ~all"),
+            ("google._domainkey.acme.io", "v=DKIM1; k=rsa; p=MIIBIjANBgkqh"),
+            ("_dmarc.acme.io", "v=DMARC1; p=reject; rua=mailto:dmarc@acme.io"),
+        ]);
+
+        let report = check_domain_auth(&resolver, "Acme.io").await;
+        assert_eq!(report.spf.status, CheckStatus::Pass);
+        assert_eq!(report.spf.record.as_deref(), Some("v=spf1 include:_spf.google.com ~all"));
+        assert_eq!(report.dkim.status, CheckStatus::Pass);
+        assert_eq!(report.dmarc.status, CheckStatus::Pass);
+        assert_eq!(report.overall(), CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_missing_dmarc_fails_with_remediation() {
+        let resolver = FakeResolver::new(&[
+            ("acme.io", "v=spf1 include:sendgrid.net ?all"),
+            ("s1._domainkey.acme.io", "k=rsa; p=MIGfMA0GCSqGSIb3"),
+        ]);
+
+        let report = check_domain_auth(&resolver, "acme.io").await;
+        assert_eq!(report.spf.status, CheckStatus::Warn);
+        assert_eq!(report.dkim.status, CheckStatus::Pass);
+        assert_eq!(report.dmarc.status, CheckStatus::Fail);
+        assert!(report.dmarc.remediation.as_deref().unwrap().contains("_dmarc.acme.io"));
+        assert_eq!(report.overall(), CheckStatus::Fail);
+
+        // Within the TTL the cache answers without touching DNS again
+        let cache = DomainAuthCache::new(DOMAIN_AUTH_CACHE_TTL);
+        cache.check(&resolver, "acme.io").await;
+        let lookups = resolver.lookups.load(Ordering::SeqCst);
+        cache.check(&resolver, "ACME.io").await;
+        assert_eq!(resolver.lookups.load(Ordering::SeqCst), lookups);
+    }
+}
@@ -0,0 +1,311 @@
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine as _};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::services::jwt_keys::jwt_keys;
+
+/// How long a user has to finish the provider's consent screen
+pub const OAUTH_STATE_TTL_SECS: i64 = 10 * 60;
+
+/// Access tokens are refreshed this long before they expire, so one is never
+/// handed to an SMTP session that outlives it
+const ACCESS_TOKEN_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Live access tokens by inbox id, so sends don't hit the token endpoint each time
+static ACCESS_TOKENS: LazyLock<Mutex<HashMap<Uuid, (String, Instant)>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Mail providers that can connect inboxes over OAuth2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthProvider {
+    Google,
+    Microsoft,
+}
+
+impl OAuthProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+            OAuthProvider::Microsoft => "microsoft",
+        }
+    }
+
+    /// Accepts the URL segment or the `provider` stored on the inbox
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "google" | "gmail" => Some(OAuthProvider::Google),
+            "microsoft" | "outlook" => Some(OAuthProvider::Microsoft),
+            _ => None,
+        }
+    }
+
+    /// Value stored in `email_accounts.provider`, matching `detect_email_provider`
+    pub fn account_provider(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+            OAuthProvider::Microsoft => "outlook",
+        }
+    }
+
+    pub fn smtp_host(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "smtp.gmail.com",
+            OAuthProvider::Microsoft => "smtp.office365.com",
+        }
+    }
+
+    fn authorize_endpoint(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://accounts.google.com/o/oauth2/v2/auth",
+            OAuthProvider::Microsoft => "https://login.microsoftonline.com/common/oauth2/v2.0/authorize",
+        }
+    }
+
+    fn token_endpoint(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://oauth2.googleapis.com/token",
+            OAuthProvider::Microsoft => "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+        }
+    }
+
+    /// SMTP and IMAP access, plus the identity claims that tell us the address
+    fn scopes(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://mail.google.com/ openid email",
+            OAuthProvider::Microsoft => {
+                "https://outlook.office.com/SMTP.Send https://outlook.office.com/IMAP.AccessAsUser.All offline_access openid email"
+            }
+        }
+    }
+
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "GOOGLE",
+            OAuthProvider::Microsoft => "MICROSOFT",
+        }
+    }
+}
+
+/// App credentials from `GOOGLE_OAUTH_CLIENT_ID`/`GOOGLE_OAUTH_CLIENT_SECRET` or the
+/// `MICROSOFT_` equivalents. Unset means the provider isn't offered.
+struct OAuthClient {
+    client_id: String,
+    client_secret: String,
+}
+
+impl OAuthClient {
+    fn for_provider(provider: OAuthProvider) -> Result<Self, String> {
+        let var = |name: &str| {
+            std::env::var(format!("{}_OAUTH_{}", provider.env_prefix(), name))
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+        };
+        match (var("CLIENT_ID"), var("CLIENT_SECRET")) {
+            (Some(client_id), Some(client_secret)) => Ok(Self { client_id, client_secret }),
+            _ => Err(format!("{} OAuth is not configured", provider.as_str())),
+        }
+    }
+}
+
+/// Tokens from the code exchange
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+    pub id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// The frontend page the provider sends the user back to; it passes `code` and
+/// `state` on to the callback endpoint.
+pub fn redirect_uri(provider: OAuthProvider) -> String {
+    format!(
+        "{}/email-accounts/oauth/{}/callback",
+        std::env::var("APP_URL").unwrap_or_else(|_| "https://app.outreachiq.com".to_string()).trim_end_matches('/'),
+        provider.as_str()
+    )
+}
+
+fn state_mac(payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(jwt_keys().current_secret().as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Signed `state` tying the consent round trip to the workspace that started it
+pub fn sign_state(workspace_id: Uuid, provider: OAuthProvider, now: i64) -> String {
+    let payload = format!("{}:{}:{}", workspace_id, provider.as_str(), now + OAUTH_STATE_TTL_SECS);
+    let signature = hex::encode(state_mac(&payload).finalize().into_bytes());
+    format!("{}.{}", URL_SAFE_NO_PAD.encode(payload), signature)
+}
+
+pub fn verify_state(state: &str, workspace_id: Uuid, provider: OAuthProvider, now: i64) -> Result<(), &'static str> {
+    let (payload, signature) = state.split_once('.').ok_or("Invalid OAuth state")?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or("Invalid OAuth state")?;
+    let signature = hex::decode(signature).map_err(|_| "Invalid OAuth state")?;
+    state_mac(&payload).verify_slice(&signature).map_err(|_| "Invalid OAuth state")?;
+
+    let mut parts = payload.split(':');
+    let (Some(ws), Some(prov), Some(expires)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("Invalid OAuth state");
+    };
+    if ws != workspace_id.to_string() || prov != provider.as_str() {
+        return Err("OAuth state belongs to another workspace or provider");
+    }
+    if !expires.parse::<i64>().is_ok_and(|expires| expires >= now) {
+        return Err("OAuth state has expired; start again");
+    }
+
+    Ok(())
+}
+
+/// Consent screen URL. Offline access with a forced prompt makes the provider
+/// issue a refresh token even if the user connected before.
+pub fn authorization_url(provider: OAuthProvider, state: &str) -> Result<String, String> {
+    let client = OAuthClient::for_provider(provider)?;
+    let mut url = Url::parse(provider.authorize_endpoint()).map_err(|e| e.to_string())?;
+    url.query_pairs_mut()
+        .append_pair("client_id", &client.client_id)
+        .append_pair("redirect_uri", &redirect_uri(provider))
+        .append_pair("response_type", "code")
+        .append_pair("scope", provider.scopes())
+        .append_pair("state", state)
+        .append_pair("access_type", "offline")
+        .append_pair("prompt", "consent");
+    Ok(url.into())
+}
+
+async fn token_request(provider: OAuthProvider, params: &[(&str, &str)]) -> Result<TokenResponse, String> {
+    let client = OAuthClient::for_provider(provider)?;
+    let mut form = vec![("client_id", client.client_id.as_str()), ("client_secret", client.client_secret.as_str())];
+    form.extend_from_slice(params);
+
+    let response = reqwest::Client::new()
+        .post(provider.token_endpoint())
+        .form(&form)
+        .timeout(Duration::from_secs(20))
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(match response.json::<TokenError>().await {
+            Ok(e) => format!("{}: {}", e.error, e.error_description.unwrap_or_default()),
+            Err(_) => format!("Token endpoint returned {}", status),
+        });
+    }
+
+    response.json::<TokenResponse>().await.map_err(|e| format!("Invalid token response: {}", e))
+}
+
+pub async fn exchange_code(provider: OAuthProvider, code: &str) -> Result<TokenResponse, String> {
+    let redirect_uri = redirect_uri(provider);
+    token_request(
+        provider,
+        &[("grant_type", "authorization_code"), ("code", code), ("redirect_uri", &redirect_uri)],
+    )
+    .await
+}
+
+/// Mailbox address from the ID token. It came straight from the provider's token
+/// endpoint over TLS, so its claims are read without checking the signature.
+pub fn email_from_id_token(id_token: &str) -> Option<String> {
+    let claims = id_token.split('.').nth(1)?;
+    let claims: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims.trim_end_matches('=')).ok()?).ok()?;
+    ["email", "preferred_username", "upn"]
+        .iter()
+        .filter_map(|claim| claims.get(*claim).and_then(|v| v.as_str()))
+        .map(|email| email.trim().to_lowercase())
+        .find(|email| email.contains('@'))
+}
+
+/// SASL XOAUTH2 initial response used by both SMTP and IMAP
+pub fn xoauth2_response(username: &str, access_token: &str) -> String {
+    STANDARD.encode(format!("user={}\x01auth=Bearer {}\x01\x01", username, access_token))
+}
+
+/// A current access token for an OAuth inbox, from the cache or by redeeming the
+/// refresh token. A refresh failure means the grant was revoked or expired, so the
+/// inbox is paused with `auth_error` set until it's reconnected.
+pub async fn access_token(pool: &PgPool, inbox_id: Uuid, provider: &str, refresh_token: &str) -> Result<String, String> {
+    if let Some((token, expires)) = ACCESS_TOKENS.lock().unwrap_or_else(|e| e.into_inner()).get(&inbox_id) {
+        if *expires > Instant::now() {
+            return Ok(token.clone());
+        }
+    }
+
+    let provider = OAuthProvider::parse(provider).ok_or_else(|| format!("{} inboxes can't use OAuth", provider))?;
+    let tokens = match token_request(provider, &[("grant_type", "refresh_token"), ("refresh_token", refresh_token)]).await {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            let error = format!("OAuth token refresh failed: {}", e);
+            mark_auth_failed(pool, inbox_id, &error).await;
+            return Err(error);
+        }
+    };
+
+    let lifetime = Duration::from_secs(tokens.expires_in.unwrap_or(3600)).saturating_sub(ACCESS_TOKEN_MARGIN);
+    ACCESS_TOKENS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(inbox_id, (tokens.access_token.clone(), Instant::now() + lifetime));
+
+    Ok(tokens.access_token)
+}
+
+async fn mark_auth_failed(pool: &PgPool, inbox_id: Uuid, error: &str) {
+    ACCESS_TOKENS.lock().unwrap_or_else(|e| e.into_inner()).remove(&inbox_id);
+
+    let result = sqlx::query(
+        "UPDATE email_accounts SET auth_error = $2, auth_failed_at = NOW(), warmup_status = 'paused' WHERE id = $1"
+    )
+    .bind(inbox_id)
+    .bind(error)
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(_) => tracing::warn!("Paused inbox {}: {}", inbox_id, error),
+        Err(e) => tracing::error!("Failed to mark inbox {} unhealthy: {}", inbox_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_is_bound_to_workspace_provider_and_time() {
+        let workspace_id = Uuid::new_v4();
+        let state = sign_state(workspace_id, OAuthProvider::Google, 1_000);
+
+        assert!(verify_state(&state, workspace_id, OAuthProvider::Google, 1_000 + OAUTH_STATE_TTL_SECS).is_ok());
+        assert!(verify_state(&state, workspace_id, OAuthProvider::Google, 1_001 + OAUTH_STATE_TTL_SECS).is_err());
+        assert!(verify_state(&state, Uuid::new_v4(), OAuthProvider::Google, 1_000).is_err());
+        assert!(verify_state(&state, workspace_id, OAuthProvider::Microsoft, 1_000).is_err());
+
+        let (payload, _) = state.split_once('.').unwrap();
+        let forged = format!("{}.{}", payload, "00".repeat(32));
+        assert!(verify_state(&forged, workspace_id, OAuthProvider::Google, 1_000).is_err());
+
+        let claims = URL_SAFE_NO_PAD.encode(r#"{"email":"Founder@Acme.io","sub":"1"}"#);
+        assert_eq!(email_from_id_token(&format!("e30.{}.sig", claims)).as_deref(), Some("founder@acme.io"));
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// `campaigns.send_blocked_reason` while the workspace is out of monthly sends
+pub const LIMIT_REACHED_REASON: &str = "monthly_email_limit_reached";
+
+/// How often a blocked send checks back, so an upgrade takes effect without
+/// waiting for the next period
+const LIMIT_RETRY_MINUTES: i64 = 60;
+
+/// This period's count, treating a counter left over from an earlier period as zero
+const SENT_THIS_PERIOD: &str =
+    "CASE WHEN email_period_start = date_trunc('month', NOW())::date THEN emails_sent_this_period ELSE 0 END";
+
+/// Result of trying to reserve one send against the monthly limit
+#[derive(Debug, PartialEq, Eq)]
+pub enum EmailReservation {
+    Granted,
+    LimitReached { limit: i64, used: i64 },
+}
+
+/// Start of the billing period after the one `now` falls in (periods are calendar months, UTC)
+pub fn next_period_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 { (now.year() + 1, 1) } else { (now.year(), now.month() + 1) };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single().expect("first of the month is unambiguous in UTC")
+}
+
+/// When a send blocked by the limit should be tried again
+pub fn retry_at(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now + Duration::minutes(LIMIT_RETRY_MINUTES)).min(next_period_start(now))
+}
+
+/// Takes one send from the workspace's monthly allowance. The check and the
+/// increment are one statement on the workspace row, so parallel sends can't
+/// both take the last slot. Workspaces without a limit are always granted.
+pub async fn reserve_send(pool: &PgPool, workspace_id: Uuid) -> Result<EmailReservation, sqlx::Error> {
+    let reserved = sqlx::query(&format!(
+        r#"
+        UPDATE workspaces
+        SET emails_sent_this_period = {sent} + 1,
+            email_period_start = date_trunc('month', NOW())::date
+        WHERE id = $1
+        AND (monthly_email_limit IS NULL OR {sent} < monthly_email_limit)
+        "#,
+        sent = SENT_THIS_PERIOD
+    ))
+    .bind(workspace_id)
+    .execute(pool)
+    .await?;
+
+    if reserved.rows_affected() > 0 {
+        return Ok(EmailReservation::Granted);
+    }
+
+    let usage: Option<(Option<i32>, i32)> = sqlx::query_as(&format!(
+        "SELECT monthly_email_limit, {} FROM workspaces WHERE id = $1",
+        SENT_THIS_PERIOD
+    ))
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match usage {
+        Some((Some(limit), used)) => EmailReservation::LimitReached { limit: limit as i64, used: used as i64 },
+        _ => EmailReservation::Granted,
+    })
+}
+
+/// Gives back a reserved send that didn't go out
+pub async fn release_send(pool: &PgPool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE workspaces
+        SET emails_sent_this_period = GREATEST(emails_sent_this_period - 1, 0)
+        WHERE id = $1 AND email_period_start = date_trunc('month', NOW())::date
+        "#
+    )
+    .bind(workspace_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Sends counted against the limit so far this period
+pub async fn sent_this_period(pool: &PgPool, workspace_id: Uuid) -> Result<i64, sqlx::Error> {
+    let sent: Option<i32> = sqlx::query_scalar(&format!("SELECT {} FROM workspaces WHERE id = $1", SENT_THIS_PERIOD))
+        .bind(workspace_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(sent.unwrap_or(0) as i64)
+}
+
+/// Whether the workspace has used up this period's sends, so the scheduler
+/// can stop queueing jobs that would only be deferred
+pub async fn is_exhausted(pool: &PgPool, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+    let exhausted: Option<bool> = sqlx::query_scalar(&format!(
+        "SELECT monthly_email_limit IS NOT NULL AND {} >= monthly_email_limit FROM workspaces WHERE id = $1",
+        SENT_THIS_PERIOD
+    ))
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(exhausted.unwrap_or(false))
+}
+
+/// Records on the campaign that it's waiting on the monthly limit
+pub async fn mark_limit_reached(pool: &PgPool, campaign_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE campaigns SET send_blocked_reason = $2 WHERE id = $1 AND send_blocked_reason IS DISTINCT FROM $2"
+    )
+    .bind(campaign_id)
+    .bind(LIMIT_REACHED_REASON)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Recounts every workspace's counter from the archived sends. The counter is
+/// kept by `reserve_send`/`release_send`; this corrects any drift (e.g. a worker
+/// that died between reserving and sending) and rolls counters into a new period.
+pub async fn refresh_counters(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE workspaces w
+        SET emails_sent_this_period = counts.sent,
+            email_period_start = date_trunc('month', NOW())::date
+        FROM (
+            SELECT w2.id, COUNT(s.id)::int AS sent
+            FROM workspaces w2
+            LEFT JOIN sent_emails s ON s.workspace_id = w2.id AND s.sent_at >= date_trunc('month', NOW())
+            GROUP BY w2.id
+        ) counts
+        WHERE counts.id = w.id
+        AND (w.emails_sent_this_period <> counts.sent OR w.email_period_start <> date_trunc('month', NOW())::date)
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_blocked_sends_retry_within_the_hour_or_at_the_new_period() {
+        let mid_month = Utc.with_ymd_and_hms(2024, 3, 14, 9, 30, 0).unwrap();
+        assert_eq!(retry_at(mid_month), mid_month + Duration::minutes(60));
+
+        let new_years_eve = Utc.with_ymd_and_hms(2024, 12, 31, 23, 40, 0).unwrap();
+        assert_eq!(retry_at(new_years_eve), Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_third_send_is_blocked_at_a_limit_of_two() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Email quota").await;
+        sqlx::query("UPDATE workspaces SET monthly_email_limit = 2 WHERE id = $1")
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(reserve_send(&pool, workspace_id).await.unwrap(), EmailReservation::Granted);
+        assert!(!is_exhausted(&pool, workspace_id).await.unwrap());
+        assert_eq!(reserve_send(&pool, workspace_id).await.unwrap(), EmailReservation::Granted);
+        assert!(is_exhausted(&pool, workspace_id).await.unwrap());
+        assert_eq!(
+            reserve_send(&pool, workspace_id).await.unwrap(),
+            EmailReservation::LimitReached { limit: 2, used: 2 }
+        );
+
+        // A send that failed gives its slot back
+        release_send(&pool, workspace_id).await.unwrap();
+        assert_eq!(reserve_send(&pool, workspace_id).await.unwrap(), EmailReservation::Granted);
+
+        // No sends were archived, so the recount clears the counter
+        refresh_counters(&pool).await.unwrap();
+        assert!(!is_exhausted(&pool, workspace_id).await.unwrap());
+    }
+}
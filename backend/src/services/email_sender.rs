@@ -1,15 +1,41 @@
 use lettre::{
     transport::smtp::authentication::Credentials,
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
-    message::header::ContentType,
+    message::dkim::{DkimConfig, DkimSigningKey},
+    message::header::{ContentType, HeaderName, HeaderValue},
 };
 use serde::{Deserialize, Serialize};
 use handlebars::Handlebars;
+use chrono::{DateTime, Duration, Utc};
 use std::collections::HashMap;
+use std::fmt;
 use sqlx::PgPool;
 use uuid::Uuid;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
+use utoipa::ToSchema;
+use crate::config::DkimKey;
+use crate::services::email_oauth;
+use crate::services::job_queue::WarmupEmailPayload;
 use crate::services::encryption::EncryptionService;
+use crate::services::send_time::next_local_midnight;
+use crate::services::smtp_pool::{SmtpPool, SmtpSettings};
+use crate::services::bounces::{self, BounceSource, BounceType, NewBounce, MAX_SOFT_BOUNCES};
+use crate::services::email_quota;
+use crate::services::mailing_address::{self, CompanyAddress};
+use crate::services::tracking;
+use crate::services::unsubscribe_token;
+use crate::services::warmup_service;
+
+/// Unsubscribe token used in previews and test sends. It doesn't decode, so the
+/// links render like the real ones but can't opt out the lead being previewed.
+const PREVIEW_UNSUBSCRIBE_TOKEN: &str = "preview";
+
+/// Merge field every campaign template has to include
+pub const UNSUBSCRIBE_PLACEHOLDER: &str = "{{unsubscribe_url}}";
+
+/// Slack after an inbox's local midnight before a capped send is retried, so the
+/// worker's once-a-minute counter reset has run by then
+const COUNTER_RESET_GRACE_MINUTES: i64 = 5;
 
 #[derive(Debug, Clone)]
 pub struct EmailSender {
@@ -28,10 +54,14 @@ pub struct SendEmailJobPayload {
     pub lead_id: Uuid,
     pub inbox_id: Uuid,
     pub email: String,
+    /// Sequence step being sent; jobs queued before sequences existed are step 0
+    #[serde(default)]
+    pub step_index: i32,
 }
 
 pub struct CampaignEmailSender {
     pool: Arc<PgPool>,
+    smtp_pool: SmtpPool,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -44,13 +74,17 @@ struct InboxCredentials {
     smtp_password: Option<String>,
     smtp_password_encrypted: Option<Vec<u8>>,
     encryption_key_id: Option<String>,
+    provider: String,
+    /// `password`, or `oauth` when the encrypted password is a refresh token
+    auth_method: String,
 }
 
 #[derive(Debug, sqlx::FromRow)]
 struct CampaignDetails {
     id: Uuid,
-    name: String,
     workspace_id: Option<Uuid>,
+    from_name: Option<String>,
+    reply_to: Option<String>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -61,31 +95,495 @@ struct LeadDetails {
     last_name: Option<String>,
     company: Option<String>,
     title: Option<String>,
+    /// Extra merge fields, e.g. `{"industry": "Fintech"}`
+    custom_fields: serde_json::Value,
+}
+
+/// Why a campaign send didn't go out
+#[derive(Debug, PartialEq)]
+pub enum CampaignSendError {
+    /// The inbox is at its daily limit. Nothing was sent; retry at `retry_at`,
+    /// once the inbox's counter has reset.
+    RateLimited { retry_at: DateTime<Utc> },
+    /// The workspace has used its monthly email allowance. Nothing was sent;
+    /// check again at `retry_at`.
+    QuotaExceeded { retry_at: DateTime<Utc> },
+    /// The workspace hasn't set the postal address the footer needs. Nothing
+    /// was sent; check again at `retry_at`.
+    AddressMissing { retry_at: DateTime<Utc> },
+    /// The recipient's server turned the message away for now. Nothing was
+    /// sent; retry at `retry_at`.
+    SoftBounced { retry_at: DateTime<Utc> },
+    Failed(String),
+}
+
+impl fmt::Display for CampaignSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CampaignSendError::RateLimited { retry_at } => {
+                write!(f, "Inbox daily send limit reached, try again after {}", retry_at)
+            }
+            CampaignSendError::QuotaExceeded { retry_at } => {
+                write!(f, "Monthly email limit reached, try again after {}", retry_at)
+            }
+            CampaignSendError::AddressMissing { retry_at } => {
+                write!(f, "Workspace has no mailing address for the email footer, try again after {}", retry_at)
+            }
+            CampaignSendError::SoftBounced { retry_at } => {
+                write!(f, "Recipient's server deferred the email, try again after {}", retry_at)
+            }
+            CampaignSendError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for CampaignSendError {
+    fn from(message: String) -> Self {
+        CampaignSendError::Failed(message)
+    }
+}
+
+impl From<&str> for CampaignSendError {
+    fn from(message: &str) -> Self {
+        CampaignSendError::Failed(message.to_string())
+    }
+}
+
+/// Why a preview or test send couldn't be produced
+#[derive(Debug)]
+pub enum PreviewError {
+    NotFound(&'static str),
+    Failed(String),
+}
+
+impl From<sqlx::Error> for PreviewError {
+    fn from(e: sqlx::Error) -> Self {
+        PreviewError::Failed(format!("DB error: {}", e))
+    }
+}
+
+impl LeadDetails {
+    /// Stand-in recipient for previewing a campaign before any leads are added
+    fn sample() -> Self {
+        Self {
+            id: Uuid::nil(),
+            email: "jane.doe@example.com".to_string(),
+            first_name: Some("Jane".to_string()),
+            last_name: Some("Doe".to_string()),
+            company: Some("Example Inc".to_string()),
+            title: Some("Head of Growth".to_string()),
+            custom_fields: serde_json::json!({}),
+        }
+    }
 }
 
 impl CampaignEmailSender {
     pub fn new(pool: Arc<PgPool>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            smtp_pool: SmtpPool::new(),
+        }
     }
 
-    pub async fn send_campaign_email(&self, payload: &SendEmailJobPayload) -> Result<String, String> {
-        // Get inbox credentials
-        let inbox = sqlx::query_as::<_, InboxCredentials>(
+    /// Opens SMTP connections for the inboxes a campaign will send from, so its
+    /// first sends don't all queue behind a handshake. Returns how many warmed up;
+    /// inboxes that fail are logged and left to connect on their first send.
+    pub async fn warm_campaign(&self, campaign_id: Uuid) -> Result<usize, String> {
+        let inboxes = sqlx::query_as::<_, InboxCredentials>(
             r#"
-            SELECT id, email, smtp_host, smtp_port, smtp_username, smtp_password, 
-                   smtp_password_encrypted, encryption_key_id
-            FROM email_accounts WHERE id = $1
+            SELECT ea.id, ea.email, ea.smtp_host, ea.smtp_port, ea.smtp_username, ea.smtp_password,
+                   ea.smtp_password_encrypted, ea.encryption_key_id, ea.provider, ea.auth_method
+            FROM email_accounts ea
+            JOIN campaigns c ON c.workspace_id = ea.workspace_id
+            WHERE c.id = $1
+              AND ea.warmup_status IN ('active', 'warming')
+              AND ea.sent_today < ea.daily_limit
+              AND (
+                  NOT EXISTS (SELECT 1 FROM campaign_email_accounts cea WHERE cea.campaign_id = c.id)
+                  OR EXISTS (
+                      SELECT 1 FROM campaign_email_accounts cea
+                      WHERE cea.campaign_id = c.id AND cea.email_account_id = ea.id
+                  )
+              )
             "#
         )
-        .bind(payload.inbox_id)
+        .bind(campaign_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+        let mut warmed = 0;
+        for inbox in &inboxes {
+            let result = match self.smtp_settings(inbox).await {
+                Ok(settings) => self.smtp_pool.warm(inbox.id, &settings).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => warmed += 1,
+                Err(e) => tracing::warn!("Could not warm SMTP connection for {}: {}", inbox.email, e),
+            }
+        }
+
+        Ok(warmed)
+    }
+
+    /// Renders the campaign email for `lead_id`, or for a sample lead when none is
+    /// given, exactly as a real send would.
+    pub async fn preview(
+        &self,
+        workspace_id: Uuid,
+        campaign_id: Uuid,
+        lead_id: Option<Uuid>,
+    ) -> Result<EmailTemplate, PreviewError> {
+        let (campaign, lead) = self.load_preview_target(workspace_id, campaign_id, lead_id).await?;
+        let template = self.load_template(campaign.id, 0).await?;
+        let address = mailing_address::footer_address(self.pool.as_ref(), Some(workspace_id)).await?;
+        self.render(&lead, &campaign, &template, PREVIEW_UNSUBSCRIBE_TOKEN, address.as_ref())
+            .map_err(PreviewError::Failed)
+    }
+
+    /// Emails the rendered campaign to `to_email` through one of the workspace's
+    /// inboxes. Nothing is recorded: daily limits, campaign counters, lead status
+    /// and the sent archive are left untouched. Returns the inbox address used.
+    pub async fn send_test(
+        &self,
+        workspace_id: Uuid,
+        campaign_id: Uuid,
+        lead_id: Option<Uuid>,
+        inbox_id: Option<Uuid>,
+        to_email: &str,
+    ) -> Result<String, PreviewError> {
+        let (campaign, lead) = self.load_preview_target(workspace_id, campaign_id, lead_id).await?;
+
+        let inbox = self
+            .find_sending_inbox(workspace_id, inbox_id, Some(campaign_id))
+            .await?
+            .ok_or(PreviewError::NotFound("Inbox not found"))?;
+
+        let template = self.load_template(campaign.id, 0).await?;
+        let address = mailing_address::footer_address(self.pool.as_ref(), Some(workspace_id)).await?;
+        let mut rendered = self
+            .render(&lead, &campaign, &template, PREVIEW_UNSUBSCRIBE_TOKEN, address.as_ref())
+            .map_err(PreviewError::Failed)?;
+        rendered.subject = format!("[Test] {}", rendered.subject);
+
+        let (email, _) = self
+            .build_message(&inbox, &campaign, to_email, &rendered, None, None)
+            .await
+            .map_err(PreviewError::Failed)?;
+        let settings = self.smtp_settings(&inbox).await.map_err(PreviewError::Failed)?;
+
+        self.smtp_pool
+            .send(inbox.id, &settings, email)
+            .await
+            .map_err(|e| PreviewError::Failed(format!("SMTP error: {}", e)))?;
+
+        Ok(inbox.email)
+    }
+
+    /// Sends a one-off message to a lead outside the campaign sequence, e.g. a
+    /// meeting reminder, from the inbox that last emailed them in `campaign_id`.
+    /// It goes through the same suppression, daily limit and monthly quota checks
+    /// as a campaign send, and counts towards the inbox's sends but not the
+    /// campaign's. Returns the Message-ID, or `None` if the recipient is suppressed.
+    pub async fn send_direct(
+        &self,
+        workspace_id: Uuid,
+        campaign_id: Option<Uuid>,
+        to: &str,
+        subject: &str,
+        body_html: &str,
+    ) -> Result<Option<String>, String> {
+        if is_suppressed(self.pool.as_ref(), Some(workspace_id), to)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+        {
+            return Ok(None);
+        }
+
+        let inbox = self
+            .find_sending_inbox(workspace_id, None, campaign_id)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+            .ok_or("No inbox available")?;
+
+        let campaign = match campaign_id {
+            Some(campaign_id) => sqlx::query_as::<_, CampaignDetails>(
+                "SELECT id, workspace_id, from_name, reply_to FROM campaigns WHERE id = $1 AND workspace_id = $2"
+            )
+            .bind(campaign_id)
+            .bind(workspace_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| format!("DB error: {}", e))?,
+            None => None,
+        };
+        let campaign = campaign.unwrap_or(CampaignDetails {
+            id: Uuid::nil(),
+            workspace_id: Some(workspace_id),
+            from_name: None,
+            reply_to: None,
+        });
+
+        let rendered = EmailTemplate {
+            subject: subject.to_string(),
+            body_html: body_html.to_string(),
+            body_text: strip_html(body_html),
+        };
+        let (email, message_id) = self.build_message(&inbox, &campaign, to, &rendered, None, None).await?;
+        let settings = self.smtp_settings(&inbox).await?;
+
+        match email_quota::reserve_send(self.pool.as_ref(), workspace_id)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+        {
+            email_quota::EmailReservation::Granted => {}
+            email_quota::EmailReservation::LimitReached { .. } => {
+                return Err("Workspace has reached its monthly email limit".to_string());
+            }
+        }
+
+        // Same atomic slot as a campaign send, so parallel tasks can't overrun the cap
+        let reserved = sqlx::query(
+            "UPDATE email_accounts SET sent_today = sent_today + 1 WHERE id = $1 AND sent_today < daily_limit"
+        )
+        .bind(inbox.id)
+        .execute(self.pool.as_ref())
+        .await;
+        let reserved = match reserved {
+            Ok(reserved) => reserved.rows_affected() > 0,
+            Err(e) => {
+                let _ = email_quota::release_send(self.pool.as_ref(), workspace_id).await;
+                return Err(format!("Failed to update inbox counter: {}", e));
+            }
+        };
+        if !reserved {
+            let _ = email_quota::release_send(self.pool.as_ref(), workspace_id).await;
+            return Err(format!("Inbox {} has reached its daily limit", inbox.email));
+        }
+
+        if let Err(e) = self.smtp_pool.send(inbox.id, &settings, email).await {
+            let _ = sqlx::query(
+                "UPDATE email_accounts SET sent_today = GREATEST(sent_today - 1, 0) WHERE id = $1"
+            )
+            .bind(inbox.id)
+            .execute(self.pool.as_ref())
+            .await;
+            let _ = email_quota::release_send(self.pool.as_ref(), workspace_id).await;
+            return Err(format!("SMTP error: {}", e));
+        }
+
+        // The email is out; failing here would only get it sent twice on retry
+        if let Err(e) = sqlx::query("UPDATE email_accounts SET total_sent = total_sent + 1 WHERE id = $1")
+            .bind(inbox.id)
+            .execute(self.pool.as_ref())
+            .await
+        {
+            tracing::warn!("Failed to count direct send from inbox {}: {}", inbox.id, e);
+        }
+
+        Ok(Some(message_id))
+    }
+
+    /// Sends a warmup email, or a partner's reply to one, and records it for the
+    /// receiving inbox's poll to find. Returns the Message-ID, or `None` if nothing
+    /// was sent: either inbox has left the workspace's warmup pool since the job
+    /// was queued, or the partner's server rejected it (recorded as a bounce).
+    pub async fn send_warmup_email(&self, payload: &WarmupEmailPayload) -> Result<Option<String>, CampaignSendError> {
+        // Both ends must still be warming or active, connected, and in one workspace
+        let workspace_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT s.workspace_id FROM email_accounts s
+            JOIN email_accounts r ON r.workspace_id = s.workspace_id
+            WHERE s.id = $1 AND r.id = $2
+              AND s.warmup_status IN ('warming', 'active') AND r.warmup_status IN ('warming', 'active')
+              AND s.auth_error IS NULL AND r.auth_error IS NULL
+            "#
+        )
+        .bind(payload.email_account_id)
+        .bind(payload.recipient_account_id)
         .fetch_optional(self.pool.as_ref())
         .await
         .map_err(|e| format!("DB error: {}", e))?
-        .ok_or("Inbox not found")?;
+        .flatten();
+
+        let Some(workspace_id) = workspace_id else {
+            return Ok(None);
+        };
+        let inbox = self
+            .find_sending_inbox(workspace_id, Some(payload.email_account_id), None)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+            .ok_or("Inbox not found")?;
+
+        if let Some(retry_at) = self.daily_limit_reset_at(inbox.id).await? {
+            return Err(CampaignSendError::RateLimited { retry_at });
+        }
+
+        let (subject, body, original_message_id) = match payload.in_reply_to {
+            Some(original_id) => {
+                let original: Option<(String, String)> = sqlx::query_as(
+                    "SELECT subject, message_id FROM warmup_emails WHERE id = $1 AND replied_at IS NULL"
+                )
+                .bind(original_id)
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| format!("DB error: {}", e))?;
 
+                // Gone, or already answered by an earlier attempt
+                let Some((subject, message_id)) = original else {
+                    return Ok(None);
+                };
+                let body = warmup_service::warmup_reply(&mut rand::thread_rng());
+                (format!("Re: {}", subject), body.to_string(), Some(message_id))
+            }
+            None => {
+                let (subject, body) = warmup_service::warmup_message(&mut rand::thread_rng());
+                (subject.to_string(), body.to_string(), None)
+            }
+        };
+
+        let variables = HashMap::from([("subject".to_string(), subject), ("body".to_string(), body)]);
+        let rendered = render_email_template(&EmailTemplates::warmup_email(), &variables)?;
+        let campaign = CampaignDetails {
+            id: Uuid::nil(),
+            workspace_id: Some(workspace_id),
+            from_name: None,
+            reply_to: None,
+        };
+        let (email, message_id) = self
+            .build_message(&inbox, &campaign, &payload.target_email, &rendered, None, original_message_id.as_deref())
+            .await?;
+        let settings = self.smtp_settings(&inbox).await?;
+
+        if let Err(e) = self.smtp_pool.send(inbox.id, &settings, email).await {
+            // Connection trouble is retried; a rejection is the bounce the warmup
+            // metrics want to know about, and would only be rejected again
+            if e.connection {
+                return Err(format!("SMTP error: {}", e).into());
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO warmup_emails (workspace_id, sender_account_id, recipient_account_id, in_reply_to_id, message_id, subject, bounced_at)
+                VALUES ($1, $2, $3, $4, $5, $6, NOW())
+                "#
+            )
+            .bind(workspace_id)
+            .bind(inbox.id)
+            .bind(payload.recipient_account_id)
+            .bind(payload.in_reply_to)
+            .bind(&message_id)
+            .bind(&rendered.subject)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| format!("Failed to record warmup bounce: {}", e))?;
+            tracing::warn!("Warmup email from {} to {} bounced: {}", inbox.email, payload.target_email, e);
+            return Ok(None);
+        }
+
+        let mut tx = self.pool.begin().await.map_err(|e| format!("DB error: {}", e))?;
+
+        // Counts against the day's limit, but not the lifetime total the
+        // bounce and spam rates are computed from
+        sqlx::query("UPDATE email_accounts SET sent_today = sent_today + 1 WHERE id = $1")
+            .bind(inbox.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update inbox counter: {}", e))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO warmup_emails (workspace_id, sender_account_id, recipient_account_id, in_reply_to_id, message_id, subject)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(workspace_id)
+        .bind(inbox.id)
+        .bind(payload.recipient_account_id)
+        .bind(payload.in_reply_to)
+        .bind(&message_id)
+        .bind(&rendered.subject)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record warmup email: {}", e))?;
+
+        if let Some(original_id) = payload.in_reply_to {
+            sqlx::query("UPDATE warmup_emails SET replied_at = NOW() WHERE id = $1")
+                .bind(original_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to record warmup reply: {}", e))?;
+        }
+
+        tx.commit().await.map_err(|e| format!("DB error: {}", e))?;
+        Ok(Some(message_id))
+    }
+
+    /// `inbox_id` if given, else the inbox `campaign_id` sent from most recently,
+    /// else the healthiest in the workspace.
+    async fn find_sending_inbox(
+        &self,
+        workspace_id: Uuid,
+        inbox_id: Option<Uuid>,
+        campaign_id: Option<Uuid>,
+    ) -> Result<Option<InboxCredentials>, sqlx::Error> {
+        sqlx::query_as::<_, InboxCredentials>(
+            r#"
+            SELECT ea.id, ea.email, ea.smtp_host, ea.smtp_port, ea.smtp_username, ea.smtp_password,
+                   ea.smtp_password_encrypted, ea.encryption_key_id, ea.provider, ea.auth_method
+            FROM email_accounts ea
+            WHERE ea.workspace_id = $1 AND ($2::uuid IS NULL OR ea.id = $2)
+            ORDER BY (
+                SELECT MAX(cl.sent_at) FROM campaign_leads cl
+                WHERE cl.campaign_id = $3 AND cl.email_account_id = ea.id
+            ) DESC NULLS LAST, ea.health_score DESC NULLS LAST
+            LIMIT 1
+            "#
+        )
+        .bind(workspace_id)
+        .bind(inbox_id)
+        .bind(campaign_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+    }
+
+    async fn load_preview_target(
+        &self,
+        workspace_id: Uuid,
+        campaign_id: Uuid,
+        lead_id: Option<Uuid>,
+    ) -> Result<(CampaignDetails, LeadDetails), PreviewError> {
+        let campaign = sqlx::query_as::<_, CampaignDetails>(
+            "SELECT id, workspace_id, from_name, reply_to FROM campaigns WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(PreviewError::NotFound("Campaign not found"))?;
+
+        let lead = match lead_id {
+            Some(lead_id) => sqlx::query_as::<_, LeadDetails>(
+                "SELECT id, email, first_name, last_name, company, title, custom_fields FROM leads WHERE id = $1 AND workspace_id = $2"
+            )
+            .bind(lead_id)
+            .bind(workspace_id)
+            .fetch_optional(self.pool.as_ref())
+            .await?
+            .ok_or(PreviewError::NotFound("Lead not found"))?,
+            None => LeadDetails::sample(),
+        };
+
+        Ok((campaign, lead))
+    }
+
+    /// Sends one campaign email and archives the rendered copy. Returns the Message-ID,
+    /// or `None` if the recipient opted out after the send was scheduled or the
+    /// email hard bounced.
+    pub async fn send_campaign_email(&self, payload: &SendEmailJobPayload) -> Result<Option<String>, CampaignSendError> {
         // Get campaign details
         let campaign = sqlx::query_as::<_, CampaignDetails>(
-            "SELECT id, name, workspace_id FROM campaigns WHERE id = $1"
+            "SELECT id, workspace_id, from_name, reply_to FROM campaigns WHERE id = $1"
         )
         .bind(payload.campaign_id)
         .fetch_optional(self.pool.as_ref())
@@ -95,7 +593,7 @@ impl CampaignEmailSender {
 
         // Get lead details
         let lead = sqlx::query_as::<_, LeadDetails>(
-            "SELECT id, email, first_name, last_name, company, title FROM leads WHERE id = $1"
+            "SELECT id, email, first_name, last_name, company, title, custom_fields FROM leads WHERE id = $1"
         )
         .bind(payload.lead_id)
         .fetch_optional(self.pool.as_ref())
@@ -103,164 +601,739 @@ impl CampaignEmailSender {
         .map_err(|e| format!("DB error: {}", e))?
         .ok_or("Lead not found")?;
 
-        // Decrypt SMTP password
-        let smtp_password = self.get_smtp_password(&inbox)?;
+        // Jobs queued before a pause, an auto-pause or a trip to the trash are
+        // dropped here; the lead goes back to the scheduler for when it resumes
+        if !self.is_still_sendable(payload.campaign_id, payload.lead_id).await? {
+            self.release_claim(payload.campaign_lead_id, payload.step_index).await?;
+            return Ok(None);
+        }
 
-        // Build personalized email
-        let subject = self.personalize_text(
-            &format!("Quick question about {}", lead.company.as_deref().unwrap_or("your company")),
-            &lead
-        );
-        
-        let body_html = self.build_email_body(&lead, &campaign);
-
-        // Generate unsubscribe token and URL
-        let unsubscribe_token = self.generate_unsubscribe_token(&lead, campaign.workspace_id);
-        let unsubscribe_url = format!(
-            "{}/unsubscribe?token={}",
-            std::env::var("APP_URL").unwrap_or_else(|_| "https://app.outreachiq.com".to_string()),
-            unsubscribe_token
-        );
+        // Checked before anything is built or reserved, so a suppressed lead costs nothing
+        if is_suppressed(self.pool.as_ref(), campaign.workspace_id, &lead.email)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+        {
+            self.mark_suppressed(payload.campaign_lead_id).await?;
+            return Ok(None);
+        }
 
-        // Build email with compliance headers
-        let from = format!("{} <{}>", inbox.email.split('@').next().unwrap_or("Team"), inbox.email);
-        let to_name = format!(
-            "{} {}",
-            lead.first_name.as_deref().unwrap_or(""),
-            lead.last_name.as_deref().unwrap_or("")
-        ).trim().to_string();
-        let to = if to_name.is_empty() {
-            lead.email.clone()
-        } else {
-            format!("{} <{}>", to_name, lead.email)
+        if self.is_opted_out(payload.campaign_lead_id, payload.step_index).await? {
+            return Ok(None);
+        }
+
+        // Get inbox credentials
+        let inbox = sqlx::query_as::<_, InboxCredentials>(
+            r#"
+            SELECT id, email, smtp_host, smtp_port, smtp_username, smtp_password, 
+                   smtp_password_encrypted, encryption_key_id, provider, auth_method
+            FROM email_accounts WHERE id = $1
+            "#
+        )
+        .bind(payload.inbox_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| format!("DB error: {}", e))?
+        .ok_or("Inbox not found")?;
+
+        // A capped inbox defers the send before anything is rendered
+        if let Some(retry_at) = self.daily_limit_reset_at(payload.inbox_id).await? {
+            return Err(CampaignSendError::RateLimited { retry_at });
+        }
+
+        // Commercial email must carry the sender's postal address (CAN-SPAM), so
+        // nothing goes out until the workspace has set one
+        let Some(address) = mailing_address::footer_address(self.pool.as_ref(), campaign.workspace_id)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?
+        else {
+            mailing_address::mark_address_missing(self.pool.as_ref(), campaign.id)
+                .await
+                .map_err(|e| format!("DB error: {}", e))?;
+            return Err(CampaignSendError::AddressMissing { retry_at: mailing_address::retry_at(Utc::now()) });
+        };
+
+        // So is a workspace that has used its monthly allowance. The slot taken
+        // here is given back if the send doesn't go out.
+        if let Some(workspace_id) = campaign.workspace_id {
+            let reservation = email_quota::reserve_send(self.pool.as_ref(), workspace_id)
+                .await
+                .map_err(|e| format!("DB error: {}", e))?;
+            if let email_quota::EmailReservation::LimitReached { .. } = reservation {
+                email_quota::mark_limit_reached(self.pool.as_ref(), campaign.id)
+                    .await
+                    .map_err(|e| format!("DB error: {}", e))?;
+                return Err(CampaignSendError::QuotaExceeded { retry_at: email_quota::retry_at(Utc::now()) });
+            }
+        }
+
+        let result = self.deliver_campaign_email(payload, &campaign, &lead, &inbox, &address).await;
+        // Only a delivered email uses up the allowance
+        if !matches!(result, Ok(Some(_))) {
+            if let Some(workspace_id) = campaign.workspace_id {
+                let _ = email_quota::release_send(self.pool.as_ref(), workspace_id).await;
+            }
+        }
+        result
+    }
+
+    /// Renders, sends and records a campaign email once the send has passed the
+    /// suppression, daily limit, mailing address and monthly quota checks
+    async fn deliver_campaign_email(
+        &self,
+        payload: &SendEmailJobPayload,
+        campaign: &CampaignDetails,
+        lead: &LeadDetails,
+        inbox: &InboxCredentials,
+        address: &CompanyAddress,
+    ) -> Result<Option<String>, CampaignSendError> {
+        let template = self
+            .load_template(campaign.id, payload.step_index)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+        let unsubscribe_token = self.generate_unsubscribe_token(lead, campaign);
+        let mut rendered = self.render(lead, campaign, &template, &unsubscribe_token, Some(address))?;
+        // Only real sends are tracked; previews and test sends stay clean
+        rendered.body_html = tracking::instrument_html(&rendered.body_html, &tracking::tracking_base_url(), payload.campaign_lead_id);
+        let (email, message_id) = self
+            .build_message(
+                inbox,
+                campaign,
+                &recipient_address(lead),
+                &rendered,
+                Some(&unsubscribe_url(&unsubscribe_token)),
+                None,
+            )
+            .await?;
+        let settings = self.smtp_settings(inbox).await?;
+
+        // Reserve a slot against the inbox's daily limit before sending. Parallel
+        // worker tasks share this counter, so the check has to be atomic.
+        let reserved = sqlx::query(
+            "UPDATE email_accounts SET sent_today = sent_today + 1 WHERE id = $1 AND sent_today < daily_limit"
+        )
+        .bind(payload.inbox_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| format!("Failed to update inbox counter: {}", e))?;
+
+        if reserved.rows_affected() == 0 {
+            // Another task took the last slot. If the counter reset in between, the
+            // retry is only a few minutes off.
+            let retry_at = self
+                .daily_limit_reset_at(payload.inbox_id)
+                .await?
+                .unwrap_or_else(|| Utc::now() + Duration::minutes(COUNTER_RESET_GRACE_MINUTES));
+            return Err(CampaignSendError::RateLimited { retry_at });
+        }
+
+        let smtp_response = match self.smtp_pool.send(inbox.id, &settings, email).await {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = sqlx::query(
+                    "UPDATE email_accounts SET sent_today = GREATEST(sent_today - 1, 0) WHERE id = $1"
+                )
+                .bind(payload.inbox_id)
+                .execute(self.pool.as_ref())
+                .await;
+
+                // A broken connection says nothing about the recipient, and
+                // some rejections (our own login failing) aren't bounces
+                let bounce_type = if e.connection { None } else { bounces::classify_smtp_rejection(&e.message) };
+                if let Some(bounce_type) = bounce_type {
+                    return self.handle_smtp_bounce(payload, campaign, lead, bounce_type, &e.message).await;
+                }
+                return Err(format!("SMTP error: {}", e).into());
+            }
         };
 
-        let email = Message::builder()
-            .from(from.parse().map_err(|e| format!("Invalid from address: {}", e))?)
+        // The email is out. A retry would send it again, so a failed write from
+        // here on is logged with the Message-ID to reconcile later, not retried.
+        let recorded: Result<(), String> = async {
+            // Mark the lead sent and archive the exact copy together
+            let mut tx = self.pool.begin().await.map_err(|e| format!("DB error: {}", e))?;
+
+            sqlx::query(
+                "UPDATE campaign_leads SET status = 'sent', sent_at = NOW(), email_account_id = $2 WHERE id = $1"
+            )
+            .bind(payload.campaign_lead_id)
+            .bind(payload.inbox_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update campaign_lead: {}", e))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO sent_emails (
+                    workspace_id, campaign_id, campaign_lead_id, lead_id, email_account_id,
+                    from_email, to_email, subject, body_html, body_text, message_id, smtp_response, step_index
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                "#
+            )
+            .bind(campaign.workspace_id)
+            .bind(payload.campaign_id)
+            .bind(payload.campaign_lead_id)
+            .bind(payload.lead_id)
+            .bind(payload.inbox_id)
+            .bind(&inbox.email)
+            .bind(&lead.email)
+            .bind(&rendered.subject)
+            .bind(&rendered.body_html)
+            .bind(&rendered.body_text)
+            .bind(&message_id)
+            .bind(&smtp_response)
+            .bind(payload.step_index)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to archive sent email: {}", e))?;
+
+            tx.commit().await.map_err(|e| format!("DB error: {}", e))?;
+
+            // Lifetime total backs the bounce/spam rates computed from provider webhooks
+            sqlx::query(
+                "UPDATE email_accounts SET total_sent = total_sent + 1 WHERE id = $1"
+            )
+            .bind(payload.inbox_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| format!("Failed to update inbox counter: {}", e))?;
+
+            // Update campaign sent counter
+            sqlx::query(
+                "UPDATE campaigns SET sent = sent + 1, send_blocked_reason = NULL WHERE id = $1"
+            )
+            .bind(payload.campaign_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| format!("Failed to update campaign counter: {}", e))?;
+
+            Ok(())
+        }
+        .await;
+        if let Err(e) = recorded {
+            tracing::error!(
+                "Sent {} for campaign lead {} but failed to record it: {}",
+                message_id, payload.campaign_lead_id, e
+            );
+        }
+
+        Ok(Some(message_id))
+    }
+
+    /// Records a send the recipient's server refused. A soft bounce is retried
+    /// with backoff until it has happened `MAX_SOFT_BOUNCES` times, after which
+    /// it's treated as hard: the lead is marked invalid and suppressed.
+    async fn handle_smtp_bounce(
+        &self,
+        payload: &SendEmailJobPayload,
+        campaign: &CampaignDetails,
+        lead: &LeadDetails,
+        bounce_type: BounceType,
+        message: &str,
+    ) -> Result<Option<String>, CampaignSendError> {
+        let now = Utc::now();
+        let mut bounce_type = bounce_type;
+        let mut reason = message.to_string();
+        let mut retry_at = None;
+
+        if bounce_type == BounceType::Soft {
+            let soft_bounces = bounces::soft_bounce_count(self.pool.as_ref(), payload.campaign_lead_id)
+                .await
+                .map_err(|e| format!("DB error: {}", e))?
+                + 1;
+            if soft_bounces >= MAX_SOFT_BOUNCES {
+                bounce_type = BounceType::Hard;
+                reason = format!("Gave up after {} soft bounces: {}", soft_bounces, message);
+            } else {
+                retry_at = Some(bounces::soft_bounce_retry_at(now, soft_bounces));
+            }
+        }
+
+        let bounce = NewBounce {
+            workspace_id: campaign.workspace_id,
+            campaign_id: Some(payload.campaign_id),
+            campaign_lead_id: Some(payload.campaign_lead_id),
+            email_account_id: Some(payload.inbox_id),
+            recipient: &lead.email,
+            bounce_type,
+            source: BounceSource::Smtp,
+            reason: Some(&reason),
+            occurred_at: now,
+        };
+
+        let mut tx = self.pool.begin().await.map_err(|e| format!("DB error: {}", e))?;
+        if bounce_type == BounceType::Hard {
+            // A refused send still counts towards the inbox's bounce rate
+            sqlx::query("UPDATE email_accounts SET total_sent = total_sent + 1 WHERE id = $1")
+                .bind(payload.inbox_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to update inbox counter: {}", e))?;
+        }
+        bounces::apply(&mut tx, &bounce)
+            .await
+            .map_err(|e| format!("Failed to record bounce: {}", e))?;
+        tx.commit().await.map_err(|e| format!("DB error: {}", e))?;
+
+        tracing::warn!("Campaign email to {} bounced ({}): {}", lead.email, bounce_type.as_str(), message);
+        match retry_at {
+            Some(retry_at) => Err(CampaignSendError::SoftBounced { retry_at }),
+            None => Ok(None),
+        }
+    }
+
+    /// Template for a sequence step. A step without one falls back to the
+    /// campaign's first template (step 0) or the built-in defaults.
+    async fn load_template(&self, campaign_id: Uuid, step_index: i32) -> Result<EmailTemplate, sqlx::Error> {
+        let step_template = sqlx::query_as::<_, (String, String, Option<String>)>(
+            r#"
+            SELECT t.subject, t.body_html, t.body_text
+            FROM campaign_steps s
+            JOIN campaign_templates t ON t.id = s.template_id
+            WHERE s.campaign_id = $1 AND s.step_index = $2
+            "#
+        )
+        .bind(campaign_id)
+        .bind(step_index)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        let template = match step_template {
+            Some(template) => Some(template),
+            None if step_index == 0 => {
+                sqlx::query_as::<_, (String, String, Option<String>)>(
+                    "SELECT subject, body_html, body_text FROM campaign_templates WHERE campaign_id = $1 ORDER BY step_order ASC LIMIT 1"
+                )
+                .bind(campaign_id)
+                .fetch_optional(self.pool.as_ref())
+                .await?
+            }
+            None => None,
+        };
+
+        Ok(match template {
+            Some((subject, body_html, body_text)) => EmailTemplate {
+                subject,
+                body_html,
+                body_text: body_text.unwrap_or_default(),
+            },
+            None if step_index == 0 => EmailTemplates::campaign_default(),
+            None => EmailTemplates::campaign_follow_up(),
+        })
+    }
+
+    /// Renders `template` for the lead, with the unsubscribe links filled in.
+    /// Real sends, previews and test sends all go through here so they can't
+    /// drift apart. A template without a plain-text body gets one from the HTML.
+    /// With an `address`, both parts end in the standard compliance footer.
+    fn render(
+        &self,
+        lead: &LeadDetails,
+        _campaign: &CampaignDetails,
+        template: &EmailTemplate,
+        unsubscribe_token: &str,
+        address: Option<&CompanyAddress>,
+    ) -> Result<EmailTemplate, String> {
+        let unsubscribe_url = unsubscribe_url(unsubscribe_token);
+        let campaign_unsubscribe_url = format!("{}&scope=campaign", unsubscribe_url);
+
+        let mut variables = merge_fields(lead);
+        variables.insert("unsubscribe_url".to_string(), unsubscribe_url.clone());
+        variables.insert("campaign_unsubscribe_url".to_string(), campaign_unsubscribe_url);
+
+        let mut rendered = render_email_template(template, &variables)?;
+        if rendered.body_text.trim().is_empty() {
+            rendered.body_text = strip_html(&rendered.body_html);
+        }
+        if let Some(address) = address {
+            mailing_address::append_footer(&mut rendered, address, &unsubscribe_url);
+        }
+
+        Ok(rendered)
+    }
+
+    /// Builds the signed MIME message from `inbox`. Returns it with its Message-ID.
+    /// Campaign sends pass their unsubscribe URL for the one-click List-Unsubscribe
+    /// headers that Gmail and Yahoo require of bulk senders; replies pass the
+    /// Message-ID they answer so they thread.
+    async fn build_message(
+        &self,
+        inbox: &InboxCredentials,
+        campaign: &CampaignDetails,
+        to: &str,
+        rendered: &EmailTemplate,
+        list_unsubscribe_url: Option<&str>,
+        in_reply_to: Option<&str>,
+    ) -> Result<(Message, String), String> {
+        let from_name = self.resolve_from_name(campaign, inbox).await;
+        let from = lettre::message::Mailbox::new(
+            Some(from_name),
+            inbox.email.parse().map_err(|e| format!("Invalid from address: {}", e))?,
+        );
+
+        // Set our own Message-ID so replies can be threaded back to the archived send
+        let message_id = generate_message_id(&inbox.email);
+
+        let mut builder = Message::builder()
+            .message_id(Some(message_id.clone()))
+            .from(from)
             .to(to.parse().map_err(|e| format!("Invalid to address: {}", e))?)
-            .subject(&subject)
+            .subject(&rendered.subject);
+
+        if let Some(reply_to) = campaign.reply_to.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+            builder = builder.reply_to(reply_to.parse().map_err(|e| format!("Invalid reply-to address: {}", e))?);
+        }
+
+        if let Some(original) = in_reply_to {
+            builder = builder.in_reply_to(original.to_string()).references(original.to_string());
+        }
+
+        if let Some(url) = list_unsubscribe_url {
+            builder = builder
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                    format!("<{}>", url),
+                ))
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ));
+        }
+
+        let mut email = builder
             .multipart(
                 lettre::message::MultiPart::alternative()
                     .singlepart(
                         lettre::message::SinglePart::builder()
                             .header(ContentType::TEXT_PLAIN)
-                            .body(strip_html(&body_html)),
+                            .body(rendered.body_text.clone()),
                     )
                     .singlepart(
                         lettre::message::SinglePart::builder()
                             .header(ContentType::TEXT_HTML)
-                            .body(body_html),
+                            .body(rendered.body_html.clone()),
                     ),
             )
             .map_err(|e| format!("Failed to build email: {}", e))?;
 
-        // Send via SMTP
-        let creds = Credentials::new(inbox.smtp_username.clone(), smtp_password);
+        sign_with_dkim(&mut email, &inbox.email);
 
-        let mailer: AsyncSmtpTransport<Tokio1Executor> =
-            AsyncSmtpTransport::<Tokio1Executor>::relay(&inbox.smtp_host)
-                .map_err(|e| format!("Failed to create transport: {}", e))?
-                .credentials(creds)
-                .port(inbox.smtp_port as u16)
-                .build();
+        Ok((email, message_id))
+    }
 
-        let response = mailer.send(email).await
-            .map_err(|e| format!("SMTP error: {}", e))?;
+    async fn smtp_settings(&self, inbox: &InboxCredentials) -> Result<SmtpSettings, String> {
+        Ok(SmtpSettings {
+            host: inbox.smtp_host.clone(),
+            port: inbox.smtp_port as u16,
+            username: inbox.smtp_username.clone(),
+            password: self.get_smtp_password(inbox).await?,
+            xoauth2: inbox.auth_method == "oauth",
+        })
+    }
 
-        let message_id = response.message().collect::<Vec<_>>().join("");
+    /// The stored password, or for OAuth inboxes a fresh access token minted from
+    /// the stored refresh token
+    async fn get_smtp_password(&self, inbox: &InboxCredentials) -> Result<String, String> {
+        let secret = decrypt_inbox_password(
+            inbox.smtp_password_encrypted.as_deref(),
+            inbox.encryption_key_id.as_deref(),
+            inbox.smtp_password.as_deref(),
+        )?;
 
-        // Update campaign_leads status
-        sqlx::query(
-            "UPDATE campaign_leads SET status = 'sent', sent_at = NOW() WHERE id = $1"
+        if inbox.auth_method == "oauth" {
+            email_oauth::access_token(self.pool.as_ref(), inbox.id, &inbox.provider, &secret).await
+        } else {
+            Ok(secret)
+        }
+    }
+
+    /// Campaign override first, then the workspace owner's name, then the inbox local part.
+    async fn resolve_from_name(&self, campaign: &CampaignDetails, inbox: &InboxCredentials) -> String {
+        if let Some(name) = campaign.from_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            return name.to_string();
+        }
+
+        if let Some(workspace_id) = campaign.workspace_id {
+            let owner_name = sqlx::query_scalar::<_, Option<String>>(
+                r#"
+                SELECT COALESCE(NULLIF(TRIM(CONCAT(u.first_name, ' ', u.last_name)), ''), u.name)
+                FROM workspace_members wm
+                JOIN users u ON u.id = wm.user_id
+                WHERE wm.workspace_id = $1 AND wm.role = 'owner'
+                ORDER BY wm.joined_at ASC
+                LIMIT 1
+                "#
+            )
+            .bind(workspace_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .ok()
+            .flatten()
+            .flatten();
+
+            if let Some(name) = owner_name.filter(|n| !n.trim().is_empty()) {
+                return name;
+            }
+        }
+
+        inbox.email.split('@').next().unwrap_or("Team").to_string()
+    }
+
+    /// When an inbox at its daily limit may send again, or `None` while it has capacity
+    async fn daily_limit_reset_at(&self, inbox_id: Uuid) -> Result<Option<DateTime<Utc>>, String> {
+        let state: Option<(bool, Option<i32>)> = sqlx::query_as(
+            "SELECT sent_today >= daily_limit, timezone_offset_minutes FROM email_accounts WHERE id = $1"
         )
-        .bind(payload.campaign_lead_id)
-        .execute(self.pool.as_ref())
+        .bind(inbox_id)
+        .fetch_optional(self.pool.as_ref())
         .await
-        .map_err(|e| format!("Failed to update campaign_lead: {}", e))?;
+        .map_err(|e| format!("DB error: {}", e))?;
+
+        Ok(state.filter(|(capped, _)| *capped).map(|(_, offset)| {
+            next_local_midnight(offset, Utc::now()) + Duration::minutes(COUNTER_RESET_GRACE_MINUTES)
+        }))
+    }
+
+    /// Checked right before sending: the recipient may have unsubscribed from this
+    /// campaign while the job sat in the queue. Follow-ups are also dropped once the
+    /// lead replied or its sequence was stopped.
+    async fn is_opted_out(&self, campaign_lead_id: Uuid, step_index: i32) -> Result<bool, String> {
+        let opted_out: Option<bool> = sqlx::query_scalar(
+            r#"
+            SELECT cl.unsubscribed_at IS NOT NULL
+                OR ($2 > 0 AND (cl.replied_at IS NOT NULL OR cl.sequence_stopped_at IS NOT NULL))
+            FROM campaign_leads cl
+            WHERE cl.id = $1
+            "#
+        )
+        .bind(campaign_lead_id)
+        .bind(step_index)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+        Ok(opted_out.unwrap_or(false))
+    }
+
+    /// Whether the campaign is still running and neither it nor the lead is in the trash
+    async fn is_still_sendable(&self, campaign_id: Uuid, lead_id: Uuid) -> Result<bool, String> {
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM campaigns c, leads l
+                WHERE c.id = $1 AND l.id = $2
+                  AND c.status = 'active' AND c.deleted_at IS NULL AND l.deleted_at IS NULL
+            )
+            "#
+        )
+        .bind(campaign_id)
+        .bind(lead_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| format!("DB error: {}", e))
+    }
 
-        // Update inbox sent_today counter
+    /// Undoes the scheduler's claim on a send that was dropped, so the lead is picked
+    /// up again once the campaign resumes: as a first send (or a resend of step 0)
+    /// if it was pending, otherwise as the follow-up that was due.
+    async fn release_claim(&self, campaign_lead_id: Uuid, step_index: i32) -> Result<(), String> {
         sqlx::query(
-            "UPDATE email_accounts SET sent_today = sent_today + 1 WHERE id = $1"
+            r#"
+            UPDATE campaign_leads
+            SET status = CASE WHEN sent_at IS NULL OR $2 = 0 THEN 'pending' ELSE 'sent' END,
+                current_step = CASE WHEN sent_at IS NULL OR $2 = 0 THEN current_step ELSE $2 - 1 END
+            WHERE id = $1 AND status = 'scheduled'
+            "#
         )
-        .bind(payload.inbox_id)
+        .bind(campaign_lead_id)
+        .bind(step_index)
         .execute(self.pool.as_ref())
         .await
-        .map_err(|e| format!("Failed to update inbox counter: {}", e))?;
+        .map_err(|e| format!("Failed to update campaign_lead: {}", e))?;
 
-        // Update campaign sent counter
+        Ok(())
+    }
+
+    /// Takes a suppressed lead out of the campaign so it isn't scheduled again
+    async fn mark_suppressed(&self, campaign_lead_id: Uuid) -> Result<(), String> {
         sqlx::query(
-            "UPDATE campaigns SET sent = sent + 1 WHERE id = $1"
+            r#"
+            UPDATE campaign_leads
+            SET status = 'suppressed',
+                sequence_stopped_at = COALESCE(sequence_stopped_at, NOW()),
+                sequence_stop_reason = COALESCE(sequence_stop_reason, 'suppressed')
+            WHERE id = $1
+            "#
         )
-        .bind(payload.campaign_id)
+        .bind(campaign_lead_id)
         .execute(self.pool.as_ref())
         .await
-        .map_err(|e| format!("Failed to update campaign counter: {}", e))?;
+        .map_err(|e| format!("Failed to update campaign_lead: {}", e))?;
 
-        Ok(message_id)
+        Ok(())
     }
 
-    fn get_smtp_password(&self, inbox: &InboxCredentials) -> Result<String, String> {
-        // Try encrypted password first
-        if let Some(encrypted) = &inbox.smtp_password_encrypted {
-            if let Ok(enc_service) = EncryptionService::new() {
-                if let Ok(decrypted) = enc_service.decrypt(encrypted) {
-                    return Ok(decrypted);
-                }
-            }
-        }
-        
-        // Fall back to plaintext (legacy)
-        inbox.smtp_password.clone().ok_or_else(|| "No SMTP password available".to_string())
+    fn generate_unsubscribe_token(&self, lead: &LeadDetails, campaign: &CampaignDetails) -> String {
+        unsubscribe_token::sign(lead.id, campaign.workspace_id, campaign.id)
     }
+}
 
-    fn personalize_text(&self, text: &str, lead: &LeadDetails) -> String {
-        text.replace("{{firstName}}", lead.first_name.as_deref().unwrap_or("there"))
-            .replace("{{lastName}}", lead.last_name.as_deref().unwrap_or(""))
-            .replace("{{company}}", lead.company.as_deref().unwrap_or("your company"))
-            .replace("{{title}}", lead.title.as_deref().unwrap_or(""))
-            .replace("{{email}}", &lead.email)
+/// Whether `email` is on the workspace's suppression list or the global one.
+/// Addresses are compared case-insensitively, and a workspace entry of
+/// `@domain` covers every address at that domain.
+pub async fn is_suppressed(pool: &PgPool, workspace_id: Option<Uuid>, email: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+                SELECT 1 FROM suppression_list
+                WHERE workspace_id IS NOT DISTINCT FROM $1
+                AND LOWER(email) IN (LOWER($2), '@' || split_part(LOWER($2), '@', 2))
+            )
+            OR EXISTS (SELECT 1 FROM global_suppression WHERE email = LOWER($2))
+        "#
+    )
+    .bind(workspace_id)
+    .bind(email)
+    .fetch_one(pool)
+    .await
+}
+
+/// Mailbox password for an inbox, from the encrypted column. A ciphertext that
+/// won't decrypt is an error, not a reason to use the legacy plaintext column,
+/// which is only read for rows `key_rotation::encrypt_plaintext_passwords`
+/// hasn't reached yet. Shared by SMTP sending and IMAP polling.
+pub fn decrypt_inbox_password(
+    encrypted: Option<&[u8]>,
+    encryption_key_id: Option<&str>,
+    plaintext: Option<&str>,
+) -> Result<String, String> {
+    if let Some(encrypted) = encrypted {
+        return EncryptionService::new()
+            .and_then(|enc_service| enc_service.decrypt_with_key_id(encrypted, encryption_key_id))
+            .map_err(|e| format!("Failed to decrypt SMTP password: {}", e));
     }
 
-    fn build_email_body(&self, lead: &LeadDetails, _campaign: &CampaignDetails) -> String {
-        let first_name = lead.first_name.as_deref().unwrap_or("there");
-        let company = lead.company.as_deref().unwrap_or("your company");
-        
-        format!(r#"
-<!DOCTYPE html>
-<html>
-<head>
-    <style>
-        body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
-        .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
-    </style>
-</head>
-<body>
-    <div class="container">
-        <p>Hi {},</p>
-        
-        <p>I noticed {} and wanted to reach out to see if you'd be interested in a quick conversation.</p>
-        
-        <p>Would you be open to a brief 15-minute call this week?</p>
-        
-        <p>Best regards</p>
-    </div>
-</body>
-</html>
-"#, first_name, company)
+    let plaintext = plaintext.ok_or_else(|| "No SMTP password available".to_string())?;
+    tracing::warn!("Using an unencrypted SMTP password; restart the worker to encrypt it");
+    Ok(plaintext.to_string())
+}
+
+/// Workspace-wide unsubscribe link. GET shows the confirmation page; POST is the
+/// RFC 8058 one-click unsubscribe mail clients send from the List-Unsubscribe header.
+fn unsubscribe_url(token: &str) -> String {
+    format!(
+        "{}/unsubscribe?token={}",
+        std::env::var("APP_URL").unwrap_or_else(|_| "https://app.outreachiq.com".to_string()),
+        token
+    )
+}
+
+/// Lead merge fields available to templates, with the fallbacks used when a
+/// field is missing. The lead's custom fields come along under their own keys;
+/// a custom field can't replace a built-in one, and one that isn't text, a
+/// number or a boolean is left out, so its tag renders empty.
+fn merge_fields(lead: &LeadDetails) -> HashMap<String, String> {
+    let mut fields: HashMap<String, String> = lead
+        .custom_fields
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((key.clone(), value))
+        })
+        .collect();
+
+    fields.extend([
+        ("firstName".to_string(), lead.first_name.clone().unwrap_or_else(|| "there".to_string())),
+        ("lastName".to_string(), lead.last_name.clone().unwrap_or_default()),
+        ("company".to_string(), lead.company.clone().unwrap_or_else(|| "your company".to_string())),
+        ("title".to_string(), lead.title.clone().unwrap_or_default()),
+        ("email".to_string(), lead.email.clone()),
+    ]);
+    fields
+}
+
+/// `Name <address>` when the lead has a name, otherwise the bare address
+fn recipient_address(lead: &LeadDetails) -> String {
+    let name = format!(
+        "{} {}",
+        lead.first_name.as_deref().unwrap_or(""),
+        lead.last_name.as_deref().unwrap_or("")
+    ).trim().to_string();
+
+    if name.is_empty() {
+        lead.email.clone()
+    } else {
+        format!("{} <{}>", name, lead.email)
     }
+}
 
-    fn generate_unsubscribe_token(&self, lead: &LeadDetails, workspace_id: Option<Uuid>) -> String {
-        use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
-        let data = format!("{}:{}:{}", 
-            lead.id, 
-            lead.email, 
-            workspace_id.map(|w| w.to_string()).unwrap_or_default()
-        );
-        URL_SAFE_NO_PAD.encode(data.as_bytes())
+/// RFC 5322 Message-ID on the sending inbox's domain, e.g. `<uuid@acme.io>`.
+fn generate_message_id(from_email: &str) -> String {
+    let domain = from_email.rsplit_once('@').map(|(_, d)| d).unwrap_or("outreachiq.local");
+    format!("<{}@{}>", Uuid::new_v4(), domain)
+}
+
+/// Connects to the SMTP server and authenticates without sending anything.
+pub async fn test_smtp_credentials(host: &str, port: u16, username: &str, password: &str) -> Result<(), String> {
+    let mailer: AsyncSmtpTransport<Tokio1Executor> =
+        AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| format!("Failed to create transport: {}", e))?
+            .credentials(Credentials::new(username.to_string(), password.to_string()))
+            .port(port)
+            .timeout(Some(std::time::Duration::from_secs(15)))
+            .build();
+
+    match mailer.test_connection().await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("SMTP server rejected the connection".to_string()),
+        Err(e) => Err(format!("SMTP error: {}", e)),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Signing config per sending domain, built on first use. `None` marks a domain
+/// without a key, so its warning is logged once rather than on every send.
+static DKIM_CONFIGS: LazyLock<Mutex<HashMap<String, Option<Arc<DkimConfig>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Adds a DKIM-Signature header when a key is configured for the sender's domain.
+///
+/// Provider-hosted mailboxes (Google, Microsoft) sign on their side, so a missing key
+/// only warrants a warning rather than failing the send.
+fn sign_with_dkim(message: &mut Message, from_email: &str) {
+    let domain = from_email.rsplit('@').next().unwrap_or("").trim().to_lowercase();
+    let config = {
+        let mut configs = DKIM_CONFIGS.lock().unwrap_or_else(|e| e.into_inner());
+        configs.entry(domain.clone()).or_insert_with(|| dkim_config(&domain)).clone()
+    };
+    if let Some(config) = config {
+        message.sign(&config);
+    }
+}
+
+fn dkim_config(domain: &str) -> Option<Arc<DkimConfig>> {
+    let Some(key) = DkimKey::for_domain(domain) else {
+        tracing::warn!("No DKIM key configured for {}, sending unsigned", domain);
+        return None;
+    };
+    // Keys are checked when they're loaded at startup, so this only fails if
+    // lettre disagrees with itself
+    match DkimSigningKey::new(&key.private_key, key.signing_algorithm()) {
+        Ok(signing_key) => Some(Arc::new(DkimConfig::default_config(key.selector, key.domain, signing_key))),
+        Err(e) => {
+            tracing::error!("Invalid DKIM key for {}: {}, sending unsigned", domain, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EmailTemplate {
     pub subject: String,
     pub body_html: String,
@@ -320,7 +1393,7 @@ impl EmailSender {
             None => request.to_email.clone(),
         };
 
-        let email = match Message::builder()
+        let mut email = match Message::builder()
             .from(from.parse().unwrap())
             .to(to.parse().unwrap())
             .subject(&request.subject)
@@ -347,6 +1420,8 @@ impl EmailSender {
             }
         };
 
+        sign_with_dkim(&mut email, &self.from_email);
+
         let creds = Credentials::new(self.smtp_username.clone(), self.smtp_password.clone());
 
         let mailer: AsyncSmtpTransport<Tokio1Executor> =
@@ -380,23 +1455,63 @@ impl EmailSender {
         template: &EmailTemplate,
         variables: &HashMap<String, String>,
     ) -> Result<(String, String, String), String> {
-        let mut handlebars = Handlebars::new();
-        handlebars.set_strict_mode(false);
+        let rendered = render_email_template(template, variables)?;
+        Ok((rendered.subject, rendered.body_html, rendered.body_text))
+    }
+}
+
+/// Renders a template's merge fields. Values are HTML-escaped in the HTML body
+/// only; the subject and plain-text body get them verbatim.
+pub fn render_email_template(
+    template: &EmailTemplate,
+    variables: &HashMap<String, String>,
+) -> Result<EmailTemplate, String> {
+    let mut html = Handlebars::new();
+    html.set_strict_mode(false);
 
-        let subject = handlebars
-            .render_template(&template.subject, variables)
-            .map_err(|e| e.to_string())?;
+    let mut plain = Handlebars::new();
+    plain.set_strict_mode(false);
+    plain.register_escape_fn(handlebars::no_escape);
 
-        let body_html = handlebars
-            .render_template(&template.body_html, variables)
-            .map_err(|e| e.to_string())?;
+    Ok(EmailTemplate {
+        subject: plain.render_template(&template.subject, variables).map_err(|e| e.to_string())?,
+        body_html: html.render_template(&template.body_html, variables).map_err(|e| e.to_string())?,
+        body_text: plain.render_template(&template.body_text, variables).map_err(|e| e.to_string())?,
+    })
+}
 
-        let body_text = handlebars
-            .render_template(&template.body_text, variables)
-            .map_err(|e| e.to_string())?;
+/// Checks a campaign template before it's saved: every part must be valid
+/// Handlebars, and each body must carry the unsubscribe link.
+pub fn validate_template(subject: &str, body_html: &str, body_text: Option<&str>) -> Result<(), String> {
+    if subject.trim().is_empty() {
+        return Err("subject is required".to_string());
+    }
 
-        Ok((subject, body_html, body_text))
+    let parts = [("subject", Some(subject)), ("body_html", Some(body_html)), ("body_text", body_text)];
+    for (name, part) in parts {
+        let Some(part) = part else { continue };
+        handlebars::Template::compile(part).map_err(|e| format!("{} is not a valid template: {}", name, e))?;
+    }
+
+    let placeholder = regex::Regex::new(r"\{\{\{?\s*unsubscribe_url\s*\}?\}\}").unwrap();
+    if !placeholder.is_match(body_html) {
+        return Err(format!("body_html must contain {}", UNSUBSCRIBE_PLACEHOLDER));
+    }
+    if body_text.is_some_and(|text| !text.trim().is_empty() && !placeholder.is_match(text)) {
+        return Err(format!("body_text must contain {}", UNSUBSCRIBE_PLACEHOLDER));
     }
+
+    Ok(())
+}
+
+/// Escapes text for HTML content and quoted attributes. Unlike Handlebars'
+/// escaping this leaves `=` alone, so URLs stay readable.
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
 }
 
 fn strip_html(html: &str) -> String {
@@ -408,6 +1523,74 @@ fn strip_html(html: &str) -> String {
 pub struct EmailTemplates;
 
 impl EmailTemplates {
+    /// Sent for campaigns that have no template of their own
+    pub fn campaign_default() -> EmailTemplate {
+        EmailTemplate {
+            subject: "Quick question about {{company}}".to_string(),
+            body_html: r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <style>
+        body { font-family: Arial, sans-serif; line-height: 1.6; color: #333; }
+        .container { max-width: 600px; margin: 0 auto; padding: 20px; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <p>Hi {{firstName}},</p>
+        
+        <p>I noticed {{company}} and wanted to reach out to see if you'd be interested in a quick conversation.</p>
+        
+        <p>Would you be open to a brief 15-minute call this week?</p>
+        
+        <p>Best regards</p>
+
+        <p style="font-size: 12px; color: #999;">
+            Not interested? <a href="{{unsubscribe_url}}">Unsubscribe</a> from all emails,
+            or just <a href="{{campaign_unsubscribe_url}}">this campaign</a>.
+        </p>
+    </div>
+</body>
+</html>
+"#.to_string(),
+            body_text: String::new(),
+        }
+    }
+
+    /// Sent for follow-up steps that have no template of their own
+    pub fn campaign_follow_up() -> EmailTemplate {
+        EmailTemplate {
+            subject: "Re: Quick question about {{company}}".to_string(),
+            body_html: r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <style>
+        body { font-family: Arial, sans-serif; line-height: 1.6; color: #333; }
+        .container { max-width: 600px; margin: 0 auto; padding: 20px; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <p>Hi {{firstName}},</p>
+        
+        <p>Just following up on my last note in case it got buried. Would a quick call about {{company}} be worth 15 minutes this week?</p>
+        
+        <p>Best regards</p>
+
+        <p style="font-size: 12px; color: #999;">
+            Not interested? <a href="{{unsubscribe_url}}">Unsubscribe</a> from all emails,
+            or just <a href="{{campaign_unsubscribe_url}}">this campaign</a>.
+        </p>
+    </div>
+</body>
+</html>
+"#.to_string(),
+            body_text: String::new(),
+        }
+    }
+
     pub fn cold_outreach() -> EmailTemplate {
         EmailTemplate {
             subject: "Quick question about {{company}}".to_string(),
@@ -507,3 +1690,437 @@ Best,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_undecryptable_password_is_an_error_not_a_plaintext_fallback() {
+        let garbage = [0u8; 40];
+        let result = decrypt_inbox_password(Some(&garbage), Some("default-key-v1"), Some("legacy-plaintext"));
+        assert!(result.unwrap_err().starts_with("Failed to decrypt SMTP password"));
+
+        // Rows the backfill hasn't reached yet still work
+        assert_eq!(decrypt_inbox_password(None, None, Some("legacy-plaintext")).unwrap(), "legacy-plaintext");
+        assert!(decrypt_inbox_password(None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_campaign_templates_need_unsubscribe_link() {
+        assert!(validate_template("Hi {{firstName}}", "<a href=\"{{unsubscribe_url}}\">Unsubscribe</a>", None).is_ok());
+        assert!(validate_template("Hi", "<p>{{ unsubscribe_url }}</p>", Some("Opt out: {{unsubscribe_url}}")).is_ok());
+        assert!(validate_template("Hi", "<p>No way out</p>", None).is_err());
+        assert!(validate_template("Hi", "{{unsubscribe_url}}", Some("No way out")).is_err());
+        assert!(validate_template("Hi {{#if}}", "{{unsubscribe_url}}", None).is_err());
+        assert!(validate_template("  ", "{{unsubscribe_url}}", None).is_err());
+        for default in [EmailTemplates::campaign_default(), EmailTemplates::campaign_follow_up()] {
+            assert!(validate_template(&default.subject, &default.body_html, None).is_ok());
+        }
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_unsubscribed_lead_is_never_sent() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Suppression test").await;
+        let email = format!("Unsub-{}@Example.com", Uuid::new_v4());
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let (lead_id, campaign_lead_id) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+
+        // Unsubscribed from everything, as the unsubscribe link records it
+        sqlx::query(
+            "INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at) VALUES ($1, $2, $3, 'unsubscribe', 'user_request', NOW())"
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(email.to_lowercase())
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+
+        let scheduled = crate::services::campaign_scheduler::CampaignScheduler::new(pool.clone())
+            .schedule_campaign_sends(campaign_id)
+            .await;
+
+        // A job queued before the unsubscribe still goes nowhere
+        let sent = CampaignEmailSender::new(pool.clone())
+            .send_campaign_email(&SendEmailJobPayload {
+                campaign_lead_id,
+                campaign_id,
+                lead_id,
+                inbox_id: Uuid::new_v4(),
+                email: email.clone(),
+                step_index: 0,
+            })
+            .await;
+
+        let status: String = sqlx::query_scalar("SELECT status FROM campaign_leads WHERE id = $1")
+            .bind(campaign_lead_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+        let archived: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sent_emails WHERE campaign_id = $1")
+            .bind(campaign_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(scheduled, Ok(0));
+        assert_eq!(sent, Ok(None));
+        assert_eq!(status, "suppressed");
+        assert_eq!(archived, 0);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_sends_past_daily_limit_are_deferred() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Daily limit test").await;
+        let address = CompanyAddress {
+            address_line1: Some("1 Main St".to_string()),
+            city: Some("Springfield".to_string()),
+            country: Some("USA".to_string()),
+            ..Default::default()
+        };
+        mailing_address::save(pool.as_ref(), workspace_id, &address).await.unwrap();
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let inbox_id = test_db::inbox(&pool, workspace_id, 2).await;
+
+        // Added one after another, so they're scheduled in this order
+        let mut campaign_lead_ids = Vec::new();
+        for n in 0..3 {
+            let email = format!("lead{}-{}@example.com", n, Uuid::new_v4());
+            let (lead_id, campaign_lead_id) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+            campaign_lead_ids.push((campaign_lead_id, lead_id));
+        }
+
+        let scheduler = crate::services::campaign_scheduler::CampaignScheduler::new(pool.clone());
+        let first_pass = scheduler.schedule_campaign_sends(campaign_id).await;
+        // Both slots are taken by queued jobs, so another pass adds nothing
+        let second_pass = scheduler.schedule_campaign_sends(campaign_id).await;
+
+        // The first two went out; a third job for the same inbox is deferred, not failed
+        sqlx::query("UPDATE email_accounts SET sent_today = 2 WHERE id = $1")
+            .bind(inbox_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        let (campaign_lead_id, lead_id) = campaign_lead_ids[2];
+        let third = CampaignEmailSender::new(pool.clone())
+            .send_campaign_email(&SendEmailJobPayload {
+                campaign_lead_id,
+                campaign_id,
+                lead_id,
+                inbox_id,
+                email: "third@example.com".to_string(),
+                step_index: 0,
+            })
+            .await;
+        let sent_today: i32 = sqlx::query_scalar("SELECT sent_today FROM email_accounts WHERE id = $1")
+            .bind(inbox_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(first_pass, Ok(2));
+        assert_eq!(second_pass, Ok(0));
+        match third {
+            Err(CampaignSendError::RateLimited { retry_at }) => assert!(retry_at > Utc::now()),
+            other => panic!("expected the third send to be deferred, got {:?}", other),
+        }
+        assert_eq!(sent_today, 2);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_direct_sends_respect_suppression_and_the_daily_limit() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Direct send test").await;
+        let inbox_id = test_db::inbox(&pool, workspace_id, 1).await;
+        // Already at its limit for today
+        sqlx::query("UPDATE email_accounts SET smtp_password = 'secret', sent_today = 1 WHERE id = $1")
+            .bind(inbox_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        let suppressed = format!("gone-{}@example.com", inbox_id);
+        sqlx::query(
+            "INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at) VALUES ($1, $2, $3, 'bounced', 'bounce', NOW())"
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(&suppressed)
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+
+        let sender = CampaignEmailSender::new(pool.clone());
+        let to_suppressed = sender.send_direct(workspace_id, None, &suppressed, "Reminder", "<p>Hi</p>").await;
+        let over_limit = sender.send_direct(workspace_id, None, "lead@example.com", "Reminder", "<p>Hi</p>").await;
+        let sent_today: i32 = sqlx::query_scalar("SELECT sent_today FROM email_accounts WHERE id = $1")
+            .bind(inbox_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+        let used: i32 = sqlx::query_scalar("SELECT emails_sent_this_period FROM workspaces WHERE id = $1")
+            .bind(workspace_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(to_suppressed, Ok(None));
+        assert!(over_limit.unwrap_err().contains("daily limit"));
+        assert_eq!(sent_today, 1);
+        // The quota reserved for the capped send was given back
+        assert_eq!(used, 0);
+    }
+
+    /// Runs the workspace's queued sends the way the worker would, then marks them done
+    async fn run_queued_sends(pool: &Arc<PgPool>, workspace_id: Uuid) -> Vec<Result<Option<String>, CampaignSendError>> {
+        let payloads: Vec<serde_json::Value> =
+            sqlx::query_scalar("SELECT payload FROM jobs WHERE workspace_id = $1 AND status = 'pending'")
+                .bind(workspace_id)
+                .fetch_all(pool.as_ref())
+                .await
+                .unwrap();
+        let sender = CampaignEmailSender::new(pool.clone());
+        let mut outcomes = Vec::new();
+        for payload in payloads {
+            let payload: SendEmailJobPayload = serde_json::from_value(payload).unwrap();
+            outcomes.push(sender.send_campaign_email(&payload).await);
+        }
+        sqlx::query("UPDATE jobs SET status = 'completed' WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        outcomes
+    }
+
+    async fn set_campaign_status(pool: &PgPool, campaign_id: Uuid, status: &str) {
+        sqlx::query("UPDATE campaigns SET status = $2 WHERE id = $1")
+            .bind(campaign_id)
+            .bind(status)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_queued_sends_are_dropped_after_a_pause_and_rescheduled_on_resume() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Paused sends").await;
+        let address = CompanyAddress {
+            address_line1: Some("1 Main St".to_string()),
+            city: Some("Springfield".to_string()),
+            country: Some("USA".to_string()),
+            ..Default::default()
+        };
+        mailing_address::save(pool.as_ref(), workspace_id, &address).await.unwrap();
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        test_db::inbox(&pool, workspace_id, 50).await;
+        let mut lead_ids = Vec::new();
+        for n in 0..2 {
+            let email = format!("lead{}-{}@example.com", n, Uuid::new_v4());
+            let (lead_id, _) = test_db::campaign_lead(&pool, workspace_id, campaign_id, &email).await;
+            lead_ids.push(lead_id);
+        }
+
+        let scheduler = crate::services::campaign_scheduler::CampaignScheduler::new(pool.clone());
+        let queued = scheduler.schedule_campaign_sends(campaign_id).await;
+        set_campaign_status(&pool, campaign_id, "paused").await;
+        let while_paused = run_queued_sends(&pool, workspace_id).await;
+        let statuses: Vec<String> = sqlx::query_scalar("SELECT status FROM campaign_leads WHERE campaign_id = $1")
+            .bind(campaign_id)
+            .fetch_all(pool.as_ref())
+            .await
+            .unwrap();
+
+        // Resuming queues both again; a lead trashed in the meantime isn't sent either
+        set_campaign_status(&pool, campaign_id, "active").await;
+        let requeued = scheduler.schedule_campaign_sends(campaign_id).await;
+        sqlx::query("UPDATE leads SET deleted_at = NOW() WHERE id = ANY($1)")
+            .bind(&lead_ids)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        let after_trash = run_queued_sends(&pool, workspace_id).await;
+        let archived: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sent_emails WHERE campaign_id = $1")
+            .bind(campaign_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(queued, Ok(2));
+        assert_eq!(while_paused, vec![Ok(None), Ok(None)]);
+        assert_eq!(statuses, ["pending", "pending"]);
+        assert_eq!(requeued, Ok(2));
+        assert_eq!(after_trash, vec![Ok(None), Ok(None)]);
+        assert_eq!(archived, 0);
+    }
+
+    #[tokio::test]
+    async fn test_campaign_sends_carry_one_click_unsubscribe_headers() {
+        let sender = CampaignEmailSender::new(Arc::new(PgPool::connect_lazy("postgres://localhost/unused").unwrap()));
+        let inbox = InboxCredentials {
+            id: Uuid::new_v4(),
+            email: "sender@acme.io".to_string(),
+            smtp_host: "smtp.acme.io".to_string(),
+            smtp_port: 587,
+            smtp_username: "sender@acme.io".to_string(),
+            smtp_password: None,
+            smtp_password_encrypted: None,
+            encryption_key_id: None,
+            provider: "other".to_string(),
+            auth_method: "password".to_string(),
+        };
+        let campaign = CampaignDetails {
+            id: Uuid::new_v4(),
+            workspace_id: None,
+            from_name: Some("Acme".to_string()),
+            reply_to: None,
+        };
+        let rendered = EmailTemplate {
+            subject: "Hi".to_string(),
+            body_html: "<p>Hi</p>".to_string(),
+            body_text: "Hi".to_string(),
+        };
+        let url = unsubscribe_url("abc123");
+
+        let (email, _) = sender
+            .build_message(&inbox, &campaign, "lead@example.com", &rendered, Some(&url), None)
+            .await
+            .unwrap();
+        assert_eq!(email.headers().get_raw("List-Unsubscribe"), Some(format!("<{}>", url).as_str()));
+        assert_eq!(email.headers().get_raw("List-Unsubscribe-Post"), Some("List-Unsubscribe=One-Click"));
+
+        // Test sends and one-off messages aren't list mail
+        let (email, _) = sender
+            .build_message(&inbox, &campaign, "lead@example.com", &rendered, None, None)
+            .await
+            .unwrap();
+        assert!(email.headers().get_raw("List-Unsubscribe").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_campaign_emails_end_in_address_and_unsubscribe_footer() {
+        let sender = CampaignEmailSender::new(Arc::new(PgPool::connect_lazy("postgres://localhost/unused").unwrap()));
+        let inbox = InboxCredentials {
+            id: Uuid::new_v4(),
+            email: "sender@acme.io".to_string(),
+            smtp_host: "smtp.acme.io".to_string(),
+            smtp_port: 587,
+            smtp_username: "sender@acme.io".to_string(),
+            smtp_password: None,
+            smtp_password_encrypted: None,
+            encryption_key_id: None,
+            provider: "other".to_string(),
+            auth_method: "password".to_string(),
+        };
+        let campaign = CampaignDetails {
+            id: Uuid::new_v4(),
+            workspace_id: None,
+            from_name: Some("Acme".to_string()),
+            reply_to: None,
+        };
+        let address = CompanyAddress {
+            company_name: Some("Smith & Sons".to_string()),
+            address_line1: Some("1 Main St".to_string()),
+            city: Some("Springfield".to_string()),
+            region: Some("IL".to_string()),
+            postal_code: Some("62701".to_string()),
+            country: Some("USA".to_string()),
+            ..Default::default()
+        };
+        let url = unsubscribe_url("abc123");
+
+        let rendered = sender
+            .render(&LeadDetails::sample(), &campaign, &EmailTemplates::campaign_default(), "abc123", Some(&address))
+            .unwrap();
+
+        let footer_at = rendered.body_html.find("1 Main St").unwrap();
+        assert!(footer_at < rendered.body_html.rfind("</body>").unwrap());
+        assert!(rendered.body_html.contains("Smith &amp; Sons<br>1 Main St<br>Springfield, IL 62701<br>USA"));
+        assert!(rendered.body_html[footer_at..].contains(&format!(r#"<a href="{}">Unsubscribe</a>"#, url)));
+        assert!(rendered.body_text.ends_with(&format!(
+            "--\nSmith & Sons\n1 Main St\nSpringfield, IL 62701\nUSA\nUnsubscribe: {}\n",
+            url
+        )));
+
+        let (email, _) = sender
+            .build_message(&inbox, &campaign, "lead@example.com", &rendered, Some(&url), None)
+            .await
+            .unwrap();
+        let raw = String::from_utf8(email.formatted()).unwrap();
+        assert_eq!(raw.matches("Springfield, IL 62701").count(), 2);
+
+        // Without an address (previews before one is set) the template is left as written
+        let bare = sender
+            .render(&LeadDetails::sample(), &campaign, &EmailTemplates::campaign_default(), "abc123", None)
+            .unwrap();
+        assert!(!bare.body_text.contains("Springfield"));
+    }
+
+    #[test]
+    fn test_html_escape_is_safe_inside_quoted_attributes() {
+        assert_eq!(
+            html_escape(r#"<a href="x" title='O'Brien & co'>"#),
+            "&lt;a href=&quot;x&quot; title=&#39;O&#39;Brien &amp; co&#39;&gt;"
+        );
+    }
+
+    #[test]
+    fn test_merge_fields_escape_only_html() {
+        let template = EmailTemplate {
+            subject: "For {{company}}".to_string(),
+            body_html: "<p>{{company}}</p><a href=\"{{unsubscribe_url}}\">x</a>".to_string(),
+            body_text: "{{company}}: {{unsubscribe_url}}".to_string(),
+        };
+        let variables = HashMap::from([
+            ("company".to_string(), "Smith & Sons".to_string()),
+            ("unsubscribe_url".to_string(), "https://app/unsubscribe?token=a&scope=campaign".to_string()),
+        ]);
+
+        let rendered = render_email_template(&template, &variables).unwrap();
+        assert_eq!(rendered.subject, "For Smith & Sons");
+        assert!(rendered.body_html.contains("<p>Smith &amp; Sons</p>"));
+        assert_eq!(rendered.body_text, "Smith & Sons: https://app/unsubscribe?token=a&scope=campaign");
+    }
+
+    #[test]
+    fn test_custom_fields_render_as_merge_tags() {
+        let lead = LeadDetails {
+            custom_fields: serde_json::json!({
+                "industry": "Fintech",
+                "employees": 120,
+                "company": "Not the real one",
+                "tags": ["a", "b"],
+            }),
+            ..LeadDetails::sample()
+        };
+        let template = EmailTemplate {
+            subject: "{{industry}} at {{company}}".to_string(),
+            body_html: "<p>{{employees}} people{{tags}}{{missing}}</p>".to_string(),
+            body_text: String::new(),
+        };
+
+        let rendered = render_email_template(&template, &merge_fields(&lead)).unwrap();
+        assert_eq!(rendered.subject, "Fintech at Example Inc");
+        assert_eq!(rendered.body_html, "<p>120 people</p>");
+    }
+}
@@ -1,53 +1,500 @@
 use crate::models::lead::VerificationStatus;
 use trust_dns_resolver::TokioAsyncResolver;
 use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
+use trust_dns_resolver::proto::op::ResponseCode;
 use regex::Regex;
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::BoxFuture;
+use sqlx::PgPool;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
 
-pub struct EmailVerifier {
+static EMAIL_SYNTAX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap()
+});
+
+/// Whether `email` looks like a deliverable address, before any DNS checks
+pub fn is_valid_syntax(email: &str) -> bool {
+    EMAIL_SYNTAX.is_match(email)
+}
+
+fn random_local_part() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(20)
+        .map(|c| (c as char).to_ascii_lowercase())
+        .collect()
+}
+
+/// Cached results are reused for this long before the address is checked again.
+pub const VERIFICATION_CACHE_TTL_DAYS: i64 = 30;
+
+/// Longest a whole SMTP probe may take, connection included
+const SMTP_PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// MX lookups and catch-all findings are reused for this long per domain
+pub const DOMAIN_CACHE_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone)]
+pub struct CachedVerification {
+    pub status: VerificationStatus,
+    pub confidence: f32,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Storage for verification results, keyed by lowercased email.
+pub trait VerificationCache: Send + Sync {
+    fn get<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Option<CachedVerification>>;
+    fn put<'a>(&'a self, email: &'a str, result: &'a CachedVerification) -> BoxFuture<'a, ()>;
+}
+
+/// Cache backed by the `email_verifications` table. Lookup and write failures
+/// are logged and treated as a miss so verification never fails on the cache.
+pub struct PgVerificationCache {
+    pool: Arc<PgPool>,
+}
+
+impl PgVerificationCache {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl VerificationCache for PgVerificationCache {
+    fn get<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Option<CachedVerification>> {
+        Box::pin(async move {
+            let row = sqlx::query_as::<_, (String, f32, DateTime<Utc>)>(
+                "SELECT status, confidence, checked_at FROM email_verifications WHERE email = $1"
+            )
+            .bind(email)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Verification cache lookup failed: {}", e);
+                None
+            })?;
+
+            Some(CachedVerification {
+                status: VerificationStatus::parse(&row.0)?,
+                confidence: row.1,
+                checked_at: row.2,
+            })
+        })
+    }
+
+    fn put<'a>(&'a self, email: &'a str, result: &'a CachedVerification) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let stored = sqlx::query(
+                r#"
+                INSERT INTO email_verifications (email, status, confidence, checked_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (email) DO UPDATE SET
+                    status = EXCLUDED.status,
+                    confidence = EXCLUDED.confidence,
+                    checked_at = EXCLUDED.checked_at
+                "#
+            )
+            .bind(email)
+            .bind(result.status.as_str())
+            .bind(result.confidence)
+            .bind(result.checked_at)
+            .execute(self.pool.as_ref())
+            .await;
+
+            if let Err(e) = stored {
+                tracing::warn!("Failed to cache verification for {}: {}", email, e);
+            }
+        })
+    }
+}
+
+/// Mail servers for a domain, as far as DNS can tell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MxLookup {
+    /// Hosts to deliver to, most preferred first
+    Hosts(Vec<String>),
+    /// The domain doesn't exist or publishes no mail server
+    NoMailServer,
+    /// DNS couldn't answer (timeout, SERVFAIL); says nothing about the address
+    Failed(String),
+}
+
+pub trait MxResolver: Send + Sync {
+    fn lookup_mx<'a>(&'a self, domain: &'a str) -> BoxFuture<'a, MxLookup>;
+}
+
+/// Resolves MX records, falling back to the domain's own address records when it
+/// has none, as RFC 5321 allows. A null MX (RFC 7505) means no mail is accepted.
+pub struct DnsMxResolver {
     resolver: TokioAsyncResolver,
 }
 
+impl DnsMxResolver {
+    pub fn new() -> Self {
+        Self {
+            resolver: TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()),
+        }
+    }
+}
+
+impl Default for DnsMxResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_records(error: &ResolveError) -> Option<ResponseCode> {
+    match error.kind() {
+        ResolveErrorKind::NoRecordsFound { response_code, .. } => Some(*response_code),
+        _ => None,
+    }
+}
+
+impl MxResolver for DnsMxResolver {
+    fn lookup_mx<'a>(&'a self, domain: &'a str) -> BoxFuture<'a, MxLookup> {
+        Box::pin(async move {
+            match self.resolver.mx_lookup(domain).await {
+                Ok(records) => {
+                    let mut records: Vec<_> = records.iter().collect();
+                    records.sort_by_key(|mx| mx.preference());
+                    let hosts: Vec<String> = records
+                        .iter()
+                        .map(|mx| mx.exchange().to_utf8().trim_end_matches('.').to_string())
+                        .filter(|host| !host.is_empty())
+                        .collect();
+                    if hosts.is_empty() {
+                        MxLookup::NoMailServer
+                    } else {
+                        MxLookup::Hosts(hosts)
+                    }
+                }
+                Err(e) => match no_records(&e) {
+                    Some(ResponseCode::NXDomain) => MxLookup::NoMailServer,
+                    Some(_) => match self.resolver.lookup_ip(domain).await {
+                        Ok(ips) if ips.iter().next().is_some() => MxLookup::Hosts(vec![domain.to_string()]),
+                        Ok(_) => MxLookup::NoMailServer,
+                        Err(e) if no_records(&e).is_some() => MxLookup::NoMailServer,
+                        Err(e) => MxLookup::Failed(e.to_string()),
+                    },
+                    None => MxLookup::Failed(e.to_string()),
+                },
+            }
+        })
+    }
+}
+
+/// What a mail server said to `RCPT TO`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcptResult {
+    Accepted,
+    /// Permanent rejection: the mailbox doesn't exist
+    Rejected,
+    /// Temporary failure, greylisting or a policy block; says nothing about the mailbox
+    Unknown,
+}
+
+pub trait SmtpProber: Send + Sync {
+    /// Opens one session to `mx_host` and asks about each recipient without sending
+    /// anything. An error means the probe couldn't be made at all.
+    fn probe<'a>(&'a self, mx_host: &'a str, recipients: &'a [String]) -> BoxFuture<'a, Result<Vec<RcptResult>, String>>;
+}
+
+/// Probes over plain SMTP on port 25. Many networks block outbound port 25 and
+/// some providers flag senders that probe, so this only runs when
+/// `EMAIL_VERIFY_SMTP_PROBE=true`.
+pub struct TcpSmtpProber {
+    mail_from: String,
+    timeout: std::time::Duration,
+}
+
+impl TcpSmtpProber {
+    /// Set from `EMAIL_VERIFY_SMTP_PROBE` and `EMAIL_VERIFY_MAIL_FROM`; `None` when probing is off
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("EMAIL_VERIFY_SMTP_PROBE")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        enabled.then(|| Self {
+            mail_from: std::env::var("EMAIL_VERIFY_MAIL_FROM").unwrap_or_else(|_| "verify@outreachiq.com".to_string()),
+            timeout: std::time::Duration::from_secs(SMTP_PROBE_TIMEOUT_SECS),
+        })
+    }
+
+    async fn session(&self, mx_host: &str, recipients: &[String]) -> Result<Vec<RcptResult>, String> {
+        let stream = TcpStream::connect((mx_host, 25)).await.map_err(|e| e.to_string())?;
+        let mut conn = BufReader::new(stream);
+
+        let helo_domain = self.mail_from.rsplit('@').next().unwrap_or("localhost");
+        expect_2xx(read_reply(&mut conn).await?, "greeting")?;
+        if smtp_command(&mut conn, &format!("EHLO {}", helo_domain)).await?.0 / 100 != 2 {
+            expect_2xx(smtp_command(&mut conn, &format!("HELO {}", helo_domain)).await?, "HELO")?;
+        }
+        expect_2xx(smtp_command(&mut conn, &format!("MAIL FROM:<{}>", self.mail_from)).await?, "MAIL FROM")?;
+
+        let mut results = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            let (code, text) = smtp_command(&mut conn, &format!("RCPT TO:<{}>", recipient)).await?;
+            results.push(match code / 100 {
+                2 => RcptResult::Accepted,
+                // 5.7.x is a policy block against us, not a verdict on the mailbox
+                5 if !text.contains("5.7.") => RcptResult::Rejected,
+                _ => RcptResult::Unknown,
+            });
+        }
+
+        let _ = smtp_command(&mut conn, "QUIT").await;
+        Ok(results)
+    }
+}
+
+impl SmtpProber for TcpSmtpProber {
+    fn probe<'a>(&'a self, mx_host: &'a str, recipients: &'a [String]) -> BoxFuture<'a, Result<Vec<RcptResult>, String>> {
+        Box::pin(async move {
+            tokio::time::timeout(self.timeout, self.session(mx_host, recipients))
+                .await
+                .map_err(|_| format!("SMTP probe of {} timed out", mx_host))?
+        })
+    }
+}
+
+/// Reads one reply, joining the lines of a multi-line response
+async fn read_reply(conn: &mut BufReader<TcpStream>) -> Result<(u16, String), String> {
+    let mut text = String::new();
+    loop {
+        let mut line = String::new();
+        if conn.read_line(&mut line).await.map_err(|e| e.to_string())? == 0 {
+            return Err("Connection closed".to_string());
+        }
+        let code = line.get(..3).and_then(|c| c.parse::<u16>().ok()).ok_or("Malformed SMTP reply")?;
+        text.push_str(line.get(4..).unwrap_or("").trim_end());
+        text.push(' ');
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok((code, text.trim_end().to_string()));
+        }
+    }
+}
+
+async fn smtp_command(conn: &mut BufReader<TcpStream>, command: &str) -> Result<(u16, String), String> {
+    conn.get_mut()
+        .write_all(format!("{}\r\n", command).as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    read_reply(conn).await
+}
+
+fn expect_2xx((code, text): (u16, String), step: &str) -> Result<(), String> {
+    if code / 100 == 2 {
+        Ok(())
+    } else {
+        Err(format!("{} refused: {} {}", step, code, text))
+    }
+}
+
+/// What's been learned about a domain
+#[derive(Debug, Clone)]
+struct DomainInfo {
+    mx: MxLookup,
+    /// Whether the server accepts any recipient; `None` until a probe finds out
+    catch_all: Option<bool>,
+    /// The server wouldn't talk to us, so don't try probing it again
+    probe_unavailable: bool,
+    checked_at: DateTime<Utc>,
+}
+
+/// Per-domain facts shared by every verifier in the process, so a list of
+/// addresses at one company costs one DNS lookup and at most a few probes.
+#[derive(Default)]
+struct DomainCache {
+    domains: Mutex<HashMap<String, DomainInfo>>,
+}
+
+impl DomainCache {
+    fn get(&self, domain: &str) -> Option<DomainInfo> {
+        let mut domains = self.domains.lock().unwrap_or_else(|e| e.into_inner());
+        domains.retain(|_, info| info.checked_at > Utc::now() - Duration::hours(DOMAIN_CACHE_TTL_HOURS));
+        domains.get(domain).cloned()
+    }
+
+    fn update(&self, domain: &str, info: DomainInfo) {
+        self.domains.lock().unwrap_or_else(|e| e.into_inner()).insert(domain.to_string(), info);
+    }
+}
+
+static SHARED_DOMAINS: LazyLock<Arc<DomainCache>> = LazyLock::new(Default::default);
+
+pub struct EmailVerifier {
+    resolver: Arc<dyn MxResolver>,
+    prober: Option<Arc<dyn SmtpProber>>,
+    domains: Arc<DomainCache>,
+    cache: Option<Arc<dyn VerificationCache>>,
+}
+
 impl EmailVerifier {
     pub async fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let resolver = TokioAsyncResolver::tokio(
-            ResolverConfig::default(),
-            ResolverOpts::default(),
-        );
-        Ok(Self { resolver })
+        Ok(Self {
+            resolver: Arc::new(DnsMxResolver::new()),
+            prober: TcpSmtpProber::from_env().map(|p| Arc::new(p) as Arc<dyn SmtpProber>),
+            domains: SHARED_DOMAINS.clone(),
+            cache: None,
+        })
+    }
+
+    pub fn with_cache(mut self, cache: Arc<dyn VerificationCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Uses `resolver` for MX lookups. What's known about domains came from the old
+    /// resolver, so the verifier starts a domain cache of its own.
+    pub fn with_resolver(mut self, resolver: Arc<dyn MxResolver>) -> Self {
+        self.resolver = resolver;
+        self.domains = Arc::default();
+        self
     }
 
+    /// Probes mailboxes with `prober`, or skips SMTP probing when `None`
+    pub fn with_prober(mut self, prober: Option<Arc<dyn SmtpProber>>) -> Self {
+        self.prober = prober;
+        self
+    }
+
+    /// Verifies an address, reusing a cached result younger than the TTL.
     pub async fn verify_email(&self, email: &str) -> (VerificationStatus, f32) {
-        // Syntax check
-        if !self.is_valid_syntax(email) {
+        self.verify_email_with(email, false).await
+    }
+
+    /// Like `verify_email`, but `force` skips the cache lookup (the fresh result is still cached).
+    /// Inconclusive results (`Pending`, e.g. DNS was down) aren't cached so the next call retries.
+    pub async fn verify_email_with(&self, email: &str, force: bool) -> (VerificationStatus, f32) {
+        let Some(cache) = &self.cache else {
+            return self.check_email(email).await;
+        };
+
+        let key = email.trim().to_lowercase();
+        if !force {
+            if let Some(cached) = cache.get(&key).await {
+                if cached.checked_at > Utc::now() - Duration::days(VERIFICATION_CACHE_TTL_DAYS) {
+                    return (cached.status, cached.confidence);
+                }
+            }
+        }
+
+        let (status, confidence) = self.check_email(email).await;
+        if status != VerificationStatus::Pending {
+            cache
+                .put(&key, &CachedVerification {
+                    status: status.clone(),
+                    confidence,
+                    checked_at: Utc::now(),
+                })
+                .await;
+        }
+        (status, confidence)
+    }
+
+    async fn check_email(&self, email: &str) -> (VerificationStatus, f32) {
+        let email = email.trim();
+        if !is_valid_syntax(email) {
             return (VerificationStatus::Invalid, 0.0);
         }
 
-        // Extract domain
-        let domain = match email.split('@').nth(1) {
-            Some(d) => d,
-            None => return (VerificationStatus::Invalid, 0.0),
+        let Some((local, domain)) = email.rsplit_once('@') else {
+            return (VerificationStatus::Invalid, 0.0);
+        };
+        let domain = domain.to_lowercase();
+
+        let mut info = match self.domains.get(&domain) {
+            Some(info) => info,
+            None => {
+                let info = DomainInfo {
+                    mx: self.resolver.lookup_mx(&domain).await,
+                    catch_all: None,
+                    probe_unavailable: false,
+                    checked_at: Utc::now(),
+                };
+                // A failed lookup is retried next time rather than remembered
+                if !matches!(info.mx, MxLookup::Failed(_)) {
+                    self.domains.update(&domain, info.clone());
+                }
+                info
+            }
+        };
+
+        let hosts = match &info.mx {
+            MxLookup::Hosts(hosts) => hosts.clone(),
+            MxLookup::NoMailServer => return (VerificationStatus::Invalid, 0.05),
+            MxLookup::Failed(e) => {
+                tracing::warn!("MX lookup for {} failed: {}", domain, e);
+                return (VerificationStatus::Pending, 0.0);
+            }
         };
-        
-        // MX record check
-        let mx_valid = self.check_mx_record(domain).await;
-        if !mx_valid {
-            return (VerificationStatus::Invalid, 0.2);
-        }
 
-        // Disposable email check
-        if self.is_disposable_domain(domain) {
+        if self.is_disposable_domain(&domain) {
             return (VerificationStatus::Risky, 0.3);
         }
 
-        // Role-based email check
-        let local = email.split('@').next().unwrap_or("");
         if self.is_role_based(local) {
             return (VerificationStatus::Risky, 0.4);
         }
 
-        // Calculate confidence score
-        let confidence = self.calculate_confidence(email, domain);
+        let heuristic = self.calculate_confidence(email, &domain);
+
+        let Some(prober) = self.prober.as_ref().filter(|_| !info.probe_unavailable) else {
+            return Self::unprobed(heuristic);
+        };
+
+        // Once a domain is known to accept anything, probing its addresses tells us nothing
+        if info.catch_all == Some(true) {
+            return Self::catch_all(heuristic);
+        }
+
+        // A made-up mailbox alongside the real one shows whether the server accepts everything
+        let canary = format!("{}@{}", random_local_part(), domain);
+        let recipients = [email.to_string(), canary];
+
+        let mut probed = None;
+        for host in hosts.iter().take(2) {
+            match prober.probe(host, &recipients).await {
+                Ok(results) => {
+                    probed = Some(results);
+                    break;
+                }
+                Err(e) => tracing::debug!("SMTP probe of {} via {} failed: {}", domain, host, e),
+            }
+        }
+
+        let Some(results) = probed else {
+            info.probe_unavailable = true;
+            self.domains.update(&domain, info);
+            return Self::unprobed(heuristic);
+        };
 
+        match (results.first(), results.get(1)) {
+            (Some(RcptResult::Rejected), _) => (VerificationStatus::Invalid, 0.05),
+            (Some(RcptResult::Accepted), Some(RcptResult::Accepted)) => {
+                info.catch_all = Some(true);
+                self.domains.update(&domain, info);
+                Self::catch_all(heuristic)
+            }
+            (Some(RcptResult::Accepted), Some(RcptResult::Rejected)) => {
+                info.catch_all = Some(false);
+                self.domains.update(&domain, info);
+                (VerificationStatus::Valid, 0.95)
+            }
+            // Accepted but the canary was greylisted, or the real address was deferred
+            (Some(RcptResult::Accepted), _) => (VerificationStatus::Valid, 0.85),
+            _ => Self::unprobed(heuristic),
+        }
+    }
+
+    /// The domain takes mail but the mailbox itself wasn't checked, so the address
+    /// pattern is all there is to go on and confidence stays below a probed result.
+    fn unprobed(heuristic: f32) -> (VerificationStatus, f32) {
+        let confidence = heuristic.min(0.8);
         if confidence > 0.7 {
             (VerificationStatus::Valid, confidence)
         } else if confidence > 0.4 {
@@ -57,6 +504,11 @@ impl EmailVerifier {
         }
     }
 
+    /// A catch-all server accepted the address, which it would do whether or not the mailbox exists
+    fn catch_all(heuristic: f32) -> (VerificationStatus, f32) {
+        (VerificationStatus::Risky, (heuristic * 0.7).clamp(0.3, 0.6))
+    }
+
     pub async fn verify_batch(&self, emails: &[String]) -> Vec<(String, VerificationStatus, f32)> {
         let mut results = Vec::new();
         for email in emails {
@@ -66,20 +518,6 @@ impl EmailVerifier {
         results
     }
 
-    fn is_valid_syntax(&self, email: &str) -> bool {
-        let email_regex = Regex::new(
-            r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$"
-        ).unwrap();
-        email_regex.is_match(email)
-    }
-
-    async fn check_mx_record(&self, domain: &str) -> bool {
-        match self.resolver.mx_lookup(domain).await {
-            Ok(mx_records) => mx_records.iter().next().is_some(),
-            Err(_) => false,
-        }
-    }
-
     fn is_disposable_domain(&self, domain: &str) -> bool {
         let disposable_domains = vec![
             "tempmail.com", "guerrillamail.com", "10minutemail.com",
@@ -119,7 +557,7 @@ impl EmailVerifier {
         }
 
         // Not a generic address
-        let generic = vec!["info", "contact", "support", "sales", "admin"];
+        let generic = ["info", "contact", "support", "sales", "admin"];
         if !generic.iter().any(|g| local.starts_with(g)) {
             score += 0.1;
         }
@@ -127,3 +565,137 @@ impl EmailVerifier {
         score.min(1.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct MemoryCache {
+        entries: Mutex<HashMap<String, CachedVerification>>,
+        hits: AtomicUsize,
+    }
+
+    impl VerificationCache for MemoryCache {
+        fn get<'a>(&'a self, email: &'a str) -> BoxFuture<'a, Option<CachedVerification>> {
+            let entry = self.entries.lock().unwrap().get(email).cloned();
+            if entry.is_some() {
+                self.hits.fetch_add(1, Ordering::SeqCst);
+            }
+            Box::pin(async move { entry })
+        }
+
+        fn put<'a>(&'a self, email: &'a str, result: &'a CachedVerification) -> BoxFuture<'a, ()> {
+            self.entries.lock().unwrap().insert(email.to_string(), result.clone());
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn test_second_verification_within_ttl_uses_cache() {
+        let cache = Arc::new(MemoryCache::default());
+        let verifier = EmailVerifier::new().await.unwrap().with_cache(cache.clone());
+
+        // Seed a fresh "valid" result for an address that would fail a real check
+        cache.entries.lock().unwrap().insert(
+            "jane@acme.io".to_string(),
+            CachedVerification { status: VerificationStatus::Valid, confidence: 0.9, checked_at: Utc::now() },
+        );
+        let (status, _) = verifier.verify_email("Jane@Acme.io").await;
+        assert_eq!(status, VerificationStatus::Valid);
+        assert_eq!(cache.hits.load(Ordering::SeqCst), 1);
+
+        // A miss is verified and stored, so the next call is served from the cache
+        verifier.verify_email("not-an-email").await;
+        let (status, confidence) = verifier.verify_email("not-an-email").await;
+        assert_eq!((status, confidence), (VerificationStatus::Invalid, 0.0));
+        assert_eq!(cache.hits.load(Ordering::SeqCst), 2);
+
+        // Forced checks skip the lookup
+        verifier.verify_email_with("not-an-email", true).await;
+        assert_eq!(cache.hits.load(Ordering::SeqCst), 2);
+    }
+
+    struct FakeResolver {
+        domains: HashMap<&'static str, MxLookup>,
+        lookups: AtomicUsize,
+    }
+
+    impl MxResolver for FakeResolver {
+        fn lookup_mx<'a>(&'a self, domain: &'a str) -> BoxFuture<'a, MxLookup> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            let result = self.domains.get(domain).cloned().unwrap_or(MxLookup::NoMailServer);
+            Box::pin(async move { result })
+        }
+    }
+
+    /// Accepts the listed mailboxes, or every mailbox on a catch-all host
+    struct FakeProber {
+        mailboxes: Vec<&'static str>,
+        catch_all_hosts: Vec<&'static str>,
+        probes: AtomicUsize,
+    }
+
+    impl SmtpProber for FakeProber {
+        fn probe<'a>(&'a self, mx_host: &'a str, recipients: &'a [String]) -> BoxFuture<'a, Result<Vec<RcptResult>, String>> {
+            self.probes.fetch_add(1, Ordering::SeqCst);
+            let results = recipients
+                .iter()
+                .map(|r| {
+                    if self.catch_all_hosts.contains(&mx_host) || self.mailboxes.contains(&r.as_str()) {
+                        RcptResult::Accepted
+                    } else {
+                        RcptResult::Rejected
+                    }
+                })
+                .collect();
+            Box::pin(async move { Ok(results) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mx_and_smtp_probe_classify_addresses() {
+        let resolver = Arc::new(FakeResolver {
+            domains: HashMap::from([
+                ("acme.io", MxLookup::Hosts(vec!["mx1.acme.io".to_string(), "mx2.acme.io".to_string()])),
+                ("catchall.com", MxLookup::Hosts(vec!["mx.catchall.com".to_string()])),
+            ]),
+            lookups: AtomicUsize::new(0),
+        });
+        let prober = Arc::new(FakeProber {
+            mailboxes: vec!["jane.doe@acme.io"],
+            catch_all_hosts: vec!["mx.catchall.com"],
+            probes: AtomicUsize::new(0),
+        });
+        let verifier = EmailVerifier::new()
+            .await
+            .unwrap()
+            .with_resolver(resolver.clone())
+            .with_prober(Some(prober.clone()));
+
+        assert_eq!(verifier.verify_email("jane.doe@acme.io").await, (VerificationStatus::Valid, 0.95));
+        assert_eq!(verifier.verify_email("john.gone@acme.io").await, (VerificationStatus::Invalid, 0.05));
+        assert_eq!(verifier.verify_email("someone@no-such-domain.io").await, (VerificationStatus::Invalid, 0.05));
+
+        // Anything is accepted, so the address can't be more than risky
+        let (status, confidence) = verifier.verify_email("jane.doe@catchall.com").await;
+        assert_eq!(status, VerificationStatus::Risky);
+        assert!(confidence <= 0.6);
+
+        // The domain is remembered as catch-all: no new lookup or probe
+        let (lookups, probes) = (resolver.lookups.load(Ordering::SeqCst), prober.probes.load(Ordering::SeqCst));
+        assert_eq!(verifier.verify_email("bob.smith@catchall.com").await.0, VerificationStatus::Risky);
+        assert_eq!(resolver.lookups.load(Ordering::SeqCst), lookups);
+        assert_eq!(prober.probes.load(Ordering::SeqCst), probes);
+        assert_eq!(lookups, 3);
+
+        // Without probing, a domain with mail servers is only as good as the address looks
+        let unprobed = EmailVerifier::new().await.unwrap().with_resolver(resolver.clone()).with_prober(None);
+        let (status, confidence) = unprobed.verify_email("john.gone@acme.io").await;
+        assert_eq!(status, VerificationStatus::Valid);
+        assert!(confidence <= 0.8);
+    }
+}
@@ -0,0 +1,532 @@
+use chrono::{DateTime, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::bounces::{self, BounceSource, BounceType, NewBounce};
+
+/// Signed webhooks older than this are rejected to limit replays.
+const MAX_SIGNATURE_AGE_SECS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmailEventType {
+    Delivered,
+    Opened,
+    Clicked,
+    Bounced,
+    SpamReport,
+}
+
+impl EmailEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailEventType::Delivered => "delivered",
+            EmailEventType::Opened => "opened",
+            EmailEventType::Clicked => "clicked",
+            EmailEventType::Bounced => "bounced",
+            EmailEventType::SpamReport => "spam_report",
+        }
+    }
+}
+
+/// A provider event normalized to what we track.
+#[derive(Debug, Clone)]
+pub struct ProviderEvent {
+    pub event_type: EmailEventType,
+    pub recipient: String,
+    pub provider_event_id: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub reason: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+/// Provider-specific signature checking and payload parsing for delivery webhooks.
+pub trait EmailEventProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `header` looks up a request header by (case-insensitive) name.
+    fn verify(&self, header: &dyn Fn(&str) -> Option<String>, body: &[u8]) -> Result<(), String>;
+
+    /// Events the provider reports that we don't track are skipped.
+    fn parse(&self, body: &[u8]) -> Result<Vec<ProviderEvent>, String>;
+}
+
+/// Looks up the provider by its URL segment. Signing secrets come from
+/// `SENDGRID_WEBHOOK_PUBLIC_KEY` and `MAILGUN_WEBHOOK_SIGNING_KEY`.
+pub fn provider_for(name: &str) -> Option<Box<dyn EmailEventProvider>> {
+    match name.to_lowercase().as_str() {
+        "sendgrid" => Some(Box::new(SendGridProvider {
+            public_key: std::env::var("SENDGRID_WEBHOOK_PUBLIC_KEY").ok(),
+        })),
+        "mailgun" => Some(Box::new(MailgunProvider {
+            signing_key: std::env::var("MAILGUN_WEBHOOK_SIGNING_KEY").ok(),
+        })),
+        _ => None,
+    }
+}
+
+fn check_signature_age(timestamp: i64) -> Result<(), String> {
+    if (Utc::now().timestamp() - timestamp).abs() > MAX_SIGNATURE_AGE_SECS {
+        return Err("Webhook timestamp outside the allowed window".to_string());
+    }
+    Ok(())
+}
+
+/// Providers report our Message-ID with or without angle brackets; sent_emails
+/// stores it as `<id@domain>`.
+fn normalize_message_id(raw: &str) -> Option<String> {
+    let id = raw.trim().trim_matches(['<', '>']);
+    (!id.is_empty()).then(|| format!("<{}>", id))
+}
+
+fn timestamp_to_datetime(seconds: f64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds.trunc() as i64, 0)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+// ============================================================================
+// SENDGRID
+// ============================================================================
+
+/// SendGrid Event Webhook with signed payloads (ECDSA P-256 over timestamp + body).
+pub struct SendGridProvider {
+    public_key: Option<String>,
+}
+
+impl EmailEventProvider for SendGridProvider {
+    fn name(&self) -> &'static str {
+        "sendgrid"
+    }
+
+    fn verify(&self, header: &dyn Fn(&str) -> Option<String>, body: &[u8]) -> Result<(), String> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+        use p256::pkcs8::DecodePublicKey;
+
+        let public_key = self.public_key.as_deref().ok_or("SendGrid webhook key not configured")?;
+        let signature = header("X-Twilio-Email-Event-Webhook-Signature").ok_or("Missing signature header")?;
+        let timestamp = header("X-Twilio-Email-Event-Webhook-Timestamp").ok_or("Missing timestamp header")?;
+        check_signature_age(timestamp.trim().parse().map_err(|_| "Invalid timestamp header")?)?;
+
+        let key_der = STANDARD.decode(public_key.trim()).map_err(|e| format!("Invalid public key: {}", e))?;
+        let key = VerifyingKey::from_public_key_der(&key_der).map_err(|e| format!("Invalid public key: {}", e))?;
+        let signature_der = STANDARD.decode(signature.trim()).map_err(|_| "Invalid signature encoding")?;
+        let signature = Signature::from_der(&signature_der).map_err(|_| "Invalid signature")?;
+
+        let mut signed = timestamp.into_bytes();
+        signed.extend_from_slice(body);
+        key.verify(&signed, &signature).map_err(|_| "Signature mismatch".to_string())
+    }
+
+    fn parse(&self, body: &[u8]) -> Result<Vec<ProviderEvent>, String> {
+        let events: Vec<serde_json::Value> =
+            serde_json::from_slice(body).map_err(|e| format!("Invalid payload: {}", e))?;
+
+        Ok(events
+            .into_iter()
+            .filter_map(|event| {
+                let event_type = match event["event"].as_str()? {
+                    "delivered" => EmailEventType::Delivered,
+                    "open" => EmailEventType::Opened,
+                    "click" => EmailEventType::Clicked,
+                    "bounce" | "dropped" => EmailEventType::Bounced,
+                    "spamreport" => EmailEventType::SpamReport,
+                    _ => return None,
+                };
+                Some(ProviderEvent {
+                    event_type,
+                    recipient: event["email"].as_str()?.trim().to_lowercase(),
+                    provider_event_id: event["sg_event_id"].as_str().map(str::to_string),
+                    // smtp-id carries the Message-ID header we set, not SendGrid's own id
+                    provider_message_id: event["smtp-id"].as_str().and_then(normalize_message_id),
+                    reason: event["reason"].as_str().map(str::to_string),
+                    occurred_at: timestamp_to_datetime(event["timestamp"].as_f64().unwrap_or(0.0)),
+                    payload: event,
+                })
+            })
+            .collect())
+    }
+}
+
+// ============================================================================
+// MAILGUN
+// ============================================================================
+
+/// Mailgun webhooks: one event per request, HMAC-SHA256 of timestamp + token.
+pub struct MailgunProvider {
+    signing_key: Option<String>,
+}
+
+impl EmailEventProvider for MailgunProvider {
+    fn name(&self) -> &'static str {
+        "mailgun"
+    }
+
+    fn verify(&self, _header: &dyn Fn(&str) -> Option<String>, body: &[u8]) -> Result<(), String> {
+        let signing_key = self.signing_key.as_deref().ok_or("Mailgun signing key not configured")?;
+        let payload: serde_json::Value =
+            serde_json::from_slice(body).map_err(|e| format!("Invalid payload: {}", e))?;
+        let signature = &payload["signature"];
+
+        let timestamp = signature["timestamp"].as_str().ok_or("Missing signature timestamp")?;
+        let token = signature["token"].as_str().ok_or("Missing signature token")?;
+        let expected = signature["signature"].as_str().ok_or("Missing signature")?;
+        check_signature_age(timestamp.parse().map_err(|_| "Invalid signature timestamp")?)?;
+
+        let expected = hex::decode(expected).map_err(|_| "Invalid signature encoding")?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(signing_key.as_bytes())
+            .map_err(|e| format!("Invalid signing key: {}", e))?;
+        mac.update(timestamp.as_bytes());
+        mac.update(token.as_bytes());
+        mac.verify_slice(&expected).map_err(|_| "Signature mismatch".to_string())
+    }
+
+    fn parse(&self, body: &[u8]) -> Result<Vec<ProviderEvent>, String> {
+        let payload: serde_json::Value =
+            serde_json::from_slice(body).map_err(|e| format!("Invalid payload: {}", e))?;
+        let event = payload["event-data"].clone();
+
+        let event_type = match event["event"].as_str().unwrap_or_default() {
+            "delivered" => EmailEventType::Delivered,
+            "opened" => EmailEventType::Opened,
+            "clicked" => EmailEventType::Clicked,
+            // Temporary failures are retried by Mailgun and don't count as bounces
+            "failed" if event["severity"].as_str() == Some("permanent") => EmailEventType::Bounced,
+            "complained" => EmailEventType::SpamReport,
+            _ => return Ok(vec![]),
+        };
+        let recipient = event["recipient"]
+            .as_str()
+            .ok_or("Missing recipient")?
+            .trim()
+            .to_lowercase();
+
+        Ok(vec![ProviderEvent {
+            event_type,
+            recipient,
+            provider_event_id: event["id"].as_str().map(str::to_string),
+            provider_message_id: event["message"]["headers"]["message-id"].as_str().and_then(normalize_message_id),
+            reason: event["delivery-status"]["description"]
+                .as_str()
+                .or(event["reason"].as_str())
+                .map(str::to_string),
+            occurred_at: timestamp_to_datetime(event["timestamp"].as_f64().unwrap_or(0.0)),
+            payload: event,
+        }])
+    }
+}
+
+// ============================================================================
+// RECORDING
+// ============================================================================
+
+#[derive(Debug, sqlx::FromRow)]
+struct SentEmailMatch {
+    campaign_lead_id: Uuid,
+    campaign_id: Uuid,
+    workspace_id: Option<Uuid>,
+    email_account_id: Option<Uuid>,
+}
+
+/// Stores the events and rolls them into campaign counters and inbox rates.
+/// Events are matched to the archived send by Message-ID and recipient, and
+/// attributed to that send's workspace; events that match no send are dropped
+/// rather than guessed at. Redelivered events (same provider event id) are
+/// ignored. Returns how many were new.
+pub async fn record_events(pool: &PgPool, provider: &str, events: &[ProviderEvent]) -> Result<usize, sqlx::Error> {
+    let mut recorded = 0;
+
+    for event in events {
+        let Some(message_id) = event.provider_message_id.as_deref() else {
+            tracing::debug!("Dropping {} {} event without a Message-ID", provider, event.event_type.as_str());
+            continue;
+        };
+
+        let mut tx = pool.begin().await?;
+
+        let sent = sqlx::query_as::<_, SentEmailMatch>(
+            r#"
+            SELECT se.campaign_lead_id, se.campaign_id, se.workspace_id, se.email_account_id
+            FROM sent_emails se
+            JOIN campaigns c ON c.id = se.campaign_id AND c.workspace_id IS NOT DISTINCT FROM se.workspace_id
+            WHERE se.message_id = $1 AND LOWER(se.to_email) = $2
+            "#
+        )
+        .bind(message_id)
+        .bind(&event.recipient)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(sent) = sent else {
+            tracing::debug!("Dropping {} event for unknown message {}", provider, message_id);
+            tx.rollback().await?;
+            continue;
+        };
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO email_events (
+                workspace_id, campaign_id, campaign_lead_id, email_account_id, provider,
+                event_type, recipient, provider_event_id, provider_message_id, reason, occurred_at, payload
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (provider, provider_event_id) WHERE provider_event_id IS NOT NULL DO NOTHING
+            "#
+        )
+        .bind(sent.workspace_id)
+        .bind(sent.campaign_id)
+        .bind(sent.campaign_lead_id)
+        .bind(sent.email_account_id)
+        .bind(provider)
+        .bind(event.event_type.as_str())
+        .bind(&event.recipient)
+        .bind(&event.provider_event_id)
+        .bind(message_id)
+        .bind(&event.reason)
+        .bind(event.occurred_at)
+        .bind(&event.payload)
+        .execute(&mut *tx)
+        .await?;
+
+        if inserted.rows_affected() == 0 {
+            tx.rollback().await?;
+            continue;
+        }
+
+        apply_event(&mut tx, &sent, event).await?;
+
+        tx.commit().await?;
+        recorded += 1;
+    }
+
+    Ok(recorded)
+}
+
+async fn apply_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    sent: &SentEmailMatch,
+    event: &ProviderEvent,
+) -> Result<(), sqlx::Error> {
+    // Campaign counters count each lead once, however many opens/clicks it has
+    let (lead_update, campaign_counter) = match event.event_type {
+        EmailEventType::Delivered => return Ok(()),
+        EmailEventType::Opened => (
+            sqlx::query("UPDATE campaign_leads SET opened_at = $2 WHERE id = $1 AND opened_at IS NULL")
+                .bind(sent.campaign_lead_id)
+                .bind(event.occurred_at),
+            Some("opened"),
+        ),
+        EmailEventType::Clicked => (
+            sqlx::query("UPDATE campaign_leads SET clicked_at = $2 WHERE id = $1 AND clicked_at IS NULL")
+                .bind(sent.campaign_lead_id)
+                .bind(event.occurred_at),
+            Some("clicked"),
+        ),
+        EmailEventType::Bounced => {
+            // Providers only report bounces they've given up on (Mailgun's
+            // temporary failures never become events), so these are all hard
+            let bounce = NewBounce {
+                workspace_id: sent.workspace_id,
+                campaign_id: Some(sent.campaign_id),
+                campaign_lead_id: Some(sent.campaign_lead_id),
+                email_account_id: sent.email_account_id,
+                recipient: &event.recipient,
+                bounce_type: BounceType::Hard,
+                source: BounceSource::Webhook,
+                reason: event.reason.as_deref(),
+                occurred_at: event.occurred_at,
+            };
+            return bounces::apply(tx, &bounce).await;
+        }
+        EmailEventType::SpamReport => (
+            sqlx::query("UPDATE campaign_leads SET status = 'complained' WHERE id = $1 AND status <> 'complained'")
+                .bind(sent.campaign_lead_id),
+            None,
+        ),
+    };
+
+    let first_time = lead_update.execute(&mut **tx).await?.rows_affected() > 0;
+
+    if let (true, Some(column)) = (first_time, campaign_counter) {
+        sqlx::query(&format!("UPDATE campaigns SET {0} = {0} + 1 WHERE id = $1", column))
+            .bind(sent.campaign_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    let Some(email_account_id) = sent.email_account_id else {
+        return Ok(());
+    };
+
+    let inbox_update = match event.event_type {
+        EmailEventType::SpamReport => {
+            r#"
+            UPDATE email_accounts
+            SET total_spam_reports = total_spam_reports + 1,
+                spam_rate = (total_spam_reports + 1)::FLOAT / GREATEST(total_sent, 1)
+            WHERE id = $1
+            "#
+        }
+        _ => return Ok(()),
+    };
+
+    sqlx::query(inbox_update)
+        .bind(email_account_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_mailgun_signature_and_parsing() {
+        let provider = MailgunProvider { signing_key: Some("key-test".to_string()) };
+        let timestamp = Utc::now().timestamp().to_string();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key-test").unwrap();
+        mac.update(timestamp.as_bytes());
+        mac.update(b"tok");
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let body = serde_json::json!({
+            "signature": {"timestamp": timestamp, "token": "tok", "signature": signature},
+            "event-data": {"id": "ev1", "event": "failed", "severity": "permanent", "recipient": "Jo@Acme.io", "timestamp": 1700000000.5}
+        })
+        .to_string();
+
+        assert!(provider.verify(&|_| None, body.as_bytes()).is_ok());
+        assert!(provider.verify(&|_| None, body.replace("tok", "bad").as_bytes()).is_err());
+
+        let events = provider.parse(body.as_bytes()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EmailEventType::Bounced);
+        assert_eq!(events[0].recipient, "jo@acme.io");
+    }
+
+    #[test]
+    fn test_sendgrid_parsing_skips_untracked_events() {
+        let provider = SendGridProvider { public_key: None };
+        let body = r#"[
+            {"email": "a@acme.io", "event": "open", "timestamp": 1700000000, "sg_event_id": "e1"},
+            {"email": "a@acme.io", "event": "processed", "timestamp": 1700000000},
+            {"email": "b@acme.io", "event": "spamreport", "timestamp": 1700000000}
+        ]"#;
+
+        let events = provider.parse(body.as_bytes()).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, EmailEventType::Opened);
+        assert_eq!(events[1].event_type, EmailEventType::SpamReport);
+        assert!(provider.verify(&|_| None, body.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_message_ids_are_normalized_to_angle_brackets() {
+        assert_eq!(normalize_message_id("abc@acme.io").as_deref(), Some("<abc@acme.io>"));
+        assert_eq!(normalize_message_id(" <abc@acme.io> ").as_deref(), Some("<abc@acme.io>"));
+        assert_eq!(normalize_message_id("<>"), None);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_events_only_touch_the_workspace_that_sent_the_message() {
+        let pool = test_db::pool().await;
+        let recipient = format!("shared-{}@example.com", Uuid::new_v4().simple());
+
+        // Both tenants mailed the same address; the other one most recently
+        let mut sends = Vec::new();
+        for (name, sent_minutes_ago) in [("Webhook sender", 10), ("Webhook bystander", 1)] {
+            let workspace_id = test_db::workspace(&pool, name).await;
+            let (campaign_id, lead_id, campaign_lead_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+            let message_id = format!("<{}@acme.io>", Uuid::new_v4());
+            sqlx::query("INSERT INTO campaigns (id, name, vertical, status, workspace_id) VALUES ($1, 'Test', 'saas', 'active', $2)")
+                .bind(campaign_id)
+                .bind(workspace_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, $3)")
+                .bind(lead_id)
+                .bind(workspace_id)
+                .bind(&recipient)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO campaign_leads (id, campaign_id, lead_id, status, sent_at) VALUES ($1, $2, $3, 'sent', NOW() - INTERVAL '1 minute' * $4)",
+            )
+            .bind(campaign_lead_id)
+            .bind(campaign_id)
+            .bind(lead_id)
+            .bind(sent_minutes_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                r#"
+                INSERT INTO sent_emails (workspace_id, campaign_id, campaign_lead_id, lead_id, from_email, to_email, subject, body_html, body_text, message_id)
+                VALUES ($1, $2, $3, $4, 'sender@acme.io', $5, 'Hi', '<p>Hi</p>', 'Hi', $6)
+                "#
+            )
+            .bind(workspace_id)
+            .bind(campaign_id)
+            .bind(campaign_lead_id)
+            .bind(lead_id)
+            .bind(&recipient)
+            .bind(&message_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sends.push((workspace_id, campaign_lead_id, message_id));
+        }
+
+        let event = |message_id: Option<&str>| ProviderEvent {
+            event_type: EmailEventType::Bounced,
+            recipient: recipient.clone(),
+            provider_event_id: Some(Uuid::new_v4().to_string()),
+            provider_message_id: message_id.and_then(normalize_message_id),
+            reason: Some("550 5.1.1 User unknown".to_string()),
+            occurred_at: Utc::now(),
+            payload: serde_json::json!({}),
+        };
+        // Mailgun reports the Message-ID without angle brackets
+        let sender_message = sends[0].2.trim_matches(['<', '>']).to_string();
+        let events = [
+            event(Some(&sender_message)),
+            event(Some("unknown@acme.io")),
+            event(None),
+        ];
+        let recorded = record_events(&pool, "mailgun", &events).await.unwrap();
+
+        let mut statuses = Vec::new();
+        for (_, campaign_lead_id, _) in &sends {
+            let status: String = sqlx::query_scalar("SELECT status FROM campaign_leads WHERE id = $1")
+                .bind(campaign_lead_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            statuses.push(status);
+        }
+        let stored: Vec<Option<Uuid>> =
+            sqlx::query_scalar("SELECT workspace_id FROM email_events WHERE recipient = $1")
+                .bind(&recipient)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+
+        for (workspace_id, _, _) in &sends {
+            test_db::delete_workspace(&pool, *workspace_id).await;
+        }
+
+        assert_eq!(recorded, 1);
+        assert_eq!(statuses, ["bounced", "sent"]);
+        assert_eq!(stored, vec![Some(sends[0].0)]);
+    }
+}
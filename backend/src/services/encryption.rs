@@ -4,34 +4,58 @@ use aes_gcm::{
 };
 use aes_gcm::aead::generic_array::GenericArray;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use std::collections::HashMap;
 use std::env;
 
 const NONCE_SIZE: usize = 12;
 
+/// Encrypts with the current key and decrypts with the current key or any
+/// retired key listed in `ENCRYPTION_PREVIOUS_KEYS`, so stored passwords keep
+/// working while they are re-encrypted after a rotation.
 pub struct EncryptionService {
     cipher: Aes256Gcm,
     key_id: String,
+    previous_keys: HashMap<String, Aes256Gcm>,
 }
 
 impl EncryptionService {
+    /// Reads `ENCRYPTION_KEY`, `ENCRYPTION_KEY_ID` and `ENCRYPTION_PREVIOUS_KEYS`
     pub fn new() -> Result<Self, String> {
-        let key_str = env::var("ENCRYPTION_KEY")
-            .map_err(|_| "ENCRYPTION_KEY environment variable not set")?;
-        
-        let key_bytes = BASE64.decode(&key_str)
+        Self::from_config(
+            env::var("ENCRYPTION_KEY").ok().as_deref(),
+            env::var("ENCRYPTION_KEY_ID").ok().as_deref(),
+            env::var("ENCRYPTION_PREVIOUS_KEYS").ok().as_deref(),
+        )
+    }
+
+    /// Builds the service from a base64 key, its id (`default-key-v1` when unset)
+    /// and retired keys as `key-id:base64key,other-key-id:base64key`
+    pub fn from_config(key: Option<&str>, key_id: Option<&str>, previous_keys: Option<&str>) -> Result<Self, String> {
+        let key_str = key
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .ok_or("ENCRYPTION_KEY environment variable not set")?;
+
+        let key_bytes = BASE64.decode(key_str)
             .map_err(|e| format!("Invalid ENCRYPTION_KEY format: {}", e))?;
-        
+
         if key_bytes.len() != 32 {
             return Err("ENCRYPTION_KEY must be 32 bytes (256 bits) when decoded".to_string());
         }
-        
-        let key = GenericArray::from_slice(&key_bytes);
-        let cipher = Aes256Gcm::new(key);
-        
-        let key_id = env::var("ENCRYPTION_KEY_ID")
-            .unwrap_or_else(|_| "default-key-v1".to_string());
-        
-        Ok(Self { cipher, key_id })
+
+        let key_id = key_id.map(str::trim).filter(|id| !id.is_empty()).unwrap_or("default-key-v1");
+        let mut service = Self::new_with_key(&key_bytes, key_id)?;
+
+        for entry in previous_keys.unwrap_or("").split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, encoded) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid ENCRYPTION_PREVIOUS_KEYS entry: {}", entry))?;
+            let bytes = BASE64.decode(encoded.trim())
+                .map_err(|e| format!("Invalid key for {} in ENCRYPTION_PREVIOUS_KEYS: {}", id, e))?;
+            service = service.with_previous_key(id.trim(), &bytes)?;
+        }
+
+        Ok(service)
     }
 
     pub fn new_with_key(key_bytes: &[u8], key_id: &str) -> Result<Self, String> {
@@ -44,10 +68,21 @@ impl EncryptionService {
         
         Ok(Self { 
             cipher, 
-            key_id: key_id.to_string() 
+            key_id: key_id.to_string(),
+            previous_keys: HashMap::new(),
         })
     }
 
+    /// Registers a retired key that can still decrypt data stored under `key_id`
+    pub fn with_previous_key(mut self, key_id: &str, key_bytes: &[u8]) -> Result<Self, String> {
+        if key_bytes.len() != 32 {
+            return Err(format!("Key {} must be 32 bytes (256 bits)", key_id));
+        }
+
+        self.previous_keys.insert(key_id.to_string(), Aes256Gcm::new(GenericArray::from_slice(key_bytes)));
+        Ok(self)
+    }
+
     pub fn encrypt(&self, plaintext: &str) -> Result<(Vec<u8>, String), String> {
         use rand::RngCore;
         
@@ -67,6 +102,25 @@ impl EncryptionService {
     }
 
     pub fn decrypt(&self, encrypted_data: &[u8]) -> Result<String, String> {
+        Self::decrypt_with(&self.cipher, encrypted_data)
+    }
+
+    /// Decrypts data stored under `key_id`. Rows without a key id predate key
+    /// tracking and are assumed to use the current key.
+    pub fn decrypt_with_key_id(&self, encrypted_data: &[u8], key_id: Option<&str>) -> Result<String, String> {
+        match key_id {
+            None => self.decrypt(encrypted_data),
+            Some(id) if id == self.key_id => self.decrypt(encrypted_data),
+            Some(id) => {
+                let cipher = self.previous_keys
+                    .get(id)
+                    .ok_or_else(|| format!("Unknown encryption key id: {}", id))?;
+                Self::decrypt_with(cipher, encrypted_data)
+            }
+        }
+    }
+
+    fn decrypt_with(cipher: &Aes256Gcm, encrypted_data: &[u8]) -> Result<String, String> {
         if encrypted_data.len() < NONCE_SIZE {
             return Err("Encrypted data too short".to_string());
         }
@@ -74,7 +128,7 @@ impl EncryptionService {
         let (nonce_bytes, ciphertext) = encrypted_data.split_at(NONCE_SIZE);
         let nonce = Nonce::from_slice(nonce_bytes);
         
-        let plaintext = self.cipher
+        let plaintext = cipher
             .decrypt(nonce, ciphertext)
             .map_err(|e| format!("Decryption failed: {}", e))?;
         
@@ -113,6 +167,39 @@ mod tests {
         assert_eq!(decrypted, plaintext);
     }
 
+    #[test]
+    fn test_decrypt_with_previous_key() {
+        let old = EncryptionService::new_with_key(&[1u8; 32], "key-v1").unwrap();
+        let (encrypted, old_id) = old.encrypt("rotate-me").unwrap();
+
+        let current = EncryptionService::new_with_key(&[2u8; 32], "key-v2")
+            .unwrap()
+            .with_previous_key("key-v1", &[1u8; 32])
+            .unwrap();
+
+        assert!(current.decrypt(&encrypted).is_err());
+        assert_eq!(current.decrypt_with_key_id(&encrypted, Some(&old_id)).unwrap(), "rotate-me");
+        assert!(current.decrypt_with_key_id(&encrypted, Some("key-v0")).is_err());
+
+        let (reencrypted, new_id) = current.encrypt("rotate-me").unwrap();
+        assert_eq!(new_id, "key-v2");
+        assert_eq!(current.decrypt_with_key_id(&reencrypted, Some(&new_id)).unwrap(), "rotate-me");
+    }
+
+    #[test]
+    fn test_missing_or_malformed_keys_are_rejected() {
+        let key = generate_encryption_key();
+
+        assert!(EncryptionService::from_config(None, None, None).is_err());
+        assert!(EncryptionService::from_config(Some("  "), None, None).is_err());
+        assert!(EncryptionService::from_config(Some("not base64!"), None, None).is_err());
+        assert!(EncryptionService::from_config(Some(&BASE64.encode([0u8; 16])), None, None).is_err());
+        assert!(EncryptionService::from_config(Some(&key), None, Some("key-v1")).is_err());
+
+        let service = EncryptionService::from_config(Some(&key), None, Some(&format!("key-v0:{}", key))).unwrap();
+        assert_eq!(service.key_id(), "default-key-v1");
+    }
+
     #[test]
     fn test_generate_key() {
         let key = generate_encryption_key();
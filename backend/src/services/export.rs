@@ -0,0 +1,421 @@
+use actix_web::http::header::ContentDisposition;
+use actix_web::web::Bytes;
+use actix_web::HttpResponse;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::stream::{self, Stream};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::future::Future;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Rows fetched per query while exporting, so a large workspace is never held in memory at once
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// `csv` (default) or `xlsx`
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// A row type that can be exported page by page, in id order
+pub trait ExportRow: Sized + Send + 'static {
+    /// What the export is limited to, e.g. the workspace
+    type Scope: Clone + Send + Sync + 'static;
+
+    const HEADERS: &'static [&'static str];
+
+    /// Up to `limit` rows after the row with id `after`
+    fn fetch_page(
+        pool: &PgPool,
+        scope: &Self::Scope,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<Self>, sqlx::Error>> + Send;
+
+    fn id(&self) -> Uuid;
+
+    /// One value per header
+    fn cells(&self) -> Vec<String>;
+}
+
+fn timestamp(value: Option<DateTime<Utc>>) -> String {
+    value.map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true)).unwrap_or_default()
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct LeadExportRow {
+    pub id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub title: Option<String>,
+    pub linkedin_url: Option<String>,
+    pub verification_status: Option<String>,
+    pub confidence_score: Option<f64>,
+    pub tags: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl ExportRow for LeadExportRow {
+    /// The workspace
+    type Scope = Uuid;
+
+    const HEADERS: &'static [&'static str] = &[
+        "email", "first_name", "last_name", "company", "title", "linkedin_url",
+        "verification_status", "confidence_score", "tags", "created_at",
+    ];
+
+    async fn fetch_page(pool: &PgPool, workspace_id: &Uuid, after: Option<Uuid>, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            SELECT l.id, l.email, l.first_name, l.last_name, l.company, l.title, l.linkedin_url,
+                   l.verification_status::text AS verification_status, l.confidence_score::float8 AS confidence_score,
+                   (SELECT string_agg(t.tag, ';' ORDER BY t.tag) FROM lead_tags t WHERE t.lead_id = l.id) AS tags,
+                   l.created_at
+            FROM leads l
+            WHERE l.workspace_id = $1 AND l.deleted_at IS NULL
+              AND ($2::uuid IS NULL OR l.id > $2)
+            ORDER BY l.id
+            LIMIT $3
+            "#
+        )
+        .bind(workspace_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.email.clone(),
+            self.first_name.clone().unwrap_or_default(),
+            self.last_name.clone().unwrap_or_default(),
+            self.company.clone().unwrap_or_default(),
+            self.title.clone().unwrap_or_default(),
+            self.linkedin_url.clone().unwrap_or_default(),
+            self.verification_status.clone().unwrap_or_default(),
+            self.confidence_score.map(|c| format!("{:.2}", c)).unwrap_or_default(),
+            self.tags.clone().unwrap_or_default(),
+            timestamp(self.created_at),
+        ]
+    }
+}
+
+/// A lead's progress through one campaign
+#[derive(Debug, sqlx::FromRow)]
+pub struct CampaignResultRow {
+    pub id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub current_step: i32,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub clicked_at: Option<DateTime<Utc>>,
+    pub replied_at: Option<DateTime<Utc>>,
+    pub reply_intent: Option<String>,
+}
+
+impl ExportRow for CampaignResultRow {
+    /// `(workspace_id, campaign_id)`
+    type Scope = (Uuid, Uuid);
+
+    const HEADERS: &'static [&'static str] = &[
+        "email", "first_name", "last_name", "company", "title", "status", "step",
+        "sent_at", "opened_at", "clicked_at", "replied_at", "reply_intent",
+    ];
+
+    async fn fetch_page(
+        pool: &PgPool,
+        (workspace_id, campaign_id): &(Uuid, Uuid),
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            SELECT cl.id, l.email, l.first_name, l.last_name, l.company, l.title, cl.status, cl.current_step,
+                   cl.sent_at, cl.opened_at, cl.clicked_at, cl.replied_at, cl.reply_intent
+            FROM campaign_leads cl
+            INNER JOIN leads l ON l.id = cl.lead_id
+            INNER JOIN campaigns c ON c.id = cl.campaign_id
+            WHERE cl.campaign_id = $1 AND c.workspace_id = $2 AND c.deleted_at IS NULL
+              AND ($3::uuid IS NULL OR cl.id > $3)
+            ORDER BY cl.id
+            LIMIT $4
+            "#
+        )
+        .bind(campaign_id)
+        .bind(workspace_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.email.clone(),
+            self.first_name.clone().unwrap_or_default(),
+            self.last_name.clone().unwrap_or_default(),
+            self.company.clone().unwrap_or_default(),
+            self.title.clone().unwrap_or_default(),
+            self.status.clone().unwrap_or_default(),
+            (self.current_step + 1).to_string(),
+            timestamp(self.sent_at),
+            timestamp(self.opened_at),
+            timestamp(self.clicked_at),
+            timestamp(self.replied_at),
+            self.reply_intent.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// A workspace suppression entry: an address, or `@domain` for a whole domain
+#[derive(Debug, sqlx::FromRow)]
+pub struct SuppressionExportRow {
+    pub id: Uuid,
+    pub email: String,
+    pub reason: String,
+    pub source: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl ExportRow for SuppressionExportRow {
+    /// The workspace
+    type Scope = Uuid;
+
+    const HEADERS: &'static [&'static str] = &["email", "reason", "source", "created_at"];
+
+    async fn fetch_page(pool: &PgPool, workspace_id: &Uuid, after: Option<Uuid>, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            SELECT id, email, reason, source, created_at
+            FROM suppression_list
+            WHERE workspace_id = $1
+              AND ($2::uuid IS NULL OR id > $2)
+            ORDER BY id
+            LIMIT $3
+            "#
+        )
+        .bind(workspace_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.email.clone(),
+            self.reason.clone(),
+            self.source.clone().unwrap_or_default(),
+            timestamp(self.created_at),
+        ]
+    }
+}
+
+/// Spreadsheet apps run cells starting with these as formulas, so such values
+/// get a leading apostrophe to keep them as text
+fn csv_cell(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn encode_csv<I, R>(records: I) -> Bytes
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = String>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer
+            .write_record(record.into_iter().map(|cell| csv_cell(&cell)))
+            .expect("writing CSV to memory can't fail");
+    }
+    Bytes::from(writer.into_inner().expect("writing CSV to memory can't fail"))
+}
+
+enum Page {
+    Header,
+    After(Option<Uuid>),
+    Done,
+}
+
+/// CSV body that fetches the next page only once the previous one has been sent
+pub fn csv_stream<R: ExportRow>(pool: PgPool, scope: R::Scope) -> impl Stream<Item = Result<Bytes, sqlx::Error>> {
+    csv_stream_paged::<R>(pool, scope, EXPORT_PAGE_SIZE)
+}
+
+fn csv_stream_paged<R: ExportRow>(pool: PgPool, scope: R::Scope, page_size: i64) -> impl Stream<Item = Result<Bytes, sqlx::Error>> {
+    stream::unfold(Page::Header, move |page| {
+        let (pool, scope) = (pool.clone(), scope.clone());
+        async move {
+            match page {
+                Page::Header => {
+                    let header = R::HEADERS.iter().map(|h| h.to_string());
+                    Some((Ok(encode_csv([header])), Page::After(None)))
+                }
+                Page::After(after) => match R::fetch_page(&pool, &scope, after, page_size).await {
+                    Ok(rows) if rows.is_empty() => None,
+                    Ok(rows) => {
+                        let next = if (rows.len() as i64) < page_size {
+                            Page::Done
+                        } else {
+                            Page::After(rows.last().map(R::id))
+                        };
+                        Some((Ok(encode_csv(rows.iter().map(R::cells))), next))
+                    }
+                    Err(e) => Some((Err(e), Page::Done)),
+                },
+                Page::Done => None,
+            }
+        }
+    })
+}
+
+/// The whole export as an .xlsx file. The zip container can't be streamed, so
+/// rows are still read a page at a time but the workbook is built in memory.
+pub async fn xlsx_workbook<R: ExportRow>(pool: &PgPool, scope: &R::Scope, sheet_name: &str) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet().set_name(sheet_name).map_err(|e| e.to_string())?;
+    let bold = Format::new().set_bold();
+
+    for (col, header) in R::HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &bold).map_err(|e| e.to_string())?;
+    }
+
+    let mut row = 0;
+    let mut after = None;
+    loop {
+        let rows = R::fetch_page(pool, scope, after, EXPORT_PAGE_SIZE).await.map_err(|e| e.to_string())?;
+        for record in &rows {
+            row += 1;
+            for (col, cell) in record.cells().iter().enumerate().filter(|(_, cell)| !cell.is_empty()) {
+                sheet.write_string(row, col as u16, cell).map_err(|e| e.to_string())?;
+            }
+        }
+        if (rows.len() as i64) < EXPORT_PAGE_SIZE {
+            break;
+        }
+        after = rows.last().map(R::id);
+    }
+
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
+
+/// Download response for an export, streamed for CSV
+pub async fn respond<R: ExportRow>(
+    pool: PgPool,
+    scope: R::Scope,
+    format: ExportFormat,
+    filename_stem: &str,
+    sheet_name: &str,
+) -> Result<HttpResponse, String> {
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition::attachment(format!("{}.{}", filename_stem, format.extension())));
+
+    Ok(match format {
+        ExportFormat::Csv => response.streaming(csv_stream::<R>(pool, scope)),
+        ExportFormat::Xlsx => response.body(xlsx_workbook::<R>(&pool, &scope, sheet_name).await?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use crate::services::lead_import;
+    use futures_util::TryStreamExt;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_imported_leads_round_trip_through_export() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Export test").await;
+
+        let csv = "Email,First Name,Last Name,Company,Title,LinkedIn URL\n\
+                   ada@example.com,Ada,Lovelace,\"Analytical Engines, Ltd\",Founder,https://linkedin.com/in/ada\n\
+                   grace@example.com,Grace,Hopper,Navy,=Rear Admiral,\n\
+                   linus@example.com,Linus,,Kernel,,\n";
+        let imported = lead_import::parse_csv(csv.as_bytes()).unwrap();
+        lead_import::insert_leads(&pool, workspace_id, &imported.leads).await.unwrap();
+
+        // A page size smaller than the list makes the stream go back for more
+        let chunks: Vec<Bytes> = csv_stream_paged::<LeadExportRow>(pool.clone(), workspace_id, 2)
+            .try_collect()
+            .await
+            .unwrap();
+        let exported = lead_import::parse_csv(&chunks.concat()).unwrap();
+
+        let mut expected = imported.leads.clone();
+        expected.sort_by(|a, b| a.email.cmp(&b.email));
+        let mut actual = exported.leads.clone();
+        actual.sort_by(|a, b| a.email.cmp(&b.email));
+
+        assert_eq!(actual.len(), 3);
+        for (actual, expected) in actual.iter().zip(&expected) {
+            assert_eq!(actual.email, expected.email);
+            assert_eq!(actual.first_name, expected.first_name);
+            assert_eq!(actual.last_name, expected.last_name);
+            assert_eq!(actual.company, expected.company);
+            assert_eq!(actual.linkedin_url, expected.linkedin_url);
+        }
+        // Formula-looking values come back escaped rather than as formulas
+        assert_eq!(actual[1].title.as_deref(), Some("'=Rear Admiral"));
+
+        let workbook = xlsx_workbook::<LeadExportRow>(&pool, &workspace_id, "Leads").await.unwrap();
+        assert!(workbook.starts_with(b"PK"));
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+    }
+}
@@ -0,0 +1,421 @@
+use chrono::{NaiveDate, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::models::signal::{
+    ConfidenceFactors, CreateFundingSignal, CreateSignal, FundingSignal, Signal, SignalSource,
+    SignalType,
+};
+
+const CRUNCHBASE_API_URL: &str = "https://api.crunchbase.com/api/v4";
+
+/// Rounds announced longer ago than this aren't news anymore
+const LOOKBACK_DAYS: i64 = 180;
+
+// ============================================================================
+// Crunchbase API Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct CrunchbaseEntity {
+    #[serde(default)]
+    cards: CrunchbaseCards,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CrunchbaseCards {
+    #[serde(default)]
+    raised_funding_rounds: Vec<CrunchbaseRound>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrunchbaseRound {
+    identifier: Option<CrunchbaseIdentifier>,
+    announced_on: Option<NaiveDate>,
+    investment_type: Option<String>,
+    money_raised: Option<CrunchbaseMoney>,
+    #[serde(default)]
+    lead_investor_identifiers: Vec<CrunchbaseIdentifier>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrunchbaseIdentifier {
+    value: Option<String>,
+    permalink: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrunchbaseMoney {
+    value_usd: Option<i64>,
+}
+
+// ============================================================================
+// Funding Types
+// ============================================================================
+
+/// One funding round, from Crunchbase or entered by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingRound {
+    pub company_name: String,
+    /// Normalized round name: "seed", "series-a", ...
+    pub round_type: Option<String>,
+    /// Whole US dollars
+    pub amount_usd: Option<i64>,
+    pub investors: Vec<String>,
+    pub lead_investor: Option<String>,
+    pub announced_date: Option<NaiveDate>,
+    pub source_url: Option<String>,
+}
+
+/// Funding rounds entered by hand, for bootstrapping without a Crunchbase key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualFundingEntry {
+    pub company_id: uuid::Uuid,
+    pub rounds: Vec<ManualFundingRound>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualFundingRound {
+    pub round_type: Option<String>,
+    /// Whole US dollars
+    pub amount_usd: Option<i64>,
+    #[serde(default)]
+    pub investors: Vec<String>,
+    pub lead_investor: Option<String>,
+    pub announced_date: Option<NaiveDate>,
+    pub url: Option<String>,
+}
+
+/// "Series A", "series_a" and "series-a" all become "series-a"
+pub fn normalize_round_type(round_type: &str) -> String {
+    round_type
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// "series-a" -> "Series A"
+fn round_label(round_type: &str) -> String {
+    round_type
+        .split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 20_000_000 -> "$20M", 1_500_000_000 -> "$1.5B"
+pub fn format_amount(amount_usd: i64) -> String {
+    let (value, suffix) = match amount_usd {
+        a if a >= 1_000_000_000 => (a as f64 / 1_000_000_000.0, "B"),
+        a if a >= 1_000_000 => (a as f64 / 1_000_000.0, "M"),
+        a if a >= 1_000 => (a as f64 / 1_000.0, "K"),
+        a => (a as f64, ""),
+    };
+    let formatted = format!("{:.1}", value);
+    format!("${}{}", formatted.trim_end_matches(".0"), suffix)
+}
+
+// ============================================================================
+// Funding Connector
+// ============================================================================
+
+pub struct FundingConnector {
+    client: Client,
+    api_key: Option<String>,
+}
+
+impl FundingConnector {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.filter(|k| !k.trim().is_empty()),
+        }
+    }
+
+    /// Reads `CRUNCHBASE_API_KEY`
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("CRUNCHBASE_API_KEY").ok())
+    }
+
+    /// Without an API key only manual entries can create funding signals
+    pub fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Fetch an organization's funding rounds from the last `LOOKBACK_DAYS` days
+    pub async fn fetch_funding_rounds(
+        &self,
+        company_name: &str,
+        permalink: &str,
+    ) -> Result<Vec<FundingRound>, Box<dyn std::error::Error + Send + Sync>> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or("CRUNCHBASE_API_KEY is not set")?;
+
+        let url = format!(
+            "{}/entities/organizations/{}?card_ids=raised_funding_rounds",
+            CRUNCHBASE_API_URL, permalink
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-cb-user-key", api_key)
+            .header(reqwest::header::USER_AGENT, "OutreachIQ/1.0")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Crunchbase API error: {}", response.status()).into());
+        }
+
+        let entity: CrunchbaseEntity = response.json().await?;
+        let cutoff = Utc::now().date_naive() - chrono::Duration::days(LOOKBACK_DAYS);
+
+        Ok(entity
+            .cards
+            .raised_funding_rounds
+            .into_iter()
+            .filter(|r| r.announced_on.is_some_and(|d| d >= cutoff))
+            .map(|r| Self::from_crunchbase(company_name, r))
+            .collect())
+    }
+
+    fn from_crunchbase(company_name: &str, round: CrunchbaseRound) -> FundingRound {
+        // Crunchbase only lists lead investors on the round card
+        let investors: Vec<String> = round
+            .lead_investor_identifiers
+            .into_iter()
+            .filter_map(|i| i.value)
+            .collect();
+
+        FundingRound {
+            company_name: company_name.to_string(),
+            round_type: round.investment_type.as_deref().map(normalize_round_type),
+            amount_usd: round.money_raised.and_then(|m| m.value_usd),
+            lead_investor: investors.first().cloned(),
+            investors,
+            announced_date: round.announced_on,
+            source_url: round
+                .identifier
+                .and_then(|i| i.permalink)
+                .map(|p| format!("https://www.crunchbase.com/funding_round/{}", p)),
+        }
+    }
+
+    /// Calculate confidence score based on round size and recency (rule-based, NOT AI)
+    pub fn calculate_confidence(&self, round: &FundingRound, source: &SignalSource) -> ConfidenceFactors {
+        let mut factors = ConfidenceFactors::new();
+
+        // Factor 1: Round size
+        match round.amount_usd {
+            Some(a) if a >= 100_000_000 => factors.add_factor("large_round", 0.25, "$100M+ raised"),
+            Some(a) if a >= 20_000_000 => factors.add_factor("medium_round", 0.15, "$20M+ raised"),
+            Some(a) if a >= 5_000_000 => factors.add_factor("small_round", 0.08, "$5M+ raised"),
+            Some(_) => {}
+            None => factors.add_factor("undisclosed_amount", -0.05, "Amount not disclosed"),
+        }
+
+        // Factor 2: Recency
+        if let Some(announced) = round.announced_date {
+            let days_ago = (Utc::now().date_naive() - announced).num_days();
+            if days_ago <= 7 {
+                factors.add_factor("very_recent_round", 0.20, "Announced in last 7 days");
+            } else if days_ago <= 30 {
+                factors.add_factor("recent_round", 0.10, "Announced in last 30 days");
+            } else if days_ago > 90 {
+                factors.add_factor("old_round", -0.10, "Announced over 90 days ago");
+            }
+        }
+
+        // Factor 3: Named lead investor
+        if round.lead_investor.is_some() {
+            factors.add_factor("known_lead_investor", 0.05, "Lead investor disclosed");
+        }
+
+        // Factor 4: Structured data source
+        if *source == SignalSource::Crunchbase {
+            factors.add_factor("crunchbase_verified", 0.10, "Reported by Crunchbase");
+        }
+
+        factors
+    }
+
+    /// Create funding signals, skipping rounds the company already has a signal for
+    pub async fn create_signals(
+        &self,
+        pool: &sqlx::PgPool,
+        company_id: uuid::Uuid,
+        rounds: &[FundingRound],
+        source: SignalSource,
+    ) -> Result<Vec<Signal>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut signals = Vec::new();
+
+        for round in rounds {
+            if FundingSignal::exists_for_round(
+                pool,
+                company_id,
+                round.round_type.as_deref(),
+                round.announced_date,
+            )
+            .await?
+            {
+                info!(
+                    "Duplicate funding round for {}, skipping: {:?} {:?}",
+                    round.company_name, round.round_type, round.announced_date
+                );
+                continue;
+            }
+
+            let confidence = self.calculate_confidence(round, &source);
+            let amount_display = round.amount_usd.map(format_amount);
+            let round_name = round
+                .round_type
+                .as_deref()
+                .map(round_label)
+                .unwrap_or_else(|| "funding round".to_string());
+
+            let title = match amount_display {
+                Some(ref amount) => format!("{} raised {} {}", round.company_name, amount, round_name),
+                None => format!("{} announced a {}", round.company_name, round_name),
+            };
+
+            let description = match round.lead_investor {
+                Some(ref lead) => Some(format!("{} led by {}.", round_name, lead)),
+                None if !round.investors.is_empty() => {
+                    Some(format!("Investors: {}.", round.investors.join(", ")))
+                }
+                None => None,
+            };
+
+            let create_signal = CreateSignal {
+                company_id,
+                signal_type: SignalType::Funding,
+                source: source.clone(),
+                title,
+                description,
+                source_url: round.source_url.clone(),
+                raw_data: serde_json::to_value(round)?,
+                confidence_score: confidence.final_score,
+                confidence_factors: confidence.to_json(),
+                signal_date: round.announced_date,
+                expires_at: Some(Utc::now() + chrono::Duration::days(90)),
+            };
+
+            let signal = Signal::create(pool, create_signal).await?;
+
+            FundingSignal::create(
+                pool,
+                CreateFundingSignal {
+                    signal_id: signal.id,
+                    round_type: round.round_type.clone(),
+                    amount_usd: round.amount_usd.and_then(|a| a.checked_mul(100)),
+                    amount_display,
+                    investors: round.investors.clone(),
+                    lead_investor: round.lead_investor.clone(),
+                    announced_date: round.announced_date,
+                    source_article_url: round.source_url.clone(),
+                },
+            )
+            .await?;
+
+            signals.push(signal);
+        }
+
+        info!("Created {} funding signals for company {}", signals.len(), company_id);
+        Ok(signals)
+    }
+
+    /// Create signals from manually entered funding rounds
+    /// Use this to bootstrap the system without a Crunchbase subscription
+    pub async fn create_signals_from_manual(
+        &self,
+        pool: &sqlx::PgPool,
+        company_name: &str,
+        entry: &ManualFundingEntry,
+    ) -> Result<Vec<Signal>, Box<dyn std::error::Error + Send + Sync>> {
+        let rounds: Vec<FundingRound> = entry
+            .rounds
+            .iter()
+            .map(|r| FundingRound {
+                company_name: company_name.to_string(),
+                round_type: r.round_type.as_deref().map(normalize_round_type),
+                amount_usd: r.amount_usd,
+                lead_investor: r.lead_investor.clone(),
+                investors: r.investors.clone(),
+                announced_date: r.announced_date,
+                source_url: r.url.clone(),
+            })
+            .collect();
+
+        self.create_signals(pool, entry.company_id, &rounds, SignalSource::Manual).await
+    }
+}
+
+impl Default for FundingConnector {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_names_and_amounts() {
+        assert_eq!(normalize_round_type("Series A"), "series-a");
+        assert_eq!(normalize_round_type("series_a"), "series-a");
+        assert_eq!(normalize_round_type(" Pre-Seed "), "pre-seed");
+        assert_eq!(round_label("series-a"), "Series A");
+
+        assert_eq!(format_amount(20_000_000), "$20M");
+        assert_eq!(format_amount(1_500_000_000), "$1.5B");
+        assert_eq!(format_amount(750_000), "$750K");
+    }
+
+    #[test]
+    fn test_crunchbase_round_conversion() {
+        let body = r#"{
+            "properties": {"identifier": {"value": "Acme"}},
+            "cards": {"raised_funding_rounds": [{
+                "identifier": {"value": "Series B - Acme", "permalink": "acme-series-b--1a2b"},
+                "announced_on": "2024-01-04",
+                "investment_type": "series_b",
+                "money_raised": {"value": 40000000, "currency": "USD", "value_usd": 40000000},
+                "lead_investor_identifiers": [{"value": "Example Ventures", "permalink": "example-ventures"}]
+            }]}
+        }"#;
+
+        let entity: CrunchbaseEntity = serde_json::from_str(body).unwrap();
+        let round = FundingConnector::from_crunchbase(
+            "Acme",
+            entity.cards.raised_funding_rounds.into_iter().next().unwrap(),
+        );
+
+        assert_eq!(round.round_type.as_deref(), Some("series-b"));
+        assert_eq!(round.amount_usd, Some(40_000_000));
+        assert_eq!(round.lead_investor.as_deref(), Some("Example Ventures"));
+        assert_eq!(round.announced_date, NaiveDate::from_ymd_opt(2024, 1, 4));
+        assert_eq!(
+            round.source_url.as_deref(),
+            Some("https://www.crunchbase.com/funding_round/acme-series-b--1a2b")
+        );
+
+        let confidence = FundingConnector::default().calculate_confidence(&round, &SignalSource::Crunchbase);
+        let names: Vec<&str> = confidence.factors.iter().map(|f| f.name.as_str()).collect();
+        assert!(names.contains(&"medium_round"));
+        assert!(names.contains(&"crunchbase_verified"));
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn, error};
+use tracing::{info, warn};
 
 use crate::models::signal::{
     ConfidenceFactors, CreateGithubSignal, CreateSignal, GithubSignal, Signal,
@@ -73,7 +73,9 @@ pub struct GithubActivityData {
     pub total_commits_7d: i32,
     pub total_commits_30d: i32,
     pub total_stars: i32,
-    pub stars_gained_7d: i32,
+    /// Stars gained by repos that have a snapshot from at least a week ago; `None`
+    /// until the org has been tracked that long
+    pub stars_gained_7d: Option<i32>,
     pub active_repos: i32,
     pub latest_release: Option<ReleaseInfo>,
 }
@@ -83,6 +85,7 @@ pub struct RepoActivity {
     pub name: String,
     pub url: String,
     pub stars: i32,
+    pub stars_gained_7d: Option<i32>,
     pub forks: i32,
     pub commits_7d: i32,
     pub commits_30d: i32,
@@ -100,6 +103,45 @@ pub struct ReleaseInfo {
     pub url: String,
 }
 
+// ============================================================================
+// Star History
+// ============================================================================
+
+/// Stars gained since the most recent snapshot taken at least 7 days ago, or `None`
+/// when the repo hasn't been tracked that long
+async fn stars_gained_since_week_ago(
+    pool: &sqlx::PgPool,
+    repo_full_name: &str,
+    current_stars: i32,
+) -> Result<Option<i32>, sqlx::Error> {
+    let week_ago_stars = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT stars FROM github_repo_snapshots
+        WHERE repo_full_name = $1 AND captured_at <= NOW() - INTERVAL '7 days'
+        ORDER BY captured_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(repo_full_name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(week_ago_stars.map(|stars| current_stars - stars))
+}
+
+async fn record_repo_snapshot(
+    pool: &sqlx::PgPool,
+    repo_full_name: &str,
+    stars: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO github_repo_snapshots (repo_full_name, stars) VALUES ($1, $2)")
+        .bind(repo_full_name)
+        .bind(stars)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 // ============================================================================
 // GitHub Connector
 // ============================================================================
@@ -136,9 +178,10 @@ impl GithubConnector {
         headers
     }
 
-    /// Fetch activity data for a GitHub organization
+    /// Fetch activity data for a GitHub organization, recording a star snapshot per repo
     pub async fn fetch_org_activity(
         &self,
+        pool: &sqlx::PgPool,
         org_name: &str,
     ) -> Result<GithubActivityData, Box<dyn std::error::Error + Send + Sync>> {
         info!("Fetching GitHub activity for org: {}", org_name);
@@ -163,6 +206,18 @@ impl GithubConnector {
             total_commits_30d += commits_30d;
             total_stars += repo.stargazers_count;
 
+            let stars_gained_7d =
+                match stars_gained_since_week_ago(pool, &repo.full_name, repo.stargazers_count).await {
+                    Ok(gained) => gained,
+                    Err(e) => {
+                        warn!("Failed to read star history for {}: {}", repo.full_name, e);
+                        None
+                    }
+                };
+            if let Err(e) = record_repo_snapshot(pool, &repo.full_name, repo.stargazers_count).await {
+                warn!("Failed to record star snapshot for {}: {}", repo.full_name, e);
+            }
+
             // Check for releases
             if let Ok(Some(release)) = self.fetch_latest_release(&repo.full_name).await {
                 if latest_release.is_none() 
@@ -182,6 +237,7 @@ impl GithubConnector {
                 name: repo.name.clone(),
                 url: repo.html_url.clone(),
                 stars: repo.stargazers_count,
+                stars_gained_7d,
                 forks: repo.forks_count,
                 commits_7d,
                 commits_30d,
@@ -192,6 +248,10 @@ impl GithubConnector {
         }
 
         let active_repos = repo_activities.iter().filter(|r| r.commits_7d > 0).count() as i32;
+        let stars_gained_7d = repo_activities
+            .iter()
+            .filter_map(|r| r.stars_gained_7d)
+            .reduce(|a, b| a + b);
 
         Ok(GithubActivityData {
             org_name: org_name.to_string(),
@@ -199,7 +259,7 @@ impl GithubConnector {
             total_commits_7d,
             total_commits_30d,
             total_stars,
-            stars_gained_7d: 0, // Would need historical data to calculate
+            stars_gained_7d,
             active_repos,
             latest_release,
         })
@@ -319,7 +379,21 @@ impl GithubConnector {
             }
         }
 
-        // Factor 4: Star count (indicates project maturity)
+        // Factor 4: Star growth over the last week (no history yet scores nothing)
+        match activity.stars_gained_7d {
+            Some(gained) if gained >= 500 => {
+                factors.add_factor("rapid_star_growth", 0.15, "500+ stars gained in last 7 days")
+            }
+            Some(gained) if gained >= 100 => {
+                factors.add_factor("strong_star_growth", 0.08, "100+ stars gained in last 7 days")
+            }
+            Some(gained) if gained >= 25 => {
+                factors.add_factor("star_growth", 0.04, "25+ stars gained in last 7 days")
+            }
+            _ => {}
+        }
+
+        // Factor 5: Star count (indicates project maturity)
         if activity.total_stars >= 10000 {
             factors.add_factor("high_stars", 0.10, "10k+ total stars");
         } else if activity.total_stars >= 1000 {
@@ -397,7 +471,7 @@ impl GithubConnector {
         let signal = Signal::create(pool, create_signal).await?;
 
         // Create detailed GitHub signal record
-        if let Some(ref main_repo) = activity.repos.first() {
+        if let Some(main_repo) = activity.repos.first() {
             let github_detail = CreateGithubSignal {
                 signal_id: signal.id,
                 repo_name: main_repo.name.clone(),
@@ -405,7 +479,7 @@ impl GithubConnector {
                 commits_last_7d: activity.total_commits_7d,
                 commits_last_30d: activity.total_commits_30d,
                 stars_count: activity.total_stars,
-                stars_gained_7d: activity.stars_gained_7d,
+                stars_gained_7d: activity.stars_gained_7d.unwrap_or(0),
                 forks_count: main_repo.forks,
                 contributors_count: 0, // Would need separate API call
                 open_issues: 0,
@@ -427,3 +501,33 @@ impl Default for GithubConnector {
         Self::new(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_stars_gained_uses_latest_snapshot_older_than_a_week() {
+        let pool = test_db::pool().await;
+        let repo = format!("acme/{}", uuid::Uuid::new_v4());
+
+        assert_eq!(stars_gained_since_week_ago(&pool, &repo, 200).await.unwrap(), None);
+
+        for (stars, days_ago) in [(100, 10), (120, 8), (180, 2)] {
+            sqlx::query(
+                "INSERT INTO github_repo_snapshots (repo_full_name, stars, captured_at) VALUES ($1, $2, NOW() - INTERVAL '1 day' * $3)",
+            )
+            .bind(&repo)
+            .bind(stars)
+            .bind(days_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(stars_gained_since_week_ago(&pool, &repo, 200).await.unwrap(), Some(80));
+    }
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::TokioAsyncResolver;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::DkimKey;
+
+/// Sends over the last 7 days before rates count at full weight. Below this a
+/// couple of bounces would swing the score far more than they mean.
+const RELIABLE_WEEKLY_SENDS: f64 = 100.0;
+/// Cap on what spam and bounces can take off on their own
+const MAX_SPAM_PENALTY: f64 = 40.0;
+const MAX_BOUNCE_PENALTY: f64 = 30.0;
+/// Points lost for each DNS record found missing or invalid
+const SPF_PENALTY: f64 = 10.0;
+const DKIM_PENALTY: f64 = 10.0;
+const DMARC_PENALTY: f64 = 5.0;
+/// How far each run moves the stored score towards the freshly computed one
+const SMOOTHING_FACTOR: f64 = 0.3;
+/// Most a single run can lower the score, so one bad day can't crater it. The
+/// health job runs every 6 hours, so that's at most 20 points a day.
+const MAX_DROP_PER_RUN: f64 = 5.0;
+
+/// SPF/DKIM/DMARC lookups for a sending domain. `None` means the check couldn't
+/// be made (DNS timeout, no DKIM selector configured) and doesn't count against it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DomainAuth {
+    pub spf: Option<bool>,
+    pub dkim: Option<bool>,
+    pub dmarc: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthInputs {
+    pub spam_rate: f64,
+    pub bounce_rate: f64,
+    pub reply_rate: f64,
+    pub sent_last_7_days: i64,
+    pub dns: DomainAuth,
+}
+
+/// How the score was reached. Component fields are points added to (positive)
+/// or taken off (negative) a perfect 100.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HealthBreakdown {
+    pub spam: f64,
+    pub bounce: f64,
+    pub reply: f64,
+    pub dns: f64,
+    /// 0-1 weight given to the rate components, from recent send volume
+    pub volume_confidence: f64,
+    pub sent_last_7_days: i64,
+    pub dns_checks: DomainAuth,
+    /// Score from this run's inputs alone
+    pub raw_score: f64,
+    /// Stored score after smoothing against the previous one
+    pub score: f64,
+}
+
+/// Combines rates, DNS results and volume into a 0-100 score. `previous` is the
+/// last computed score; when present the new score moves towards the raw one
+/// gradually and never drops more than `MAX_DROP_PER_RUN` in one go.
+pub fn compute_health_score(inputs: &HealthInputs, previous: Option<f64>) -> HealthBreakdown {
+    let volume_confidence = (inputs.sent_last_7_days.max(0) as f64 / RELIABLE_WEEKLY_SENDS).min(1.0);
+
+    // 1% spam = -10 points, 1% bounces = -3 points
+    let spam = -(inputs.spam_rate * 1000.0).min(MAX_SPAM_PENALTY) * volume_confidence;
+    let bounce = -(inputs.bounce_rate * 300.0).min(MAX_BOUNCE_PENALTY) * volume_confidence;
+
+    let reply_points = if inputs.reply_rate >= 0.05 {
+        5.0
+    } else if inputs.reply_rate >= 0.03 {
+        2.5
+    } else if inputs.reply_rate >= 0.01 {
+        0.0
+    } else {
+        -5.0
+    };
+    let reply = reply_points * volume_confidence;
+
+    let failed = |check: Option<bool>, penalty: f64| if check == Some(false) { -penalty } else { 0.0 };
+    let dns = failed(inputs.dns.spf, SPF_PENALTY)
+        + failed(inputs.dns.dkim, DKIM_PENALTY)
+        + failed(inputs.dns.dmarc, DMARC_PENALTY);
+
+    let raw_score = (100.0 + spam + bounce + reply + dns).clamp(0.0, 100.0);
+
+    let score = match previous {
+        Some(previous) => {
+            let smoothed = previous + (raw_score - previous) * SMOOTHING_FACTOR;
+            smoothed.max(previous - MAX_DROP_PER_RUN).clamp(0.0, 100.0)
+        }
+        None => raw_score,
+    };
+
+    HealthBreakdown {
+        spam,
+        bounce,
+        reply,
+        dns,
+        volume_confidence,
+        sent_last_7_days: inputs.sent_last_7_days,
+        dns_checks: inputs.dns,
+        raw_score,
+        score,
+    }
+}
+
+/// Looks up SPF and DMARC TXT records, and DKIM when a selector is configured
+/// for the domain. Missing records are failures; lookup errors are unknown.
+pub async fn check_domain_auth(resolver: &TokioAsyncResolver, domain: &str) -> DomainAuth {
+    let dkim = match DkimKey::for_domain(domain) {
+        Some(key) => has_txt_record(resolver, &format!("{}._domainkey.{}", key.selector, domain), "v=DKIM1").await,
+        None => None,
+    };
+
+    DomainAuth {
+        spf: has_txt_record(resolver, domain, "v=spf1").await,
+        dkim,
+        dmarc: has_txt_record(resolver, &format!("_dmarc.{}", domain), "v=DMARC1").await,
+    }
+}
+
+async fn has_txt_record(resolver: &TokioAsyncResolver, name: &str, prefix: &str) -> Option<bool> {
+    match resolver.txt_lookup(name).await {
+        Ok(records) => Some(records.iter().any(|record| {
+            let text: String = record.txt_data().iter().map(|part| String::from_utf8_lossy(part)).collect();
+            text.trim_start().to_lowercase().starts_with(&prefix.to_lowercase())
+        })),
+        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Some(false),
+        Err(_) => None,
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct AccountHealthRow {
+    id: Uuid,
+    email: String,
+    health_score: Option<f64>,
+    has_breakdown: bool,
+    spam_rate: f64,
+    bounce_rate: f64,
+    reply_rate: f64,
+    sent_last_7_days: i64,
+}
+
+/// Recomputes and stores the health score and breakdown for every inbox in the
+/// workspace. Returns how many inboxes were updated.
+pub async fn refresh_workspace_scores(
+    pool: &PgPool,
+    resolver: &TokioAsyncResolver,
+    workspace_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let accounts = sqlx::query_as::<_, AccountHealthRow>(
+        r#"
+        SELECT
+            ea.id,
+            ea.email,
+            ea.health_score,
+            ea.health_breakdown IS NOT NULL as has_breakdown,
+            COALESCE(ea.spam_rate, 0) as spam_rate,
+            COALESCE(ea.bounce_rate, 0) as bounce_rate,
+            COALESCE(ea.reply_rate, 0) as reply_rate,
+            (
+                SELECT COUNT(*) FROM sent_emails se
+                WHERE se.email_account_id = ea.id AND se.sent_at > NOW() - INTERVAL '7 days'
+            ) as sent_last_7_days
+        FROM email_accounts ea
+        WHERE ea.workspace_id = $1
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    // Inboxes usually share a handful of domains
+    let mut dns_by_domain: HashMap<String, DomainAuth> = HashMap::new();
+
+    for account in &accounts {
+        let domain = account.email.rsplit_once('@').map(|(_, d)| d.to_lowercase()).unwrap_or_default();
+        let dns = match dns_by_domain.get(&domain) {
+            Some(dns) => *dns,
+            None => {
+                let dns = check_domain_auth(resolver, &domain).await;
+                dns_by_domain.insert(domain, dns);
+                dns
+            }
+        };
+
+        let inputs = HealthInputs {
+            spam_rate: account.spam_rate,
+            bounce_rate: account.bounce_rate,
+            reply_rate: account.reply_rate,
+            sent_last_7_days: account.sent_last_7_days,
+            dns,
+        };
+        // Only smooth against scores this formula produced, not the creation default
+        let previous = if account.has_breakdown { account.health_score } else { None };
+        let breakdown = compute_health_score(&inputs, previous);
+
+        sqlx::query("UPDATE email_accounts SET health_score = $2, health_breakdown = $3 WHERE id = $1")
+            .bind(account.id)
+            .bind(breakdown.score)
+            .bind(sqlx::types::Json(&breakdown))
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(accounts.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(spam_rate: f64, bounce_rate: f64, reply_rate: f64, sent_last_7_days: i64) -> HealthInputs {
+        HealthInputs {
+            spam_rate,
+            bounce_rate,
+            reply_rate,
+            sent_last_7_days,
+            dns: DomainAuth { spf: Some(true), dkim: None, dmarc: Some(true) },
+        }
+    }
+
+    #[test]
+    fn test_representative_inboxes() {
+        // Healthy, busy inbox
+        let healthy = compute_health_score(&inputs(0.0, 0.01, 0.04, 400), None);
+        assert_eq!(healthy.raw_score, 99.5);
+
+        // 2% spam and 5% bounces at full volume: -20 and -15, minus 5 for no replies
+        let struggling = compute_health_score(&inputs(0.02, 0.05, 0.0, 400), None);
+        assert_eq!(struggling.raw_score, 60.0);
+
+        // The same rates from 25 sends only count a quarter as much
+        let new_inbox = compute_health_score(&inputs(0.02, 0.05, 0.0, 25), None);
+        assert_eq!(new_inbox.volume_confidence, 0.25);
+        assert_eq!(new_inbox.raw_score, 90.0);
+
+        // Missing SPF and DMARC cost points regardless of volume
+        let mut no_auth = inputs(0.0, 0.0, 0.05, 0);
+        no_auth.dns = DomainAuth { spf: Some(false), dkim: None, dmarc: Some(false) };
+        assert_eq!(compute_health_score(&no_auth, None).raw_score, 85.0);
+
+        // Spam and bounce penalties are capped, leaving room for the other components
+        let terrible = compute_health_score(&inputs(0.2, 0.5, 0.0, 400), None);
+        assert_eq!(terrible.raw_score, 25.0);
+    }
+
+    #[test]
+    fn test_one_bad_day_is_smoothed() {
+        let bad_day = inputs(0.03, 0.08, 0.0, 400);
+        let first = compute_health_score(&bad_day, Some(98.0));
+        assert!(first.raw_score < 60.0);
+        // Drop is capped for the run, then recovers towards the raw score over time
+        assert_eq!(first.score, 93.0);
+
+        let recovered = compute_health_score(&inputs(0.0, 0.0, 0.05, 400), Some(first.score));
+        assert!(recovered.score > first.score && recovered.score < 100.0);
+    }
+}
@@ -0,0 +1,651 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use mail_parser::{Message, MessageParser, MimeHeaders};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector, TlsStream};
+use uuid::Uuid;
+
+use crate::services::bounces::{self, BounceSource, NewBounce};
+use crate::services::email_oauth;
+use crate::services::email_sender::decrypt_inbox_password;
+use crate::services::reply_classifier;
+use crate::services::warmup_service;
+
+const DEFAULT_IMAP_PORT: i32 = 993;
+/// Messages fetched per inbox per poll; the rest are picked up next time
+const MAX_MESSAGES_PER_POLL: usize = 50;
+/// How far back the first poll of an inbox (or one after a UIDVALIDITY reset) looks
+const FIRST_POLL_LOOKBACK_DAYS: i64 = 14;
+/// Upper bound on one inbox's poll, so a hung server can't stall the others
+const POLL_TIMEOUT_SECS: u64 = 120;
+
+pub struct ImapPoller {
+    pool: Arc<PgPool>,
+}
+
+#[derive(Debug, Default)]
+pub struct PollSummary {
+    pub accounts: usize,
+    pub failed: usize,
+    pub replies: usize,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ImapAccount {
+    id: Uuid,
+    workspace_id: Uuid,
+    email: String,
+    provider: String,
+    smtp_host: String,
+    smtp_username: String,
+    smtp_password: Option<String>,
+    smtp_password_encrypted: Option<Vec<u8>>,
+    encryption_key_id: Option<String>,
+    auth_method: String,
+    imap_host: Option<String>,
+    imap_port: Option<i32>,
+    imap_last_uid: Option<i64>,
+    imap_uid_validity: Option<i64>,
+}
+
+/// What a poll did with one fetched message
+#[derive(Debug, PartialEq)]
+enum Ingested {
+    /// Stored as a reply to one of our campaign sends
+    Reply,
+    /// A warmup email from another inbox in the workspace
+    Warmup,
+    /// A bounce-back for one of our campaign sends
+    Bounce,
+    Skipped,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReplyTarget {
+    campaign_id: Uuid,
+    campaign_lead_id: Uuid,
+    lead_id: Uuid,
+}
+
+/// IMAP server for an inbox when none is configured: the provider's well-known
+/// host, else the SMTP host with `smtp.` swapped for `imap.`.
+pub fn imap_server(provider: &str, smtp_host: &str) -> String {
+    let known = match provider {
+        "google" | "gmail" => Some("imap.gmail.com"),
+        "outlook" | "microsoft" => Some("outlook.office365.com"),
+        "zoho" => Some("imap.zoho.com"),
+        "yahoo" => Some("imap.mail.yahoo.com"),
+        "apple" | "icloud" => Some("imap.mail.me.com"),
+        _ => None,
+    };
+
+    match known {
+        Some(host) => host.to_string(),
+        None => match smtp_host.strip_prefix("smtp.") {
+            Some(domain) => format!("imap.{}", domain),
+            None => smtp_host.to_string(),
+        },
+    }
+}
+
+/// Out-of-office and other automatic responses, which shouldn't count as replies
+pub fn is_auto_reply(message: &Message) -> bool {
+    let headers: Vec<(&str, &str)> = message
+        .headers()
+        .iter()
+        .filter_map(|h| message.header_raw(h.name()).map(|value| (h.name(), value)))
+        .collect();
+    let body = message.body_text(0).unwrap_or_default();
+
+    reply_classifier::is_auto_reply(&headers, message.subject().unwrap_or(""), &body)
+}
+
+impl ImapPoller {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Polls every workspace inbox once. A failing inbox is recorded in
+    /// `imap_last_error` and doesn't stop the rest.
+    pub async fn poll_all(&self) -> Result<PollSummary, String> {
+        let accounts = sqlx::query_as::<_, ImapAccount>(
+            r#"
+            SELECT id, workspace_id, email, provider, smtp_host, smtp_username, smtp_password,
+                   smtp_password_encrypted, encryption_key_id, auth_method, imap_host, imap_port,
+                   imap_last_uid, imap_uid_validity
+            FROM email_accounts
+            -- Inboxes whose OAuth grant failed wait to be reconnected
+            WHERE workspace_id IS NOT NULL AND auth_error IS NULL
+            ORDER BY imap_last_polled_at ASC NULLS FIRST
+            "#
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut summary = PollSummary { accounts: accounts.len(), ..Default::default() };
+
+        for account in &accounts {
+            let result = tokio::time::timeout(
+                std::time::Duration::from_secs(POLL_TIMEOUT_SECS),
+                self.poll_account(account),
+            )
+            .await
+            .unwrap_or_else(|_| Err("IMAP poll timed out".to_string()));
+
+            match result {
+                Ok(replies) => summary.replies += replies,
+                Err(e) => {
+                    summary.failed += 1;
+                    tracing::warn!("IMAP poll failed for {}: {}", account.email, e);
+                    let _ = sqlx::query(
+                        "UPDATE email_accounts SET imap_last_error = $2, imap_last_polled_at = NOW() WHERE id = $1"
+                    )
+                    .bind(account.id)
+                    .bind(&e)
+                    .execute(self.pool.as_ref())
+                    .await;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Fetches messages newer than the last seen UID and stores the campaign
+    /// replies among them. Returns how many replies were stored.
+    async fn poll_account(&self, account: &ImapAccount) -> Result<usize, String> {
+        let secret = decrypt_inbox_password(
+            account.smtp_password_encrypted.as_deref(),
+            account.encryption_key_id.as_deref(),
+            account.smtp_password.as_deref(),
+        )?;
+        let host = account
+            .imap_host
+            .clone()
+            .filter(|h| !h.trim().is_empty())
+            .unwrap_or_else(|| imap_server(&account.provider, &account.smtp_host));
+        let port = account.imap_port.unwrap_or(DEFAULT_IMAP_PORT);
+        let port = u16::try_from(port).map_err(|_| format!("Invalid IMAP port {}", port))?;
+
+        let mut session = ImapSession::connect(&host, port).await?;
+        if account.auth_method == "oauth" {
+            let token = email_oauth::access_token(self.pool.as_ref(), account.id, &account.provider, &secret).await?;
+            session
+                .command(&format!("AUTHENTICATE XOAUTH2 {}", email_oauth::xoauth2_response(&account.smtp_username, &token)))
+                .await?;
+        } else {
+            session
+                .command(&format!("LOGIN {} {}", quote(&account.smtp_username), quote(&secret)))
+                .await?;
+        }
+        let selected = session.command("SELECT INBOX").await?;
+        let uid_validity = response_code(&selected, "UIDVALIDITY").ok_or("Server sent no UIDVALIDITY")?;
+        let uid_next = response_code(&selected, "UIDNEXT");
+
+        // UIDs from before a UIDVALIDITY change mean nothing now
+        let last_uid = account.imap_last_uid.filter(|_| account.imap_uid_validity == Some(uid_validity as i64));
+
+        let mut uids = match last_uid {
+            Some(last) => {
+                let found = session.command(&format!("UID SEARCH UID {}:*", last + 1)).await?;
+                // `n:*` always matches the newest message, even when it's older than n
+                search_results(&found).into_iter().filter(|uid| *uid as i64 > last).collect()
+            }
+            None => {
+                let since = Utc::now() - Duration::days(FIRST_POLL_LOOKBACK_DAYS);
+                let found = session.command(&format!("UID SEARCH SINCE {}", since.format("%d-%b-%Y"))).await?;
+                search_results(&found)
+            }
+        };
+        uids.sort_unstable();
+        uids.truncate(MAX_MESSAGES_PER_POLL);
+
+        let mut stored = 0;
+        let mut highest = last_uid;
+        let mut failure = None;
+
+        for uid in uids {
+            let raw = match session.fetch_message(uid).await {
+                Ok(raw) => raw,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            };
+
+            match self.ingest(account, &raw).await {
+                Ok(Ingested::Reply) => stored += 1,
+                // Opening it is the engagement the warmup is for
+                Ok(Ingested::Warmup) => {
+                    if let Err(e) = session.command(&format!("UID STORE {} +FLAGS (\\Seen)", uid)).await {
+                        tracing::warn!("Failed to mark warmup email {} seen in {}: {}", uid, account.email, e);
+                    }
+                }
+                Ok(Ingested::Bounce | Ingested::Skipped) => {}
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+            highest = Some(uid as i64);
+        }
+
+        let _ = session.command("LOGOUT").await;
+
+        // Nothing to process on a first poll: start from the current end of the mailbox
+        let highest = highest.or_else(|| uid_next.map(|next| next as i64 - 1));
+
+        sqlx::query(
+            r#"
+            UPDATE email_accounts
+            SET imap_last_uid = $2, imap_uid_validity = $3, imap_last_polled_at = NOW(), imap_last_error = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(account.id)
+        .bind(highest)
+        .bind(uid_validity as i64)
+        .bind(&failure)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(stored),
+        }
+    }
+
+    /// Applies a bounce-back for one of the inbox's campaign sends, matched by
+    /// the failed recipient. Bounces of anything else (warmup mail, say) are skipped.
+    async fn ingest_bounce(&self, account: &ImapAccount, raw: &[u8]) -> Result<Ingested, String> {
+        let Some(dsn) = bounces::parse_dsn(&String::from_utf8_lossy(raw)) else {
+            return Ok(Ingested::Skipped);
+        };
+
+        let target: Option<(Uuid, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT cl.id, cl.campaign_id
+            FROM campaign_leads cl
+            JOIN leads l ON l.id = cl.lead_id
+            WHERE cl.email_account_id = $1 AND LOWER(l.email) = $2 AND cl.sent_at IS NOT NULL
+            ORDER BY cl.sent_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(account.id)
+        .bind(&dsn.recipient)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let Some((campaign_lead_id, campaign_id)) = target else {
+            return Ok(Ingested::Skipped);
+        };
+
+        let bounce = NewBounce {
+            workspace_id: Some(account.workspace_id),
+            campaign_id: Some(campaign_id),
+            campaign_lead_id: Some(campaign_lead_id),
+            email_account_id: Some(account.id),
+            recipient: &dsn.recipient,
+            bounce_type: dsn.bounce_type,
+            source: BounceSource::Imap,
+            reason: dsn.reason.as_deref(),
+            occurred_at: Utc::now(),
+        };
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+        bounces::apply(&mut tx, &bounce).await.map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        Ok(Ingested::Bounce)
+    }
+
+    /// Stores `raw` as a reply if it answers one of our sends, matched by
+    /// In-Reply-To/References first and then by the lead's address. Warmup
+    /// emails from the inbox's partners are recorded as opened instead.
+    async fn ingest(&self, account: &ImapAccount, raw: &[u8]) -> Result<Ingested, String> {
+        let Some(message) = MessageParser::default().parse(raw) else {
+            return Ok(Ingested::Skipped);
+        };
+
+        let Some(from) = message.from().and_then(|f| f.first()) else {
+            return Ok(Ingested::Skipped);
+        };
+        let from_email = from.address().unwrap_or("").trim().to_lowercase();
+        let local_part = from_email.split('@').next().unwrap_or("");
+        let is_report = message
+            .content_type()
+            .is_some_and(|ct| ct.ctype().eq_ignore_ascii_case("multipart") && ct.subtype().is_some_and(|s| s.eq_ignore_ascii_case("report")));
+        if is_report || matches!(local_part, "mailer-daemon" | "postmaster") {
+            return self.ingest_bounce(account, raw).await;
+        }
+
+        // Our own copies
+        if from_email.is_empty() || from_email == account.email.to_lowercase() {
+            return Ok(Ingested::Skipped);
+        }
+
+        if let Some(message_id) = message.message_id() {
+            let message_id = format!("<{}>", message_id.trim_matches(['<', '>']));
+            if warmup_service::record_warmup_opened(self.pool.as_ref(), account.id, &message_id)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                return Ok(Ingested::Warmup);
+            }
+        }
+
+        // mail-parser strips the angle brackets we store Message-IDs with
+        let referenced: Vec<String> = [message.in_reply_to(), message.references()]
+            .iter()
+            .filter_map(|h| h.as_text_list())
+            .flatten()
+            .map(|id| format!("<{}>", id.trim_matches(['<', '>'])))
+            .collect();
+
+        let mut target = None;
+        if !referenced.is_empty() {
+            target = sqlx::query_as::<_, ReplyTarget>(
+                r#"
+                SELECT campaign_id, campaign_lead_id, lead_id FROM sent_emails
+                WHERE workspace_id = $1 AND message_id = ANY($2)
+                ORDER BY sent_at DESC
+                LIMIT 1
+                "#
+            )
+            .bind(account.workspace_id)
+            .bind(&referenced)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        if target.is_none() {
+            target = sqlx::query_as::<_, ReplyTarget>(
+                r#"
+                SELECT cl.campaign_id, cl.id as campaign_lead_id, cl.lead_id
+                FROM campaign_leads cl
+                JOIN leads l ON l.id = cl.lead_id
+                WHERE cl.email_account_id = $1 AND LOWER(l.email) = $2 AND cl.sent_at IS NOT NULL
+                ORDER BY cl.sent_at DESC
+                LIMIT 1
+                "#
+            )
+            .bind(account.id)
+            .bind(&from_email)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+
+        let Some(target) = target else {
+            return Ok(Ingested::Skipped);
+        };
+
+        let body_text = message
+            .body_text(0)
+            .map(|t| t.into_owned())
+            .or_else(|| message.body_html(0).map(|h| strip_tags(&h)))
+            .unwrap_or_default();
+        let body_html = message.body_html(0).map(|h| h.into_owned());
+        let received_at = message
+            .date()
+            .and_then(|d| Utc.timestamp_opt(d.to_timestamp(), 0).single())
+            .unwrap_or_else(Utc::now);
+        let message_id = message.message_id().map(|id| format!("<{}>", id));
+        let in_reply_to = message.in_reply_to().as_text().map(|id| format!("<{}>", id));
+
+        // Automatic responses are labelled outright. Human replies get a quick
+        // heuristic label and stay unclassified so the model can look at them.
+        let auto_reply = is_auto_reply(&message);
+        let (classification, classified_at) = if auto_reply {
+            (reply_classifier::auto_reply_classification(), Some(Utc::now()))
+        } else {
+            (reply_classifier::classify_reply_heuristic(&body_text), None::<DateTime<Utc>>)
+        };
+
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        let inserted: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO email_replies (
+                workspace_id, campaign_id, lead_id, email_account_id, from_email, from_name, subject,
+                body_text, body_html, intent, intent_confidence, sentiment, is_urgent, classified_at,
+                message_id, in_reply_to, received_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (email_account_id, message_id) WHERE message_id IS NOT NULL DO NOTHING
+            RETURNING id
+            "#
+        )
+        .bind(account.workspace_id)
+        .bind(target.campaign_id)
+        .bind(target.lead_id)
+        .bind(account.id)
+        .bind(&from_email)
+        .bind(from.name())
+        .bind(message.subject())
+        .bind(&body_text)
+        .bind(&body_html)
+        .bind(&classification.intent)
+        .bind(classification.confidence)
+        .bind(classification.sentiment)
+        .bind(classification.urgent)
+        .bind(classified_at)
+        .bind(&message_id)
+        .bind(&in_reply_to)
+        .bind(received_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if inserted.is_none() {
+            return Ok(Ingested::Skipped);
+        }
+
+        // Only the first human reply from a lead counts towards the campaign
+        if !auto_reply {
+            let first_reply = sqlx::query(
+                "UPDATE campaign_leads SET replied_at = $2 WHERE id = $1 AND replied_at IS NULL"
+            )
+            .bind(target.campaign_lead_id)
+            .bind(received_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            if first_reply.rows_affected() > 0 {
+                sqlx::query("UPDATE campaigns SET replied = COALESCE(replied, 0) + 1 WHERE id = $1")
+                    .bind(target.campaign_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(Ingested::Reply)
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let re = regex::Regex::new(r"<[^>]*>").unwrap();
+    re.replace_all(html, "").trim().to_string()
+}
+
+/// IMAP quoted string
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// One untagged server response, with the literals (`{n}` blocks) it carried
+struct ImapResponse {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+/// Size of the literal announced at the end of a response line, e.g. `{1234}`
+fn literal_len(line: &str) -> Option<usize> {
+    let inner = line.strip_suffix('}')?;
+    let start = inner.rfind('{')?;
+    inner[start + 1..].trim_end_matches('+').parse().ok()
+}
+
+/// Number in a response code such as `[UIDVALIDITY 3857529045]`
+fn response_code(responses: &[ImapResponse], code: &str) -> Option<u32> {
+    let marker = format!("[{} ", code);
+    responses.iter().find_map(|r| {
+        let rest = &r.text[r.text.find(&marker)? + marker.len()..];
+        rest[..rest.find(']')?].trim().parse().ok()
+    })
+}
+
+fn search_results(responses: &[ImapResponse]) -> Vec<u32> {
+    responses
+        .iter()
+        .filter_map(|r| r.text.strip_prefix("* SEARCH"))
+        .flat_map(|ids| ids.split_whitespace().filter_map(|id| id.parse().ok()))
+        .collect()
+}
+
+/// Just enough IMAP4rev1 over implicit TLS for polling: login, select, search
+/// and fetch, one command at a time.
+struct ImapSession {
+    stream: BufReader<TlsStream<TcpStream>>,
+    next_tag: u32,
+}
+
+impl ImapSession {
+    async fn connect(host: &str, port: u16) -> Result<Self, String> {
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| format!("Failed to connect to {}:{}: {}", host, port, e))?;
+        let connector = native_tls::TlsConnector::new().map_err(|e| format!("TLS error: {}", e))?;
+        let tls = TlsConnector::from(connector)
+            .connect(host, tcp)
+            .await
+            .map_err(|e| format!("TLS handshake with {} failed: {}", host, e))?;
+
+        let mut session = Self { stream: BufReader::new(tls), next_tag: 0 };
+        let greeting = session.read_response().await?;
+        if !greeting.text.starts_with("* OK") && !greeting.text.starts_with("* PREAUTH") {
+            return Err(format!("Unexpected IMAP greeting: {}", greeting.text));
+        }
+
+        Ok(session)
+    }
+
+    async fn read_line(&mut self) -> Result<String, String> {
+        let mut buf = Vec::new();
+        let read = self.stream.read_until(b'\n', &mut buf).await.map_err(|e| format!("IMAP read failed: {}", e))?;
+        if read == 0 {
+            return Err("IMAP server closed the connection".to_string());
+        }
+        Ok(String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    async fn read_response(&mut self) -> Result<ImapResponse, String> {
+        let mut response = ImapResponse { text: String::new(), literals: Vec::new() };
+        loop {
+            let line = self.read_line().await?;
+            let literal = literal_len(&line);
+            response.text.push_str(&line);
+
+            let Some(len) = literal else {
+                return Ok(response);
+            };
+            let mut literal = vec![0; len];
+            self.stream.read_exact(&mut literal).await.map_err(|e| format!("IMAP read failed: {}", e))?;
+            response.literals.push(literal);
+        }
+    }
+
+    /// Runs a command and returns its untagged responses, or the server's
+    /// message if it didn't answer OK.
+    async fn command(&mut self, command: &str) -> Result<Vec<ImapResponse>, String> {
+        self.next_tag += 1;
+        let tag = format!("A{:04}", self.next_tag);
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(format!("{} {}\r\n", tag, command).as_bytes())
+            .await
+            .map_err(|e| format!("IMAP write failed: {}", e))?;
+        stream.flush().await.map_err(|e| format!("IMAP write failed: {}", e))?;
+
+        // Never echo the command itself: LOGIN and AUTHENTICATE carry credentials
+        let verb = command.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+        let tagged = format!("{} ", tag);
+        let mut untagged = Vec::new();
+        loop {
+            let response = self.read_response().await?;
+            // A rejected XOAUTH2 login sends its error as a challenge; an empty
+            // reply lets the server finish with the tagged NO
+            if response.text.starts_with('+') && verb.starts_with("AUTHENTICATE") {
+                let stream = self.stream.get_mut();
+                stream.write_all(b"\r\n").await.map_err(|e| format!("IMAP write failed: {}", e))?;
+                stream.flush().await.map_err(|e| format!("IMAP write failed: {}", e))?;
+                continue;
+            }
+            if let Some(status) = response.text.strip_prefix(&tagged) {
+                return if status.starts_with("OK") {
+                    Ok(untagged)
+                } else if verb.starts_with("LOGIN") || verb.starts_with("AUTHENTICATE") {
+                    Err(format!("IMAP login failed: {}", status))
+                } else {
+                    Err(format!("IMAP {} failed: {}", verb, status))
+                };
+            }
+            untagged.push(response);
+        }
+    }
+
+    async fn fetch_message(&mut self, uid: u32) -> Result<Vec<u8>, String> {
+        let responses = self.command(&format!("UID FETCH {} (BODY.PEEK[])", uid)).await?;
+        responses
+            .into_iter()
+            .find(|r| r.text.contains("FETCH"))
+            .and_then(|r| r.literals.into_iter().next())
+            .ok_or_else(|| format!("Message {} has no body", uid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imap_server_and_response_parsing() {
+        assert_eq!(imap_server("google", "smtp.gmail.com"), "imap.gmail.com");
+        assert_eq!(imap_server("other", "smtp.acme.io"), "imap.acme.io");
+        assert_eq!(imap_server("other", "mail.acme.io"), "mail.acme.io");
+
+        assert_eq!(literal_len("* 1 FETCH (UID 7 BODY[] {2048}"), Some(2048));
+        assert_eq!(literal_len("* 1 FETCH (UID 7 FLAGS (\\Seen))"), None);
+
+        let responses = vec![
+            ImapResponse { text: "* OK [UIDVALIDITY 3857529045] UIDs valid".to_string(), literals: vec![] },
+            ImapResponse { text: "* OK [UIDNEXT 42] Predicted next UID".to_string(), literals: vec![] },
+            ImapResponse { text: "* SEARCH 38 40 41".to_string(), literals: vec![] },
+        ];
+        assert_eq!(response_code(&responses, "UIDVALIDITY"), Some(3857529045));
+        assert_eq!(response_code(&responses, "UIDNEXT"), Some(42));
+        assert_eq!(search_results(&responses), vec![38, 40, 41]);
+    }
+
+    #[test]
+    fn test_auto_replies_are_recognized() {
+        let parse = |raw: &'static str| MessageParser::default().parse(raw.as_bytes()).unwrap();
+
+        let human = parse("From: Jane <jane@example.com>\r\nSubject: Re: Quick question\r\n\r\nSounds good, Tuesday works.\r\n");
+        assert!(!is_auto_reply(&human));
+
+        let out_of_office = parse("From: jane@example.com\r\nSubject: Out of Office: Re: Quick question\r\n\r\nBack Monday.\r\n");
+        assert!(is_auto_reply(&out_of_office));
+
+        let auto_submitted = parse("From: jane@example.com\r\nAuto-Submitted: auto-replied\r\nSubject: Re: Quick question\r\n\r\nThanks!\r\n");
+        assert!(is_auto_reply(&auto_submitted));
+    }
+}
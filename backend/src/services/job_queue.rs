@@ -12,6 +12,25 @@ pub enum JobType {
     WarmupEmail,
     ProcessCampaign,
     UpdateAnalytics,
+    ClassifyReply,
+}
+
+/// Claim order for jobs; higher runs first, oldest first within a priority.
+/// Transactional sends use `PRIORITY_HIGH` so bulk work queued earlier can't hold them up.
+pub const PRIORITY_HIGH: i32 = 10;
+pub const PRIORITY_NORMAL: i32 = 0;
+pub const PRIORITY_LOW: i32 = -10;
+
+impl JobType {
+    pub fn default_priority(&self) -> i32 {
+        match self {
+            JobType::SendEmail => PRIORITY_HIGH,
+            JobType::WarmupEmail => PRIORITY_LOW,
+            JobType::VerifyEmail | JobType::ProcessCampaign | JobType::UpdateAnalytics | JobType::ClassifyReply => {
+                PRIORITY_NORMAL
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -49,6 +68,7 @@ pub struct Job {
     pub error: Option<String>,
     pub workspace_id: Option<Uuid>,
     pub next_retry_at: Option<DateTime<Utc>>,
+    pub priority: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,8 +90,14 @@ pub struct VerifyEmailPayload {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WarmupEmailPayload {
+    /// Inbox sending the warmup email
     pub email_account_id: Uuid,
+    /// Partner inbox in the same workspace receiving it
+    pub recipient_account_id: Uuid,
     pub target_email: String,
+    /// The warmup email this answers, when it's the partner's reply
+    #[serde(default)]
+    pub in_reply_to: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +105,22 @@ pub struct ProcessCampaignPayload {
     pub campaign_id: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifyReplyPayload {
+    /// Set on jobs queued before replies were classified in batches
+    #[serde(default)]
+    pub reply_id: Option<Uuid>,
+    #[serde(default)]
+    pub reply_ids: Vec<Uuid>,
+}
+
+impl ClassifyReplyPayload {
+    /// Every reply the job covers
+    pub fn reply_ids(&self) -> Vec<Uuid> {
+        self.reply_id.into_iter().chain(self.reply_ids.iter().copied()).collect()
+    }
+}
+
 pub struct JobQueue {
     pool: Arc<PgPool>,
     sender: mpsc::Sender<Job>,
@@ -90,14 +132,22 @@ impl JobQueue {
         (Self { pool, sender }, receiver)
     }
 
-    pub async fn enqueue(&self, job_type: JobType, payload: serde_json::Value, workspace_id: Option<Uuid>) -> Result<Uuid, String> {
+    /// Queues a job at `priority`, or the job type's default priority when `None`
+    pub async fn enqueue(
+        &self,
+        job_type: JobType,
+        payload: serde_json::Value,
+        workspace_id: Option<Uuid>,
+        priority: Option<i32>,
+    ) -> Result<Uuid, String> {
         let job_id = Uuid::new_v4();
         let now = Utc::now();
+        let priority = priority.unwrap_or_else(|| job_type.default_priority());
 
         let result = sqlx::query(
             r#"
-            INSERT INTO jobs (id, job_type, payload, status, attempts, max_attempts, created_at, workspace_id, retry_count, max_retries)
-            VALUES ($1, $2, $3, 'pending', 0, 3, $4, $5, 0, 3)
+            INSERT INTO jobs (id, job_type, payload, status, attempts, max_attempts, created_at, workspace_id, retry_count, max_retries, priority)
+            VALUES ($1, $2, $3, 'pending', 0, 3, $4, $5, 0, 3, $6)
             "#
         )
         .bind(job_id)
@@ -105,6 +155,7 @@ impl JobQueue {
         .bind(&payload)
         .bind(now)
         .bind(workspace_id)
+        .bind(priority)
         .execute(self.pool.as_ref())
         .await;
 
@@ -123,6 +174,7 @@ impl JobQueue {
                     error: None,
                     workspace_id,
                     next_retry_at: None,
+                    priority,
                 };
                 
                 let _ = self.sender.send(job).await;
@@ -132,39 +184,42 @@ impl JobQueue {
         }
     }
 
-    pub async fn enqueue_send_email(&self, payload: SendEmailPayload, workspace_id: Option<Uuid>) -> Result<Uuid, String> {
-        self.enqueue(JobType::SendEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id).await
+    pub async fn enqueue_send_email(&self, payload: SendEmailPayload, workspace_id: Option<Uuid>, priority: Option<i32>) -> Result<Uuid, String> {
+        self.enqueue(JobType::SendEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id, priority).await
     }
 
-    pub async fn enqueue_verify_email(&self, payload: VerifyEmailPayload, workspace_id: Option<Uuid>) -> Result<Uuid, String> {
-        self.enqueue(JobType::VerifyEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id).await
+    pub async fn enqueue_verify_email(&self, payload: VerifyEmailPayload, workspace_id: Option<Uuid>, priority: Option<i32>) -> Result<Uuid, String> {
+        self.enqueue(JobType::VerifyEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id, priority).await
     }
 
-    pub async fn enqueue_warmup_email(&self, payload: WarmupEmailPayload, workspace_id: Option<Uuid>) -> Result<Uuid, String> {
-        self.enqueue(JobType::WarmupEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id).await
+    pub async fn enqueue_warmup_email(&self, payload: WarmupEmailPayload, workspace_id: Option<Uuid>, priority: Option<i32>) -> Result<Uuid, String> {
+        self.enqueue(JobType::WarmupEmail, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id, priority).await
     }
 
-    pub async fn enqueue_process_campaign(&self, payload: ProcessCampaignPayload, workspace_id: Option<Uuid>) -> Result<Uuid, String> {
-        self.enqueue(JobType::ProcessCampaign, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id).await
+    pub async fn enqueue_process_campaign(&self, payload: ProcessCampaignPayload, workspace_id: Option<Uuid>, priority: Option<i32>) -> Result<Uuid, String> {
+        self.enqueue(JobType::ProcessCampaign, serde_json::to_value(payload).map_err(|e| e.to_string())?, workspace_id, priority).await
     }
 
     /// Atomically claim pending jobs using SELECT FOR UPDATE SKIP LOCKED
-    /// This prevents race conditions when multiple workers are running
+    /// This prevents race conditions when multiple workers are running.
+    /// Jobs come back highest priority first.
     pub async fn claim_pending_jobs(&self, limit: i32) -> Vec<Job> {
         // Use a transaction with FOR UPDATE SKIP LOCKED for atomic claiming
-        let result = sqlx::query_as::<_, (Uuid, String, serde_json::Value, i32, i32, DateTime<Utc>, Option<Uuid>)>(
+        let result = sqlx::query_as::<_, (Uuid, String, serde_json::Value, i32, i32, DateTime<Utc>, Option<Uuid>, i32)>(
             r#"
             WITH claimed AS (
                 SELECT id FROM jobs
                 WHERE (status = 'pending' OR (status = 'scheduled' AND next_retry_at <= NOW()))
-                ORDER BY created_at ASC
+                ORDER BY priority DESC, created_at ASC
                 LIMIT $1
                 FOR UPDATE SKIP LOCKED
+            ), updated AS (
+                UPDATE jobs SET status = 'processing', started_at = NOW(), retry_count = retry_count + 1
+                FROM claimed
+                WHERE jobs.id = claimed.id
+                RETURNING jobs.id, jobs.job_type, jobs.payload, jobs.retry_count, jobs.max_retries, jobs.created_at, jobs.workspace_id, jobs.priority
             )
-            UPDATE jobs SET status = 'processing', started_at = NOW(), retry_count = retry_count + 1
-            FROM claimed
-            WHERE jobs.id = claimed.id
-            RETURNING jobs.id, jobs.job_type, jobs.payload, jobs.retry_count, jobs.max_retries, jobs.created_at, jobs.workspace_id
+            SELECT * FROM updated ORDER BY priority DESC, created_at ASC
             "#
         )
         .bind(limit)
@@ -172,7 +227,7 @@ impl JobQueue {
         .await;
 
         match result {
-            Ok(rows) => rows.into_iter().map(|(id, job_type_str, payload, attempts, max_attempts, created_at, workspace_id)| {
+            Ok(rows) => rows.into_iter().map(|(id, job_type_str, payload, attempts, max_attempts, created_at, workspace_id, priority)| {
                 Job {
                     id,
                     job_type: serde_json::from_str(&job_type_str).unwrap_or(JobType::SendEmail),
@@ -186,6 +241,7 @@ impl JobQueue {
                     error: None,
                     workspace_id,
                     next_retry_at: None,
+                    priority,
                 }
             }).collect(),
             Err(e) => {
@@ -206,36 +262,65 @@ impl JobQueue {
         .map_err(|e| e.to_string())
     }
 
-    pub async fn mark_failed(&self, job_id: Uuid, error: &str) -> Result<(), String> {
-        // Calculate exponential backoff for retry: 30s, 2min, 8min
-        let backoff_seconds = 30;
-        let next_retry = Utc::now() + Duration::seconds(backoff_seconds);
-        
-        sqlx::query(
-            r#"
-            UPDATE jobs 
-            SET status = CASE 
-                    WHEN retry_count < max_retries THEN 'scheduled' 
-                    ELSE 'failed' 
-                END,
-                error = $2,
-                next_retry_at = CASE 
-                    WHEN retry_count < max_retries THEN $3 
-                    ELSE NULL 
-                END
-            WHERE id = $1
-            "#
-        )
-        .bind(job_id)
-        .bind(error)
-        .bind(next_retry)
-        .execute(self.pool.as_ref())
-        .await
-        .map(|_| ())
-        .map_err(|e| e.to_string())
+    /// Schedules the job's next attempt, or fails it once its retries are used up.
+    /// `retry_count` is the job's count after the claim that just failed.
+    pub async fn mark_failed(&self, job_id: Uuid, retry_count: i32, error: &str) -> Result<(), String> {
+        mark_failed(self.pool.as_ref(), job_id, retry_count, error).await
     }
 }
 
+/// Delay before the first retry; each later retry waits `RETRY_BACKOFF_FACTOR` times longer
+pub const RETRY_BASE_DELAY_SECONDS: i64 = 60;
+pub const RETRY_BACKOFF_FACTOR: i64 = 5;
+/// No retry waits longer than this
+pub const RETRY_MAX_DELAY_SECONDS: i64 = 60 * 60;
+
+/// When a job that just failed should run again: 1 minute after the first failed
+/// attempt, then 5 and 25 minutes, capped at an hour. `retry_count` is the job's
+/// count after the claim, so 1 is the first attempt; 0 (never claimed) is treated
+/// the same.
+pub fn compute_next_retry(retry_count: i32) -> DateTime<Utc> {
+    Utc::now() + retry_delay(retry_count)
+}
+
+fn retry_delay(retry_count: i32) -> Duration {
+    let exponent = retry_count.saturating_sub(1).clamp(0, 10) as u32;
+    let seconds = RETRY_BACKOFF_FACTOR
+        .checked_pow(exponent)
+        .and_then(|factor| factor.checked_mul(RETRY_BASE_DELAY_SECONDS))
+        .unwrap_or(RETRY_MAX_DELAY_SECONDS)
+        .min(RETRY_MAX_DELAY_SECONDS);
+    Duration::seconds(seconds)
+}
+
+/// Shared by every worker loop so retry timing doesn't depend on which one ran
+/// the job. The last attempt leaves the job `failed` with no retry time.
+pub async fn mark_failed(pool: &PgPool, job_id: Uuid, retry_count: i32, error: &str) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        UPDATE jobs 
+        SET status = CASE 
+                WHEN retry_count < max_retries THEN 'scheduled' 
+                ELSE 'failed' 
+            END,
+            error = $2,
+            failed_at = NOW(),
+            next_retry_at = CASE 
+                WHEN retry_count < max_retries THEN $3 
+                ELSE NULL 
+            END
+        WHERE id = $1
+        "#
+    )
+    .bind(job_id)
+    .bind(error)
+    .bind(compute_next_retry(retry_count))
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
 pub struct JobWorker {
     pool: Arc<PgPool>,
     queue: Arc<JobQueue>,
@@ -267,7 +352,7 @@ impl JobWorker {
                     }
                     Err(e) => {
                         eprintln!("Job {} failed: {}", job.id, e);
-                        if let Err(mark_err) = self.queue.mark_failed(job.id, &e).await {
+                        if let Err(mark_err) = self.queue.mark_failed(job.id, job.attempts, &e).await {
                             eprintln!("Failed to mark job {} as failed: {}", job.id, mark_err);
                         }
                     }
@@ -285,9 +370,18 @@ impl JobWorker {
             JobType::WarmupEmail => self.process_warmup_email(job).await,
             JobType::ProcessCampaign => self.process_campaign(job).await,
             JobType::UpdateAnalytics => self.process_analytics(job).await,
+            JobType::ClassifyReply => self.process_classify_reply(job).await,
         }
     }
 
+    async fn process_classify_reply(&self, job: &Job) -> Result<(), String> {
+        let payload: ClassifyReplyPayload = serde_json::from_value(job.payload.clone())
+            .map_err(|e| e.to_string())?;
+
+        crate::services::reply_classifier::classify_and_store_batch(&self.pool, &payload.reply_ids()).await?;
+        Ok(())
+    }
+
     async fn process_send_email(&self, job: &Job) -> Result<(), String> {
         let payload: SendEmailPayload = serde_json::from_value(job.payload.clone())
             .map_err(|e| e.to_string())?;
@@ -358,7 +452,8 @@ impl JobWorker {
             .map_err(|e| e.to_string())?;
 
         let verifier = crate::services::email_verifier::EmailVerifier::new().await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| e.to_string())?
+            .with_cache(Arc::new(crate::services::email_verifier::PgVerificationCache::new(self.pool.clone())));
         let (status, confidence) = verifier.verify_email(&payload.email).await;
 
         let _ = sqlx::query(
@@ -377,8 +472,8 @@ impl JobWorker {
         let payload: WarmupEmailPayload = serde_json::from_value(job.payload.clone())
             .map_err(|e| e.to_string())?;
 
-        // Warmup emails are sent to partner inboxes to build reputation
-        // This is a simplified implementation
+        // Sending needs SMTP connections, which only the worker binary holds; see
+        // `CampaignEmailSender::send_warmup_email`
         println!("Processing warmup email for account {} to {}", payload.email_account_id, payload.target_email);
         
         Ok(())
@@ -390,7 +485,7 @@ impl JobWorker {
 
         // Get campaign details
         let campaign = sqlx::query_as::<_, (String, i32)>(
-            "SELECT status, total_leads FROM campaigns WHERE id = $1"
+            "SELECT status, total_leads FROM campaigns WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(payload.campaign_id)
         .fetch_optional(self.pool.as_ref())
@@ -408,7 +503,7 @@ impl JobWorker {
             SELECT l.id, l.email, l.first_name
             FROM leads l
             INNER JOIN campaign_leads cl ON l.id = cl.lead_id
-            WHERE cl.campaign_id = $1 AND cl.status = 'pending'
+            WHERE cl.campaign_id = $1 AND cl.status = 'pending' AND l.deleted_at IS NULL
             LIMIT 50
             "#
         )
@@ -427,3 +522,213 @@ impl JobWorker {
         Ok(())
     }
 }
+
+/// A job that used up its retries, with the error from its last attempt
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct FailedJob {
+    pub id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub error: Option<String>,
+    pub retry_count: i32,
+    pub max_retries: i32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// A job still `processing` this long after it was claimed belongs to a worker
+/// that stopped without finishing it
+pub const STUCK_JOB_MINUTES: i32 = 10;
+
+/// Most recently failed first
+pub async fn failed_jobs(pool: &PgPool, limit: i64) -> Result<Vec<FailedJob>, sqlx::Error> {
+    sqlx::query_as::<_, FailedJob>(
+        r#"
+        SELECT id, workspace_id, TRIM(BOTH '"' FROM job_type) as job_type, payload, error,
+               COALESCE(retry_count, 0) as retry_count, COALESCE(max_retries, 0) as max_retries,
+               COALESCE(created_at, NOW()) as created_at, started_at
+        FROM jobs
+        WHERE status = 'failed'
+        ORDER BY started_at DESC NULLS LAST, created_at DESC
+        LIMIT $1
+        "#
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Puts a failed job back in the queue with its retries reset. Returns false if
+/// there is no failed job with that ID.
+pub async fn requeue_job(pool: &PgPool, job_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = 'pending', retry_count = 0, next_retry_at = NULL, started_at = NULL, completed_at = NULL
+        WHERE id = $1 AND status = 'failed'
+        "#
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns jobs a crashed worker left in `processing` to the queue. The claim
+/// already counted an attempt, so a job that keeps taking its worker down ends
+/// up failed once its retries run out rather than looping forever.
+pub async fn reclaim_stuck_jobs(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = CASE WHEN retry_count >= max_retries THEN 'failed' ELSE 'pending' END,
+            error = 'Worker stopped while processing the job',
+            failed_at = NOW(),
+            started_at = CASE WHEN retry_count >= max_retries THEN started_at ELSE NULL END
+        WHERE status = 'processing'
+          AND started_at < NOW() - make_interval(mins => $1)
+        "#
+    )
+    .bind(STUCK_JOB_MINUTES)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_retry_delays_grow_and_cap() {
+        let minutes = |retry_count| retry_delay(retry_count).num_minutes();
+        assert_eq!(minutes(0), 1);
+        assert_eq!(minutes(1), 1);
+        assert_eq!(minutes(2), 5);
+        assert_eq!(minutes(3), 25);
+        assert_eq!(minutes(4), 60);
+        assert_eq!(minutes(i32::MAX), 60);
+    }
+
+    async fn insert_job(pool: &PgPool, workspace_id: Uuid, status: &str, retry_count: i32, started_minutes_ago: i32) -> Uuid {
+        let job_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, workspace_id, job_type, payload, status, retry_count, max_retries, started_at, error)
+            VALUES ($1, $2, '"SendEmail"', '{}', $3, $4, 3, NOW() - make_interval(mins => $5), 'SMTP error: timeout')
+            "#
+        )
+        .bind(job_id)
+        .bind(workspace_id)
+        .bind(status)
+        .bind(retry_count)
+        .bind(started_minutes_ago)
+        .execute(pool)
+        .await
+        .unwrap();
+        job_id
+    }
+
+    async fn job_state(pool: &PgPool, job_id: Uuid) -> (String, i32) {
+        sqlx::query_as("SELECT status, retry_count FROM jobs WHERE id = $1")
+            .bind(job_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_high_priority_job_is_claimed_first() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Job queue test").await;
+        let (queue, _receiver) = JobQueue::new(pool.clone());
+
+        let warmup = queue
+            .enqueue_warmup_email(
+                WarmupEmailPayload {
+                    email_account_id: Uuid::new_v4(),
+                    recipient_account_id: Uuid::new_v4(),
+                    target_email: "seed@example.com".to_string(),
+                    in_reply_to: None,
+                },
+                Some(workspace_id),
+                None,
+            )
+            .await
+            .unwrap();
+        let urgent = queue
+            .enqueue_verify_email(
+                VerifyEmailPayload { lead_id: Uuid::new_v4(), email: "lead@example.com".to_string() },
+                Some(workspace_id),
+                Some(PRIORITY_HIGH),
+            )
+            .await
+            .unwrap();
+
+        // Other jobs in a shared database may be claimed too; only the relative order matters
+        let claimed: Vec<Uuid> = queue.claim_pending_jobs(1000).await.into_iter().map(|job| job.id).collect();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let position = |id| claimed.iter().position(|claimed| *claimed == id).unwrap();
+        assert!(position(urgent) < position(warmup));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_stuck_jobs_are_reclaimed() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Job queue test").await;
+
+        let stuck = insert_job(&pool, workspace_id, "processing", 1, 30).await;
+        let out_of_retries = insert_job(&pool, workspace_id, "processing", 3, 30).await;
+        let running = insert_job(&pool, workspace_id, "processing", 1, 2).await;
+
+        reclaim_stuck_jobs(&pool).await.unwrap();
+        let states = [
+            job_state(&pool, stuck).await,
+            job_state(&pool, out_of_retries).await,
+            job_state(&pool, running).await,
+        ];
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(states[0], ("pending".to_string(), 1));
+        assert_eq!(states[1], ("failed".to_string(), 3));
+        assert_eq!(states[2], ("processing".to_string(), 1));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_failed_job_is_requeued_with_fresh_retries() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Job queue test").await;
+
+        let failed = insert_job(&pool, workspace_id, "failed", 3, 60).await;
+        let completed = insert_job(&pool, workspace_id, "completed", 1, 60).await;
+
+        let listed = failed_jobs(&pool, 1000).await.unwrap();
+        let requeued = requeue_job(&pool, failed).await.unwrap();
+        let requeued_twice = requeue_job(&pool, failed).await.unwrap();
+        let requeued_completed = requeue_job(&pool, completed).await.unwrap();
+        let state = job_state(&pool, failed).await;
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let listed = listed.iter().find(|job| job.id == failed).unwrap();
+        assert_eq!(listed.job_type, "SendEmail");
+        assert_eq!(listed.error.as_deref(), Some("SMTP error: timeout"));
+        assert!(requeued);
+        assert!(!requeued_twice);
+        assert!(!requeued_completed);
+        assert_eq!(state, ("pending".to_string(), 0));
+    }
+}
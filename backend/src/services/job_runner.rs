@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// Number of claimed jobs processed in parallel when `WORKER_CONCURRENCY` is unset
+pub const DEFAULT_WORKER_CONCURRENCY: usize = 4;
+
+/// Reads `WORKER_CONCURRENCY`, falling back to the default for missing or invalid values
+pub fn worker_concurrency() -> usize {
+    std::env::var("WORKER_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_WORKER_CONCURRENCY)
+}
+
+/// Runs `handler` over every item with at most `concurrency` tasks in flight.
+/// Outputs are returned in completion order; panicking tasks are logged and dropped.
+pub async fn run_bounded<T, F, Fut>(items: Vec<T>, concurrency: usize, handler: F) -> Vec<Fut::Output>
+where
+    T: Send + 'static,
+    F: Fn(T) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    let concurrency = concurrency.max(1);
+    let handler = Arc::new(handler);
+    let mut tasks = JoinSet::new();
+    let mut outputs = Vec::with_capacity(items.len());
+
+    for item in items {
+        while tasks.len() >= concurrency {
+            collect_next(&mut tasks, &mut outputs).await;
+        }
+        let handler = handler.clone();
+        tasks.spawn(async move { handler(item).await });
+    }
+
+    while !tasks.is_empty() {
+        collect_next(&mut tasks, &mut outputs).await;
+    }
+
+    outputs
+}
+
+async fn collect_next<O: Send + 'static>(tasks: &mut JoinSet<O>, outputs: &mut Vec<O>) {
+    match tasks.join_next().await {
+        Some(Ok(output)) => outputs.push(output),
+        Some(Err(e)) => tracing::error!("Worker task failed: {}", e),
+        None => {}
+    }
+}
+
+/// Caps how many tasks may work against the same key (e.g. one sending inbox) at once,
+/// so parallel workers don't open concurrent SMTP sessions on a single account.
+pub struct KeyedLimiter {
+    permits_per_key: usize,
+    slots: Mutex<HashMap<Uuid, Arc<Semaphore>>>,
+}
+
+impl KeyedLimiter {
+    pub fn new(permits_per_key: usize) -> Self {
+        Self {
+            permits_per_key: permits_per_key.max(1),
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn acquire(&self, key: Uuid) -> OwnedSemaphorePermit {
+        let semaphore = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            slots
+                .entry(key)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.permits_per_key)))
+                .clone()
+        };
+
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("keyed limiter semaphore is never closed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_independent_jobs_complete_concurrently() {
+        let started = Instant::now();
+        let outputs = run_bounded((0..4).collect(), 4, |n: u32| async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            n
+        })
+        .await;
+
+        assert_eq!(outputs.len(), 4);
+        assert!(started.elapsed() < Duration::from_millis(600));
+
+        // Jobs sharing a key are still serialized
+        let limiter = Arc::new(KeyedLimiter::new(1));
+        let inbox = Uuid::new_v4();
+        let started = Instant::now();
+        run_bounded((0..2).collect(), 2, move |_: u32| {
+            let limiter = limiter.clone();
+            async move {
+                let _permit = limiter.acquire(inbox).await;
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+        })
+        .await;
+
+        assert!(started.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_jobs_beyond_the_limit_wait_for_a_free_slot() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let started = Instant::now();
+
+        let counters = (in_flight.clone(), max_in_flight.clone());
+        let outputs = run_bounded((0..9).collect(), 3, move |n: u32| {
+            let (in_flight, max_in_flight) = counters.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                n
+            }
+        })
+        .await;
+
+        // Three waves of three, well short of the 900ms nine serial jobs would take
+        let elapsed = started.elapsed();
+        assert_eq!(outputs.len(), 9);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+        assert!(elapsed >= Duration::from_millis(300));
+        assert!(elapsed < Duration::from_millis(700));
+    }
+}
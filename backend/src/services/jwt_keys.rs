@@ -0,0 +1,116 @@
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{de::DeserializeOwned, Serialize};
+use std::env;
+use std::sync::LazyLock;
+
+pub const MIN_SECRET_BYTES: usize = 32;
+
+static JWT_KEYS: LazyLock<JwtKeys> = LazyLock::new(|| {
+    // Unit tests sign links and state without any environment set up
+    #[cfg(test)]
+    if env::var("JWT_SECRET").is_err() {
+        return JwtKeys::new("unit-test-secret-unit-test-secret".to_string(), None).unwrap();
+    }
+    JwtKeys::from_env().unwrap_or_else(|e| panic!("Invalid JWT configuration: {}", e))
+});
+
+/// Process-wide keys from the environment. Binaries touch this at startup so a
+/// missing or weak `JWT_SECRET` stops them before they serve anything.
+pub fn jwt_keys() -> &'static JwtKeys {
+    &JWT_KEYS
+}
+
+/// Signs with `JWT_SECRET` and verifies with it or `JWT_SECRET_PREVIOUS`, so tokens
+/// issued before a rotation keep working until they expire.
+pub struct JwtKeys {
+    current: String,
+    previous: Option<String>,
+}
+
+impl JwtKeys {
+    pub fn from_env() -> Result<Self, String> {
+        let current = env::var("JWT_SECRET").map_err(|_| "JWT_SECRET must be set")?;
+        let previous = env::var("JWT_SECRET_PREVIOUS").ok().filter(|s| !s.trim().is_empty());
+        Self::new(current, previous)
+    }
+
+    pub fn new(current: String, previous: Option<String>) -> Result<Self, String> {
+        if current.len() < MIN_SECRET_BYTES {
+            return Err(format!("JWT_SECRET must be at least {} bytes", MIN_SECRET_BYTES));
+        }
+        if previous.as_ref().is_some_and(|p| p.len() < MIN_SECRET_BYTES) {
+            return Err(format!("JWT_SECRET_PREVIOUS must be at least {} bytes", MIN_SECRET_BYTES));
+        }
+        Ok(Self { current, previous })
+    }
+
+    /// Secret for new signatures, also used to key the app's other HMACs
+    pub fn current_secret(&self) -> &str {
+        &self.current
+    }
+
+    /// Current secret first, then the one being rotated out
+    pub fn verification_secrets(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.current.as_str()).chain(self.previous.as_deref())
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, jsonwebtoken::errors::Error> {
+        encode(&Header::default(), claims, &EncodingKey::from_secret(self.current.as_bytes()))
+    }
+
+    /// Claims from a valid, unexpired token signed with either secret
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> Option<T> {
+        self.verification_secrets().find_map(|secret| {
+            decode::<T>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::default())
+                .ok()
+                .map(|data| data.claims)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestClaims {
+        sub: String,
+        exp: usize,
+    }
+
+    fn claims() -> TestClaims {
+        TestClaims {
+            sub: "alice@acme.io".to_string(),
+            exp: (chrono::Utc::now().timestamp() + 3600) as usize,
+        }
+    }
+
+    #[test]
+    fn test_rejects_missing_or_short_secrets() {
+        assert!(JwtKeys::new("too-short".to_string(), None).is_err());
+        assert!(JwtKeys::new("a".repeat(32), Some("short".to_string())).is_err());
+        assert!(JwtKeys::new("a".repeat(32), Some("b".repeat(32))).is_ok());
+    }
+
+    #[test]
+    fn test_previous_secret_still_verifies_after_rotation() {
+        let old_secret = "old-secret-old-secret-old-secret".to_string();
+        let before = JwtKeys::new(old_secret.clone(), None).unwrap();
+        let token = before.encode(&claims()).unwrap();
+
+        let rotated = JwtKeys::new("new-secret-new-secret-new-secret".to_string(), Some(old_secret)).unwrap();
+        assert_eq!(rotated.decode::<TestClaims>(&token).map(|c| c.sub), Some("alice@acme.io".to_string()));
+
+        // Once the old secret is dropped, its tokens stop working
+        let finished = JwtKeys::new("new-secret-new-secret-new-secret".to_string(), None).unwrap();
+        assert!(finished.decode::<TestClaims>(&token).is_none());
+    }
+
+    #[test]
+    fn test_token_from_unknown_key_is_rejected() {
+        let attacker = JwtKeys::new("x".repeat(48), None).unwrap();
+        let keys = JwtKeys::new("a".repeat(32), Some("b".repeat(32))).unwrap();
+        assert!(keys.decode::<TestClaims>(&attacker.encode(&claims()).unwrap()).is_none());
+    }
+}
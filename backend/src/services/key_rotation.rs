@@ -0,0 +1,352 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::encryption::EncryptionService;
+
+#[derive(Debug, sqlx::FromRow)]
+struct StoredPassword {
+    id: Uuid,
+    smtp_password_encrypted: Vec<u8>,
+    encryption_key_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyRotationFailure {
+    pub email_account_id: Uuid,
+    pub error: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct StoredTotpSecret {
+    id: Uuid,
+    totp_secret_encrypted: Vec<u8>,
+    totp_key_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpRotationFailure {
+    pub user_id: Uuid,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpRotationSummary {
+    pub key_id: String,
+    pub rotated: usize,
+    pub failed: Vec<TotpRotationFailure>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyRotationSummary {
+    pub key_id: String,
+    pub rotated: usize,
+    /// Legacy plaintext passwords encrypted on this run
+    pub encrypted_plaintext: usize,
+    pub failed: Vec<KeyRotationFailure>,
+}
+
+/// Encrypts every password still in the legacy plaintext `smtp_password`
+/// column and clears it. A row that already has a ciphertext the current keys
+/// can read only loses its plaintext copy. Returns how many rows changed.
+pub async fn encrypt_plaintext_passwords(pool: &PgPool, encryption: &EncryptionService) -> Result<usize, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query_as::<_, (Uuid, String, Option<Vec<u8>>, Option<String>)>(
+        r#"
+        SELECT id, smtp_password, smtp_password_encrypted, encryption_key_id
+        FROM email_accounts
+        WHERE smtp_password IS NOT NULL
+        FOR UPDATE
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to load plaintext passwords: {}", e))?;
+
+    for (id, plaintext, encrypted, key_id) in &rows {
+        let readable = encrypted
+            .as_deref()
+            .is_some_and(|encrypted| encryption.decrypt_with_key_id(encrypted, key_id.as_deref()).is_ok());
+
+        if readable {
+            sqlx::query("UPDATE email_accounts SET smtp_password = NULL WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to update email account {}: {}", id, e))?;
+            continue;
+        }
+
+        let (ciphertext, key_id) = encryption.encrypt(plaintext)?;
+        sqlx::query(
+            "UPDATE email_accounts SET smtp_password = NULL, smtp_password_encrypted = $1, encryption_key_id = $2 WHERE id = $3"
+        )
+        .bind(&ciphertext)
+        .bind(&key_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update email account {}: {}", id, e))?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if !rows.is_empty() {
+        tracing::info!("Encrypted {} legacy plaintext SMTP passwords", rows.len());
+    }
+    Ok(rows.len())
+}
+
+/// Re-encrypts every stored SMTP password that isn't under the current key.
+///
+/// Rows are locked and rewritten in a single transaction, so a database error
+/// leaves every password on its old key. Rows that can't be decrypted (e.g. the
+/// old key is missing from `ENCRYPTION_PREVIOUS_KEYS`) are reported and left
+/// untouched rather than aborting the rotation. Legacy plaintext passwords are
+/// encrypted first, so they end up under the current key too.
+pub async fn reencrypt_smtp_passwords(pool: &PgPool, encryption: &EncryptionService) -> Result<KeyRotationSummary, String> {
+    let encrypted_plaintext = encrypt_plaintext_passwords(pool, encryption).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query_as::<_, StoredPassword>(
+        r#"
+        SELECT id, smtp_password_encrypted, encryption_key_id
+        FROM email_accounts
+        WHERE smtp_password_encrypted IS NOT NULL
+          AND encryption_key_id IS DISTINCT FROM $1
+        FOR UPDATE
+        "#
+    )
+    .bind(encryption.key_id())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to load encrypted passwords: {}", e))?;
+
+    let mut rotated = 0;
+    let mut failed = Vec::new();
+
+    for row in rows {
+        let reencrypted = encryption
+            .decrypt_with_key_id(&row.smtp_password_encrypted, row.encryption_key_id.as_deref())
+            .and_then(|plaintext| encryption.encrypt(&plaintext));
+
+        let (ciphertext, key_id) = match reencrypted {
+            Ok(result) => result,
+            Err(error) => {
+                failed.push(KeyRotationFailure { email_account_id: row.id, error });
+                continue;
+            }
+        };
+
+        sqlx::query(
+            "UPDATE email_accounts SET smtp_password_encrypted = $1, encryption_key_id = $2 WHERE id = $3"
+        )
+        .bind(&ciphertext)
+        .bind(&key_id)
+        .bind(row.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update email account {}: {}", row.id, e))?;
+
+        rotated += 1;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if !failed.is_empty() {
+        tracing::warn!("Key rotation skipped {} email accounts that could not be decrypted", failed.len());
+    }
+    tracing::info!("Re-encrypted {} SMTP passwords with key {}", rotated, encryption.key_id());
+
+    Ok(KeyRotationSummary {
+        key_id: encryption.key_id().to_string(),
+        rotated,
+        encrypted_plaintext,
+        failed,
+    })
+}
+
+/// Re-encrypts every stored TOTP secret that isn't under the current key, the
+/// same way `reencrypt_smtp_passwords` does for SMTP passwords. Pending
+/// enrollments are included, so a half-finished setup survives the rotation too.
+pub async fn reencrypt_totp_secrets(pool: &PgPool, encryption: &EncryptionService) -> Result<TotpRotationSummary, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query_as::<_, StoredTotpSecret>(
+        r#"
+        SELECT id, totp_secret_encrypted, totp_key_id
+        FROM users
+        WHERE totp_secret_encrypted IS NOT NULL
+          AND totp_key_id IS DISTINCT FROM $1
+        FOR UPDATE
+        "#
+    )
+    .bind(encryption.key_id())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to load TOTP secrets: {}", e))?;
+
+    let mut rotated = 0;
+    let mut failed = Vec::new();
+
+    for row in rows {
+        let reencrypted = encryption
+            .decrypt_with_key_id(&row.totp_secret_encrypted, row.totp_key_id.as_deref())
+            .and_then(|secret| encryption.encrypt(&secret));
+
+        let (ciphertext, key_id) = match reencrypted {
+            Ok(result) => result,
+            Err(error) => {
+                failed.push(TotpRotationFailure { user_id: row.id, error });
+                continue;
+            }
+        };
+
+        sqlx::query("UPDATE users SET totp_secret_encrypted = $1, totp_key_id = $2 WHERE id = $3")
+            .bind(&ciphertext)
+            .bind(&key_id)
+            .bind(row.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update user {}: {}", row.id, e))?;
+
+        rotated += 1;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if !failed.is_empty() {
+        tracing::warn!("Key rotation skipped {} TOTP secrets that could not be decrypted", failed.len());
+    }
+    tracing::info!("Re-encrypted {} TOTP secrets with key {}", rotated, encryption.key_id());
+
+    Ok(TotpRotationSummary {
+        key_id: encryption.key_id().to_string(),
+        rotated,
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_passwords_under_v1_still_decrypt_after_rotating_to_v2() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Key rotation").await;
+        // Ids unique to this run, so rows from other runs are never under the new key
+        let (v1, v2) = (format!("v1-{}", workspace_id), format!("v2-{}", workspace_id));
+        let old = EncryptionService::new_with_key(&[1u8; 32], &v1).unwrap();
+        let unknown = EncryptionService::new_with_key(&[9u8; 32], &format!("v0-{}", workspace_id)).unwrap();
+
+        let mut accounts = Vec::new();
+        for (service, password) in [(&old, "hunter2"), (&unknown, "lost-key")] {
+            let (ciphertext, key_id) = service.encrypt(password).unwrap();
+            let account_id = test_db::inbox(&pool, workspace_id, 50).await;
+            sqlx::query("UPDATE email_accounts SET smtp_password_encrypted = $2, encryption_key_id = $3 WHERE id = $1")
+                .bind(account_id)
+                .bind(&ciphertext)
+                .bind(&key_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            accounts.push((account_id, ciphertext));
+        }
+
+        let current = EncryptionService::new_with_key(&[2u8; 32], &v2)
+            .unwrap()
+            .with_previous_key(&v1, &[1u8; 32])
+            .unwrap();
+        let summary = reencrypt_smtp_passwords(&pool, &current).await.unwrap();
+        // Everything movable already moved, so a second run has nothing to do
+        let again = reencrypt_smtp_passwords(&pool, &current).await.unwrap();
+
+        let stored = |id: Uuid| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_as::<_, (Vec<u8>, Option<String>)>(
+                    "SELECT smtp_password_encrypted, encryption_key_id FROM email_accounts WHERE id = $1"
+                )
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+            }
+        };
+        let (rotated, rotated_key) = stored(accounts[0].0).await;
+        let (skipped, _) = stored(accounts[1].0).await;
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(rotated_key.as_deref(), Some(v2.as_str()));
+        assert_ne!(rotated, accounts[0].1);
+        assert_eq!(current.decrypt_with_key_id(&rotated, rotated_key.as_deref()).unwrap(), "hunter2");
+        // Without the v1 key the new ciphertext is the only way back
+        assert!(old.decrypt(&rotated).is_err());
+
+        assert_eq!(summary.rotated, 1);
+        assert!(summary.failed.iter().any(|f| f.email_account_id == accounts[1].0));
+        assert_eq!(skipped, accounts[1].1);
+        assert_eq!(again.rotated, 0);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_plaintext_passwords_are_encrypted_and_cleared() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Plaintext backfill").await;
+        let encryption = EncryptionService::new_with_key(&[3u8; 32], &format!("backfill-{}", workspace_id)).unwrap();
+        let (already, _) = encryption.encrypt("current").unwrap();
+
+        // A legacy plaintext-only row, and one that also kept a plaintext copy
+        let mut accounts = Vec::new();
+        for (plaintext, encrypted) in [("legacy", None), ("stale-copy", Some(already))] {
+            let account_id = test_db::inbox(&pool, workspace_id, 50).await;
+            sqlx::query("UPDATE email_accounts SET smtp_password = $2, smtp_password_encrypted = $3, encryption_key_id = $4 WHERE id = $1")
+                .bind(account_id)
+                .bind(plaintext)
+                .bind(&encrypted)
+                .bind(encrypted.as_ref().map(|_| encryption.key_id()))
+                .execute(&pool)
+                .await
+                .unwrap();
+            accounts.push(account_id);
+        }
+
+        encrypt_plaintext_passwords(&pool, &encryption).await.unwrap();
+
+        let mut stored = Vec::new();
+        for id in &accounts {
+            let row: (Option<String>, Vec<u8>, Option<String>) = sqlx::query_as(
+                "SELECT smtp_password, smtp_password_encrypted, encryption_key_id FROM email_accounts WHERE id = $1"
+            )
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            stored.push(row);
+        }
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_accounts WHERE workspace_id = $1 AND smtp_password IS NOT NULL")
+            .bind(workspace_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let decrypt = |(_, encrypted, key_id): &(Option<String>, Vec<u8>, Option<String>)| {
+            encryption.decrypt_with_key_id(encrypted, key_id.as_deref()).unwrap()
+        };
+        assert_eq!(remaining, 0);
+        assert_eq!(decrypt(&stored[0]), "legacy");
+        // A readable ciphertext wins over the plaintext copy
+        assert_eq!(decrypt(&stored[1]), "current");
+    }
+}
@@ -0,0 +1,441 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Words dropped from the end of a company name before comparing, so
+/// "Acme, Inc." and "ACME" match
+const COMPANY_SUFFIXES: &[&str] = &[
+    "inc", "incorporated", "llc", "ltd", "limited", "corp", "corporation", "co", "company", "gmbh", "plc", "sa",
+];
+
+/// Lowercases an address and drops any `+tag` from the local part, so
+/// `Jane+news@Acme.io` and `jane@acme.io` are stored as one lead
+pub fn normalize_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let local = local.split_once('+').map_or(local, |(base, _)| base);
+            if local.is_empty() {
+                email
+            } else {
+                format!("{}@{}", local, domain)
+            }
+        }
+        None => email,
+    }
+}
+
+/// Lowercase letters and digits of each word, the rest dropped
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn company_key(company: &str) -> String {
+    let mut words = words(&company.replace([',', '.'], " "));
+    while words.len() > 1 && words.last().is_some_and(|w| COMPANY_SUFFIXES.contains(&w.as_str())) {
+        words.pop();
+    }
+    words.concat()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, sqlx::FromRow)]
+pub struct DuplicateCandidate {
+    pub id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub confidence_score: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DuplicateCandidate {
+    fn person_key(&self) -> Option<(String, String)> {
+        let name = words(&format!(
+            "{} {}",
+            self.first_name.as_deref().unwrap_or_default(),
+            self.last_name.as_deref().unwrap_or_default()
+        ))
+        .join(" ");
+        let company = company_key(self.company.as_deref().unwrap_or_default());
+        (!name.is_empty() && !company.is_empty()).then_some((name, company))
+    }
+}
+
+/// Why leads were grouped together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMatch {
+    /// Same address once case and `+tag` are ignored
+    Email,
+    /// Same full name at what looks like the same company
+    NameCompany,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DuplicateGroup {
+    pub match_on: Vec<DuplicateMatch>,
+    /// The record a merge would keep comes first
+    pub leads: Vec<DuplicateCandidate>,
+}
+
+/// Orders a merge's survivor first: highest confidence, then the oldest record
+fn survivor_order(a: &DuplicateCandidate, b: &DuplicateCandidate) -> std::cmp::Ordering {
+    b.confidence_score
+        .total_cmp(&a.confidence_score)
+        .then(a.created_at.cmp(&b.created_at))
+        .then(a.id.cmp(&b.id))
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Joins the groups of `a` and `b`, remembering why
+fn link(parents: &mut [usize], matched: &mut [Vec<DuplicateMatch>], a: usize, b: usize, on: DuplicateMatch) {
+    let (root_a, root_b) = (find_root(parents, a), find_root(parents, b));
+    if root_a != root_b {
+        let moved = std::mem::take(&mut matched[root_b]);
+        matched[root_a].extend(moved);
+        parents[root_b] = root_a;
+    }
+    let reasons = &mut matched[root_a];
+    reasons.push(on);
+    reasons.sort_by_key(|m| *m as u8);
+    reasons.dedup();
+}
+
+/// Groups leads that share a normalized address or a name and company.
+/// Matches chain, so A~B by email and B~C by name put all three together.
+pub fn group_duplicates(candidates: Vec<DuplicateCandidate>) -> Vec<DuplicateGroup> {
+    let mut parents: Vec<usize> = (0..candidates.len()).collect();
+    let mut matched: Vec<Vec<DuplicateMatch>> = vec![Vec::new(); candidates.len()];
+    let mut by_email: HashMap<String, usize> = HashMap::new();
+    let mut by_person: HashMap<(String, String), usize> = HashMap::new();
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        match by_email.get(&normalize_email(&candidate.email)) {
+            Some(&first) => link(&mut parents, &mut matched, first, i, DuplicateMatch::Email),
+            None => {
+                by_email.insert(normalize_email(&candidate.email), i);
+            }
+        }
+        if let Some(key) = candidate.person_key() {
+            match by_person.get(&key) {
+                Some(&first) => link(&mut parents, &mut matched, first, i, DuplicateMatch::NameCompany),
+                None => {
+                    by_person.insert(key, i);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<DuplicateCandidate>> = HashMap::new();
+    for (i, candidate) in candidates.into_iter().enumerate() {
+        let root = find_root(&mut parents, i);
+        groups.entry(root).or_default().push(candidate);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter(|(_, leads)| leads.len() > 1)
+        .map(|(root, mut leads)| {
+            leads.sort_by(survivor_order);
+            DuplicateGroup { match_on: std::mem::take(&mut matched[root]), leads }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.leads[0].email.cmp(&b.leads[0].email));
+    groups
+}
+
+/// Likely duplicates among the workspace's live leads
+pub async fn find_duplicates(pool: &PgPool, workspace_id: Uuid) -> Result<Vec<DuplicateGroup>, sqlx::Error> {
+    let candidates = sqlx::query_as::<_, DuplicateCandidate>(
+        r#"
+        SELECT id, email, first_name, last_name, company, COALESCE(confidence_score, 0)::float8 AS confidence_score, created_at
+        FROM leads
+        WHERE workspace_id = $1 AND deleted_at IS NULL
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(group_duplicates(candidates))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MergeOutcome {
+    /// The record the others were merged into
+    pub lead_id: Uuid,
+    pub merged_lead_ids: Vec<Uuid>,
+    /// Campaign memberships moved onto the surviving lead
+    pub campaign_leads_moved: u64,
+}
+
+/// Merges the leads into the one with the highest confidence. Its blank
+/// fields are filled from the others, their campaign memberships, replies,
+/// meetings and tags move to it, and they are moved to the trash. Where two
+/// of the leads are in the same campaign, the survivor keeps its own
+/// membership, or else the one furthest along.
+///
+/// Returns `None` unless every id is a live lead in the workspace.
+pub async fn merge(pool: &PgPool, workspace_id: Uuid, lead_ids: &[Uuid]) -> Result<Option<MergeOutcome>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut leads = sqlx::query_as::<_, DuplicateCandidate>(
+        r#"
+        SELECT id, email, first_name, last_name, company, COALESCE(confidence_score, 0)::float8 AS confidence_score, created_at
+        FROM leads
+        WHERE workspace_id = $1 AND id = ANY($2) AND deleted_at IS NULL
+        FOR UPDATE
+        "#
+    )
+    .bind(workspace_id)
+    .bind(lead_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut requested = lead_ids.to_vec();
+    requested.sort();
+    requested.dedup();
+    if leads.len() != requested.len() {
+        return Ok(None);
+    }
+
+    leads.sort_by(survivor_order);
+    let survivor = leads[0].id;
+    let merged: Vec<Uuid> = leads[1..].iter().map(|l| l.id).collect();
+
+    // Fill the survivor's gaps from the others, best record first
+    sqlx::query(
+        r#"
+        UPDATE leads k SET
+            first_name = COALESCE(k.first_name, o.first_name),
+            last_name = COALESCE(k.last_name, o.last_name),
+            company = COALESCE(k.company, o.company),
+            title = COALESCE(k.title, o.title),
+            linkedin_url = COALESCE(k.linkedin_url, o.linkedin_url)
+        FROM (
+            SELECT (array_agg(first_name ORDER BY ord) FILTER (WHERE first_name IS NOT NULL))[1] AS first_name,
+                   (array_agg(last_name ORDER BY ord) FILTER (WHERE last_name IS NOT NULL))[1] AS last_name,
+                   (array_agg(company ORDER BY ord) FILTER (WHERE company IS NOT NULL))[1] AS company,
+                   (array_agg(title ORDER BY ord) FILTER (WHERE title IS NOT NULL))[1] AS title,
+                   (array_agg(linkedin_url ORDER BY ord) FILTER (WHERE linkedin_url IS NOT NULL))[1] AS linkedin_url
+            FROM leads l
+            JOIN UNNEST($2::uuid[]) WITH ORDINALITY AS m(id, ord) ON m.id = l.id
+        ) o
+        WHERE k.id = $1
+        "#
+    )
+    .bind(survivor)
+    .bind(&merged)
+    .execute(&mut *tx)
+    .await?;
+
+    // One membership per campaign, so only campaigns the survivor isn't in yet move
+    let moved = sqlx::query(
+        r#"
+        UPDATE campaign_leads SET lead_id = $1
+        WHERE id IN (
+            SELECT DISTINCT ON (cl.campaign_id) cl.id
+            FROM campaign_leads cl
+            WHERE cl.lead_id = ANY($2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM campaign_leads k WHERE k.lead_id = $1 AND k.campaign_id = cl.campaign_id
+              )
+            ORDER BY cl.campaign_id, cl.current_step DESC, cl.sent_at DESC NULLS LAST, cl.created_at
+        )
+        "#
+    )
+    .bind(survivor)
+    .bind(&merged)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        "UPDATE sent_emails SET lead_id = $1 WHERE lead_id = ANY($2) AND campaign_lead_id IN (SELECT id FROM campaign_leads WHERE lead_id = $1)"
+    )
+    .bind(survivor)
+    .bind(&merged)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in ["email_replies", "meetings"] {
+        sqlx::query(&format!("UPDATE {} SET lead_id = $1 WHERE lead_id = ANY($2) AND workspace_id = $3", table))
+            .bind(survivor)
+            .bind(&merged)
+            .bind(workspace_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO lead_tags (lead_id, workspace_id, tag)
+        SELECT $1, workspace_id, tag FROM lead_tags WHERE lead_id = ANY($2)
+        ON CONFLICT (lead_id, tag) DO NOTHING
+        "#
+    )
+    .bind(survivor)
+    .bind(&merged)
+    .execute(&mut *tx)
+    .await?;
+
+    // Trashed like a normal delete, so anything left behind stays in the history
+    sqlx::query("UPDATE leads SET deleted_at = NOW() WHERE id = ANY($1)")
+        .bind(&merged)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(MergeOutcome {
+        lead_id: survivor,
+        merged_lead_ids: merged,
+        campaign_leads_moved: moved,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    fn candidate(email: &str, name: (&str, &str), company: &str, confidence: f64) -> DuplicateCandidate {
+        DuplicateCandidate {
+            id: Uuid::new_v4(),
+            email: email.to_string(),
+            first_name: Some(name.0.to_string()).filter(|n| !n.is_empty()),
+            last_name: Some(name.1.to_string()).filter(|n| !n.is_empty()),
+            company: Some(company.to_string()).filter(|c| !c.is_empty()),
+            confidence_score: confidence,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_duplicates_are_grouped_by_address_and_by_name_at_company() {
+        assert_eq!(normalize_email(" Jane+News@Acme.IO "), "jane@acme.io");
+        assert_eq!(normalize_email("+only@acme.io"), "+only@acme.io");
+
+        let groups = group_duplicates(vec![
+            candidate("jane@acme.io", ("Jane", "Doe"), "Acme", 0.6),
+            candidate("Jane+crm@acme.io", ("", ""), "", 0.9),
+            candidate("j.doe@gmail.com", ("jane", "DOE"), "ACME, Inc.", 0.5),
+            candidate("sam@acme.io", ("Sam", "Doe"), "Acme", 0.7),
+            // Same name, different company
+            candidate("jane@other.io", ("Jane", "Doe"), "Other Ltd", 0.8),
+        ]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].match_on, vec![DuplicateMatch::Email, DuplicateMatch::NameCompany]);
+        let emails: Vec<&str> = groups[0].leads.iter().map(|l| l.email.as_str()).collect();
+        assert_eq!(emails, vec!["Jane+crm@acme.io", "jane@acme.io", "j.doe@gmail.com"]);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_merge_keeps_campaign_memberships() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Lead merge").await;
+
+        let (keeper, duplicate) = (Uuid::new_v4(), Uuid::new_v4());
+        for (id, email, confidence, title) in [
+            (keeper, "jane@merge.test", 0.9, None),
+            (duplicate, "jane+old@merge.test", 0.4, Some("CTO")),
+        ] {
+            sqlx::query(
+                "INSERT INTO leads (id, workspace_id, email, first_name, last_name, company, title, confidence_score)
+                 VALUES ($1, $2, $3, 'Jane', 'Doe', 'Acme', $4, $5)"
+            )
+            .bind(id)
+            .bind(workspace_id)
+            .bind(email)
+            .bind(title)
+            .bind(confidence)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Both are in the shared campaign; only the duplicate is in the other one
+        let (shared, only_duplicate) = (Uuid::new_v4(), Uuid::new_v4());
+        for campaign_id in [shared, only_duplicate] {
+            sqlx::query("INSERT INTO campaigns (id, workspace_id, name, vertical, status) VALUES ($1, $2, 'Merge', 'saas', 'active')")
+                .bind(campaign_id)
+                .bind(workspace_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (campaign_id, lead_id) in [(shared, keeper), (shared, duplicate), (only_duplicate, duplicate)] {
+            sqlx::query("INSERT INTO campaign_leads (id, campaign_id, lead_id) VALUES ($1, $2, $3)")
+                .bind(Uuid::new_v4())
+                .bind(campaign_id)
+                .bind(lead_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO email_replies (workspace_id, campaign_id, lead_id, from_email) VALUES ($1, $2, $3, 'jane+old@merge.test')")
+            .bind(workspace_id)
+            .bind(only_duplicate)
+            .bind(duplicate)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let groups = find_duplicates(&pool, workspace_id).await.unwrap();
+        let outcome = merge(&pool, workspace_id, &[duplicate, keeper]).await.unwrap().unwrap();
+        let memberships: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            "SELECT campaign_id, lead_id FROM campaign_leads WHERE campaign_id IN ($1, $2) ORDER BY campaign_id = $1 DESC, lead_id = $3 DESC"
+        )
+        .bind(shared)
+        .bind(only_duplicate)
+        .bind(keeper)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let reply_lead: Uuid = sqlx::query_scalar("SELECT lead_id FROM email_replies WHERE workspace_id = $1")
+            .bind(workspace_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let (title, duplicate_deleted): (Option<String>, bool) = sqlx::query_as(
+            "SELECT (SELECT title FROM leads WHERE id = $1), (SELECT deleted_at IS NOT NULL FROM leads WHERE id = $2)"
+        )
+        .bind(keeper)
+        .bind(duplicate)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let after = find_duplicates(&pool, workspace_id).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].leads[0].id, keeper);
+        assert_eq!(outcome.lead_id, keeper);
+        assert_eq!(outcome.merged_lead_ids, vec![duplicate]);
+        assert_eq!(outcome.campaign_leads_moved, 1);
+        // The survivor keeps its own row in the shared campaign and takes over the other
+        assert_eq!(memberships, vec![(shared, keeper), (shared, duplicate), (only_duplicate, keeper)]);
+        assert_eq!(reply_lead, keeper);
+        assert_eq!(title.as_deref(), Some("CTO"));
+        assert!(duplicate_deleted);
+        assert!(after.is_empty());
+    }
+}
@@ -1,10 +1,18 @@
 use crate::models::lead::VerificationStatus;
-use reqwest::Client;
+use crate::services::send_time;
+use futures_util::{stream, Future, StreamExt};
+use sqlx::PgPool;
+use std::collections::HashSet;
 use uuid::Uuid;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How many addresses are verified at once. Each check is mostly waiting on
+/// DNS and SMTP, so running them serially made large searches crawl.
+pub const VERIFY_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GeneratedLead {
     pub id: Uuid,
     pub email: String,
@@ -20,15 +28,11 @@ pub struct GeneratedLead {
     pub verified_at: Option<chrono::DateTime<Utc>>,
 }
 
-pub struct LeadGenerator {
-    client: Client,
-}
+pub struct LeadGenerator;
 
 impl LeadGenerator {
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+        Self
     }
 
     /// Generate leads based on signals and vertical/industry
@@ -64,7 +68,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("Ethereum Labs", "ethereumlabs.io"),
             ("DeFi Protocol", "defiprotocol.xyz"),
             ("NFT Marketplace", "nftmarket.io"),
@@ -108,7 +112,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("CloudSync", "cloudsync.io"),
             ("DataFlow", "dataflow.com"),
             ("MetricsPro", "metricspro.io"),
@@ -152,7 +156,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("Digital Spark", "digitalspark.co"),
             ("Creative Labs", "creativelabs.agency"),
             ("Growth Studio", "growthstudio.io"),
@@ -218,7 +222,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("ShopifyPlus Store", "shopifyplus.store"),
             ("Commerce Cloud", "commercecloud.io"),
             ("RetailTech", "retailtech.com"),
@@ -262,7 +266,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("PayFlow", "payflow.io"),
             ("LendTech", "lendtech.com"),
             ("InvestPro", "investpro.io"),
@@ -306,7 +310,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("HealthTech Solutions", "healthtech.io"),
             ("MedConnect", "medconnect.com"),
             ("CareFlow", "careflow.health"),
@@ -350,7 +354,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("LearnHub", "learnhub.io"),
             ("EdTech Academy", "edtechacademy.com"),
             ("SkillPath", "skillpath.io"),
@@ -394,7 +398,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("PropTech Solutions", "proptech.io"),
             ("RealtyFlow", "realtyflow.com"),
             ("HomeStack", "homestack.io"),
@@ -438,7 +442,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("Strategy Partners", "strategypartners.co"),
             ("Growth Advisors", "growthadvisors.com"),
             ("Digital Consulting", "digitalconsulting.io"),
@@ -482,7 +486,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("SmartFactory", "smartfactory.io"),
             ("Industrial IoT", "industrialiot.com"),
             ("ManuTech", "manutech.io"),
@@ -526,7 +530,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("ContentHub", "contenthub.io"),
             ("MediaStream", "mediastream.com"),
             ("CreatorPlatform", "creatorplatform.io"),
@@ -570,7 +574,7 @@ impl LeadGenerator {
         limit: usize,
     ) -> Result<Vec<GeneratedLead>, Box<dyn std::error::Error + Send + Sync>> {
         let mut leads = Vec::new();
-        let companies = vec![
+        let companies = [
             ("FleetManager", "fleetmanager.io"),
             ("LogiTech", "logitech-supply.com"),
             ("ShipFast", "shipfast.io"),
@@ -672,3 +676,180 @@ impl Default for LeadGenerator {
         Self::new()
     }
 }
+
+/// Runs `verify` over every lead, at most [`VERIFY_CONCURRENCY`] at a time, and
+/// records each result on its lead
+pub async fn verify_leads<F, Fut>(leads: &mut [GeneratedLead], verify: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = (VerificationStatus, f32)>,
+{
+    let results: Vec<_> = stream::iter(leads.iter().map(|lead| verify(lead.email.clone())))
+        .buffered(VERIFY_CONCURRENCY)
+        .collect()
+        .await;
+
+    for (lead, (status, confidence)) in leads.iter_mut().zip(results) {
+        lead.verification_status = status;
+        lead.confidence_score = confidence;
+    }
+}
+
+/// Stores generated leads in one statement, under their lowercased addresses.
+/// Live leads the workspace already has get the fresh verification and signals,
+/// while a trashed lead stays in the trash and its address comes back as a new
+/// lead; addresses erased on request stay out. Returns how many rows were inserted or updated.
+pub async fn store_leads(pool: &PgPool, workspace_id: Uuid, leads: &[GeneratedLead]) -> Result<u64, sqlx::Error> {
+    // One statement can't touch the same row twice, so repeated addresses keep their first lead
+    let mut seen = HashSet::new();
+    let (leads, emails): (Vec<&GeneratedLead>, Vec<String>) = leads
+        .iter()
+        .map(|l| (l, l.email.trim().to_lowercase()))
+        .filter(|(_, email)| seen.insert(email.clone()))
+        .unzip();
+    if leads.is_empty() {
+        return Ok(0);
+    }
+
+    let (timezone_offsets, send_hours): (Vec<Option<i32>>, Vec<Option<i16>>) = emails
+        .iter()
+        .map(|email| send_time::guess_send_preferences(email))
+        .unzip();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO leads (id, workspace_id, email, first_name, last_name, company, title,
+                          linkedin_url, verification_status, confidence_score, signals, created_at,
+                          timezone_offset_minutes, preferred_send_hour)
+        SELECT i.id, $1, i.email, i.first_name, i.last_name, i.company, i.title,
+               i.linkedin_url, i.verification_status, i.confidence_score, i.signals, i.created_at,
+               i.timezone_offset_minutes, i.preferred_send_hour
+        FROM UNNEST($2::uuid[], $3::text[], $4::text[], $5::text[], $6::text[], $7::text[], $8::text[],
+                    $9::text[], $10::real[], $11::jsonb[], $12::timestamptz[], $13::int[], $14::smallint[])
+            AS i(id, email, first_name, last_name, company, title, linkedin_url,
+                 verification_status, confidence_score, signals, created_at,
+                 timezone_offset_minutes, preferred_send_hour)
+        -- Addresses erased on request stay out
+        WHERE NOT EXISTS (
+            SELECT 1 FROM suppression_list s
+            WHERE s.workspace_id = $1 AND LOWER(s.email) = LOWER(i.email) AND s.reason = 'erased'
+        )
+        ON CONFLICT (workspace_id, email) WHERE deleted_at IS NULL DO UPDATE SET
+            verification_status = EXCLUDED.verification_status,
+            confidence_score = EXCLUDED.confidence_score,
+            signals = EXCLUDED.signals,
+            timezone_offset_minutes = COALESCE(leads.timezone_offset_minutes, EXCLUDED.timezone_offset_minutes),
+            preferred_send_hour = COALESCE(leads.preferred_send_hour, EXCLUDED.preferred_send_hour)
+        "#
+    )
+    .bind(workspace_id)
+    .bind(leads.iter().map(|l| l.id).collect::<Vec<_>>())
+    .bind(emails)
+    .bind(leads.iter().map(|l| l.first_name.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.last_name.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.company.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.title.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.linkedin_url.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.verification_status.as_str()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.confidence_score).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.signals.clone()).collect::<Vec<_>>())
+    .bind(leads.iter().map(|l| l.created_at).collect::<Vec<_>>())
+    .bind(timezone_offsets)
+    .bind(send_hours)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_verification_runs_concurrently_and_keeps_lead_order() {
+        let mut leads = LeadGenerator::new().generate_leads("saas", None, 20).await.unwrap();
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+
+        verify_leads(&mut leads, |email| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                (VerificationStatus::Risky, email.len() as f32)
+            }
+        })
+        .await;
+
+        let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight > 1, "verification ran serially");
+        assert!(max_in_flight <= VERIFY_CONCURRENCY);
+        assert!(leads.iter().all(|l| l.confidence_score == l.email.len() as f32));
+        assert!(leads.iter().all(|l| l.verification_status == VerificationStatus::Risky));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_store_leads_upserts_the_batch_in_one_statement() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Store leads").await;
+
+        let mut leads = LeadGenerator::new().generate_leads("saas", None, 6).await.unwrap();
+        // The last lead repeats lead2's address
+        for (i, lead) in leads.iter_mut().enumerate() {
+            lead.email = format!("lead{}@store-leads.test", if i == 5 { 2 } else { i });
+        }
+        sqlx::query("INSERT INTO leads (id, workspace_id, email, verification_status, timezone_offset_minutes) VALUES ($1, $2, 'lead0@store-leads.test', 'pending', 120)")
+            .bind(Uuid::new_v4())
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let trashed = Uuid::new_v4();
+        sqlx::query("INSERT INTO leads (id, workspace_id, email, verification_status, deleted_at) VALUES ($1, $2, 'lead3@store-leads.test', 'invalid', NOW())")
+            .bind(trashed)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO suppression_list (workspace_id, email, reason) VALUES ($1, 'lead1@store-leads.test', 'erased')")
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        leads[0].verification_status = VerificationStatus::Valid;
+
+        let stored = store_leads(&pool, workspace_id, &leads).await.unwrap();
+        let rows: Vec<(String, String, Option<i32>)> = sqlx::query_as(
+            "SELECT email, verification_status, timezone_offset_minutes FROM leads WHERE workspace_id = $1 AND deleted_at IS NULL ORDER BY email"
+        )
+        .bind(workspace_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let trashed: (String, bool) = sqlx::query_as("SELECT verification_status, deleted_at IS NOT NULL FROM leads WHERE id = $1")
+            .bind(trashed)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        // lead0 updated in place, lead1 erased, lead2..4 inserted once each, and
+        // lead3 inserted alongside its trashed copy rather than reviving it
+        assert_eq!(stored, 4);
+        let emails: Vec<&str> = rows.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(emails, vec!["lead0@store-leads.test", "lead2@store-leads.test", "lead3@store-leads.test", "lead4@store-leads.test"]);
+        assert_eq!(rows[0].1, "valid");
+        assert_eq!(rows[0].2, Some(120));
+        assert_eq!(trashed, ("invalid".to_string(), true));
+    }
+}
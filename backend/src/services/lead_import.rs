@@ -0,0 +1,330 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::email_verifier::is_valid_syntax;
+use crate::services::send_time;
+
+/// Rows scanned for a header before giving up; spreadsheet exports often start
+/// with a title or a blank line
+const HEADER_SEARCH_ROWS: usize = 10;
+
+/// Leads inserted per statement
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// Unmapped columns kept as custom fields; any further ones are ignored
+const MAX_CUSTOM_FIELDS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Column {
+    Email,
+    FirstName,
+    LastName,
+    Company,
+    Title,
+    LinkedinUrl,
+    /// Any other named column, kept in `leads.custom_fields` under this key
+    Custom(String),
+}
+
+/// Merge-tag-friendly key for a header, e.g. "Recent Funding" -> `recent_funding`
+fn custom_field_key(cell: &str) -> String {
+    cell.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+impl Column {
+    /// Matches a header cell, ignoring case, spaces and punctuation
+    fn from_header(cell: &str) -> Option<Self> {
+        let name: String = cell
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        match name.as_str() {
+            "email" | "emailaddress" | "mail" | "workemail" | "businessemail" => Some(Self::Email),
+            "firstname" | "first" | "givenname" | "fname" => Some(Self::FirstName),
+            "lastname" | "last" | "surname" | "familyname" | "lname" => Some(Self::LastName),
+            "company" | "companyname" | "organization" | "organisation" | "account" | "accountname" => Some(Self::Company),
+            "title" | "jobtitle" | "position" | "role" => Some(Self::Title),
+            "linkedin" | "linkedinurl" | "linkedinprofile" | "linkedinprofileurl" => Some(Self::LinkedinUrl),
+            _ => Some(custom_field_key(cell)).filter(|key| !key.is_empty()).map(Self::Custom),
+        }
+    }
+
+    /// Longest value the `leads` column holds
+    fn max_len(&self) -> usize {
+        match self {
+            Self::FirstName | Self::LastName => 100,
+            Self::LinkedinUrl => usize::MAX,
+            Self::Email | Self::Company | Self::Title => 255,
+            Self::Custom(_) => 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedLead {
+    pub line: usize,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub title: Option<String>,
+    pub linkedin_url: Option<String>,
+    /// Values from unmapped columns, keyed by `custom_field_key`
+    pub custom_fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RejectedRow {
+    /// 1-based line in the uploaded file
+    pub line: usize,
+    pub email: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub leads: Vec<ImportedLead>,
+    pub rejected: Vec<RejectedRow>,
+    /// Rows repeating an address that appeared earlier in the file
+    pub duplicates: usize,
+}
+
+/// Guesses the delimiter from the first line, since spreadsheets saved in some
+/// locales use semicolons and others export tab-separated text.
+fn sniff_delimiter(data: &[u8]) -> u8 {
+    let first_line = data.split(|b| *b == b'\n').next().unwrap_or_default();
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| first_line.iter().filter(|b| *b == d).count())
+        .filter(|d| first_line.contains(d))
+        .unwrap_or(b',')
+}
+
+/// Parses an uploaded lead list. The header row is found by looking for an email
+/// column in the first few rows; other named columns become custom fields.
+/// Addresses are lowercased, so the same address in different case counts as a
+/// duplicate. `+tag` variants are kept as given; `lead_dedup` groups those later.
+pub fn parse_csv(data: &[u8]) -> Result<ParsedImport, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .delimiter(sniff_delimiter(data))
+        .from_reader(data);
+
+    let mut records = reader.records();
+    let mut columns: Vec<Option<Column>> = Vec::new();
+
+    for _ in 0..HEADER_SEARCH_ROWS {
+        let Some(record) = records.next() else { break };
+        let record = record.map_err(|e| format!("Could not read the file: {}", e))?;
+        let candidate: Vec<Option<Column>> = record.iter().map(Column::from_header).collect();
+        if candidate.contains(&Some(Column::Email)) {
+            columns = candidate;
+            break;
+        }
+    }
+
+    // A repeated header keeps its first column, and only so many become fields
+    let mut custom_keys = HashSet::new();
+    for column in &mut columns {
+        if let Some(Column::Custom(key)) = column {
+            if custom_keys.len() >= MAX_CUSTOM_FIELDS || !custom_keys.insert(key.clone()) {
+                *column = None;
+            }
+        }
+    }
+
+    if columns.is_empty() {
+        return Err("No email column found; the file needs a header row naming an email column".to_string());
+    }
+
+    let mut parsed = ParsedImport::default();
+    let mut seen = HashSet::new();
+
+    for record in records {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                parsed.rejected.push(RejectedRow {
+                    line: e.position().map_or(0, |p| p.line() as usize),
+                    email: None,
+                    reason: format!("Could not parse row: {}", e),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line() as usize);
+
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+
+        let mut lead = ImportedLead {
+            line,
+            email: String::new(),
+            first_name: None,
+            last_name: None,
+            company: None,
+            title: None,
+            linkedin_url: None,
+            custom_fields: serde_json::Map::new(),
+        };
+        for (column, value) in columns.iter().zip(record.iter()) {
+            let Some(column) = column else { continue };
+            if value.is_empty() {
+                continue;
+            }
+            let value: String = value.chars().take(column.max_len()).collect();
+            match column {
+                Column::Email => lead.email = value.to_lowercase(),
+                Column::FirstName => lead.first_name = Some(value),
+                Column::LastName => lead.last_name = Some(value),
+                Column::Company => lead.company = Some(value),
+                Column::Title => lead.title = Some(value),
+                Column::LinkedinUrl => lead.linkedin_url = Some(value),
+                Column::Custom(key) => {
+                    lead.custom_fields.insert(key.clone(), serde_json::Value::String(value));
+                }
+            }
+        }
+
+        let reason = if lead.email.is_empty() {
+            Some("Missing email address")
+        } else if !is_valid_syntax(&lead.email) {
+            Some("Invalid email address")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            parsed.rejected.push(RejectedRow {
+                line,
+                email: Some(lead.email).filter(|e| !e.is_empty()),
+                reason: reason.to_string(),
+            });
+            continue;
+        }
+
+        if !seen.insert(lead.email.clone()) {
+            parsed.duplicates += 1;
+            continue;
+        }
+        parsed.leads.push(lead);
+    }
+
+    Ok(parsed)
+}
+
+/// How many of `leads` the workspace already has as live leads. Trashed leads
+/// don't count: importing their address adds a new lead and leaves them in the trash.
+pub async fn count_existing(pool: &PgPool, workspace_id: Uuid, leads: &[ImportedLead]) -> Result<i64, sqlx::Error> {
+    let emails: Vec<&str> = leads.iter().map(|l| l.email.as_str()).collect();
+    sqlx::query_scalar("SELECT COUNT(*) FROM leads WHERE workspace_id = $1 AND LOWER(email) = ANY($2) AND deleted_at IS NULL")
+        .bind(workspace_id)
+        .bind(&emails)
+        .fetch_one(pool)
+        .await
+}
+
+/// Inserts the leads as unverified, skipping addresses the workspace already has
+/// as live leads and any it erased on request.
+/// Returns how many were inserted.
+pub async fn insert_leads(pool: &PgPool, workspace_id: Uuid, leads: &[ImportedLead]) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for batch in leads.chunks(INSERT_BATCH_SIZE) {
+        let (timezone_offsets, send_hours): (Vec<Option<i32>>, Vec<Option<i16>>) = batch
+            .iter()
+            .map(|l| send_time::guess_send_preferences(&l.email))
+            .unzip();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO leads (id, workspace_id, email, first_name, last_name, company, title, linkedin_url,
+                               verification_status, timezone_offset_minutes, preferred_send_hour, custom_fields, created_at)
+            SELECT gen_random_uuid(), $1, i.email, i.first_name, i.last_name, i.company, i.title, i.linkedin_url,
+                   'pending', i.timezone_offset_minutes, i.preferred_send_hour, i.custom_fields, NOW()
+            FROM UNNEST($2::text[], $3::text[], $4::text[], $5::text[], $6::text[], $7::text[], $8::int[], $9::smallint[], $10::jsonb[])
+                AS i(email, first_name, last_name, company, title, linkedin_url, timezone_offset_minutes, preferred_send_hour, custom_fields)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM leads l WHERE l.workspace_id = $1 AND LOWER(l.email) = i.email AND l.deleted_at IS NULL
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM suppression_list s
+                WHERE s.workspace_id = $1 AND LOWER(s.email) = i.email AND s.reason = 'erased'
+            )
+            ON CONFLICT (workspace_id, email) WHERE deleted_at IS NULL DO NOTHING
+            "#
+        )
+        .bind(workspace_id)
+        .bind(batch.iter().map(|l| l.email.clone()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|l| l.first_name.clone()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|l| l.last_name.clone()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|l| l.company.clone()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|l| l.title.clone()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|l| l.linkedin_url.clone()).collect::<Vec<_>>())
+        .bind(timezone_offsets)
+        .bind(send_hours)
+        .bind(batch.iter().map(|l| serde_json::Value::Object(l.custom_fields.clone())).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+
+        inserted += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_is_detected_and_rows_validated() {
+        let csv = "Exported from CRM;;;\n\
+                   E-mail Address;First Name;Company Name;Notes;LinkedIn\n\
+                   Jane@Acme.io;Jane;Acme;met at conf;https://linkedin.com/in/jane\n\
+                   not-an-email;Bob;Bobco;;\n\
+                   ;Nobody;;;\n\
+                   jane@acme.io;Jane again;;;\n\
+                   ;;;;\n\
+                   sam@example.com;Sam\n";
+
+        let parsed = parse_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.leads.len(), 2);
+        assert_eq!(parsed.leads[0], ImportedLead {
+            line: 3,
+            email: "jane@acme.io".to_string(),
+            first_name: Some("Jane".to_string()),
+            last_name: None,
+            company: Some("Acme".to_string()),
+            title: None,
+            linkedin_url: Some("https://linkedin.com/in/jane".to_string()),
+            custom_fields: serde_json::Map::from_iter([("notes".to_string(), serde_json::json!("met at conf"))]),
+        });
+        assert_eq!(parsed.leads[1].email, "sam@example.com");
+        assert_eq!(parsed.leads[1].line, 8);
+        assert_eq!(parsed.duplicates, 1);
+        assert_eq!(parsed.rejected, vec![
+            RejectedRow { line: 4, email: Some("not-an-email".to_string()), reason: "Invalid email address".to_string() },
+            RejectedRow { line: 5, email: None, reason: "Missing email address".to_string() },
+        ]);
+
+        assert!(parse_csv(b"name,phone\nJane,555\n").is_err());
+
+        let tagged = parse_csv(b"email\nJane+Sales@Acme.io\njane@acme.io\n").unwrap();
+        assert_eq!(tagged.leads.iter().map(|l| l.email.as_str()).collect::<Vec<_>>(), ["jane+sales@acme.io", "jane@acme.io"]);
+    }
+}
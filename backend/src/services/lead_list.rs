@@ -0,0 +1,152 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::lead::{Lead, VerificationStatus};
+use crate::models::pagination::{PageQuery, Paginated};
+
+/// Page size of the leads list when the client doesn't ask for one
+pub const DEFAULT_LEAD_PAGE_SIZE: i64 = 100;
+
+/// Narrows the leads list. Every filter that is set must match.
+#[derive(Debug, Default)]
+pub struct LeadFilter {
+    /// Already-normalized tag
+    pub tag: Option<String>,
+    pub status: Option<VerificationStatus>,
+    /// Company name, ignoring case
+    pub company: Option<String>,
+    /// Free text matched against email, name and company
+    pub search: Option<String>,
+}
+
+/// `%text%` for a case-insensitive LIKE, with the wildcards in `text` escaped
+fn like_pattern(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let escaped = text.to_lowercase().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    Some(format!("%{}%", escaped))
+}
+
+/// One page of the workspace's leads, newest first, with the number matching
+/// the filter. Ties on creation time are broken by id so pages don't overlap.
+pub async fn list(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    filter: &LeadFilter,
+    page: &PageQuery,
+) -> Result<Paginated<Lead>, sqlx::Error> {
+    let status = filter.status.as_ref().map(VerificationStatus::as_str);
+    let company = filter.company.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let search = filter.search.as_deref().and_then(like_pattern);
+
+    const FILTER: &str = r#"
+        WHERE l.workspace_id = $1 AND l.deleted_at IS NULL
+          AND ($2::text IS NULL OR EXISTS (
+              SELECT 1 FROM lead_tags t WHERE t.lead_id = l.id AND t.workspace_id = $1 AND t.tag = $2
+          ))
+          AND ($3::text IS NULL OR l.verification_status = $3)
+          AND ($4::text IS NULL OR LOWER(l.company) = LOWER($4))
+          AND ($5::text IS NULL
+               OR LOWER(l.email) LIKE $5
+               OR LOWER(l.company) LIKE $5
+               OR LOWER(CONCAT_WS(' ', l.first_name, l.last_name)) LIKE $5)
+    "#;
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM leads l {}", FILTER))
+        .bind(workspace_id)
+        .bind(&filter.tag)
+        .bind(status)
+        .bind(company)
+        .bind(&search)
+        .fetch_one(pool)
+        .await?;
+
+    let leads = sqlx::query_as::<_, Lead>(&format!(
+        "SELECT l.* FROM leads l {} ORDER BY l.created_at DESC, l.id DESC LIMIT $6 OFFSET $7",
+        FILTER
+    ))
+    .bind(workspace_id)
+    .bind(&filter.tag)
+    .bind(status)
+    .bind(company)
+    .bind(&search)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Paginated {
+        data: leads,
+        total,
+        limit: page.limit(),
+        offset: page.offset(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_leads_filter_by_status_and_page_without_overlap() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Lead list").await;
+
+        // Seven leads sharing one timestamp, so only the id tiebreak orders them
+        for i in 0..7 {
+            let status = if i % 2 == 0 { "valid" } else { "invalid" };
+            sqlx::query(
+                "INSERT INTO leads (id, workspace_id, email, first_name, company, verification_status, created_at)
+                 VALUES ($1, $2, $3, $4, 'Acme 100%', $5, '2024-01-01T00:00:00Z')"
+            )
+            .bind(Uuid::new_v4())
+            .bind(workspace_id)
+            .bind(format!("lead{}@lead-list.test", i))
+            .bind(format!("Person{}", i))
+            .bind(status)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let valid = LeadFilter { status: Some(VerificationStatus::Valid), ..Default::default() };
+        let valid = list(&pool, workspace_id, &valid, &PageQuery { limit: None, offset: None }).await.unwrap();
+
+        let mut paged = Vec::new();
+        for offset in [0, 3, 6] {
+            let page = list(&pool, workspace_id, &LeadFilter::default(), &PageQuery { limit: Some(3), offset: Some(offset) })
+                .await
+                .unwrap();
+            assert_eq!(page.total, 7);
+            paged.extend(page.data.into_iter().map(|l| l.id));
+        }
+
+        let search = LeadFilter { search: Some("person3".to_string()), ..Default::default() };
+        let search = list(&pool, workspace_id, &search, &PageQuery { limit: None, offset: None }).await.unwrap();
+        // A literal % in the query isn't a wildcard
+        let literal = LeadFilter { search: Some("acme 1%".to_string()), ..Default::default() };
+        let literal = list(&pool, workspace_id, &literal, &PageQuery { limit: None, offset: None }).await.unwrap();
+        let company = LeadFilter { company: Some("ACME 100%".to_string()), ..Default::default() };
+        let company = list(&pool, workspace_id, &company, &PageQuery { limit: None, offset: None }).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert_eq!(valid.total, 4);
+        assert!(valid.data.iter().all(|l| l.verification_status == "valid"));
+
+        let mut unique = paged.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(paged.len(), 7);
+        assert_eq!(unique.len(), 7);
+
+        assert_eq!(search.data.iter().map(|l| l.email.as_str()).collect::<Vec<_>>(), vec!["lead3@lead-list.test"]);
+        assert_eq!(literal.total, 0);
+        assert_eq!(company.total, 7);
+    }
+}
@@ -0,0 +1,124 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How long a reservation holds quota if the request never releases it
+const RESERVATION_TTL_MINUTES: i32 = 15;
+
+/// Result of trying to reserve leads against the monthly limit
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reservation {
+    /// Quota is held until `release` is called (or the reservation expires).
+    /// `None` when the workspace has no limit and nothing needed reserving.
+    Granted(Option<Uuid>),
+    LimitExceeded { limit: i64, used: i64 },
+}
+
+/// Whether `requested` more leads fit under `limit` given what's already used or reserved.
+pub fn fits(limit: i64, used: i64, requested: i64) -> bool {
+    used.checked_add(requested).is_some_and(|total| total <= limit)
+}
+
+/// Reserves `requested` leads for this month. The workspace row is locked for the
+/// check-and-insert, so concurrent searches are serialized and can't both slip
+/// past the cap. Leads created this month and unexpired reservations both count.
+pub async fn reserve(pool: &PgPool, workspace_id: Uuid, requested: i64) -> Result<Reservation, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let limit: Option<Option<i32>> = sqlx::query_scalar(
+        "SELECT monthly_lead_limit FROM workspaces WHERE id = $1 FOR UPDATE"
+    )
+    .bind(workspace_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(Some(limit)) = limit else {
+        return Ok(Reservation::Granted(None));
+    };
+
+    let used: i64 = sqlx::query_scalar(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM leads
+             WHERE workspace_id = $1 AND created_at >= date_trunc('month', NOW()))
+          + (SELECT COALESCE(SUM(reserved), 0) FROM lead_reservations
+             WHERE workspace_id = $1 AND expires_at > NOW())
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // The reservation column is an INT, so anything wider can't fit under the limit either
+    let reserved = match i32::try_from(requested) {
+        Ok(reserved) if fits(i64::from(limit), used, requested) => reserved,
+        _ => return Ok(Reservation::LimitExceeded { limit: i64::from(limit), used }),
+    };
+
+    let reservation_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO lead_reservations (workspace_id, reserved, expires_at)
+        VALUES ($1, $2, NOW() + make_interval(mins => $3))
+        RETURNING id
+        "#
+    )
+    .bind(workspace_id)
+    .bind(reserved)
+    .bind(RESERVATION_TTL_MINUTES)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Reservation::Granted(Some(reservation_id)))
+}
+
+/// Releases a reservation once its leads are stored (or the search failed).
+/// Expired reservations are cleaned up for the workspace at the same time.
+pub async fn release(pool: &PgPool, workspace_id: Uuid, reservation_id: Option<Uuid>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM lead_reservations WHERE workspace_id = $1 AND (id = $2 OR expires_at <= NOW())"
+    )
+    .bind(workspace_id)
+    .bind(reservation_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_reservation_must_fit_under_limit() {
+        assert!(fits(100, 0, 50));
+        assert!(fits(100, 50, 50));
+        assert!(!fits(100, 51, 50));
+        assert!(!fits(100, 100, 1));
+        assert!(!fits(100, 1, i64::MAX));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_concurrent_searches_at_the_boundary() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Quota test").await;
+        sqlx::query("UPDATE workspaces SET monthly_lead_limit = 10 WHERE id = $1")
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Each search fits on its own, but not both together
+        let (a, b) = tokio::join!(reserve(&pool, workspace_id, 6), reserve(&pool, workspace_id, 6));
+        let outcomes = [a.unwrap(), b.unwrap()];
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let granted = outcomes.iter().filter(|o| matches!(o, Reservation::Granted(Some(_)))).count();
+        assert_eq!(granted, 1);
+        assert!(outcomes.contains(&Reservation::LimitExceeded { limit: 10, used: 6 }));
+    }
+}
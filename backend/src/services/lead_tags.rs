@@ -0,0 +1,111 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Longest tag accepted, in characters
+pub const MAX_TAG_LENGTH: usize = 50;
+/// Most tags one request can add
+pub const MAX_TAGS_PER_REQUEST: usize = 20;
+
+/// Trims and lowercases a tag so "Warm " and "warm" are the same group. Letters,
+/// digits, spaces, `-`, `_` and `.` are allowed.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_LENGTH
+        && tag.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'));
+
+    valid.then_some(tag)
+}
+
+/// Normalizes the tags of an add request, or explains why they can't be added.
+/// Shared by lead and campaign tags.
+pub fn normalize_request_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    if tags.is_empty() || tags.len() > MAX_TAGS_PER_REQUEST {
+        return Err(format!("Provide between 1 and {} tags", MAX_TAGS_PER_REQUEST));
+    }
+
+    tags.iter()
+        .map(|tag| {
+            normalize_tag(tag).ok_or_else(|| {
+                format!(
+                    "Invalid tag '{}': use up to {} letters, digits, spaces, '-', '_' or '.'",
+                    tag, MAX_TAG_LENGTH
+                )
+            })
+        })
+        .collect()
+}
+
+/// Tags on a lead, alphabetically
+pub async fn list_tags(pool: &PgPool, workspace_id: Uuid, lead_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT tag FROM lead_tags WHERE lead_id = $1 AND workspace_id = $2 ORDER BY tag"
+    )
+    .bind(lead_id)
+    .bind(workspace_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Adds already-normalized tags to a lead in the workspace. Returns `None` if the
+/// lead doesn't exist there, otherwise the lead's tags afterwards.
+pub async fn add_tags(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    lead_id: Uuid,
+    tags: &[String],
+) -> Result<Option<Vec<String>>, sqlx::Error> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM leads WHERE id = $1 AND workspace_id = $2 AND deleted_at IS NULL)"
+    )
+    .bind(lead_id)
+    .bind(workspace_id)
+    .fetch_one(pool)
+    .await?;
+
+    if !exists {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO lead_tags (lead_id, workspace_id, tag)
+        SELECT $1, $2, UNNEST($3::text[])
+        ON CONFLICT (lead_id, tag) DO NOTHING
+        "#
+    )
+    .bind(lead_id)
+    .bind(workspace_id)
+    .bind(tags)
+    .execute(pool)
+    .await?;
+
+    list_tags(pool, workspace_id, lead_id).await.map(Some)
+}
+
+/// Removes a tag from a lead. Returns whether it was there.
+pub async fn remove_tag(pool: &PgPool, workspace_id: Uuid, lead_id: Uuid, tag: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM lead_tags WHERE lead_id = $1 AND workspace_id = $2 AND tag = $3")
+        .bind(lead_id)
+        .bind(workspace_id)
+        .bind(tag)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  Warm "), Some("warm".to_string()));
+        assert_eq!(normalize_tag("Event-List_2024"), Some("event-list_2024".to_string()));
+        assert_eq!(normalize_tag("saastr 2024"), Some("saastr 2024".to_string()));
+        assert_eq!(normalize_tag("   "), None);
+        assert_eq!(normalize_tag("a,b"), None);
+        assert_eq!(normalize_tag(&"x".repeat(MAX_TAG_LENGTH + 1)), None);
+    }
+}
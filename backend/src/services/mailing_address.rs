@@ -0,0 +1,228 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::email_sender::{html_escape, EmailTemplate};
+
+/// `campaigns.send_blocked_reason` while the workspace has no postal address
+pub const ADDRESS_MISSING_REASON: &str = "mailing_address_missing";
+
+/// How often a held send checks back for an address
+const ADDRESS_RETRY_MINUTES: i64 = 60;
+
+const MAX_FIELD_LEN: usize = 255;
+const MAX_POSTAL_CODE_LEN: usize = 32;
+
+/// The workspace's physical postal address, which CAN-SPAM requires in every
+/// commercial email. Line 1, city and country are required before campaigns send.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CompanyAddress {
+    pub company_name: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl CompanyAddress {
+    /// Trims every field and drops empty ones
+    pub fn normalized(self) -> Self {
+        Self {
+            company_name: clean(self.company_name),
+            address_line1: clean(self.address_line1),
+            address_line2: clean(self.address_line2),
+            city: clean(self.city),
+            region: clean(self.region),
+            postal_code: clean(self.postal_code),
+            country: clean(self.country),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        [&self.address_line1, &self.city, &self.country]
+            .iter()
+            .all(|field| field.as_deref().is_some_and(|v| !v.trim().is_empty()))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.is_complete() {
+            return Err("address_line1, city and country are required".to_string());
+        }
+
+        let fields = [
+            ("company_name", &self.company_name, MAX_FIELD_LEN),
+            ("address_line1", &self.address_line1, MAX_FIELD_LEN),
+            ("address_line2", &self.address_line2, MAX_FIELD_LEN),
+            ("city", &self.city, MAX_FIELD_LEN),
+            ("region", &self.region, MAX_FIELD_LEN),
+            ("postal_code", &self.postal_code, MAX_POSTAL_CODE_LEN),
+            ("country", &self.country, MAX_FIELD_LEN),
+        ];
+        for (name, value, max) in fields {
+            if value.as_deref().is_some_and(|v| v.chars().count() > max) {
+                return Err(format!("{} must be at most {} characters", name, max));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The address as printed, e.g. `Acme Inc`, `1 Main St`, `Springfield, IL 62701`, `USA`
+    pub fn lines(&self) -> Vec<String> {
+        let region_postal = [&self.region, &self.postal_code]
+            .iter()
+            .filter_map(|v| v.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let locality = [self.city.as_deref(), Some(region_postal.as_str()).filter(|v| !v.is_empty())]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        [
+            self.company_name.clone(),
+            self.address_line1.clone(),
+            self.address_line2.clone(),
+            Some(locality).filter(|v| !v.is_empty()),
+            self.country.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// The stored address, empty if the workspace hasn't set one
+pub async fn get(pool: &PgPool, workspace_id: Uuid) -> Result<CompanyAddress, sqlx::Error> {
+    let address = sqlx::query_as::<_, CompanyAddress>(
+        r#"
+        SELECT company_name, company_address_line1 AS address_line1, company_address_line2 AS address_line2,
+               company_city AS city, company_region AS region, company_postal_code AS postal_code,
+               company_country AS country
+        FROM workspace_settings
+        WHERE workspace_id = $1
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(address.unwrap_or_default())
+}
+
+/// The address to print in campaign footers, or `None` when the workspace
+/// (if any) hasn't completed one
+pub async fn footer_address(pool: &PgPool, workspace_id: Option<Uuid>) -> Result<Option<CompanyAddress>, sqlx::Error> {
+    let Some(workspace_id) = workspace_id else {
+        return Ok(None);
+    };
+
+    let address = get(pool, workspace_id).await?;
+    Ok(address.is_complete().then_some(address))
+}
+
+pub async fn save(pool: &PgPool, workspace_id: Uuid, address: &CompanyAddress) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO workspace_settings (
+            workspace_id, company_name, company_address_line1, company_address_line2,
+            company_city, company_region, company_postal_code, company_country
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (workspace_id) DO UPDATE SET
+            company_name = EXCLUDED.company_name,
+            company_address_line1 = EXCLUDED.company_address_line1,
+            company_address_line2 = EXCLUDED.company_address_line2,
+            company_city = EXCLUDED.company_city,
+            company_region = EXCLUDED.company_region,
+            company_postal_code = EXCLUDED.company_postal_code,
+            company_country = EXCLUDED.company_country,
+            updated_at = NOW()
+        "#
+    )
+    .bind(workspace_id)
+    .bind(&address.company_name)
+    .bind(&address.address_line1)
+    .bind(&address.address_line2)
+    .bind(&address.city)
+    .bind(&address.region)
+    .bind(&address.postal_code)
+    .bind(&address.country)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// When a send held for a missing address should be tried again
+pub fn retry_at(now: DateTime<Utc>) -> DateTime<Utc> {
+    now + Duration::minutes(ADDRESS_RETRY_MINUTES)
+}
+
+/// Records on the campaign that it's waiting on a postal address
+pub async fn mark_address_missing(pool: &PgPool, campaign_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE campaigns SET send_blocked_reason = $2 WHERE id = $1 AND send_blocked_reason IS DISTINCT FROM $2"
+    )
+    .bind(campaign_id)
+    .bind(ADDRESS_MISSING_REASON)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Appends the standard footer (postal address and unsubscribe link) to both
+/// parts of a rendered campaign email. In HTML it goes just inside `</body>`
+/// when the template has one.
+pub fn append_footer(rendered: &mut EmailTemplate, address: &CompanyAddress, unsubscribe_url: &str) {
+    let lines = address.lines();
+
+    let html_footer = format!(
+        r#"<div style="margin-top: 24px; font-size: 12px; color: #999;"><p>{}</p><p><a href="{}">Unsubscribe</a></p></div>"#,
+        lines.iter().map(|line| html_escape(line)).collect::<Vec<_>>().join("<br>"),
+        html_escape(unsubscribe_url),
+    );
+    match rendered.body_html.to_ascii_lowercase().rfind("</body>") {
+        Some(at) => rendered.body_html.insert_str(at, &html_footer),
+        None => rendered.body_html.push_str(&html_footer),
+    }
+
+    let text = rendered.body_text.trim_end();
+    rendered.body_text = format!("{}\n\n--\n{}\nUnsubscribe: {}\n", text, lines.join("\n"), unsubscribe_url);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_needs_line1_city_and_country() {
+        let address = CompanyAddress {
+            company_name: Some(" Smith & Sons ".to_string()),
+            address_line1: Some("1 Main St".to_string()),
+            address_line2: Some("".to_string()),
+            city: Some("Springfield".to_string()),
+            region: Some("IL".to_string()),
+            postal_code: Some("62701".to_string()),
+            country: Some("USA".to_string()),
+        }
+        .normalized();
+
+        assert!(address.validate().is_ok());
+        assert_eq!(address.lines(), vec!["Smith & Sons", "1 Main St", "Springfield, IL 62701", "USA"]);
+
+        let no_country = CompanyAddress { country: Some("  ".to_string()), ..address.clone() }.normalized();
+        assert!(!no_country.is_complete());
+        assert!(no_country.validate().is_err());
+        assert!(!CompanyAddress::default().is_complete());
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::email_sender::{html_escape, CampaignEmailSender};
+use crate::services::job_queue;
+use crate::services::zapier;
+
+/// Attempts per reminder before the job is left failed
+const MAX_REMINDER_ATTEMPTS: i32 = 3;
+
+/// Reminders sent before each meeting, furthest out first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    DayBefore,
+    HourBefore,
+}
+
+impl ReminderKind {
+    pub const ALL: [ReminderKind; 2] = [ReminderKind::DayBefore, ReminderKind::HourBefore];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::DayBefore => "24h",
+            ReminderKind::HourBefore => "1h",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    fn lead_time(&self) -> Duration {
+        match self {
+            ReminderKind::DayBefore => Duration::hours(24),
+            ReminderKind::HourBefore => Duration::hours(1),
+        }
+    }
+}
+
+/// Job payload for one reminder
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingReminderPayload {
+    pub reminder_id: Uuid,
+}
+
+/// The reminder to send now for a meeting at `scheduled_at`, if any. Only the
+/// most imminent window counts, so a meeting booked 30 minutes out gets the 1h
+/// reminder but never a late 24h one. The 24h reminder is also skipped for
+/// meetings booked less than a day ahead, where it would just echo the booking.
+pub fn due_reminder(
+    scheduled_at: DateTime<Utc>,
+    booked_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    already_sent: &[ReminderKind],
+) -> Option<ReminderKind> {
+    let until = scheduled_at - now;
+    if until <= Duration::zero() {
+        return None;
+    }
+
+    let kind = if until <= ReminderKind::HourBefore.lead_time() {
+        ReminderKind::HourBefore
+    } else if until <= ReminderKind::DayBefore.lead_time() {
+        let booked_late = booked_at.is_some_and(|booked| scheduled_at - booked < ReminderKind::DayBefore.lead_time());
+        if booked_late {
+            return None;
+        }
+        ReminderKind::DayBefore
+    } else {
+        return None;
+    };
+
+    (!already_sent.contains(&kind)).then_some(kind)
+}
+
+/// Meeting time as the attendee sees it, e.g. `Tuesday 14 May at 15:00 (UTC+02:00)`.
+/// Falls back to UTC when no offset is known.
+pub fn local_meeting_time(scheduled_at: DateTime<Utc>, timezone_offset_minutes: Option<i32>) -> String {
+    let offset = timezone_offset_minutes
+        .and_then(|minutes| FixedOffset::east_opt(minutes * 60))
+        .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is valid"));
+
+    scheduled_at.with_timezone(&offset).format("%A %-d %B at %H:%M (UTC%:z)").to_string()
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct UpcomingMeeting {
+    id: Uuid,
+    scheduled_at: DateTime<Utc>,
+    created_at: Option<DateTime<Utc>>,
+    sent_kinds: Vec<String>,
+}
+
+/// Records and queues every reminder that's due. The unique (meeting, kind) row
+/// is claimed before the job is queued, so overlapping runs can't double up.
+/// Returns how many reminders were queued.
+pub async fn enqueue_due_reminders(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let meetings = sqlx::query_as::<_, UpcomingMeeting>(
+        r#"
+        SELECT
+            m.id,
+            m.scheduled_at,
+            m.created_at,
+            ARRAY(SELECT r.kind::text FROM meeting_reminders r WHERE r.meeting_id = m.id) as sent_kinds
+        FROM meetings m
+        WHERE m.status = 'scheduled'
+          AND m.scheduled_at > NOW()
+          AND m.scheduled_at <= NOW() + INTERVAL '24 hours'
+          AND (m.lead_id IS NOT NULL OR m.reply_id IS NOT NULL)
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let now = Utc::now();
+    let mut queued = 0;
+
+    for meeting in meetings {
+        let sent: Vec<ReminderKind> = meeting.sent_kinds.iter().filter_map(|k| ReminderKind::parse(k)).collect();
+        let Some(kind) = due_reminder(meeting.scheduled_at, meeting.created_at, now, &sent) else {
+            continue;
+        };
+
+        let mut tx = pool.begin().await?;
+
+        let reminder_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO meeting_reminders (meeting_id, kind)
+            VALUES ($1, $2)
+            ON CONFLICT (meeting_id, kind) DO NOTHING
+            RETURNING id
+            "#
+        )
+        .bind(meeting.id)
+        .bind(kind.as_str())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(reminder_id) = reminder_id else {
+            continue;
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries, priority)
+            SELECT gen_random_uuid(), m.workspace_id, '"SendMeetingReminder"', $2, 'pending', NOW(), 0, $3, $4
+            FROM meetings m WHERE m.id = $1
+            "#
+        )
+        .bind(meeting.id)
+        .bind(serde_json::json!({ "reminder_id": reminder_id }))
+        .bind(MAX_REMINDER_ATTEMPTS)
+        // Reminders are time-sensitive, like campaign sends
+        .bind(job_queue::PRIORITY_HIGH)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        queued += 1;
+    }
+
+    Ok(queued)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReminderDetails {
+    kind: String,
+    sent_at: Option<DateTime<Utc>>,
+    meeting_id: Uuid,
+    workspace_id: Uuid,
+    campaign_id: Option<Uuid>,
+    title: Option<String>,
+    scheduled_at: Option<DateTime<Utc>>,
+    meeting_link: Option<String>,
+    status: Option<String>,
+    timezone_offset_minutes: Option<i32>,
+    lead_email: Option<String>,
+    first_name: Option<String>,
+}
+
+/// Worker entry point for a `SendMeetingReminder` job. Emails the lead and fires
+/// any `meeting.reminder` Zapier hooks. Returns `false` when the reminder no
+/// longer applies: the meeting was cancelled, completed, moved into the past,
+/// the reminder already went out or the lead has since been suppressed.
+pub async fn send_reminder(
+    pool: &PgPool,
+    email_sender: &CampaignEmailSender,
+    payload: &MeetingReminderPayload,
+) -> Result<bool, String> {
+    let reminder = sqlx::query_as::<_, ReminderDetails>(
+        r#"
+        SELECT
+            r.kind,
+            r.sent_at,
+            m.id as meeting_id,
+            m.workspace_id,
+            m.campaign_id,
+            m.title,
+            m.scheduled_at,
+            m.meeting_link,
+            m.status,
+            COALESCE(m.timezone_offset_minutes, l.timezone_offset_minutes) as timezone_offset_minutes,
+            COALESCE(l.email, er.from_email) as lead_email,
+            l.first_name
+        FROM meeting_reminders r
+        JOIN meetings m ON m.id = r.meeting_id
+        LEFT JOIN leads l ON l.id = m.lead_id
+        LEFT JOIN email_replies er ON er.id = m.reply_id
+        WHERE r.id = $1
+        "#
+    )
+    .bind(payload.reminder_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?
+    .ok_or("Reminder not found")?;
+
+    let kind = ReminderKind::parse(&reminder.kind).ok_or_else(|| format!("Unknown reminder kind {}", reminder.kind))?;
+    let (Some(scheduled_at), Some(lead_email)) = (reminder.scheduled_at, reminder.lead_email.as_deref()) else {
+        return Ok(false);
+    };
+    if reminder.sent_at.is_some() || reminder.status.as_deref() != Some("scheduled") || scheduled_at <= Utc::now() {
+        return Ok(false);
+    }
+
+    let title = reminder.title.as_deref().map(str::trim).filter(|t| !t.is_empty()).unwrap_or("our call");
+    let local_time = local_meeting_time(scheduled_at, reminder.timezone_offset_minutes);
+    let (subject, when) = match kind {
+        ReminderKind::DayBefore => (format!("Reminder: {}", title), format!("on {}", local_time)),
+        ReminderKind::HourBefore => (format!("Starting soon: {}", title), format!("in about an hour, {}", local_time)),
+    };
+
+    let link = reminder
+        .meeting_link
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| format!("<p>Join here: <a href=\"{0}\">{0}</a></p>", html_escape(l)))
+        .unwrap_or_default();
+
+    let body_html = format!(
+        "<p>Hi {},</p>\n<p>Just a reminder that we're meeting {}: <strong>{}</strong>.</p>\n{}<p>See you then!</p>",
+        html_escape(reminder.first_name.as_deref().unwrap_or("there")),
+        html_escape(&when),
+        html_escape(title),
+        link
+    );
+
+    let sent = email_sender
+        .send_direct(reminder.workspace_id, reminder.campaign_id, lead_email, &subject, &body_html)
+        .await?;
+    if sent.is_none() {
+        return Ok(false);
+    }
+
+    sqlx::query("UPDATE meeting_reminders SET sent_at = NOW() WHERE id = $1")
+        .bind(payload.reminder_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    if let Err(e) = zapier::emit_meeting_reminder(pool, reminder.workspace_id, reminder.meeting_id, payload.reminder_id).await {
+        tracing::warn!("Failed to queue Zapier hooks for meeting reminder {}: {}", payload.reminder_id, e);
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_reminder_windows() {
+        let meeting = Utc.with_ymd_and_hms(2024, 5, 14, 13, 0, 0).unwrap();
+        let booked = Some(meeting - Duration::days(3));
+
+        assert_eq!(due_reminder(meeting, booked, meeting - Duration::hours(30), &[]), None);
+        assert_eq!(due_reminder(meeting, booked, meeting - Duration::hours(20), &[]), Some(ReminderKind::DayBefore));
+        assert_eq!(due_reminder(meeting, booked, meeting - Duration::hours(20), &[ReminderKind::DayBefore]), None);
+        assert_eq!(
+            due_reminder(meeting, booked, meeting - Duration::minutes(45), &[ReminderKind::DayBefore]),
+            Some(ReminderKind::HourBefore)
+        );
+        assert_eq!(due_reminder(meeting, booked, meeting + Duration::minutes(5), &[]), None);
+
+        // Booked the same day: no 24h reminder, but still the 1h one
+        let same_day = Some(meeting - Duration::hours(6));
+        assert_eq!(due_reminder(meeting, same_day, meeting - Duration::hours(5), &[]), None);
+        assert_eq!(due_reminder(meeting, same_day, meeting - Duration::minutes(30), &[]), Some(ReminderKind::HourBefore));
+    }
+
+    #[test]
+    fn test_local_meeting_time() {
+        let meeting = Utc.with_ymd_and_hms(2024, 5, 14, 13, 0, 0).unwrap();
+        assert_eq!(local_meeting_time(meeting, Some(120)), "Tuesday 14 May at 15:00 (UTC+02:00)");
+        assert_eq!(local_meeting_time(meeting, Some(-300)), "Tuesday 14 May at 08:00 (UTC-05:00)");
+        assert_eq!(local_meeting_time(meeting, None), "Tuesday 14 May at 13:00 (UTC+00:00)");
+    }
+}
@@ -1,13 +1,52 @@
 pub mod lead_generator;
+pub mod lead_quota;
+pub mod email_quota;
+pub mod mailing_address;
+pub mod lead_tags;
+pub mod lead_list;
+pub mod lead_dedup;
+pub mod lead_import;
+pub mod export;
 pub mod email_verifier;
 pub mod signal_tracker;
 pub mod deliverability;
+pub mod bounces;
+pub mod analytics;
+pub mod health_score;
+pub mod domain_auth;
 pub mod email_sender;
 pub mod job_queue;
+pub mod job_runner;
+pub mod worker_status;
+pub mod smtp_pool;
 pub mod encryption;
+pub mod email_oauth;
 pub mod campaign_scheduler;
+pub mod campaign_clone;
+pub mod campaign_tags;
 pub mod warmup_service;
 pub mod github_connector;
 pub mod wellfound_connector;
+pub mod rss_connector;
+pub mod funding_connector;
 pub mod reply_classifier;
+pub mod imap_poller;
 pub mod auto_pause;
+pub mod pause_notifications;
+pub mod send_time;
+pub mod company_discovery;
+pub mod email_webhooks;
+pub mod zapier;
+pub mod tracking;
+pub mod unsubscribe_token;
+pub mod meeting_reminders;
+pub mod data_retention;
+pub mod data_subject;
+pub mod suppression;
+pub mod key_rotation;
+pub mod rate_limiter;
+pub mod jwt_keys;
+pub mod workspace_membership;
+pub mod two_factor;
+pub mod password_reset;
+pub mod billing;
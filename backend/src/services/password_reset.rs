@@ -0,0 +1,244 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Argon2,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::services::email_sender::{html_escape, EmailSender, SendEmailRequest};
+
+/// Reset links stop working after this long
+pub const RESET_TOKEN_TTL_MINUTES: i64 = 60;
+
+pub const MIN_PASSWORD_LENGTH: usize = 10;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+#[derive(Debug)]
+pub enum ResetError {
+    /// Unknown, expired or already-used token; deliberately not told apart
+    InvalidToken,
+    WeakPassword(&'static str),
+    Hash(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ResetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResetError::InvalidToken => write!(f, "Reset link is invalid or has expired"),
+            ResetError::WeakPassword(reason) => write!(f, "{}", reason),
+            ResetError::Hash(e) => write!(f, "Failed to hash password: {}", e),
+            ResetError::Database(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ResetError {
+    fn from(e: sqlx::Error) -> Self {
+        ResetError::Database(e)
+    }
+}
+
+/// At least `MIN_PASSWORD_LENGTH` characters with both letters and digits
+pub fn validate_password_strength(password: &str) -> Result<(), &'static str> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_LENGTH {
+        return Err("Password must be at least 10 characters");
+    }
+    if length > MAX_PASSWORD_LENGTH {
+        return Err("Password must be at most 128 characters");
+    }
+    if !password.chars().any(char::is_alphabetic) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Password must contain both letters and numbers");
+    }
+    Ok(())
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Issues a reset token for the user, replacing any earlier unused one. The raw token
+/// is returned for the email and never stored.
+pub async fn create_reset_token(pool: &PgPool, user_id: Uuid) -> Result<String, sqlx::Error> {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = URL_SAFE_NO_PAD.encode(bytes);
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("UPDATE password_resets SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO password_resets (user_id, token_hash, expires_at)
+        VALUES ($1, $2, NOW() + INTERVAL '1 minute' * $3)
+        "#,
+    )
+    .bind(user_id)
+    .bind(hash_token(&token))
+    .bind(RESET_TOKEN_TTL_MINUTES as i32)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(token)
+}
+
+/// Sets a new password if `token` is valid, consuming the token. Returns the user's id.
+pub async fn reset_password(pool: &PgPool, token: &str, new_password: &str) -> Result<Uuid, ResetError> {
+    validate_password_strength(new_password).map_err(ResetError::WeakPassword)?;
+
+    let salt = SaltString::generate(&mut OsRng);
+    let password_hash = Argon2::default()
+        .hash_password(new_password.as_bytes(), &salt)
+        .map_err(|e| ResetError::Hash(e.to_string()))?
+        .to_string();
+
+    let mut tx = pool.begin().await?;
+
+    // Claiming the token in the same statement that checks it makes it single-use
+    // even when two resets race
+    let user_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        UPDATE password_resets SET used_at = NOW()
+        WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id
+        "#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let user_id = user_id.ok_or(ResetError::InvalidToken)?;
+
+    sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+        .bind(&password_hash)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(user_id)
+}
+
+/// Emails the reset link. Logs rather than fails, since the caller's response must
+/// not reveal whether the address has an account.
+pub async fn send_reset_email(to_email: &str, to_name: &str, token: &str) {
+    let Some(sender) = EmailSender::from_env() else {
+        tracing::warn!("SMTP is not configured; password reset email to {} not sent", to_email);
+        return;
+    };
+
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let reset_url = format!("{}/reset-password?token={}", frontend_url.trim_end_matches('/'), token);
+
+    let result = sender
+        .send(SendEmailRequest {
+            to_email: to_email.to_string(),
+            to_name: Some(to_name.to_string()),
+            subject: "Reset your OutreachIQ password".to_string(),
+            body_html: format!(
+                "<p>Hi {},</p>\
+                 <p>Someone asked to reset the password for your OutreachIQ account. \
+                 If it was you, <a href=\"{}\">choose a new password</a>. The link works once \
+                 and expires in {} minutes.</p>\
+                 <p>If you didn't ask for this, you can ignore this email.</p>",
+                html_escape(to_name),
+                reset_url,
+                RESET_TOKEN_TTL_MINUTES
+            ),
+            body_text: Some(format!(
+                "Hi {},\n\nSomeone asked to reset the password for your OutreachIQ account. \
+                 If it was you, choose a new password here:\n\n{}\n\nThe link works once and expires \
+                 in {} minutes. If you didn't ask for this, you can ignore this email.\n",
+                to_name, reset_url, RESET_TOKEN_TTL_MINUTES
+            )),
+        })
+        .await;
+
+    if !result.success {
+        tracing::error!(
+            "Failed to send password reset email to {}: {}",
+            to_email,
+            result.error.unwrap_or_default()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[test]
+    fn test_password_strength() {
+        assert!(validate_password_strength("short1").is_err());
+        assert!(validate_password_strength("onlyletterslong").is_err());
+        assert!(validate_password_strength("1234567890123").is_err());
+        assert!(validate_password_strength(&"a1".repeat(65)).is_err());
+        assert!(validate_password_strength("correct horse 42").is_ok());
+    }
+
+    async fn password_hash(pool: &PgPool, user_id: Uuid) -> String {
+        sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_valid_token_resets_once() {
+        let pool = test_db::pool().await;
+        let (user_id, _) = test_db::user(&pool, "old-hash").await;
+
+        let token = create_reset_token(&pool, user_id).await.unwrap();
+        assert_eq!(reset_password(&pool, &token, "new password 123").await.unwrap(), user_id);
+        assert!(password_hash(&pool, user_id).await.starts_with("$argon2"));
+
+        // Reusing the token fails and leaves the new password in place
+        let hash = password_hash(&pool, user_id).await;
+        assert!(matches!(
+            reset_password(&pool, &token, "another password 456").await,
+            Err(ResetError::InvalidToken)
+        ));
+        assert_eq!(password_hash(&pool, user_id).await, hash);
+
+        // A newer link replaces an older unused one
+        let older = create_reset_token(&pool, user_id).await.unwrap();
+        let newer = create_reset_token(&pool, user_id).await.unwrap();
+        assert!(matches!(reset_password(&pool, &older, "third password 789").await, Err(ResetError::InvalidToken)));
+        assert!(reset_password(&pool, &newer, "third password 789").await.is_ok());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_expired_token_is_rejected() {
+        let pool = test_db::pool().await;
+        let (user_id, _) = test_db::user(&pool, "old-hash").await;
+
+        let token = create_reset_token(&pool, user_id).await.unwrap();
+        sqlx::query("UPDATE password_resets SET expires_at = NOW() - INTERVAL '1 minute' WHERE token_hash = $1")
+            .bind(hash_token(&token))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            reset_password(&pool, &token, "new password 123").await,
+            Err(ResetError::InvalidToken)
+        ));
+        assert_eq!(password_hash(&pool, user_id).await, "old-hash");
+    }
+}
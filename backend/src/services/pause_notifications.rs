@@ -0,0 +1,309 @@
+use reqwest::{redirect, Client, Url};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::services::email_sender::{html_escape, EmailSender, SendEmailRequest};
+
+static HTTP: LazyLock<Client> = LazyLock::new(|| {
+    // The host is checked before posting; a redirect could lead anywhere
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(redirect::Policy::none())
+        .build()
+        .unwrap_or_else(|_| Client::new())
+});
+
+/// A campaign the health check just paused
+#[derive(Debug, Clone)]
+pub struct PausedCampaign {
+    pub campaign_id: Uuid,
+    pub campaign_name: String,
+    /// `pause_reason_detail`, the sentence shown to the user
+    pub detail: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct NotificationTargets {
+    workspace_name: String,
+    notification_email: Option<String>,
+    slack_webhook_url: Option<String>,
+}
+
+fn campaigns_url(campaign_id: Uuid) -> String {
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    format!("{}/campaigns/{}", frontend_url.trim_end_matches('/'), campaign_id)
+}
+
+fn headline(workspace_name: &str, paused: &[PausedCampaign]) -> String {
+    match paused {
+        [one] => format!("Campaign \"{}\" was auto-paused in {}", one.campaign_name, workspace_name),
+        _ => format!("{} campaigns were auto-paused in {}", paused.len(), workspace_name),
+    }
+}
+
+/// Incoming-webhook body: a plain `text` fallback for notifications plus one
+/// block per paused campaign
+pub fn slack_payload(workspace_name: &str, paused: &[PausedCampaign]) -> serde_json::Value {
+    let headline = headline(workspace_name, paused);
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": headline },
+    })];
+    blocks.extend(paused.iter().map(|campaign| {
+        json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(
+                    "*<{}|{}>*\n{}",
+                    campaigns_url(campaign.campaign_id),
+                    slack_escape(&campaign.campaign_name),
+                    slack_escape(&campaign.detail)
+                ),
+            },
+        })
+    }));
+
+    json!({ "text": headline, "blocks": blocks })
+}
+
+/// Whether `url` is a Slack incoming webhook. The URL is workspace-supplied and
+/// the worker posts to it, so anything else (internal hosts, metadata endpoints)
+/// is refused.
+pub fn is_valid_slack_webhook_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url.host_str() == Some("hooks.slack.com")
+            && url.port().is_none()
+            && url.username().is_empty()
+            && url.password().is_none()
+    })
+}
+
+/// Posts to a Slack incoming webhook; any non-2xx response is an error
+pub async fn post_to_slack(webhook_url: &str, payload: &serde_json::Value) -> Result<(), String> {
+    if !is_valid_slack_webhook_url(webhook_url) {
+        return Err("Slack webhook URL must start with https://hooks.slack.com/".to_string());
+    }
+    post_to_webhook(webhook_url, payload).await
+}
+
+async fn post_to_webhook(webhook_url: &str, payload: &serde_json::Value) -> Result<(), String> {
+    let response = HTTP
+        .post(webhook_url)
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| format!("Slack webhook request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Slack webhook returned {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Tells the workspace about the campaigns one health-check cycle paused, as a
+/// single digest on each configured channel. Best-effort: failures are logged,
+/// since the pauses themselves have already happened.
+pub async fn notify_auto_paused(pool: &PgPool, workspace_id: Uuid, paused: &[PausedCampaign]) {
+    if paused.is_empty() {
+        return;
+    }
+
+    let targets = sqlx::query_as::<_, NotificationTargets>(
+        r#"
+        SELECT w.name AS workspace_name, ws.notification_email, ws.slack_webhook_url
+        FROM workspaces w
+        LEFT JOIN workspace_settings ws ON ws.workspace_id = w.id
+        WHERE w.id = $1
+        "#
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await;
+
+    let targets = match targets {
+        Ok(Some(targets)) => targets,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("Failed to load notification settings for workspace {}: {}", workspace_id, e);
+            return;
+        }
+    };
+
+    if let Some(webhook_url) = targets.slack_webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        if let Err(e) = post_to_slack(webhook_url, &slack_payload(&targets.workspace_name, paused)).await {
+            tracing::warn!("Auto-pause Slack notification for workspace {} failed: {}", workspace_id, e);
+        }
+    }
+
+    if let Some(to_email) = targets.notification_email.as_deref().map(str::trim).filter(|e| !e.is_empty()) {
+        send_digest_email(to_email, &targets.workspace_name, paused).await;
+    }
+}
+
+async fn send_digest_email(to_email: &str, workspace_name: &str, paused: &[PausedCampaign]) {
+    let Some(sender) = EmailSender::from_env() else {
+        tracing::warn!("SMTP is not configured; auto-pause email to {} not sent", to_email);
+        return;
+    };
+
+    let headline = headline(workspace_name, paused);
+    let items_html: String = paused
+        .iter()
+        .map(|campaign| {
+            format!(
+                "<li><a href=\"{}\">{}</a>: {}</li>",
+                campaigns_url(campaign.campaign_id),
+                html_escape(&campaign.campaign_name),
+                html_escape(&campaign.detail)
+            )
+        })
+        .collect();
+    let items_text: String = paused
+        .iter()
+        .map(|campaign| format!("- {}: {}\n  {}\n", campaign.campaign_name, campaign.detail, campaigns_url(campaign.campaign_id)))
+        .collect();
+
+    let result = sender
+        .send(SendEmailRequest {
+            to_email: to_email.to_string(),
+            to_name: None,
+            subject: headline.clone(),
+            body_html: format!(
+                "<p>{}.</p><ul>{}</ul>\
+                 <p>Sending stays paused until you resume the campaign.</p>",
+                html_escape(&headline),
+                items_html
+            ),
+            body_text: Some(format!(
+                "{}.\n\n{}\nSending stays paused until you resume the campaign.\n",
+                headline, items_text
+            )),
+        })
+        .await;
+
+    if !result.success {
+        tracing::warn!(
+            "Failed to send auto-pause email to {}: {}",
+            to_email,
+            result.error.unwrap_or_default()
+        );
+    }
+}
+
+/// Slack treats `&`, `<` and `>` as control characters in mrkdwn
+fn slack_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn paused(name: &str, detail: &str) -> PausedCampaign {
+        PausedCampaign {
+            campaign_id: Uuid::new_v4(),
+            campaign_name: name.to_string(),
+            detail: detail.to_string(),
+        }
+    }
+
+    /// Accepts one request, answers with `status`, and hands back the request body
+    async fn mock_webhook(status: u16) -> (String, tokio::task::JoinHandle<serde_json::Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/services/T000/B000/XXXX", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                }
+            };
+            let response = format!("HTTP/1.1 {} X\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok", status);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            serde_json::from_str(&body).unwrap()
+        });
+
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_one_digest_lists_every_paused_campaign() {
+        let batch = vec![
+            paused("Q3 <Founders>", "Spam rate spiked to 4.2% (threshold: 3.0%)"),
+            paused("Agencies", "Bounce rate reached 9.1% (threshold: 8.0%)"),
+        ];
+        let (url, server) = mock_webhook(200).await;
+
+        post_to_webhook(&url, &slack_payload("Acme", &batch)).await.unwrap();
+        let body = server.await.unwrap();
+
+        assert_eq!(body["text"], "2 campaigns were auto-paused in Acme");
+        let blocks = body["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "header");
+        let first = blocks[1]["text"]["text"].as_str().unwrap();
+        assert!(first.contains("Q3 &lt;Founders&gt;"));
+        assert!(first.contains("Spam rate spiked to 4.2%"));
+        assert!(first.contains(&format!("/campaigns/{}|", batch[0].campaign_id)));
+        assert!(blocks[2]["text"]["text"].as_str().unwrap().contains("Bounce rate reached 9.1%"));
+
+        assert_eq!(
+            slack_payload("Acme", &batch[1..])["text"],
+            "Campaign \"Agencies\" was auto-paused in Acme"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_failure_is_reported_not_panicked() {
+        let (url, server) = mock_webhook(500).await;
+        let err = post_to_webhook(&url, &slack_payload("Acme", &[paused("Agencies", "Bounce")])).await.unwrap_err();
+        assert!(err.contains("500"));
+        server.await.unwrap();
+
+        // Nothing listening at all
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(post_to_webhook(&url, &json!({ "text": "hi" })).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_slack_webhook_urls_are_posted_to() {
+        assert!(is_valid_slack_webhook_url("https://hooks.slack.com/services/T000/B000/XXXX"));
+        for url in [
+            "http://hooks.slack.com/services/T000/B000/XXXX",
+            "https://hooks.slack.com:8443/services/T000",
+            "https://hooks.slack.com.evil.io/services/T000",
+            "https://user@hooks.slack.com/services/T000",
+            "https://169.254.169.254/latest/meta-data/",
+            "not a url",
+        ] {
+            assert!(!is_valid_slack_webhook_url(url), "{}", url);
+        }
+
+        // Refused before any request is made
+        let (url, server) = mock_webhook(200).await;
+        assert!(post_to_slack(&url, &json!({ "text": "hi" })).await.is_err());
+        server.abort();
+    }
+}
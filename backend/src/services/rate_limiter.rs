@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tracked callers before expired windows are swept out of the map
+const SWEEP_THRESHOLD: usize = 1024;
+
+/// Fixed-window request limiter keyed by caller (e.g. user ID).
+///
+/// State lives in process memory, so each API instance enforces the limit on its
+/// own. That's enough to stop one caller from hammering expensive endpoints.
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests: max_requests.max(1),
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request for `key`. Returns the time until the window resets if
+    /// the caller has already used up their requests.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if windows.len() >= SWEEP_THRESHOLD {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+        }
+
+        let (started, count) = windows.entry(key.to_string()).or_insert((now, 0));
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+
+        if *count >= self.max_requests {
+            return Err(self.window - now.duration_since(*started));
+        }
+
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Longest a repeat offender can be locked out for
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
+struct FailureState {
+    window_started: Instant,
+    failures: u32,
+    last_failure: Instant,
+    /// Lockouts served since the key last went a full window without failing
+    lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+/// Failed-login counter keyed by caller (an email address or client IP).
+///
+/// `max_failures` failures within `window` lock the key out for `base_lockout`,
+/// doubling with each further lockout up to an hour. A key that goes a full window
+/// without failing starts over. Like `RateLimiter`, state is per process.
+pub struct LoginThrottle {
+    max_failures: u32,
+    window: Duration,
+    base_lockout: Duration,
+    state: Mutex<HashMap<String, FailureState>>,
+}
+
+impl LoginThrottle {
+    pub fn new(max_failures: u32, window: Duration, base_lockout: Duration) -> Self {
+        Self {
+            max_failures: max_failures.max(1),
+            window,
+            base_lockout,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads the failure limit from `max_failures_var`, and the window and first lockout
+    /// from `LOGIN_FAILURE_WINDOW_SECS` (default 15 minutes) and `LOGIN_LOCKOUT_SECS`
+    /// (default 1 minute)
+    pub fn from_env(max_failures_var: &str, default_max_failures: u32) -> Self {
+        fn env_u64(var: &str) -> Option<u64> {
+            std::env::var(var).ok()?.trim().parse().ok().filter(|v| *v > 0)
+        }
+
+        Self::new(
+            env_u64(max_failures_var).map_or(default_max_failures, |v| v as u32),
+            Duration::from_secs(env_u64("LOGIN_FAILURE_WINDOW_SECS").unwrap_or(15 * 60)),
+            Duration::from_secs(env_u64("LOGIN_LOCKOUT_SECS").unwrap_or(60)),
+        )
+    }
+
+    /// Returns how long `key` is still locked out for, if it is
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    pub fn record_failure(&self, key: &str) {
+        self.record_failure_at(key, Instant::now())
+    }
+
+    /// Clears the key's failures and lockout history
+    pub fn record_success(&self, key: &str) {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.get(key).and_then(|s| s.locked_until) {
+            Some(until) if until > now => Err(until - now),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_failure_at(&self, key: &str, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if state.len() >= SWEEP_THRESHOLD {
+            let keep_for = self.window.max(MAX_LOCKOUT);
+            state.retain(|_, s| now.duration_since(s.last_failure) < keep_for);
+        }
+
+        let entry = state.entry(key.to_string()).or_insert(FailureState {
+            window_started: now,
+            failures: 0,
+            last_failure: now,
+            lockouts: 0,
+            locked_until: None,
+        });
+
+        if now.duration_since(entry.last_failure) >= self.window {
+            entry.lockouts = 0;
+        }
+        if now.duration_since(entry.window_started) >= self.window {
+            entry.window_started = now;
+            entry.failures = 0;
+        }
+
+        entry.failures += 1;
+        entry.last_failure = now;
+
+        if entry.failures >= self.max_failures {
+            let lockout = self
+                .base_lockout
+                .saturating_mul(2u32.saturating_pow(entry.lockouts))
+                .min(MAX_LOCKOUT);
+            entry.locked_until = Some(now + lockout);
+            entry.lockouts += 1;
+            entry.window_started = now;
+            entry.failures = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_is_per_caller_and_resets_with_the_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(limiter.check_at("alice", start).is_ok());
+        assert!(limiter.check_at("alice", start + Duration::from_secs(1)).is_ok());
+        assert_eq!(
+            limiter.check_at("alice", start + Duration::from_secs(20)),
+            Err(Duration::from_secs(40))
+        );
+
+        // Other callers have their own budget
+        assert!(limiter.check_at("bob", start + Duration::from_secs(20)).is_ok());
+
+        assert!(limiter.check_at("alice", start + Duration::from_secs(60)).is_ok());
+    }
+
+    #[test]
+    fn test_login_locks_out_after_repeated_failures() {
+        let throttle = LoginThrottle::new(6, Duration::from_secs(900), Duration::from_secs(60));
+        let start = Instant::now();
+
+        for attempt in 0..6 {
+            let at = start + Duration::from_secs(attempt);
+            assert!(throttle.check_at("email:alice@acme.io", at).is_ok());
+            throttle.record_failure_at("email:alice@acme.io", at);
+        }
+
+        // 7th attempt is refused until the lockout ends
+        let seventh = start + Duration::from_secs(6);
+        assert_eq!(throttle.check_at("email:alice@acme.io", seventh), Err(Duration::from_secs(59)));
+        assert!(throttle.check_at("email:bob@acme.io", seventh).is_ok());
+
+        // The next lockout doubles
+        let after_lockout = start + Duration::from_secs(70);
+        assert!(throttle.check_at("email:alice@acme.io", after_lockout).is_ok());
+        for _ in 0..6 {
+            throttle.record_failure_at("email:alice@acme.io", after_lockout);
+        }
+        assert_eq!(throttle.check_at("email:alice@acme.io", after_lockout), Err(Duration::from_secs(120)));
+
+        throttle.record_success("email:alice@acme.io");
+        assert!(throttle.check_at("email:alice@acme.io", after_lockout).is_ok());
+    }
+}
@@ -1,6 +1,24 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::env;
+use std::sync::LazyLock;
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::{campaign_scheduler, zapier};
+
+/// Model calls in flight at once from background classification, however many jobs run
+const MAX_CONCURRENT_CLASSIFICATIONS: usize = 2;
+/// Replies looked at per background sweep
+const CLASSIFY_SWEEP_LIMIT: i64 = 200;
+/// Replies sent to Claude in one request; a job classifies one batch
+pub const CLASSIFY_BATCH_SIZE: usize = 10;
+/// Older unclassified replies are left alone rather than spending budget on history
+const CLASSIFY_LOOKBACK_DAYS: i32 = 30;
+
+static CLASSIFY_PERMITS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_CLASSIFICATIONS));
 
 #[derive(Debug, Serialize)]
 struct ClaudeRequest {
@@ -25,30 +43,386 @@ struct ClaudeContent {
     text: String,
 }
 
-const CLASSIFICATION_PROMPT: &str = r#"Classify this cold email reply into ONE category:
-- interested: Shows clear interest ("Tell me more", "Let's chat", "Send calendar", "Yes", "Sounds good", positive engagement)
-- maybe_later: Timing issue but not negative ("Not now", "Check back Q2", "Timing bad", "Maybe next quarter")
-- objection: Has questions or concerns ("How much?", "Who else uses this?", "What's the pricing?", "Need more info")
-- negative: Wants to stop ("Unsubscribe", "Stop", "Remove me", angry tone, explicit rejection)
-- auto_reply: Automated response (OOO, bounce-back, vacation, auto-responder)
+/// Built-in intents, in the order they appear in the classification prompt.
+const DEFAULT_CATEGORIES: &[(&str, &str)] = &[
+    ("interested", r#"Shows clear interest ("Tell me more", "Let's chat", "Send calendar", "Yes", "Sounds good", positive engagement)"#),
+    ("maybe_later", r#"Timing issue but not negative ("Not now", "Check back Q2", "Timing bad", "Maybe next quarter")"#),
+    ("objection", r#"Has questions or concerns ("How much?", "Who else uses this?", "What's the pricing?", "Need more info")"#),
+    ("negative", r#"Wants to stop ("Unsubscribe", "Stop", "Remove me", angry tone, explicit rejection)"#),
+    ("auto_reply", "Automated response (OOO, bounce-back, vacation, auto-responder)"),
+];
+
+/// Dashboard ordering when a workspace hasn't set its own; unlisted intents sort last.
+pub const DEFAULT_PRIORITY: &[&str] = &["interested", "objection", "maybe_later"];
+
+/// Used when the model's answer doesn't match any active category
+const FALLBACK_INTENT: &str = "auto_reply";
+
+/// Confidence given to Claude classifications
+const MODEL_CONFIDENCE: f32 = 0.85;
+/// Rule-based labels stand in for Claude when it's unavailable, so they never
+/// claim to be as sure as the model
+const RULE_FALLBACK_MAX_CONFIDENCE: f32 = 0.6;
+
+/// Must fit `email_replies.intent`
+const MAX_LABEL_LEN: usize = 20;
+
+/// Headers that mark a message as machine-generated (RFC 3834 and common vendor ones)
+const AUTO_REPLY_HEADERS: &[&str] = &["X-Autoreply", "X-Autorespond", "X-Auto-Response-Suppress"];
+/// Subject prefixes of out-of-office notices and delivery failures
+const AUTO_REPLY_SUBJECTS: &[&str] = &[
+    "auto:",
+    "automatic reply",
+    "autoreply",
+    "auto-reply",
+    "out of office",
+    "out of the office",
+    "away from the office",
+    "on vacation",
+    "undeliverable",
+    "undelivered mail",
+    "delivery status notification",
+    "mail delivery failed",
+    "delivery failure",
+    "returned mail",
+];
+
+/// Phrases that mean the sender wants to move quickly
+const URGENCY_PATTERNS: &[&str] = &[
+    "this week", "today", "tomorrow", "asap", "as soon as possible", "urgent",
+    "right away", "end of the week", "by friday", "by monday", "tight timeline",
+    "deadline", "need this soon", "call me", "can we talk now",
+];
+
+const POSITIVE_WORDS: &[&str] = &[
+    "great", "love", "excited", "perfect", "awesome", "thanks", "thank you",
+    "glad", "happy", "impressive", "exactly what",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "annoying", "annoyed", "waste", "never", "stop", "spam", "angry",
+    "frustrated", "terrible", "useless", "don't",
+];
+
+/// Intent plus the sentiment and urgency used to order the reply queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplyClassification {
+    pub intent: String,
+    pub confidence: f32,
+    /// -1 (hostile) to 1 (enthusiastic)
+    pub sentiment: f64,
+    /// Asks to talk this week or mentions a tight timeline
+    pub urgent: bool,
+}
+
+/// Shape the model is asked to answer in
+#[derive(Debug, Deserialize)]
+struct ModelClassification {
+    /// Which reply of a batch this answers
+    index: Option<usize>,
+    intent: String,
+    sentiment: Option<f64>,
+    urgent: Option<bool>,
+}
+
+/// A reply intent the classifier can choose from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReplyCategory {
+    pub label: String,
+    pub description: String,
+}
+
+/// Categories and dashboard priority for one workspace
+#[derive(Debug, Clone)]
+pub struct ReplyCategoryConfig {
+    /// Workspace-defined categories, on top of the built-in ones
+    pub custom: Vec<ReplyCategory>,
+    pub priority: Vec<String>,
+}
+
+impl Default for ReplyCategoryConfig {
+    fn default() -> Self {
+        Self {
+            custom: Vec::new(),
+            priority: default_priority(),
+        }
+    }
+}
+
+impl ReplyCategoryConfig {
+    /// Built-in categories followed by the custom ones
+    pub fn categories(&self) -> Vec<ReplyCategory> {
+        let mut categories = default_categories();
+        categories.extend(self.custom.iter().cloned());
+        categories
+    }
+}
+
+pub fn default_categories() -> Vec<ReplyCategory> {
+    DEFAULT_CATEGORIES
+        .iter()
+        .map(|(label, description)| ReplyCategory {
+            label: label.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+pub fn default_priority() -> Vec<String> {
+    DEFAULT_PRIORITY.iter().map(|s| s.to_string()).collect()
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_LABEL_LEN
+        && label.starts_with(|c: char| c.is_ascii_lowercase())
+        && label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Checks a workspace's custom categories: snake_case labels that fit the intent
+/// column, a description for each, and no clashes with built-in or other custom labels.
+pub fn validate_custom_categories(custom: &[ReplyCategory]) -> Result<(), String> {
+    let mut seen: Vec<&str> = DEFAULT_CATEGORIES.iter().map(|(label, _)| *label).collect();
+
+    for category in custom {
+        if !is_valid_label(&category.label) {
+            return Err(format!(
+                "Invalid category label '{}': use lowercase letters, digits and underscores (max {} chars)",
+                category.label, MAX_LABEL_LEN
+            ));
+        }
+        if category.description.trim().is_empty() {
+            return Err(format!("Category '{}' needs a description", category.label));
+        }
+        if seen.contains(&category.label.as_str()) {
+            return Err(format!("Duplicate category label '{}'", category.label));
+        }
+        seen.push(&category.label);
+    }
+
+    Ok(())
+}
+
+/// Checks that every label in a priority order is an active category and listed once.
+pub fn validate_priority(priority: &[String], categories: &[ReplyCategory]) -> Result<(), String> {
+    for (i, label) in priority.iter().enumerate() {
+        if !categories.iter().any(|c| &c.label == label) {
+            return Err(format!("Unknown category '{}' in priority order", label));
+        }
+        if priority[..i].contains(label) {
+            return Err(format!("Category '{}' is listed twice in priority order", label));
+        }
+    }
+    Ok(())
+}
+
+/// Loads the active categories and priority for a workspace, falling back to the
+/// defaults when it has no settings row.
+pub async fn load_workspace_config(pool: &PgPool, workspace_id: Uuid) -> Result<ReplyCategoryConfig, sqlx::Error> {
+    let row: Option<(sqlx::types::Json<Vec<ReplyCategory>>, Vec<String>)> = sqlx::query_as(
+        "SELECT reply_categories, reply_category_priority FROM workspace_settings WHERE workspace_id = $1"
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some((custom, priority)) => ReplyCategoryConfig { custom: custom.0, priority },
+        None => ReplyCategoryConfig::default(),
+    })
+}
+
+fn build_prompt(categories: &[ReplyCategory], reply_text: &str) -> String {
+    let list = categories
+        .iter()
+        .map(|c| format!("- {}: {}", c.label, c.description))
+        .collect::<Vec<_>>()
+        .join("\n");
 
-Reply text:
-{reply_text}
+    format!(
+        "Classify this cold email reply into ONE category:\n{}\n\nReply text:\n{}\n\n\
+         Also rate its sentiment from -1 (hostile) to 1 (enthusiastic), and whether it is urgent \
+         (asks to talk this week, or mentions a tight timeline or deadline).\n\n\
+         Return ONLY a JSON object: {{\"intent\": \"<category name>\", \"sentiment\": <number>, \"urgent\": <true|false>}}. Nothing else.",
+        list, reply_text
+    )
+}
+
+/// Reads the model's JSON answer. Falls back to treating the output as a bare
+/// category name, with sentiment and urgency from the heuristics.
+fn parse_classification(output: &str, categories: &[ReplyCategory], reply_text: &str) -> ReplyClassification {
+    let json = output
+        .find('{')
+        .zip(output.rfind('}'))
+        .and_then(|(start, end)| output.get(start..=end))
+        .and_then(|json| serde_json::from_str::<ModelClassification>(json).ok());
+
+    let intent = match &json {
+        Some(parsed) => match_intent(&parsed.intent, categories),
+        None => match_intent(output, categories),
+    };
+
+    model_classification(
+        intent,
+        json.as_ref().and_then(|parsed| parsed.sentiment),
+        json.as_ref().and_then(|parsed| parsed.urgent),
+        reply_text,
+    )
+}
+
+/// The model's answer, with sentiment and urgency from the heuristics when it
+/// left them out
+fn model_classification(intent: String, sentiment: Option<f64>, urgent: Option<bool>, reply_text: &str) -> ReplyClassification {
+    let sentiment = sentiment
+        .filter(|s| s.is_finite())
+        .map(|s| s.clamp(-1.0, 1.0))
+        .unwrap_or_else(|| heuristic_sentiment(reply_text, &intent));
+    let urgent = urgent.unwrap_or_else(|| detect_urgency(reply_text));
+
+    ReplyClassification { intent, confidence: MODEL_CONFIDENCE, sentiment, urgent }
+}
+
+fn build_batch_prompt(categories: &[ReplyCategory], replies: &[String]) -> String {
+    let list = categories
+        .iter()
+        .map(|c| format!("- {}: {}", c.label, c.description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let replies = replies
+        .iter()
+        .enumerate()
+        .map(|(index, text)| format!("<reply index=\"{}\">\n{}\n</reply>", index, text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        "Classify each of these cold email replies into ONE category:\n{}\n\nReplies:\n{}\n\n\
+         For each reply also rate its sentiment from -1 (hostile) to 1 (enthusiastic), and whether it is urgent \
+         (asks to talk this week, or mentions a tight timeline or deadline).\n\n\
+         Return ONLY a JSON array with one object per reply: \
+         [{{\"index\": <reply index>, \"intent\": \"<category name>\", \"sentiment\": <number>, \"urgent\": <true|false>}}]. Nothing else.",
+        list, replies
+    )
+}
+
+/// Reads the model's JSON array for a batch, matching answers to replies by
+/// index (or by position when indexes are missing). `None` unless every reply
+/// got exactly one answer.
+fn parse_batch_classification(output: &str, categories: &[ReplyCategory], replies: &[String]) -> Option<Vec<ReplyClassification>> {
+    let json = output
+        .find('[')
+        .zip(output.rfind(']'))
+        .and_then(|(start, end)| output.get(start..=end))?;
+    let answers: Vec<ModelClassification> = serde_json::from_str(json).ok()?;
+    if answers.len() != replies.len() {
+        return None;
+    }
+
+    let mut results: Vec<Option<ReplyClassification>> = vec![None; replies.len()];
+    for (position, answer) in answers.into_iter().enumerate() {
+        let index = answer.index.unwrap_or(position);
+        let slot = results.get_mut(index).filter(|slot| slot.is_none())?;
+        *slot = Some(model_classification(
+            match_intent(&answer.intent, categories),
+            answer.sentiment,
+            answer.urgent,
+            &replies[index],
+        ));
+    }
+
+    results.into_iter().collect()
+}
+
+/// Rough sentiment for the rule-based classifier: a baseline per intent, nudged
+/// by positive and negative wording.
+pub fn heuristic_sentiment(reply_text: &str, intent: &str) -> f64 {
+    let base = match intent {
+        "interested" => 0.6,
+        "maybe_later" => 0.1,
+        "negative" => -0.7,
+        _ => 0.0,
+    };
+
+    let text = reply_text.to_lowercase();
+    let count = |words: &[&str]| words.iter().filter(|w| text.contains(*w)).count() as f64;
+    (base + 0.1 * count(POSITIVE_WORDS) - 0.1 * count(NEGATIVE_WORDS)).clamp(-1.0, 1.0)
+}
+
+/// Rule-based urgency: asks for a call soon or mentions a tight timeline
+pub fn detect_urgency(reply_text: &str) -> bool {
+    let text = reply_text.to_lowercase();
+    URGENCY_PATTERNS.iter().any(|p| text.contains(p))
+}
+
+/// Maps raw model output onto an active category label.
+fn match_intent(output: &str, categories: &[ReplyCategory]) -> String {
+    let output = output.trim().to_lowercase();
+    if categories.iter().any(|c| c.label == output) {
+        return output;
+    }
 
-Return ONLY the category name (one word, lowercase). Nothing else."#;
+    // Try to extract a valid intent from the response, preferring the longest
+    // label so "not_negative" isn't read as "negative"
+    let mut labels: Vec<&str> = categories.iter().map(|c| c.label.as_str()).collect();
+    labels.sort_by_key(|l| std::cmp::Reverse(l.len()));
+    labels
+        .into_iter()
+        .find(|l| output.contains(l))
+        .unwrap_or(FALLBACK_INTENT)
+        .to_string()
+}
 
-pub async fn classify_reply(reply_text: &str) -> Result<(String, f32), String> {
-    let api_key = env::var("ANTHROPIC_API_KEY")
-        .or_else(|_| env::var("CLAUDE_API_KEY"))
-        .map_err(|_| "ANTHROPIC_API_KEY or CLAUDE_API_KEY not set")?;
+fn api_key() -> Option<String> {
+    env::var("ANTHROPIC_API_KEY").or_else(|_| env::var("CLAUDE_API_KEY")).ok()
+}
+
+pub async fn classify_reply(reply_text: &str) -> Result<ReplyClassification, String> {
+    classify_reply_with_categories(reply_text, &default_categories()).await
+}
+
+/// Classifies a reply into one of `categories` (normally a workspace's active set),
+/// scoring sentiment and urgency in the same call.
+pub async fn classify_reply_with_categories(
+    reply_text: &str,
+    categories: &[ReplyCategory],
+) -> Result<ReplyClassification, String> {
+    let output = call_claude(build_prompt(categories, reply_text), 60).await?;
+
+    // Validate the classification against the active set
+    Ok(parse_classification(&output, categories, reply_text))
+}
+
+pub async fn classify_replies_batch(replies: &[String]) -> Result<Vec<ReplyClassification>, String> {
+    classify_replies_batch_with_categories(replies, &default_categories()).await
+}
+
+/// Classifies several replies in one Claude request, returning one result per
+/// reply in the same order. Errors when the answer can't be matched back to
+/// every reply.
+pub async fn classify_replies_batch_with_categories(
+    replies: &[String],
+    categories: &[ReplyCategory],
+) -> Result<Vec<ReplyClassification>, String> {
+    if replies.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let max_tokens = 40 + 60 * replies.len() as u32;
+    let output = call_claude(build_batch_prompt(categories, replies), max_tokens).await?;
+
+    parse_batch_classification(&output, categories, replies)
+        .ok_or_else(|| format!("Couldn't match Claude's batch answer to {} replies", replies.len()))
+}
+
+/// Sends one prompt to Claude and returns the text of its answer
+async fn call_claude(prompt: String, max_tokens: u32) -> Result<String, String> {
+    let api_key = api_key().ok_or("ANTHROPIC_API_KEY or CLAUDE_API_KEY not set")?;
 
     let client = Client::new();
-    
-    let prompt = CLASSIFICATION_PROMPT.replace("{reply_text}", reply_text);
-    
+
     let request = ClaudeRequest {
         model: "claude-3-haiku-20240307".to_string(),  // Fast and cheap for classification
-        max_tokens: 20,
+        max_tokens,
         messages: vec![ClaudeMessage {
             role: "user".to_string(),
             content: prompt,
@@ -76,29 +450,12 @@ pub async fn classify_reply(reply_text: &str) -> Result<(String, f32), String> {
         .await
         .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
 
-    let classification = claude_response
+    Ok(claude_response
         .content
-        .first()
-        .map(|c| c.text.trim().to_lowercase())
-        .unwrap_or_else(|| "auto_reply".to_string());
-
-    // Validate the classification
-    let valid_intents = ["interested", "maybe_later", "objection", "negative", "auto_reply"];
-    let intent = if valid_intents.contains(&classification.as_str()) {
-        classification
-    } else {
-        // Try to extract a valid intent from the response
-        valid_intents
-            .iter()
-            .find(|&&i| classification.contains(i))
-            .map(|&s| s.to_string())
-            .unwrap_or_else(|| "auto_reply".to_string())
-    };
-
-    // Confidence is high for Claude classifications
-    let confidence = 0.85_f32;
-
-    Ok((intent, confidence))
+        .into_iter()
+        .next()
+        .map(|c| c.text)
+        .unwrap_or_else(|| FALLBACK_INTENT.to_string()))
 }
 
 pub fn classify_reply_simple(reply_text: &str) -> (String, f32) {
@@ -136,7 +493,7 @@ pub fn classify_reply_simple(reply_text: &str) -> (String, f32) {
         "let's chat", "let's talk", "schedule a call", "book a meeting",
         "send me your calendar", "interested", "tell me more", "sounds good",
         "yes", "love to learn more", "set up a time", "when are you free",
-        "happy to connect", "let's do it"
+        "happy to connect", "let's do it", "sounds great"
     ];
     
     for pattern in interested_patterns {
@@ -175,15 +532,306 @@ pub fn classify_reply_simple(reply_text: &str) -> (String, f32) {
     ("auto_reply".to_string(), 0.50)
 }
 
-pub async fn classify_reply_with_fallback(reply_text: &str) -> (String, f32) {
-    // Try Claude first
-    match classify_reply(reply_text).await {
-        Ok(result) => result,
+/// Rule-based intent with heuristic sentiment and urgency
+pub fn classify_reply_heuristic(reply_text: &str) -> ReplyClassification {
+    let (intent, confidence) = classify_reply_simple(reply_text);
+    let sentiment = heuristic_sentiment(reply_text, &intent);
+    ReplyClassification { intent, confidence, sentiment, urgent: detect_urgency(reply_text) }
+}
+
+/// Rule-based stand-in for Claude, with confidence capped below the model's
+pub fn classify_reply_fallback(reply_text: &str) -> ReplyClassification {
+    let classification = classify_reply_heuristic(reply_text);
+    ReplyClassification {
+        confidence: classification.confidence.min(RULE_FALLBACK_MAX_CONFIDENCE),
+        ..classification
+    }
+}
+
+/// Classifies with Claude when an API key is set, and with the rules when it
+/// isn't or the call fails, so replies still get labelled in self-hosted and
+/// dev setups
+pub async fn classify_reply_or_fallback(reply_text: &str, categories: &[ReplyCategory]) -> ReplyClassification {
+    if api_key().is_none() {
+        tracing::info!("No Claude API key set; classifying reply with rules");
+        return classify_reply_fallback(reply_text);
+    }
+
+    match classify_reply_with_categories(reply_text, categories).await {
+        Ok(result) => {
+            tracing::debug!("Classified reply with Claude as {}", result.intent);
+            result
+        }
         Err(e) => {
-            tracing::warn!("Claude classification failed, using fallback: {}", e);
-            classify_reply_simple(reply_text)
+            tracing::warn!("Claude classification failed, classifying reply with rules: {}", e);
+            classify_reply_fallback(reply_text)
+        }
+    }
+}
+
+/// Batch version of [`classify_reply_or_fallback`]. A batch answer that can't
+/// be matched back to its replies is retried one reply at a time.
+pub async fn classify_replies_or_fallback(replies: &[String], categories: &[ReplyCategory]) -> Vec<ReplyClassification> {
+    if api_key().is_none() {
+        tracing::info!("No Claude API key set; classifying {} replies with rules", replies.len());
+        return replies.iter().map(|text| classify_reply_fallback(text)).collect();
+    }
+
+    match classify_replies_batch_with_categories(replies, categories).await {
+        Ok(results) => {
+            tracing::debug!("Classified {} replies with one Claude request", results.len());
+            results
+        }
+        Err(e) => {
+            tracing::warn!("Batch classification failed, classifying replies one at a time: {}", e);
+            let mut results = Vec::with_capacity(replies.len());
+            for text in replies {
+                results.push(classify_reply_or_fallback(text, categories).await);
+            }
+            results
+        }
+    }
+}
+
+pub async fn classify_reply_with_fallback(reply_text: &str) -> ReplyClassification {
+    classify_reply_or_fallback(reply_text, &default_categories()).await
+}
+
+/// Cheap pre-filter for out-of-office notices and bounces, which get the
+/// auto_reply label without a model call
+pub fn looks_automated(reply_text: &str) -> bool {
+    let (intent, confidence) = classify_reply_simple(reply_text);
+    intent == "auto_reply" && confidence >= 0.95
+}
+
+/// Out-of-office notices, auto-responders and delivery status notifications,
+/// judged by their headers (when the caller still has them), subject and body.
+/// `headers` are (name, value) pairs; names are matched ignoring case.
+pub fn is_auto_reply(headers: &[(&str, &str)], subject: &str, body: &str) -> bool {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.trim().to_lowercase())
+    };
+
+    let auto_submitted = header("Auto-Submitted").is_some_and(|v| v != "no");
+    let precedence = header("Precedence").is_some_and(|v| matches!(v.as_str(), "bulk" | "junk" | "list" | "auto_reply"));
+    let vendor_header = AUTO_REPLY_HEADERS.iter().any(|h| header(h).is_some());
+    let delivery_status = header("Content-Type")
+        .is_some_and(|v| v.starts_with("multipart/report") || v.starts_with("message/delivery-status"));
+    let subject = subject.trim().to_lowercase();
+    let auto_subject = AUTO_REPLY_SUBJECTS.iter().any(|s| subject.starts_with(s));
+
+    auto_submitted || precedence || vendor_header || delivery_status || auto_subject || looks_automated(body)
+}
+
+/// The label for a message [`is_auto_reply`] caught
+pub fn auto_reply_classification() -> ReplyClassification {
+    ReplyClassification {
+        intent: "auto_reply".to_string(),
+        confidence: 1.0,
+        sentiment: 0.0,
+        urgent: false,
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct UnclassifiedReply {
+    id: Uuid,
+    workspace_id: Uuid,
+    subject: Option<String>,
+    body_text: Option<String>,
+}
+
+/// Queues `ClassifyReply` jobs for recent replies without a model
+/// classification, up to [`CLASSIFY_BATCH_SIZE`] replies from one workspace per
+/// job. Heuristic labels (as stored by the IMAP poller) still count as
+/// unclassified. Automated replies are labelled on the spot instead. Returns
+/// how many replies were queued.
+pub async fn enqueue_unclassified(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let replies = sqlx::query_as::<_, UnclassifiedReply>(
+        r#"
+        SELECT id, workspace_id, subject, body_text FROM email_replies
+        WHERE (classified_at IS NULL OR intent IS NULL OR intent = '')
+          AND classification_queued_at IS NULL
+          AND anonymized_at IS NULL
+          AND COALESCE(received_at, created_at) > NOW() - make_interval(days => $1)
+        ORDER BY created_at ASC
+        LIMIT $2
+        "#
+    )
+    .bind(CLASSIFY_LOOKBACK_DAYS)
+    .bind(CLASSIFY_SWEEP_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    // Oldest first within each workspace, so a batch keeps the sweep's order
+    let mut by_workspace: Vec<(Uuid, Vec<Uuid>)> = Vec::new();
+    for reply in replies {
+        let text = reply.body_text.unwrap_or_default();
+        if is_auto_reply(&[], reply.subject.as_deref().unwrap_or(""), &text) {
+            store_classification(pool, reply.id, &auto_reply_classification()).await?;
+            continue;
+        }
+        if text.trim().is_empty() {
+            store_classification(pool, reply.id, &classify_reply_heuristic(&text)).await?;
+            continue;
+        }
+
+        match by_workspace.iter_mut().find(|(workspace_id, _)| *workspace_id == reply.workspace_id) {
+            Some((_, ids)) => ids.push(reply.id),
+            None => by_workspace.push((reply.workspace_id, vec![reply.id])),
+        }
+    }
+
+    let mut queued = 0;
+    for (workspace_id, ids) in by_workspace {
+        for batch in ids.chunks(CLASSIFY_BATCH_SIZE) {
+            let mut tx = pool.begin().await?;
+
+            let claimed: Vec<Uuid> = sqlx::query_scalar(
+                "UPDATE email_replies SET classification_queued_at = NOW() WHERE id = ANY($1) AND classification_queued_at IS NULL RETURNING id"
+            )
+            .bind(batch)
+            .fetch_all(&mut *tx)
+            .await?;
+            if claimed.is_empty() {
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries)
+                VALUES (gen_random_uuid(), $1, '"ClassifyReply"', $2, 'pending', NOW(), 0, 3)
+                "#
+            )
+            .bind(workspace_id)
+            .bind(serde_json::json!({ "reply_ids": claimed }))
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+            queued += claimed.len() as u64;
         }
     }
+
+    Ok(queued)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReplyToClassify {
+    id: Uuid,
+    workspace_id: Uuid,
+    from_email: String,
+    subject: Option<String>,
+    body_text: Option<String>,
+}
+
+/// Worker entry point for a `ClassifyReply` job. Classifies the replies that
+/// are still unclassified against their workspace's categories, one Claude
+/// request per workspace, and stores the results. A negative reply puts the
+/// sender on the workspace suppression list; an interested one ends the lead's
+/// follow-up sequence and fires the `reply.interested` Zapier hooks. Replies
+/// that are gone or were classified in the meantime are left out of the result.
+pub async fn classify_and_store_batch(pool: &PgPool, reply_ids: &[Uuid]) -> Result<Vec<(Uuid, ReplyClassification)>, String> {
+    let replies = sqlx::query_as::<_, ReplyToClassify>(
+        r#"
+        SELECT id, workspace_id, from_email, subject, body_text FROM email_replies
+        WHERE id = ANY($1) AND classified_at IS NULL
+        ORDER BY workspace_id, created_at
+        "#
+    )
+    .bind(reply_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {}", e))?;
+
+    let mut results = Vec::with_capacity(replies.len());
+    for workspace in replies.chunk_by(|a, b| a.workspace_id == b.workspace_id) {
+        let texts: Vec<String> = workspace.iter().map(|r| r.body_text.clone().unwrap_or_default()).collect();
+        let automated: Vec<bool> = workspace
+            .iter()
+            .zip(&texts)
+            .map(|(reply, text)| is_auto_reply(&[], reply.subject.as_deref().unwrap_or(""), text))
+            .collect();
+        let to_model: Vec<usize> = (0..texts.len()).filter(|&i| !automated[i]).collect();
+
+        // Everything not caught as automated is overwritten by the model below
+        let mut classifications = vec![auto_reply_classification(); texts.len()];
+        if !to_model.is_empty() {
+            let config = load_workspace_config(pool, workspace[0].workspace_id)
+                .await
+                .map_err(|e| format!("DB error: {}", e))?;
+            let model_texts: Vec<String> = to_model.iter().map(|&i| texts[i].clone()).collect();
+            let _permit = CLASSIFY_PERMITS.acquire().await.map_err(|e| e.to_string())?;
+            let model_results = classify_replies_or_fallback(&model_texts, &config.categories()).await;
+            for (i, classification) in to_model.into_iter().zip(model_results) {
+                classifications[i] = classification;
+            }
+        }
+
+        for (reply, classification) in workspace.iter().zip(classifications) {
+            apply_classification(pool, reply, &classification).await?;
+            results.push((reply.id, classification));
+        }
+    }
+
+    Ok(results)
+}
+
+/// Stores a classification and acts on it
+async fn apply_classification(pool: &PgPool, reply: &ReplyToClassify, classification: &ReplyClassification) -> Result<(), String> {
+    store_classification(pool, reply.id, classification)
+        .await
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    match classification.intent.as_str() {
+        "negative" => {
+            sqlx::query(
+                r#"
+                INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at)
+                VALUES (gen_random_uuid(), $1, LOWER($2), 'negative_reply', 'reply_classifier', NOW())
+                ON CONFLICT (workspace_id, email) DO NOTHING
+                "#
+            )
+            .bind(reply.workspace_id)
+            .bind(&reply.from_email)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("DB error: {}", e))?;
+        }
+        "interested" => {
+            campaign_scheduler::stop_sequence_for_reply(pool, reply.id, "interested")
+                .await
+                .map_err(|e| format!("DB error: {}", e))?;
+            if let Err(e) = zapier::emit_reply_interested(pool, reply.workspace_id, reply.id).await {
+                tracing::warn!("Failed to queue Zapier hooks for reply {}: {}", reply.id, e);
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn store_classification(pool: &PgPool, reply_id: Uuid, classification: &ReplyClassification) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_replies
+        SET intent = $2, intent_confidence = $3, sentiment = $4, is_urgent = $5, classified_at = NOW()
+        WHERE id = $1
+        "#
+    )
+    .bind(reply_id)
+    .bind(&classification.intent)
+    .bind(classification.confidence)
+    .bind(classification.sentiment)
+    .bind(classification.urgent)
+    .execute(pool)
+    .await?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -197,5 +845,136 @@ mod tests {
         assert_eq!(classify_reply_simple("Let's schedule a call!").0, "interested");
         assert_eq!(classify_reply_simple("Not a good time, check back in Q2").0, "maybe_later");
         assert_eq!(classify_reply_simple("How much does this cost?").0, "objection");
+
+        assert!(looks_automated("I am currently out of the office until Monday"));
+        assert!(!looks_automated("Thanks, can you send over pricing?"));
+    }
+
+    #[test]
+    fn test_custom_categories_in_prompt_and_validation() {
+        let mut categories = default_categories();
+        let referral = ReplyCategory {
+            label: "referral".to_string(),
+            description: "Points us to someone else at the company".to_string(),
+        };
+        assert!(validate_custom_categories(std::slice::from_ref(&referral)).is_ok());
+        categories.push(referral);
+
+        let prompt = build_prompt(&categories, "Talk to Dana instead");
+        assert!(prompt.contains("- interested: Shows clear interest"));
+        assert!(prompt.contains("- referral: Points us to someone else"));
+        assert!(prompt.ends_with("Nothing else."));
+
+        assert_eq!(match_intent(" Referral\n", &categories), "referral");
+        assert_eq!(match_intent("referral", &default_categories()), "auto_reply");
+
+        let bad = |label: &str| ReplyCategory { label: label.to_string(), description: "x".to_string() };
+        assert!(validate_custom_categories(&[bad("Wrong Person")]).is_err());
+        assert!(validate_custom_categories(&[bad("negative")]).is_err());
+        assert!(validate_custom_categories(&[bad("wrong_person"), bad("wrong_person")]).is_err());
+
+        assert!(validate_priority(&default_priority(), &default_categories()).is_ok());
+        assert!(validate_priority(&["referral".to_string()], &default_categories()).is_err());
+    }
+
+    #[test]
+    fn test_sentiment_and_urgency() {
+        let categories = default_categories();
+        let reply = "Sounds great, can we talk this week?";
+
+        let parsed = parse_classification(r#"{"intent": "interested", "sentiment": 0.9, "urgent": true}"#, &categories, reply);
+        assert_eq!((parsed.intent.as_str(), parsed.sentiment, parsed.urgent), ("interested", 0.9, true));
+
+        // Out-of-range sentiment is clamped; a bare label falls back to the heuristics
+        let parsed = parse_classification(r#"Sure: {"intent": "negative", "sentiment": -3}"#, &categories, "Stop emailing me");
+        assert_eq!((parsed.intent.as_str(), parsed.sentiment, parsed.urgent), ("negative", -1.0, false));
+        let parsed = parse_classification("interested", &categories, reply);
+        assert!(parsed.urgent && parsed.sentiment > 0.6);
+
+        let heuristic = classify_reply_heuristic("Not a good time, check back next year");
+        assert_eq!(heuristic.intent, "maybe_later");
+        assert!(!heuristic.urgent);
+        assert!(classify_reply_heuristic("Please unsubscribe me, this is spam").sentiment < -0.5);
+    }
+
+    #[test]
+    fn test_out_of_office_and_delivery_notices_skip_the_model() {
+        // Outlook's automatic reply
+        let outlook = [("Auto-Submitted", "auto-generated"), ("X-Auto-Response-Suppress", "All")];
+        assert!(is_auto_reply(&outlook, "Automatic reply: Quick question about hiring", "Thank you for your email."));
+        // Gmail's vacation responder only sets the subject prefix on some accounts
+        assert!(is_auto_reply(&[], "Out of Office: Re: Quick question", "I'll reply when I'm back on the 12th."));
+        assert!(is_auto_reply(
+            &[("precedence", " Bulk ")],
+            "Re: Quick question",
+            "We received your message and will get back to you within two business days."
+        ));
+        // Postfix bounce, as a DSN and as stored with only its subject and body
+        let dsn = [("Content-Type", "multipart/report; report-type=delivery-status; boundary=\"B1\"")];
+        assert!(is_auto_reply(&dsn, "Undelivered Mail Returned to Sender", ""));
+        assert!(is_auto_reply(&[], "Undeliverable: Quick question", "Your message couldn't be delivered."));
+        assert!(is_auto_reply(&[], "Mail delivery failed: returning message to sender", ""));
+        // No headers or subject left, but the body says it
+        assert!(is_auto_reply(&[], "Re: Quick question", "I am currently out of the office with limited access to email."));
+
+        // A person replying, even one who mentions a vacation
+        assert!(!is_auto_reply(&[("Auto-Submitted", "no")], "Re: Quick question", "Happy to chat, does Tuesday work?"));
+        assert!(!is_auto_reply(&[], "Re: Out of office plans?", "Let's talk after the holidays."));
+        assert_eq!(auto_reply_classification().intent, "auto_reply");
+    }
+
+    #[test]
+    fn test_batch_answers_map_back_to_their_replies() {
+        let categories = default_categories();
+        let replies: Vec<String> = [
+            "How much does it cost?",
+            "Sounds great, can we talk this week?",
+            "Please remove me from your list",
+            "Not now, check back next quarter",
+        ]
+        .iter()
+        .map(|r| r.to_string())
+        .collect();
+
+        let prompt = build_batch_prompt(&categories, &replies);
+        assert!(prompt.contains("<reply index=\"3\">\nNot now, check back next quarter\n</reply>"));
+
+        // Out of order, and the last answer leaves sentiment and urgency to the heuristics
+        let output = r#"Here you go:
+            [{"index": 2, "intent": "negative", "sentiment": -0.8, "urgent": false},
+             {"index": 0, "intent": "Objection", "sentiment": 0.0, "urgent": false},
+             {"index": 1, "intent": "interested", "sentiment": 0.9, "urgent": true},
+             {"index": 3, "intent": "maybe_later"}]"#;
+        let parsed = parse_batch_classification(output, &categories, &replies).unwrap();
+        let intents: Vec<&str> = parsed.iter().map(|c| c.intent.as_str()).collect();
+        assert_eq!(intents, vec!["objection", "interested", "negative", "maybe_later"]);
+        assert!(parsed[1].urgent && parsed[1].sentiment == 0.9);
+        assert_eq!(parsed[3].sentiment, heuristic_sentiment(&replies[3], "maybe_later"));
+        assert!(parsed.iter().all(|c| c.confidence == MODEL_CONFIDENCE));
+
+        // A missing, repeated or out-of-range answer sends the batch back to one-at-a-time
+        let short = r#"[{"index": 0, "intent": "objection"}]"#;
+        let repeated = r#"[{"index": 0, "intent": "objection"}, {"index": 0, "intent": "negative"},
+                          {"index": 2, "intent": "negative"}, {"index": 3, "intent": "maybe_later"}]"#;
+        let out_of_range = r#"[{"index": 0, "intent": "objection"}, {"index": 1, "intent": "interested"},
+                              {"index": 2, "intent": "negative"}, {"index": 9, "intent": "maybe_later"}]"#;
+        for output in [short, repeated, out_of_range, "objection"] {
+            assert!(parse_batch_classification(output, &categories, &replies).is_none());
+        }
+    }
+
+    #[test]
+    fn test_fallback_labels_common_replies_with_lower_confidence() {
+        let unsubscribe = classify_reply_fallback("Please unsubscribe me from this list.");
+        let interested = classify_reply_fallback("Sounds great, send over some times next week.");
+        let ooo = classify_reply_fallback("I am out of office until the 14th with limited access to email.");
+
+        assert_eq!(unsubscribe.intent, "negative");
+        assert_eq!(interested.intent, "interested");
+        assert_eq!(ooo.intent, "auto_reply");
+        for classification in [&unsubscribe, &interested, &ooo] {
+            assert!(classification.confidence <= RULE_FALLBACK_MAX_CONFIDENCE);
+            assert!(classification.confidence < MODEL_CONFIDENCE);
+        }
     }
 }
@@ -0,0 +1,442 @@
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use tracing::info;
+
+use crate::models::signal::{ConfidenceFactors, CreateSignal, Signal, SignalSource, SignalType};
+
+/// Posts older than this are ignored
+const LOOKBACK_DAYS: i64 = 14;
+
+/// Keywords that mark a post as a product launch
+const LAUNCH_KEYWORDS: &[&str] = &[
+    "launch",
+    "now available",
+    "introducing",
+    "announcing",
+    "generally available",
+    "released",
+];
+
+/// Keywords that mark a post as company growth
+const EXPANSION_KEYWORDS: &[&str] = &[
+    "funding",
+    "raised",
+    "series a",
+    "series b",
+    "series c",
+    "expansion",
+    "expands",
+    "new office",
+    "acquires",
+    "acquisition",
+];
+
+// ============================================================================
+// Feed Types
+// ============================================================================
+
+/// One entry from an RSS 2.0 `<item>` or Atom `<entry>`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FeedPost {
+    pub title: String,
+    pub link: Option<String>,
+    pub summary: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl FeedPost {
+    /// Keywords from `keywords` found in the title or summary, case-insensitively
+    fn keyword_hits(&self, keywords: &[&'static str]) -> Vec<&'static str> {
+        let text = format!(
+            "{} {}",
+            self.title,
+            self.summary.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+
+        keywords.iter().copied().filter(|k| text.contains(k)).collect()
+    }
+
+    /// Launch or expansion, whichever has more keyword hits; `None` for ordinary posts
+    pub fn classify(&self) -> Option<(SignalType, Vec<&'static str>)> {
+        let launch = self.keyword_hits(LAUNCH_KEYWORDS);
+        let expansion = self.keyword_hits(EXPANSION_KEYWORDS);
+
+        if launch.is_empty() && expansion.is_empty() {
+            None
+        } else if expansion.len() > launch.len() {
+            Some((SignalType::Expansion, expansion))
+        } else {
+            Some((SignalType::ProductLaunch, launch))
+        }
+    }
+}
+
+// ============================================================================
+// Feed Parsing (RSS 2.0 and Atom)
+// ============================================================================
+
+/// Parse an RSS 2.0 or Atom document into its posts. Entries without a title are skipped.
+pub fn parse_feed(xml: &str) -> Vec<FeedPost> {
+    let is_atom = find_element(xml, "feed", 0).is_some();
+    let entry_tag = if is_atom { "entry" } else { "item" };
+
+    elements(xml, entry_tag)
+        .into_iter()
+        .filter_map(|entry| {
+            let title = child_text(entry, "title")?;
+            if title.is_empty() {
+                return None;
+            }
+
+            let (link, summary, published_at) = if is_atom {
+                (
+                    atom_link(entry),
+                    child_text(entry, "summary").or_else(|| child_text(entry, "content")),
+                    child_text(entry, "published")
+                        .or_else(|| child_text(entry, "updated"))
+                        .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+                        .map(|d| d.with_timezone(&Utc)),
+                )
+            } else {
+                (
+                    child_text(entry, "link"),
+                    child_text(entry, "description"),
+                    child_text(entry, "pubDate")
+                        .and_then(|d| DateTime::parse_from_rfc2822(&d).ok())
+                        .map(|d| d.with_timezone(&Utc)),
+                )
+            };
+
+            Some(FeedPost {
+                title,
+                link: link.filter(|l| !l.is_empty()),
+                summary: summary.filter(|s| !s.is_empty()),
+                published_at,
+            })
+        })
+        .collect()
+}
+
+/// Byte range of the next `<tag ...>...</tag>` (or self-closing `<tag .../>`) at or after
+/// `from`, as (start of open tag, end of open tag, end of element)
+fn find_element(xml: &str, tag: &str, from: usize) -> Option<(usize, usize, usize)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut pos = from;
+
+    loop {
+        let start = pos + xml[pos..].find(&open)?;
+        let after = start + open.len();
+        // `<link` must not match `<linkedin>`
+        match xml[after..].chars().next() {
+            Some(c) if c == '>' || c == '/' || c.is_whitespace() => {}
+            _ => {
+                pos = after;
+                continue;
+            }
+        }
+
+        let open_end = after + xml[after..].find('>')? + 1;
+        if xml[..open_end].ends_with("/>") {
+            return Some((start, open_end, open_end));
+        }
+        let end = open_end + xml[open_end..].find(&close)? + close.len();
+        return Some((start, open_end, end));
+    }
+}
+
+/// Every top-level `tag` element in `xml`
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some((start, _, end)) = find_element(xml, tag, pos) {
+        found.push(&xml[start..end]);
+        pos = end;
+    }
+    found
+}
+
+/// Decoded text content of the first `tag` child, with CDATA unwrapped and markup removed
+fn child_text(xml: &str, tag: &str) -> Option<String> {
+    let (_, open_end, end) = find_element(xml, tag, 0)?;
+    let inner = xml.get(open_end..end.saturating_sub(tag.len() + 3))?;
+    Some(decode_text(inner))
+}
+
+/// Atom `<link href="..."/>`, preferring the `alternate` (post page) link
+fn atom_link(entry: &str) -> Option<String> {
+    let links: Vec<&str> = elements(entry, "link")
+        .into_iter()
+        .map(|l| &l[..l.find('>').map_or(l.len(), |i| i + 1)])
+        .collect();
+
+    links
+        .iter()
+        .find(|l| attribute(l, "rel").is_none_or(|rel| rel == "alternate"))
+        .or_else(|| links.first())
+        .and_then(|l| attribute(l, "href"))
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    for quote in ['"', '\''] {
+        let needle = format!(" {}={}", name, quote);
+        if let Some(start) = tag.find(&needle) {
+            let value_start = start + needle.len();
+            let value_end = value_start + tag[value_start..].find(quote)?;
+            return Some(decode_entities(&tag[value_start..value_end]));
+        }
+    }
+    None
+}
+
+fn decode_text(raw: &str) -> String {
+    let raw = raw.trim();
+    let text = match raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => decode_entities(raw),
+    };
+    strip_tags(&text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Summaries are often escaped HTML; drop the markup and keep the words
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// ============================================================================
+// RSS Connector
+// ============================================================================
+
+pub struct RssConnector {
+    client: Client,
+}
+
+impl RssConnector {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent("OutreachIQ/1.0")
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        }
+    }
+
+    /// Fetch a feed and return its posts from the last `LOOKBACK_DAYS` days
+    pub async fn fetch_recent_posts(
+        &self,
+        feed_url: &str,
+    ) -> Result<Vec<FeedPost>, Box<dyn std::error::Error + Send + Sync>> {
+        let response = self.client.get(feed_url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Feed request failed with status {}", response.status()).into());
+        }
+
+        let body = response.text().await?;
+        let cutoff = Utc::now() - chrono::Duration::days(LOOKBACK_DAYS);
+
+        // Undated posts can't be placed in the window, so they're skipped
+        Ok(parse_feed(&body)
+            .into_iter()
+            .filter(|p| p.published_at.is_some_and(|d| d >= cutoff))
+            .collect())
+    }
+
+    /// Calculate confidence score for a post (rule-based, NOT AI)
+    pub fn calculate_confidence(&self, post: &FeedPost, keywords: &[&str]) -> ConfidenceFactors {
+        let mut factors = ConfidenceFactors::new();
+
+        // Factor 1: Recency
+        if let Some(published) = post.published_at {
+            let days_ago = (Utc::now() - published).num_days();
+            if days_ago <= 3 {
+                factors.add_factor("very_recent_post", 0.15, "Posted in last 3 days");
+            } else if days_ago <= 7 {
+                factors.add_factor("recent_post", 0.10, "Posted in last 7 days");
+            } else {
+                factors.add_factor("older_post", 0.03, "Posted in last 14 days");
+            }
+        }
+
+        // Factor 2: Keyword hits
+        if keywords.len() >= 2 {
+            factors.add_factor("multiple_keywords", 0.15, "2+ signal keywords in post");
+        } else if !keywords.is_empty() {
+            factors.add_factor("keyword_match", 0.08, "Signal keyword in post");
+        }
+
+        // Factor 3: Announced in the headline rather than buried in the body
+        let title = post.title.to_lowercase();
+        if keywords.iter().any(|k| title.contains(k)) {
+            factors.add_factor("keyword_in_title", 0.10, "Signal keyword in post title");
+        }
+
+        factors
+    }
+
+    /// Create launch/expansion signals for posts that match the keyword rules
+    pub async fn create_signals(
+        &self,
+        pool: &sqlx::PgPool,
+        company_id: uuid::Uuid,
+        posts: &[FeedPost],
+    ) -> Result<Vec<Signal>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut signals = Vec::new();
+
+        for post in posts {
+            let Some((signal_type, keywords)) = post.classify() else {
+                continue;
+            };
+
+            if Signal::exists_duplicate(
+                pool,
+                company_id,
+                signal_type.as_str(),
+                SignalSource::RssFeed.as_str(),
+                &post.title,
+            )
+            .await?
+            {
+                info!("Duplicate signal detected, skipping: {}", post.title);
+                continue;
+            }
+
+            if let Some(ref link) = post.link {
+                if Signal::exists_for_source_url(pool, company_id, SignalSource::RssFeed.as_str(), link).await? {
+                    info!("Signal already exists for post, skipping: {}", link);
+                    continue;
+                }
+            }
+
+            let confidence = self.calculate_confidence(post, &keywords);
+
+            let create_signal = CreateSignal {
+                company_id,
+                signal_type,
+                source: SignalSource::RssFeed,
+                title: post.title.clone(),
+                description: post.summary.as_ref().map(|s| s.chars().take(500).collect()),
+                source_url: post.link.clone(),
+                raw_data: serde_json::json!({
+                    "post": post,
+                    "keywords": keywords,
+                }),
+                confidence_score: confidence.final_score,
+                confidence_factors: confidence.to_json(),
+                signal_date: post.published_at.map(|d| d.date_naive()),
+                expires_at: Some(Utc::now() + chrono::Duration::days(30)),
+            };
+
+            signals.push(Signal::create(pool, create_signal).await?);
+        }
+
+        info!("Created {} feed signals for company {}", signals.len(), company_id);
+        Ok(signals)
+    }
+}
+
+impl Default for RssConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss_feed() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Acme Blog</title>
+    <link>https://acme.dev/blog</link>
+    <item>
+      <title>Introducing Acme Cloud &amp; CLI</title>
+      <link>https://acme.dev/blog/acme-cloud</link>
+      <description><![CDATA[<p>Acme Cloud is <b>now available</b> to everyone.</p>]]></description>
+      <pubDate>Tue, 02 Jan 2024 15:04:05 +0000</pubDate>
+    </item>
+    <item>
+      <title>Team offsite recap</title>
+      <link>https://acme.dev/blog/offsite</link>
+    </item>
+  </channel>
+</rss>"#;
+
+        let posts = parse_feed(xml);
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].title, "Introducing Acme Cloud & CLI");
+        assert_eq!(posts[0].link.as_deref(), Some("https://acme.dev/blog/acme-cloud"));
+        assert_eq!(posts[0].summary.as_deref(), Some("Acme Cloud is now available to everyone."));
+        assert_eq!(
+            posts[0].published_at,
+            Some(DateTime::parse_from_rfc3339("2024-01-02T15:04:05Z").unwrap().with_timezone(&Utc))
+        );
+        assert_eq!(posts[1].published_at, None);
+
+        assert_eq!(posts[0].classify().map(|(t, _)| t), Some(SignalType::ProductLaunch));
+        assert_eq!(posts[1].classify(), None);
+    }
+
+    #[test]
+    fn test_parse_atom_feed() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Acme News</title>
+  <link href="https://acme.dev/"/>
+  <updated>2024-01-05T09:00:00Z</updated>
+  <entry>
+    <title type="html">Acme raised a $20M Series A to fund expansion</title>
+    <link rel="self" href="https://acme.dev/feed/123"/>
+    <link rel="alternate" href="https://acme.dev/news/series-a"/>
+    <id>urn:uuid:123</id>
+    <updated>2024-01-05T09:00:00Z</updated>
+    <published>2024-01-04T08:30:00+01:00</published>
+    <summary>New funding will open our London office.</summary>
+  </entry>
+</feed>"#;
+
+        let posts = parse_feed(xml);
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].title, "Acme raised a $20M Series A to fund expansion");
+        assert_eq!(posts[0].link.as_deref(), Some("https://acme.dev/news/series-a"));
+        assert_eq!(
+            posts[0].published_at,
+            Some(DateTime::parse_from_rfc3339("2024-01-04T07:30:00Z").unwrap().with_timezone(&Utc))
+        );
+
+        let (signal_type, keywords) = posts[0].classify().unwrap();
+        assert_eq!(signal_type, SignalType::Expansion);
+        assert!(keywords.contains(&"funding"));
+    }
+}
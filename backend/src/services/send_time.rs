@@ -0,0 +1,122 @@
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use std::cmp::Ordering;
+
+/// Local hour we aim to land in a lead's inbox when nothing better is known.
+pub const DEFAULT_LOCAL_SEND_HOUR: i16 = 10;
+
+/// How many hours either side of the preferred hour still count as a good slot.
+pub const SEND_HOUR_TOLERANCE: i32 = 1;
+
+/// A lead is never held back for its preferred hour longer than this.
+pub const MAX_DEFERRAL_HOURS: i32 = 24;
+
+/// Local hours `[start, end)` a recipient with a known timezone can be emailed in,
+/// when the campaign sets `recipient_timezone_field`.
+pub const RECIPIENT_BUSINESS_HOURS: (i32, i32) = (9, 17);
+
+/// Whether an inbox may send at `now`. The window is in the inbox's local time,
+/// UTC when it has no offset. An end before the start wraps past midnight, and
+/// equal start and end leave the window open all day.
+pub fn in_send_window(start: NaiveTime, end: NaiveTime, offset_minutes: Option<i32>, now: DateTime<Utc>) -> bool {
+    let local = (now + Duration::minutes(offset_minutes.unwrap_or(0) as i64)).time();
+    match start.cmp(&end) {
+        Ordering::Equal => true,
+        Ordering::Less => start <= local && local < end,
+        Ordering::Greater => local >= start || local < end,
+    }
+}
+
+/// Start of the next local day for an inbox, when its `sent_today` counter resets
+pub fn next_local_midnight(offset_minutes: Option<i32>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let offset = Duration::minutes(offset_minutes.unwrap_or(0) as i64);
+    let tomorrow = (now + offset).date_naive() + Duration::days(1);
+    tomorrow.and_time(NaiveTime::MIN).and_utc() - offset
+}
+
+/// Best-effort UTC offset guess (in minutes) from the country-code TLD of an
+/// email or company domain. Generic TLDs (.com, .io, ...) return `None`.
+pub fn guess_utc_offset_minutes(email_or_domain: &str) -> Option<i32> {
+    let domain = email_or_domain.rsplit('@').next()?.trim().to_lowercase();
+    let tld = domain.rsplit('.').next()?;
+
+    let hours: f32 = match tld {
+        "uk" | "ie" | "pt" | "is" => 0.0,
+        "de" | "fr" | "es" | "it" | "nl" | "be" | "ch" | "at" | "se" | "no" | "dk" | "pl"
+        | "cz" | "hu" | "lu" => 1.0,
+        "fi" | "gr" | "ro" | "bg" | "ee" | "lv" | "lt" | "ua" | "il" | "za" | "eg" => 2.0,
+        "tr" | "ru" | "sa" | "ke" => 3.0,
+        "ae" => 4.0,
+        "pk" => 5.0,
+        "in" => 5.5,
+        "th" | "vn" | "id" => 7.0,
+        "sg" | "my" | "ph" | "cn" | "hk" | "tw" => 8.0,
+        "jp" | "kr" => 9.0,
+        "au" => 10.0,
+        "nz" => 12.0,
+        "br" | "ar" => -3.0,
+        "cl" => -4.0,
+        "co" | "pe" => -5.0,
+        "mx" => -6.0,
+        "ca" => -5.0,
+        "us" => -5.0,
+        _ => return None,
+    };
+
+    Some((hours * 60.0) as i32)
+}
+
+/// Returns `(timezone_offset_minutes, preferred_send_hour)` for a new lead, or
+/// `(None, None)` when the timezone can't be guessed so the campaign-level
+/// schedule applies instead.
+pub fn guess_send_preferences(email: &str) -> (Option<i32>, Option<i16>) {
+    match guess_utc_offset_minutes(email) {
+        Some(offset) => (Some(offset), Some(DEFAULT_LOCAL_SEND_HOUR)),
+        None => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_offset_from_cctld() {
+        assert_eq!(guess_utc_offset_minutes("anna@firma.de"), Some(60));
+        assert_eq!(guess_utc_offset_minutes("raj@startup.in"), Some(330));
+        assert_eq!(guess_utc_offset_minutes("sam@acme.co.uk"), Some(0));
+        assert_eq!(guess_utc_offset_minutes("jo@acme.com"), None);
+        assert_eq!(guess_utc_offset_minutes("jo@acme.io"), None);
+    }
+
+    #[test]
+    fn test_send_preferences_fall_back_when_unknown() {
+        assert_eq!(guess_send_preferences("jo@acme.com"), (None, None));
+        assert_eq!(
+            guess_send_preferences("kenji@example.jp"),
+            (Some(540), Some(DEFAULT_LOCAL_SEND_HOUR))
+        );
+    }
+
+    #[test]
+    fn test_send_window_in_inbox_local_time() {
+        let at = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let utc = |h: u32, m: u32| Utc::now().date_naive().and_time(at(h, m)).and_utc();
+
+        // No timezone: 9-5 UTC
+        assert!(in_send_window(at(9, 0), at(17, 0), None, utc(9, 0)));
+        assert!(!in_send_window(at(9, 0), at(17, 0), None, utc(17, 0)));
+        assert!(!in_send_window(at(9, 0), at(17, 0), None, utc(3, 0)));
+
+        // 9-5 in UTC-5 is 14:00-22:00 UTC
+        assert!(in_send_window(at(9, 0), at(17, 0), Some(-300), utc(21, 30)));
+        assert!(!in_send_window(at(9, 0), at(17, 0), Some(-300), utc(13, 59)));
+
+        // 22:00-06:00 wraps past midnight
+        assert!(in_send_window(at(22, 0), at(6, 0), None, utc(23, 0)));
+        assert!(in_send_window(at(22, 0), at(6, 0), None, utc(5, 59)));
+        assert!(!in_send_window(at(22, 0), at(6, 0), None, utc(12, 0)));
+
+        // Equal bounds: always open
+        assert!(in_send_window(at(0, 0), at(0, 0), Some(330), utc(3, 0)));
+    }
+}
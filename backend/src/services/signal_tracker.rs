@@ -4,8 +4,11 @@ use tracing::{info, warn, error};
 use uuid::Uuid;
 
 use crate::models::company::Company;
-use crate::models::signal::{PublicSignal, Signal};
+use crate::models::pagination::Cursor;
+use crate::models::signal::{FeedFilter, PublicSignal, Signal, SignalSource as ModelSignalSource};
+use crate::services::funding_connector::FundingConnector;
 use crate::services::github_connector::GithubConnector;
+use crate::services::rss_connector::RssConnector;
 use crate::services::wellfound_connector::WellfoundConnector;
 
 // ============================================================================
@@ -25,9 +28,102 @@ pub struct CompanySignalSummary {
     pub avg_confidence: f64,
 }
 
+/// External source a company's signals are scraped from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalSource {
+    #[default]
+    Github,
+    Wellfound,
+    RssFeed,
+    Crunchbase,
+}
+
+impl SignalSource {
+    pub const ALL: [SignalSource; 4] = [
+        SignalSource::Github,
+        SignalSource::Wellfound,
+        SignalSource::RssFeed,
+        SignalSource::Crunchbase,
+    ];
+
+    /// Name stored in `scraper_state.source`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalSource::Github => "github",
+            SignalSource::Wellfound => "wellfound",
+            SignalSource::RssFeed => "rss_feed",
+            SignalSource::Crunchbase => "crunchbase",
+        }
+    }
+
+    /// Hiring pages change more slowly than commit activity, so they're refreshed less often.
+    /// Funding rounds are rare and Crunchbase calls are metered, so those wait a week.
+    pub fn default_stale_hours(&self) -> i32 {
+        match self {
+            SignalSource::Github => 24,
+            SignalSource::Wellfound => 72,
+            SignalSource::RssFeed => 12,
+            SignalSource::Crunchbase => 168,
+        }
+    }
+
+    /// Reads `GITHUB_SIGNAL_STALE_HOURS` / `WELLFOUND_SIGNAL_STALE_HOURS` /
+    /// `RSS_FEED_SIGNAL_STALE_HOURS` / `CRUNCHBASE_SIGNAL_STALE_HOURS`, falling back
+    /// to the default for missing or invalid values
+    pub fn stale_hours(&self) -> i32 {
+        let var = format!("{}_SIGNAL_STALE_HOURS", self.as_str().to_uppercase());
+        std::env::var(var)
+            .ok()
+            .and_then(|v| v.trim().parse::<i32>().ok())
+            .filter(|h| *h > 0)
+            .unwrap_or_else(|| self.default_stale_hours())
+    }
+}
+
+/// Outcome of one stale-signal refresh run for a single source
+#[derive(Debug, Default)]
+pub struct RefreshSummary {
+    pub source: SignalSource,
+    pub companies_found: usize,
+    pub refreshed: usize,
+    pub failed: usize,
+    pub signals_created: usize,
+}
+
+/// Upserts the per-source scrape state. Failures bump `error_count` but keep the
+/// last successful time, so the company is retried once it's stale again.
+async fn record_scrape(
+    pool: &PgPool,
+    company_id: Uuid,
+    source: SignalSource,
+    error: Option<&String>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO scraper_state (source, company_id, last_scraped_at, last_successful_at, last_error, error_count)
+        VALUES ($1, $2, NOW(), CASE WHEN $3::text IS NULL THEN NOW() END, $3, CASE WHEN $3::text IS NULL THEN 0 ELSE 1 END)
+        ON CONFLICT (source, company_id) DO UPDATE SET
+            last_scraped_at = NOW(),
+            last_successful_at = COALESCE(EXCLUDED.last_successful_at, scraper_state.last_successful_at),
+            last_error = EXCLUDED.last_error,
+            error_count = CASE WHEN $3::text IS NULL THEN 0 ELSE scraper_state.error_count + 1 END,
+            updated_at = NOW()
+        "#
+    )
+    .bind(source.as_str())
+    .bind(company_id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 pub struct SignalTracker {
     github: GithubConnector,
     wellfound: WellfoundConnector,
+    rss: RssConnector,
+    funding: FundingConnector,
 }
 
 impl SignalTracker {
@@ -35,6 +131,8 @@ impl SignalTracker {
         Self {
             github: GithubConnector::new(github_token),
             wellfound: WellfoundConnector::new(),
+            rss: RssConnector::new(),
+            funding: FundingConnector::from_env(),
         }
     }
 
@@ -48,32 +146,9 @@ impl SignalTracker {
 
         info!("Ingesting signals for company: {} ({})", company.name, company.domain);
 
-        // 1. GitHub signals (if org is configured)
-        if let Some(ref github_org) = company.github_org {
-            match self.github.fetch_org_activity(github_org).await {
-                Ok(activity) => {
-                    if let Ok(Some(signal)) = self.github.create_signal(pool, company.id, &activity).await {
-                        all_signals.push(signal);
-                    }
-                }
-                Err(e) => {
-                    warn!("GitHub fetch failed for {}: {}", github_org, e);
-                }
-            }
-        }
-
-        // 2. Wellfound hiring signals (if slug is configured)
-        if let Some(ref wellfound_slug) = company.wellfound_slug {
-            match self.wellfound.fetch_company_jobs(wellfound_slug).await {
-                Ok(jobs) => {
-                    match self.wellfound.create_signals(pool, company.id, &jobs).await {
-                        Ok(signals) => all_signals.extend(signals),
-                        Err(e) => warn!("Failed to create hiring signals for {}: {}", wellfound_slug, e),
-                    }
-                }
-                Err(e) => {
-                    warn!("Wellfound fetch failed for {}: {}", wellfound_slug, e);
-                }
+        for source in SignalSource::ALL {
+            if let Ok(signals) = self.ingest_from_source(pool, company, source).await {
+                all_signals.extend(signals);
             }
         }
 
@@ -92,6 +167,98 @@ impl SignalTracker {
         Ok(all_signals)
     }
 
+    /// Ingest signals for one company from a single source and record the attempt
+    /// in `scraper_state`. Companies without the source configured yield nothing.
+    async fn ingest_from_source(
+        &self,
+        pool: &PgPool,
+        company: &Company,
+        source: SignalSource,
+    ) -> Result<Vec<Signal>, String> {
+        let result = match source {
+            // 1. GitHub signals (if org is configured)
+            SignalSource::Github => match company.github_org {
+                Some(ref github_org) => match self.github.fetch_org_activity(pool, github_org).await {
+                    Ok(activity) => {
+                        let mut signals = Vec::new();
+                        if let Ok(Some(signal)) = self.github.create_signal(pool, company.id, &activity).await {
+                            signals.push(signal);
+                        }
+                        Ok(signals)
+                    }
+                    Err(e) => {
+                        warn!("GitHub fetch failed for {}: {}", github_org, e);
+                        Err(e.to_string())
+                    }
+                },
+                None => return Ok(Vec::new()),
+            },
+            // 2. Wellfound hiring signals (if slug is configured)
+            SignalSource::Wellfound => match company.wellfound_slug {
+                Some(ref wellfound_slug) => match self.wellfound.fetch_company_jobs(wellfound_slug).await {
+                    Ok(jobs) => match self.wellfound.create_signals(pool, company.id, &jobs).await {
+                        Ok(signals) => Ok(signals),
+                        Err(e) => {
+                            warn!("Failed to create hiring signals for {}: {}", wellfound_slug, e);
+                            Err(e.to_string())
+                        }
+                    },
+                    Err(e) => {
+                        warn!("Wellfound fetch failed for {}: {}", wellfound_slug, e);
+                        Err(e.to_string())
+                    }
+                },
+                None => return Ok(Vec::new()),
+            },
+            // 3. Blog/news feed launch and expansion signals (if a feed is configured)
+            SignalSource::RssFeed => match company.rss_feed_url {
+                Some(ref feed_url) => match self.rss.fetch_recent_posts(feed_url).await {
+                    Ok(posts) => match self.rss.create_signals(pool, company.id, &posts).await {
+                        Ok(signals) => Ok(signals),
+                        Err(e) => {
+                            warn!("Failed to create feed signals for {}: {}", feed_url, e);
+                            Err(e.to_string())
+                        }
+                    },
+                    Err(e) => {
+                        warn!("Feed fetch failed for {}: {}", feed_url, e);
+                        Err(e.to_string())
+                    }
+                },
+                None => return Ok(Vec::new()),
+            },
+            // 4. Crunchbase funding rounds (if a permalink and API key are configured)
+            SignalSource::Crunchbase => match company.crunchbase_permalink {
+                Some(ref permalink) if self.funding.is_configured() => {
+                    match self.funding.fetch_funding_rounds(&company.name, permalink).await {
+                        Ok(rounds) => match self
+                            .funding
+                            .create_signals(pool, company.id, &rounds, ModelSignalSource::Crunchbase)
+                            .await
+                        {
+                            Ok(signals) => Ok(signals),
+                            Err(e) => {
+                                warn!("Failed to create funding signals for {}: {}", permalink, e);
+                                Err(e.to_string())
+                            }
+                        },
+                        Err(e) => {
+                            warn!("Crunchbase fetch failed for {}: {}", permalink, e);
+                            Err(e.to_string())
+                        }
+                    }
+                }
+                _ => return Ok(Vec::new()),
+            },
+        };
+
+        if let Err(e) = record_scrape(pool, company.id, source, result.as_ref().err()).await {
+            warn!("Failed to record {} scrape for {}: {}", source.as_str(), company.name, e);
+        }
+
+        result
+    }
+
     /// Ingest signals for all active companies
     pub async fn ingest_all_signals(
         &self,
@@ -118,45 +285,83 @@ impl SignalTracker {
         Ok(all_signals)
     }
 
-    /// Ingest signals for companies that need updating (stale data)
+    /// Refresh one source for companies whose data from it is older than `stale_hours`
     pub async fn ingest_stale_signals(
         &self,
         pool: &PgPool,
-        source: &str,
+        source: SignalSource,
         stale_hours: i32,
-    ) -> Result<Vec<Signal>, Box<dyn std::error::Error + Send + Sync>> {
-        let companies = Company::find_needing_scrape(pool, source, stale_hours).await?;
-        let mut all_signals = Vec::new();
+    ) -> Result<RefreshSummary, Box<dyn std::error::Error + Send + Sync>> {
+        if source == SignalSource::Crunchbase && !self.funding.is_configured() {
+            return Ok(RefreshSummary { source, ..Default::default() });
+        }
+
+        let companies = Company::find_needing_scrape(pool, source.as_str(), stale_hours).await?;
+        let mut summary = RefreshSummary {
+            source,
+            companies_found: companies.len(),
+            ..Default::default()
+        };
 
         info!(
             "Found {} companies needing {} signal refresh (stale > {} hours)",
             companies.len(),
-            source,
+            source.as_str(),
             stale_hours
         );
 
         for company in companies {
-            match self.ingest_company_signals(pool, &company).await {
-                Ok(signals) => all_signals.extend(signals),
+            match self.ingest_from_source(pool, &company, source).await {
+                Ok(signals) => {
+                    summary.refreshed += 1;
+                    summary.signals_created += signals.len();
+                }
                 Err(e) => {
-                    error!("Failed to ingest signals for {}: {}", company.name, e);
+                    summary.failed += 1;
+                    error!("Failed to refresh {} signals for {}: {}", source.as_str(), company.name, e);
                 }
             }
 
             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         }
 
-        Ok(all_signals)
+        info!(
+            "{} signal refresh: {} of {} companies refreshed, {} failed, {} signals created",
+            source.as_str(),
+            summary.refreshed,
+            summary.companies_found,
+            summary.failed,
+            summary.signals_created
+        );
+
+        Ok(summary)
     }
 
-    /// Get the public signal feed
+    /// Get one page of the public signal feed matching `filter`, along with the
+    /// cursor for the next page (`None` on the last page)
     pub async fn get_public_feed(
         &self,
         pool: &PgPool,
+        filter: &FeedFilter,
         limit: i64,
-    ) -> Result<Vec<PublicSignal>, Box<dyn std::error::Error + Send + Sync>> {
-        let signals = Signal::find_recent(pool, limit).await?;
-        Ok(signals)
+        after: Option<&Cursor>,
+    ) -> Result<(Vec<PublicSignal>, Option<Cursor>), Box<dyn std::error::Error + Send + Sync>> {
+        let after = match after {
+            Some(cursor) => Some((cursor.timestamp().ok_or("Invalid feed cursor")?, cursor.id)),
+            None => None,
+        };
+
+        // One extra row tells whether another page exists
+        let mut signals = Signal::find_recent(pool, filter, limit + 1, after).await?;
+
+        let next_cursor = if signals.len() as i64 > limit {
+            signals.truncate(limit as usize);
+            signals.last().map(|s| Cursor::from_timestamp(s.detected_at, s.id))
+        } else {
+            None
+        };
+
+        Ok((signals, next_cursor))
     }
 
     /// Get signals by type
@@ -166,7 +371,7 @@ impl SignalTracker {
         signal_type: &str,
         limit: i64,
     ) -> Result<Vec<PublicSignal>, Box<dyn std::error::Error + Send + Sync>> {
-        let signals = Signal::find_by_type(pool, signal_type, limit).await?;
+        let signals = Signal::find_by_type(pool, signal_type, limit, None).await?;
         Ok(signals)
     }
 
@@ -218,3 +423,141 @@ impl Default for SignalTracker {
         Self::new(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    async fn seed_company(pool: &PgPool) -> Uuid {
+        sqlx::query_scalar("INSERT INTO companies (name, domain) VALUES ('Feed Test Co', $1) RETURNING id")
+            .bind(format!("{}.example.com", Uuid::new_v4()))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Published signal detected `hours_ago` hours ago
+    async fn seed_signal(
+        pool: &PgPool,
+        company_id: Uuid,
+        signal_type: &str,
+        source: &str,
+        confidence: f64,
+        hours_ago: i32,
+    ) -> Uuid {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO signals (company_id, signal_type, source, title, confidence_score, detected_at)
+            VALUES ($1, $2, $3, 'Feed test', $4, NOW() - INTERVAL '1 hour' * $5)
+            RETURNING id
+            "#,
+        )
+        .bind(company_id)
+        .bind(signal_type)
+        .bind(source)
+        .bind(confidence)
+        .bind(hours_ago)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_feed_pages_without_overlap_or_gaps() {
+        let pool = test_db::pool().await;
+        let company_id = seed_company(&pool).await;
+
+        // Dated in the future so they lead the feed; pairs share a timestamp to
+        // exercise the id tie-break across page boundaries
+        let mut seeded = Vec::new();
+        for hours in [3, 2, 2, 1, 1, 0] {
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO signals (company_id, signal_type, source, title, confidence_score, detected_at)
+                VALUES ($1, 'hiring', 'manual', 'Paging test', 0.5,
+                        DATE_TRUNC('hour', NOW()) + INTERVAL '100 years' + INTERVAL '1 hour' * $2)
+                RETURNING id
+                "#,
+            )
+            .bind(company_id)
+            .bind(hours)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            seeded.push((hours, id));
+        }
+
+        let tracker = SignalTracker::default();
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        for page_number in 0..3 {
+            let (page, next) = tracker.get_public_feed(&pool, &FeedFilter::default(), 2, cursor.as_ref()).await.unwrap();
+            assert_eq!(page.len(), 2);
+            seen.extend(page.iter().map(|s| s.id));
+            if page_number < 2 {
+                assert!(next.is_some());
+            }
+            cursor = next;
+        }
+
+        sqlx::query("DELETE FROM companies WHERE id = $1").bind(company_id).execute(&pool).await.unwrap();
+
+        // Newest first, ties by descending id
+        seeded.sort_by(|a, b| b.cmp(a));
+        let expected: Vec<Uuid> = seeded.into_iter().map(|(_, id)| id).collect();
+        assert_eq!(seen, expected);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_feed_filters_alone_and_combined() {
+        let pool = test_db::pool().await;
+        let company_id = seed_company(&pool).await;
+
+        let fresh_hiring = seed_signal(&pool, company_id, "hiring", "wellfound", 0.9, 1).await;
+        let stale_hiring = seed_signal(&pool, company_id, "hiring", "wellfound", 0.8, 24 * 10).await;
+        let weak_hiring = seed_signal(&pool, company_id, "hiring", "manual", 0.4, 2).await;
+        let fresh_github = seed_signal(&pool, company_id, "github_activity", "github", 0.75, 3).await;
+
+        let ids = |filter: FeedFilter| {
+            let pool = pool.clone();
+            async move {
+                let filter = FeedFilter { company_id: Some(company_id), ..filter };
+                let (signals, _) =
+                    SignalTracker::default().get_public_feed(&pool, &filter, 100, None).await.unwrap();
+                signals.into_iter().map(|s| s.id).collect::<Vec<_>>()
+            }
+        };
+        let three_days_ago = Some(chrono::Utc::now() - chrono::Duration::days(3));
+
+        assert_eq!(ids(FeedFilter::default()).await.len(), 4);
+        assert_eq!(
+            ids(FeedFilter { min_confidence: Some(0.7), ..Default::default() }).await,
+            [fresh_hiring, fresh_github, stale_hiring]
+        );
+        assert_eq!(
+            ids(FeedFilter { since: three_days_ago, ..Default::default() }).await,
+            [fresh_hiring, weak_hiring, fresh_github]
+        );
+        assert_eq!(
+            ids(FeedFilter { sources: Some(vec!["github".into(), "manual".into()]), ..Default::default() }).await,
+            [weak_hiring, fresh_github]
+        );
+        assert_eq!(
+            ids(FeedFilter {
+                signal_type: Some("hiring".into()),
+                min_confidence: Some(0.7),
+                since: three_days_ago,
+                ..Default::default()
+            })
+            .await,
+            [fresh_hiring]
+        );
+
+        sqlx::query("DELETE FROM companies WHERE id = $1").bind(company_id).execute(&pool).await.unwrap();
+    }
+}
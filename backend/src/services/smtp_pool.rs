@@ -0,0 +1,278 @@
+use lettre::{
+    transport::smtp::{
+        authentication::{Credentials, Mechanism},
+        Error as SmtpError, PoolConfig,
+    },
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Pooled connections, and transports nobody has used, are dropped after this long idle
+pub const SMTP_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Most connections kept open to a single account's SMTP server
+pub const SMTP_MAX_CONNECTIONS: u32 = 10;
+
+/// Everything a transport is built from. If an account's settings change (e.g. a new
+/// password), its cached transport is replaced on the next send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    /// The account password, or an OAuth access token when `xoauth2` is set
+    pub password: String,
+    pub xoauth2: bool,
+}
+
+/// A failed send. `connection` is set when the transport itself looks broken
+/// (network, TLS or dropped session) rather than the server rejecting the message.
+/// `permanent` marks a 5xx rejection, which retrying won't change.
+#[derive(Debug)]
+pub struct SendError {
+    pub message: String,
+    pub connection: bool,
+    pub permanent: bool,
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<SmtpError> for SendError {
+    fn from(e: SmtpError) -> Self {
+        Self {
+            connection: !(e.is_transient() || e.is_permanent() || e.is_response() || e.is_client()),
+            permanent: e.is_permanent(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// The part of an SMTP transport the pool needs. Cloning must share the underlying
+/// connections, as lettre's pooled transport does.
+pub trait PooledTransport: Clone + Send + Sync + 'static {
+    /// Sends `message` and returns the server's response text
+    fn send_message(&self, message: Message) -> impl Future<Output = Result<String, SendError>> + Send;
+
+    /// Opens a connection, or checks an open one, without sending anything
+    fn connect(&self) -> impl Future<Output = Result<(), SendError>> + Send;
+}
+
+impl PooledTransport for AsyncSmtpTransport<Tokio1Executor> {
+    async fn send_message(&self, message: Message) -> Result<String, SendError> {
+        let response = self.send(message).await?;
+        Ok(response.message().collect::<Vec<_>>().join(""))
+    }
+
+    async fn connect(&self) -> Result<(), SendError> {
+        match self.test_connection().await? {
+            true => Ok(()),
+            false => Err(SendError {
+                message: "SMTP server did not accept the connection".to_string(),
+                connection: true,
+                permanent: false,
+            }),
+        }
+    }
+}
+
+fn build_transport(settings: &SmtpSettings) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let creds = Credentials::new(settings.username.clone(), settings.password.clone());
+    let pool_config = PoolConfig::new()
+        .max_size(SMTP_MAX_CONNECTIONS)
+        .idle_timeout(SMTP_IDLE_TIMEOUT);
+
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)
+        .map_err(|e| format!("Failed to create transport: {}", e))?
+        .credentials(creds)
+        .port(settings.port)
+        .pool_config(pool_config);
+    if settings.xoauth2 {
+        builder = builder.authentication(vec![Mechanism::Xoauth2]);
+    }
+
+    Ok(builder.build())
+}
+
+struct CachedTransport<T> {
+    settings: SmtpSettings,
+    transport: T,
+    last_used: Instant,
+}
+
+type TransportBuilder<T> = Box<dyn Fn(&SmtpSettings) -> Result<T, String> + Send + Sync>;
+
+/// One transport per sending inbox, shared by every job in the process, so a burst
+/// of sends reuses open sessions instead of paying a TLS handshake and login each.
+pub struct SmtpPool<T = AsyncSmtpTransport<Tokio1Executor>> {
+    transports: Mutex<HashMap<Uuid, CachedTransport<T>>>,
+    build: TransportBuilder<T>,
+}
+
+impl SmtpPool {
+    pub fn new() -> Self {
+        Self::with_builder(build_transport)
+    }
+}
+
+impl Default for SmtpPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PooledTransport> SmtpPool<T> {
+    pub fn with_builder(build: impl Fn(&SmtpSettings) -> Result<T, String> + Send + Sync + 'static) -> Self {
+        Self {
+            transports: Mutex::new(HashMap::new()),
+            build: Box::new(build),
+        }
+    }
+
+    /// Sends through the inbox's cached transport, building it on first use. A
+    /// connection error evicts the transport so the next send starts afresh.
+    pub async fn send(&self, inbox_id: Uuid, settings: &SmtpSettings, message: Message) -> Result<String, SendError> {
+        let transport = self.checkout(inbox_id, settings).map_err(|message| SendError {
+            message,
+            connection: false,
+            permanent: false,
+        })?;
+
+        transport.send_message(message).await.inspect_err(|e| {
+            if e.connection {
+                self.evict(inbox_id);
+            }
+        })
+    }
+
+    /// Opens a connection for the inbox ahead of its first send
+    pub async fn warm(&self, inbox_id: Uuid, settings: &SmtpSettings) -> Result<(), String> {
+        let transport = self.checkout(inbox_id, settings)?;
+
+        transport.connect().await.map_err(|e| {
+            if e.connection {
+                self.evict(inbox_id);
+            }
+            e.message
+        })
+    }
+
+    pub fn evict(&self, inbox_id: Uuid) {
+        self.lock().remove(&inbox_id);
+    }
+
+    fn checkout(&self, inbox_id: Uuid, settings: &SmtpSettings) -> Result<T, String> {
+        let now = Instant::now();
+        let mut transports = self.lock();
+        transports.retain(|_, cached| now.duration_since(cached.last_used) < SMTP_IDLE_TIMEOUT);
+
+        if let Some(cached) = transports.get_mut(&inbox_id).filter(|cached| cached.settings == *settings) {
+            cached.last_used = now;
+            return Ok(cached.transport.clone());
+        }
+
+        let transport = (self.build)(settings)?;
+        transports.insert(
+            inbox_id,
+            CachedTransport {
+                settings: settings.clone(),
+                transport: transport.clone(),
+                last_used: now,
+            },
+        );
+        Ok(transport)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, CachedTransport<T>>> {
+        self.transports.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct MockTransport {
+        sent: Arc<AtomicUsize>,
+        drop_connection: Arc<AtomicBool>,
+    }
+
+    impl PooledTransport for MockTransport {
+        async fn send_message(&self, _message: Message) -> Result<String, SendError> {
+            if self.drop_connection.swap(false, Ordering::SeqCst) {
+                return Err(SendError {
+                    message: "connection reset".to_string(),
+                    connection: true,
+                    permanent: false,
+                });
+            }
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok("250 OK".to_string())
+        }
+
+        async fn connect(&self) -> Result<(), SendError> {
+            Ok(())
+        }
+    }
+
+    fn message(n: usize) -> Message {
+        Message::builder()
+            .from("sender@example.com".parse().unwrap())
+            .to(format!("lead{}@example.com", n).parse().unwrap())
+            .subject("Hello")
+            .body("Hi there".to_string())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sends_reuse_one_transport_per_inbox() {
+        let built = Arc::new(AtomicUsize::new(0));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let drop_connection = Arc::new(AtomicBool::new(false));
+
+        let pool = {
+            let (built, sent, drop_connection) = (built.clone(), sent.clone(), drop_connection.clone());
+            SmtpPool::with_builder(move |_: &SmtpSettings| {
+                built.fetch_add(1, Ordering::SeqCst);
+                Ok(MockTransport {
+                    sent: sent.clone(),
+                    drop_connection: drop_connection.clone(),
+                })
+            })
+        };
+        let inbox_id = Uuid::new_v4();
+        let settings = SmtpSettings {
+            host: "smtp.example.com".to_string(),
+            port: 587,
+            username: "sender@example.com".to_string(),
+            password: "secret".to_string(),
+            xoauth2: false,
+        };
+
+        pool.warm(inbox_id, &settings).await.unwrap();
+        for n in 0..50 {
+            pool.send(inbox_id, &settings, message(n)).await.unwrap();
+        }
+
+        assert_eq!(sent.load(Ordering::SeqCst), 50);
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+
+        // A dropped connection throws the transport away and the next send rebuilds it
+        drop_connection.store(true, Ordering::SeqCst);
+        assert!(pool.send(inbox_id, &settings, message(50)).await.unwrap_err().connection);
+        pool.send(inbox_id, &settings, message(51)).await.unwrap();
+
+        assert_eq!(built.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,263 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::compliance::SuppressionReason;
+
+/// Rows upserted per statement during an import
+const IMPORT_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Serialize)]
+pub struct SkippedImportLine {
+    pub line: usize,
+    pub value: String,
+    pub reason: String,
+}
+
+/// A parsed suppression import, ready to upsert
+#[derive(Debug, Default)]
+pub struct SuppressionImport {
+    pub entries: Vec<(String, SuppressionReason)>,
+    pub skipped: Vec<SkippedImportLine>,
+}
+
+/// Lowercased list entry for an address or, when it starts with `@`, a whole
+/// domain. A leading apostrophe is dropped, since that's how our CSV exports
+/// keep `@domain` cells from being read as formulas.
+pub fn normalize_entry(value: &str) -> Option<String> {
+    let entry = value.trim().trim_start_matches('\'').to_lowercase();
+
+    match entry.strip_prefix('@') {
+        Some(domain) => {
+            let valid = domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && format!("postmaster@{}", domain).parse::<lettre::Address>().is_ok();
+            valid.then_some(entry)
+        }
+        None => entry.parse::<lettre::Address>().is_ok().then_some(entry),
+    }
+}
+
+/// Parses a newline-separated list or CSV (address or `@domain` in the first
+/// column, optional reason in the second). A header row starting with "email"
+/// is ignored; lines without a reason get `default_reason`.
+pub fn parse_import(body: &str, default_reason: &SuppressionReason) -> SuppressionImport {
+    let mut import = SuppressionImport::default();
+    let mut seen = HashSet::new();
+
+    for (idx, raw_line) in body.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut columns = line.split([',', ';', '\t']).map(|c| c.trim().trim_matches('"').trim());
+        let first = columns.next().unwrap_or("");
+
+        if line_no == 1 && first.eq_ignore_ascii_case("email") {
+            continue;
+        }
+
+        let skip = |reason: String| SkippedImportLine {
+            line: line_no,
+            value: raw_line.to_string(),
+            reason,
+        };
+
+        let Some(email) = normalize_entry(first) else {
+            import.skipped.push(skip("invalid email address or domain".to_string()));
+            continue;
+        };
+
+        let reason = match columns.next().filter(|c| !c.is_empty()) {
+            Some(r) => match SuppressionReason::parse(r) {
+                Some(reason) => reason,
+                None => {
+                    import.skipped.push(skip(format!("unknown reason '{}'", r)));
+                    continue;
+                }
+            },
+            None => default_reason.clone(),
+        };
+
+        if !seen.insert(email.clone()) {
+            import.skipped.push(skip("duplicate in import".to_string()));
+            continue;
+        }
+
+        import.entries.push((email, reason));
+    }
+
+    import
+}
+
+/// Upserts entries into the workspace's list in one transaction, overwriting
+/// the reason of addresses already listed. Returns `(added, updated)`.
+pub async fn upsert_entries(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    entries: &[(String, SuppressionReason)],
+    source: &str,
+) -> Result<(i64, i64), sqlx::Error> {
+    let mut added = 0i64;
+    let mut updated = 0i64;
+
+    let mut tx = pool.begin().await?;
+
+    for chunk in entries.chunks(IMPORT_CHUNK_SIZE) {
+        let emails: Vec<&str> = chunk.iter().map(|(e, _)| e.as_str()).collect();
+        let reasons: Vec<&str> = chunk.iter().map(|(_, r)| r.as_str()).collect();
+
+        // xmax = 0 only for freshly inserted rows, which lets us split added/updated
+        let inserted_flags: Vec<bool> = sqlx::query_scalar(
+            r#"
+            INSERT INTO suppression_list (id, workspace_id, email, reason, source, created_at)
+            SELECT gen_random_uuid(), $1, t.email, t.reason, $4, NOW()
+            FROM UNNEST($2::text[], $3::text[]) AS t(email, reason)
+            ON CONFLICT (workspace_id, email) DO UPDATE SET
+                reason = EXCLUDED.reason,
+                source = EXCLUDED.source
+            RETURNING (xmax = 0)
+            "#
+        )
+        .bind(workspace_id)
+        .bind(&emails)
+        .bind(&reasons)
+        .bind(source)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for inserted in inserted_flags {
+            if inserted {
+                added += 1;
+            } else {
+                updated += 1;
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok((added, updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use crate::services::export::{csv_stream, SuppressionExportRow};
+    use futures_util::TryStreamExt;
+
+    #[test]
+    fn test_import_accepts_addresses_and_domains() {
+        let body = "Email,Reason\n\
+                    Ada@Example.com,bounced\n\
+                    @Competitor.com\n\
+                    '@rival.io,complained\n\
+                    not-an-address\n\
+                    @localhost\n\
+                    ada@example.com\n\
+                    grace@example.com,sneezed\n";
+        let import = parse_import(body, &SuppressionReason::Manual);
+
+        assert_eq!(
+            import.entries,
+            vec![
+                ("ada@example.com".to_string(), SuppressionReason::Bounced),
+                ("@competitor.com".to_string(), SuppressionReason::Manual),
+                ("@rival.io".to_string(), SuppressionReason::Complained),
+            ]
+        );
+        let skipped: Vec<usize> = import.skipped.iter().map(|s| s.line).collect();
+        assert_eq!(skipped, vec![5, 6, 7, 8]);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_bulk_import_upserts_and_round_trips_through_export() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Suppression import").await;
+
+        let mut body = String::from("email,reason\n");
+        for i in 0..1000 {
+            body.push_str(&format!("bad-{}@example.com,bounced\n", i));
+        }
+        body.push_str("@competitor.com,manual\n");
+        let import = parse_import(&body, &SuppressionReason::Manual);
+        assert!(import.skipped.is_empty());
+        assert_eq!(upsert_entries(&pool, workspace_id, &import.entries, "import").await.unwrap(), (1001, 0));
+
+        // Importing an address again replaces its reason instead of failing
+        let again = parse_import("bad-0@example.com,complained", &SuppressionReason::Manual);
+        assert_eq!(upsert_entries(&pool, workspace_id, &again.entries, "import").await.unwrap(), (0, 1));
+
+        let chunks: Vec<actix_web::web::Bytes> = csv_stream::<SuppressionExportRow>(pool.clone(), workspace_id)
+            .try_collect()
+            .await
+            .unwrap();
+        let exported = parse_import(std::str::from_utf8(&chunks.concat()).unwrap(), &SuppressionReason::Manual);
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        assert!(exported.skipped.is_empty());
+        let mut expected = import.entries.clone();
+        expected[0].1 = SuppressionReason::Complained;
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut actual = exported.entries;
+        actual.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(actual, expected);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_domain_entry_blocks_send_to_any_address_at_it() {
+        use crate::services::campaign_scheduler::CampaignScheduler;
+        use crate::services::email_sender::{is_suppressed, CampaignEmailSender, SendEmailJobPayload};
+        use std::sync::Arc;
+
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Domain suppression").await;
+        let other_workspace = test_db::workspace(&pool, "Domain suppression (other)").await;
+        let email = "Buyer@Competitor.com";
+        let campaign_id = test_db::campaign(&pool, workspace_id, "active").await;
+        let (lead_id, campaign_lead_id) = test_db::campaign_lead(&pool, workspace_id, campaign_id, email).await;
+
+        let import = parse_import("@competitor.com", &SuppressionReason::Manual);
+        upsert_entries(&pool, workspace_id, &import.entries, "import").await.unwrap();
+
+        let scheduled = CampaignScheduler::new(pool.clone()).schedule_campaign_sends(campaign_id).await;
+        let sent = CampaignEmailSender::new(pool.clone())
+            .send_campaign_email(&SendEmailJobPayload {
+                campaign_lead_id,
+                campaign_id,
+                lead_id,
+                inbox_id: Uuid::new_v4(),
+                email: email.to_string(),
+                step_index: 0,
+            })
+            .await;
+        let status: String = sqlx::query_scalar("SELECT status FROM campaign_leads WHERE id = $1")
+            .bind(campaign_lead_id)
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+
+        let elsewhere = is_suppressed(&pool, Some(other_workspace), email).await.unwrap();
+        let lookalike = is_suppressed(&pool, Some(workspace_id), "buyer@notcompetitor.com").await.unwrap();
+
+        for id in [workspace_id, other_workspace] {
+            test_db::delete_workspace(&pool, id).await;
+        }
+
+        assert_eq!(scheduled, Ok(0));
+        assert_eq!(sent, Ok(None));
+        assert_eq!(status, "suppressed");
+        assert!(!elsewhere);
+        assert!(!lookalike);
+    }
+}
@@ -0,0 +1,220 @@
+use hmac::{Hmac, Mac};
+use regex::{Captures, Regex};
+use reqwest::Url;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::services::jwt_keys::jwt_keys;
+
+/// Transparent 1x1 GIF served by the open pixel
+pub const PIXEL_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+/// Absolute links in `<a href>`, double- or single-quoted
+static LINK_HREF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(<a\s[^>]*?href\s*=\s*)(?:"(https?://[^"]*)"|'(https?://[^']*)')"#).unwrap()
+});
+
+static BODY_CLOSE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</body\s*>").unwrap());
+
+/// Public origin of the API, which tracking links point at. Set `TRACKING_BASE_URL`
+/// when the API isn't reachable at the default (e.g. behind a tracking subdomain).
+pub fn tracking_base_url() -> String {
+    std::env::var("TRACKING_BASE_URL")
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| "http://localhost:8080".to_string())
+}
+
+fn click_mac(secret: &str, campaign_lead_id: Uuid, destination: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(campaign_lead_id.as_bytes());
+    mac.update(destination.as_bytes());
+    mac
+}
+
+/// Click links carry a signature over the lead and destination, so the redirect
+/// can't be pointed somewhere else by editing the URL. Links signed before a
+/// `JWT_SECRET` rotation still verify against the previous secret.
+pub fn verify_click(campaign_lead_id: Uuid, destination: &str, signature: &str) -> bool {
+    let Ok(sig) = hex::decode(signature) else {
+        return false;
+    };
+    jwt_keys()
+        .verification_secrets()
+        .any(|secret| click_mac(secret, campaign_lead_id, destination).verify_slice(&sig).is_ok())
+}
+
+pub fn open_pixel_url(base_url: &str, campaign_lead_id: Uuid) -> String {
+    format!("{}/api/track/open/{}", base_url, campaign_lead_id)
+}
+
+pub fn click_url(base_url: &str, campaign_lead_id: Uuid, destination: &str) -> Option<String> {
+    let signature = hex::encode(click_mac(jwt_keys().current_secret(), campaign_lead_id, destination).finalize().into_bytes());
+    let mut url = Url::parse(&format!("{}/api/track/click/{}", base_url, campaign_lead_id)).ok()?;
+    url.query_pairs_mut()
+        .append_pair("url", destination)
+        .append_pair("sig", &signature);
+    Some(url.into())
+}
+
+/// Routes the email's links through the click redirect and adds the open pixel.
+/// Unsubscribe links are left pointing straight at the unsubscribe page.
+pub fn instrument_html(html: &str, base_url: &str, campaign_lead_id: Uuid) -> String {
+    let tracked_prefix = format!("{}/api/track/", base_url);
+
+    let linked = LINK_HREF.replace_all(html, |caps: &Captures| {
+        let (href, quote) = match (caps.get(2), caps.get(3)) {
+            (Some(href), _) => (href.as_str(), '"'),
+            (None, Some(href)) => (href.as_str(), '\''),
+            (None, None) => return caps[0].to_string(),
+        };
+        let destination = href.replace("&amp;", "&");
+
+        if destination.contains("/unsubscribe") || destination.starts_with(&tracked_prefix) {
+            return caps[0].to_string();
+        }
+
+        match click_url(base_url, campaign_lead_id, &destination) {
+            Some(tracked) => format!("{}{}{}{}", &caps[1], quote, tracked.replace('&', "&amp;"), quote),
+            None => caps[0].to_string(),
+        }
+    });
+
+    let pixel = format!(
+        r#"<img src="{}" width="1" height="1" alt="" style="display:none" />"#,
+        open_pixel_url(base_url, campaign_lead_id)
+    );
+
+    match BODY_CLOSE.find(&linked) {
+        Some(close) => format!("{}{}{}", &linked[..close.start()], pixel, &linked[close.start()..]),
+        None => format!("{}{}", linked, pixel),
+    }
+}
+
+/// Records an open from the tracking pixel. Only the first counts: image proxies and
+/// security scanners often fetch the pixel several times, and repeat hits leave
+/// `opened_at` and the campaign counter alone. Returns whether this was the first.
+pub async fn record_open(pool: &PgPool, campaign_lead_id: Uuid) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let first = mark_opened(&mut tx, campaign_lead_id).await?;
+    if first {
+        insert_event(&mut tx, campaign_lead_id, "opened", serde_json::json!({})).await?;
+    }
+    tx.commit().await?;
+    Ok(first)
+}
+
+/// Records a click on `destination`. Every click is kept with its URL, but the
+/// campaign counter counts each lead once. A click also counts as an open, since
+/// it proves the email was read even when images were blocked.
+pub async fn record_click(pool: &PgPool, campaign_lead_id: Uuid, destination: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let campaign_id: Option<Option<Uuid>> = sqlx::query_scalar(
+        "UPDATE campaign_leads SET clicked_at = NOW() WHERE id = $1 AND clicked_at IS NULL RETURNING campaign_id"
+    )
+    .bind(campaign_lead_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let first = campaign_id.is_some();
+    if let Some(Some(campaign_id)) = campaign_id {
+        sqlx::query("UPDATE campaigns SET clicked = clicked + 1 WHERE id = $1")
+            .bind(campaign_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    mark_opened(&mut tx, campaign_lead_id).await?;
+    insert_event(&mut tx, campaign_lead_id, "clicked", serde_json::json!({ "url": destination })).await?;
+
+    tx.commit().await?;
+    Ok(first)
+}
+
+async fn mark_opened(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, campaign_lead_id: Uuid) -> Result<bool, sqlx::Error> {
+    let campaign_id: Option<Option<Uuid>> = sqlx::query_scalar(
+        "UPDATE campaign_leads SET opened_at = NOW() WHERE id = $1 AND opened_at IS NULL RETURNING campaign_id"
+    )
+    .bind(campaign_lead_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if let Some(Some(campaign_id)) = campaign_id {
+        sqlx::query("UPDATE campaigns SET opened = opened + 1 WHERE id = $1")
+            .bind(campaign_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(campaign_id.is_some())
+}
+
+async fn insert_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    campaign_lead_id: Uuid,
+    event_type: &str,
+    payload: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO email_events (
+            workspace_id, campaign_id, campaign_lead_id, email_account_id, provider,
+            event_type, recipient, occurred_at, payload
+        )
+        SELECT c.workspace_id, cl.campaign_id, cl.id, cl.email_account_id, 'tracking', $2, l.email, NOW(), $3
+        FROM campaign_leads cl
+        JOIN leads l ON l.id = cl.lead_id
+        JOIN campaigns c ON c.id = cl.campaign_id
+        WHERE cl.id = $1
+        "#
+    )
+    .bind(campaign_lead_id)
+    .bind(event_type)
+    .bind(payload)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_are_tracked_except_unsubscribe() {
+        let lead = Uuid::new_v4();
+        let html = concat!(
+            r#"<html><body><p><a href="https://acme.io/pricing?plan=pro&amp;ref=email">Pricing</a> "#,
+            r#"<a class='cta' href='http://acme.io/demo'>Demo</a> <a href="mailto:hi@acme.io">Mail</a></p>"#,
+            r#"<a href="https://app.outreachiq.com/unsubscribe?token=abc">Unsubscribe</a></body></html>"#,
+        );
+
+        let tracked = instrument_html(html, "https://track.example.com", lead);
+
+        let pricing = click_url("https://track.example.com", lead, "https://acme.io/pricing?plan=pro&ref=email").unwrap();
+        assert!(tracked.contains(&format!(r#"<a href="{}">Pricing</a>"#, pricing.replace('&', "&amp;"))));
+        assert!(tracked.contains(&format!("track/click/{}?url=http%3A%2F%2Facme.io%2Fdemo&amp;sig=", lead)));
+        assert!(tracked.contains(r#"href="mailto:hi@acme.io""#));
+        assert!(tracked.contains(r#"href="https://app.outreachiq.com/unsubscribe?token=abc""#));
+        assert!(tracked.ends_with(&format!(
+            r#"<img src="https://track.example.com/api/track/open/{}" width="1" height="1" alt="" style="display:none" /></body></html>"#,
+            lead
+        )));
+
+        // The signature ties the destination to the lead
+        let url = Url::parse(&pricing).unwrap();
+        let sig = url.query_pairs().find(|(k, _)| k == "sig").unwrap().1.to_string();
+        assert!(verify_click(lead, "https://acme.io/pricing?plan=pro&ref=email", &sig));
+        assert!(!verify_click(lead, "https://evil.example.com", &sig));
+        assert!(!verify_click(Uuid::new_v4(), "https://acme.io/pricing?plan=pro&ref=email", &sig));
+    }
+}
@@ -0,0 +1,334 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::env;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::services::jwt_keys::MIN_SECRET_BYTES;
+
+static UNSUBSCRIBE_KEYS: LazyLock<UnsubscribeKeys> = LazyLock::new(|| {
+    // Unit tests sign links without any environment set up
+    #[cfg(test)]
+    if env::var("UNSUBSCRIBE_SECRET").is_err() {
+        return UnsubscribeKeys::new("unit-test-unsubscribe-secret-0123".to_string(), Vec::new()).unwrap();
+    }
+    UnsubscribeKeys::from_env().unwrap_or_else(|e| panic!("Invalid unsubscribe link configuration: {}", e))
+});
+
+/// Process-wide keys from the environment. Binaries touch this at startup so a
+/// missing or weak `UNSUBSCRIBE_SECRET` stops them before they send anything.
+pub fn unsubscribe_keys() -> &'static UnsubscribeKeys {
+    &UNSUBSCRIBE_KEYS
+}
+
+/// Signs unsubscribe links with `UNSUBSCRIBE_SECRET` and verifies them with it or
+/// any of `UNSUBSCRIBE_SECRET_PREVIOUS`. Links sit in inboxes indefinitely and
+/// have to keep working, so these keys are kept apart from `JWT_SECRET` and its
+/// short rotation window.
+pub struct UnsubscribeKeys {
+    current: String,
+    previous: Vec<String>,
+}
+
+impl UnsubscribeKeys {
+    pub fn from_env() -> Result<Self, String> {
+        let current = env::var("UNSUBSCRIBE_SECRET").map_err(|_| "UNSUBSCRIBE_SECRET must be set")?;
+        let previous = env::var("UNSUBSCRIBE_SECRET_PREVIOUS")
+            .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default();
+        Self::new(current, previous)
+    }
+
+    pub fn new(current: String, previous: Vec<String>) -> Result<Self, String> {
+        if current.len() < MIN_SECRET_BYTES {
+            return Err(format!("UNSUBSCRIBE_SECRET must be at least {} bytes", MIN_SECRET_BYTES));
+        }
+        if previous.iter().any(|p| p.len() < MIN_SECRET_BYTES) {
+            return Err(format!("Every UNSUBSCRIBE_SECRET_PREVIOUS entry must be at least {} bytes", MIN_SECRET_BYTES));
+        }
+        Ok(Self { current, previous })
+    }
+
+    /// Current secret first, then the retired ones
+    fn verification_secrets(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.current.as_str()).chain(self.previous.iter().map(String::as_str))
+    }
+}
+
+/// Who an unsubscribe link was sent to
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsubscribeRecipient {
+    pub email: String,
+    pub workspace_id: Option<Uuid>,
+    /// Campaign the email was sent for. Missing from links sent before
+    /// campaign-scoped unsubscribes existed.
+    pub campaign_id: Option<Uuid>,
+}
+
+/// What a token says once it has checked out
+#[derive(Debug, PartialEq)]
+pub enum TokenClaims {
+    /// `base64(lead_id:workspace_id:campaign_id).hex(hmac)`. Carries no address;
+    /// the recipient is looked up from the lead.
+    Signed {
+        lead_id: Uuid,
+        workspace_id: Option<Uuid>,
+        campaign_id: Uuid,
+    },
+    /// Unsigned `base64(lead_id:email:workspace_id[:campaign_id])` from links sent
+    /// before tokens were signed. Only honored while the legacy window is open.
+    Legacy {
+        lead_id: Option<Uuid>,
+        email: String,
+        workspace_id: Option<Uuid>,
+        campaign_id: Option<Uuid>,
+    },
+}
+
+fn token_mac(secret: &str, payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Token for the unsubscribe links in a campaign email, signed with `UNSUBSCRIBE_SECRET`
+pub fn sign(lead_id: Uuid, workspace_id: Option<Uuid>, campaign_id: Uuid) -> String {
+    sign_with(unsubscribe_keys(), lead_id, workspace_id, campaign_id)
+}
+
+fn sign_with(keys: &UnsubscribeKeys, lead_id: Uuid, workspace_id: Option<Uuid>, campaign_id: Uuid) -> String {
+    let payload = format!(
+        "{}:{}:{}",
+        lead_id,
+        workspace_id.map(|w| w.to_string()).unwrap_or_default(),
+        campaign_id
+    );
+    let signature = hex::encode(token_mac(&keys.current, &payload).finalize().into_bytes());
+    format!("{}.{}", URL_SAFE_NO_PAD.encode(&payload), signature)
+}
+
+/// Last moment unsigned tokens are accepted, from `UNSUBSCRIBE_LEGACY_TOKENS_UNTIL`
+/// (`YYYY-MM-DD`, exclusive). Unset keeps them working.
+pub fn legacy_accepted_until() -> Option<DateTime<Utc>> {
+    std::env::var("UNSUBSCRIBE_LEGACY_TOKENS_UNTIL")
+        .ok()
+        .and_then(|v| NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|at| at.and_utc())
+}
+
+/// Checks a token's signature, or for a legacy token its format and the
+/// deprecation window. Links signed before an `UNSUBSCRIBE_SECRET` rotation
+/// still verify against the retired secrets.
+pub fn decode(token: &str, now: DateTime<Utc>, legacy_until: Option<DateTime<Utc>>) -> Result<TokenClaims, &'static str> {
+    decode_with(unsubscribe_keys(), token, now, legacy_until)
+}
+
+fn decode_with(
+    keys: &UnsubscribeKeys,
+    token: &str,
+    now: DateTime<Utc>,
+    legacy_until: Option<DateTime<Utc>>,
+) -> Result<TokenClaims, &'static str> {
+    // Base64url never contains '.', so its absence marks a legacy token
+    let Some((payload, signature)) = token.split_once('.') else {
+        if legacy_until.is_some_and(|until| now >= until) {
+            return Err("This unsubscribe link has expired");
+        }
+        return decode_legacy(token);
+    };
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or("Invalid token")?;
+    let signature = hex::decode(signature).map_err(|_| "Invalid token")?;
+    if !keys
+        .verification_secrets()
+        .any(|secret| token_mac(secret, &payload).verify_slice(&signature).is_ok())
+    {
+        return Err("Invalid token");
+    }
+
+    let mut parts = payload.split(':');
+    let (Some(lead_id), Some(workspace_id), Some(campaign_id), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err("Invalid token structure");
+    };
+
+    Ok(TokenClaims::Signed {
+        lead_id: Uuid::parse_str(lead_id).map_err(|_| "Invalid token structure")?,
+        workspace_id: Uuid::parse_str(workspace_id).ok(),
+        campaign_id: Uuid::parse_str(campaign_id).map_err(|_| "Invalid token structure")?,
+    })
+}
+
+fn decode_legacy(token: &str) -> Result<TokenClaims, &'static str> {
+    let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| "Invalid token")?;
+    let decoded = String::from_utf8(bytes).map_err(|_| "Invalid token format")?;
+
+    let parts: Vec<&str> = decoded.split(':').collect();
+    if parts.len() < 2 || parts[1].is_empty() {
+        return Err("Invalid token structure");
+    }
+
+    Ok(TokenClaims::Legacy {
+        lead_id: Uuid::parse_str(parts[0]).ok(),
+        email: parts[1].to_string(),
+        workspace_id: parts.get(2).and_then(|s| Uuid::parse_str(s).ok()),
+        campaign_id: parts.get(3).and_then(|s| Uuid::parse_str(s).ok()),
+    })
+}
+
+/// The recipient a token names, or `None` when no lead matches it. Anyone can
+/// write a legacy token, so one is only honored when its lead id, address and
+/// workspace all belong to the same lead; the lead id can't be guessed.
+pub async fn recipient(pool: &PgPool, claims: TokenClaims) -> Result<Option<UnsubscribeRecipient>, sqlx::Error> {
+    match claims {
+        TokenClaims::Signed { lead_id, workspace_id, campaign_id } => {
+            let email: Option<String> = sqlx::query_scalar(
+                "SELECT email FROM leads WHERE id = $1 AND workspace_id IS NOT DISTINCT FROM $2"
+            )
+            .bind(lead_id)
+            .bind(workspace_id)
+            .fetch_optional(pool)
+            .await?;
+
+            Ok(email.map(|email| UnsubscribeRecipient {
+                email,
+                workspace_id,
+                campaign_id: Some(campaign_id),
+            }))
+        }
+        TokenClaims::Legacy { lead_id, email, workspace_id, campaign_id } => {
+            let Some(lead_id) = lead_id else {
+                return Ok(None);
+            };
+            let matches: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM leads WHERE id = $1 AND LOWER(email) = LOWER($2) AND workspace_id IS NOT DISTINCT FROM $3)"
+            )
+            .bind(lead_id)
+            .bind(&email)
+            .bind(workspace_id)
+            .fetch_one(pool)
+            .await?;
+
+            if matches {
+                tracing::info!("Accepted a legacy unsubscribe token for lead {}", lead_id);
+            }
+            Ok(matches.then_some(UnsubscribeRecipient { email, workspace_id, campaign_id }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use chrono::Duration;
+
+    fn legacy_token(lead_id: Uuid, email: &str, workspace_id: Uuid, campaign_id: Uuid) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}:{}:{}", lead_id, email, workspace_id, campaign_id))
+    }
+
+    #[test]
+    fn test_signed_tokens_verify_and_tampering_is_rejected() {
+        let (lead_id, workspace_id, campaign_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+        let token = sign(lead_id, Some(workspace_id), campaign_id);
+
+        assert_eq!(
+            decode(&token, now, None),
+            Ok(TokenClaims::Signed { lead_id, workspace_id: Some(workspace_id), campaign_id })
+        );
+        assert!(!token.contains('@'));
+
+        // One flipped byte anywhere, payload or signature, breaks it
+        for at in [3, token.len() - 3] {
+            let mut bytes = token.clone().into_bytes();
+            bytes[at] = if bytes[at] == b'a' { b'b' } else { b'a' };
+            let tampered = String::from_utf8(bytes).unwrap();
+            assert_eq!(decode(&tampered, now, None), Err("Invalid token"));
+        }
+
+        // A valid payload can't be pointed at another lead without the key
+        let forged_payload = URL_SAFE_NO_PAD.encode(format!("{}:{}:{}", Uuid::new_v4(), workspace_id, campaign_id));
+        let forged = format!("{}.{}", forged_payload, token.split_once('.').unwrap().1);
+        assert_eq!(decode(&forged, now, None), Err("Invalid token"));
+    }
+
+    #[test]
+    fn test_links_signed_before_a_rotation_verify_with_the_retired_secret() {
+        let (lead_id, workspace_id, campaign_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (old, new) = ("old-unsubscribe-secret-0123456789".to_string(), "new-unsubscribe-secret-0123456789".to_string());
+        let token = sign_with(&UnsubscribeKeys::new(old.clone(), Vec::new()).unwrap(), lead_id, Some(workspace_id), campaign_id);
+        let now = Utc::now();
+
+        let rotated = UnsubscribeKeys::new(new.clone(), vec![old]).unwrap();
+        let dropped = UnsubscribeKeys::new(new, Vec::new()).unwrap();
+        assert_eq!(
+            decode_with(&rotated, &token, now, None),
+            Ok(TokenClaims::Signed { lead_id, workspace_id: Some(workspace_id), campaign_id })
+        );
+        assert_eq!(decode_with(&dropped, &token, now, None), Err("Invalid token"));
+
+        assert!(UnsubscribeKeys::new("short".to_string(), Vec::new()).is_err());
+        assert!(UnsubscribeKeys::new("long-enough-unsubscribe-secret-0123".to_string(), vec!["short".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_legacy_tokens_are_accepted_until_the_window_closes() {
+        let (lead_id, workspace_id, campaign_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+        let token = legacy_token(lead_id, "ada@example.com", workspace_id, campaign_id);
+        let expected = TokenClaims::Legacy {
+            lead_id: Some(lead_id),
+            email: "ada@example.com".to_string(),
+            workspace_id: Some(workspace_id),
+            campaign_id: Some(campaign_id),
+        };
+
+        assert_eq!(decode(&token, now, None), Ok(expected));
+        assert!(decode(&token, now, Some(now + Duration::days(30))).is_ok());
+        assert_eq!(decode(&token, now, Some(now - Duration::days(1))), Err("This unsubscribe link has expired"));
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_tokens_resolve_to_the_lead_they_were_issued_for() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Unsubscribe token").await;
+        let (lead_id, campaign_id) = (Uuid::new_v4(), Uuid::new_v4());
+        sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, 'Ada@Example.com')")
+            .bind(lead_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let signed = decode(&sign(lead_id, Some(workspace_id), campaign_id), now, None).unwrap();
+        let legacy = decode(&legacy_token(lead_id, "ada@example.com", workspace_id, campaign_id), now, None).unwrap();
+        // Someone else's address on a real lead id is refused
+        let forged = decode(&legacy_token(lead_id, "victim@example.com", workspace_id, campaign_id), now, None).unwrap();
+
+        let signed = recipient(&pool, signed).await.unwrap();
+        let legacy = recipient(&pool, legacy).await.unwrap();
+        let forged = recipient(&pool, forged).await.unwrap();
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let expected = |email: &str| UnsubscribeRecipient {
+            email: email.to_string(),
+            workspace_id: Some(workspace_id),
+            campaign_id: Some(campaign_id),
+        };
+        assert_eq!(signed, Some(expected("Ada@Example.com")));
+        assert_eq!(legacy, Some(expected("ada@example.com")));
+        assert_eq!(forged, None);
+    }
+}
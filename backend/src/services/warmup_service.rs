@@ -1,22 +1,330 @@
 use sqlx::PgPool;
 use uuid::Uuid;
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rand::seq::SliceRandom;
+use rand::Rng;
 use std::sync::Arc;
 
+use crate::services::job_queue::{self, WarmupEmailPayload};
+
+/// Daily volume at the end of the warmup ramp, for inboxes without their own target
+const WARMUP_TARGET_LIMIT: i32 = 50;
+/// Warmup emails sent on the first day of the ramp, and how many more each day after
+const WARMUP_START_VOLUME: i32 = 10;
+const WARMUP_DAILY_INCREASE: i32 = 5;
+/// Partners reply somewhere in this window after seeing a warmup email, not instantly
+const WARMUP_REPLY_DELAY_MINUTES: (i32, i32) = (5, 90);
+/// Rates at or below these count as healthy (the dashboard's "warning" thresholds)
+const HEALTHY_SPAM_RATE: f64 = 0.02;
+const HEALTHY_BOUNCE_RATE: f64 = 0.05;
+/// How long rates must stay healthy at full volume before an inbox graduates
+const SUSTAINED_HEALTHY_DAYS: i64 = 7;
+/// Minimum health score to graduate
+const GRADUATION_HEALTH_SCORE: f64 = 90.0;
+/// Day of the ramp a demoted inbox restarts from (30 emails/day)
+const DEMOTED_RAMP_DAY: i64 = 4;
+
+/// Innocuous subject and body pairs for warmup emails
+const WARMUP_MESSAGES: &[(&str, &str)] = &[
+    ("Quick question about next week", "Are we still on for the catch-up next week? Let me know if another day works better."),
+    ("Notes from today", "Thanks for the chat earlier. I'll tidy up my notes and share them by Friday."),
+    ("Lunch on Thursday?", "A few of us are grabbing lunch on Thursday around noon. Want to join?"),
+    ("Following up on the doc", "Had a look at the draft you sent over. Looks good to me, just a couple of small comments."),
+    ("Checking in", "Hope the week is going well. Anything you need from me before the deadline?"),
+    ("Meeting moved", "Heads up that the planning meeting moved to 3pm. Same room as before."),
+];
+
+/// Replies a partner sends back
+const WARMUP_REPLIES: &[&str] = &[
+    "Thanks, sounds good!",
+    "Got it, thanks for letting me know.",
+    "Perfect, that works for me.",
+    "Great, talk soon.",
+    "Appreciate it, will take a look.",
+];
+
 pub struct WarmupService {
     pool: Arc<PgPool>,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 struct WarmingInbox {
     id: Uuid,
     email: String,
     warmup_status: String,
     daily_limit: i32,
-    sent_today: i32,
-    health_score: f32,
+    health_score: f64,
     created_at: chrono::DateTime<Utc>,
     workspace_id: Option<Uuid>,
+    spam_rate: Option<f64>,
+    bounce_rate: Option<f64>,
+    healthy_since: Option<DateTime<Utc>>,
+    warmup_started_at: Option<DateTime<Utc>>,
+    warmup_daily_target: Option<i32>,
+}
+
+impl WarmingInbox {
+    fn is_healthy(&self) -> bool {
+        self.spam_rate.unwrap_or(0.0) <= HEALTHY_SPAM_RATE
+            && self.bounce_rate.unwrap_or(0.0) <= HEALTHY_BOUNCE_RATE
+    }
+
+    /// The ramp counts from when warmup (re)started; older inboxes fall back to creation
+    fn ramp_start(&self) -> DateTime<Utc> {
+        self.warmup_started_at.unwrap_or(self.created_at)
+    }
+
+    /// Daily volume the ramp ends at
+    fn target_volume(&self) -> i32 {
+        self.warmup_daily_target.filter(|target| *target > 0).unwrap_or(WARMUP_TARGET_LIMIT)
+    }
+}
+
+/// An inbox in a workspace's warmup pool
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct WarmupPartner {
+    id: Uuid,
+    email: String,
+}
+
+/// A warmup email the receiving inbox has just seen
+#[derive(Debug, sqlx::FromRow)]
+struct OpenedWarmupEmail {
+    id: Uuid,
+    in_reply_to_id: Option<Uuid>,
+    sender_account_id: Uuid,
+    sender_email: String,
+    workspace_id: Option<Uuid>,
+}
+
+/// Warmup emails to send on day `days` of the ramp: `WARMUP_START_VOLUME` on day 0,
+/// `WARMUP_DAILY_INCREASE` more each day after, never more than `target`
+fn warmup_ramp_volume(days: i64, target: i32) -> i32 {
+    let ramped = WARMUP_START_VOLUME as i64 + WARMUP_DAILY_INCREASE as i64 * days.max(0);
+    ramped.min(target as i64) as i32
+}
+
+/// A random partner for `sender` from its pool; never the sender itself, nor
+/// another inbox with the same address
+fn pick_partner<'a, R: Rng + ?Sized>(
+    sender: &WarmingInbox,
+    pool: &'a [WarmupPartner],
+    rng: &mut R,
+) -> Option<&'a WarmupPartner> {
+    let candidates: Vec<&WarmupPartner> = pool
+        .iter()
+        .filter(|partner| partner.id != sender.id && !partner.email.eq_ignore_ascii_case(&sender.email))
+        .collect();
+    candidates.choose(rng).copied()
+}
+
+/// Subject and body for a new warmup email
+pub fn warmup_message<R: Rng + ?Sized>(rng: &mut R) -> (&'static str, &'static str) {
+    *WARMUP_MESSAGES.choose(rng).expect("warmup messages are not empty")
+}
+
+/// Body for a partner's reply to a warmup email
+pub fn warmup_reply<R: Rng + ?Sized>(rng: &mut R) -> &'static str {
+    WARMUP_REPLIES.choose(rng).expect("warmup replies are not empty")
+}
+
+/// Status change the warmup cycle applies to an inbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WarmupTransition {
+    /// warming -> active, with the full provider limit
+    Graduate,
+    /// active -> warming, back onto the ramp
+    Demote,
+}
+
+/// A warming inbox graduates once it's sending the full warmup volume with good
+/// health and spam/bounce rates have been healthy for `SUSTAINED_HEALTHY_DAYS`.
+/// An active inbox whose rates turn unhealthy goes back to warming.
+fn warmup_transition(inbox: &WarmingInbox, now: DateTime<Utc>) -> Option<WarmupTransition> {
+    match inbox.warmup_status.as_str() {
+        "warming" => {
+            let sustained = inbox.is_healthy()
+                && inbox
+                    .healthy_since
+                    .is_some_and(|since| now - since >= Duration::days(SUSTAINED_HEALTHY_DAYS));
+
+            (inbox.daily_limit >= inbox.target_volume()
+                && inbox.health_score >= GRADUATION_HEALTH_SCORE
+                && sustained)
+                .then_some(WarmupTransition::Graduate)
+        }
+        "active" => (!inbox.is_healthy()).then_some(WarmupTransition::Demote),
+        _ => None,
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CounterResetState {
+    id: Uuid,
+    timezone_offset_minutes: Option<i32>,
+    last_counter_reset_date: Option<NaiveDate>,
+}
+
+/// Calendar date at the account's local time (UTC when no offset is set)
+fn local_date(now: DateTime<Utc>, timezone_offset_minutes: Option<i32>) -> NaiveDate {
+    (now + Duration::minutes(timezone_offset_minutes.unwrap_or(0) as i64)).date_naive()
+}
+
+/// The counter is due once the account's local date has moved past the last reset
+fn needs_counter_reset(
+    now: DateTime<Utc>,
+    timezone_offset_minutes: Option<i32>,
+    last_reset: Option<NaiveDate>,
+) -> bool {
+    last_reset.is_none_or(|last| last < local_date(now, timezone_offset_minutes))
+}
+
+/// Rolling window of daily metrics the warmup stats cover
+const WARMUP_STATS_WINDOW_DAYS: i32 = 14;
+/// A delivered warmup email the partner hasn't found in INBOX after this long
+/// is counted as spam-foldered
+const SPAM_VERDICT_HOURS: i32 = 24;
+/// Days of metrics each cycle recomputes; older days no longer change
+const METRICS_REFRESH_DAYS: i32 = 2;
+
+/// Where an inbox's warmup stands: its ramp and summed daily metrics
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WarmupSummary {
+    pub warmup_status: String,
+    /// Daily volume the ramp ends at
+    pub target_volume: i32,
+    pub ramp_start: DateTime<Utc>,
+    /// Warmup emails delivered in the last 24 hours
+    pub volume_24h: i64,
+    pub delivered: i64,
+    pub landed_inbox: i64,
+    pub spam_foldered: i64,
+    pub bounced: i64,
+}
+
+impl WarmupSummary {
+    /// Percentage of placed warmup emails that reached the inbox. Emails the
+    /// partner hasn't had time to see yet count towards neither rate.
+    pub fn inbox_rate(&self) -> f64 {
+        percentage(self.landed_inbox, self.landed_inbox + self.spam_foldered)
+    }
+
+    pub fn spam_rate(&self) -> f64 {
+        percentage(self.spam_foldered, self.landed_inbox + self.spam_foldered)
+    }
+
+    /// Percentage of attempted warmup emails the partner's server rejected
+    pub fn bounce_rate(&self) -> f64 {
+        percentage(self.bounced, self.delivered + self.bounced)
+    }
+
+    /// How far through warmup the inbox is: the volume it's actually sending,
+    /// capped by where its ramp should be after the days elapsed, against its target
+    pub fn progress(&self, now: DateTime<Utc>) -> f64 {
+        if self.warmup_status == "active" {
+            return 100.0;
+        }
+        let expected = warmup_ramp_volume((now - self.ramp_start).num_days(), self.target_volume) as i64;
+        percentage(self.volume_24h.min(expected), self.target_volume as i64).min(100.0)
+    }
+}
+
+fn percentage(part: i64, whole: i64) -> f64 {
+    if whole <= 0 {
+        return 0.0;
+    }
+    part as f64 / whole as f64 * 100.0
+}
+
+/// An inbox's ramp and its warmup metrics over the last `WARMUP_STATS_WINDOW_DAYS`
+pub async fn warmup_summary(pool: &PgPool, account_id: Uuid) -> Result<Option<WarmupSummary>, sqlx::Error> {
+    sqlx::query_as::<_, WarmupSummary>(
+        r#"
+        SELECT ea.warmup_status,
+               COALESCE(NULLIF(ea.warmup_daily_target, 0), $2) AS target_volume,
+               COALESCE(ea.warmup_started_at, ea.created_at) AS ramp_start,
+               (SELECT COUNT(*) FROM warmup_emails we
+                WHERE we.sender_account_id = ea.id AND we.bounced_at IS NULL
+                  AND we.sent_at > NOW() - INTERVAL '1 day') AS volume_24h,
+               COALESCE(SUM(m.delivered), 0)::BIGINT AS delivered,
+               COALESCE(SUM(m.landed_inbox), 0)::BIGINT AS landed_inbox,
+               COALESCE(SUM(m.spam_foldered), 0)::BIGINT AS spam_foldered,
+               COALESCE(SUM(m.bounced), 0)::BIGINT AS bounced
+        FROM email_accounts ea
+        LEFT JOIN warmup_metrics m ON m.email_account_id = ea.id
+             AND m.metric_date > (NOW() AT TIME ZONE 'UTC')::date - $3
+        WHERE ea.id = $1
+        GROUP BY ea.id
+        "#
+    )
+    .bind(account_id)
+    .bind(WARMUP_TARGET_LIMIT)
+    .bind(WARMUP_STATS_WINDOW_DAYS)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Marks the warmup email with `message_id` as opened by `recipient_account_id`,
+/// whose poll found it in INBOX, and schedules the partner's reply unless it's
+/// already a reply. Returns false when it isn't a warmup email to that inbox.
+pub async fn record_warmup_opened(
+    pool: &PgPool,
+    recipient_account_id: Uuid,
+    message_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // The opened_at guard makes a re-polled message a no-op
+    let opened = sqlx::query_as::<_, OpenedWarmupEmail>(
+        r#"
+        UPDATE warmup_emails we SET opened_at = NOW()
+        FROM email_accounts sender
+        WHERE sender.id = we.sender_account_id
+          AND we.recipient_account_id = $1 AND we.message_id = $2 AND we.opened_at IS NULL
+        RETURNING we.id, we.in_reply_to_id, we.sender_account_id, sender.email AS sender_email, we.workspace_id
+        "#
+    )
+    .bind(recipient_account_id)
+    .bind(message_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(opened) = opened else {
+        return sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM warmup_emails WHERE recipient_account_id = $1 AND message_id = $2)"
+        )
+        .bind(recipient_account_id)
+        .bind(message_id)
+        .fetch_one(pool)
+        .await;
+    };
+
+    if opened.in_reply_to_id.is_none() {
+        let payload = WarmupEmailPayload {
+            email_account_id: recipient_account_id,
+            recipient_account_id: opened.sender_account_id,
+            target_email: opened.sender_email,
+            in_reply_to: Some(opened.id),
+        };
+        let (min_delay, max_delay) = WARMUP_REPLY_DELAY_MINUTES;
+        let delay = rand::thread_rng().gen_range(min_delay..=max_delay);
+
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, next_retry_at, retry_count, max_retries, priority)
+            VALUES (gen_random_uuid(), $1, '"WarmupEmail"', $2, 'scheduled', NOW(), NOW() + INTERVAL '1 minute' * $3, 0, 3, $4)
+            "#
+        )
+        .bind(opened.workspace_id)
+        .bind(serde_json::to_value(&payload).map_err(|e| sqlx::Error::Protocol(e.to_string()))?)
+        .bind(delay)
+        .bind(job_queue::PRIORITY_LOW)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(true)
 }
 
 impl WarmupService {
@@ -25,63 +333,167 @@ impl WarmupService {
     }
 
     pub async fn execute_warmup_cycle(&self) -> Result<(), String> {
-        // Get all warming inboxes
+        if let Err(e) = self.refresh_warmup_metrics().await {
+            eprintln!("Failed to refresh warmup metrics: {}", e);
+        }
+
+        // Start or break each inbox's healthy streak before judging graduation
+        sqlx::query(
+            r#"
+            UPDATE email_accounts
+            SET healthy_since = CASE
+                WHEN COALESCE(spam_rate, 0) <= $1 AND COALESCE(bounce_rate, 0) <= $2
+                    THEN COALESCE(healthy_since, NOW())
+                ELSE NULL
+            END
+            WHERE warmup_status IN ('warming', 'active')
+            "#
+        )
+        .bind(HEALTHY_SPAM_RATE)
+        .bind(HEALTHY_BOUNCE_RATE)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
         let inboxes = sqlx::query_as::<_, WarmingInbox>(
             r#"
-            SELECT id, email, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id
+            SELECT id, email, warmup_status, daily_limit, health_score, created_at, workspace_id,
+                   spam_rate, bounce_rate, healthy_since, warmup_started_at, warmup_daily_target
             FROM email_accounts 
-            WHERE warmup_status = 'warming'
+            WHERE warmup_status IN ('warming', 'active')
             "#
         )
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| e.to_string())?;
 
-        println!("Processing {} warming inboxes", inboxes.len());
+        println!("Processing {} warming and active inboxes", inboxes.len());
 
+        let now = Utc::now();
         for inbox in inboxes {
-            let target_volume = self.calculate_target_volume(&inbox);
-            let remaining = target_volume.saturating_sub(inbox.sent_today);
-            
-            if remaining > 0 {
-                println!("Inbox {} needs {} warmup emails today", inbox.email, remaining);
-                
-                // In production, you would send warmup emails to partner inboxes
-                // For now, we just track the progress
+            if inbox.warmup_status == "warming" {
                 if let Err(e) = self.update_warmup_progress(&inbox).await {
                     eprintln!("Failed to update warmup progress for {}: {}", inbox.email, e);
                 }
+                if let Err(e) = self.queue_warmup_send(&inbox).await {
+                    eprintln!("Failed to queue warmup email for {}: {}", inbox.email, e);
+                }
             }
 
-            // Check if warmup is complete
-            if self.is_warmup_complete(&inbox) {
-                if let Err(e) = self.mark_warmup_complete(inbox.id).await {
-                    eprintln!("Failed to mark warmup complete for {}: {}", inbox.email, e);
-                }
+            let result = match warmup_transition(&inbox, now) {
+                Some(WarmupTransition::Graduate) => self.graduate(&inbox).await,
+                Some(WarmupTransition::Demote) => self.demote(&inbox).await,
+                None => Ok(()),
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to update warmup status for {}: {}", inbox.email, e);
             }
         }
 
         Ok(())
     }
 
+    /// Rolls the last few days of warmup_emails up into warmup_metrics. Recent
+    /// days are recomputed whole, so running this every cycle is idempotent.
+    async fn refresh_warmup_metrics(&self) -> Result<u64, String> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO warmup_metrics (email_account_id, workspace_id, metric_date, delivered, landed_inbox, spam_foldered, bounced, updated_at)
+            SELECT sender_account_id, workspace_id, (sent_at AT TIME ZONE 'UTC')::date,
+                   COUNT(*) FILTER (WHERE bounced_at IS NULL),
+                   COUNT(*) FILTER (WHERE bounced_at IS NULL AND opened_at IS NOT NULL),
+                   COUNT(*) FILTER (WHERE bounced_at IS NULL AND opened_at IS NULL
+                                      AND sent_at < NOW() - INTERVAL '1 hour' * $1),
+                   COUNT(*) FILTER (WHERE bounced_at IS NOT NULL),
+                   NOW()
+            FROM warmup_emails
+            WHERE (sent_at AT TIME ZONE 'UTC')::date >= (NOW() AT TIME ZONE 'UTC')::date - $2
+            GROUP BY sender_account_id, workspace_id, (sent_at AT TIME ZONE 'UTC')::date
+            ON CONFLICT (email_account_id, metric_date) DO UPDATE SET
+                delivered = EXCLUDED.delivered,
+                landed_inbox = EXCLUDED.landed_inbox,
+                spam_foldered = EXCLUDED.spam_foldered,
+                bounced = EXCLUDED.bounced,
+                updated_at = NOW()
+            "#
+        )
+        .bind(SPAM_VERDICT_HOURS)
+        .bind(METRICS_REFRESH_DAYS)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(result.rows_affected())
+    }
+
     fn calculate_target_volume(&self, inbox: &WarmingInbox) -> i32 {
-        // Gradual increase based on days since creation
-        let days_active = (Utc::now() - inbox.created_at).num_days();
-        match days_active {
-            0..=2 => 5,
-            3..=5 => 10,
-            6..=9 => 15,
-            10..=14 => 20,
-            15..=21 => 30,
-            22..=28 => 40,
-            _ => 50,
-        }
+        warmup_ramp_volume((Utc::now() - inbox.ramp_start()).num_days(), inbox.target_volume())
     }
 
-    fn is_warmup_complete(&self, inbox: &WarmingInbox) -> bool {
-        let days_active = (Utc::now() - inbox.created_at).num_days();
-        // Warmup complete after 30 days with good health score
-        days_active >= 30 && inbox.health_score >= 90.0
+    /// Queues the inbox's next warmup email to a random partner in its workspace.
+    /// One goes out per cycle, so the day's volume is spread out rather than sent
+    /// in a burst, until the last 24 hours have reached the ramp volume.
+    async fn queue_warmup_send(&self, inbox: &WarmingInbox) -> Result<(), String> {
+        let Some(workspace_id) = inbox.workspace_id else {
+            return Ok(());
+        };
+
+        let (sent, queued): (i64, bool) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM warmup_emails
+                 WHERE sender_account_id = $1 AND in_reply_to_id IS NULL AND bounced_at IS NULL
+                   AND sent_at > NOW() - INTERVAL '1 day'),
+                EXISTS(SELECT 1 FROM jobs
+                       WHERE job_type = '"WarmupEmail"' AND status IN ('pending', 'processing', 'scheduled')
+                         AND payload->>'email_account_id' = $1::text AND payload->>'in_reply_to' IS NULL)
+            "#
+        )
+        .bind(inbox.id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if queued || sent >= self.calculate_target_volume(inbox) as i64 {
+            return Ok(());
+        }
+
+        // Inboxes whose OAuth grant failed can't receive our checks or reply
+        let pool = sqlx::query_as::<_, WarmupPartner>(
+            r#"
+            SELECT id, email FROM email_accounts
+            WHERE workspace_id = $1 AND warmup_status IN ('warming', 'active') AND auth_error IS NULL
+            "#
+        )
+        .bind(workspace_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let Some(partner) = pick_partner(inbox, &pool, &mut rand::thread_rng()).cloned() else {
+            return Ok(());
+        };
+
+        let payload = WarmupEmailPayload {
+            email_account_id: inbox.id,
+            recipient_account_id: partner.id,
+            target_email: partner.email,
+            in_reply_to: None,
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries, priority)
+            VALUES (gen_random_uuid(), $1, '"WarmupEmail"', $2, 'pending', NOW(), 0, 3, $3)
+            "#
+        )
+        .bind(workspace_id)
+        .bind(serde_json::to_value(&payload).map_err(|e| e.to_string())?)
+        .bind(job_queue::PRIORITY_LOW)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
     }
 
     async fn update_warmup_progress(&self, inbox: &WarmingInbox) -> Result<(), String> {
@@ -100,16 +512,107 @@ impl WarmupService {
         Ok(())
     }
 
-    async fn mark_warmup_complete(&self, inbox_id: Uuid) -> Result<(), String> {
+    /// Moves a warming inbox to active and raises it to its provider's full daily limit
+    async fn graduate(&self, inbox: &WarmingInbox) -> Result<(), String> {
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        // The status guard makes a concurrent cycle's second attempt a no-op
+        let daily_limit: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE email_accounts
+            SET warmup_status = 'active', daily_limit = GREATEST(COALESCE(provider_daily_limit, $2), $2)
+            WHERE id = $1 AND warmup_status = 'warming'
+            RETURNING daily_limit
+            "#
+        )
+        .bind(inbox.id)
+        .bind(inbox.target_volume())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let Some(daily_limit) = daily_limit else {
+            return Ok(());
+        };
+
+        let detail = format!(
+            "Healthy for {}+ days at {} emails/day; limit raised to {}",
+            SUSTAINED_HEALTHY_DAYS, inbox.target_volume(), daily_limit
+        );
+        self.record_event(&mut tx, inbox, "graduated", &detail, daily_limit).await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        println!("🎓 Inbox {} graduated from warmup, now active at {}/day", inbox.email, daily_limit);
+        Ok(())
+    }
+
+    /// Puts an active inbox whose rates regressed back on the ramp, part-way through
+    async fn demote(&self, inbox: &WarmingInbox) -> Result<(), String> {
+        let ramp_start = Utc::now() - Duration::days(DEMOTED_RAMP_DAY);
+        let demoted = WarmingInbox {
+            warmup_started_at: Some(ramp_start),
+            ..inbox.clone()
+        };
+        let daily_limit = self.calculate_target_volume(&demoted);
+
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE email_accounts
+            SET warmup_status = 'warming', daily_limit = $2, warmup_started_at = $3
+            WHERE id = $1 AND warmup_status = 'active'
+            "#
+        )
+        .bind(inbox.id)
+        .bind(daily_limit)
+        .bind(ramp_start)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if result.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let detail = format!(
+            "Spam rate {:.1}%, bounce rate {:.1}%; limit lowered to {}",
+            inbox.spam_rate.unwrap_or(0.0) * 100.0,
+            inbox.bounce_rate.unwrap_or(0.0) * 100.0,
+            daily_limit
+        );
+        self.record_event(&mut tx, inbox, "demoted", &detail, daily_limit).await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        println!("⬇️ Inbox {} demoted back to warming: {}", inbox.email, detail);
+        Ok(())
+    }
+
+    async fn record_event(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        inbox: &WarmingInbox,
+        event: &str,
+        detail: &str,
+        daily_limit: i32,
+    ) -> Result<(), String> {
         sqlx::query(
-            "UPDATE email_accounts SET warmup_status = 'active', daily_limit = 50 WHERE id = $1"
+            r#"
+            INSERT INTO inbox_warmup_events (email_account_id, workspace_id, event, detail, spam_rate, bounce_rate, daily_limit)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
         )
-        .bind(inbox_id)
-        .execute(self.pool.as_ref())
+        .bind(inbox.id)
+        .bind(inbox.workspace_id)
+        .bind(event)
+        .bind(detail)
+        .bind(inbox.spam_rate)
+        .bind(inbox.bounce_rate)
+        .bind(daily_limit)
+        .execute(&mut **tx)
         .await
         .map_err(|e| e.to_string())?;
 
-        println!("Inbox {} warmup complete, now active", inbox_id);
         Ok(())
     }
 
@@ -117,7 +620,8 @@ impl WarmupService {
         // Find inboxes with low health scores
         let risky_inboxes = sqlx::query_as::<_, WarmingInbox>(
             r#"
-            SELECT id, email, warmup_status, daily_limit, sent_today, health_score, created_at, workspace_id
+            SELECT id, email, warmup_status, daily_limit, health_score, created_at, workspace_id,
+                   spam_rate, bounce_rate, healthy_since, warmup_started_at, warmup_daily_target
             FROM email_accounts
             WHERE health_score < 75.0 
               AND warmup_status IN ('warming', 'active')
@@ -147,15 +651,46 @@ impl WarmupService {
         Ok(())
     }
 
-    pub async fn reset_daily_counters(&self) -> Result<(), String> {
-        // Reset sent_today for all inboxes (run at midnight)
-        sqlx::query("UPDATE email_accounts SET sent_today = 0")
+    /// Resets `sent_today` for every inbox whose local day has rolled over since
+    /// its last reset. Safe to call as often as needed; returns how many were reset.
+    pub async fn reset_daily_counters(&self) -> Result<u64, String> {
+        let now = Utc::now();
+        let accounts = sqlx::query_as::<_, CounterResetState>(
+            "SELECT id, timezone_offset_minutes, last_counter_reset_date FROM email_accounts"
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut reset = 0;
+        for account in accounts {
+            if !needs_counter_reset(now, account.timezone_offset_minutes, account.last_counter_reset_date) {
+                continue;
+            }
+
+            let today = local_date(now, account.timezone_offset_minutes);
+            // The date guard keeps concurrent workers from resetting the same inbox twice
+            let result = sqlx::query(
+                r#"
+                UPDATE email_accounts
+                SET sent_today = 0, last_counter_reset_date = $2
+                WHERE id = $1
+                  AND (last_counter_reset_date IS NULL OR last_counter_reset_date < $2)
+                "#
+            )
+            .bind(account.id)
+            .bind(today)
             .execute(self.pool.as_ref())
             .await
             .map_err(|e| e.to_string())?;
 
-        println!("Reset daily send counters for all inboxes");
-        Ok(())
+            reset += result.rows_affected();
+        }
+
+        if reset > 0 {
+            println!("Reset daily send counters for {} inboxes", reset);
+        }
+        Ok(reset)
     }
 
     pub async fn update_health_scores(&self) -> Result<(), String> {
@@ -179,3 +714,215 @@ impl WarmupService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_counter_reset_is_once_per_local_day() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 23, 30, 0).unwrap();
+
+        assert!(needs_counter_reset(now, None, None));
+        let first_reset = local_date(now, None);
+        // A second call later the same day is a no-op
+        assert!(!needs_counter_reset(now + Duration::minutes(20), None, Some(first_reset)));
+
+        // At UTC+2 it's already the next day, so that inbox is due
+        assert!(needs_counter_reset(now, Some(120), Some(first_reset)));
+        // At UTC-5 it's still the same day as the last reset
+        assert!(!needs_counter_reset(now, Some(-300), Some(first_reset)));
+    }
+
+    fn inbox(status: &str, daily_limit: i32, spam_rate: f64, healthy_days: Option<i64>, now: DateTime<Utc>) -> WarmingInbox {
+        WarmingInbox {
+            id: Uuid::nil(),
+            email: "sales@acme.io".to_string(),
+            warmup_status: status.to_string(),
+            daily_limit,
+            health_score: 95.0,
+            created_at: now - Duration::days(40),
+            workspace_id: None,
+            spam_rate: Some(spam_rate),
+            bounce_rate: Some(0.01),
+            healthy_since: healthy_days.map(|days| now - Duration::days(days)),
+            warmup_started_at: None,
+            warmup_daily_target: None,
+        }
+    }
+
+    #[test]
+    fn test_graduates_after_sustained_health_and_demotes_on_regression() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+
+        assert_eq!(warmup_transition(&inbox("warming", 50, 0.01, Some(7), now), now), Some(WarmupTransition::Graduate));
+        // Not at full volume yet, or not healthy for long enough
+        assert_eq!(warmup_transition(&inbox("warming", 40, 0.01, Some(10), now), now), None);
+        assert_eq!(warmup_transition(&inbox("warming", 50, 0.01, Some(6), now), now), None);
+        // Rates just went bad, even though the streak hasn't been cleared yet
+        assert_eq!(warmup_transition(&inbox("warming", 50, 0.03, Some(10), now), now), None);
+
+        assert_eq!(warmup_transition(&inbox("active", 500, 0.01, Some(30), now), now), None);
+        assert_eq!(warmup_transition(&inbox("active", 500, 0.03, None, now), now), Some(WarmupTransition::Demote));
+        assert_eq!(warmup_transition(&inbox("paused", 50, 0.03, None, now), now), None);
+    }
+
+    #[test]
+    fn test_ramp_starts_at_ten_and_adds_five_a_day_up_to_target() {
+        assert_eq!(warmup_ramp_volume(0, 50), 10);
+        assert_eq!(warmup_ramp_volume(1, 50), 15);
+        assert_eq!(warmup_ramp_volume(DEMOTED_RAMP_DAY, 50), 30);
+        assert_eq!(warmup_ramp_volume(8, 50), 50);
+        assert_eq!(warmup_ramp_volume(30, 50), 50);
+        // A lower or higher per-inbox target moves where the ramp stops
+        assert_eq!(warmup_ramp_volume(3, 20), 20);
+        assert_eq!(warmup_ramp_volume(18, 80), 80);
+        // A start time in the future (clock skew) is still day 0
+        assert_eq!(warmup_ramp_volume(-2, 50), 10);
+
+        let now = Utc::now();
+        let custom = WarmingInbox { warmup_daily_target: Some(80), ..inbox("warming", 80, 0.01, Some(7), now) };
+        assert_eq!(custom.target_volume(), 80);
+        assert_eq!(warmup_transition(&custom, now), Some(WarmupTransition::Graduate));
+        let short = WarmingInbox { warmup_daily_target: Some(80), ..inbox("warming", 50, 0.01, Some(7), now) };
+        assert_eq!(warmup_transition(&short, now), None);
+    }
+
+    #[test]
+    fn test_partner_is_never_the_sender() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let now = Utc::now();
+        let sender = WarmingInbox { id: Uuid::new_v4(), ..inbox("warming", 10, 0.0, None, now) };
+        let partner = |email: &str| WarmupPartner { id: Uuid::new_v4(), email: email.to_string() };
+        let me = WarmupPartner { id: sender.id, email: sender.email.clone() };
+        let pool = vec![me.clone(), partner("Sales@Acme.io"), partner("ops@acme.io"), partner("hello@acme.io")];
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut picked = std::collections::HashSet::new();
+        for _ in 0..100 {
+            let choice = pick_partner(&sender, &pool, &mut rng).expect("pool has other inboxes");
+            assert_ne!(choice.id, sender.id);
+            // Same address under another id (e.g. connected twice) doesn't count as a partner
+            assert!(!choice.email.eq_ignore_ascii_case(&sender.email));
+            picked.insert(choice.email.clone());
+        }
+        assert_eq!(picked.len(), 2);
+
+        assert!(pick_partner(&sender, &[me], &mut rng).is_none());
+        assert!(pick_partner(&sender, &[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_progress_follows_ramp_and_actual_volume() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let summary = |status: &str, days: i64, volume_24h: i64| WarmupSummary {
+            warmup_status: status.to_string(),
+            target_volume: 50,
+            ramp_start: now - Duration::days(days),
+            volume_24h,
+            delivered: 0,
+            landed_inbox: 0,
+            spam_foldered: 0,
+            bounced: 0,
+        };
+
+        // Day 4 of the ramp is 30/day: 60% once the inbox actually sends that many
+        assert_eq!(summary("warming", 4, 30).progress(now), 60.0);
+        // Sending less than the ramp allows, or more (e.g. replies), doesn't count
+        assert_eq!(summary("warming", 4, 10).progress(now), 20.0);
+        assert_eq!(summary("warming", 4, 45).progress(now), 60.0);
+        assert_eq!(summary("warming", 20, 50).progress(now), 100.0);
+        assert_eq!(summary("active", 1, 0).progress(now), 100.0);
+        assert_eq!(summary("warming", 0, 0).inbox_rate(), 0.0);
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_summary_rates_come_from_recent_metrics() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Warmup").await;
+        let account_id = test_db::inbox(&pool, workspace_id, 20).await;
+        sqlx::query("UPDATE email_accounts SET warmup_status = 'warming', warmup_started_at = NOW() - INTERVAL '2 days' WHERE id = $1")
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // (days ago, delivered, landed, spam, bounced); the last row is outside the window
+        for (days_ago, delivered, landed, spam, bounced) in [(0, 20, 12, 0, 0), (1, 15, 12, 3, 1), (2, 10, 6, 2, 1), (30, 50, 0, 50, 10)] {
+            sqlx::query(
+                r#"
+                INSERT INTO warmup_metrics (email_account_id, workspace_id, metric_date, delivered, landed_inbox, spam_foldered, bounced)
+                VALUES ($1, $2, (NOW() AT TIME ZONE 'UTC')::date - $3, $4, $5, $6, $7)
+                "#
+            )
+            .bind(account_id)
+            .bind(workspace_id)
+            .bind(days_ago)
+            .bind(delivered)
+            .bind(landed)
+            .bind(spam)
+            .bind(bounced)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let summary = warmup_summary(&pool, account_id).await.unwrap().expect("account exists");
+        assert_eq!((summary.delivered, summary.landed_inbox, summary.spam_foldered, summary.bounced), (45, 30, 5, 2));
+        assert_eq!(summary.target_volume, WARMUP_TARGET_LIMIT);
+        // 30 of the 35 placed emails reached the inbox; 2 of 47 attempts bounced
+        assert!((summary.inbox_rate() - 85.714).abs() < 0.01);
+        assert!((summary.spam_rate() - 14.286).abs() < 0.01);
+        assert!((summary.bounce_rate() - 4.255).abs() < 0.01);
+        // No warmup emails in the last day yet, so no progress credited
+        assert_eq!(summary.progress(Utc::now()), 0.0);
+
+        assert!(warmup_summary(&pool, Uuid::new_v4()).await.unwrap().is_none());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_counter_reset_runs_once_per_day_across_workers() {
+        let pool = Arc::new(test_db::pool().await);
+        let workspace_id = test_db::workspace(&pool, "Counter reset").await;
+        let account_id = test_db::inbox(&pool, workspace_id, 50).await;
+        sqlx::query("UPDATE email_accounts SET sent_today = 7, last_counter_reset_date = $2 WHERE id = $1")
+            .bind(account_id)
+            .bind(local_date(Utc::now(), None) - Duration::days(1))
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+
+        let counter = || async {
+            sqlx::query_as::<_, (i32, Option<NaiveDate>)>("SELECT sent_today, last_counter_reset_date FROM email_accounts WHERE id = $1")
+                .bind(account_id)
+                .fetch_one(pool.as_ref())
+                .await
+                .unwrap()
+        };
+
+        // Two workers polling in the same minute
+        let (first, second) = (WarmupService::new(pool.clone()), WarmupService::new(pool.clone()));
+        let (a, b) = tokio::join!(first.reset_daily_counters(), second.reset_daily_counters());
+        a.unwrap();
+        b.unwrap();
+        let after_reset = counter().await;
+
+        // Sends after the reset must survive the next poll the same day
+        sqlx::query("UPDATE email_accounts SET sent_today = 3 WHERE id = $1").bind(account_id).execute(pool.as_ref()).await.unwrap();
+        first.reset_daily_counters().await.unwrap();
+        let after_second_poll = counter().await;
+
+        test_db::delete_workspace(&pool, workspace_id).await;
+
+        let today = local_date(Utc::now(), None);
+        assert_eq!(after_reset, (0, Some(today)));
+        assert_eq!(after_second_poll, (3, Some(today)));
+    }
+}
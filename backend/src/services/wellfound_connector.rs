@@ -1,7 +1,9 @@
 use chrono::{NaiveDate, Utc};
 use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn, error};
+use std::sync::LazyLock;
+use tracing::{info, warn};
 
 use crate::models::signal::{
     ConfidenceFactors, CreateHiringSignal, CreateSignal, HiringSignal, Signal,
@@ -25,6 +27,53 @@ const SENIOR_KEYWORDS: &[&str] = &[
     "vp", "architect", "manager",
 ];
 
+// ============================================================================
+// Wellfound DOM Selectors
+// ============================================================================
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).unwrap()
+}
+
+static JOB_LISTINGS: LazyLock<Selector> = LazyLock::new(|| selector(r#"[data-test="JobListings"]"#));
+static NO_JOB_LISTINGS: LazyLock<Selector> = LazyLock::new(|| selector(r#"[data-test="JobListings-empty"]"#));
+static JOB_CARD: LazyLock<Selector> = LazyLock::new(|| selector(r#"[data-test="JobListing"]"#));
+static JOB_TITLE: LazyLock<Selector> = LazyLock::new(|| selector(r#"a[data-test="JobListing-title"]"#));
+static JOB_LOCATION: LazyLock<Selector> = LazyLock::new(|| selector(r#"[data-test="JobListing-location"]"#));
+static JOB_POSTED: LazyLock<Selector> = LazyLock::new(|| selector(r#"[data-test="JobListing-posted"]"#));
+static DEPARTMENT_HEADING: LazyLock<Selector> = LazyLock::new(|| selector("h2, h3, h4"));
+
+/// Visible text of an element with whitespace collapsed
+fn element_text(element: ElementRef) -> String {
+    element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Wellfound shows relative dates: "Posted today", "yesterday", "3 days ago", "2 weeks ago"
+fn parse_posted_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let text = text.to_lowercase();
+    if text.contains("today") || text.contains("just now") || text.contains("hour") || text.contains("minute") {
+        return Some(today);
+    }
+    if text.contains("yesterday") {
+        return today.pred_opt();
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let unit_idx = words.iter().position(|w| {
+        ["day", "days", "week", "weeks", "month", "months"].contains(w)
+    })?;
+    let count: i64 = match words.get(unit_idx.checked_sub(1)?)? {
+        &"a" | &"an" => 1,
+        n => n.trim_end_matches('+').parse().ok()?,
+    };
+    let days = match words[unit_idx].trim_end_matches('s') {
+        "day" => count,
+        "week" => count * 7,
+        _ => count * 30,
+    };
+    today.checked_sub_signed(chrono::Duration::days(days))
+}
+
 // ============================================================================
 // Job Posting Data (what we extract from Wellfound)
 // ============================================================================
@@ -75,9 +124,7 @@ impl WellfoundConnector {
         }
     }
 
-    /// Fetch job postings for a company from Wellfound
-    /// Note: This is a simplified implementation. In production, you'd need
-    /// to handle Wellfound's actual HTML structure or use their API if available.
+    /// Fetch job postings for a company from its Wellfound jobs page
     pub async fn fetch_company_jobs(
         &self,
         company_slug: &str,
@@ -96,7 +143,7 @@ impl WellfoundConnector {
         let html = response.text().await?;
         
         // Parse jobs from HTML
-        let jobs = self.parse_jobs_from_html(&html, company_slug)?;
+        let jobs = self.parse_jobs_from_html(&html, company_slug, Utc::now().date_naive())?;
         
         let web3_jobs = jobs.iter().filter(|j| j.is_web3_role).count() as i32;
 
@@ -110,49 +157,75 @@ impl WellfoundConnector {
         })
     }
 
-    /// Parse job postings from Wellfound HTML
-    /// This is a simplified parser - in production you'd use a proper HTML parser
+    /// Parse the job cards on a Wellfound company jobs page. A page without the job
+    /// listings container (and without the empty-state marker) means the layout changed,
+    /// which is reported as an error rather than as a company with no open roles.
     fn parse_jobs_from_html(
         &self,
         html: &str,
         company_slug: &str,
+        today: NaiveDate,
     ) -> Result<Vec<JobPosting>, Box<dyn std::error::Error + Send + Sync>> {
+        let document = Html::parse_document(html);
+
+        let Some(container) = document.select(&JOB_LISTINGS).next() else {
+            if document.select(&NO_JOB_LISTINGS).next().is_some() {
+                return Ok(Vec::new());
+            }
+            return Err(format!(
+                "Wellfound page for {} has no job listings container; the page layout may have changed",
+                company_slug
+            )
+            .into());
+        };
+
         let mut jobs = Vec::new();
 
-        // Look for job titles in the HTML
-        // Wellfound typically has job listings in specific div structures
-        // This is a heuristic approach - would need refinement based on actual HTML
-
-        // Simple regex-like extraction for job titles
-        // In production, use scraper or select.rs crate
-        let title_patterns = [
-            "Senior Solidity Developer",
-            "Blockchain Engineer",
-            "Smart Contract Developer",
-            "Protocol Engineer",
-            "DeFi Developer",
-            "Web3 Frontend Engineer",
-            "Rust Engineer",
-            "Backend Engineer",
-            "Full Stack Developer",
-            "DevOps Engineer",
-            "Product Manager",
-            "Engineering Manager",
-        ];
-
-        // Check if any job-related content exists
-        let has_jobs = html.contains("job") || html.contains("career") || html.contains("position");
-        
-        if !has_jobs {
-            return Ok(jobs);
-        }
+        for (idx, card) in container.select(&JOB_CARD).enumerate() {
+            let Some(link) = card.select(&JOB_TITLE).next() else {
+                continue;
+            };
+            let title = element_text(link);
+            if title.is_empty() {
+                continue;
+            }
+
+            let mut job = self.create_job_from_title(&title, company_slug, idx);
+            let href = link.value().attr("href").unwrap_or_default();
+
+            // "/jobs/2891234-senior-solidity-engineer" -> "2891234"
+            if let Some(job_id) = href
+                .rsplit('/')
+                .next()
+                .and_then(|slug| slug.split('-').next())
+                .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            {
+                job.id = job_id.to_string();
+            }
+            if href.starts_with('/') {
+                job.url = format!("https://wellfound.com{}", href);
+            }
 
-        // For MVP: Create jobs based on detected patterns in HTML
-        for (idx, pattern) in title_patterns.iter().enumerate() {
-            if html.to_lowercase().contains(&pattern.to_lowercase()) {
-                let job = self.create_job_from_title(pattern, company_slug, idx);
-                jobs.push(job);
+            job.location = card.select(&JOB_LOCATION).next().map(element_text).filter(|l| !l.is_empty());
+            job.posted_date = card
+                .select(&JOB_POSTED)
+                .next()
+                .and_then(|posted| parse_posted_date(&element_text(posted), today));
+            job.job_type = None;
+
+            // Listings are grouped under department headings; fall back to the title guess
+            if let Some(department) = card
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|e| e.value().attr("data-test") == Some("JobListings-department"))
+                .and_then(|group| group.select(&DEPARTMENT_HEADING).next())
+                .map(element_text)
+                .filter(|d| !d.is_empty())
+            {
+                job.department = Some(department);
             }
+
+            jobs.push(job);
         }
 
         Ok(jobs)
@@ -412,3 +485,50 @@ impl WellfoundConnector {
         self.create_signals(pool, company_id, &company_jobs).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/wellfound_company_jobs.html");
+
+    #[test]
+    fn test_parses_job_cards_from_saved_page() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let jobs = WellfoundConnector::new()
+            .parse_jobs_from_html(FIXTURE, "acme-labs", today)
+            .unwrap();
+
+        // Titles only mentioned in the footer are not jobs
+        let titles: Vec<&str> = jobs.iter().map(|j| j.title.as_str()).collect();
+        assert_eq!(titles, ["Senior Solidity Engineer", "Backend Engineer", "Product Marketing Lead"]);
+
+        let solidity = &jobs[0];
+        assert_eq!(solidity.id, "2891234");
+        assert_eq!(solidity.url, "https://wellfound.com/jobs/2891234-senior-solidity-engineer");
+        assert_eq!(solidity.location.as_deref(), Some("Remote • United States"));
+        assert_eq!(solidity.department.as_deref(), Some("Engineering"));
+        assert_eq!(solidity.posted_date, Some(today));
+        assert!(solidity.is_web3_role);
+        assert_eq!(solidity.experience_level.as_deref(), Some("senior"));
+
+        assert_eq!(jobs[1].posted_date, NaiveDate::from_ymd_opt(2024, 3, 12));
+        assert!(!jobs[1].is_web3_role);
+
+        assert_eq!(jobs[2].department.as_deref(), Some("Growth"));
+        assert_eq!(jobs[2].location, None);
+        assert_eq!(jobs[2].posted_date, NaiveDate::from_ymd_opt(2024, 3, 1));
+    }
+
+    #[test]
+    fn test_missing_job_container_is_an_error() {
+        let connector = WellfoundConnector::new();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let changed_layout = "<html><body><div class=\"jobs\">Blockchain Engineer</div></body></html>";
+        assert!(connector.parse_jobs_from_html(changed_layout, "acme-labs", today).is_err());
+
+        let no_openings = r#"<html><body><div data-test="JobListings-empty">No open positions</div></body></html>"#;
+        assert!(connector.parse_jobs_from_html(no_openings, "acme-labs", today).unwrap().is_empty());
+    }
+}
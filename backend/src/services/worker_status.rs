@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Window the throughput figures cover
+pub const THROUGHPUT_WINDOW_MINUTES: i32 = 60;
+
+/// A periodic worker cycle whose last run is recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerTask {
+    /// Claiming and running queued jobs, every ~5 seconds
+    JobLoop,
+    /// Activating scheduled campaigns and queueing sends
+    Scheduler,
+    Warmup,
+    AutoPause,
+}
+
+impl WorkerTask {
+    pub const ALL: [WorkerTask; 4] = [WorkerTask::JobLoop, WorkerTask::Scheduler, WorkerTask::Warmup, WorkerTask::AutoPause];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkerTask::JobLoop => "job_loop",
+            WorkerTask::Scheduler => "scheduler",
+            WorkerTask::Warmup => "warmup",
+            WorkerTask::AutoPause => "auto_pause",
+        }
+    }
+}
+
+/// Notes that a cycle finished, successfully when `error` is `None`
+pub async fn record_cycle(pool: &PgPool, task: WorkerTask, error: Option<&str>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO worker_heartbeats (task, last_success_at, last_error_at, last_error, updated_at)
+        VALUES ($1, CASE WHEN $2::text IS NULL THEN NOW() END, CASE WHEN $2::text IS NOT NULL THEN NOW() END, $2, NOW())
+        ON CONFLICT (task) DO UPDATE SET
+            last_success_at = COALESCE(EXCLUDED.last_success_at, worker_heartbeats.last_success_at),
+            last_error_at = COALESCE(EXCLUDED.last_error_at, worker_heartbeats.last_error_at),
+            last_error = COALESCE(EXCLUDED.last_error, worker_heartbeats.last_error),
+            updated_at = NOW()
+        "#
+    )
+    .bind(task.as_str())
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Jobs by status, and what the worker got through in the last hour
+#[derive(Debug, Default, PartialEq, Serialize, sqlx::FromRow)]
+pub struct JobCounts {
+    pub pending: i64,
+    /// Deferred or waiting to retry
+    pub scheduled: i64,
+    pub processing: i64,
+    /// Out of retries
+    pub failed: i64,
+    pub completed_last_hour: i64,
+    /// Attempts that failed, including ones that will be retried
+    pub failed_last_hour: i64,
+}
+
+impl JobCounts {
+    /// Share of last hour's finished attempts that failed, 0 when there were none
+    pub fn failure_rate(&self) -> f64 {
+        let finished = self.completed_last_hour + self.failed_last_hour;
+        if finished == 0 {
+            0.0
+        } else {
+            self.failed_last_hour as f64 / finished as f64
+        }
+    }
+}
+
+pub async fn job_counts<'e>(executor: impl sqlx::PgExecutor<'e>) -> Result<JobCounts, sqlx::Error> {
+    sqlx::query_as::<_, JobCounts>(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE status = 'pending') AS pending,
+            COUNT(*) FILTER (WHERE status = 'scheduled') AS scheduled,
+            COUNT(*) FILTER (WHERE status = 'processing') AS processing,
+            COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+            COUNT(*) FILTER (WHERE status = 'completed' AND completed_at >= NOW() - make_interval(mins => $1)) AS completed_last_hour,
+            COUNT(*) FILTER (WHERE failed_at >= NOW() - make_interval(mins => $1)) AS failed_last_hour
+        FROM jobs
+        WHERE status IN ('pending', 'scheduled', 'processing', 'failed')
+           OR completed_at >= NOW() - make_interval(mins => $1)
+           OR failed_at >= NOW() - make_interval(mins => $1)
+        "#
+    )
+    .bind(THROUGHPUT_WINDOW_MINUTES)
+    .fetch_one(executor)
+    .await
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CycleStatus {
+    pub task: String,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkerStatus {
+    pub jobs: JobCounts,
+    pub failure_rate: f64,
+    /// Every cycle, including ones that have never run
+    pub cycles: Vec<CycleStatus>,
+}
+
+pub async fn status(pool: &PgPool) -> Result<WorkerStatus, sqlx::Error> {
+    let jobs = job_counts(pool).await?;
+
+    let mut heartbeats = sqlx::query_as::<_, CycleStatus>(
+        "SELECT task, last_success_at, last_error_at, last_error FROM worker_heartbeats"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let cycles = WorkerTask::ALL
+        .iter()
+        .map(|task| match heartbeats.iter().position(|h| h.task == task.as_str()) {
+            Some(at) => heartbeats.swap_remove(at),
+            None => CycleStatus {
+                task: task.as_str().to_string(),
+                last_success_at: None,
+                last_error_at: None,
+                last_error: None,
+            },
+        })
+        .collect();
+
+    Ok(WorkerStatus {
+        failure_rate: jobs.failure_rate(),
+        jobs,
+        cycles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+    use uuid::Uuid;
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_job_counts_by_status_and_last_hour() {
+        let pool = test_db::pool().await;
+        // One snapshot for both counts, so jobs other tests add meanwhile don't show
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ").execute(&mut *tx).await.unwrap();
+        let before = job_counts(&mut *tx).await.unwrap();
+
+        for (status, completed_ago, failed_ago) in [
+            ("pending", None, None),
+            ("pending", None, None),
+            ("scheduled", None, Some(5)),
+            ("processing", None, None),
+            ("failed", None, Some(10)),
+            ("failed", None, Some(180)),
+            ("completed", Some(5), None),
+            ("completed", Some(30), Some(40)),
+            ("completed", Some(120), None),
+        ] {
+            sqlx::query(
+                "INSERT INTO jobs (id, job_type, status, completed_at, failed_at)
+                 VALUES ($1, 'send_email', $2, NOW() - make_interval(mins => $3), NOW() - make_interval(mins => $4))"
+            )
+            .bind(Uuid::new_v4())
+            .bind(status)
+            .bind(completed_ago)
+            .bind(failed_ago)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+
+        let after = job_counts(&mut *tx).await.unwrap();
+        tx.rollback().await.unwrap();
+
+        let added = JobCounts {
+            pending: after.pending - before.pending,
+            scheduled: after.scheduled - before.scheduled,
+            processing: after.processing - before.processing,
+            failed: after.failed - before.failed,
+            completed_last_hour: after.completed_last_hour - before.completed_last_hour,
+            failed_last_hour: after.failed_last_hour - before.failed_last_hour,
+        };
+        assert_eq!(added, JobCounts {
+            pending: 2,
+            scheduled: 1,
+            processing: 1,
+            failed: 2,
+            completed_last_hour: 2,
+            failed_last_hour: 3,
+        });
+        assert_eq!(JobCounts { completed_last_hour: 3, failed_last_hour: 1, ..Default::default() }.failure_rate(), 0.25);
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::workspace::WorkspaceRole;
+
+/// A workspace the user belongs to, with their role in it
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct WorkspaceMembership {
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub role: Option<String>,
+    pub joined_at: Option<DateTime<Utc>>,
+}
+
+impl WorkspaceMembership {
+    /// Unrecognised or missing roles get the least access
+    pub fn workspace_role(&self) -> WorkspaceRole {
+        self.role.as_deref().and_then(WorkspaceRole::parse).unwrap_or(WorkspaceRole::Viewer)
+    }
+}
+
+/// Outcome of checking a caller's role in a workspace
+#[derive(Debug, PartialEq)]
+pub enum RoleCheck {
+    Allowed(WorkspaceRole),
+    NotMember,
+    Insufficient(WorkspaceRole),
+}
+
+const MEMBERSHIP_COLUMNS: &str = r#"
+    SELECT w.id AS workspace_id, w.name, w.slug, wm.role, wm.joined_at
+    FROM workspaces w
+    INNER JOIN workspace_members wm ON w.id = wm.workspace_id
+"#;
+
+/// Every workspace the user is a member of, oldest membership first (the one login picks)
+pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<WorkspaceMembership>, sqlx::Error> {
+    sqlx::query_as::<_, WorkspaceMembership>(&format!(
+        "{} WHERE wm.user_id = $1 ORDER BY wm.joined_at ASC, w.name ASC",
+        MEMBERSHIP_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// The user's membership of one workspace; `None` when they don't belong to it
+pub async fn find(pool: &PgPool, user_id: Uuid, workspace_id: Uuid) -> Result<Option<WorkspaceMembership>, sqlx::Error> {
+    sqlx::query_as::<_, WorkspaceMembership>(&format!(
+        "{} WHERE wm.user_id = $1 AND w.id = $2",
+        MEMBERSHIP_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Whether the user holds at least `min_role` in the workspace
+pub async fn check_role(
+    pool: &PgPool,
+    user_id: Uuid,
+    workspace_id: Uuid,
+    min_role: &WorkspaceRole,
+) -> Result<RoleCheck, sqlx::Error> {
+    let Some(membership) = find(pool, user_id, workspace_id).await? else {
+        return Ok(RoleCheck::NotMember);
+    };
+
+    let role = membership.workspace_role();
+    Ok(if role.is_at_least(min_role) {
+        RoleCheck::Allowed(role)
+    } else {
+        RoleCheck::Insufficient(role)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    async fn add_member(pool: &PgPool, workspace_id: Uuid, user_id: Uuid, role: &str, joined_days_ago: i32) {
+        sqlx::query(
+            "INSERT INTO workspace_members (workspace_id, user_id, role, joined_at) VALUES ($1, $2, $3, NOW() - INTERVAL '1 day' * $4)",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .bind(role)
+        .bind(joined_days_ago)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_two_workspace_user_switches_back_and_forth() {
+        let pool = test_db::pool().await;
+        let (user_id, _) = test_db::user(&pool, "hash").await;
+
+        let agency = test_db::workspace(&pool, "Agency").await;
+        let client = test_db::workspace(&pool, "Client").await;
+        let stranger = test_db::workspace(&pool, "Stranger").await;
+        add_member(&pool, agency, user_id, "owner", 10).await;
+        add_member(&pool, client, user_id, "member", 2).await;
+
+        let listed: Vec<Uuid> = list_for_user(&pool, user_id).await.unwrap().iter().map(|m| m.workspace_id).collect();
+        assert_eq!(listed, vec![agency, client]);
+
+        for (target, role) in [(client, "member"), (agency, "owner"), (client, "member")] {
+            let membership = find(&pool, user_id, target).await.unwrap().expect("member of both");
+            assert_eq!(membership.workspace_id, target);
+            assert_eq!(membership.role.as_deref(), Some(role));
+        }
+
+        assert!(find(&pool, user_id, stranger).await.unwrap().is_none());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_viewer_cannot_mutate_but_owner_can() {
+        let pool = test_db::pool().await;
+        let workspace_id = test_db::workspace(&pool, "Roles").await;
+
+        let mut users = Vec::new();
+        for role in ["owner", "viewer"] {
+            let (user_id, _) = test_db::user(&pool, "hash").await;
+            add_member(&pool, workspace_id, user_id, role, 0).await;
+            users.push(user_id);
+        }
+        let (owner, viewer) = (users[0], users[1]);
+
+        // DELETE /campaigns/{id} and the other mutating routes require at least a member
+        assert_eq!(
+            check_role(&pool, viewer, workspace_id, &WorkspaceRole::Member).await.unwrap(),
+            RoleCheck::Insufficient(WorkspaceRole::Viewer)
+        );
+        assert_eq!(
+            check_role(&pool, owner, workspace_id, &WorkspaceRole::Member).await.unwrap(),
+            RoleCheck::Allowed(WorkspaceRole::Owner)
+        );
+        assert_eq!(
+            check_role(&pool, viewer, workspace_id, &WorkspaceRole::Viewer).await.unwrap(),
+            RoleCheck::Allowed(WorkspaceRole::Viewer)
+        );
+        assert_eq!(
+            check_role(&pool, viewer, test_db::workspace(&pool, "Other").await, &WorkspaceRole::Viewer).await.unwrap(),
+            RoleCheck::NotMember
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use chrono::{DateTime, Utc};
+use reqwest::{redirect, Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Attempts per delivery before the job is left failed
+const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+
+/// Events a Zapier subscription can listen for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZapierEvent {
+    ReplyInterested,
+    MeetingCreated,
+    MeetingReminder,
+}
+
+impl ZapierEvent {
+    pub const ALL: [ZapierEvent; 3] = [
+        ZapierEvent::ReplyInterested,
+        ZapierEvent::MeetingCreated,
+        ZapierEvent::MeetingReminder,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZapierEvent::ReplyInterested => "reply.interested",
+            ZapierEvent::MeetingCreated => "meeting.created",
+            ZapierEvent::MeetingReminder => "meeting.reminder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+/// Flat payload posted to subscribers. Every field is always present so Zapier
+/// can map them; `id` lets it deduplicate retried deliveries.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ZapierPayload {
+    pub id: Uuid,
+    pub event: String,
+    pub lead_email: String,
+    pub lead_name: String,
+    pub company: String,
+    pub campaign: String,
+    /// Reply text for `reply.interested`, meeting title for the meeting events
+    pub message: String,
+    pub meeting_at: Option<DateTime<Utc>>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Payload plus the campaign used to match campaign-scoped subscriptions
+#[derive(sqlx::FromRow)]
+struct EventRow {
+    #[sqlx(flatten)]
+    payload: ZapierPayload,
+    campaign_id: Option<Uuid>,
+}
+
+/// Job payload for one delivery attempt. The hook URL is read from the
+/// subscription when the job runs, so deleted subscriptions get nothing.
+#[derive(Debug, Deserialize)]
+pub struct ZapierDeliveryPayload {
+    pub subscription_id: Uuid,
+    pub body: serde_json::Value,
+}
+
+/// Hook URLs must be absolute https URLs
+pub fn is_valid_target_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| url.scheme() == "https" && url.host_str().is_some_and(|h| !h.is_empty()))
+}
+
+/// Whether `ip` is on the public internet. Hooks are user-supplied, so they must
+/// not reach loopback, private networks or cloud metadata endpoints.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || a >= 240
+        // Carrier-grade NAT, IETF protocol assignments and benchmarking
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b)))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local, link-local, documentation and NAT64
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && second == 0x0db8)
+        || (first == 0x0064 && second == 0xff9b))
+}
+
+/// Resolves the hook's host and returns its addresses, refusing the URL unless
+/// it is a valid target and every address it resolves to is public.
+pub async fn resolve_target(target_url: &str) -> Result<(Url, Vec<SocketAddr>), String> {
+    if !is_valid_target_url(target_url) {
+        return Err("Hook URL must be an https URL".to_string());
+    }
+    let url = Url::parse(target_url).map_err(|e| e.to_string())?;
+    let host = url.host_str().unwrap_or_default().trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Could not resolve hook host: {}", e))?
+        .collect();
+
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err("Hook URL must point to a public address".to_string());
+    }
+
+    Ok((url, addrs))
+}
+
+/// Example payload for the test trigger, so users can map fields before a real event
+pub fn sample_payload(event: ZapierEvent) -> ZapierPayload {
+    let (message, meeting_at) = match event {
+        ZapierEvent::ReplyInterested => (
+            "Thanks for reaching out - this sounds interesting. Do you have time for a call next week?",
+            None,
+        ),
+        ZapierEvent::MeetingCreated | ZapierEvent::MeetingReminder => ("Intro call with Jane Doe", Some(Utc::now())),
+    };
+
+    ZapierPayload {
+        id: Uuid::nil(),
+        event: event.as_str().to_string(),
+        lead_email: "jane.doe@example.com".to_string(),
+        lead_name: "Jane Doe".to_string(),
+        company: "Example Inc".to_string(),
+        campaign: "Sample campaign".to_string(),
+        message: message.to_string(),
+        meeting_at,
+        occurred_at: Utc::now(),
+    }
+}
+
+/// Queues `reply.interested` deliveries for a reply classified as interested
+pub async fn emit_reply_interested(pool: &PgPool, workspace_id: Uuid, reply_id: Uuid) -> Result<u64, sqlx::Error> {
+    let row = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT
+            er.id,
+            er.campaign_id,
+            'reply.interested' as event,
+            er.from_email as lead_email,
+            COALESCE(NULLIF(TRIM(CONCAT(l.first_name, ' ', l.last_name)), ''), er.from_name, '') as lead_name,
+            COALESCE(l.company, '') as company,
+            COALESCE(c.name, '') as campaign,
+            COALESCE(er.body_text, '') as message,
+            NULL::timestamptz as meeting_at,
+            COALESCE(er.classified_at, NOW()) as occurred_at
+        FROM email_replies er
+        LEFT JOIN leads l ON er.lead_id = l.id
+        LEFT JOIN campaigns c ON er.campaign_id = c.id
+        WHERE er.id = $1 AND er.workspace_id = $2
+        "#
+    )
+    .bind(reply_id)
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => enqueue_deliveries(pool, workspace_id, ZapierEvent::ReplyInterested, row.campaign_id, &row.payload).await,
+        None => Ok(0),
+    }
+}
+
+/// Queues `meeting.created` deliveries for a newly booked meeting
+pub async fn emit_meeting_created(pool: &PgPool, workspace_id: Uuid, meeting_id: Uuid) -> Result<u64, sqlx::Error> {
+    emit_meeting_event(pool, workspace_id, meeting_id, ZapierEvent::MeetingCreated, None).await
+}
+
+/// Queues `meeting.reminder` deliveries alongside a reminder email to the lead.
+/// The payload id is the reminder's, so Zapier doesn't dedupe the 1h reminder
+/// against the 24h one.
+pub async fn emit_meeting_reminder(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    meeting_id: Uuid,
+    reminder_id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    emit_meeting_event(pool, workspace_id, meeting_id, ZapierEvent::MeetingReminder, Some(reminder_id)).await
+}
+
+async fn emit_meeting_event(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    meeting_id: Uuid,
+    event: ZapierEvent,
+    payload_id: Option<Uuid>,
+) -> Result<u64, sqlx::Error> {
+    let row = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT
+            COALESCE($4::uuid, m.id) as id,
+            m.campaign_id,
+            $3 as event,
+            COALESCE(l.email, er.from_email, '') as lead_email,
+            COALESCE(NULLIF(TRIM(CONCAT(l.first_name, ' ', l.last_name)), ''), er.from_name, '') as lead_name,
+            COALESCE(l.company, '') as company,
+            COALESCE(c.name, '') as campaign,
+            COALESCE(m.title, '') as message,
+            m.scheduled_at as meeting_at,
+            COALESCE(m.created_at, NOW()) as occurred_at
+        FROM meetings m
+        LEFT JOIN leads l ON m.lead_id = l.id
+        LEFT JOIN email_replies er ON m.reply_id = er.id
+        LEFT JOIN campaigns c ON m.campaign_id = c.id
+        WHERE m.id = $1 AND m.workspace_id = $2
+        "#
+    )
+    .bind(meeting_id)
+    .bind(workspace_id)
+    .bind(event.as_str())
+    .bind(payload_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => enqueue_deliveries(pool, workspace_id, event, row.campaign_id, &row.payload).await,
+        None => Ok(0),
+    }
+}
+
+/// One delivery job per matching subscription: those for every campaign, plus
+/// those scoped to the event's campaign.
+async fn enqueue_deliveries(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    event: ZapierEvent,
+    campaign_id: Option<Uuid>,
+    payload: &ZapierPayload,
+) -> Result<u64, sqlx::Error> {
+    let body = serde_json::to_value(payload).unwrap_or_default();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO jobs (id, workspace_id, job_type, payload, status, created_at, retry_count, max_retries)
+        SELECT
+            gen_random_uuid(), s.workspace_id, '"DeliverZapierHook"',
+            jsonb_build_object('subscription_id', s.id, 'body', $4::jsonb),
+            'pending', NOW(), 0, $5
+        FROM zapier_subscriptions s
+        WHERE s.workspace_id = $1 AND s.event = $2
+          AND (s.campaign_id IS NULL OR s.campaign_id = $3)
+        "#
+    )
+    .bind(workspace_id)
+    .bind(event.as_str())
+    .bind(campaign_id)
+    .bind(body)
+    .bind(MAX_DELIVERY_ATTEMPTS)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Result of posting to a hook URL
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Delivered,
+    /// 410 Gone: the Zap was turned off or deleted, so the subscription should go
+    Gone,
+    /// The subscription was deleted after the job was queued, so nothing was sent
+    Unsubscribed,
+}
+
+/// Posts `body` to the hook. The client connects only to the addresses checked
+/// by `resolve_target` and doesn't follow redirects, so a hook can't be pointed
+/// at an internal host after it was accepted. Any non-2xx status other than 410
+/// is an error so the job queue retries it.
+pub async fn post(target_url: &str, body: &serde_json::Value) -> Result<DeliveryOutcome, String> {
+    let (url, addrs) = resolve_target(target_url).await?;
+
+    let mut client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(redirect::Policy::none());
+    if let Some(domain) = url.domain() {
+        client = client.resolve_to_addrs(domain, &addrs);
+    }
+    let client = client.build().map_err(|e| format!("Could not build hook client: {}", e))?;
+
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("Hook request failed: {}", e))?;
+
+    match response.status() {
+        StatusCode::GONE => Ok(DeliveryOutcome::Gone),
+        status if status.is_success() => Ok(DeliveryOutcome::Delivered),
+        status => Err(format!("Hook returned {}", status)),
+    }
+}
+
+/// Worker entry point for a `DeliverZapierHook` job
+pub async fn deliver(pool: &PgPool, payload: &ZapierDeliveryPayload) -> Result<DeliveryOutcome, String> {
+    let target_url: Option<String> = sqlx::query_scalar("SELECT target_url FROM zapier_subscriptions WHERE id = $1")
+        .bind(payload.subscription_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(target_url) = target_url else {
+        return Ok(DeliveryOutcome::Unsubscribed);
+    };
+
+    let outcome = post(&target_url, &payload.body).await?;
+
+    if outcome == DeliveryOutcome::Gone {
+        sqlx::query("DELETE FROM zapier_subscriptions WHERE id = $1")
+            .bind(payload.subscription_id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_and_target_urls() {
+        assert_eq!(ZapierEvent::parse("reply.interested"), Some(ZapierEvent::ReplyInterested));
+        assert_eq!(ZapierEvent::parse("meeting.created"), Some(ZapierEvent::MeetingCreated));
+        assert_eq!(ZapierEvent::parse("reply.negative"), None);
+
+        assert!(is_valid_target_url("https://hooks.zapier.com/hooks/catch/123/abc/"));
+        assert!(!is_valid_target_url("http://hooks.zapier.com/hooks/catch/123/abc/"));
+        assert!(!is_valid_target_url("hooks.zapier.com/hooks/catch"));
+        assert!(!is_valid_target_url("file:///etc/passwd"));
+    }
+
+    #[test]
+    fn test_only_public_addresses_are_hook_targets() {
+        for ip in ["34.200.10.1", "2600:1f18::1"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.5",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_internal_hosts_are_refused() {
+        assert!(resolve_target("https://127.0.0.1/hook").await.is_err());
+        assert!(resolve_target("https://[::1]/hook").await.is_err());
+        assert!(resolve_target("https://localhost/hook").await.is_err());
+        assert!(resolve_target("https://169.254.169.254/latest/meta-data").await.is_err());
+        assert!(resolve_target("http://34.200.10.1/hook").await.is_err());
+        assert!(resolve_target("https://34.200.10.1/hook").await.is_ok());
+    }
+
+    /// Needs a migrated database: `DATABASE_URL=... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL"]
+    async fn test_jobs_for_deleted_subscriptions_are_dropped() {
+        let pool = crate::test_db::pool().await;
+        let payload = ZapierDeliveryPayload {
+            subscription_id: Uuid::new_v4(),
+            body: serde_json::json!({ "event": "reply.interested" }),
+        };
+
+        assert_eq!(deliver(&pool, &payload).await, Ok(DeliveryOutcome::Unsubscribed));
+    }
+}
@@ -0,0 +1,128 @@
+//! Fixtures for tests that run against a migrated database. Those tests are
+//! `#[ignore]`d; run them with `DATABASE_URL=... cargo test -- --ignored`.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn pool() -> PgPool {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for database tests");
+    PgPool::connect(&url).await.unwrap()
+}
+
+/// Creates a workspace with a unique slug. Deleting it cascades to the
+/// campaigns, leads and inboxes a test adds under it.
+pub async fn workspace(pool: &PgPool, name: &str) -> Uuid {
+    sqlx::query_scalar("INSERT INTO workspaces (name, slug) VALUES ($1, $2) RETURNING id")
+        .bind(name)
+        .bind(format!("{}-{}", name.to_lowercase().replace(' ', "-"), Uuid::new_v4()))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// Deletes the workspace and everything under it, including the dashboard
+/// tables whose workspace_id has no foreign key to cascade from.
+pub async fn delete_workspace(pool: &PgPool, workspace_id: Uuid) {
+    for table in [
+        "email_replies",
+        "meetings",
+        "auto_pause_events",
+        "campaign_costs",
+        "inbox_health_metrics",
+        "inbox_warmup_events",
+        "workspace_settings",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE workspace_id = $1", table))
+            .bind(workspace_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("DELETE FROM workspaces WHERE id = $1")
+        .bind(workspace_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Creates a user with a unique address and returns its id and email.
+pub async fn user(pool: &PgPool, password_hash: &str) -> (Uuid, String) {
+    let email = format!("{}@example.com", Uuid::new_v4());
+    let id = sqlx::query_scalar(
+        "INSERT INTO users (id, email, password_hash, name, role) VALUES ($1, $2, $3, 'Test User', 'user') RETURNING id",
+    )
+    .bind(Uuid::new_v4())
+    .bind(&email)
+    .bind(password_hash)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    (id, email)
+}
+
+pub async fn delete_user(pool: &PgPool, user_id: Uuid) {
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Creates an active, healthy inbox that can send whenever the test runs: its
+/// send window is open around the clock and its counter was already reset
+/// today, so a reset running alongside leaves it alone. Tests that need other
+/// columns set them with an UPDATE.
+pub async fn inbox(pool: &PgPool, workspace_id: Uuid, daily_limit: i32) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO email_accounts (id, workspace_id, email, provider, smtp_host, smtp_port, smtp_username, warmup_status,
+                                    daily_limit, sent_today, health_score, send_window_start, send_window_end,
+                                    last_counter_reset_date)
+        VALUES ($1, $2, $3, 'gmail', 'smtp.example.com', 587, $3, 'active', $4, 0, 100.0, '00:00', '00:00',
+                (NOW() AT TIME ZONE 'UTC')::date)
+        "#,
+    )
+    .bind(id)
+    .bind(workspace_id)
+    .bind(format!("sender-{}@example.com", id))
+    .bind(daily_limit)
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
+
+/// Creates a campaign in the given status.
+pub async fn campaign(pool: &PgPool, workspace_id: Uuid, status: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO campaigns (id, workspace_id, name, vertical, status) VALUES ($1, $2, 'Test', 'saas', $3)")
+        .bind(id)
+        .bind(workspace_id)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+/// Creates a lead with the given address and adds it to the campaign as
+/// `pending`. Returns the lead id and the campaign lead id.
+pub async fn campaign_lead(pool: &PgPool, workspace_id: Uuid, campaign_id: Uuid, email: &str) -> (Uuid, Uuid) {
+    let (lead_id, campaign_lead_id) = (Uuid::new_v4(), Uuid::new_v4());
+    sqlx::query("INSERT INTO leads (id, workspace_id, email) VALUES ($1, $2, $3)")
+        .bind(lead_id)
+        .bind(workspace_id)
+        .bind(email)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO campaign_leads (id, campaign_id, lead_id, status) VALUES ($1, $2, $3, 'pending')")
+        .bind(campaign_lead_id)
+        .bind(campaign_id)
+        .bind(lead_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    (lead_id, campaign_lead_id)
+}
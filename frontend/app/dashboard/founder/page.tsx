@@ -24,7 +24,7 @@ import {
   X,
   HelpCircle
 } from 'lucide-react';
-import { api, FounderDashboardData, CampaignCard, InboxHealthCard, ReplyCard } from '@/lib/api';
+import { api, FounderDashboardData, CampaignCard, InboxHealthCard, DomainHealthCard, ReplyCard } from '@/lib/api';
 
 // ============================================================================
 // HELPER FUNCTIONS
@@ -104,7 +104,8 @@ function getIntentLabel(intent: string): string {
     case 'objection': return 'Question';
     case 'negative': return 'Negative';
     case 'auto_reply': return 'Auto-reply';
-    default: return intent;
+    // Custom categories: "wrong_person" -> "Wrong person"
+    default: return intent.charAt(0).toUpperCase() + intent.slice(1).replace(/_/g, ' ');
   }
 }
 
@@ -177,6 +178,8 @@ function getDemoData(): FounderDashboardData {
         bounce_rate: 0.02,
         daily_limit: 50,
         sent_today: 32,
+        domain: 'outreach.io',
+        domain_health_status: 'healthy',
       },
       {
         id: 'inbox-2',
@@ -189,6 +192,8 @@ function getDemoData(): FounderDashboardData {
         bounce_rate: 0.04,
         daily_limit: 40,
         sent_today: 38,
+        domain: 'company.com',
+        domain_health_status: 'warning',
       },
       {
         id: 'inbox-3',
@@ -201,6 +206,40 @@ function getDemoData(): FounderDashboardData {
         bounce_rate: 0.09,
         daily_limit: 30,
         sent_today: 12,
+        domain: 'startup.io',
+        domain_health_status: 'danger',
+      },
+    ],
+    domains: [
+      {
+        domain: 'outreach.io',
+        inbox_count: 1,
+        danger_inboxes: 0,
+        health_status: 'healthy',
+        spam_rate: 0.01,
+        bounce_rate: 0.02,
+        reply_rate: 0.08,
+        sent_today: 32,
+      },
+      {
+        domain: 'company.com',
+        inbox_count: 1,
+        danger_inboxes: 0,
+        health_status: 'warning',
+        spam_rate: 0.025,
+        bounce_rate: 0.04,
+        reply_rate: 0.05,
+        sent_today: 38,
+      },
+      {
+        domain: 'startup.io',
+        inbox_count: 1,
+        danger_inboxes: 1,
+        health_status: 'danger',
+        spam_rate: 0.042,
+        bounce_rate: 0.09,
+        reply_rate: 0.02,
+        sent_today: 12,
       },
     ],
     recent_replies: [
@@ -623,6 +662,9 @@ function InboxHealthCardItem({ inbox }: InboxHealthCardItemProps) {
         {inbox.health_status === 'healthy' && (
           <span className="text-nord-success">{formatPercent(inbox.reply_rate)} reply rate</span>
         )}
+        {inbox.domain_health_status === 'danger' && (
+          <div className="text-nord-error text-xs mt-1">Domain {inbox.domain} is at risk</div>
+        )}
       </div>
       
       {/* Progress bar */}
@@ -652,6 +694,27 @@ function InboxHealthCardItem({ inbox }: InboxHealthCardItemProps) {
   );
 }
 
+interface DomainHealthRowProps {
+  domain: DomainHealthCard;
+}
+
+function DomainHealthRow({ domain }: DomainHealthRowProps) {
+  return (
+    <div className="flex items-center justify-between py-2">
+      <div>
+        <div className="text-sm font-medium text-nord-text">{domain.domain}</div>
+        <div className="text-xs text-nord-text-muted">
+          {domain.inbox_count} {domain.inbox_count === 1 ? 'inbox' : 'inboxes'} · {formatPercent(domain.spam_rate)} spam · {formatPercent(domain.bounce_rate)} bounce
+        </div>
+      </div>
+      <span className={`flex items-center gap-1.5 px-2 py-1 rounded-md text-xs font-medium ${getHealthColor(domain.health_status)}`}>
+        {getHealthIcon(domain.health_status)}
+        <span className="capitalize">{domain.health_status}</span>
+      </span>
+    </div>
+  );
+}
+
 // ============================================================================
 // REPLY CARD COMPONENT (DARK THEME)
 // ============================================================================
@@ -1007,6 +1070,20 @@ export default function FounderDashboardPage() {
               </div>
             </div>
 
+            {data.domains && data.domains.length > 0 && (
+              <div className="bg-nord-surface border border-nord-elevated/50 rounded-xl p-5">
+                <h3 className="font-medium text-nord-text mb-2 flex items-center gap-2">
+                  <Mail className="w-4 h-4 text-nord-frost3" />
+                  Domain Health
+                </h3>
+                <div className="divide-y divide-nord-elevated/50">
+                  {data.domains.map((domain) => (
+                    <DomainHealthRow key={domain.domain} domain={domain} />
+                  ))}
+                </div>
+              </div>
+            )}
+
             {/* Auto-Pause Settings Card */}
             <div className="bg-nord-surface border border-nord-elevated/50 rounded-xl p-5">
               <div className="flex items-center justify-between mb-4">
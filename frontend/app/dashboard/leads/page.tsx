@@ -71,7 +71,7 @@ export default function LeadsPage() {
   const loadLeadsFromAPI = async () => {
     setIsLoading(true);
     try {
-      const apiLeads = await api.getLeads();
+      const { data: apiLeads } = await api.getLeads();
       // Transform API leads to EnhancedLead format
       const enhancedLeads: EnhancedLead[] = apiLeads.map(lead => ({
         id: lead.id,
@@ -25,6 +25,7 @@ export default function SettingsPage() {
     warmupIncrement: 2,
     sendDelay: 60,
     bounceThreshold: 5,
+    deliverabilityScoreThreshold: 75,
     // Lead Settings
     defaultLeadLimit: 100,
     verificationThreshold: 70,
@@ -128,6 +129,7 @@ ${senderTitle}`;
         spam_rate_threshold: settings.bounceThreshold,
         bounce_rate_threshold: settings.bounceThreshold,
         reply_drop_threshold: 20,
+        deliverability_score_threshold: settings.deliverabilityScoreThreshold,
         google_daily_limit: settings.dailyLimit,
         outlook_daily_limit: settings.dailyLimit,
         zoho_daily_limit: settings.dailyLimit,
@@ -152,6 +154,7 @@ ${senderTitle}`;
         warmupIncrement: 2,
         sendDelay: 60,
         bounceThreshold: 5,
+        deliverabilityScoreThreshold: 75,
         defaultLeadLimit: 100,
         verificationThreshold: 70,
         autoVerify: true,
@@ -301,6 +304,18 @@ ${senderTitle}`;
                   />
                   <p className="text-xs text-nord-text-muted mt-1">Pause sending if bounce rate exceeds</p>
                 </div>
+                <div>
+                  <label className="block text-sm font-medium text-nord-text-secondary mb-2">Deliverability Score Threshold</label>
+                  <input
+                    type="number"
+                    min={0}
+                    max={100}
+                    value={settings.deliverabilityScoreThreshold}
+                    onChange={(e) => setSettings({...settings, deliverabilityScoreThreshold: parseInt(e.target.value)})}
+                    className="w-full px-4 py-2 border border-nord-elevated rounded-lg focus:outline-none focus:ring-2 focus:ring-nord-frost3"
+                  />
+                  <p className="text-xs text-nord-text-muted mt-1">Pause campaigns if the 0-100 score drops below</p>
+                </div>
               </div>
             </div>
           )}
@@ -4,7 +4,7 @@ import { useState, useEffect } from 'react';
 import { useRouter } from 'next/navigation';
 import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer } from 'recharts';
 import { Mail, TrendingUp, Shield, AlertTriangle, CheckCircle, Activity, Plus, Settings, Play, Pause, RefreshCw, Link2, Copy, ExternalLink } from 'lucide-react';
-import { api, EmailAccount, Campaign } from '@/lib/api';
+import { api, EmailAccount, EmailOAuthProvider, Campaign } from '@/lib/api';
 
 interface InboxIssue {
   type: 'critical' | 'warning';
@@ -179,6 +179,16 @@ export default function WarmupPage() {
     }
   };
 
+  const handleConnectOAuth = async (provider: EmailOAuthProvider) => {
+    try {
+      const { authorization_url } = await api.startEmailAccountOAuth(provider);
+      window.location.href = authorization_url;
+    } catch (error) {
+      console.error('Failed to start OAuth:', error);
+      alert(error instanceof Error ? error.message : 'Failed to connect account');
+    }
+  };
+
   // Use API data or empty array
   const displayInboxes = inboxes.length > 0 ? inboxes : [];
 
@@ -291,6 +301,21 @@ export default function WarmupPage() {
         <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
           <div className="bg-nord-surface rounded-xl p-6 w-full max-w-md border border-nord-elevated/50">
             <h3 className="text-lg font-bold text-nord-text mb-4">Add Email Account</h3>
+            <div className="grid grid-cols-2 gap-3 mb-4">
+              <button
+                onClick={() => handleConnectOAuth('google')}
+                className="px-4 py-2 border border-nord-elevated rounded-lg hover:bg-nord-elevated/50 transition-all text-sm"
+              >
+                Connect Gmail
+              </button>
+              <button
+                onClick={() => handleConnectOAuth('microsoft')}
+                className="px-4 py-2 border border-nord-elevated rounded-lg hover:bg-nord-elevated/50 transition-all text-sm"
+              >
+                Connect Outlook
+              </button>
+            </div>
+            <p className="text-xs text-nord-text-muted mb-4">Or enter SMTP credentials:</p>
             <div className="space-y-4">
               <div>
                 <label className="block text-sm font-medium text-nord-text-secondary mb-1">Email Address</label>
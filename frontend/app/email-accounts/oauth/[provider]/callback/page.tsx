@@ -0,0 +1,50 @@
+'use client';
+
+import { useEffect, useRef, useState } from 'react';
+import { useParams, useRouter, useSearchParams } from 'next/navigation';
+import Link from 'next/link';
+import { api, EmailOAuthProvider } from '@/lib/api';
+
+export default function EmailAccountOAuthCallbackPage() {
+  const router = useRouter();
+  const params = useParams<{ provider: string }>();
+  const searchParams = useSearchParams();
+  const [error, setError] = useState('');
+  const submitted = useRef(false);
+
+  useEffect(() => {
+    // The consent code is single-use, so don't redeem it twice in dev strict mode
+    if (submitted.current) return;
+    submitted.current = true;
+
+    const code = searchParams.get('code');
+    const state = searchParams.get('state');
+    if (!code || !state) {
+      setError(searchParams.get('error_description') || searchParams.get('error') || 'Missing authorization code');
+      return;
+    }
+
+    api.completeEmailAccountOAuth(params.provider as EmailOAuthProvider, code, state)
+      .then(() => router.replace('/dashboard/warmup'))
+      .catch((err) => setError(err instanceof Error ? err.message : 'Failed to connect account'));
+  }, [params.provider, router, searchParams]);
+
+  return (
+    <div className="min-h-screen bg-nord-bg flex items-center justify-center p-4">
+      <div className="w-full max-w-md bg-nord-surface rounded-xl border border-nord-elevated/50 p-8 text-center">
+        {error ? (
+          <>
+            <div className="bg-nord-red/10 border border-nord-red/30 text-nord-red px-4 py-3 rounded-lg text-sm mb-6">
+              {error}
+            </div>
+            <Link href="/dashboard/warmup" className="text-nord-frost3 hover:text-nord-frost2">
+              Back to inboxes
+            </Link>
+          </>
+        ) : (
+          <p className="text-nord-text-muted">Connecting your inbox...</p>
+        )}
+      </div>
+    </div>
+  );
+}
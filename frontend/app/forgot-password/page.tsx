@@ -0,0 +1,96 @@
+'use client';
+
+import { useState } from 'react';
+import Link from 'next/link';
+import { Mail, ArrowRight } from 'lucide-react';
+import { api } from '@/lib/api';
+
+export default function ForgotPasswordPage() {
+  const [email, setEmail] = useState('');
+  const [sent, setSent] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    setLoading(true);
+    setError('');
+
+    try {
+      await api.forgotPassword(email);
+      setSent(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Request failed');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen bg-nord-bg flex items-center justify-center p-4">
+      <div className="w-full max-w-md">
+        <div className="text-center mb-8">
+          <Link href="/" className="text-2xl font-bold text-nord-text">
+            OutreachIQ
+          </Link>
+          <p className="text-nord-text-muted mt-2">Reset your password</p>
+        </div>
+
+        <div className="bg-nord-surface rounded-xl border border-nord-elevated/50 p-8">
+          {sent ? (
+            <p className="text-nord-text-secondary text-center">
+              If an account exists for <span className="font-medium text-nord-text">{email}</span>,
+              we&apos;ve sent a link to reset your password. It expires in an hour.
+            </p>
+          ) : (
+            <form onSubmit={handleSubmit} className="space-y-6">
+              {error && (
+                <div className="bg-nord-red/10 border border-nord-red/30 text-nord-red px-4 py-3 rounded-lg text-sm">
+                  {error}
+                </div>
+              )}
+
+              <div>
+                <label className="block text-sm font-medium text-nord-text-secondary mb-2">
+                  Email
+                </label>
+                <div className="relative">
+                  <Mail className="absolute left-3 top-1/2 -translate-y-1/2 text-nord-text-muted" size={20} />
+                  <input
+                    type="email"
+                    value={email}
+                    onChange={(e) => setEmail(e.target.value)}
+                    className="w-full pl-10 pr-4 py-3 border border-nord-elevated rounded-lg bg-nord-bg focus:outline-none focus:ring-2 focus:ring-nord-frost3 text-nord-text placeholder:text-nord-text-muted"
+                    placeholder="you@example.com"
+                    required
+                  />
+                </div>
+              </div>
+
+              <button
+                type="submit"
+                disabled={loading}
+                className="w-full bg-nord-frost3 text-nord-bg py-3 rounded-lg hover:bg-nord-frost2 transition-all disabled:opacity-50 flex items-center justify-center gap-2 font-medium"
+              >
+                {loading ? (
+                  'Sending...'
+                ) : (
+                  <>
+                    Send reset link
+                    <ArrowRight size={18} />
+                  </>
+                )}
+              </button>
+            </form>
+          )}
+
+          <div className="mt-6 text-center">
+            <Link href="/login" className="text-nord-frost3 font-medium hover:underline">
+              Back to sign in
+            </Link>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
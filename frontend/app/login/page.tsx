@@ -3,7 +3,7 @@
 import { useState } from 'react';
 import { useRouter } from 'next/navigation';
 import Link from 'next/link';
-import { Mail, Lock, Eye, EyeOff, ArrowRight } from 'lucide-react';
+import { Mail, Lock, Eye, EyeOff, ArrowRight, ShieldCheck } from 'lucide-react';
 import { api } from '@/lib/api';
 
 export default function LoginPage() {
@@ -11,6 +11,8 @@ export default function LoginPage() {
   const [email, setEmail] = useState('');
   const [password, setPassword] = useState('');
   const [showPassword, setShowPassword] = useState(false);
+  const [twoFactorRequired, setTwoFactorRequired] = useState(false);
+  const [twoFactorCode, setTwoFactorCode] = useState('');
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
 
@@ -20,7 +22,15 @@ export default function LoginPage() {
     setError('');
 
     try {
-      await api.login({ email, password });
+      const result = await api.login({
+        email,
+        password,
+        two_factor_code: twoFactorRequired ? twoFactorCode : undefined,
+      });
+      if ('two_factor_required' in result) {
+        setTwoFactorRequired(true);
+        return;
+      }
       router.push('/dashboard');
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Login failed');
@@ -88,6 +98,28 @@ export default function LoginPage() {
               </div>
             </div>
 
+            {twoFactorRequired && (
+              <div>
+                <label className="block text-sm font-medium text-nord-text-secondary mb-2">
+                  Authentication code
+                </label>
+                <div className="relative">
+                  <ShieldCheck className="absolute left-3 top-1/2 -translate-y-1/2 text-nord-text-muted" size={20} />
+                  <input
+                    type="text"
+                    inputMode="text"
+                    autoComplete="one-time-code"
+                    value={twoFactorCode}
+                    onChange={(e) => setTwoFactorCode(e.target.value)}
+                    className="w-full pl-10 pr-4 py-3 border border-nord-elevated rounded-lg bg-nord-bg focus:outline-none focus:ring-2 focus:ring-nord-frost3 text-nord-text placeholder:text-nord-text-muted"
+                    placeholder="6-digit code or recovery code"
+                    autoFocus
+                    required
+                  />
+                </div>
+              </div>
+            )}
+
             <div className="flex items-center justify-between">
               <label className="flex items-center">
                 <input type="checkbox" className="w-4 h-4 rounded border-nord-elevated" />
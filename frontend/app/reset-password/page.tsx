@@ -0,0 +1,117 @@
+'use client';
+
+import { Suspense, useState } from 'react';
+import { useRouter, useSearchParams } from 'next/navigation';
+import Link from 'next/link';
+import { Lock, ArrowRight } from 'lucide-react';
+import { api } from '@/lib/api';
+
+function ResetPasswordForm() {
+  const router = useRouter();
+  const token = useSearchParams().get('token') ?? '';
+  const [password, setPassword] = useState('');
+  const [confirm, setConfirm] = useState('');
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (password !== confirm) {
+      setError('Passwords do not match');
+      return;
+    }
+
+    setLoading(true);
+    setError('');
+
+    try {
+      await api.resetPassword(token, password);
+      router.push('/login');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Reset failed');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  if (!token) {
+    return (
+      <p className="text-nord-text-secondary text-center">
+        This reset link is incomplete.{' '}
+        <Link href="/forgot-password" className="text-nord-frost3 hover:underline">
+          Request a new one
+        </Link>
+        .
+      </p>
+    );
+  }
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-6">
+      {error && (
+        <div className="bg-nord-red/10 border border-nord-red/30 text-nord-red px-4 py-3 rounded-lg text-sm">
+          {error}
+        </div>
+      )}
+
+      {[
+        { label: 'New password', value: password, onChange: setPassword },
+        { label: 'Confirm password', value: confirm, onChange: setConfirm },
+      ].map((field) => (
+        <div key={field.label}>
+          <label className="block text-sm font-medium text-nord-text-secondary mb-2">
+            {field.label}
+          </label>
+          <div className="relative">
+            <Lock className="absolute left-3 top-1/2 -translate-y-1/2 text-nord-text-muted" size={20} />
+            <input
+              type="password"
+              value={field.value}
+              onChange={(e) => field.onChange(e.target.value)}
+              className="w-full pl-10 pr-4 py-3 border border-nord-elevated rounded-lg bg-nord-bg focus:outline-none focus:ring-2 focus:ring-nord-frost3 text-nord-text placeholder:text-nord-text-muted"
+              placeholder="At least 10 characters, letters and numbers"
+              minLength={10}
+              required
+            />
+          </div>
+        </div>
+      ))}
+
+      <button
+        type="submit"
+        disabled={loading}
+        className="w-full bg-nord-frost3 text-nord-bg py-3 rounded-lg hover:bg-nord-frost2 transition-all disabled:opacity-50 flex items-center justify-center gap-2 font-medium"
+      >
+        {loading ? (
+          'Saving...'
+        ) : (
+          <>
+            Set new password
+            <ArrowRight size={18} />
+          </>
+        )}
+      </button>
+    </form>
+  );
+}
+
+export default function ResetPasswordPage() {
+  return (
+    <div className="min-h-screen bg-nord-bg flex items-center justify-center p-4">
+      <div className="w-full max-w-md">
+        <div className="text-center mb-8">
+          <Link href="/" className="text-2xl font-bold text-nord-text">
+            OutreachIQ
+          </Link>
+          <p className="text-nord-text-muted mt-2">Choose a new password</p>
+        </div>
+
+        <div className="bg-nord-surface rounded-xl border border-nord-elevated/50 p-8">
+          <Suspense fallback={null}>
+            <ResetPasswordForm />
+          </Suspense>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -241,6 +241,8 @@ export default function SignalsPage() {
   const [signals, setSignals] = useState<Signal[]>([]);
   const [stats, setStats] = useState<SignalStats | null>(null);
   const [loading, setLoading] = useState(true);
+  const [nextCursor, setNextCursor] = useState<string | null>(null);
+  const [loadingMore, setLoadingMore] = useState(false);
   const [filter, setFilter] = useState<string>('all');
   const [error, setError] = useState<string | null>(null);
 
@@ -268,9 +270,11 @@ export default function SignalsPage() {
       const statsData = await statsRes.json();
 
       setSignals(signalsData.signals || []);
+      setNextCursor(signalsData.next_cursor ?? null);
       setStats(statsData);
     } catch (err) {
       setSignals(getDemoSignals(filter));
+      setNextCursor(null);
       setStats(DEMO_STATS);
       setError(err instanceof Error ? err.message : 'Failed to load live signals. Showing demo data.');
     } finally {
@@ -278,6 +282,29 @@ export default function SignalsPage() {
     }
   };
 
+  const loadMore = async () => {
+    if (!nextCursor) return;
+    setLoadingMore(true);
+
+    try {
+      const params = new URLSearchParams({ limit: '50', cursor: nextCursor });
+      if (filter !== 'all') {
+        params.append('signal_type', filter);
+      }
+
+      const res = await fetch(`${API_BASE}/signals/feed?${params}`);
+      if (!res.ok) throw new Error('Failed to fetch signals');
+
+      const data = await res.json();
+      setSignals((prev) => [...prev, ...(data.signals || [])]);
+      setNextCursor(data.next_cursor ?? null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load more signals');
+    } finally {
+      setLoadingMore(false);
+    }
+  };
+
   useEffect(() => {
     fetchSignals();
   }, [filter]);
@@ -378,6 +405,15 @@ export default function SignalsPage() {
             {signals.map((signal) => (
               <SignalCard key={signal.id} signal={signal} />
             ))}
+            {nextCursor && (
+              <button
+                onClick={loadMore}
+                disabled={loadingMore}
+                className="w-full py-3 text-sm font-medium text-nord-frost3 bg-nord-surface rounded-lg hover:bg-nord-elevated disabled:opacity-50"
+              >
+                {loadingMore ? 'Loading...' : 'Load more'}
+              </button>
+            )}
           </div>
         )}
 
@@ -16,9 +16,32 @@ export interface AuthResponse {
   user: User;
 }
 
+export interface WorkspaceMembership {
+  workspace_id: string;
+  name: string;
+  slug: string;
+  role: string | null;
+  joined_at: string | null;
+}
+
 export interface LoginParams {
   email: string;
   password: string;
+  two_factor_code?: string;
+}
+
+export interface TwoFactorChallenge {
+  two_factor_required: true;
+}
+
+export interface TwoFactorSetup {
+  secret: string;
+  otpauth_uri: string;
+}
+
+export interface TwoFactorVerifyResult {
+  enabled: boolean;
+  recovery_codes: string[];
 }
 
 export interface RegisterParams {
@@ -83,20 +106,161 @@ export interface Lead {
   signals: LeadSignals;
   created_at: string;
   verified_at: string | null;
+  /** Extra imported columns, each usable as a {{key}} merge tag */
+  custom_fields: Record<string, string | number | boolean>;
+}
+
+export interface LeadListParams {
+  limit?: number;
+  offset?: number;
+  tag?: string;
+  status?: Lead['verification_status'];
+  company?: string;
+  q?: string;
+}
+
+export interface LeadPage {
+  data: Lead[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
+export interface DuplicateLead {
+  id: string;
+  email: string;
+  first_name: string | null;
+  last_name: string | null;
+  company: string | null;
+  confidence_score: number;
+  created_at: string;
+}
+
+export interface DuplicateLeadGroup {
+  match_on: Array<'email' | 'name_company'>;
+  /** The lead a merge would keep comes first */
+  leads: DuplicateLead[];
+}
+
+export interface LeadMergeResult {
+  lead_id: string;
+  merged_lead_ids: string[];
+  campaign_leads_moved: number;
 }
 
 export interface Campaign {
   id: string;
   name: string;
   vertical: string;
-  status: 'draft' | 'active' | 'paused' | 'completed';
+  status: 'draft' | 'scheduled' | 'active' | 'paused' | 'completed';
   total_leads: number;
   sent: number;
   opened: number;
   clicked: number;
   replied: number;
+  bounced: number;
   created_at: string;
   started_at: string | null;
+  scheduled_start_at: string | null;
+  from_name: string | null;
+  reply_to: string | null;
+  recipient_timezone_field: string | null;
+  /** Auto-pause overrides as fractions; null uses the workspace threshold */
+  spam_rate_threshold: number | null;
+  reply_drop_threshold: number | null;
+  bounce_rate_threshold: number | null;
+  // Why an active campaign isn't sending, e.g. 'monthly_email_limit_reached'
+  // or 'mailing_address_missing'
+  send_blocked_reason: string | null;
+}
+
+export interface SentEmail {
+  id: string;
+  campaign_id: string;
+  campaign_lead_id: string;
+  lead_id: string;
+  email_account_id: string | null;
+  from_email: string;
+  to_email: string;
+  subject: string;
+  body_html: string;
+  body_text: string;
+  message_id: string;
+  smtp_response: string | null;
+  sent_at: string;
+  step_index: number;
+}
+
+export interface EmailPreview {
+  subject: string;
+  body_html: string;
+  body_text: string;
+}
+
+export interface SpamFinding {
+  rule: string;
+  points: number;
+  detail: string;
+}
+
+export interface SpamCheckResult {
+  score: number;
+  risk: 'low' | 'medium' | 'high';
+  findings: SpamFinding[];
+}
+
+/** Postal address printed in campaign footers; line 1, city and country are required */
+export interface CompanyAddress {
+  company_name: string | null;
+  address_line1: string | null;
+  address_line2: string | null;
+  city: string | null;
+  region: string | null;
+  postal_code: string | null;
+  country: string | null;
+}
+
+export interface MailingAddressResponse {
+  complete: boolean;
+  address: CompanyAddress;
+}
+
+export interface CampaignTemplate {
+  id: string;
+  campaign_id: string;
+  step_order: number;
+  subject: string;
+  body_html: string;
+  body_text: string | null;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface CampaignTemplateParams {
+  step_order?: number;
+  subject: string;
+  body_html: string;
+  body_text?: string;
+}
+
+export type StepCondition = 'no_reply' | 'not_opened' | 'opened';
+
+export interface CampaignStep {
+  id: string;
+  campaign_id: string;
+  step_index: number;
+  template_id: string | null;
+  delay_days: number;
+  condition: StepCondition;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface CampaignStepParams {
+  step_index?: number;
+  template_id?: string;
+  delay_days?: number;
+  condition?: StepCondition;
 }
 
 export interface EmailAccount {
@@ -110,6 +274,29 @@ export interface EmailAccount {
   sent_today: number;
   health_score: number;
   created_at: string;
+  timezone_offset_minutes: number | null;
+  auth_method: 'password' | 'oauth';
+  auth_error: string | null;
+  send_window_start: string;
+  send_window_end: string;
+}
+
+export interface SendWindowParams {
+  send_window_start: string;
+  send_window_end: string;
+  timezone_offset_minutes?: number;
+}
+
+export type EmailOAuthProvider = 'google' | 'microsoft';
+
+export interface CampaignInbox {
+  email_account_id: string;
+  email: string;
+  warmup_status: string;
+  daily_limit: number;
+  sent_today: number;
+  health_status: 'healthy' | 'warning' | 'danger';
+  assigned_at: string;
 }
 
 export interface LeadSearchParams {
@@ -135,6 +322,39 @@ export interface CreateEmailAccountParams {
   smtp_port: number;
   smtp_username: string;
   smtp_password: string;
+  timezone_offset_minutes?: number;
+  imap_host?: string;
+  imap_port?: number;
+  send_window_start?: string;
+  send_window_end?: string;
+}
+
+export interface RejectedLeadRow {
+  line: number;
+  email: string | null;
+  reason: string;
+}
+
+export type ExportFormat = 'csv' | 'xlsx';
+
+export interface LeadImportResponse {
+  imported: number;
+  duplicates: number;
+  rejected: RejectedLeadRow[];
+}
+
+export interface ImportEmailAccountResult {
+  line: number;
+  email: string | null;
+  success: boolean;
+  id: string | null;
+  error: string | null;
+}
+
+export interface ImportEmailAccountsResponse {
+  imported: number;
+  failed: number;
+  results: ImportEmailAccountResult[];
 }
 
 export interface OverviewStats {
@@ -183,6 +403,72 @@ export interface LeadAnalytics {
   };
 }
 
+export type TimeseriesMetric = 'sent' | 'opened' | 'replied';
+
+export interface TimeseriesParams {
+  metric: TimeseriesMetric;
+  /** Inclusive UTC days, YYYY-MM-DD; defaults to the last 30 days */
+  from?: string;
+  to?: string;
+  interval?: 'day' | 'week';
+  campaign_id?: string;
+}
+
+export interface Timeseries {
+  metric: TimeseriesMetric;
+  interval: 'day' | 'week';
+  from: string;
+  to: string;
+  campaign_id: string | null;
+  // Every bucket in the range, oldest first; quiet ones have count 0
+  points: { bucket: string; count: number }[];
+}
+
+export type InboxHealthStatus = 'healthy' | 'warning' | 'danger';
+export type HealthTrend = 'improving' | 'stable' | 'declining';
+
+export interface InboxHealthPoint {
+  day: string;
+  sent: number;
+  delivered: number;
+  // Estimated from the inbox's bounce and spam rates
+  bounced: number;
+  replied: number;
+  spam_complaints: number;
+  spam_rate: number;
+  bounce_rate: number;
+  reply_rate: number;
+  health_score: number;
+  health_status: InboxHealthStatus;
+}
+
+export interface InboxHealth {
+  from: string;
+  to: string;
+  inbox: {
+    email_account_id: string;
+    email: string;
+    // Only days the health job measured, oldest first
+    points: InboxHealthPoint[];
+    totals: { sent: number; delivered: number; bounced: number; replied: number; spam_complaints: number };
+    trend: HealthTrend | null;
+    health_status: InboxHealthStatus | null;
+  };
+}
+
+export interface InboxHealthRank {
+  rank: number;
+  email_account_id: string;
+  email: string;
+  health_score: number;
+  spam_rate: number;
+  bounce_rate: number;
+  reply_rate: number;
+  health_status: InboxHealthStatus;
+  trend: HealthTrend | null;
+  measured_at: string | null;
+}
+
 export interface DeliverabilityMetrics {
   inbox_rate: number;
   bounce_rate: number;
@@ -199,6 +485,23 @@ export interface WarmupStats {
   warmup_progress: number;
 }
 
+export type DnsCheckStatus = 'pass' | 'warn' | 'fail' | 'unknown';
+
+export interface DnsMechanismCheck {
+  status: DnsCheckStatus;
+  record: string | null;
+  detail: string;
+  remediation: string | null;
+}
+
+export interface DomainAuthReport {
+  domain: string;
+  spf: DnsMechanismCheck;
+  dkim: DnsMechanismCheck;
+  dmarc: DnsMechanismCheck;
+  checked_at: string;
+}
+
 // ============================================================================
 // FOUNDER DASHBOARD TYPES
 // ============================================================================
@@ -225,6 +528,33 @@ export interface InboxHealthCard {
   bounce_rate: number;
   daily_limit: number;
   sent_today: number;
+  domain: string;
+  domain_health_status: 'healthy' | 'warning' | 'danger';
+  health_breakdown: HealthBreakdown | null;
+}
+
+// Points each component adds to (or takes off) a perfect 100
+export interface HealthBreakdown {
+  spam: number;
+  bounce: number;
+  reply: number;
+  dns: number;
+  volume_confidence: number;
+  sent_last_7_days: number;
+  dns_checks: { spf: boolean | null; dkim: boolean | null; dmarc: boolean | null };
+  raw_score: number;
+  score: number;
+}
+
+export interface DomainHealthCard {
+  domain: string;
+  inbox_count: number;
+  danger_inboxes: number;
+  health_status: 'healthy' | 'warning' | 'danger';
+  spam_rate: number;
+  bounce_rate: number;
+  reply_rate: number;
+  sent_today: number;
 }
 
 export interface CampaignCard {
@@ -248,19 +578,24 @@ export interface ReplyCard {
   from_name: string | null;
   subject: string | null;
   body_preview: string;
-  intent: 'interested' | 'maybe_later' | 'objection' | 'negative' | 'auto_reply';
+  // Built-in intents, or a workspace's custom reply category label
+  intent: 'interested' | 'maybe_later' | 'objection' | 'negative' | 'auto_reply' | (string & {});
   intent_confidence: number;
   campaign_id: string | null;
   campaign_name: string | null;
   received_at: string;
   is_read: boolean;
   is_actioned: boolean;
+  // -1 (hostile) to 1 (enthusiastic); null until classified
+  sentiment: number | null;
+  is_urgent: boolean;
 }
 
 export interface FounderDashboardData {
   overview: DashboardOverview;
   campaigns: CampaignCard[];
   inboxes: InboxHealthCard[];
+  domains: DomainHealthCard[];
   recent_replies: ReplyCard[];
   unread_count: number;
   action_required_count: number;
@@ -298,11 +633,35 @@ export interface WorkspaceSettings {
   spam_rate_threshold: number;
   reply_drop_threshold: number;
   bounce_rate_threshold: number;
+  deliverability_score_threshold: number;
   google_daily_limit: number;
   outlook_daily_limit: number;
   zoho_daily_limit: number;
   notification_email: string | null;
   slack_webhook_url: string | null;
+  reply_categories: ReplyCategory[];
+  reply_category_priority: string[];
+  // Months to keep data for; null keeps it forever (send 0 to clear)
+  event_retention_months: number | null;
+  reply_retention_months: number | null;
+  // Resume auto-paused campaigns after metrics stay healthy for the cooldown
+  auto_resume_enabled: boolean;
+  auto_resume_cooldown_hours: number;
+}
+
+export interface RetentionPurgeRun {
+  id: string;
+  workspace_id: string;
+  event_retention_months: number | null;
+  reply_retention_months: number | null;
+  events_deleted: number;
+  replies_anonymized: number;
+  ran_at: string | null;
+}
+
+export interface ReplyCategory {
+  label: string;
+  description: string;
 }
 
 export interface Meeting {
@@ -328,6 +687,60 @@ export interface CompanySignals {
   industry: string | null;
 }
 
+export interface CompanySignalSummary {
+  company_id: string;
+  company_name: string;
+  domain: string;
+  total_signals: number;
+  hiring_signals: number;
+  github_signals: number;
+  funding_signals: number;
+  latest_signal_date: string | null;
+  avg_confidence: number;
+}
+
+export interface ManualFundingRound {
+  round_type?: string;
+  /** Whole US dollars */
+  amount_usd?: number;
+  investors?: string[];
+  lead_investor?: string;
+  announced_date?: string;
+  url?: string;
+}
+
+export interface SignalIngestResult {
+  success: boolean;
+  signals_created: number;
+  message: string;
+}
+
+// ============================================================================
+// INTEGRATION TYPES
+// ============================================================================
+
+export type ZapierEvent = 'reply.interested' | 'meeting.created' | 'meeting.reminder';
+
+export interface ZapierSubscription {
+  id: string;
+  event: ZapierEvent;
+  target_url: string;
+  campaign_id: string | null;
+  created_at: string | null;
+}
+
+export interface ZapierPayload {
+  id: string;
+  event: ZapierEvent;
+  lead_email: string;
+  lead_name: string;
+  company: string;
+  campaign: string;
+  message: string;
+  meeting_at: string | null;
+  occurred_at: string;
+}
+
 // ============================================================================
 // AUTH TOKEN MANAGEMENT
 // ============================================================================
@@ -385,8 +798,9 @@ class ApiClient {
   ): Promise<T> {
     const url = `${this.baseUrl}${endpoint}`;
     
+    // Let the browser set the multipart boundary for file uploads
     const headers: Record<string, string> = {
-      'Content-Type': 'application/json',
+      ...(options.body instanceof FormData ? {} : { 'Content-Type': 'application/json' }),
       ...(requiresAuth ? this.getAuthHeaders() : {}),
       ...(options.headers as Record<string, string> || {}),
     };
@@ -424,15 +838,35 @@ class ApiClient {
     }
   }
 
+  /** Fetches a file download such as an export, with the same auth handling as `request` */
+  private async download(endpoint: string): Promise<Blob> {
+    const response = await fetch(`${this.baseUrl}${endpoint}`, { headers: this.getAuthHeaders() });
+    if (response.status === 401) {
+      clearAuthData();
+      if (typeof window !== 'undefined') {
+        window.location.href = '/login';
+      }
+      throw new Error('Authentication required');
+    }
+    if (!response.ok) {
+      const error = await response.json().catch(() => ({}));
+      throw new Error(error.error || error.message || `HTTP error! status: ${response.status}`);
+    }
+    return response.blob();
+  }
+
   // ============================================================================
   // AUTH ENDPOINTS
   // ============================================================================
 
-  async login(params: LoginParams): Promise<AuthResponse> {
-    const response = await this.request<AuthResponse>('/auth/login', {
+  async login(params: LoginParams): Promise<AuthResponse | TwoFactorChallenge> {
+    const response = await this.request<AuthResponse | TwoFactorChallenge>('/auth/login', {
       method: 'POST',
       body: JSON.stringify(params),
     }, false);
+    if ('two_factor_required' in response) {
+      return response;
+    }
     setAuthData(response.token, response.user);
     return response;
   }
@@ -446,10 +880,42 @@ class ApiClient {
     return response;
   }
 
+  async forgotPassword(email: string): Promise<{ message: string }> {
+    return this.request<{ message: string }>('/auth/forgot-password', {
+      method: 'POST',
+      body: JSON.stringify({ email }),
+    }, false);
+  }
+
+  async resetPassword(token: string, password: string): Promise<{ message: string }> {
+    return this.request<{ message: string }>('/auth/reset-password', {
+      method: 'POST',
+      body: JSON.stringify({ token, password }),
+    }, false);
+  }
+
   async getCurrentUser(): Promise<User> {
     return this.request<User>('/auth/me');
   }
 
+  async setupTwoFactor(): Promise<TwoFactorSetup> {
+    return this.request<TwoFactorSetup>('/auth/2fa/setup', { method: 'POST' });
+  }
+
+  async verifyTwoFactor(code: string): Promise<TwoFactorVerifyResult> {
+    return this.request<TwoFactorVerifyResult>('/auth/2fa/verify', {
+      method: 'POST',
+      body: JSON.stringify({ code }),
+    });
+  }
+
+  async disableTwoFactor(password: string): Promise<{ enabled: boolean }> {
+    return this.request<{ enabled: boolean }>('/auth/2fa/disable', {
+      method: 'POST',
+      body: JSON.stringify({ password }),
+    });
+  }
+
   async refreshToken(): Promise<{ token: string }> {
     const response = await this.request<{ token: string }>('/auth/refresh', {
       method: 'POST',
@@ -461,6 +927,22 @@ class ApiClient {
     return response;
   }
 
+  async getWorkspaces(): Promise<WorkspaceMembership[]> {
+    return this.request<WorkspaceMembership[]>('/auth/workspaces');
+  }
+
+  async switchWorkspace(workspaceId: string): Promise<{ token: string; workspace: WorkspaceMembership }> {
+    const response = await this.request<{ token: string; workspace: WorkspaceMembership }>(
+      `/auth/switch-workspace/${workspaceId}`,
+      { method: 'POST' }
+    );
+    const user = getStoredUser();
+    if (user) {
+      setAuthData(response.token, user);
+    }
+    return response;
+  }
+
   logout(): void {
     clearAuthData();
     if (typeof window !== 'undefined') {
@@ -499,13 +981,27 @@ class ApiClient {
   // LEADS ENDPOINTS
   // ============================================================================
 
-  async getLeads(params?: { limit?: number; offset?: number; vertical?: string }): Promise<Lead[]> {
+  async getLeads(params?: LeadListParams): Promise<LeadPage> {
     const queryParams = new URLSearchParams();
     if (params?.limit) queryParams.append('limit', params.limit.toString());
     if (params?.offset) queryParams.append('offset', params.offset.toString());
-    if (params?.vertical) queryParams.append('vertical', params.vertical);
+    if (params?.tag) queryParams.append('tag', params.tag);
+    if (params?.status) queryParams.append('status', params.status);
+    if (params?.company) queryParams.append('company', params.company);
+    if (params?.q) queryParams.append('q', params.q);
     const query = queryParams.toString();
-    return this.request<Lead[]>(`/leads${query ? `?${query}` : ''}`);
+    return this.request<LeadPage>(`/leads${query ? `?${query}` : ''}`);
+  }
+
+  async getDuplicateLeads(): Promise<{ groups: DuplicateLeadGroup[] }> {
+    return this.request('/leads/duplicates');
+  }
+
+  async mergeLeads(leadIds: string[]): Promise<LeadMergeResult> {
+    return this.request<LeadMergeResult>('/leads/merge', {
+      method: 'POST',
+      body: JSON.stringify({ lead_ids: leadIds }),
+    });
   }
 
   async getLeadById(id: string): Promise<Lead> {
@@ -519,8 +1015,21 @@ class ApiClient {
     });
   }
 
-  async verifyLeads(emails: string[]): Promise<Array<{ email: string; status: string; confidence: number }>> {
-    return this.request('/leads/verify', {
+  async importLeads(file: File): Promise<LeadImportResponse> {
+    const form = new FormData();
+    form.append('file', file);
+    return this.request<LeadImportResponse>('/leads/import', {
+      method: 'POST',
+      body: form,
+    });
+  }
+
+  async exportLeads(format: ExportFormat = 'csv'): Promise<Blob> {
+    return this.download(`/leads/export?format=${format}`);
+  }
+
+  async verifyLeads(emails: string[], force = false): Promise<Array<{ email: string; status: string; confidence: number }>> {
+    return this.request(`/leads/verify${force ? '?force=true' : ''}`, {
       method: 'POST',
       body: JSON.stringify(emails),
     });
@@ -530,16 +1039,65 @@ class ApiClient {
     return this.request<CompanySignals>(`/leads/signals/${domain}`);
   }
 
+  async getCompanySignalSummary(companyId: string): Promise<CompanySignalSummary> {
+    return this.request<CompanySignalSummary>(`/signals/companies/${companyId}/summary`);
+  }
+
+  async addManualFunding(companyId: string, rounds: ManualFundingRound[]): Promise<SignalIngestResult> {
+    return this.request<SignalIngestResult>('/signals/funding/manual', {
+      method: 'POST',
+      body: JSON.stringify({ company_id: companyId, rounds }),
+    });
+  }
+
   async deleteLead(id: string): Promise<void> {
     return this.request(`/leads/${id}`, { method: 'DELETE' });
   }
 
+  async restoreLead(id: string): Promise<void> {
+    return this.request(`/leads/${id}/restore`, { method: 'POST' });
+  }
+
+  async getLeadTags(id: string): Promise<string[]> {
+    return this.request<string[]>(`/leads/${id}/tags`);
+  }
+
+  async addLeadTags(id: string, tags: string[]): Promise<string[]> {
+    return this.request<string[]>(`/leads/${id}/tags`, {
+      method: 'POST',
+      body: JSON.stringify({ tags }),
+    });
+  }
+
+  async removeLeadTag(id: string, tag: string): Promise<void> {
+    return this.request(`/leads/${id}/tags?tag=${encodeURIComponent(tag)}`, { method: 'DELETE' });
+  }
+
   // ============================================================================
   // CAMPAIGNS ENDPOINTS
   // ============================================================================
 
-  async getCampaigns(): Promise<Campaign[]> {
-    return this.request<Campaign[]>('/campaigns');
+  async getCampaigns(params?: { tags?: string[]; status?: string }): Promise<Campaign[]> {
+    const queryParams = new URLSearchParams();
+    if (params?.tags?.length) queryParams.append('tags', params.tags.join(','));
+    if (params?.status) queryParams.append('status', params.status);
+    const query = queryParams.toString();
+    return this.request<Campaign[]>(`/campaigns${query ? `?${query}` : ''}`);
+  }
+
+  async getCampaignTags(id: string): Promise<string[]> {
+    return this.request<string[]>(`/campaigns/${id}/tags`);
+  }
+
+  async addCampaignTags(id: string, tags: string[]): Promise<string[]> {
+    return this.request<string[]>(`/campaigns/${id}/tags`, {
+      method: 'POST',
+      body: JSON.stringify({ tags }),
+    });
+  }
+
+  async removeCampaignTag(id: string, tag: string): Promise<void> {
+    return this.request(`/campaigns/${id}/tags?tag=${encodeURIComponent(tag)}`, { method: 'DELETE' });
   }
 
   async getCampaignById(id: string): Promise<Campaign> {
@@ -564,8 +1122,30 @@ class ApiClient {
     return this.request(`/campaigns/${id}`, { method: 'DELETE' });
   }
 
-  async startCampaign(id: string): Promise<Campaign> {
-    return this.request<Campaign>(`/campaigns/${id}/start`, { method: 'POST' });
+  async restoreCampaign(id: string): Promise<void> {
+    return this.request(`/campaigns/${id}/restore`, { method: 'POST' });
+  }
+
+  async startCampaign(id: string, scheduledStartAt?: string): Promise<Campaign> {
+    return this.request<Campaign>(`/campaigns/${id}/start`, {
+      method: 'POST',
+      ...(scheduledStartAt && { body: JSON.stringify({ scheduled_start_at: scheduledStartAt }) }),
+    });
+  }
+
+  async cloneCampaign(id: string): Promise<Campaign> {
+    return this.request<Campaign>(`/campaigns/${id}/clone`, { method: 'POST' });
+  }
+
+  async scheduleCampaign(id: string, scheduledStartAt: string): Promise<{ status: string; scheduled_start_at: string }> {
+    return this.request<{ status: string; scheduled_start_at: string }>(`/campaigns/${id}/schedule`, {
+      method: 'POST',
+      body: JSON.stringify({ scheduled_start_at: scheduledStartAt }),
+    });
+  }
+
+  async cancelCampaignSchedule(id: string): Promise<{ status: string }> {
+    return this.request<{ status: string }>(`/campaigns/${id}/schedule`, { method: 'DELETE' });
   }
 
   async pauseCampaign(id: string): Promise<Campaign> {
@@ -579,10 +1159,120 @@ class ApiClient {
     });
   }
 
+  async addTaggedLeadsToCampaign(campaignId: string, tag: string): Promise<{ added: number; campaign_id: string }> {
+    return this.request(`/campaigns/${campaignId}/leads?by_tag=${encodeURIComponent(tag)}`, {
+      method: 'POST',
+      body: JSON.stringify({ lead_ids: [] }),
+    });
+  }
+
   async getCampaignLeads(campaignId: string): Promise<Lead[]> {
     return this.request<Lead[]>(`/campaigns/${campaignId}/leads`);
   }
 
+  async exportCampaignResults(campaignId: string, format: ExportFormat = 'csv'): Promise<Blob> {
+    return this.download(`/campaigns/${campaignId}/export?format=${format}`);
+  }
+
+  async getCampaignInboxes(campaignId: string): Promise<CampaignInbox[]> {
+    return this.request<CampaignInbox[]>(`/campaigns/${campaignId}/inboxes`);
+  }
+
+  async setCampaignInboxes(campaignId: string, emailAccountIds: string[]): Promise<CampaignInbox[]> {
+    return this.request<CampaignInbox[]>(`/campaigns/${campaignId}/inboxes`, {
+      method: 'PUT',
+      body: JSON.stringify({ email_account_ids: emailAccountIds }),
+    });
+  }
+
+  async resendToLead(campaignId: string, leadId: string): Promise<{ status: 'scheduled' | 'pending'; queued: boolean; job_id?: string }> {
+    return this.request(`/campaigns/${campaignId}/leads/${leadId}/resend`, { method: 'POST' });
+  }
+
+  async getSentEmails(campaignId: string, leadId: string): Promise<SentEmail[]> {
+    return this.request<SentEmail[]>(`/campaigns/${campaignId}/sent/${leadId}`);
+  }
+
+  async previewCampaign(campaignId: string, leadId?: string): Promise<EmailPreview> {
+    return this.request<EmailPreview>(`/campaigns/${campaignId}/preview`, {
+      method: 'POST',
+      body: JSON.stringify({ lead_id: leadId }),
+    });
+  }
+
+  async spamCheck(subject: string, bodyHtml: string, bodyText?: string): Promise<SpamCheckResult> {
+    return this.request<SpamCheckResult>('/compliance/spam-check', {
+      method: 'POST',
+      body: JSON.stringify({ subject, body_html: bodyHtml, body_text: bodyText }),
+    });
+  }
+
+  async getMailingAddress(): Promise<MailingAddressResponse> {
+    return this.request<MailingAddressResponse>('/compliance/mailing-address');
+  }
+
+  async updateMailingAddress(address: CompanyAddress): Promise<MailingAddressResponse> {
+    return this.request<MailingAddressResponse>('/compliance/mailing-address', {
+      method: 'PUT',
+      body: JSON.stringify(address),
+    });
+  }
+
+  async getCampaignTemplates(campaignId: string): Promise<CampaignTemplate[]> {
+    return this.request<CampaignTemplate[]>(`/campaigns/${campaignId}/templates`);
+  }
+
+  async createCampaignTemplate(campaignId: string, params: CampaignTemplateParams): Promise<CampaignTemplate> {
+    return this.request<CampaignTemplate>(`/campaigns/${campaignId}/templates`, {
+      method: 'POST',
+      body: JSON.stringify(params),
+    });
+  }
+
+  async updateCampaignTemplate(campaignId: string, templateId: string, params: CampaignTemplateParams): Promise<CampaignTemplate> {
+    return this.request<CampaignTemplate>(`/campaigns/${campaignId}/templates/${templateId}`, {
+      method: 'PUT',
+      body: JSON.stringify(params),
+    });
+  }
+
+  async deleteCampaignTemplate(campaignId: string, templateId: string): Promise<{ deleted: boolean }> {
+    return this.request(`/campaigns/${campaignId}/templates/${templateId}`, {
+      method: 'DELETE',
+    });
+  }
+
+  async getCampaignSteps(campaignId: string): Promise<CampaignStep[]> {
+    return this.request<CampaignStep[]>(`/campaigns/${campaignId}/steps`);
+  }
+
+  async createCampaignStep(campaignId: string, params: CampaignStepParams): Promise<CampaignStep> {
+    return this.request<CampaignStep>(`/campaigns/${campaignId}/steps`, {
+      method: 'POST',
+      body: JSON.stringify(params),
+    });
+  }
+
+  async updateCampaignStep(campaignId: string, stepId: string, params: CampaignStepParams): Promise<CampaignStep> {
+    return this.request<CampaignStep>(`/campaigns/${campaignId}/steps/${stepId}`, {
+      method: 'PUT',
+      body: JSON.stringify(params),
+    });
+  }
+
+  async deleteCampaignStep(campaignId: string, stepId: string): Promise<{ deleted: boolean }> {
+    return this.request(`/campaigns/${campaignId}/steps/${stepId}`, {
+      method: 'DELETE',
+    });
+  }
+
+  async sendTestEmail(campaignId: string, options?: { lead_id?: string; inbox_id?: string }): Promise<{ sent: boolean; to: string; from: string }> {
+    return this.request(`/campaigns/${campaignId}/send-test`, {
+      method: 'POST',
+      body: JSON.stringify(options ?? {}),
+    });
+  }
+
   // ============================================================================
   // ANALYTICS ENDPOINTS
   // ============================================================================
@@ -607,6 +1297,27 @@ class ApiClient {
     return this.request<DeliverabilityMetrics>('/analytics/deliverability');
   }
 
+  async getAnalyticsTimeseries(params: TimeseriesParams): Promise<Timeseries> {
+    const queryParams = new URLSearchParams({ metric: params.metric });
+    if (params.from) queryParams.append('from', params.from);
+    if (params.to) queryParams.append('to', params.to);
+    if (params.interval) queryParams.append('interval', params.interval);
+    if (params.campaign_id) queryParams.append('campaign_id', params.campaign_id);
+    return this.request<Timeseries>(`/analytics/timeseries?${queryParams.toString()}`);
+  }
+
+  async getInboxHealth(id: string, params?: { from?: string; to?: string }): Promise<InboxHealth> {
+    const queryParams = new URLSearchParams();
+    if (params?.from) queryParams.append('from', params.from);
+    if (params?.to) queryParams.append('to', params.to);
+    const query = queryParams.toString();
+    return this.request<InboxHealth>(`/analytics/inboxes/${id}${query ? `?${query}` : ''}`);
+  }
+
+  async getInboxRanking(): Promise<{ trend_days: number; inboxes: InboxHealthRank[] }> {
+    return this.request('/analytics/inboxes');
+  }
+
   async getOverview(): Promise<OverviewStats & { total_leads: number; verified_leads: number; total_campaigns: number; active_campaigns: number }> {
     return this.request('/analytics/overview');
   }
@@ -626,6 +1337,32 @@ class ApiClient {
     });
   }
 
+  async importEmailAccounts(csv: string): Promise<ImportEmailAccountsResponse> {
+    return this.request<ImportEmailAccountsResponse>('/email-accounts/import', {
+      method: 'POST',
+      headers: { 'Content-Type': 'text/csv' },
+      body: csv,
+    });
+  }
+
+  async startEmailAccountOAuth(provider: EmailOAuthProvider): Promise<{ authorization_url: string }> {
+    return this.request(`/email-accounts/oauth/${provider}/start`);
+  }
+
+  async completeEmailAccountOAuth(provider: EmailOAuthProvider, code: string, state: string): Promise<EmailAccount> {
+    return this.request<EmailAccount>(`/email-accounts/oauth/${provider}/callback`, {
+      method: 'POST',
+      body: JSON.stringify({ code, state }),
+    });
+  }
+
+  async updateSendWindow(accountId: string, params: SendWindowParams): Promise<EmailAccount> {
+    return this.request<EmailAccount>(`/email-accounts/${accountId}/send-window`, {
+      method: 'PUT',
+      body: JSON.stringify(params),
+    });
+  }
+
   async startWarmup(accountId: string): Promise<EmailAccount> {
     return this.request<EmailAccount>(`/email-accounts/${accountId}/warmup/start`, { method: 'POST' });
   }
@@ -638,6 +1375,10 @@ class ApiClient {
     return this.request<WarmupStats>(`/email-accounts/${accountId}/warmup/stats`);
   }
 
+  async checkEmailAccountDns(accountId: string): Promise<DomainAuthReport> {
+    return this.request<DomainAuthReport>(`/email-accounts/${accountId}/dns-check`);
+  }
+
   // ============================================================================
   // FOUNDER DASHBOARD ENDPOINTS
   // ============================================================================
@@ -666,6 +1407,10 @@ class ApiClient {
     return this.request<InboxHealthCard>(`/founder/inboxes/${inboxId}/health`);
   }
 
+  async getDomainHealth(): Promise<DomainHealthCard[]> {
+    return this.request<DomainHealthCard[]>('/founder/domains/health');
+  }
+
   async getReplies(params?: { intent?: string; limit?: number; offset?: number }): Promise<ReplyCard[]> {
     const queryParams = new URLSearchParams();
     if (params?.intent) queryParams.append('intent', params.intent);
@@ -682,7 +1427,7 @@ class ApiClient {
     });
   }
 
-  async classifyReply(replyId: string): Promise<{ reply_id: string; intent: string; confidence: number }> {
+  async classifyReply(replyId: string): Promise<{ reply_id: string; intent: string; confidence: number; sentiment: number; urgent: boolean }> {
     return this.request('/founder/replies/classify', {
       method: 'POST',
       body: JSON.stringify({ reply_id: replyId }),
@@ -725,6 +1470,7 @@ class ApiClient {
     reply_id?: string;
     title?: string;
     scheduled_at?: string;
+    timezone_offset_minutes?: number;
   }): Promise<{ id: string }> {
     return this.request('/founder/meetings', {
       method: 'POST',
@@ -742,6 +1488,37 @@ class ApiClient {
       body: JSON.stringify(settings),
     });
   }
+
+  async getRetentionRuns(): Promise<RetentionPurgeRun[]> {
+    return this.request<RetentionPurgeRun[]>('/founder/retention-runs');
+  }
+
+  // ============================================================================
+  // INTEGRATION ENDPOINTS
+  // ============================================================================
+
+  async getZapierSubscriptions(): Promise<ZapierSubscription[]> {
+    return this.request<ZapierSubscription[]>('/integrations/zapier/subscriptions');
+  }
+
+  async createZapierSubscription(data: {
+    event: ZapierEvent;
+    target_url: string;
+    campaign_id?: string;
+  }): Promise<ZapierSubscription> {
+    return this.request('/integrations/zapier/subscriptions', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+  }
+
+  async deleteZapierSubscription(id: string): Promise<void> {
+    return this.request(`/integrations/zapier/subscriptions/${id}`, { method: 'DELETE' });
+  }
+
+  async testZapierSubscription(id: string): Promise<{ delivered: boolean; error: string | null; payload: ZapierPayload }> {
+    return this.request(`/integrations/zapier/subscriptions/${id}/test`, { method: 'POST' });
+  }
 }
 
 // Export singleton instance